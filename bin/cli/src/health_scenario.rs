@@ -0,0 +1,181 @@
+use fixed::types::I80F48;
+use mango_v4::accounts_ix::Serum3Side;
+use mango_v4::health::HealthScenarioAction;
+use mango_v4::state::{PerpMarketIndex, Side, TokenIndex, QUOTE_TOKEN_INDEX};
+use mango_v4_client::{
+    account_fetcher_fetch_mango_account, health_cache, MangoGroupContext, RpcAccountFetcher,
+};
+use solana_sdk::pubkey::Pubkey;
+
+pub const ACTION_HELP: &str = "amounts and prices are in ui units, markets and tokens are \
+names or indexes: deposit:TOKEN:AMOUNT, withdraw:TOKEN:AMOUNT, \
+swap:SOURCE:TARGET:SOURCE_AMOUNT:TARGET_PER_SOURCE_PRICE, serum3-bid:MARKET:QUOTE_AMOUNT, \
+serum3-ask:MARKET:BASE_AMOUNT, perp-buy:MARKET:BASE_AMOUNT:PRICE, \
+perp-sell:MARKET:BASE_AMOUNT:PRICE, token-shock:TOKEN:FACTOR, perp-shock:MARKET:FACTOR";
+
+fn token_index(context: &MangoGroupContext, token: &str) -> anyhow::Result<TokenIndex> {
+    if let Some(index) = context.token_indexes_by_name.get(token) {
+        return Ok(*index);
+    }
+    let index = token.parse::<TokenIndex>()?;
+    anyhow::ensure!(context.tokens.contains_key(&index), "unknown token {token}");
+    Ok(index)
+}
+
+fn perp_market_index(context: &MangoGroupContext, market: &str) -> anyhow::Result<PerpMarketIndex> {
+    if let Some(index) = context.perp_market_indexes_by_name.get(market) {
+        return Ok(*index);
+    }
+    let index = market.parse::<PerpMarketIndex>()?;
+    anyhow::ensure!(
+        context.perp_markets.contains_key(&index),
+        "unknown perp market {market}"
+    );
+    Ok(index)
+}
+
+fn num(value: &str) -> anyhow::Result<f64> {
+    Ok(value.parse::<f64>()?)
+}
+
+fn native(ui_amount: f64, decimals: u8) -> I80F48 {
+    I80F48::from_num(ui_amount * 10f64.powi(decimals.into()))
+}
+
+fn parse_action(context: &MangoGroupContext, action: &str) -> anyhow::Result<HealthScenarioAction> {
+    let parts = action.split(':').collect::<Vec<_>>();
+    let args = &parts[1..];
+    let arg_count = match parts[0] {
+        "deposit" | "withdraw" | "serum3-bid" | "serum3-ask" | "token-shock" | "perp-shock" => 2,
+        "perp-buy" | "perp-sell" => 3,
+        "swap" => 4,
+        kind => anyhow::bail!("unknown action kind {kind}"),
+    };
+    anyhow::ensure!(
+        args.len() == arg_count,
+        "action {action} needs {arg_count} arguments"
+    );
+
+    Ok(match parts[0] {
+        "deposit" | "withdraw" => {
+            let token_index = token_index(context, args[0])?;
+            let amount = native(num(args[1])?, context.token(token_index).decimals);
+            if parts[0] == "deposit" {
+                HealthScenarioAction::TokenDeposit {
+                    token_index,
+                    amount,
+                }
+            } else {
+                HealthScenarioAction::TokenWithdraw {
+                    token_index,
+                    amount,
+                }
+            }
+        }
+        "swap" => {
+            let source = context.token(token_index(context, args[0])?);
+            let target = context.token(token_index(context, args[1])?);
+            let price = num(args[3])? * 10f64.powi(target.decimals as i32 - source.decimals as i32);
+            HealthScenarioAction::TokenSwap {
+                source_token_index: source.token_index,
+                target_token_index: target.token_index,
+                amount: native(num(args[2])?, source.decimals),
+                price: I80F48::from_num(price),
+            }
+        }
+        "serum3-bid" | "serum3-ask" => {
+            let market_index = match context.serum3_market_indexes_by_name.get(args[0]) {
+                Some(index) => *index,
+                None => args[0].parse()?,
+            };
+            let (side, token) = if parts[0] == "serum3-bid" {
+                (Serum3Side::Bid, context.serum3_quote_token(market_index))
+            } else {
+                (Serum3Side::Ask, context.serum3_base_token(market_index))
+            };
+            HealthScenarioAction::Serum3PlaceOrder {
+                market_index,
+                side,
+                amount: native(num(args[1])?, token.decimals),
+            }
+        }
+        "perp-buy" | "perp-sell" => {
+            let perp_market_index = perp_market_index(context, args[0])?;
+            let market = &context.perp(perp_market_index).market;
+            let settle_decimals = context.token(market.settle_token_index).decimals;
+            let base_native = num(args[1])? * 10f64.powi(market.base_decimals.into());
+            let price =
+                num(args[2])? * 10f64.powi(settle_decimals as i32 - market.base_decimals as i32);
+            HealthScenarioAction::PerpTrade {
+                perp_market_index,
+                side: if parts[0] == "perp-buy" {
+                    Side::Bid
+                } else {
+                    Side::Ask
+                },
+                base_lots: (base_native / market.base_lot_size as f64).round() as i64,
+                price: I80F48::from_num(price),
+            }
+        }
+        "token-shock" => HealthScenarioAction::TokenPriceShock {
+            token_index: token_index(context, args[0])?,
+            factor: I80F48::from_num(num(args[1])?),
+        },
+        "perp-shock" => HealthScenarioAction::PerpPriceShock {
+            perp_market_index: perp_market_index(context, args[0])?,
+            factor: I80F48::from_num(num(args[1])?),
+        },
+        _ => unreachable!(),
+    })
+}
+
+pub async fn run(
+    account_fetcher: &RpcAccountFetcher,
+    address: Pubkey,
+    actions: &[String],
+) -> anyhow::Result<()> {
+    let account = account_fetcher_fetch_mango_account(account_fetcher, &address).await?;
+    let context =
+        MangoGroupContext::new_from_rpc(&account_fetcher.rpc, account.fixed.group).await?;
+    let actions = actions
+        .iter()
+        .map(|a| parse_action(&context, a))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let result = health_cache::scenario(&context, account_fetcher, &account, &actions).await?;
+
+    let quote_decimals = context.token(QUOTE_TOKEN_INDEX).decimals;
+    let ui_value = |native: I80F48| native.to_num::<f64>() / 10f64.powi(quote_decimals.into());
+    println!("init health: {:.2}", ui_value(result.init_health));
+    println!("maint health: {:.2}", ui_value(result.maint_health));
+    println!("init health ratio: {:.2}%", result.init_health_ratio);
+    println!("maint health ratio: {:.2}%", result.maint_health_ratio);
+    println!("leverage: {:.2}", result.leverage);
+    let fmt_price = |price: Option<I80F48>, decimals: u8| match price {
+        Some(price) => format!(
+            "{}",
+            price.to_num::<f64>() * 10f64.powi(decimals as i32 - quote_decimals as i32)
+        ),
+        None => "none".to_string(),
+    };
+    println!("liquidation prices (lower, upper):");
+    for (token_index, prices) in result.token_liquidation_prices.iter() {
+        let token = context.token(*token_index);
+        println!(
+            "  {}: {}, {}",
+            token.name,
+            fmt_price(prices.lower, token.decimals),
+            fmt_price(prices.upper, token.decimals)
+        );
+    }
+    for (perp_market_index, prices) in result.perp_liquidation_prices.iter() {
+        let market = &context.perp(*perp_market_index).market;
+        println!(
+            "  {}: {}, {}",
+            market.name(),
+            fmt_price(prices.lower, market.base_decimals),
+            fmt_price(prices.upper, market.base_decimals)
+        );
+    }
+    Ok(())
+}
@@ -1,11 +1,14 @@
 use clap::{Args, Parser, Subcommand};
 use mango_v4_client::{
-    keypair_from_cli, pubkey_from_cli, Client, MangoClient, TransactionBuilderConfig,
+    account_fetcher_fetch_mango_account, keypair_from_cli, pubkey_from_cli, report, Client,
+    MangoClient, MangoGroupContext, RpcAccountFetcher, TransactionBuilderConfig,
 };
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 use std::sync::Arc;
 
+mod health_scenario;
+mod stress_test;
 mod test_oracles;
 
 #[derive(Parser, Debug, Clone)]
@@ -109,6 +112,28 @@ enum Command {
         #[clap(short, long, default_value = "0")]
         num: u32,
     },
+    /// Prints balances, positions, open orders and health of an account
+    AccountReport {
+        #[clap(short, long)]
+        account: String,
+
+        #[clap(flatten)]
+        rpc: Rpc,
+    },
+    /// Prints health and liquidation prices of an account after hypothetical actions
+    HealthScenario {
+        #[clap(short, long)]
+        account: String,
+
+        /// can be passed multiple times, actions are applied in order
+        #[clap(long, help = health_scenario::ACTION_HELP)]
+        action: Vec<String>,
+
+        #[clap(flatten)]
+        rpc: Rpc,
+    },
+    /// Applies price shocks to all accounts of a group and reports liquidations and bad debt
+    StressTest(stress_test::StressTest),
     /// Regularly fetches all oracles and prints their prices
     TestOracles {
         #[clap(short, long)]
@@ -216,6 +241,32 @@ async fn main() -> Result<(), anyhow::Error> {
             .0;
             println!("{}", address);
         }
+        Command::AccountReport { account, rpc } => {
+            let client = rpc.client(None)?;
+            let address = pubkey_from_cli(&account);
+            let account_fetcher = RpcAccountFetcher {
+                rpc: client.rpc_async(),
+            };
+            let account = account_fetcher_fetch_mango_account(&account_fetcher, &address).await?;
+            let context =
+                MangoGroupContext::new_from_rpc(&client.rpc_async(), account.fixed.group).await?;
+            let report = report::account_report(&context, &account_fetcher, address).await?;
+            print!("{}", report);
+        }
+        Command::HealthScenario {
+            account,
+            action,
+            rpc,
+        } => {
+            let client = rpc.client(None)?;
+            let account_fetcher = RpcAccountFetcher {
+                rpc: client.rpc_async(),
+            };
+            health_scenario::run(&account_fetcher, pubkey_from_cli(&account), &action).await?;
+        }
+        Command::StressTest(cmd) => {
+            stress_test::run(cmd).await?;
+        }
         Command::TestOracles { group, rpc } => {
             let client = rpc.client(None)?;
             let group = pubkey_from_cli(&group);
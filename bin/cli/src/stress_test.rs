@@ -0,0 +1,104 @@
+use clap::Args;
+use mango_v4::state::QUOTE_TOKEN_INDEX;
+use mango_v4_client::stress_test::{self, PriceScenario};
+use mango_v4_client::{
+    pubkey_from_cli, CachedAccountFetcher, MangoGroupContext, RpcAccountFetcher,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::Rpc;
+
+#[derive(Args, Debug, Clone)]
+pub struct StressTest {
+    #[clap(short, long)]
+    group: String,
+
+    /// json file with a list of scenarios like
+    /// {"name": "sol crash", "all": 0.8, "tokens": {"SOL": 0.6}, "perps": {"SOL-PERP": 0.6}},
+    /// see PriceScenario. No price history is fetched: to replay a historical move,
+    /// enter its observed price changes as factors here
+    #[clap(long)]
+    scenario_file: Option<PathBuf>,
+
+    /// move all non-stable token and perp prices by this factor, can be passed multiple times
+    #[clap(long)]
+    correlated: Vec<f64>,
+
+    /// move a single token price, as TOKEN:FACTOR, can be passed multiple times
+    #[clap(long)]
+    token_shock: Vec<String>,
+
+    /// tokens that correlated moves don't apply to, in addition to the quote token
+    #[clap(long)]
+    stable_token: Vec<String>,
+
+    /// how many liquidatable accounts to list per scenario
+    #[clap(long, default_value = "20")]
+    max_listed: usize,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+fn scenarios(cmd: &StressTest) -> anyhow::Result<Vec<PriceScenario>> {
+    let mut scenarios = vec![];
+    if let Some(path) = &cmd.scenario_file {
+        let file = std::fs::File::open(path)?;
+        let from_file: Vec<PriceScenario> = serde_json::from_reader(file)?;
+        scenarios.extend(from_file);
+    }
+    for factor in &cmd.correlated {
+        scenarios.push(PriceScenario {
+            name: format!("all x{factor}"),
+            all: Some(*factor),
+            ..PriceScenario::default()
+        });
+    }
+    for shock in &cmd.token_shock {
+        let (token, factor) = match shock.split_once(':') {
+            Some((token, factor)) => (token, factor.parse::<f64>()?),
+            None => anyhow::bail!("token shock {shock} must be TOKEN:FACTOR"),
+        };
+        scenarios.push(PriceScenario {
+            name: format!("{token} x{factor}"),
+            tokens: HashMap::from([(token.to_string(), factor)]),
+            ..PriceScenario::default()
+        });
+    }
+    anyhow::ensure!(!scenarios.is_empty(), "no scenarios given");
+    Ok(scenarios)
+}
+
+pub async fn run(cmd: StressTest) -> anyhow::Result<()> {
+    let scenarios = scenarios(&cmd)?;
+    let client = cmd.rpc.client(None)?;
+    let group = pubkey_from_cli(&cmd.group);
+    let context = MangoGroupContext::new_from_rpc(&client.rpc_async(), group).await?;
+
+    let mut stable_tokens = vec![QUOTE_TOKEN_INDEX];
+    for name in &cmd.stable_token {
+        match context.token_indexes_by_name.get(name) {
+            Some(token_index) => stable_tokens.push(*token_index),
+            None => anyhow::bail!("unknown token {name}"),
+        }
+    }
+
+    // banks, oracles and markets are shared between accounts and only fetched once
+    let account_fetcher = CachedAccountFetcher::new(Arc::new(RpcAccountFetcher {
+        rpc: client.rpc_async(),
+    }));
+    let mut report = stress_test::run(
+        &context,
+        &account_fetcher,
+        &stress_test::Config {
+            scenarios,
+            stable_tokens,
+        },
+    )
+    .await?;
+    report.max_listed_accounts = cmd.max_listed;
+    print!("{}", report);
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use clap::{Args, Parser, Subcommand};
+use mango_v4_client::{pubkey_from_cli, Client, TransactionBuilderConfig};
+use rusqlite::ToSql;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Keypair;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod records;
+mod source;
+mod store;
+
+#[derive(Parser, Debug, Clone)]
+#[clap()]
+struct Cli {
+    /// sqlite database to write to and query from
+    #[clap(long, env, default_value = "indexer.sqlite")]
+    db: PathBuf,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Args, Debug, Clone)]
+struct IngestRpc {
+    #[clap(short, long, env, default_value = "m")]
+    rpc_url: String,
+
+    #[clap(short, long, env)]
+    group: String,
+
+    #[clap(long, env, default_value = "10")]
+    interval_secs: u64,
+
+    /// how many past transactions to index when starting with an empty database
+    #[clap(long, env, default_value = "1000")]
+    backfill_limit: usize,
+
+    /// also append all fetched transactions to this file
+    #[clap(long, env)]
+    record_file: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+struct QueryArgs {
+    /// only show rows involving this mango account
+    #[clap(short, long)]
+    account: Option<String>,
+
+    /// only show rows for this perp market or token index
+    #[clap(short, long)]
+    market: Option<u16>,
+
+    #[clap(short, long, default_value = "50")]
+    limit: u32,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Query {
+    Trades(QueryArgs),
+    Transfers(QueryArgs),
+    TokenLiquidations(QueryArgs),
+    PerpLiquidations(QueryArgs),
+    Funding(QueryArgs),
+    Settlements(QueryArgs),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Continuously index new transactions of a group from rpc
+    IngestRpc(IngestRpc),
+    /// Index transactions recorded with --record-file
+    IngestFile {
+        #[clap(short, long)]
+        path: PathBuf,
+    },
+    /// Print the newest indexed rows
+    #[clap(subcommand)]
+    Query(Query),
+}
+
+/// Where and how the rows of a query subcommand are found
+struct QuerySpec {
+    table: &'static str,
+    /// columns to match against --account
+    account_columns: &'static [&'static str],
+    /// column to match against --market
+    market_column: &'static str,
+}
+
+impl Query {
+    fn spec(&self) -> (QuerySpec, &QueryArgs) {
+        let (table, account_columns, market_column, args): (_, &'static [_], _, _) = match self {
+            Query::Trades(a) => ("trades", &["maker", "taker"], "market_index", a),
+            Query::Transfers(a) => ("transfers", &["account"], "token_index", a),
+            Query::TokenLiquidations(a) => (
+                "token_liquidations",
+                &["liqor", "liqee"],
+                "liab_token_index",
+                a,
+            ),
+            Query::PerpLiquidations(a) => {
+                ("perp_liquidations", &["liqor", "liqee"], "market_index", a)
+            }
+            Query::Funding(a) => ("funding", &[], "market_index", a),
+            Query::Settlements(a) => (
+                "settlements",
+                &["account", "counterparty"],
+                "market_index",
+                a,
+            ),
+        };
+        (
+            QuerySpec {
+                table,
+                account_columns,
+                market_column,
+            },
+            args,
+        )
+    }
+
+    fn run(&self, store: &store::Store) -> anyhow::Result<()> {
+        let (
+            QuerySpec {
+                table,
+                account_columns,
+                market_column,
+            },
+            args,
+        ) = self.spec();
+        let mut conditions = vec![];
+        let mut params: Vec<Box<dyn ToSql>> = vec![];
+        if let Some(account) = &args.account {
+            anyhow::ensure!(
+                !account_columns.is_empty(),
+                "{table} can't be filtered by account"
+            );
+            params.push(Box::new(pubkey_from_cli(account).to_string()));
+            let idx = params.len();
+            conditions.push(format!(
+                "({})",
+                account_columns
+                    .iter()
+                    .map(|c| format!("{c} = ?{idx}"))
+                    .collect::<Vec<_>>()
+                    .join(" OR ")
+            ));
+        }
+        if let Some(market) = args.market {
+            params.push(Box::new(market));
+            conditions.push(format!("{market_column} = ?{}", params.len()));
+        }
+        params.push(Box::new(args.limit));
+
+        let where_clause = if conditions.is_empty() {
+            "".to_string()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT * FROM {table} {where_clause} ORDER BY slot DESC LIMIT ?{}",
+            params.len()
+        );
+        let param_refs = params.iter().map(|p| p.as_ref()).collect::<Vec<_>>();
+        store.print_query(&sql, &param_refs)
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    mango_v4_client::tracing_subscriber_init();
+
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+
+    let mut store = store::Store::open(&cli.db)?;
+
+    match cli.command {
+        Command::IngestRpc(cmd) => {
+            // the indexer never signs anything
+            let client = Client::new(
+                anchor_client::Cluster::from_str(&cmd.rpc_url)?,
+                CommitmentConfig::confirmed(),
+                Arc::new(Keypair::new()),
+                Some(Duration::from_secs(30)),
+                TransactionBuilderConfig {
+                    prioritization_micro_lamports: None,
+                },
+            );
+            source::ingest_rpc(
+                &mut store,
+                &client.rpc_async(),
+                source::RpcConfig {
+                    mango_group: pubkey_from_cli(&cmd.group),
+                    interval: Duration::from_secs(cmd.interval_secs),
+                    backfill_limit: cmd.backfill_limit,
+                    record_file: cmd.record_file,
+                },
+            )
+            .await?;
+        }
+        Command::IngestFile { path } => {
+            source::ingest_file(&mut store, &path)?;
+        }
+        Command::Query(query) => {
+            query.run(&store)?;
+        }
+    }
+
+    Ok(())
+}
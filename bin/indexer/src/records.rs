@@ -0,0 +1,349 @@
+use fixed::types::I80F48;
+use mango_v4_client::events::MangoEvent;
+use solana_sdk::pubkey::Pubkey;
+
+/// A normalized row derived from a mango event
+pub enum Record {
+    Trade {
+        market_index: u16,
+        seq_num: u64,
+        taker_side: u8,
+        maker: Pubkey,
+        taker: Pubkey,
+        /// in quote lots per base lot
+        price: i64,
+        /// in base lots
+        quantity: i64,
+        maker_fee: f64,
+        taker_fee: f64,
+    },
+    Transfer {
+        kind: &'static str,
+        account: Pubkey,
+        token_index: u16,
+        quantity: u64,
+        price: f64,
+    },
+    TokenLiquidation {
+        kind: &'static str,
+        liqor: Pubkey,
+        liqee: Pubkey,
+        asset_token_index: u16,
+        liab_token_index: u16,
+        asset_transfer: f64,
+        liab_transfer: f64,
+        liab_price: f64,
+    },
+    PerpLiquidation {
+        kind: &'static str,
+        liqor: Pubkey,
+        liqee: Pubkey,
+        market_index: u16,
+        base_transfer: i64,
+        quote_transfer: f64,
+        socialized_loss: f64,
+    },
+    Funding {
+        market_index: u16,
+        long_funding: f64,
+        short_funding: f64,
+        price: f64,
+        stable_price: f64,
+        open_interest: i64,
+    },
+    Settlement {
+        kind: &'static str,
+        market_index: u16,
+        account: Pubkey,
+        counterparty: Option<Pubkey>,
+        settler: Option<Pubkey>,
+        settlement: f64,
+        fee: f64,
+    },
+}
+
+fn f(bits: i128) -> f64 {
+    I80F48::from_bits(bits).to_num::<f64>()
+}
+
+/// Maps an event to the record it should be stored as, if it's of interest
+pub fn from_event(event: &MangoEvent) -> Option<Record> {
+    use MangoEvent::*;
+    Some(match event {
+        FillLogV2(e) => Record::Trade {
+            market_index: e.market_index,
+            seq_num: e.seq_num,
+            taker_side: e.taker_side,
+            maker: e.maker,
+            taker: e.taker,
+            price: e.price,
+            quantity: e.quantity,
+            maker_fee: e.maker_fee as f64,
+            taker_fee: e.taker_fee as f64,
+        },
+        FillLogV3(e) => Record::Trade {
+            market_index: e.market_index,
+            seq_num: e.seq_num,
+            taker_side: e.taker_side,
+            maker: e.maker,
+            taker: e.taker,
+            price: e.price,
+            quantity: e.quantity,
+            maker_fee: e.maker_fee as f64,
+            taker_fee: e.taker_fee as f64,
+        },
+        DepositLog(e) => Record::Transfer {
+            kind: "deposit",
+            account: e.mango_account,
+            token_index: e.token_index,
+            quantity: e.quantity,
+            price: f(e.price),
+        },
+        WithdrawLog(e) => Record::Transfer {
+            kind: "withdraw",
+            account: e.mango_account,
+            token_index: e.token_index,
+            quantity: e.quantity,
+            price: f(e.price),
+        },
+        TokenLiqWithTokenLog(e) => Record::TokenLiquidation {
+            kind: "token_liq_with_token",
+            liqor: e.liqor,
+            liqee: e.liqee,
+            asset_token_index: e.asset_token_index,
+            liab_token_index: e.liab_token_index,
+            asset_transfer: f(e.asset_transfer),
+            liab_transfer: f(e.liab_transfer),
+            liab_price: f(e.liab_price),
+        },
+        TokenForceCloseBorrowsWithTokenLog(e) => Record::TokenLiquidation {
+            kind: "token_force_close_borrows",
+            liqor: e.liqor,
+            liqee: e.liqee,
+            asset_token_index: e.asset_token_index,
+            liab_token_index: e.liab_token_index,
+            asset_transfer: f(e.asset_transfer),
+            liab_transfer: f(e.liab_transfer),
+            liab_price: f(e.liab_price),
+        },
+        // the insurance fund is the asset side of a bankruptcy
+        TokenLiqBankruptcyLog(e) => Record::TokenLiquidation {
+            kind: "token_liq_bankruptcy",
+            liqor: e.liqor,
+            liqee: e.liqee,
+            asset_token_index: e.insurance_token_index,
+            liab_token_index: e.liab_token_index,
+            asset_transfer: f(e.insurance_transfer),
+            liab_transfer: f(e.initial_liab_native),
+            liab_price: f(e.liab_price),
+        },
+        PerpLiqBaseOrPositivePnlLog(e) => Record::PerpLiquidation {
+            kind: "perp_liq_base_or_positive_pnl",
+            liqor: e.liqor,
+            liqee: e.liqee,
+            market_index: e.perp_market_index,
+            base_transfer: e.base_transfer,
+            quote_transfer: f(e.quote_transfer),
+            socialized_loss: 0.0,
+        },
+        PerpLiqNegativePnlOrBankruptcyLog(e) => Record::PerpLiquidation {
+            kind: "perp_liq_negative_pnl_or_bankruptcy",
+            liqor: e.liqor,
+            liqee: e.liqee,
+            market_index: e.perp_market_index,
+            base_transfer: 0,
+            quote_transfer: f(e.settlement),
+            socialized_loss: 0.0,
+        },
+        PerpLiqBankruptcyLog(e) => Record::PerpLiquidation {
+            kind: "perp_liq_bankruptcy",
+            liqor: e.liqor,
+            liqee: e.liqee,
+            market_index: e.perp_market_index,
+            base_transfer: 0,
+            quote_transfer: f(e.insurance_transfer),
+            socialized_loss: f(e.socialized_loss),
+        },
+        PerpLiqIsolatedPositionLog(e) => Record::PerpLiquidation {
+            kind: "perp_liq_isolated_position",
+            liqor: e.liqor,
+            liqee: e.liqee,
+            market_index: e.perp_market_index,
+            base_transfer: e.base_transfer,
+            quote_transfer: f(e.quote_transfer),
+            // losses are socialized through PerpLiqBankruptcyLog
+            socialized_loss: 0.0,
+        },
+        PerpUpdateFundingLog(e) => Record::Funding {
+            market_index: e.market_index,
+            long_funding: f(e.long_funding),
+            short_funding: f(e.short_funding),
+            price: f(e.price),
+            stable_price: f(e.stable_price),
+            open_interest: e.open_interest,
+        },
+        PerpUpdateFundingLogV2(e) => Record::Funding {
+            market_index: e.market_index,
+            long_funding: f(e.long_funding),
+            short_funding: f(e.short_funding),
+            price: f(e.price),
+            stable_price: f(e.stable_price),
+            open_interest: e.open_interest,
+        },
+        PerpSettlePnlLog(e) => Record::Settlement {
+            kind: "pnl",
+            market_index: e.perp_market_index,
+            account: e.mango_account_a,
+            counterparty: Some(e.mango_account_b),
+            settler: Some(e.settler),
+            settlement: f(e.settlement),
+            fee: f(e.fee),
+        },
+        PerpSettleFeesLog(e) => Record::Settlement {
+            kind: "fees",
+            market_index: e.perp_market_index,
+            account: e.mango_account,
+            counterparty: None,
+            settler: None,
+            settlement: f(e.settlement),
+            fee: 0.0,
+        },
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mango_v4::logs::{DepositLog, FillLogV3, PerpSettleFeesLog, UpdateIndexLog};
+
+    #[test]
+    fn test_from_event_transfer() {
+        let account = Pubkey::new_unique();
+        let event = MangoEvent::DepositLog(DepositLog {
+            mango_group: Pubkey::new_unique(),
+            mango_account: account,
+            signer: Pubkey::new_unique(),
+            token_index: 3,
+            quantity: 1000,
+            price: I80F48::from_num(1.5).to_bits(),
+        });
+        match from_event(&event) {
+            Some(Record::Transfer {
+                kind,
+                account: record_account,
+                token_index,
+                quantity,
+                price,
+            }) => {
+                assert_eq!(kind, "deposit");
+                assert_eq!(record_account, account);
+                assert_eq!(token_index, 3);
+                assert_eq!(quantity, 1000);
+                assert_eq!(price, 1.5);
+            }
+            _ => panic!("expected a transfer"),
+        }
+    }
+
+    #[test]
+    fn test_from_event_trade() {
+        let maker = Pubkey::new_unique();
+        let taker = Pubkey::new_unique();
+        let event = MangoEvent::FillLogV3(FillLogV3 {
+            mango_group: Pubkey::new_unique(),
+            market_index: 2,
+            taker_side: 1,
+            maker_slot: 0,
+            maker_out: true,
+            timestamp: 0,
+            seq_num: 42,
+            maker,
+            maker_client_order_id: 0,
+            maker_fee: -0.0002,
+            maker_timestamp: 0,
+            taker,
+            taker_client_order_id: 0,
+            taker_fee: 0.0004,
+            price: 100,
+            quantity: 5,
+            maker_closed_pnl: 0.0,
+            taker_closed_pnl: 0.0,
+        });
+        match from_event(&event) {
+            Some(Record::Trade {
+                market_index,
+                seq_num,
+                taker_side,
+                maker: record_maker,
+                taker: record_taker,
+                price,
+                quantity,
+                maker_fee,
+                taker_fee,
+            }) => {
+                assert_eq!(market_index, 2);
+                assert_eq!(seq_num, 42);
+                assert_eq!(taker_side, 1);
+                assert_eq!(record_maker, maker);
+                assert_eq!(record_taker, taker);
+                assert_eq!(price, 100);
+                assert_eq!(quantity, 5);
+                assert_eq!(maker_fee, -0.0002f32 as f64);
+                assert_eq!(taker_fee, 0.0004f32 as f64);
+            }
+            _ => panic!("expected a trade"),
+        }
+    }
+
+    #[test]
+    fn test_from_event_settlement() {
+        let account = Pubkey::new_unique();
+        let event = MangoEvent::PerpSettleFeesLog(PerpSettleFeesLog {
+            mango_group: Pubkey::new_unique(),
+            mango_account: account,
+            perp_market_index: 1,
+            settlement: I80F48::from_num(-20).to_bits(),
+        });
+        match from_event(&event) {
+            Some(Record::Settlement {
+                kind,
+                market_index,
+                account: record_account,
+                counterparty,
+                settler,
+                settlement,
+                fee,
+            }) => {
+                assert_eq!(kind, "fees");
+                assert_eq!(market_index, 1);
+                assert_eq!(record_account, account);
+                assert!(counterparty.is_none());
+                assert!(settler.is_none());
+                assert_eq!(settlement, -20.0);
+                assert_eq!(fee, 0.0);
+            }
+            _ => panic!("expected a settlement"),
+        }
+    }
+
+    #[test]
+    fn test_from_event_ignored() {
+        let event = MangoEvent::UpdateIndexLog(UpdateIndexLog {
+            mango_group: Pubkey::new_unique(),
+            token_index: 0,
+            deposit_index: 0,
+            borrow_index: 0,
+            avg_utilization: 0,
+            price: 0,
+            stable_price: 0,
+            collected_fees: 0,
+            loan_fee_rate: 0,
+            total_borrows: 0,
+            total_deposits: 0,
+            borrow_rate: 0,
+            deposit_rate: 0,
+        });
+        assert!(from_event(&event).is_none());
+    }
+}
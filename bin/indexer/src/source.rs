@@ -0,0 +1,186 @@
+use mango_v4_client::events::parse_transaction_logs;
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::*;
+
+use crate::records;
+use crate::store::{Store, TxInfo};
+
+/// One line of a recorded transaction log file
+#[derive(Serialize, Deserialize)]
+pub struct RecordedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
+
+fn index_transaction(store: &mut Store, tx: &RecordedTransaction) -> anyhow::Result<bool> {
+    let records = parse_transaction_logs(&tx.logs)
+        .iter()
+        .filter_map(records::from_event)
+        .collect::<Vec<_>>();
+    store.insert_transaction(
+        &TxInfo {
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+        },
+        &records,
+    )
+}
+
+/// Indexes a file with one json RecordedTransaction per line
+pub fn ingest_file(store: &mut Store, path: &Path) -> anyhow::Result<()> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut count = 0;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tx: RecordedTransaction = serde_json::from_str(&line)?;
+        if index_transaction(store, &tx)? {
+            count += 1;
+        }
+    }
+    info!("indexed {count} new transactions from {}", path.display());
+    Ok(())
+}
+
+pub struct RpcConfig {
+    pub mango_group: Pubkey,
+    pub interval: Duration,
+    /// How many signatures to go back when there is no cursor yet
+    pub backfill_limit: usize,
+    /// Append all fetched transactions to this file, to replay them later
+    pub record_file: Option<PathBuf>,
+}
+
+/// Returns all successful signatures for the group newer than `until`, oldest first
+async fn new_signatures(
+    rpc: &RpcClient,
+    config: &RpcConfig,
+    until: Option<Signature>,
+) -> anyhow::Result<Vec<(Signature, u64, Option<i64>)>> {
+    let mut signatures = vec![];
+    let mut before = None;
+    loop {
+        let page = rpc
+            .get_signatures_for_address_with_config(
+                &config.mango_group,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(1000),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+        let page_len = page.len();
+        for status in page {
+            let signature = Signature::from_str(&status.signature)?;
+            before = Some(signature);
+            if status.err.is_none() {
+                signatures.push((signature, status.slot, status.block_time));
+            }
+        }
+        let backfill_done = until.is_none() && signatures.len() >= config.backfill_limit;
+        if page_len < 1000 || backfill_done {
+            break;
+        }
+    }
+    if until.is_none() {
+        signatures.truncate(config.backfill_limit);
+    }
+    signatures.reverse();
+    Ok(signatures)
+}
+
+async fn fetch_logs(rpc: &RpcClient, signature: &Signature) -> anyhow::Result<Vec<String>> {
+    let tx = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    let logs = tx
+        .transaction
+        .meta
+        .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+        .unwrap_or_default();
+    Ok(logs)
+}
+
+/// Regularly polls the rpc for new transactions involving the group and indexes them
+pub async fn ingest_rpc(
+    store: &mut Store,
+    rpc: &RpcClient,
+    config: RpcConfig,
+) -> anyhow::Result<()> {
+    let cursor_name = format!("rpc:{}", config.mango_group);
+    let mut record_file = config
+        .record_file
+        .as_ref()
+        .map(|path| OpenOptions::new().create(true).append(true).open(path))
+        .transpose()?;
+    let mut interval = tokio::time::interval(config.interval);
+    loop {
+        interval.tick().await;
+
+        let until = store
+            .cursor(&cursor_name)?
+            .map(|s| Signature::from_str(&s))
+            .transpose()?;
+        let signatures = match new_signatures(rpc, &config, until).await {
+            Ok(s) => s,
+            Err(err) => {
+                warn!("could not fetch signatures: {err:?}");
+                continue;
+            }
+        };
+
+        let mut count = 0;
+        for (signature, slot, block_time) in signatures {
+            let logs = match fetch_logs(rpc, &signature).await {
+                Ok(logs) => logs,
+                Err(err) => {
+                    // retry from here in the next iteration
+                    warn!("could not fetch transaction {signature}: {err:?}");
+                    break;
+                }
+            };
+            let tx = RecordedTransaction {
+                signature: signature.to_string(),
+                slot,
+                block_time,
+                logs,
+            };
+            if let Some(file) = record_file.as_mut() {
+                writeln!(file, "{}", serde_json::to_string(&tx)?)?;
+            }
+            if index_transaction(store, &tx)? {
+                count += 1;
+            }
+            store.set_cursor(&cursor_name, &tx.signature)?;
+        }
+        if count > 0 {
+            info!("indexed {count} new transactions");
+        }
+    }
+}
@@ -0,0 +1,443 @@
+use rusqlite::{params, types::ValueRef, Connection, OptionalExtension, ToSql};
+use std::path::Path;
+
+use crate::records::Record;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER
+);
+CREATE TABLE IF NOT EXISTS cursors (
+    name TEXT PRIMARY KEY,
+    signature TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS trades (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    market_index INTEGER NOT NULL,
+    seq_num INTEGER NOT NULL,
+    taker_side INTEGER NOT NULL,
+    maker TEXT NOT NULL,
+    taker TEXT NOT NULL,
+    price INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    maker_fee REAL NOT NULL,
+    taker_fee REAL NOT NULL,
+    UNIQUE (market_index, seq_num)
+);
+CREATE INDEX IF NOT EXISTS trades_maker ON trades (maker);
+CREATE INDEX IF NOT EXISTS trades_taker ON trades (taker);
+CREATE TABLE IF NOT EXISTS transfers (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    kind TEXT NOT NULL,
+    account TEXT NOT NULL,
+    token_index INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    price REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS transfers_account ON transfers (account);
+CREATE TABLE IF NOT EXISTS token_liquidations (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    kind TEXT NOT NULL,
+    liqor TEXT NOT NULL,
+    liqee TEXT NOT NULL,
+    asset_token_index INTEGER NOT NULL,
+    liab_token_index INTEGER NOT NULL,
+    asset_transfer REAL NOT NULL,
+    liab_transfer REAL NOT NULL,
+    liab_price REAL NOT NULL
+);
+CREATE TABLE IF NOT EXISTS perp_liquidations (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    kind TEXT NOT NULL,
+    liqor TEXT NOT NULL,
+    liqee TEXT NOT NULL,
+    market_index INTEGER NOT NULL,
+    base_transfer INTEGER NOT NULL,
+    quote_transfer REAL NOT NULL,
+    socialized_loss REAL NOT NULL
+);
+CREATE TABLE IF NOT EXISTS funding (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    market_index INTEGER NOT NULL,
+    long_funding REAL NOT NULL,
+    short_funding REAL NOT NULL,
+    price REAL NOT NULL,
+    stable_price REAL NOT NULL,
+    open_interest INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS settlements (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    kind TEXT NOT NULL,
+    market_index INTEGER NOT NULL,
+    account TEXT NOT NULL,
+    counterparty TEXT,
+    settler TEXT,
+    settlement REAL NOT NULL,
+    fee REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS settlements_account ON settlements (account);
+";
+
+/// Basic information about an indexed transaction
+pub struct TxInfo {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn cursor(&self, name: &str) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT signature FROM cursors WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_cursor(&self, name: &str, signature: &str) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT INTO cursors (name, signature) VALUES (?1, ?2)
+             ON CONFLICT (name) DO UPDATE SET signature = excluded.signature",
+            params![name, signature],
+        )?;
+        Ok(())
+    }
+
+    /// Stores all records of a transaction atomically.
+    ///
+    /// Returns false if the transaction was indexed before, in which case nothing is written.
+    pub fn insert_transaction(&mut self, tx: &TxInfo, records: &[Record]) -> anyhow::Result<bool> {
+        let db_tx = self.conn.transaction()?;
+        let inserted = db_tx.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time) VALUES (?1, ?2, ?3)",
+            params![tx.signature, tx.slot, tx.block_time],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+        for record in records {
+            insert_record(&db_tx, tx, record)?;
+        }
+        db_tx.commit()?;
+        Ok(true)
+    }
+
+    /// Runs a query and prints the result as tab separated columns
+    pub fn print_query(&self, sql: &str, params: &[&dyn ToSql]) -> anyhow::Result<()> {
+        let mut stmt = self.conn.prepare(sql)?;
+        println!("{}", stmt.column_names().join("\t"));
+        let column_count = stmt.column_count();
+        let mut rows = stmt.query(params)?;
+        while let Some(row) = rows.next()? {
+            let values = (0..column_count)
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => "".to_string(),
+                        ValueRef::Integer(v) => v.to_string(),
+                        ValueRef::Real(v) => v.to_string(),
+                        ValueRef::Text(v) | ValueRef::Blob(v) => {
+                            String::from_utf8_lossy(v).to_string()
+                        }
+                    })
+                })
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            println!("{}", values.join("\t"));
+        }
+        Ok(())
+    }
+}
+
+fn insert_record(conn: &Connection, tx: &TxInfo, record: &Record) -> anyhow::Result<()> {
+    match record {
+        Record::Trade {
+            market_index,
+            seq_num,
+            taker_side,
+            maker,
+            taker,
+            price,
+            quantity,
+            maker_fee,
+            taker_fee,
+        } => {
+            conn.execute(
+                "INSERT OR IGNORE INTO trades (signature, slot, block_time, market_index, seq_num,
+                    taker_side, maker, taker, price, quantity, maker_fee, taker_fee)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    tx.signature,
+                    tx.slot,
+                    tx.block_time,
+                    market_index,
+                    seq_num,
+                    taker_side,
+                    maker.to_string(),
+                    taker.to_string(),
+                    price,
+                    quantity,
+                    maker_fee,
+                    taker_fee,
+                ],
+            )?;
+        }
+        Record::Transfer {
+            kind,
+            account,
+            token_index,
+            quantity,
+            price,
+        } => {
+            conn.execute(
+                "INSERT INTO transfers (signature, slot, block_time, kind, account, token_index,
+                    quantity, price)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    tx.signature,
+                    tx.slot,
+                    tx.block_time,
+                    kind,
+                    account.to_string(),
+                    token_index,
+                    quantity,
+                    price,
+                ],
+            )?;
+        }
+        Record::TokenLiquidation {
+            kind,
+            liqor,
+            liqee,
+            asset_token_index,
+            liab_token_index,
+            asset_transfer,
+            liab_transfer,
+            liab_price,
+        } => {
+            conn.execute(
+                "INSERT INTO token_liquidations (signature, slot, block_time, kind, liqor, liqee,
+                    asset_token_index, liab_token_index, asset_transfer, liab_transfer, liab_price)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    tx.signature,
+                    tx.slot,
+                    tx.block_time,
+                    kind,
+                    liqor.to_string(),
+                    liqee.to_string(),
+                    asset_token_index,
+                    liab_token_index,
+                    asset_transfer,
+                    liab_transfer,
+                    liab_price,
+                ],
+            )?;
+        }
+        Record::PerpLiquidation {
+            kind,
+            liqor,
+            liqee,
+            market_index,
+            base_transfer,
+            quote_transfer,
+            socialized_loss,
+        } => {
+            conn.execute(
+                "INSERT INTO perp_liquidations (signature, slot, block_time, kind, liqor, liqee,
+                    market_index, base_transfer, quote_transfer, socialized_loss)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    tx.signature,
+                    tx.slot,
+                    tx.block_time,
+                    kind,
+                    liqor.to_string(),
+                    liqee.to_string(),
+                    market_index,
+                    base_transfer,
+                    quote_transfer,
+                    socialized_loss,
+                ],
+            )?;
+        }
+        Record::Funding {
+            market_index,
+            long_funding,
+            short_funding,
+            price,
+            stable_price,
+            open_interest,
+        } => {
+            conn.execute(
+                "INSERT INTO funding (signature, slot, block_time, market_index, long_funding,
+                    short_funding, price, stable_price, open_interest)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    tx.signature,
+                    tx.slot,
+                    tx.block_time,
+                    market_index,
+                    long_funding,
+                    short_funding,
+                    price,
+                    stable_price,
+                    open_interest,
+                ],
+            )?;
+        }
+        Record::Settlement {
+            kind,
+            market_index,
+            account,
+            counterparty,
+            settler,
+            settlement,
+            fee,
+        } => {
+            conn.execute(
+                "INSERT INTO settlements (signature, slot, block_time, kind, market_index, account,
+                    counterparty, settler, settlement, fee)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    tx.signature,
+                    tx.slot,
+                    tx.block_time,
+                    kind,
+                    market_index,
+                    account.to_string(),
+                    counterparty.map(|pk| pk.to_string()),
+                    settler.map(|pk| pk.to_string()),
+                    settlement,
+                    fee,
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{ingest_file, RecordedTransaction};
+    use anchor_client::anchor_lang::Event;
+    use fixed::types::I80F48;
+    use mango_v4::logs::DepositLog;
+    use solana_sdk::pubkey::Pubkey;
+    use std::io::Write;
+
+    fn count(store: &Store, table: &str) -> i64 {
+        store
+            .conn
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_ingest_file_roundtrip() -> anyhow::Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("mango-v4-indexer-test-{}", Pubkey::new_unique()));
+        std::fs::create_dir_all(&dir)?;
+
+        let account = Pubkey::new_unique();
+        let deposit = DepositLog {
+            mango_group: Pubkey::new_unique(),
+            mango_account: account,
+            signer: Pubkey::new_unique(),
+            token_index: 1,
+            quantity: 1234,
+            price: I80F48::from_num(2).to_bits(),
+        };
+        let mango = mango_v4::id().to_string();
+        let tx = RecordedTransaction {
+            signature: "sig1".to_string(),
+            slot: 100,
+            block_time: Some(1_700_000_000),
+            logs: vec![
+                format!("Program {mango} invoke [1]"),
+                format!("Program data: {}", base64::encode(deposit.data())),
+                format!("Program {mango} success"),
+            ],
+        };
+
+        // the same transaction recorded twice must only be indexed once
+        let tx_path = dir.join("transactions.jsonl");
+        let mut file = std::fs::File::create(&tx_path)?;
+        let line = serde_json::to_string(&tx)?;
+        writeln!(file, "{line}\n\n{line}")?;
+        drop(file);
+
+        let db_path = dir.join("indexer.sqlite");
+        let mut store = Store::open(&db_path)?;
+        ingest_file(&mut store, &tx_path)?;
+        assert_eq!(count(&store, "transactions"), 1);
+        assert_eq!(count(&store, "transfers"), 1);
+        drop(store);
+
+        // reopening the database keeps the rows and re-ingesting adds nothing
+        let mut store = Store::open(&db_path)?;
+        ingest_file(&mut store, &tx_path)?;
+        assert_eq!(count(&store, "transactions"), 1);
+        let row = store.conn.query_row(
+            "SELECT signature, slot, block_time, kind, account, token_index, quantity, price
+             FROM transfers",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, u64>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, u16>(5)?,
+                    row.get::<_, u64>(6)?,
+                    row.get::<_, f64>(7)?,
+                ))
+            },
+        )?;
+        assert_eq!(
+            row,
+            (
+                "sig1".to_string(),
+                100,
+                Some(1_700_000_000),
+                "deposit".to_string(),
+                account.to_string(),
+                1,
+                1234,
+                2.0
+            )
+        );
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -1,172 +1,282 @@
-use std::{collections::HashSet, sync::Arc, time::Duration, time::Instant};
+use std::{
+    collections::HashMap,
+    collections::HashSet,
+    sync::{Arc, RwLock},
+    time::Duration,
+    time::Instant,
+};
 
 use crate::MangoClient;
 use itertools::Itertools;
 
-use anchor_lang::{__private::bytemuck::cast_ref, solana_program};
+use anchor_lang::Discriminator;
+use anchor_lang::__private::bytemuck::cast_ref;
 use futures::Future;
-use mango_v4::state::{EventQueue, EventType, FillEvent, OutEvent, PerpMarket, TokenIndex};
-use prometheus::{register_histogram, Encoder, Histogram, IntCounter, Registry};
-use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
-    pubkey::Pubkey,
+use mango_v4::accounts_zerocopy::LoadZeroCopy;
+use mango_v4::state::{
+    EventQueue, EventType, FillEvent, OutEvent, PerpMarket, PerpMarketIndex, TokenIndex,
+};
+use mango_v4_client::{
+    account_update_stream, chain_data, websocket_source, AsyncChannelSendUnlessFull,
+    MangoGroupContext,
 };
+use mango_v4_metrics::prometheus::{Histogram, IntGaugeVec};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
 use tokio::time;
 use tracing::*;
-use warp::Filter;
+
+use crate::{METRICS, TX_METRICS};
 
 lazy_static::lazy_static! {
-    pub static ref METRICS_REGISTRY: Registry = Registry::new_custom(Some("keeper".to_string()), None).unwrap();
-    pub static ref METRIC_UPDATE_TOKENS_SUCCESS: IntCounter =
-        IntCounter::new("update_tokens_success", "Successful update token transactions").unwrap();
-    pub static ref METRIC_UPDATE_TOKENS_FAILURE: IntCounter =
-        IntCounter::new("update_tokens_failure", "Failed update token transactions").unwrap();
-    pub static ref METRIC_CONSUME_EVENTS_SUCCESS: IntCounter =
-        IntCounter::new("consume_events_success", "Successful consume events transactions").unwrap();
-    pub static ref METRIC_CONSUME_EVENTS_FAILURE: IntCounter =
-        IntCounter::new("consume_events_failure", "Failed consume events transactions").unwrap();
-    pub static ref METRIC_UPDATE_FUNDING_SUCCESS: IntCounter =
-        IntCounter::new("update_funding_success", "Successful update funding transactions").unwrap();
-    pub static ref METRIC_UPDATE_FUNDING_FAILURE: IntCounter =
-        IntCounter::new("update_funding_failure", "Failed update funding transactions").unwrap();
-    pub static ref METRIC_CONFIRMATION_TIMES: Histogram = register_histogram!(
-        "confirmation_times", "Transaction confirmation times",
-        vec![1000.0, 3000.0, 5000.0, 7000.0, 10000.0, 15000.0, 20000.0, 30000.0, 40000.0, 50000.0, 60000.0]
-    ).unwrap();
+    static ref METRIC_EVENT_QUEUE_DEPTH: IntGaugeVec = METRICS.register_gauge_vec(
+        "event_queue_depth",
+        "Number of events in the perp event queue",
+        &["market"],
+    );
+    static ref METRIC_CONSUME_EVENTS_LAG: Histogram = METRICS.register_histogram(
+        "consume_events_lag_seconds",
+        "Time from first seeing events in a queue to confirming their consumption",
+        vec![0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 15.0, 20.0, 30.0, 60.0],
+    );
 }
 
-// TODO: move instructions into the client proper
-
-async fn serve_metrics() {
-    METRICS_REGISTRY
-        .register(Box::new(METRIC_UPDATE_TOKENS_SUCCESS.clone()))
-        .unwrap();
-    METRICS_REGISTRY
-        .register(Box::new(METRIC_UPDATE_TOKENS_FAILURE.clone()))
-        .unwrap();
-    METRICS_REGISTRY
-        .register(Box::new(METRIC_CONSUME_EVENTS_SUCCESS.clone()))
-        .unwrap();
-    METRICS_REGISTRY
-        .register(Box::new(METRIC_CONSUME_EVENTS_FAILURE.clone()))
-        .unwrap();
-    METRICS_REGISTRY
-        .register(Box::new(METRIC_UPDATE_FUNDING_SUCCESS.clone()))
-        .unwrap();
-    METRICS_REGISTRY
-        .register(Box::new(METRIC_UPDATE_FUNDING_FAILURE.clone()))
-        .unwrap();
-    METRICS_REGISTRY
-        .register(Box::new(METRIC_CONFIRMATION_TIMES.clone()))
-        .unwrap();
-
-    let metrics_route = warp::path!("metrics").map(|| {
-        let mut buffer = Vec::<u8>::new();
-        let encoder = prometheus::TextEncoder::new();
-        encoder
-            .encode(&METRICS_REGISTRY.gather(), &mut buffer)
-            .unwrap();
-
-        String::from_utf8(buffer.clone()).unwrap()
-    });
-    println!("Metrics server starting on port 9091");
-    warp::serve(metrics_route).run(([0, 0, 0, 0], 9091)).await;
+pub struct Intervals {
+    pub update_banks: u64,
+    /// Event queues are consumed when the websocket reports events, this is the
+    /// fallback for checking all queues in case updates were missed
+    pub consume_events: u64,
+    pub update_funding: u64,
+    pub check_new_listings: u64,
 }
 
 pub async fn runner(
     mango_client: Arc<MangoClient>,
     debugging_handle: impl Future,
-    interval_update_banks: u64,
-    interval_consume_events: u64,
-    interval_update_funding: u64,
-    interval_check_new_listings_and_abort: u64,
+    intervals: Intervals,
 ) -> Result<(), anyhow::Error> {
-    let handles1 = mango_client
-        .context
-        .tokens
-        .keys()
-        // TODO: grouping tokens whose oracle might have less confidencen e.g. ORCA with the rest, fails whole ix
-        // TokenUpdateIndexAndRate is known to take max 71k cu
-        // from cargo test-bpf local tests
-        // chunk size of 8 seems to be max before encountering "VersionedTransaction too large" issues
-        .chunks(8)
-        .into_iter()
-        .map(|chunk| {
-            loop_update_index_and_rate(
-                mango_client.clone(),
-                chunk.copied().collect::<Vec<TokenIndex>>(),
-                interval_update_banks,
-            )
-        })
-        .collect::<Vec<_>>();
-
-    let handles2 = mango_client
-        .context
-        .perp_markets
-        .values()
-        .filter(|perp|
-            // MNGO-PERP-OLD
-            perp.market.perp_market_index != 1)
-        .map(|perp| {
-            loop_consume_events(
-                mango_client.clone(),
-                perp.address,
-                perp.market,
-                interval_consume_events,
-            )
-        })
-        .collect::<Vec<_>>();
-
-    let handles3 = mango_client
-        .context
-        .perp_markets
-        .values()
-        .filter(|perp|
-            // MNGO-PERP-OLD
-            perp.market.perp_market_index != 1)
-        .map(|perp| {
-            loop_update_funding(
-                mango_client.clone(),
-                perp.address,
-                perp.market,
-                interval_update_funding,
-            )
-        })
-        .collect::<Vec<_>>();
+    let event_queue_feed = Arc::new(start_event_queue_feed(&mango_client));
 
     futures::join!(
-        futures::future::join_all(handles1),
-        futures::future::join_all(handles2),
-        futures::future::join_all(handles3),
-        loop_check_new_listings_and_abort(
-            mango_client.clone(),
-            interval_check_new_listings_and_abort
-        ),
-        serve_metrics(),
+        loop_manage_crank_tasks(mango_client, event_queue_feed, intervals),
         debugging_handle,
     );
 
     Ok(())
 }
 
-pub async fn loop_check_new_listings_and_abort(mango_client: Arc<MangoClient>, interval: u64) {
-    let mut interval = time::interval(Duration::from_secs(interval));
+/// Event queue data, kept up to date from the websocket
+pub struct EventQueueFeed {
+    account_fetcher: chain_data::AccountFetcher,
+    /// Addresses of event queues that were seen with events in them
+    nonempty_queues: async_channel::Receiver<Pubkey>,
+}
+
+fn start_event_queue_feed(mango_client: &MangoClient) -> EventQueueFeed {
+    let (account_update_sender, account_update_receiver) =
+        async_channel::unbounded::<account_update_stream::Message>();
+    // Only event queues are of interest, streaming all mango accounts would be wasteful.
+    // Queues of perp markets listed later are included, since the filter is by type.
+    websocket_source::start(
+        websocket_source::Config {
+            rpc_ws_url: mango_client.client.cluster.ws_url().to_string(),
+            serum_program: None,
+            open_orders_authority: mango_client.group(),
+            mango_program_filters: vec![
+                RpcFilterType::DataSize((8 + std::mem::size_of::<EventQueue>()) as u64),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    EventQueue::discriminator().to_vec(),
+                )),
+            ],
+        },
+        vec![],
+        account_update_sender,
+    );
+
+    let chain_data = Arc::new(RwLock::new(chain_data::ChainData::new()));
+    let (nonempty_sender, nonempty_receiver) = async_channel::bounded::<Pubkey>(1000);
+    tokio::spawn({
+        let chain_data = chain_data.clone();
+        async move {
+            loop {
+                let message = account_update_receiver
+                    .recv()
+                    .await
+                    .expect("channel not closed");
+                message.update_chain_data(&mut chain_data.write().unwrap());
+
+                if let account_update_stream::Message::Account(update) = message {
+                    let has_events = update
+                        .account
+                        .load::<EventQueue>()
+                        .map(|event_queue| !event_queue.is_empty())
+                        .unwrap_or(false);
+                    if has_events {
+                        nonempty_sender.send_unless_full(update.pubkey).unwrap();
+                    }
+                }
+            }
+        }
+    });
+
+    EventQueueFeed {
+        account_fetcher: chain_data::AccountFetcher {
+            chain_data,
+            rpc: mango_client.client.rpc_async(),
+        },
+        nonempty_queues: nonempty_receiver,
+    }
+}
+
+/// A crank loop, identified by the on-chain state it was started with.
+///
+/// When that state changes, for example because a bank was added to a token, the
+/// loop is restarted with a fresh MangoGroupContext.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum CrankTask {
+    /// Token indexes and their banks
+    UpdateIndexAndRate(Vec<(TokenIndex, Vec<Pubkey>)>),
+    /// Perp market indexes and addresses, one task consumes events for all markets
+    /// so it can combine them into fewer transactions
+    ConsumeEvents(Vec<(PerpMarketIndex, Pubkey)>),
+    /// Perp market index and address
+    UpdateFunding(PerpMarketIndex, Pubkey),
+}
+
+fn crank_tasks(context: &MangoGroupContext) -> HashSet<CrankTask> {
+    let mut tasks = HashSet::new();
+
+    let token_banks = context
+        .tokens
+        .values()
+        .map(|token| (token.token_index, token.mint_info.banks().to_vec()))
+        .sorted_by_key(|(token_index, _)| *token_index)
+        .collect_vec();
+    // TODO: grouping tokens whose oracle might have less confidencen e.g. ORCA with the rest, fails whole ix
+    // TokenUpdateIndexAndRate is known to take max 71k cu
+    // from cargo test-bpf local tests
+    // chunk size of 8 seems to be max before encountering "VersionedTransaction too large" issues
+    for chunk in token_banks.chunks(8) {
+        tasks.insert(CrankTask::UpdateIndexAndRate(chunk.to_vec()));
+    }
+
+    // Force closed markets are being wound down and don't need cranking
+    let perp_markets = context
+        .perp_markets
+        .values()
+        .filter(|perp| !perp.market.is_force_close())
+        .map(|perp| (perp.market.perp_market_index, perp.address))
+        .sorted()
+        .collect_vec();
+    for (index, address) in perp_markets.iter() {
+        tasks.insert(CrankTask::UpdateFunding(*index, *address));
+    }
+    if !perp_markets.is_empty() {
+        tasks.insert(CrankTask::ConsumeEvents(perp_markets));
+    }
+
+    tasks
+}
+
+fn start_crank_task(
+    mango_client: &Arc<MangoClient>,
+    event_queue_feed: &Arc<EventQueueFeed>,
+    task: &CrankTask,
+    intervals: &Intervals,
+) -> JoinHandle<()> {
+    let client = mango_client.clone();
+    match task {
+        CrankTask::UpdateIndexAndRate(token_banks) => {
+            let token_indices = token_banks.iter().map(|(ti, _)| *ti).collect_vec();
+            tokio::spawn(loop_update_index_and_rate(
+                client,
+                token_indices,
+                intervals.update_banks,
+            ))
+        }
+        CrankTask::ConsumeEvents(perp_markets) => {
+            let perp_markets = perp_markets
+                .iter()
+                .map(|(perp_market_index, _)| mango_client.context.perp(*perp_market_index).market)
+                .collect_vec();
+            tokio::spawn(loop_consume_events(
+                client,
+                event_queue_feed.clone(),
+                perp_markets,
+                intervals.consume_events,
+            ))
+        }
+        CrankTask::UpdateFunding(perp_market_index, _) => {
+            let perp_market = mango_client.context.perp(*perp_market_index).market;
+            tokio::spawn(loop_update_funding(
+                client,
+                perp_market,
+                intervals.update_funding,
+            ))
+        }
+    }
+}
+
+/// A client like `mango_client`, but with a freshly loaded group context
+async fn reload_client(mango_client: &MangoClient) -> anyhow::Result<MangoClient> {
+    let context =
+        MangoGroupContext::new_from_rpc(&mango_client.client.rpc_async(), mango_client.group())
+            .await?;
+    MangoClient::new_detail(
+        mango_client.client.clone(),
+        mango_client.mango_account_address,
+        mango_client.owner.clone(),
+        context,
+        mango_client.account_fetcher.clone(),
+    )
+}
+
+/// Runs the crank loops and regularly reloads the group to start loops for new
+/// listings and stop the ones for closed markets.
+async fn loop_manage_crank_tasks(
+    mango_client: Arc<MangoClient>,
+    event_queue_feed: Arc<EventQueueFeed>,
+    intervals: Intervals,
+) {
+    let mut mango_client = mango_client;
+    let mut running = HashMap::<CrankTask, JoinHandle<()>>::new();
+    let mut interval = time::interval(Duration::from_secs(intervals.check_new_listings));
+    // the first tick is immediate and uses the context the client was created with
+    interval.tick().await;
     loop {
-        if mango_client
-            .context
-            .new_tokens_listed(&mango_client.client.rpc_async())
-            .await
-            .unwrap()
-            || mango_client
-                .context
-                .new_perp_markets_listed(&mango_client.client.rpc_async())
-                .await
-                .unwrap()
-        {
-            std::process::abort();
+        let tasks = crank_tasks(&mango_client.context);
+        running.retain(|task, handle| {
+            let keep = tasks.contains(task);
+            if !keep {
+                info!("stopping crank task {:?}", task);
+                handle.abort();
+            }
+            keep
+        });
+        for task in tasks {
+            if running.contains_key(&task) {
+                continue;
+            }
+            info!("starting crank task {:?}", task);
+            let handle = start_crank_task(&mango_client, &event_queue_feed, &task, &intervals);
+            running.insert(task, handle);
         }
 
         interval.tick().await;
+        loop {
+            match reload_client(&mango_client).await {
+                Ok(client) => {
+                    mango_client = Arc::new(client);
+                    break;
+                }
+                Err(err) => {
+                    error!("could not reload group context: {err:?}");
+                    interval.tick().await;
+                }
+            }
+        }
     }
 }
 
@@ -188,55 +298,25 @@ pub async fn loop_update_index_and_rate(
             .map(|token_index| client.context.token(*token_index).name.to_owned())
             .join(",");
 
-        let mut instructions = vec![];
-        for token_index in token_indices_clone.iter() {
-            let token = client.context.token(*token_index);
-            let banks_for_a_token = token.mint_info.banks();
-            let oracle = token.mint_info.oracle;
-
-            let mut ix = Instruction {
-                program_id: mango_v4::id(),
-                accounts: anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::TokenUpdateIndexAndRate {
-                        group: token.mint_info.group,
-                        mint_info: token.mint_info_address,
-                        oracle,
-                        instructions: solana_program::sysvar::instructions::id(),
-                    },
-                    None,
-                ),
-                data: anchor_lang::InstructionData::data(
-                    &mango_v4::instruction::TokenUpdateIndexAndRate {},
-                ),
-            };
-            let mut banks = banks_for_a_token
-                .iter()
-                .map(|bank_pubkey| AccountMeta {
-                    pubkey: *bank_pubkey,
-                    is_signer: false,
-                    is_writable: true,
-                })
-                .collect::<Vec<_>>();
-            ix.accounts.append(&mut banks);
-            instructions.push(ix);
-        }
+        let instructions = token_indices_clone
+            .iter()
+            .map(|token_index| client.token_update_index_and_rate_instruction(*token_index))
+            .collect_vec();
         let pre = Instant::now();
         let sig_result = client
             .send_and_confirm_permissionless_tx(instructions)
             .await;
 
         let confirmation_time = pre.elapsed().as_millis();
-        METRIC_CONFIRMATION_TIMES.observe(confirmation_time as f64);
+        TX_METRICS.record("update_index_and_rate", pre, &sig_result);
 
         if let Err(e) = sig_result {
-            METRIC_UPDATE_TOKENS_FAILURE.inc();
             info!(
                 "metricName=UpdateTokensV4Failure tokens={} durationMs={} error={}",
                 token_names, confirmation_time, e
             );
             error!("{:?}", e)
         } else {
-            METRIC_UPDATE_TOKENS_SUCCESS.inc();
             info!(
                 "metricName=UpdateTokensV4Success tokens={} durationMs={}",
                 token_names, confirmation_time,
@@ -246,129 +326,219 @@ pub async fn loop_update_index_and_rate(
     }
 }
 
+/// Max events to consume per market and transaction
+const CONSUME_EVENTS_LIMIT: usize = 10;
+
+/// Consume events instructions for several markets are packed into one transaction
+/// as long as it doesn't reference more accounts than this
+const CONSUME_EVENTS_MAX_ACCOUNTS_PER_TX: usize = 30;
+
+struct ConsumeEventsWork {
+    perp_market: PerpMarket,
+    event_accounts: HashSet<Pubkey>,
+    num_of_events: u32,
+}
+
+/// The mango accounts involved in the next events of the queue
+fn event_accounts(mut event_queue: EventQueue) -> anyhow::Result<(HashSet<Pubkey>, u32)> {
+    let mut num_of_events = 0;
+    // TODO: future, choose better constant of how many max mango accounts to pack
+    let mut set = HashSet::new();
+    for _ in 0..CONSUME_EVENTS_LIMIT {
+        let event = match event_queue.peek_front() {
+            None => break,
+            Some(e) => e,
+        };
+        match EventType::try_from(event.event_type)? {
+            EventType::Fill => {
+                let fill: &FillEvent = cast_ref(event);
+                set.insert(fill.maker);
+                set.insert(fill.taker);
+            }
+            EventType::Out => {
+                let out: &OutEvent = cast_ref(event);
+                set.insert(out.owner);
+            }
+            EventType::Liquidate => {}
+        }
+        event_queue.pop_front()?;
+        num_of_events += 1;
+    }
+    Ok((set, num_of_events))
+}
+
+/// Groups work into transactions, see CONSUME_EVENTS_MAX_ACCOUNTS_PER_TX
+fn batch_consume_events_work(work: Vec<ConsumeEventsWork>) -> Vec<Vec<ConsumeEventsWork>> {
+    let mut batches: Vec<Vec<ConsumeEventsWork>> = vec![];
+    let mut batch_accounts = 0;
+    for w in work {
+        // group is shared, perp market, event queue and mango accounts are per market
+        let accounts = 2 + w.event_accounts.len();
+        match batches.last_mut() {
+            Some(batch) if batch_accounts + accounts <= CONSUME_EVENTS_MAX_ACCOUNTS_PER_TX => {
+                batch_accounts += accounts;
+                batch.push(w);
+            }
+            _ => {
+                batch_accounts = 1 + accounts;
+                batches.push(vec![w]);
+            }
+        }
+    }
+    batches
+}
+
 pub async fn loop_consume_events(
     mango_client: Arc<MangoClient>,
-    pk: Pubkey,
-    perp_market: PerpMarket,
+    event_queue_feed: Arc<EventQueueFeed>,
+    perp_markets: Vec<PerpMarket>,
     interval: u64,
 ) {
-    let mut interval = time::interval(Duration::from_secs(interval));
-    loop {
-        interval.tick().await;
+    let perp_markets = perp_markets
+        .into_iter()
+        .map(|perp_market| (perp_market.event_queue, perp_market))
+        .collect::<HashMap<Pubkey, PerpMarket>>();
+    let account_fetcher = &event_queue_feed.account_fetcher;
 
-        let client = mango_client.clone();
+    // When events were first seen in a queue, for the lag metric
+    let mut nonempty_since = HashMap::<Pubkey, Instant>::new();
 
-        let find_accounts = || async {
-            let mut num_of_events = 0;
-            let mut event_queue: EventQueue = client
-                .client
-                .rpc_anchor_account(&perp_market.event_queue)
-                .await?;
-
-            // TODO: future, choose better constant of how many max events to pack
-            // TODO: future, choose better constant of how many max mango accounts to pack
-            let mut set = HashSet::new();
-            for _ in 0..10 {
-                let event = match event_queue.peek_front() {
-                    None => break,
-                    Some(e) => e,
-                };
-                match EventType::try_from(event.event_type)? {
-                    EventType::Fill => {
-                        let fill: &FillEvent = cast_ref(event);
-                        set.insert(fill.maker);
-                        set.insert(fill.taker);
-                    }
-                    EventType::Out => {
-                        let out: &OutEvent = cast_ref(event);
-                        set.insert(out.owner);
+    // Queues that still had events after consuming, they are processed without waiting
+    let mut remaining_queues = HashSet::<Pubkey>::new();
+
+    let mut interval = time::interval(Duration::from_secs(interval));
+    loop {
+        let mut queues = std::mem::take(&mut remaining_queues);
+        if queues.is_empty() {
+            tokio::select! {
+                queue = event_queue_feed.nonempty_queues.recv() => {
+                    queues.insert(queue.expect("channel not closed"));
+                }
+                _ = interval.tick() => {
+                    // fallback in case websocket updates were missed, also loads the
+                    // queues initially
+                    for queue in perp_markets.keys() {
+                        if let Err(err) = account_fetcher.refresh_account_via_rpc(queue).await {
+                            error!("refreshing event queue {queue}: {err:?}");
+                            continue;
+                        }
+                        queues.insert(*queue);
                     }
-                    EventType::Liquidate => {}
                 }
-                event_queue.pop_front()?;
-                num_of_events += 1;
             }
+        }
+        while let Ok(queue) = event_queue_feed.nonempty_queues.try_recv() {
+            queues.insert(queue);
+        }
 
-            if num_of_events == 0 {
-                return Ok(None);
-            }
-
-            Ok(Some((set, num_of_events)))
-        };
-
-        let event_info: anyhow::Result<Option<(HashSet<Pubkey>, u32)>> = find_accounts().await;
-
-        let (event_accounts, num_of_events) = match event_info {
-            Ok(Some(x)) => x,
-            Ok(None) => continue,
-            Err(err) => {
-                error!("preparing consume_events ams: {err:?}");
+        let mut work = vec![];
+        for queue in queues {
+            // updates for queues of other groups or closed markets are ignored
+            let perp_market = match perp_markets.get(&queue) {
+                Some(perp_market) => perp_market,
+                None => continue,
+            };
+            let event_queue: EventQueue = match account_fetcher.fetch(&queue) {
+                Ok(event_queue) => event_queue,
+                Err(err) => {
+                    error!("loading event queue of {}: {err:?}", perp_market.name());
+                    continue;
+                }
+            };
+            METRIC_EVENT_QUEUE_DEPTH
+                .with_label_values(&[perp_market.name()])
+                .set(event_queue.len() as i64);
+            if event_queue.is_empty() {
+                nonempty_since.remove(&queue);
                 continue;
             }
-        };
+            nonempty_since.entry(queue).or_insert_with(Instant::now);
+
+            match event_accounts(event_queue) {
+                Ok((event_accounts, num_of_events)) => work.push(ConsumeEventsWork {
+                    perp_market: *perp_market,
+                    event_accounts,
+                    num_of_events,
+                }),
+                Err(err) => error!("preparing consume_events ams: {err:?}"),
+            }
+        }
 
-        let mut event_ams = event_accounts
-            .iter()
-            .map(|key| -> AccountMeta {
-                AccountMeta {
-                    pubkey: *key,
-                    is_signer: false,
-                    is_writable: true,
+        for batch in batch_consume_events_work(work) {
+            let ixs = batch
+                .iter()
+                .map(|w| {
+                    mango_client.perp_consume_events_instruction(
+                        w.perp_market.perp_market_index,
+                        w.event_accounts.iter().copied(),
+                        CONSUME_EVENTS_LIMIT,
+                    )
+                })
+                .collect_vec();
+            let markets = batch.iter().map(|w| w.perp_market.name()).join(",");
+            let num_of_events: u32 = batch.iter().map(|w| w.num_of_events).sum();
+
+            let pre = Instant::now();
+            let sig_result = mango_client.send_and_confirm_permissionless_tx(ixs).await;
+
+            let confirmation_time = pre.elapsed().as_millis();
+            TX_METRICS.record("consume_events", pre, &sig_result);
+
+            let sig = match sig_result {
+                Ok(sig) => sig,
+                Err(e) => {
+                    info!(
+                        "metricName=ConsumeEventsV4Failure market={} durationMs={} consumed={} error={}",
+                        markets,
+                        confirmation_time,
+                        num_of_events,
+                        e.to_string()
+                    );
+                    error!("{:?}", e);
+                    continue;
                 }
-            })
-            .collect::<Vec<_>>();
-
-        let pre = Instant::now();
-        let ix = Instruction {
-            program_id: mango_v4::id(),
-            accounts: {
-                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpConsumeEvents {
-                        group: perp_market.group,
-                        perp_market: pk,
-                        event_queue: perp_market.event_queue,
-                    },
-                    None,
-                );
-                ams.append(&mut event_ams);
-                ams
-            },
-            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpConsumeEvents {
-                limit: 10,
-            }),
-        };
-
-        let sig_result = client.send_and_confirm_permissionless_tx(vec![ix]).await;
-
-        let confirmation_time = pre.elapsed().as_millis();
-        METRIC_CONFIRMATION_TIMES.observe(confirmation_time as f64);
-
-        if let Err(e) = sig_result {
-            METRIC_CONSUME_EVENTS_FAILURE.inc();
-            info!(
-                "metricName=ConsumeEventsV4Failure market={} durationMs={} consumed={} error={}",
-                perp_market.name(),
-                confirmation_time,
-                num_of_events,
-                e.to_string()
-            );
-            error!("{:?}", e)
-        } else {
-            METRIC_CONSUME_EVENTS_SUCCESS.inc();
+            };
             info!(
                 "metricName=ConsumeEventsV4Success market={} durationMs={} consumed={}",
-                perp_market.name(),
-                confirmation_time,
-                num_of_events,
+                markets, confirmation_time, num_of_events,
             );
-            info!("{:?}", sig_result);
+            info!("{:?}", sig);
+
+            let queues = batch
+                .iter()
+                .map(|w| w.perp_market.event_queue)
+                .collect_vec();
+            for queue in queues.iter() {
+                if let Some(since) = nonempty_since.remove(queue) {
+                    METRIC_CONSUME_EVENTS_LAG.observe(since.elapsed().as_secs_f64());
+                }
+            }
+
+            // Make sure the consumed events aren't seen again in stale queue data,
+            // remaining events will be picked up from the refreshed queues
+            let refreshed = async {
+                let slot = account_fetcher.transaction_max_slot(&[sig]).await?;
+                account_fetcher
+                    .refresh_accounts_via_rpc_until_slot(&queues, slot, Duration::from_secs(10))
+                    .await
+            };
+            if let Err(err) = refreshed.await {
+                warn!("could not refresh event queues after consuming: {err:?}");
+                continue;
+            }
+            for queue in queues {
+                if let Ok(event_queue) = account_fetcher.fetch::<EventQueue>(&queue) {
+                    if !event_queue.is_empty() {
+                        remaining_queues.insert(queue);
+                    }
+                }
+            }
         }
     }
 }
 
 pub async fn loop_update_funding(
     mango_client: Arc<MangoClient>,
-    pk: Pubkey,
     perp_market: PerpMarket,
     interval: u64,
 ) {
@@ -379,27 +549,13 @@ pub async fn loop_update_funding(
         let client = mango_client.clone();
 
         let pre = Instant::now();
-        let ix = Instruction {
-            program_id: mango_v4::id(),
-            accounts: anchor_lang::ToAccountMetas::to_account_metas(
-                &mango_v4::accounts::PerpUpdateFunding {
-                    group: perp_market.group,
-                    perp_market: pk,
-                    bids: perp_market.bids,
-                    asks: perp_market.asks,
-                    oracle: perp_market.oracle,
-                },
-                None,
-            ),
-            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpUpdateFunding {}),
-        };
+        let ix = client.perp_update_funding_instruction(perp_market.perp_market_index);
         let sig_result = client.send_and_confirm_permissionless_tx(vec![ix]).await;
 
         let confirmation_time = pre.elapsed().as_millis();
-        METRIC_CONFIRMATION_TIMES.observe(confirmation_time as f64);
+        TX_METRICS.record("update_funding", pre, &sig_result);
 
         if let Err(e) = sig_result {
-            METRIC_UPDATE_FUNDING_FAILURE.inc();
             error!(
                 "metricName=UpdateFundingV4Error market={} durationMs={} error={}",
                 perp_market.name(),
@@ -408,7 +564,6 @@ pub async fn loop_update_funding(
             );
             error!("{:?}", e)
         } else {
-            METRIC_UPDATE_FUNDING_SUCCESS.inc();
             info!(
                 "metricName=UpdateFundingV4Success market={} durationMs={}",
                 perp_market.name(),
@@ -1,5 +1,6 @@
 mod crank;
 mod taker;
+mod tcs_cleanup;
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,6 +9,7 @@ use anchor_client::Cluster;
 
 use clap::{Parser, Subcommand};
 use mango_v4_client::{keypair_from_cli, Client, MangoClient, TransactionBuilderConfig};
+use mango_v4_metrics::{Metrics, TransactionMetrics};
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use tokio::time;
@@ -20,6 +22,11 @@ use tracing::*;
 // - I'm really annoyed about Keypair not being clonable. Seems everyone works around that manually. Should make a PR to solana to newtype it and provide that function.
 // keypair_from_arg_or_env could be a function
 
+lazy_static::lazy_static! {
+    pub static ref METRICS: Metrics = Metrics::new("keeper");
+    pub static ref TX_METRICS: TransactionMetrics = TransactionMetrics::new(&METRICS);
+}
+
 #[derive(Parser, Debug)]
 #[clap()]
 struct CliDotenv {
@@ -49,14 +56,29 @@ struct Cli {
     // TODO: use duration type from rust instead of u64 for all these below intervals
     interval_update_banks: u64,
 
-    #[clap(long, env, default_value_t = 5)]
+    /// events are consumed as soon as the websocket reports them, this is how often
+    /// all event queues are checked in case updates were missed
+    #[clap(long, env, default_value_t = 30)]
     interval_consume_events: u64,
 
     #[clap(long, env, default_value_t = 5)]
     interval_update_funding: u64,
 
-    #[clap(long, env, default_value_t = 120)]
-    interval_check_new_listings_and_abort: u64,
+    /// how often to check the group for new or closed markets, default 120
+    #[clap(long, env)]
+    interval_check_new_listings: Option<u64>,
+
+    /// deprecated alias of interval_check_new_listings, the flag and env var still work
+    #[clap(long, env, hide = true)]
+    interval_check_new_listings_and_abort: Option<u64>,
+
+    /// how often to look for and remove expired token conditional swaps
+    #[clap(long, env, default_value_t = 600)]
+    interval_tcs_cleanup: u64,
+
+    /// compute limit requested per removed token conditional swap
+    #[clap(long, env, default_value_t = 150_000)]
+    compute_limit_for_tcs_cleanup: u32,
 
     #[clap(long, env, default_value_t = 10)]
     timeout: u64,
@@ -64,12 +86,22 @@ struct Cli {
     /// prioritize each transaction with this many microlamports/cu
     #[clap(long, env, default_value = "0")]
     prioritization_micro_lamports: u64,
+
+    /// port for serving prometheus metrics on /metrics
+    #[clap(long, env, default_value = "9091")]
+    metrics_port: u16,
 }
 
 #[derive(Subcommand, Debug, Clone)]
 enum Command {
     Crank {},
     Taker {},
+    /// Remove expired token conditional swaps from all accounts of the group
+    ///
+    /// Token positions that only the removed tcs kept open are dusted and closed along
+    /// with them. Other empty positions are out of scope: the program has no
+    /// permissionless way of closing them, only the account owner can.
+    TcsCleanup {},
 }
 
 #[tokio::main]
@@ -94,6 +126,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let commitment = match cli.command {
         Command::Crank { .. } => CommitmentConfig::confirmed(),
         Command::Taker { .. } => CommitmentConfig::confirmed(),
+        Command::TcsCleanup { .. } => CommitmentConfig::confirmed(),
     };
 
     let mango_client = Arc::new(
@@ -128,16 +161,23 @@ async fn main() -> Result<(), anyhow::Error> {
         }
     };
 
+    tokio::spawn(mango_v4_metrics::serve(METRICS.clone(), cli.metrics_port));
+
     match cli.command {
         Command::Crank { .. } => {
             let client = mango_client.clone();
             crank::runner(
                 client,
                 debugging_handle,
-                cli.interval_update_banks,
-                cli.interval_consume_events,
-                cli.interval_update_funding,
-                cli.interval_check_new_listings_and_abort,
+                crank::Intervals {
+                    update_banks: cli.interval_update_banks,
+                    consume_events: cli.interval_consume_events,
+                    update_funding: cli.interval_update_funding,
+                    check_new_listings: cli
+                        .interval_check_new_listings
+                        .or(cli.interval_check_new_listings_and_abort)
+                        .unwrap_or(120),
+                },
             )
             .await
         }
@@ -145,5 +185,17 @@ async fn main() -> Result<(), anyhow::Error> {
             let client = mango_client.clone();
             taker::runner(client, debugging_handle).await
         }
+        Command::TcsCleanup { .. } => {
+            let client = mango_client.clone();
+            tcs_cleanup::runner(
+                client,
+                debugging_handle,
+                tcs_cleanup::Config {
+                    interval: Duration::from_secs(cli.interval_tcs_cleanup),
+                    compute_limit: cli.compute_limit_for_tcs_cleanup,
+                },
+            )
+            .await
+        }
     }
 }
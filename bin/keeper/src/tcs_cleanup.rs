@@ -0,0 +1,300 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::Discriminator;
+use futures::Future;
+use itertools::Itertools;
+use mango_v4::state::{MangoAccount, MangoAccountValue, TokenIndex};
+use mango_v4_client::{
+    account_update_stream, chain_data, snapshot_source, websocket_source, AccountFetcher,
+    MangoClient,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::{account::ReadableAccount, pubkey::Pubkey};
+use tokio::time;
+use tracing::*;
+
+use crate::TX_METRICS;
+
+/// Max number of expired tcs removed per transaction, they all need the same accounts
+const MAX_TCS_PER_TX: usize = 4;
+
+pub struct Config {
+    pub interval: Duration,
+    pub compute_limit: u32,
+}
+
+/// Expired token conditional swaps on one account
+struct ExpiredTcs {
+    pubkey: Pubkey,
+    tcs_ids: Vec<u64>,
+}
+
+/// What removing expired token conditional swaps from an account achieved
+struct Cleaned {
+    pubkey: Pubkey,
+    tcs_ids: Vec<u64>,
+    /// Token positions that were only kept alive by the removed tcs. The trigger
+    /// dusts and closes them; this is the only dust cleanup the keeper can do, since
+    /// other empty positions can only be closed by their owner.
+    closed_token_positions: Vec<TokenIndex>,
+}
+
+pub async fn runner(
+    mango_client: Arc<MangoClient>,
+    debugging_handle: impl Future,
+    config: Config,
+) -> Result<(), anyhow::Error> {
+    let mango_group = mango_client.group();
+    let cluster = &mango_client.client.cluster;
+
+    // The representation of current on-chain account data
+    let chain_data = Arc::new(RwLock::new(chain_data::ChainData::new()));
+    let account_fetcher = chain_data::AccountFetcher {
+        chain_data: chain_data.clone(),
+        rpc: mango_client.client.rpc_async(),
+    };
+
+    let (account_update_sender, account_update_receiver) =
+        async_channel::unbounded::<account_update_stream::Message>();
+
+    // The websocket keeps slots and changed accounts current, the snapshots
+    // provide all mango accounts
+    websocket_source::start(
+        websocket_source::Config {
+            rpc_ws_url: cluster.ws_url().to_string(),
+            serum_program: None,
+            open_orders_authority: mango_group,
+            // only mango accounts of the group
+            mango_program_filters: vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    MangoAccount::discriminator().to_vec(),
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, mango_group.to_bytes().to_vec())),
+            ],
+        },
+        vec![],
+        account_update_sender.clone(),
+    );
+    let first_websocket_slot = websocket_source::get_next_create_bank_slot(
+        account_update_receiver.clone(),
+        Duration::from_secs(10),
+    )
+    .await?;
+    snapshot_source::start(
+        snapshot_source::Config {
+            rpc_http_url: cluster.url().to_string(),
+            mango_group,
+            get_multiple_accounts_count: 100,
+            parallel_rpc_requests: 10,
+            snapshot_interval: config.interval,
+            min_slot: first_websocket_slot + 10,
+            snapshot_file: None,
+        },
+        vec![],
+        account_update_sender,
+    );
+
+    let one_snapshot_done = Arc::new(AtomicBool::new(false));
+    let data_job = {
+        let one_snapshot_done = one_snapshot_done.clone();
+        async move {
+            loop {
+                let message = account_update_receiver
+                    .recv()
+                    .await
+                    .expect("channel not closed");
+                message.update_chain_data(&mut chain_data.write().unwrap());
+                if let account_update_stream::Message::Snapshot(_) = message {
+                    one_snapshot_done.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    };
+
+    let cleanup_job = async {
+        let mut interval = time::interval(config.interval);
+        loop {
+            interval.tick().await;
+            if !one_snapshot_done.load(Ordering::Relaxed) {
+                continue;
+            }
+            if let Err(err) = cleanup(&mango_client, &account_fetcher, &config).await {
+                error!("tcs cleanup: {err:?}");
+            }
+        }
+    };
+
+    futures::join!(data_job, cleanup_job, debugging_handle);
+
+    Ok(())
+}
+
+fn expired_tcs(
+    mango_client: &MangoClient,
+    accounts: Vec<(Pubkey, MangoAccountValue)>,
+    now_ts: u64,
+) -> Vec<ExpiredTcs> {
+    accounts
+        .into_iter()
+        // the keeper's own account acts as the liqor and can't trigger its own tcs
+        .filter(|(pubkey, _)| *pubkey != mango_client.mango_account_address)
+        .filter_map(|(pubkey, account)| {
+            let tcs_ids = account
+                .active_token_conditional_swaps()
+                .filter(|tcs| tcs.is_expired(now_ts))
+                .map(|tcs| tcs.id)
+                .collect_vec();
+            (!tcs_ids.is_empty()).then_some(ExpiredTcs { pubkey, tcs_ids })
+        })
+        .collect()
+}
+
+async fn cleanup(
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let now_ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let accounts = account_fetcher
+        .fetch_program_accounts(&mango_v4::id(), MangoAccount::discriminator())
+        .await?
+        .into_iter()
+        .filter_map(|(pk, acc)| {
+            let mango_account = MangoAccountValue::from_bytes(&acc.data()[8..]).ok()?;
+            (mango_account.fixed.group == mango_client.group()).then_some((pk, mango_account))
+        })
+        .collect_vec();
+    let scanned = accounts.len();
+
+    let work = expired_tcs(mango_client, accounts, now_ts);
+    let expired: usize = work.iter().map(|w| w.tcs_ids.len()).sum();
+    info!(
+        "scanned {} accounts, found {} expired tcs on {} accounts",
+        scanned,
+        expired,
+        work.len()
+    );
+
+    let mut cleaned = vec![];
+    for account_work in work {
+        let pubkey = account_work.pubkey;
+        match remove_expired_tcs(mango_client, account_fetcher, account_work, config, now_ts).await
+        {
+            Ok(result) => {
+                info!(
+                    "metricName=TcsCleanupSuccess account={} tcs={:?} closedTokenPositions={:?}",
+                    result.pubkey, result.tcs_ids, result.closed_token_positions,
+                );
+                cleaned.push(result);
+            }
+            Err(err) => {
+                info!(
+                    "metricName=TcsCleanupFailure account={} error={}",
+                    pubkey,
+                    err.to_string()
+                );
+                error!("{:?}", err);
+            }
+        }
+    }
+
+    info!(
+        "removed {} expired tcs on {} accounts, closing {} token positions",
+        cleaned.iter().map(|c| c.tcs_ids.len()).sum::<usize>(),
+        cleaned.len(),
+        cleaned
+            .iter()
+            .map(|c| c.closed_token_positions.len())
+            .sum::<usize>(),
+    );
+    Ok(())
+}
+
+/// Triggering an expired tcs removes it without any token exchange and releases
+/// the token positions it held.
+async fn remove_expired_tcs(
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    work: ExpiredTcs,
+    config: &Config,
+    now_ts: u64,
+) -> anyhow::Result<Cleaned> {
+    // the owner may have cancelled some of them since the scan
+    let liqee = account_fetcher
+        .fetch_fresh_mango_account(&work.pubkey)
+        .await?;
+    let tcs_ids = work
+        .tcs_ids
+        .iter()
+        .copied()
+        .filter(|id| {
+            liqee
+                .token_conditional_swap_by_id(*id)
+                .map(|(_, tcs)| tcs.is_expired(now_ts))
+                .unwrap_or(false)
+        })
+        .collect_vec();
+    let active_tokens_before: HashSet<TokenIndex> = liqee
+        .active_token_positions()
+        .map(|p| p.token_index)
+        .collect();
+
+    let mut removed = vec![];
+    for tcs_ids in tcs_ids.chunks(MAX_TCS_PER_TX) {
+        let mut ixs = vec![
+            solana_sdk::compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(
+                config.compute_limit * tcs_ids.len() as u32,
+            ),
+        ];
+        for tcs_id in tcs_ids {
+            ixs.push(
+                mango_client
+                    .token_conditional_swap_trigger_instruction(
+                        (&work.pubkey, &liqee),
+                        *tcs_id,
+                        0,
+                        0,
+                        &[],
+                    )
+                    .await?,
+            );
+        }
+        let started = Instant::now();
+        let result = mango_client.send_and_confirm_owner_tx(ixs).await;
+        TX_METRICS.record("tcs_cleanup", started, &result);
+        let txsig = result?;
+        info!(
+            pubkey = %work.pubkey,
+            ?tcs_ids,
+            %txsig,
+            "removed expired token conditional swaps",
+        );
+        removed.extend_from_slice(tcs_ids);
+    }
+
+    let liqee = account_fetcher
+        .fetch_fresh_mango_account(&work.pubkey)
+        .await?;
+    let active_tokens_after: HashSet<TokenIndex> = liqee
+        .active_token_positions()
+        .map(|p| p.token_index)
+        .collect();
+    Ok(Cleaned {
+        pubkey: work.pubkey,
+        tcs_ids: removed,
+        closed_token_positions: active_tokens_before
+            .difference(&active_tokens_after)
+            .copied()
+            .sorted()
+            .collect(),
+    })
+}
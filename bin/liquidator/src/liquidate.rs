@@ -1,10 +1,11 @@
 use std::collections::HashSet;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use mango_v4::health::{HealthCache, HealthType};
-use mango_v4::state::{MangoAccountValue, PerpMarketIndex, Side, TokenIndex, QUOTE_TOKEN_INDEX};
+use mango_v4::state::{MangoAccountValue, PerpMarketIndex, Side, TokenIndex};
 use mango_v4_client::{chain_data, health_cache, MangoClient};
+use mango_v4_metrics::TransactionMetrics;
 use solana_sdk::signature::Signature;
 
 use futures::{stream, StreamExt, TryStreamExt};
@@ -12,6 +13,8 @@ use rand::seq::SliceRandom;
 use tracing::*;
 use {anyhow::Context, fixed::types::I80F48, solana_sdk::pubkey::Pubkey};
 
+use crate::profitability::{self, Estimate};
+use crate::token_swap_info::TokenSwapInfoUpdater;
 use crate::util;
 
 #[derive(Clone)]
@@ -19,6 +22,24 @@ pub struct Config {
     pub min_health_ratio: f64,
     pub refresh_timeout: Duration,
     pub compute_limit_for_liq_ix: u32,
+    pub prioritization_micro_lamports: u64,
+    /// Liquidations with a lower expected profit are skipped, in native quote
+    pub min_profit: f64,
+    pub tx_metrics: TransactionMetrics,
+}
+
+/// A phase 2 liquidation step
+#[derive(Clone, Debug)]
+enum LiquidationAction {
+    PerpBaseOrPositivePnl {
+        perp_market_index: PerpMarketIndex,
+        base_lots: i64,
+        price: I80F48,
+    },
+    TokenWithToken {
+        asset_token_index: TokenIndex,
+        liab_token_index: TokenIndex,
+    },
 }
 
 struct LiquidateHelper<'a> {
@@ -31,6 +52,7 @@ struct LiquidateHelper<'a> {
     liqor_min_health_ratio: I80F48,
     allowed_asset_tokens: HashSet<Pubkey>,
     allowed_liab_tokens: HashSet<Pubkey>,
+    token_swap_info: &'a TokenSwapInfoUpdater,
     config: Config,
 }
 
@@ -110,41 +132,135 @@ impl<'a> LiquidateHelper<'a> {
         )
     }
 
-    async fn perp_liq_base_or_positive_pnl(&self) -> anyhow::Result<Option<Signature>> {
-        let all_perp_base_positions: anyhow::Result<
-            Vec<Option<(PerpMarketIndex, i64, I80F48, I80F48)>>,
-        > = stream::iter(self.liqee.active_perp_positions())
-            .then(|pp| async {
-                let base_lots = pp.base_position_lots();
-                if (base_lots == 0 && pp.quote_position_native() <= 0) || pp.has_open_taker_fills()
-                {
-                    return Ok(None);
-                }
-                let price = self.client.perp_oracle_price(pp.market_index).await?;
-                Ok(Some((
-                    pp.market_index,
+    /// Signature and priority fees of a liquidation transaction, in native quote
+    async fn tx_cost(&self) -> anyhow::Result<f64> {
+        let lamports = profitability::tx_cost_lamports(
+            self.config.compute_limit_for_liq_ix,
+            self.config.prioritization_micro_lamports,
+        );
+        // the wrapped SOL bank's price is per lamport
+        let sol_price = match self
+            .client
+            .context
+            .token_by_mint(&spl_token::native_mint::id())
+        {
+            Ok(token) => self.client.bank_oracle_price(token.token_index).await?,
+            Err(_) => return Ok(0.0),
+        };
+        Ok(lamports as f64 * sol_price.to_num::<f64>())
+    }
+
+    /// Logs the inputs of the profitability decision and checks it against the configured minimum
+    fn is_profitable(&self, action: &LiquidationAction, estimate: &Estimate) -> bool {
+        let profitable = estimate.profit() >= self.config.min_profit;
+        info!(
+            ?action,
+            value = estimate.value,
+            fee = estimate.fee,
+            rebalance_cost = estimate.rebalance_cost,
+            tx_cost = estimate.tx_cost,
+            profit = estimate.profit(),
+            min_profit = self.config.min_profit,
+            profitable,
+            "liquidation profitability",
+        );
+        profitable
+    }
+
+    /// Phase 2 liquidations the liqor could do, with the expected profit if it
+    /// could take over the full positions
+    async fn liquidation_candidates(
+        &self,
+        tx_cost: f64,
+    ) -> anyhow::Result<Vec<(LiquidationAction, Estimate)>> {
+        let mut candidates = vec![];
+
+        for pp in self.liqee.active_perp_positions() {
+            let base_lots = pp.base_position_lots();
+            let quote = pp.quote_position_native();
+            if (base_lots == 0 && quote <= 0) || pp.has_open_taker_fills() {
+                continue;
+            }
+            let perp = self.client.context.perp(pp.market_index);
+            let price = self.client.perp_oracle_price(pp.market_index).await?;
+            let base_value = (pp.base_position_native(&perp.market).abs() * price).to_num::<f64>();
+            // positive pnl is only taken over once the base position is gone
+            let pnl_value = if base_lots == 0 {
+                quote.to_num::<f64>()
+            } else {
+                0.0
+            };
+            candidates.push((
+                LiquidationAction::PerpBaseOrPositivePnl {
+                    perp_market_index: pp.market_index,
                     base_lots,
                     price,
-                    I80F48::from(base_lots.abs()) * price,
-                )))
-            })
-            .try_collect()
-            .await;
-        let mut perp_base_positions = all_perp_base_positions?
-            .into_iter()
-            .filter_map(|x| x)
-            .collect::<Vec<_>>();
-        perp_base_positions.sort_by(|a, b| a.3.cmp(&b.3));
+                },
+                profitability::perp_liq_base_or_positive_pnl(
+                    base_value,
+                    pnl_value,
+                    &perp.market,
+                    tx_cost,
+                ),
+            ));
+        }
 
-        if perp_base_positions.is_empty() {
-            return Ok(None);
+        if self.health_cache.has_possible_spot_liquidations() {
+            let tokens = self.tokens().await?;
+            let assets = tokens
+                .iter()
+                .filter(|(asset_token_index, _asset_price, asset_usdc_equivalent)| {
+                    asset_usdc_equivalent.is_positive()
+                        && self
+                            .allowed_asset_tokens
+                            .contains(&self.client.context.token(*asset_token_index).mint_info.mint)
+                })
+                .collect_vec();
+            let liabs = tokens
+                .iter()
+                .filter(|(liab_token_index, _liab_price, liab_usdc_equivalent)| {
+                    liab_usdc_equivalent.is_negative()
+                        && self
+                            .allowed_liab_tokens
+                            .contains(&self.client.context.token(*liab_token_index).mint_info.mint)
+                })
+                .collect_vec();
+            for (liab_token_index, _, liab_usdc_equivalent) in liabs {
+                let liab_bank = self.client.first_bank(*liab_token_index).await?;
+                for (asset_token_index, _, asset_usdc_equivalent) in assets.iter() {
+                    let value = (*asset_usdc_equivalent)
+                        .min(-*liab_usdc_equivalent)
+                        .to_num::<f64>();
+                    candidates.push((
+                        LiquidationAction::TokenWithToken {
+                            asset_token_index: *asset_token_index,
+                            liab_token_index: *liab_token_index,
+                        },
+                        profitability::token_liq_with_token(
+                            value,
+                            &liab_bank,
+                            self.token_swap_info.swap_info(*asset_token_index),
+                            self.token_swap_info.swap_info(*liab_token_index),
+                            tx_cost,
+                        ),
+                    ));
+                }
+            }
         }
 
-        // Liquidate the highest-value perp base position
-        let (perp_market_index, base_lots, price, _) = perp_base_positions.last().unwrap();
-        let perp = self.client.context.perp(*perp_market_index);
+        Ok(candidates)
+    }
 
-        let (side, side_signum) = if *base_lots > 0 {
+    async fn perp_liq_base_or_positive_pnl(
+        &self,
+        perp_market_index: PerpMarketIndex,
+        base_lots: i64,
+        price: I80F48,
+        tx_cost: f64,
+    ) -> anyhow::Result<Option<Signature>> {
+        let perp = self.client.context.perp(perp_market_index);
+
+        let (side, side_signum) = if base_lots > 0 {
             (Side::Bid, 1)
         } else {
             (Side::Ask, -1)
@@ -154,31 +270,32 @@ impl<'a> LiquidateHelper<'a> {
         // TODO: This is risky for the liqor. It should track how much pnl is usually settleable
         // in the market before agreeding to take it over. Also, the liqor should check how much
         // settle limit it's going to get along with the unsettled pnl.
-        let (max_base_transfer_abs, max_pnl_transfer) = {
+        let (max_base_transfer_abs, max_pnl_transfer, settle_token_price) = {
             let mut liqor = self
                 .account_fetcher
                 .fetch_fresh_mango_account(&self.client.mango_account_address)
                 .await
                 .context("getting liquidator account")?;
-            liqor.ensure_perp_position(*perp_market_index, QUOTE_TOKEN_INDEX)?;
+            let settle_token_index = perp.market.settle_token_index;
+            liqor.ensure_perp_position(perp_market_index, settle_token_index)?;
             let mut health_cache =
                 health_cache::new(&self.client.context, self.account_fetcher, &liqor)
                     .await
                     .expect("always ok");
-            let quote_bank = self
+            let settle_bank = self
                 .client
-                .first_bank(QUOTE_TOKEN_INDEX)
+                .first_bank(settle_token_index)
                 .await
-                .context("getting quote bank")?;
-            let max_usdc_borrow = health_cache.max_borrow_for_health_ratio(
+                .context("getting settle bank")?;
+            let max_settle_borrow = health_cache.max_borrow_for_health_ratio(
                 &liqor,
-                &quote_bank,
+                &settle_bank,
                 self.liqor_min_health_ratio,
             )?;
             // Ideally we'd predict how much positive pnl we're going to take over and then allocate
             // the base and quote amount accordingly. This just goes with allocating a fraction of the
             // available amount to quote and the rest to base.
-            let allowed_usdc_borrow = I80F48::from_num(0.25) * max_usdc_borrow;
+            let allowed_settle_borrow = I80F48::from_num(0.25) * max_settle_borrow;
             // Perp overall asset weights > 0 mean that we get some health back for every unit of unsettled pnl
             // and hence we can take over more than the pure-borrow amount.
             let max_perp_unsettled_leverage = I80F48::from_num(0.95);
@@ -187,20 +304,26 @@ impl<'a> LiquidateHelper<'a> {
                     .market
                     .init_overall_asset_weight
                     .min(max_perp_unsettled_leverage);
-            let max_pnl_transfer = allowed_usdc_borrow / perp_unsettled_cost;
+            let max_pnl_transfer = allowed_settle_borrow / perp_unsettled_cost;
 
             // Update the health cache so we can determine how many base lots the liqor can take on,
             // assuming that the max_quote_transfer amount of positive unsettled pnl was taken over.
-            health_cache.adjust_token_balance(&quote_bank, -allowed_usdc_borrow)?;
+            health_cache.adjust_token_balance(&settle_bank, -allowed_settle_borrow)?;
 
+            // The health cache works with perp prices in settle token units
+            let settle_token_price = health_cache.token_info(settle_token_index)?.prices.oracle;
             let max_base_transfer = health_cache.max_perp_for_health_ratio(
-                *perp_market_index,
-                *price,
+                perp_market_index,
+                price / settle_token_price,
                 side,
                 self.liqor_min_health_ratio,
             )?;
 
-            (max_base_transfer, max_pnl_transfer.floor().to_num::<u64>())
+            (
+                max_base_transfer,
+                max_pnl_transfer.floor().to_num::<u64>(),
+                settle_token_price,
+            )
         };
         trace!(
             max_base_transfer_abs,
@@ -208,21 +331,52 @@ impl<'a> LiquidateHelper<'a> {
             "computed transfer maximums"
         );
 
+        let base_value = I80F48::from(base_lots.abs().min(max_base_transfer_abs))
+            * I80F48::from(perp.market.base_lot_size)
+            * price;
+        let pnl_value = if base_lots == 0 {
+            let liqee_pnl = self
+                .liqee
+                .perp_position(perp_market_index)?
+                .quote_position_native();
+            liqee_pnl.min(I80F48::from_num(max_pnl_transfer) * settle_token_price)
+        } else {
+            I80F48::ZERO
+        };
+        let estimate = profitability::perp_liq_base_or_positive_pnl(
+            base_value.to_num(),
+            pnl_value.to_num(),
+            &perp.market,
+            tx_cost,
+        );
+        let action = LiquidationAction::PerpBaseOrPositivePnl {
+            perp_market_index,
+            base_lots,
+            price,
+        };
+        if !self.is_profitable(&action, &estimate) {
+            return Ok(None);
+        }
+
         let liq_ix = self
             .client
             .perp_liq_base_or_positive_pnl_instruction(
                 (self.pubkey, &self.liqee),
-                *perp_market_index,
+                perp_market_index,
                 side_signum * max_base_transfer_abs,
                 max_pnl_transfer,
             )
             .await
             .context("creating perp_liq_base_or_positive_pnl_instruction")?;
-        let txsig = self
+        let started = Instant::now();
+        let result = self
             .client
             .send_and_confirm_owner_tx(vec![self.liq_compute_limit_instruction(), liq_ix])
-            .await
-            .context("sending perp_liq_base_or_positive_pnl_instruction")?;
+            .await;
+        self.config
+            .tx_metrics
+            .record("perp_liq_base_or_positive_pnl", started, &result);
+        let txsig = result.context("sending perp_liq_base_or_positive_pnl_instruction")?;
         info!(
             perp_market_index,
             %txsig,
@@ -263,11 +417,15 @@ impl<'a> LiquidateHelper<'a> {
             )
             .await
             .context("creating perp_liq_negative_pnl_or_bankruptcy_instruction")?;
-        let txsig = self
+        let started = Instant::now();
+        let result = self
             .client
             .send_and_confirm_owner_tx(vec![self.liq_compute_limit_instruction(), liq_ix])
-            .await
-            .context("sending perp_liq_negative_pnl_or_bankruptcy_instruction")?;
+            .await;
+        self.config
+            .tx_metrics
+            .record("perp_liq_negative_pnl_or_bankruptcy", started, &result);
+        let txsig = result.context("sending perp_liq_negative_pnl_or_bankruptcy_instruction")?;
         info!(
             perp_market_index,
             %txsig,
@@ -324,52 +482,36 @@ impl<'a> LiquidateHelper<'a> {
         )
     }
 
-    async fn token_liq(&self) -> anyhow::Result<Option<Signature>> {
-        if !self.health_cache.has_possible_spot_liquidations() {
-            return Ok(None);
-        }
-
-        let tokens = self.tokens().await?;
-
-        let asset_token_index = tokens
-            .iter()
-            .rev()
-            .find(|(asset_token_index, _asset_price, asset_usdc_equivalent)| {
-                asset_usdc_equivalent.is_positive()
-                    && self
-                        .allowed_asset_tokens
-                        .contains(&self.client.context.token(*asset_token_index).mint_info.mint)
-            })
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "mango account {}, has no asset tokens that are sellable for USDC: {:?}",
-                    self.pubkey,
-                    tokens
-                )
-            })?
-            .0;
-        let liab_token_index = tokens
-            .iter()
-            .find(|(liab_token_index, _liab_price, liab_usdc_equivalent)| {
-                liab_usdc_equivalent.is_negative()
-                    && self
-                        .allowed_liab_tokens
-                        .contains(&self.client.context.token(*liab_token_index).mint_info.mint)
-            })
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "mango account {}, has no liab tokens that are purchasable for USDC: {:?}",
-                    self.pubkey,
-                    tokens
-                )
-            })?
-            .0;
-
+    async fn token_liq(
+        &self,
+        asset_token_index: TokenIndex,
+        liab_token_index: TokenIndex,
+        max_value: f64,
+        tx_cost: f64,
+    ) -> anyhow::Result<Option<Signature>> {
         let max_liab_transfer = self
             .max_token_liab_transfer(liab_token_index, asset_token_index)
             .await
             .context("getting max_liab_transfer")?;
 
+        let liab_price = self.client.bank_oracle_price(liab_token_index).await?;
+        let liab_bank = self.client.first_bank(liab_token_index).await?;
+        let value = (max_liab_transfer * liab_price).to_num::<f64>();
+        let estimate = profitability::token_liq_with_token(
+            value.min(max_value),
+            &liab_bank,
+            self.token_swap_info.swap_info(asset_token_index),
+            self.token_swap_info.swap_info(liab_token_index),
+            tx_cost,
+        );
+        let action = LiquidationAction::TokenWithToken {
+            asset_token_index,
+            liab_token_index,
+        };
+        if !self.is_profitable(&action, &estimate) {
+            return Ok(None);
+        }
+
         //
         // TODO: log liqor's assets in UI form
         // TODO: log liquee's liab_needed, need to refactor program code to be able to be accessed from client side
@@ -384,11 +526,15 @@ impl<'a> LiquidateHelper<'a> {
             )
             .await
             .context("creating liq_token_with_token ix")?;
-        let txsig = self
+        let started = Instant::now();
+        let result = self
             .client
             .send_and_confirm_owner_tx(vec![self.liq_compute_limit_instruction(), liq_ix])
-            .await
-            .context("sending liq_token_with_token")?;
+            .await;
+        self.config
+            .tx_metrics
+            .record("liq_token_with_token", started, &result);
+        let txsig = result.context("sending liq_token_with_token")?;
         info!(
             asset_token_index,
             liab_token_index,
@@ -442,11 +588,15 @@ impl<'a> LiquidateHelper<'a> {
             )
             .await
             .context("creating liq_token_bankruptcy")?;
-        let txsig = self
+        let started = Instant::now();
+        let result = self
             .client
             .send_and_confirm_owner_tx(vec![self.liq_compute_limit_instruction(), liq_ix])
-            .await
-            .context("sending liq_token_with_token")?;
+            .await;
+        self.config
+            .tx_metrics
+            .record("liq_token_bankruptcy", started, &result);
+        let txsig = result.context("sending liq_token_with_token")?;
         info!(
             liab_token_index,
             %txsig,
@@ -455,6 +605,40 @@ impl<'a> LiquidateHelper<'a> {
         Ok(Some(txsig))
     }
 
+    async fn liquidate(
+        &self,
+        action: &LiquidationAction,
+        upper_bound: &Estimate,
+    ) -> anyhow::Result<Option<Signature>> {
+        match *action {
+            LiquidationAction::PerpBaseOrPositivePnl {
+                perp_market_index,
+                base_lots,
+                price,
+            } => {
+                self.perp_liq_base_or_positive_pnl(
+                    perp_market_index,
+                    base_lots,
+                    price,
+                    upper_bound.tx_cost,
+                )
+                .await
+            }
+            LiquidationAction::TokenWithToken {
+                asset_token_index,
+                liab_token_index,
+            } => {
+                self.token_liq(
+                    asset_token_index,
+                    liab_token_index,
+                    upper_bound.value,
+                    upper_bound.tx_cost,
+                )
+                .await
+            }
+        }
+    }
+
     #[instrument(skip(self), fields(pubkey = %*self.pubkey, maint = %self.maint_health))]
     async fn send_liq_tx(&self) -> anyhow::Result<Option<Signature>> {
         // TODO: Should we make an attempt to settle positive PNL first?
@@ -489,12 +673,19 @@ impl<'a> LiquidateHelper<'a> {
         // Phase 2: token, perp base, perp positive pnl
         //
 
-        if let Some(txsig) = self.perp_liq_base_or_positive_pnl().await? {
-            return Ok(Some(txsig));
+        let tx_cost = self.tx_cost().await?;
+        let mut candidates = self.liquidation_candidates(tx_cost).await?;
+        // Try the most profitable liquidation first, the actual profit is lower when the
+        // liqor can't take over the full position
+        candidates.sort_by(|a, b| b.1.profit().total_cmp(&a.1.profit()));
+        for (action, upper_bound) in candidates.iter() {
+            if let Some(txsig) = self.liquidate(action, upper_bound).await? {
+                return Ok(Some(txsig));
+            }
         }
-
-        if let Some(txsig) = self.token_liq().await? {
-            return Ok(Some(txsig));
+        if !candidates.is_empty() {
+            info!("no liquidation is profitable enough, skipping");
+            return Ok(None);
         }
 
         if self.health_cache.has_perp_open_fills() {
@@ -539,6 +730,7 @@ pub async fn maybe_liquidate_account(
     mango_client: &MangoClient,
     account_fetcher: &chain_data::AccountFetcher,
     pubkey: &Pubkey,
+    token_swap_info: &TokenSwapInfoUpdater,
     config: &Config,
 ) -> anyhow::Result<bool> {
     let liqor_min_health_ratio = I80F48::from_num(config.min_health_ratio);
@@ -590,23 +782,29 @@ pub async fn maybe_liquidate_account(
         liqor_min_health_ratio,
         allowed_asset_tokens: all_token_mints.clone(),
         allowed_liab_tokens: all_token_mints,
+        token_swap_info,
         config: config.clone(),
     }
     .send_liq_tx()
     .await?;
 
-    if let Some(txsig) = maybe_txsig {
-        let slot = account_fetcher.transaction_max_slot(&[txsig]).await?;
-        if let Err(e) = account_fetcher
-            .refresh_accounts_via_rpc_until_slot(
-                &[*pubkey, mango_client.mango_account_address],
-                slot,
-                config.refresh_timeout,
-            )
-            .await
-        {
-            info!("could not refresh after liquidation: {}", e);
-        }
+    // Nothing was sent, like when waiting for perp fills or when no liquidation
+    // is profitable. Other liquidatable accounts should get a chance.
+    let txsig = match maybe_txsig {
+        Some(txsig) => txsig,
+        None => return Ok(false),
+    };
+
+    let slot = account_fetcher.transaction_max_slot(&[txsig]).await?;
+    if let Err(e) = account_fetcher
+        .refresh_accounts_via_rpc_until_slot(
+            &[*pubkey, mango_client.mango_account_address],
+            slot,
+            config.refresh_timeout,
+        )
+        .await
+    {
+        info!("could not refresh after liquidation: {}", e);
     }
 
     Ok(true)
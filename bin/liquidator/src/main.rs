@@ -1,24 +1,26 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 use anchor_client::Cluster;
 use clap::Parser;
-use mango_v4::state::{PerpMarketIndex, TokenIndex};
+use mango_v4::state::{PerpMarketIndex, TokenIndex, QUOTE_TOKEN_INDEX};
 use mango_v4_client::{
-    account_update_stream, chain_data, jupiter, keypair_from_cli, snapshot_source,
+    account_update_stream, chain_data, geyser_source, jupiter, keypair_from_cli, snapshot_source,
     websocket_source, Client, MangoClient, MangoClientError, MangoGroupContext,
     TransactionBuilderConfig,
 };
 
 use itertools::Itertools;
+use mango_v4_metrics::TransactionMetrics;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use tracing::*;
 
 pub mod liquidate;
-pub mod metrics;
+pub mod profitability;
 pub mod rebalance;
 pub mod telemetry;
 pub mod token_swap_info;
@@ -108,10 +110,27 @@ struct Cli {
     #[clap(long, env, default_value = "0")]
     prioritization_micro_lamports: u64,
 
+    /// stream account updates from this geyser grpc endpoint instead of the rpc websocket
+    #[clap(long, env)]
+    geyser_grpc_url: Option<String>,
+
+    /// x-token for authenticating with the geyser grpc endpoint
+    #[clap(long, env)]
+    geyser_grpc_x_token: Option<String>,
+
+    /// file for persisting account snapshots, used to warm start after a restart
+    #[clap(long, env)]
+    snapshot_file: Option<PathBuf>,
+
     /// compute limit requested for liquidation instructions
     #[clap(long, env, default_value = "250000")]
     compute_limit_for_liquidation: u32,
 
+    /// liquidations whose expected profit after rebalancing slippage and transaction
+    /// fees is below this many USD are skipped
+    #[clap(long, env, default_value = "0")]
+    min_liquidation_profit_usd: f64,
+
     /// compute limit requested for tcs trigger instructions
     #[clap(long, env, default_value = "300000")]
     compute_limit_for_tcs: u32,
@@ -123,6 +142,10 @@ struct Cli {
     /// report liquidator's existence and pubkey
     #[clap(long, env, value_enum, default_value = "true")]
     telemetry: BoolArg,
+
+    /// port for serving prometheus metrics on /metrics
+    #[clap(long, env, default_value = "9092")]
+    metrics_port: u16,
 }
 
 pub fn encode_address(addr: &Pubkey) -> String {
@@ -176,11 +199,19 @@ async fn main() -> anyhow::Result<()> {
 
     let group_context = MangoGroupContext::new_from_rpc(&client.rpc_async(), mango_group).await?;
 
-    let mango_oracles = group_context
+    // Choosing between the banks of a token compares their vault balances
+    let mango_oracles_and_vaults = group_context
         .tokens
         .values()
         .map(|value| value.mint_info.oracle)
         .chain(group_context.perp_markets.values().map(|p| p.market.oracle))
+        .chain(
+            group_context
+                .tokens
+                .values()
+                .filter(|value| value.mint_info.num_banks() > 1)
+                .flat_map(|value| value.mint_info.vaults().to_vec()),
+        )
         .unique()
         .collect::<Vec<Pubkey>>();
 
@@ -190,8 +221,6 @@ async fn main() -> anyhow::Result<()> {
         .map(|s3| s3.market.serum_program)
         .unique()
         .collect_vec();
-    // TODO: Currently the websocket source only supports a single serum program address!
-    assert_eq!(serum_programs.len(), 1);
 
     //
     // feed setup
@@ -201,22 +230,41 @@ async fn main() -> anyhow::Result<()> {
 
     info!("startup");
 
-    let metrics = metrics::start();
+    let metrics = mango_v4_metrics::start("liquidator", cli.metrics_port);
+    let tx_metrics = TransactionMetrics::new(&metrics);
 
     let (account_update_sender, account_update_receiver) =
         async_channel::unbounded::<account_update_stream::Message>();
 
-    // Sourcing account and slot data from solana via websockets
-    // FUTURE: websocket feed should take which accounts to listen to as an input
-    websocket_source::start(
-        websocket_source::Config {
-            rpc_ws_url: ws_url.clone(),
-            serum_program: *serum_programs.first().unwrap(),
-            open_orders_authority: mango_group,
-        },
-        mango_oracles.clone(),
-        account_update_sender.clone(),
-    );
+    if let Some(grpc_url) = cli.geyser_grpc_url.clone() {
+        // Sourcing account and slot data from a geyser plugin
+        geyser_source::start(
+            geyser_source::Config {
+                grpc_url,
+                grpc_x_token: cli.geyser_grpc_x_token.clone(),
+                serum_programs,
+                open_orders_authority: mango_group,
+            },
+            mango_oracles_and_vaults.clone(),
+            account_update_sender.clone(),
+        );
+    } else {
+        // TODO: Currently the websocket source only supports a single serum program address!
+        assert!(serum_programs.len() <= 1);
+
+        // Sourcing account and slot data from solana via websockets
+        // FUTURE: websocket feed should take which accounts to listen to as an input
+        websocket_source::start(
+            websocket_source::Config {
+                rpc_ws_url: ws_url.clone(),
+                serum_program: serum_programs.first().copied(),
+                open_orders_authority: mango_group,
+                mango_program_filters: vec![],
+            },
+            mango_oracles_and_vaults.clone(),
+            account_update_sender.clone(),
+        );
+    }
 
     let first_websocket_slot = websocket_source::get_next_create_bank_slot(
         account_update_receiver.clone(),
@@ -234,12 +282,13 @@ async fn main() -> anyhow::Result<()> {
             parallel_rpc_requests: cli.parallel_rpc_requests,
             snapshot_interval: Duration::from_secs(cli.snapshot_interval_secs),
             min_slot: first_websocket_slot + 10,
+            snapshot_file: cli.snapshot_file.clone(),
         },
-        mango_oracles,
+        mango_oracles_and_vaults,
         account_update_sender,
     );
 
-    start_chain_data_metrics(chain_data.clone(), &metrics);
+    chain_data::start_metrics(chain_data.clone(), &metrics);
 
     let shared_state = Arc::new(RwLock::new(SharedState::default()));
 
@@ -267,9 +316,13 @@ async fn main() -> anyhow::Result<()> {
         token_swap_info_config,
     ));
 
+    let quote_decimals = mango_client.context.token(QUOTE_TOKEN_INDEX).decimals;
     let liq_config = liquidate::Config {
         min_health_ratio: cli.min_health_ratio,
         compute_limit_for_liq_ix: cli.compute_limit_for_liquidation,
+        prioritization_micro_lamports: cli.prioritization_micro_lamports,
+        min_profit: cli.min_liquidation_profit_usd * 10f64.powi(quote_decimals.into()),
+        tx_metrics: tx_metrics.clone(),
         // TODO: config
         refresh_timeout: Duration::from_secs(30),
     };
@@ -279,6 +332,7 @@ async fn main() -> anyhow::Result<()> {
         max_trigger_quote_amount: 1_000_000_000, // TODO: config, $1000
         jupiter_version: cli.jupiter_version.into(),
         compute_limit_for_trigger: cli.compute_limit_for_tcs,
+        tx_metrics: tx_metrics.clone(),
         // TODO: config
         refresh_timeout: Duration::from_secs(30),
     };
@@ -291,6 +345,7 @@ async fn main() -> anyhow::Result<()> {
         borrow_settle_excess: 1.05,
         refresh_timeout: Duration::from_secs(30),
         jupiter_version: cli.jupiter_version.into(),
+        tx_metrics,
     };
 
     let rebalancer = Arc::new(rebalance::Rebalancer {
@@ -340,9 +395,12 @@ async fn main() -> anyhow::Result<()> {
 
         let shared_state = shared_state.clone();
 
-        let mut metric_account_update_queue_len =
-            metrics.register_u64("account_update_queue_length".into());
-        let mut metric_mango_accounts = metrics.register_u64("mango_accounts".into());
+        let metric_account_update_queue_len = metrics.register_u64(
+            "account_update_queue_length",
+            "Account updates waiting to be applied to the chain data",
+        );
+        let metric_mango_accounts =
+            metrics.register_u64("mango_accounts", "Mango accounts of the group");
 
         let mut mint_infos = HashMap::<TokenIndex, Pubkey>::new();
         let mut oracles = HashSet::<Pubkey>::new();
@@ -662,6 +720,7 @@ impl LiquidationState {
             &self.mango_client,
             &self.account_fetcher,
             pubkey,
+            &self.token_swap_info,
             &self.liquidation_config,
         )
         .await;
@@ -711,6 +770,7 @@ impl LiquidationState {
 
         // Find interesting (pubkey, tcsid, volume)
         let mut interesting_tcs = Vec::with_capacity(accounts.len());
+        let mut interesting_perp_orders = vec![];
         for pubkey in accounts.iter() {
             if let Some(error_entry) = self
                 .tcs_collection_hard_errors
@@ -756,8 +816,32 @@ impl LiquidationState {
                         .record_error(pubkey, now, e.to_string());
                 }
             }
+
+            match trigger_tcs::find_interesting_perp_conditional_orders_for_account(
+                pubkey,
+                &self.mango_client,
+                &self.account_fetcher,
+                now_ts,
+            ) {
+                Ok(v) => {
+                    for it in v.iter() {
+                        if let Err(e) = it {
+                            self.tcs_collection_partial_errors.record_error(
+                                pubkey,
+                                now,
+                                e.to_string(),
+                            );
+                        }
+                    }
+                    interesting_perp_orders.extend(v.iter().filter_map(|it| it.as_ref().ok()));
+                }
+                Err(e) => {
+                    self.tcs_collection_hard_errors
+                        .record_error(pubkey, now, e.to_string());
+                }
+            }
         }
-        if interesting_tcs.is_empty() {
+        if interesting_tcs.is_empty() && interesting_perp_orders.is_empty() {
             return Ok(());
         }
 
@@ -767,9 +851,17 @@ impl LiquidationState {
             token_swap_info: self.token_swap_info.clone(),
             config: self.trigger_tcs_config.clone(),
         };
-        let (txsigs, mut changed_pubkeys) = tcs_context
+        let (mut txsigs, mut changed_pubkeys) = tcs_context
             .execute_tcs(&mut interesting_tcs, &mut self.tcs_execution_errors)
             .await?;
+        let (perp_txsigs, perp_changed_pubkeys) = tcs_context
+            .execute_perp_conditional_orders(
+                &interesting_perp_orders,
+                &mut self.tcs_execution_errors,
+            )
+            .await?;
+        txsigs.extend(perp_txsigs);
+        changed_pubkeys.extend(perp_changed_pubkeys);
         changed_pubkeys.push(self.mango_client.mango_account_address);
 
         // Force a refresh of affected accounts
@@ -812,22 +904,3 @@ impl LiquidationState {
             .log_persistent_errors("tcs collection partial", min_duration);
     }
 }
-
-fn start_chain_data_metrics(chain: Arc<RwLock<chain_data::ChainData>>, metrics: &metrics::Metrics) {
-    let mut interval = tokio::time::interval(Duration::from_secs(600));
-
-    let mut metric_slots_count = metrics.register_u64("chain_data_slots_count".into());
-    let mut metric_accounts_count = metrics.register_u64("chain_data_accounts_count".into());
-    let mut metric_account_write_count =
-        metrics.register_u64("chain_data_account_write_count".into());
-
-    tokio::spawn(async move {
-        loop {
-            interval.tick().await;
-            let chain_lock = chain.read().unwrap();
-            metric_slots_count.set(chain_lock.slots_count() as u64);
-            metric_accounts_count.set(chain_lock.accounts_count() as u64);
-            metric_account_write_count.set(chain_lock.account_writes_count() as u64);
-        }
-    });
-}
@@ -0,0 +1,172 @@
+use mango_v4::state::{Bank, PerpMarket};
+
+use crate::token_swap_info::TokenSwapInfo;
+
+/// Fee for the single signature of a liquidation transaction
+const SIGNATURE_FEE_LAMPORTS: u64 = 5000;
+
+/// Expected outcome of a liquidation for the liqor
+///
+/// All values are in native quote units, like the health cache.
+#[derive(Clone, Debug, Default)]
+pub struct Estimate {
+    /// Value of the liqee position that is taken over
+    pub value: f64,
+    /// Liquidation fee the liqor receives
+    pub fee: f64,
+    /// Expected cost of getting rid of the taken over position again
+    pub rebalance_cost: f64,
+    /// Signature and priority fees
+    pub tx_cost: f64,
+}
+
+impl Estimate {
+    pub fn profit(&self) -> f64 {
+        self.fee - self.rebalance_cost - self.tx_cost
+    }
+}
+
+/// Lamports paid for a transaction with the given compute limit
+pub fn tx_cost_lamports(compute_limit: u32, prioritization_micro_lamports: u64) -> u64 {
+    SIGNATURE_FEE_LAMPORTS + compute_limit as u64 * prioritization_micro_lamports / 1_000_000
+}
+
+/// Buy and sell prices relative to the oracle, assuming oracle prices when the
+/// token swap info isn't known yet
+fn buy_sell_over_oracle(swap_info: Option<TokenSwapInfo>) -> (f64, f64) {
+    swap_info
+        .map(|info| (info.buy_over_oracle, info.sell_over_oracle))
+        .unwrap_or((1.0, 1.0))
+}
+
+/// Liquidating `value` worth of liabs gives the liqor assets worth `value * (1 + liab fee)`.
+///
+/// The rebalancer then sells these assets and buys the liab tokens to repay the borrow.
+pub fn token_liq_with_token(
+    value: f64,
+    liab_bank: &Bank,
+    asset_swap_info: Option<TokenSwapInfo>,
+    liab_swap_info: Option<TokenSwapInfo>,
+    tx_cost: f64,
+) -> Estimate {
+    let fee_factor = 1.0 + liab_bank.liquidation_fee.to_num::<f64>();
+    let (_, asset_sell_over_oracle) = buy_sell_over_oracle(asset_swap_info);
+    let (liab_buy_over_oracle, _) = buy_sell_over_oracle(liab_swap_info);
+
+    let asset_value = value * fee_factor;
+    let sell_cost = asset_value - asset_value / asset_sell_over_oracle;
+    let buy_cost = value * liab_buy_over_oracle - value;
+    Estimate {
+        value,
+        fee: asset_value - value,
+        rebalance_cost: sell_cost + buy_cost,
+        tx_cost,
+    }
+}
+
+/// The liqor takes over `base_value` of base position at a discount of the base
+/// liquidation fee and `pnl_value` of positive pnl at a discount of the positive
+/// pnl liquidation fee.
+///
+/// The rebalancer closes base positions with ioc orders, paying the taker fee.
+pub fn perp_liq_base_or_positive_pnl(
+    base_value: f64,
+    pnl_value: f64,
+    perp_market: &PerpMarket,
+    tx_cost: f64,
+) -> Estimate {
+    let base_fee = base_value * perp_market.base_liquidation_fee.to_num::<f64>();
+    let pnl_fee = pnl_value * perp_market.positive_pnl_liquidation_fee.to_num::<f64>();
+    Estimate {
+        value: base_value + pnl_value,
+        fee: base_fee + pnl_fee,
+        rebalance_cost: base_value * perp_market.taker_fee.to_num::<f64>(),
+        tx_cost,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+    use fixed::types::I80F48;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_tx_cost_lamports() {
+        assert_eq!(tx_cost_lamports(200_000, 0), 5000);
+        assert_eq!(tx_cost_lamports(200_000, 10), 5000 + 2);
+        assert_eq!(tx_cost_lamports(1_400_000, 1_000_000), 5000 + 1_400_000);
+        // partial lamports are rounded down
+        assert_eq!(tx_cost_lamports(100, 1), 5000);
+    }
+
+    #[test]
+    fn test_estimate_profit() {
+        let estimate = Estimate {
+            value: 1000.0,
+            fee: 50.0,
+            rebalance_cost: 20.0,
+            tx_cost: 5.0,
+        };
+        assert_close(estimate.profit(), 25.0);
+        assert_close(Estimate::default().profit(), 0.0);
+    }
+
+    #[test]
+    fn test_token_liq_with_token() {
+        let mut liab_bank = Bank::zeroed();
+        liab_bank.liquidation_fee = I80F48::from_num(0.05);
+
+        // without swap info, rebalancing happens at oracle prices
+        let estimate = token_liq_with_token(100.0, &liab_bank, None, None, 1.0);
+        assert_close(estimate.value, 100.0);
+        assert_close(estimate.fee, 5.0);
+        assert_close(estimate.rebalance_cost, 0.0);
+        assert_close(estimate.tx_cost, 1.0);
+        assert_close(estimate.profit(), 4.0);
+
+        // selling the 105 of assets loses 105 - 105 / 1.05 = 5,
+        // buying the 100 of liabs costs another 2
+        let estimate = token_liq_with_token(
+            100.0,
+            &liab_bank,
+            Some(TokenSwapInfo {
+                buy_over_oracle: 1.5,
+                sell_over_oracle: 1.05,
+            }),
+            Some(TokenSwapInfo {
+                buy_over_oracle: 1.02,
+                sell_over_oracle: 1.5,
+            }),
+            1.0,
+        );
+        assert_close(estimate.fee, 5.0);
+        assert_close(estimate.rebalance_cost, 7.0);
+        assert_close(estimate.profit(), -3.0);
+    }
+
+    #[test]
+    fn test_perp_liq_base_or_positive_pnl() {
+        let mut perp_market = PerpMarket::zeroed();
+        perp_market.base_liquidation_fee = I80F48::from_num(0.02);
+        perp_market.positive_pnl_liquidation_fee = I80F48::from_num(0.01);
+        perp_market.taker_fee = I80F48::from_num(0.0005);
+
+        let estimate = perp_liq_base_or_positive_pnl(1000.0, 200.0, &perp_market, 0.5);
+        assert_close(estimate.value, 1200.0);
+        assert_close(estimate.fee, 20.0 + 2.0);
+        // only the base position needs to be closed
+        assert_close(estimate.rebalance_cost, 0.5);
+        assert_close(estimate.tx_cost, 0.5);
+        assert_close(estimate.profit(), 21.0);
+
+        let estimate = perp_liq_base_or_positive_pnl(0.0, 200.0, &perp_market, 0.5);
+        assert_close(estimate.fee, 2.0);
+        assert_close(estimate.rebalance_cost, 0.0);
+        assert_close(estimate.profit(), 1.5);
+    }
+}
@@ -7,12 +7,13 @@ use mango_v4_client::{
     chain_data, jupiter, perp_pnl, MangoClient, PerpMarketContext, TokenContext,
     TransactionBuilder, TransactionSize,
 };
+use mango_v4_metrics::TransactionMetrics;
 
 use {fixed::types::I80F48, solana_sdk::pubkey::Pubkey};
 
 use solana_sdk::signature::Signature;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::*;
 
 #[derive(Clone)]
@@ -26,6 +27,7 @@ pub struct Config {
     pub borrow_settle_excess: f64,
     pub refresh_timeout: Duration,
     pub jupiter_version: jupiter::Version,
+    pub tx_metrics: TransactionMetrics,
 }
 
 fn token_bank(
@@ -172,9 +174,12 @@ impl Rebalancer {
                 &alternatives,
             )
             .await?;
-        let sig = tx_builder
-            .send_and_confirm(&self.mango_client.client)
-            .await?;
+        let started = Instant::now();
+        let result = tx_builder.send_and_confirm(&self.mango_client.client).await;
+        self.config
+            .tx_metrics
+            .record("rebalance_swap", started, &result);
+        let sig = result?;
         Ok((sig, route))
     }
 
@@ -233,9 +238,12 @@ impl Rebalancer {
             )
             .await?;
 
-        let sig = tx_builder
-            .send_and_confirm(&self.mango_client.client)
-            .await?;
+        let started = Instant::now();
+        let result = tx_builder.send_and_confirm(&self.mango_client.client).await;
+        self.config
+            .tx_metrics
+            .record("rebalance_swap", started, &result);
+        let sig = result?;
         Ok((sig, route))
     }
 
@@ -71,7 +71,14 @@ impl TokenSwapInfoUpdater {
 
         let quote_index = self.config.quote_index;
         if token_index == quote_index {
-            self.update(quote_index, TokenSwapInfo::default());
+            // the quote token trades at the oracle price by definition
+            self.update(
+                quote_index,
+                TokenSwapInfo {
+                    buy_over_oracle: 1.0,
+                    sell_over_oracle: 1.0,
+                },
+            );
             return Ok(());
         }
 
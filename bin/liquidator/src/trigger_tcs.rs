@@ -7,10 +7,15 @@ use std::{
 use futures_core::Future;
 use itertools::Itertools;
 use mango_v4::{
+    accounts_zerocopy::KeyedAccountSharedData,
     i80f48::ClampToInt,
-    state::{Bank, MangoAccountValue, TokenConditionalSwap, TokenIndex},
+    state::{
+        Bank, MangoAccountValue, PerpConditionalOrder, PerpConditionalOrderExecution, PerpMarket,
+        Side, TokenConditionalSwap, TokenIndex,
+    },
 };
 use mango_v4_client::{chain_data, health_cache, jupiter, MangoClient, MangoGroupContext};
+use mango_v4_metrics::TransactionMetrics;
 
 use solana_sdk::signature::Signature;
 use tracing::*;
@@ -36,6 +41,7 @@ pub struct Config {
     pub refresh_timeout: Duration,
     pub jupiter_version: jupiter::Version,
     pub compute_limit_for_trigger: u32,
+    pub tx_metrics: TransactionMetrics,
 }
 
 fn tcs_is_in_price_range(
@@ -51,12 +57,20 @@ fn tcs_is_in_price_range(
     Ok(tcs.price_in_range(base_price))
 }
 
+/// Linear premium auctions start with the first trigger while the price is in range,
+/// a trigger that doesn't swap anything is enough
+fn tcs_needs_auction_start(tcs: &TokenConditionalSwap) -> bool {
+    tcs.is_premium_auction() && !tcs.premium_auction_started()
+}
+
 fn tcs_has_plausible_premium(
     tcs: &TokenConditionalSwap,
     token_swap_info: &token_swap_info::TokenSwapInfoUpdater,
+    now_ts: u64,
 ) -> anyhow::Result<bool> {
-    // The premium the taker receives needs to take taker fees into account
-    let premium = tcs.taker_price(tcs.premium_price(1.0)) as f64;
+    // The premium the taker receives needs to take taker fees into account.
+    // For linear auctions this is the current premium, which rises over time.
+    let premium = tcs.taker_price(tcs.premium_price(1.0, now_ts)) as f64;
 
     // Never take tcs where the fee exceeds the premium and the triggerer exchanges
     // tokens at below oracle price.
@@ -87,7 +101,8 @@ fn tcs_is_interesting(
 ) -> anyhow::Result<bool> {
     Ok(tcs.is_expired(now_ts)
         || (tcs_is_in_price_range(context, account_fetcher, tcs)?
-            && tcs_has_plausible_premium(tcs, token_swap_info)?))
+            && (tcs_needs_auction_start(tcs)
+                || tcs_has_plausible_premium(tcs, token_swap_info, now_ts)?)))
 }
 
 /// Returns the maximum execution size of a tcs order in quote units
@@ -96,7 +111,13 @@ fn tcs_max_volume(
     mango_client: &MangoClient,
     account_fetcher: &chain_data::AccountFetcher,
     tcs: &TokenConditionalSwap,
+    now_ts: u64,
 ) -> anyhow::Result<Option<u64>> {
+    // Starting an auction doesn't swap anything
+    if tcs_needs_auction_start(tcs) {
+        return Ok(Some(0));
+    }
+
     let buy_bank_pk = mango_client
         .context
         .mint_info(tcs.buy_token_index)
@@ -109,7 +130,7 @@ fn tcs_max_volume(
     let sell_token_price = account_fetcher.fetch_bank_price(&sell_bank_pk)?;
 
     let (max_buy, max_sell) =
-        match tcs_max_liqee_execution(account, mango_client, account_fetcher, tcs)? {
+        match tcs_max_liqee_execution(account, mango_client, account_fetcher, tcs, now_ts)? {
             Some(v) => v,
             None => return Ok(None),
         };
@@ -124,33 +145,41 @@ fn tcs_max_volume(
 /// This includes
 /// - tcs restrictions (remaining buy/sell, create borrows/deposits)
 /// - reduce only banks
+/// - the sell amount still available in the current interval, for twap tcs
 /// - net borrow limits on BOTH sides, even though the buy side is technically
 ///   a liqor limitation: the liqor could acquire the token before trying the
 ///   execution... but in practice the liqor will work on margin
 ///
 /// Returns Some((native buy amount, native sell amount)) if execution is sensible
-/// Returns None if the execution should be skipped (due to net borrow limits...)
+/// Returns None if the execution should be skipped (due to net borrow limits,
+/// waiting for the next twap interval...)
 fn tcs_max_liqee_execution(
     account: &MangoAccountValue,
     mango_client: &MangoClient,
     account_fetcher: &chain_data::AccountFetcher,
     tcs: &TokenConditionalSwap,
+    now_ts: u64,
 ) -> anyhow::Result<Option<(u64, u64)>> {
-    let buy_bank_pk = mango_client
-        .context
-        .mint_info(tcs.buy_token_index)
-        .first_bank();
-    let sell_bank_pk = mango_client
-        .context
-        .mint_info(tcs.sell_token_index)
-        .first_bank();
-    let buy_bank: Bank = account_fetcher.fetch(&buy_bank_pk)?;
-    let sell_bank: Bank = account_fetcher.fetch(&sell_bank_pk)?;
+    let available_sell_in_interval = tcs.available_sell_in_interval(now_ts);
+    if available_sell_in_interval == 0 {
+        trace!(
+            tcs_id = tcs.id,
+            next_interval_start = tcs.next_interval_start_timestamp(),
+            "waiting for next twap interval",
+        );
+        return Ok(None);
+    }
+
+    // The trigger instruction checks the net borrow limits of these banks
+    let (buy_bank_pk, buy_bank) =
+        util::borrow_bank(mango_client, account_fetcher, tcs.buy_token_index)?;
+    let (sell_bank_pk, sell_bank) =
+        util::borrow_bank(mango_client, account_fetcher, tcs.sell_token_index)?;
     let buy_token_price = account_fetcher.fetch_bank_price(&buy_bank_pk)?;
     let sell_token_price = account_fetcher.fetch_bank_price(&sell_bank_pk)?;
 
     let base_price = buy_token_price / sell_token_price;
-    let premium_price = tcs.premium_price(base_price.to_num());
+    let premium_price = tcs.premium_price(base_price.to_num(), now_ts);
     let maker_price = tcs.maker_price(premium_price);
 
     let buy_position = account
@@ -175,7 +204,8 @@ fn tcs_max_liqee_execution(
     )?
     .floor()
     .to_num::<u64>()
-    .min(tcs.max_sell_for_position(sell_position, &sell_bank));
+    .min(tcs.max_sell_for_position(sell_position, &sell_bank))
+    .min(available_sell_in_interval);
 
     let max_buy_ignoring_net_borrows = tcs.max_buy_for_position(buy_position, &buy_bank);
 
@@ -246,7 +276,7 @@ pub fn find_interesting_tcs_for_account(
         ) {
             Ok(true) => {
                 // Filter out Ok(None) resuts of tcs that shouldn't be executed right now
-                match tcs_max_volume(&liqee, mango_client, account_fetcher, tcs) {
+                match tcs_max_volume(&liqee, mango_client, account_fetcher, tcs, now_ts) {
                     Ok(Some(v)) => Some(Ok((*pubkey, tcs.id, v))),
                     Ok(None) => None,
                     Err(e) => Some(Err(e)),
@@ -259,6 +289,82 @@ pub fn find_interesting_tcs_for_account(
     Ok(interesting_tcs.collect_vec())
 }
 
+fn perp_oracle_price(
+    account_fetcher: &chain_data::AccountFetcher,
+    perp_market: &PerpMarket,
+) -> anyhow::Result<I80F48> {
+    let oracle_account_data = account_fetcher.fetch_raw(&perp_market.oracle)?;
+    let oracle_account = KeyedAccountSharedData::new(perp_market.oracle, oracle_account_data);
+    Ok(perp_market.oracle_price(&oracle_account, None)?)
+}
+
+/// The liqor closes the perp position it takes over via the rebalancer, which
+/// places ioc orders. Only take orders where the premium covers the taker fee.
+fn perp_conditional_order_has_plausible_premium(
+    order: &PerpConditionalOrder,
+    perp_market: &PerpMarket,
+) -> bool {
+    order.price_premium_rate > perp_market.taker_fee.to_num::<f64>()
+}
+
+/// Returns the maximum execution size of a perp conditional order in quote units,
+/// or None if it shouldn't be executed right now.
+fn perp_conditional_order_max_volume(
+    account: &MangoAccountValue,
+    account_fetcher: &chain_data::AccountFetcher,
+    perp_market: &PerpMarket,
+    order: &PerpConditionalOrder,
+    now_ts: u64,
+) -> anyhow::Result<Option<u64>> {
+    // Orders that place into the book are not traded against the liqor
+    if order.execution() != PerpConditionalOrderExecution::TradeWithTriggerer {
+        return Ok(None);
+    }
+    if order.is_expired(now_ts) {
+        return Ok(Some(0));
+    }
+
+    let oracle_price = perp_oracle_price(account_fetcher, perp_market)?;
+    if !order.price_in_range(oracle_price.to_num())
+        || !perp_conditional_order_has_plausible_premium(order, perp_market)
+    {
+        return Ok(None);
+    }
+
+    let base_lots = account
+        .perp_position(order.perp_market_index)
+        .map(|pp| pp.base_position_lots())
+        .unwrap_or(0);
+    let max_base_lots = order.max_base_lots_for_position(base_lots, perp_market.is_reduce_only());
+    if max_base_lots == 0 {
+        return Ok(None);
+    }
+
+    let volume =
+        I80F48::from(max_base_lots) * I80F48::from(perp_market.base_lot_size) * oracle_price;
+    Ok(Some(volume.floor().clamp_to_u64()))
+}
+
+pub fn find_interesting_perp_conditional_orders_for_account(
+    pubkey: &Pubkey,
+    mango_client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    now_ts: u64,
+) -> anyhow::Result<Vec<anyhow::Result<(Pubkey, u64, u64)>>> {
+    let liqee = account_fetcher.fetch_mango_account(pubkey)?;
+
+    let interesting_orders = liqee.active_perp_conditional_orders().filter_map(|order| {
+        let perp_market = &mango_client.context.perp(order.perp_market_index).market;
+        match perp_conditional_order_max_volume(&liqee, account_fetcher, perp_market, order, now_ts)
+        {
+            Ok(Some(v)) => Some(Ok((*pubkey, order.id, v))),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    });
+    Ok(interesting_orders.collect_vec())
+}
+
 #[derive(Clone)]
 struct PreparedExecution {
     pubkey: Pubkey,
@@ -295,6 +401,16 @@ async fn prepare_token_conditional_swap(
             max_buy_token_to_liqee: 0,
             max_sell_token_to_liqor: 0,
         }))
+    } else if tcs_needs_auction_start(tcs) {
+        // Triggering like this starts the auction, the instruction checks that the price is in range
+        Ok(Some(PreparedExecution {
+            pubkey: *pubkey,
+            tcs_id,
+            volume: 0,
+            token_indexes: vec![],
+            max_buy_token_to_liqee: 0,
+            max_sell_token_to_liqor: 0,
+        }))
     } else {
         prepare_token_conditional_swap_inner(
             mango_client,
@@ -357,6 +473,7 @@ async fn prepare_token_conditional_swap_inner(
         config,
         &liqee,
         tcs,
+        now_ts,
     )
     .await
 }
@@ -370,6 +487,7 @@ async fn prepare_token_conditional_swap_inner2(
     config: &Config,
     liqee: &MangoAccountValue,
     tcs: &TokenConditionalSwap,
+    now_ts: u64,
 ) -> anyhow::Result<Option<PreparedExecution>> {
     let liqor_min_health_ratio = I80F48::from_num(config.min_health_ratio);
 
@@ -386,13 +504,13 @@ async fn prepare_token_conditional_swap_inner2(
     let sell_token_price = account_fetcher.fetch_bank_price(&sell_bank)?;
 
     let base_price = buy_token_price / sell_token_price;
-    let premium_price = tcs.premium_price(base_price.to_num());
+    let premium_price = tcs.premium_price(base_price.to_num(), now_ts);
     let taker_price = I80F48::from_num(tcs.taker_price(premium_price));
 
     let max_take_quote = I80F48::from(config.max_trigger_quote_amount);
 
     let (liqee_max_buy, liqee_max_sell) =
-        match tcs_max_liqee_execution(liqee, mango_client, account_fetcher, tcs)? {
+        match tcs_max_liqee_execution(liqee, mango_client, account_fetcher, tcs, now_ts)? {
             Some(v) => v,
             None => return Ok(None),
         };
@@ -684,6 +802,164 @@ impl ExecutionContext {
         Some(Box::pin(job))
     }
 
+    /// Triggers perp conditional orders one by one
+    ///
+    /// Unlike token conditional swaps, these don't need a jupiter route: the liqor takes
+    /// over the opposite perp position, which the rebalancer closes later. Execution stops
+    /// once the max_trigger_quote_amount is exhausted.
+    ///
+    /// Returns a list of transaction signatures as well as the pubkeys of liqees.
+    pub async fn execute_perp_conditional_orders(
+        &self,
+        orders: &[(Pubkey, u64, u64)],
+        error_tracking: &mut ErrorTracking,
+    ) -> anyhow::Result<(Vec<Signature>, Vec<Pubkey>)> {
+        let mut available_volume = self.config.max_trigger_quote_amount;
+        let mut txsigs = vec![];
+        let mut changed_pubkeys = vec![];
+
+        for (pubkey, order_id, volume) in orders.iter() {
+            if *volume > available_volume {
+                continue;
+            }
+            if let Some(error_entry) = error_tracking.had_too_many_errors(pubkey, Instant::now()) {
+                trace!(
+                    "skip triggering perp conditional orders on account {pubkey}, had {} errors recently",
+                    error_entry.count
+                );
+                continue;
+            }
+
+            match self
+                .trigger_perp_conditional_order(pubkey, *order_id, available_volume)
+                .await
+            {
+                Ok(Some((txsig, used_volume))) => {
+                    available_volume = available_volume.saturating_sub(used_volume);
+                    txsigs.push(txsig);
+                    changed_pubkeys.push(*pubkey);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error_tracking.record_error(pubkey, Instant::now(), e.to_string());
+                }
+            }
+        }
+
+        Ok((txsigs, changed_pubkeys))
+    }
+
+    /// Returns the signature and the executed quote volume, if a trigger was sent
+    #[instrument(skip_all, fields(%pubkey, order_id))]
+    async fn trigger_perp_conditional_order(
+        &self,
+        pubkey: &Pubkey,
+        order_id: u64,
+        max_volume: u64,
+    ) -> anyhow::Result<Option<(Signature, u64)>> {
+        let now_ts: u64 = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs()
+            .try_into()?;
+
+        // get a fresh account and re-check the order and health
+        let liqee = self
+            .account_fetcher
+            .fetch_fresh_mango_account(pubkey)
+            .await?;
+        let (_, order) = liqee.perp_conditional_order_by_id(order_id)?;
+        let perp = self.mango_client.context.perp(order.perp_market_index);
+
+        let (max_base_lots, volume) = if order.is_expired(now_ts) {
+            // Triggering like this will close the expired order and not affect the liqor
+            (0, 0)
+        } else {
+            let liqee_health_cache =
+                health_cache::new(&self.mango_client.context, &*self.account_fetcher, &liqee)
+                    .await
+                    .context("creating liqee health cache")?;
+            if liqee_health_cache.is_liquidatable() {
+                return Ok(None);
+            }
+
+            let liqee_max_volume = match perp_conditional_order_max_volume(
+                &liqee,
+                &self.account_fetcher,
+                &perp.market,
+                order,
+                now_ts,
+            )? {
+                Some(v) => v,
+                None => return Ok(None),
+            };
+
+            // The liqor takes the opposite side and must stay above its min health ratio
+            let oracle_price = perp_oracle_price(&self.account_fetcher, &perp.market)?;
+            let premium_price = I80F48::from_num(order.premium_price(oracle_price.to_num()));
+            let liqor_side = match order.side() {
+                Side::Bid => Side::Ask,
+                Side::Ask => Side::Bid,
+            };
+            let mut liqor = self.mango_client.mango_account().await?;
+            let settle_token_index = perp.market.settle_token_index;
+            liqor.ensure_perp_position(order.perp_market_index, settle_token_index)?;
+            let liqor_health_cache =
+                health_cache::new(&self.mango_client.context, &*self.account_fetcher, &liqor)
+                    .await
+                    .context("creating liqor health cache")?;
+            // The health cache works with perp prices in settle token units
+            let settle_token_price = liqor_health_cache
+                .token_info(settle_token_index)?
+                .prices
+                .oracle;
+            let liqor_max_base_lots = liqor_health_cache.max_perp_for_health_ratio(
+                order.perp_market_index,
+                premium_price / settle_token_price,
+                liqor_side,
+                I80F48::from_num(self.config.min_health_ratio),
+            )?;
+
+            let lot_value = I80F48::from(perp.market.base_lot_size) * oracle_price;
+            let volume_max_base_lots = (I80F48::from(max_volume.min(liqee_max_volume)) / lot_value)
+                .floor()
+                .clamp_to_i64();
+            let max_base_lots = liqor_max_base_lots.min(volume_max_base_lots);
+            if max_base_lots <= 0 {
+                return Ok(None);
+            }
+            let volume = (I80F48::from(max_base_lots) * lot_value)
+                .floor()
+                .clamp_to_u64();
+            (max_base_lots, volume)
+        };
+
+        let compute_ix =
+            solana_sdk::compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(
+                self.config.compute_limit_for_trigger,
+            );
+        let trigger_ix = self
+            .mango_client
+            .perp_conditional_order_trigger_instruction((pubkey, &liqee), order_id, max_base_lots)
+            .await?;
+        let started = Instant::now();
+        let result = self
+            .mango_client
+            .send_and_confirm_owner_tx(vec![compute_ix, trigger_ix])
+            .await;
+        self.config
+            .tx_metrics
+            .record("perp_conditional_order_trigger", started, &result);
+        let txsig = result?;
+        info!(
+            %pubkey,
+            order_id,
+            max_base_lots,
+            %txsig,
+            "executed perp conditional order",
+        );
+        Ok(Some((txsig, volume)))
+    }
+
     async fn start_prepared_job(
         &self,
         pending: PreparedExecution,
@@ -715,10 +991,15 @@ impl ExecutionContext {
                 &allowed_tokens,
             )
             .await?;
-        let txsig = self
+        let started = Instant::now();
+        let result = self
             .mango_client
             .send_and_confirm_owner_tx(vec![compute_ix, trigger_ix])
-            .await?;
+            .await;
+        self.config
+            .tx_metrics
+            .record("token_conditional_swap_trigger", started, &result);
+        let txsig = result?;
         info!(
             pubkey = %pending.pubkey,
             tcs_id = pending.tcs_id,
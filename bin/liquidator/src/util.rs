@@ -120,3 +120,20 @@ pub fn max_swap_source_ignore_net_borrows(
         .context("getting max_swap_source")?;
     Ok(amount)
 }
+
+/// The bank of the token that MangoClient::borrow_bank() chooses, read from chain data
+pub fn borrow_bank(
+    client: &MangoClient,
+    account_fetcher: &chain_data::AccountFetcher,
+    token_index: TokenIndex,
+) -> anyhow::Result<(Pubkey, Bank)> {
+    let mut banks = vec![];
+    for bank_address in client.context.mint_info(token_index).banks() {
+        let bank: Bank = account_fetcher.fetch(bank_address)?;
+        banks.push((*bank_address, bank));
+    }
+    Ok(banks
+        .into_iter()
+        .min_by_key(|(_, bank)| bank.net_borrows_in_window)
+        .unwrap())
+}
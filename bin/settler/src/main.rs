@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
@@ -9,6 +10,7 @@ use mango_v4_client::{
     account_update_stream, chain_data, keypair_from_cli, snapshot_source, websocket_source,
     AsyncChannelSendUnlessFull, Client, MangoClient, MangoGroupContext, TransactionBuilderConfig,
 };
+use mango_v4_metrics::TransactionMetrics;
 use tracing::*;
 
 use itertools::Itertools;
@@ -16,7 +18,6 @@ use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashSet;
 
-pub mod metrics;
 pub mod settle;
 pub mod util;
 
@@ -67,6 +68,14 @@ struct Cli {
     /// prioritize each transaction with this many microlamports/cu
     #[clap(long, env, default_value = "0")]
     prioritization_micro_lamports: u64,
+
+    /// file for persisting account snapshots, used to warm start after a restart
+    #[clap(long, env)]
+    snapshot_file: Option<PathBuf>,
+
+    /// port for serving prometheus metrics on /metrics
+    #[clap(long, env, default_value = "9093")]
+    metrics_port: u16,
 }
 
 pub fn encode_address(addr: &Pubkey) -> String {
@@ -137,7 +146,7 @@ async fn main() -> anyhow::Result<()> {
     solana_logger::setup_with_default("info");
     info!("startup");
 
-    let metrics = metrics::start();
+    let metrics = mango_v4_metrics::start("settler", cli.metrics_port);
 
     let (account_update_sender, account_update_receiver) =
         async_channel::unbounded::<account_update_stream::Message>();
@@ -147,8 +156,9 @@ async fn main() -> anyhow::Result<()> {
     websocket_source::start(
         websocket_source::Config {
             rpc_ws_url: ws_url.clone(),
-            serum_program: cli.serum_program,
+            serum_program: Some(cli.serum_program),
             open_orders_authority: mango_group,
+            mango_program_filters: vec![],
         },
         mango_oracles.clone(),
         account_update_sender.clone(),
@@ -170,12 +180,13 @@ async fn main() -> anyhow::Result<()> {
             parallel_rpc_requests: cli.parallel_rpc_requests,
             snapshot_interval: std::time::Duration::from_secs(cli.snapshot_interval_secs),
             min_slot: first_websocket_slot + 10,
+            snapshot_file: cli.snapshot_file.clone(),
         },
         mango_oracles,
         account_update_sender.clone(),
     );
 
-    start_chain_data_metrics(chain_data.clone(), &metrics);
+    chain_data::start_metrics(chain_data.clone(), &metrics);
 
     let shared_state = Arc::new(RwLock::new(SharedState::default()));
 
@@ -200,6 +211,7 @@ async fn main() -> anyhow::Result<()> {
         mango_client: mango_client.clone(),
         account_fetcher: account_fetcher.clone(),
         config: settle_config,
+        tx_metrics: TransactionMetrics::new(&metrics),
         recently_settled: Default::default(),
     };
 
@@ -213,9 +225,12 @@ async fn main() -> anyhow::Result<()> {
 
         let shared_state = shared_state.clone();
 
-        let mut metric_account_update_queue_len =
-            metrics.register_u64("account_update_queue_length".into());
-        let mut metric_mango_accounts = metrics.register_u64("mango_accounts".into());
+        let metric_account_update_queue_len = metrics.register_u64(
+            "account_update_queue_length",
+            "Account updates waiting to be applied to the chain data",
+        );
+        let metric_mango_accounts =
+            metrics.register_u64("mango_accounts", "Mango accounts of the group");
 
         let mut mint_infos = HashMap::<TokenIndex, Pubkey>::new();
         let mut oracles = HashSet::<Pubkey>::new();
@@ -345,22 +360,3 @@ struct SharedState {
     /// Check all accounts?
     health_check_all: bool,
 }
-
-fn start_chain_data_metrics(chain: Arc<RwLock<chain_data::ChainData>>, metrics: &metrics::Metrics) {
-    let mut interval = tokio::time::interval(std::time::Duration::from_secs(600));
-
-    let mut metric_slots_count = metrics.register_u64("chain_data_slots_count".into());
-    let mut metric_accounts_count = metrics.register_u64("chain_data_accounts_count".into());
-    let mut metric_account_write_count =
-        metrics.register_u64("chain_data_account_write_count".into());
-
-    tokio::spawn(async move {
-        loop {
-            interval.tick().await;
-            let chain_lock = chain.read().unwrap();
-            metric_slots_count.set(chain_lock.slots_count() as u64);
-            metric_accounts_count.set(chain_lock.accounts_count() as u64);
-            metric_account_write_count.set(chain_lock.account_writes_count() as u64);
-        }
-    });
-}
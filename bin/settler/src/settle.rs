@@ -8,6 +8,7 @@ use mango_v4::state::{PerpMarket, PerpMarketIndex};
 use mango_v4_client::{
     chain_data, health_cache, prettify_solana_client_error, MangoClient, TransactionBuilder,
 };
+use mango_v4_metrics::TransactionMetrics;
 use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::instruction::Instruction;
@@ -57,6 +58,7 @@ pub struct SettlementState {
     pub mango_client: Arc<MangoClient>,
     pub account_fetcher: Arc<chain_data::AccountFetcher>,
     pub config: Config,
+    pub tx_metrics: TransactionMetrics,
 
     pub recently_settled: HashMap<Pubkey, Instant>,
 }
@@ -124,13 +126,19 @@ impl SettlementState {
                 };
                 let perp_max_settle =
                     health_cache.perp_max_settle(perp_market.settle_token_index)?;
+                let settle_token_price = health_cache
+                    .token_info(perp_market.settle_token_index)?
+                    .prices
+                    .oracle;
 
                 let perp_position = account.perp_position_mut(perp_market_index).unwrap();
                 perp_position.settle_funding(perp_market);
                 perp_position.update_settle_limit(perp_market, now_ts);
 
+                // Work in settle token native units, like the perp_settle_pnl instruction
                 let unsettled = perp_position.unsettled_pnl(perp_market, *price)?;
-                let limited = perp_position.apply_pnl_settle_limit(perp_market, unsettled);
+                let limited = perp_position.apply_pnl_settle_limit(perp_market, unsettled)
+                    / settle_token_price;
                 let settleable = if limited >= 0 {
                     limited
                 } else {
@@ -189,6 +197,7 @@ impl SettlementState {
                     .await?
                     .0,
                 address_lookup_tables: &address_lookup_tables,
+                tx_metrics: &self.tx_metrics,
             };
 
             for (account_a, mut settleable_a, fee) in positive_settleable {
@@ -246,6 +255,7 @@ struct SettleBatchProcessor<'a> {
     max_batch_size: usize,
     blockhash: solana_sdk::hash::Hash,
     address_lookup_tables: &'a Vec<AddressLookupTableAccount>,
+    tx_metrics: &'a TransactionMetrics,
 }
 
 impl<'a> SettleBatchProcessor<'a> {
@@ -271,6 +281,7 @@ impl<'a> SettleBatchProcessor<'a> {
         let tx = self.transaction()?;
         self.instructions.clear();
 
+        let started = Instant::now();
         let send_result = self
             .mango_client
             .client
@@ -278,6 +289,8 @@ impl<'a> SettleBatchProcessor<'a> {
             .send_transaction_with_config(&tx, self.mango_client.client.rpc_send_transaction_config)
             .await
             .map_err(|e| prettify_solana_client_error(e));
+        self.tx_metrics
+            .record("perp_settle_pnl", started, &send_result);
 
         if let Err(err) = send_result {
             info!("error while sending settle batch: {}", err);
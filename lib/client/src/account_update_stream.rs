@@ -10,6 +10,9 @@ use crate::chain_data;
 pub struct AccountUpdate {
     pub pubkey: Pubkey,
     pub slot: u64,
+    /// Orders writes within a slot. Sources that don't provide it use 0 for
+    /// snapshots and 1 for streamed updates, so streamed data wins.
+    pub write_version: u64,
     pub account: AccountSharedData,
 }
 
@@ -24,6 +27,7 @@ impl AccountUpdate {
         Ok(AccountUpdate {
             pubkey,
             slot: rpc.context.slot,
+            write_version: 1,
             account,
         })
     }
@@ -47,7 +51,7 @@ impl Message {
                     AccountData {
                         slot: account_write.slot,
                         account: account_write.account.clone(),
-                        write_version: 1,
+                        write_version: account_write.write_version,
                     },
                 );
             }
@@ -58,7 +62,7 @@ impl Message {
                         chain_data::AccountData {
                             slot: account_update.slot,
                             account: account_update.account.clone(),
-                            write_version: 0,
+                            write_version: account_update.write_version,
                         },
                     );
                 }
@@ -0,0 +1,1291 @@
+//! Instruction builders for the group admin and token/market listing instructions.
+//!
+//! These use the MangoClient's owner as the admin (or creator) signer.
+
+use std::sync::Arc;
+
+use anchor_lang::prelude::System;
+use anchor_lang::Id;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::Token;
+
+use fixed::types::I80F48;
+
+use mango_v4::state::{
+    Group, OpenbookV2MarketIndex, PerpMarketIndex, Serum3MarketIndex, TokenIndex,
+};
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::sysvar;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use crate::account_fetcher::account_fetcher_fetch_anchor_account;
+use crate::{util, Client, MangoClient, TransactionBuilder};
+
+fn find_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &mango_v4::id()).0
+}
+
+impl MangoClient {
+    //
+    // Group
+    //
+
+    pub fn group_create_instruction(
+        creator: Pubkey,
+        payer: Pubkey,
+        insurance_mint: Pubkey,
+        group_num: u32,
+        testing: u8,
+        version: u8,
+    ) -> Instruction {
+        let group = Self::group_for_admin(creator, group_num);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::GroupCreate {
+                    group,
+                    creator,
+                    insurance_mint,
+                    insurance_vault: find_pda(&[b"InsuranceVault".as_ref(), group.as_ref()]),
+                    payer,
+                    token_program: Token::id(),
+                    system_program: System::id(),
+                    rent: sysvar::rent::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::GroupCreate {
+                group_num,
+                testing,
+                version,
+            }),
+        }
+    }
+
+    /// Creates a new group, before there is a MangoClient for it. Returns the group address.
+    pub async fn group_create(
+        client: &Client,
+        creator: Arc<Keypair>,
+        insurance_mint: Pubkey,
+        group_num: u32,
+        testing: u8,
+        version: u8,
+    ) -> anyhow::Result<(Pubkey, Signature)> {
+        let ix = Self::group_create_instruction(
+            creator.pubkey(),
+            client.fee_payer.pubkey(),
+            insurance_mint,
+            group_num,
+            testing,
+            version,
+        );
+        let signature = TransactionBuilder {
+            instructions: vec![ix],
+            address_lookup_tables: vec![],
+            payer: client.fee_payer.pubkey(),
+            signers: vec![creator.clone(), client.fee_payer.clone()],
+            config: client.transaction_builder_config,
+        }
+        .send_and_confirm(client)
+        .await?;
+        Ok((
+            Self::group_for_admin(creator.pubkey(), group_num),
+            signature,
+        ))
+    }
+
+    pub fn group_edit_instruction(&self, edit: mango_v4::instruction::GroupEdit) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::GroupEdit {
+                    group: self.group(),
+                    admin: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&edit),
+        }
+    }
+
+    pub async fn group_edit(
+        &self,
+        edit: mango_v4::instruction::GroupEdit,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.group_edit_instruction(edit);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn group_withdraw_insurance_fund_instruction(
+        &self,
+        destination: Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<Instruction> {
+        let group: Group =
+            account_fetcher_fetch_anchor_account(&*self.account_fetcher, &self.group()).await?;
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::GroupWithdrawInsuranceFund {
+                    group: self.group(),
+                    admin: self.owner(),
+                    insurance_vault: group.insurance_vault,
+                    destination,
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::GroupWithdrawInsuranceFund { amount },
+            ),
+        })
+    }
+
+    pub async fn group_withdraw_insurance_fund(
+        &self,
+        destination: Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .group_withdraw_insurance_fund_instruction(destination, amount)
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn group_close_instruction(&self) -> anyhow::Result<Instruction> {
+        let group: Group =
+            account_fetcher_fetch_anchor_account(&*self.account_fetcher, &self.group()).await?;
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::GroupClose {
+                    group: self.group(),
+                    admin: self.owner(),
+                    insurance_vault: group.insurance_vault,
+                    sol_destination: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::GroupClose {}),
+        })
+    }
+
+    pub async fn group_close(&self) -> anyhow::Result<Signature> {
+        let ix = self.group_close_instruction().await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn ix_gate_set_instruction(&self, ix_gate: u128) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::IxGateSet {
+                    group: self.group(),
+                    admin: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::IxGateSet { ix_gate }),
+        }
+    }
+
+    pub async fn ix_gate_set(&self, ix_gate: u128) -> anyhow::Result<Signature> {
+        let ix = self.ix_gate_set_instruction(ix_gate);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn alt_set_instruction(&self, address_lookup_table: Pubkey, index: u8) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AltSet {
+                    group: self.group(),
+                    admin: self.owner(),
+                    address_lookup_table,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AltSet { index }),
+        }
+    }
+
+    pub async fn alt_set(
+        &self,
+        address_lookup_table: Pubkey,
+        index: u8,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.alt_set_instruction(address_lookup_table, index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn alt_extend_instruction(
+        &self,
+        address_lookup_table: Pubkey,
+        index: u8,
+        new_addresses: Vec<Pubkey>,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AltExtend {
+                    group: self.group(),
+                    admin: self.owner(),
+                    payer: self.client.fee_payer.pubkey(),
+                    address_lookup_table,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AltExtend {
+                index,
+                new_addresses,
+            }),
+        }
+    }
+
+    pub async fn alt_extend(
+        &self,
+        address_lookup_table: Pubkey,
+        index: u8,
+        new_addresses: Vec<Pubkey>,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.alt_extend_instruction(address_lookup_table, index, new_addresses);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn account_toggle_freeze_instruction(&self, account: Pubkey, freeze: bool) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountToggleFreeze {
+                    group: self.group(),
+                    account,
+                    admin: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountToggleFreeze {
+                freeze,
+            }),
+        }
+    }
+
+    pub async fn account_toggle_freeze(
+        &self,
+        account: Pubkey,
+        freeze: bool,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.account_toggle_freeze_instruction(account, freeze);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    //
+    // Tokens
+    //
+
+    /// Registers a new token. The bank, vault and mint info addresses are derived
+    /// from the token index in `params`.
+    pub fn token_register_instruction(
+        &self,
+        mint: Pubkey,
+        oracle: Pubkey,
+        params: mango_v4::instruction::TokenRegister,
+    ) -> Instruction {
+        let group = self.group();
+        let token_index = params.token_index.to_le_bytes();
+        let bank_num = 0u32.to_le_bytes();
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenRegister {
+                    group,
+                    admin: self.owner(),
+                    mint,
+                    bank: find_pda(&[b"Bank".as_ref(), group.as_ref(), &token_index, &bank_num]),
+                    vault: find_pda(&[b"Vault".as_ref(), group.as_ref(), &token_index, &bank_num]),
+                    mint_info: find_pda(&[b"MintInfo".as_ref(), group.as_ref(), mint.as_ref()]),
+                    oracle,
+                    payer: self.client.fee_payer.pubkey(),
+                    token_program: Token::id(),
+                    system_program: System::id(),
+                    rent: sysvar::rent::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&params),
+        }
+    }
+
+    pub async fn token_register(
+        &self,
+        mint: Pubkey,
+        oracle: Pubkey,
+        params: mango_v4::instruction::TokenRegister,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_register_instruction(mint, oracle, params);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Registers a new token with default parameters, needs the group's fast listing admin
+    pub fn token_register_trustless_instruction(
+        &self,
+        mint: Pubkey,
+        oracle: Pubkey,
+        token_index: TokenIndex,
+        name: String,
+    ) -> Instruction {
+        let group = self.group();
+        let token_index_bytes = token_index.to_le_bytes();
+        let bank_num = 0u32.to_le_bytes();
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenRegisterTrustless {
+                    group,
+                    admin: self.owner(),
+                    mint,
+                    bank: find_pda(&[
+                        b"Bank".as_ref(),
+                        group.as_ref(),
+                        &token_index_bytes,
+                        &bank_num,
+                    ]),
+                    vault: find_pda(&[
+                        b"Vault".as_ref(),
+                        group.as_ref(),
+                        &token_index_bytes,
+                        &bank_num,
+                    ]),
+                    mint_info: find_pda(&[b"MintInfo".as_ref(), group.as_ref(), mint.as_ref()]),
+                    oracle,
+                    payer: self.client.fee_payer.pubkey(),
+                    token_program: Token::id(),
+                    system_program: System::id(),
+                    rent: sysvar::rent::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenRegisterTrustless { token_index, name },
+            ),
+        }
+    }
+
+    pub async fn token_register_trustless(
+        &self,
+        mint: Pubkey,
+        oracle: Pubkey,
+        token_index: TokenIndex,
+        name: String,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_register_trustless_instruction(mint, oracle, token_index, name);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Changes token parameters. Pass the new oracle if `params` changes it.
+    pub fn token_edit_instruction(
+        &self,
+        token_index: TokenIndex,
+        oracle: Pubkey,
+        params: mango_v4::instruction::TokenEdit,
+    ) -> Instruction {
+        let token = self.context.token(token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::TokenEdit {
+                        group: self.group(),
+                        admin: self.owner(),
+                        mint_info: token.mint_info_address,
+                        oracle,
+                    },
+                    None,
+                );
+                ams.extend(
+                    token
+                        .mint_info
+                        .banks()
+                        .iter()
+                        .map(|bank| util::to_writable_account_meta(*bank)),
+                );
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&params),
+        }
+    }
+
+    pub async fn token_edit(
+        &self,
+        token_index: TokenIndex,
+        oracle: Pubkey,
+        params: mango_v4::instruction::TokenEdit,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_edit_instruction(token_index, oracle, params);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn token_add_bank_instruction(
+        &self,
+        token_index: TokenIndex,
+        bank_num: u32,
+    ) -> Instruction {
+        let group = self.group();
+        let token = self.context.token(token_index);
+        let token_index_bytes = token_index.to_le_bytes();
+        let bank_num_bytes = bank_num.to_le_bytes();
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenAddBank {
+                    group,
+                    admin: self.owner(),
+                    mint: token.mint_info.mint,
+                    existing_bank: token.mint_info.first_bank(),
+                    bank: find_pda(&[
+                        b"Bank".as_ref(),
+                        group.as_ref(),
+                        &token_index_bytes,
+                        &bank_num_bytes,
+                    ]),
+                    vault: find_pda(&[
+                        b"Vault".as_ref(),
+                        group.as_ref(),
+                        &token_index_bytes,
+                        &bank_num_bytes,
+                    ]),
+                    mint_info: token.mint_info_address,
+                    payer: self.client.fee_payer.pubkey(),
+                    token_program: Token::id(),
+                    system_program: System::id(),
+                    rent: sysvar::rent::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::TokenAddBank {
+                token_index,
+                bank_num,
+            }),
+        }
+    }
+
+    pub async fn token_add_bank(
+        &self,
+        token_index: TokenIndex,
+        bank_num: u32,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_add_bank_instruction(token_index, bank_num);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Removes a token from a testing group, vault dust goes to the admin's token account
+    pub fn token_deregister_instruction(&self, token_index: TokenIndex) -> Instruction {
+        let token = self.context.token(token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::TokenDeregister {
+                        group: self.group(),
+                        admin: self.owner(),
+                        mint_info: token.mint_info_address,
+                        dust_vault: get_associated_token_address(
+                            &self.owner(),
+                            &token.mint_info.mint,
+                        ),
+                        sol_destination: self.owner(),
+                        token_program: Token::id(),
+                    },
+                    None,
+                );
+                for (bank, vault) in token.mint_info.banks_and_vaults() {
+                    ams.push(util::to_writable_account_meta(bank));
+                    ams.push(util::to_writable_account_meta(vault));
+                }
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::TokenDeregister {}),
+        }
+    }
+
+    pub async fn token_deregister(&self, token_index: TokenIndex) -> anyhow::Result<Signature> {
+        let ix = self.token_deregister_instruction(token_index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn token_insurance_fund_address(&self, token_index: TokenIndex) -> (Pubkey, Pubkey) {
+        let group = self.group();
+        let token_index_bytes = token_index.to_le_bytes();
+        (
+            find_pda(&[
+                b"TokenInsuranceFund".as_ref(),
+                group.as_ref(),
+                &token_index_bytes,
+            ]),
+            find_pda(&[
+                b"TokenInsuranceFundVault".as_ref(),
+                group.as_ref(),
+                &token_index_bytes,
+            ]),
+        )
+    }
+
+    pub fn token_insurance_fund_create_instruction(&self, token_index: TokenIndex) -> Instruction {
+        let token = self.context.token(token_index);
+        let (insurance_fund, vault) = self.token_insurance_fund_address(token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::TokenInsuranceFundCreate {
+                        group: self.group(),
+                        admin: self.owner(),
+                        mint_info: token.mint_info_address,
+                        mint: token.mint_info.mint,
+                        insurance_fund,
+                        vault,
+                        payer: self.client.fee_payer.pubkey(),
+                        token_program: Token::id(),
+                        system_program: System::id(),
+                        rent: sysvar::rent::id(),
+                    },
+                    None,
+                );
+                ams.extend(
+                    token
+                        .mint_info
+                        .banks()
+                        .iter()
+                        .map(|bank| util::to_writable_account_meta(*bank)),
+                );
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenInsuranceFundCreate { token_index },
+            ),
+        }
+    }
+
+    pub async fn token_insurance_fund_create(
+        &self,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_insurance_fund_create_instruction(token_index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Deposits from the admin's associated token account into the token insurance fund
+    pub fn token_insurance_fund_deposit_instruction(
+        &self,
+        token_index: TokenIndex,
+        amount: u64,
+    ) -> Instruction {
+        let token = self.context.token(token_index);
+        let (insurance_fund, vault) = self.token_insurance_fund_address(token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenInsuranceFundDeposit {
+                    group: self.group(),
+                    admin: self.owner(),
+                    insurance_fund,
+                    vault,
+                    token_account: get_associated_token_address(
+                        &self.owner(),
+                        &token.mint_info.mint,
+                    ),
+                    token_authority: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenInsuranceFundDeposit { amount },
+            ),
+        }
+    }
+
+    pub async fn token_insurance_fund_deposit(
+        &self,
+        token_index: TokenIndex,
+        amount: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_insurance_fund_deposit_instruction(token_index, amount);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn token_insurance_fund_withdraw_instruction(
+        &self,
+        token_index: TokenIndex,
+        destination: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let (insurance_fund, vault) = self.token_insurance_fund_address(token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenInsuranceFundWithdraw {
+                    group: self.group(),
+                    admin: self.owner(),
+                    insurance_fund,
+                    vault,
+                    destination,
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenInsuranceFundWithdraw { amount },
+            ),
+        }
+    }
+
+    pub async fn token_insurance_fund_withdraw(
+        &self,
+        token_index: TokenIndex,
+        destination: Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_insurance_fund_withdraw_instruction(token_index, destination, amount);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Withdraws the token's collected fees into the admin's associated token account
+    pub fn admin_token_withdraw_fees_instruction(&self, token_index: TokenIndex) -> Instruction {
+        let token = self.context.token(token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AdminTokenWithdrawFees {
+                    group: self.group(),
+                    bank: token.mint_info.first_bank(),
+                    vault: token.mint_info.first_vault(),
+                    token_account: get_associated_token_address(
+                        &self.owner(),
+                        &token.mint_info.mint,
+                    ),
+                    token_program: Token::id(),
+                    admin: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::AdminTokenWithdrawFees {},
+            ),
+        }
+    }
+
+    pub async fn admin_token_withdraw_fees(
+        &self,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.admin_token_withdraw_fees_instruction(token_index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    //
+    // Oracles
+    //
+
+    pub fn stub_oracle_address(&self, mint: &Pubkey) -> Pubkey {
+        find_pda(&[b"StubOracle".as_ref(), self.group().as_ref(), mint.as_ref()])
+    }
+
+    pub fn stub_oracle_create_instruction(&self, mint: Pubkey, price: I80F48) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::StubOracleCreate {
+                    group: self.group(),
+                    oracle: self.stub_oracle_address(&mint),
+                    admin: self.owner(),
+                    mint,
+                    payer: self.client.fee_payer.pubkey(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::StubOracleCreate {
+                price,
+            }),
+        }
+    }
+
+    pub async fn stub_oracle_create(
+        &self,
+        mint: Pubkey,
+        price: I80F48,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.stub_oracle_create_instruction(mint, price);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn stub_oracle_close_instruction(&self, oracle: Pubkey) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::StubOracleClose {
+                    group: self.group(),
+                    admin: self.owner(),
+                    oracle,
+                    sol_destination: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::StubOracleClose {}),
+        }
+    }
+
+    pub async fn stub_oracle_close(&self, oracle: Pubkey) -> anyhow::Result<Signature> {
+        let ix = self.stub_oracle_close_instruction(oracle);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn stub_oracle_set_instruction(&self, oracle: Pubkey, price: I80F48) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::StubOracleSet {
+                    group: self.group(),
+                    admin: self.owner(),
+                    oracle,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::StubOracleSet {
+                price,
+            }),
+        }
+    }
+
+    pub async fn stub_oracle_set(
+        &self,
+        oracle: Pubkey,
+        price: I80F48,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.stub_oracle_set_instruction(oracle, price);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Like stub_oracle_set_instruction(), but also sets the last update slot and deviation
+    pub fn stub_oracle_set_test_instruction(
+        &self,
+        oracle: Pubkey,
+        price: I80F48,
+        last_update_slot: u64,
+        deviation: I80F48,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::StubOracleSet {
+                    group: self.group(),
+                    admin: self.owner(),
+                    oracle,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::StubOracleSetTest {
+                price,
+                last_update_slot,
+                deviation,
+            }),
+        }
+    }
+
+    pub async fn stub_oracle_set_test(
+        &self,
+        oracle: Pubkey,
+        price: I80F48,
+        last_update_slot: u64,
+        deviation: I80F48,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.stub_oracle_set_test_instruction(oracle, price, last_update_slot, deviation);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn twap_oracle_address(&self, mint: &Pubkey) -> Pubkey {
+        find_pda(&[b"TwapOracle".as_ref(), self.group().as_ref(), mint.as_ref()])
+    }
+
+    pub fn twap_oracle_create_instruction(
+        &self,
+        mint: Pubkey,
+        pool: Pubkey,
+        window_secs: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TwapOracleCreate {
+                    group: self.group(),
+                    oracle: self.twap_oracle_address(&mint),
+                    admin: self.owner(),
+                    mint,
+                    pool,
+                    payer: self.client.fee_payer.pubkey(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::TwapOracleCreate {
+                window_secs,
+            }),
+        }
+    }
+
+    pub async fn twap_oracle_create(
+        &self,
+        mint: Pubkey,
+        pool: Pubkey,
+        window_secs: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.twap_oracle_create_instruction(mint, pool, window_secs);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Permissionless update of a twap oracle from its pool
+    pub fn twap_oracle_update_instruction(&self, oracle: Pubkey, pool: Pubkey) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TwapOracleUpdate {
+                    group: self.group(),
+                    oracle,
+                    pool,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::TwapOracleUpdate {}),
+        }
+    }
+
+    pub async fn twap_oracle_update(
+        &self,
+        oracle: Pubkey,
+        pool: Pubkey,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.twap_oracle_update_instruction(oracle, pool);
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    //
+    // Serum3
+    //
+
+    fn serum3_index_reservation_address(&self, market_index: Serum3MarketIndex) -> Pubkey {
+        find_pda(&[
+            b"Serum3Index".as_ref(),
+            self.group().as_ref(),
+            &market_index.to_le_bytes(),
+        ])
+    }
+
+    pub fn serum3_register_market_instruction(
+        &self,
+        serum_program: Pubkey,
+        serum_market_external: Pubkey,
+        base_token_index: TokenIndex,
+        quote_token_index: TokenIndex,
+        market_index: Serum3MarketIndex,
+        name: String,
+    ) -> Instruction {
+        let group = self.group();
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::Serum3RegisterMarket {
+                    group,
+                    admin: self.owner(),
+                    serum_program,
+                    serum_market_external,
+                    serum_market: find_pda(&[
+                        b"Serum3Market".as_ref(),
+                        group.as_ref(),
+                        serum_market_external.as_ref(),
+                    ]),
+                    index_reservation: self.serum3_index_reservation_address(market_index),
+                    quote_bank: self.context.mint_info(quote_token_index).first_bank(),
+                    base_bank: self.context.mint_info(base_token_index).first_bank(),
+                    payer: self.client.fee_payer.pubkey(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::Serum3RegisterMarket { market_index, name },
+            ),
+        }
+    }
+
+    pub async fn serum3_register_market(
+        &self,
+        serum_program: Pubkey,
+        serum_market_external: Pubkey,
+        base_token_index: TokenIndex,
+        quote_token_index: TokenIndex,
+        market_index: Serum3MarketIndex,
+        name: String,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.serum3_register_market_instruction(
+            serum_program,
+            serum_market_external,
+            base_token_index,
+            quote_token_index,
+            market_index,
+            name,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn serum3_edit_market_instruction(
+        &self,
+        market_index: Serum3MarketIndex,
+        params: mango_v4::instruction::Serum3EditMarket,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::Serum3EditMarket {
+                    group: self.group(),
+                    admin: self.owner(),
+                    market: self.context.serum3(market_index).address,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&params),
+        }
+    }
+
+    pub async fn serum3_edit_market(
+        &self,
+        market_index: Serum3MarketIndex,
+        params: mango_v4::instruction::Serum3EditMarket,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.serum3_edit_market_instruction(market_index, params);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn serum3_deregister_market_instruction(
+        &self,
+        market_index: Serum3MarketIndex,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::Serum3DeregisterMarket {
+                    group: self.group(),
+                    admin: self.owner(),
+                    serum_market: self.context.serum3(market_index).address,
+                    index_reservation: self.serum3_index_reservation_address(market_index),
+                    sol_destination: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::Serum3DeregisterMarket {},
+            ),
+        }
+    }
+
+    pub async fn serum3_deregister_market(
+        &self,
+        market_index: Serum3MarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.serum3_deregister_market_instruction(market_index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    //
+    // Openbook v2
+    //
+
+    fn openbook_v2_index_reservation_address(&self, market_index: OpenbookV2MarketIndex) -> Pubkey {
+        find_pda(&[
+            b"OpenbookV2Index".as_ref(),
+            self.group().as_ref(),
+            &market_index.to_le_bytes(),
+        ])
+    }
+
+    pub fn openbook_v2_register_market_instruction(
+        &self,
+        openbook_v2_program: Pubkey,
+        openbook_v2_market_external: Pubkey,
+        base_token_index: TokenIndex,
+        quote_token_index: TokenIndex,
+        market_index: OpenbookV2MarketIndex,
+        name: String,
+    ) -> Instruction {
+        let group = self.group();
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::OpenbookV2RegisterMarket {
+                    group,
+                    admin: self.owner(),
+                    openbook_v2_program,
+                    openbook_v2_market_external,
+                    openbook_v2_market: find_pda(&[
+                        b"OpenbookV2Market".as_ref(),
+                        group.as_ref(),
+                        openbook_v2_market_external.as_ref(),
+                    ]),
+                    index_reservation: self.openbook_v2_index_reservation_address(market_index),
+                    quote_bank: self.context.mint_info(quote_token_index).first_bank(),
+                    base_bank: self.context.mint_info(base_token_index).first_bank(),
+                    payer: self.client.fee_payer.pubkey(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2RegisterMarket { market_index, name },
+            ),
+        }
+    }
+
+    pub async fn openbook_v2_register_market(
+        &self,
+        openbook_v2_program: Pubkey,
+        openbook_v2_market_external: Pubkey,
+        base_token_index: TokenIndex,
+        quote_token_index: TokenIndex,
+        market_index: OpenbookV2MarketIndex,
+        name: String,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.openbook_v2_register_market_instruction(
+            openbook_v2_program,
+            openbook_v2_market_external,
+            base_token_index,
+            quote_token_index,
+            market_index,
+            name,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn openbook_v2_edit_market_instruction(
+        &self,
+        market_index: OpenbookV2MarketIndex,
+        reduce_only_opt: Option<bool>,
+        force_close_opt: Option<bool>,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::OpenbookV2EditMarket {
+                    group: self.group(),
+                    admin: self.owner(),
+                    market: self.context.openbook_v2(market_index).address,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2EditMarket {
+                    reduce_only_opt,
+                    force_close_opt,
+                },
+            ),
+        }
+    }
+
+    pub async fn openbook_v2_edit_market(
+        &self,
+        market_index: OpenbookV2MarketIndex,
+        reduce_only_opt: Option<bool>,
+        force_close_opt: Option<bool>,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.openbook_v2_edit_market_instruction(
+            market_index,
+            reduce_only_opt,
+            force_close_opt,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn openbook_v2_deregister_market_instruction(
+        &self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::OpenbookV2DeregisterMarket {
+                    group: self.group(),
+                    admin: self.owner(),
+                    openbook_v2_market: self.context.openbook_v2(market_index).address,
+                    index_reservation: self.openbook_v2_index_reservation_address(market_index),
+                    sol_destination: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2DeregisterMarket {},
+            ),
+        }
+    }
+
+    pub async fn openbook_v2_deregister_market(
+        &self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.openbook_v2_deregister_market_instruction(market_index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    //
+    // Perps
+    //
+
+    /// Creates a perp market. The bids, asks and event queue accounts must already
+    /// exist, allocated with the right size and owned by the program.
+    pub fn perp_create_market_instruction(
+        &self,
+        oracle: Pubkey,
+        bids: Pubkey,
+        asks: Pubkey,
+        event_queue: Pubkey,
+        params: mango_v4::instruction::PerpCreateMarket,
+    ) -> Instruction {
+        let group = self.group();
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpCreateMarket {
+                    group,
+                    admin: self.owner(),
+                    oracle,
+                    perp_market: find_pda(&[
+                        b"PerpMarket".as_ref(),
+                        group.as_ref(),
+                        &params.perp_market_index.to_le_bytes(),
+                    ]),
+                    bids,
+                    asks,
+                    event_queue,
+                    payer: self.client.fee_payer.pubkey(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&params),
+        }
+    }
+
+    pub async fn perp_create_market(
+        &self,
+        oracle: Pubkey,
+        bids: Pubkey,
+        asks: Pubkey,
+        event_queue: Pubkey,
+        params: mango_v4::instruction::PerpCreateMarket,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_create_market_instruction(oracle, bids, asks, event_queue, params);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Changes perp market parameters. Pass the new oracle if `params` changes it.
+    pub fn perp_edit_market_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        oracle: Pubkey,
+        params: mango_v4::instruction::PerpEditMarket,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpEditMarket {
+                    group: self.group(),
+                    admin: self.owner(),
+                    perp_market: self.context.perp(market_index).address,
+                    oracle,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&params),
+        }
+    }
+
+    pub async fn perp_edit_market(
+        &self,
+        market_index: PerpMarketIndex,
+        oracle: Pubkey,
+        params: mango_v4::instruction::PerpEditMarket,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_edit_market_instruction(market_index, oracle, params);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_close_market_instruction(&self, market_index: PerpMarketIndex) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpCloseMarket {
+                    group: self.group(),
+                    admin: self.owner(),
+                    perp_market: perp.address,
+                    bids: perp.market.bids,
+                    asks: perp.market.asks,
+                    event_queue: perp.market.event_queue,
+                    sol_destination: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpCloseMarket {}),
+        }
+    }
+
+    pub async fn perp_close_market(
+        &self,
+        market_index: PerpMarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_close_market_instruction(market_index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Withdraws the perp market's collected fees into the admin's settle token account
+    pub fn admin_perp_withdraw_fees_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+    ) -> Instruction {
+        let perp = self.context.perp(market_index);
+        let settle_token = self.context.token(perp.market.settle_token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AdminPerpWithdrawFees {
+                    group: self.group(),
+                    perp_market: perp.address,
+                    bank: settle_token.mint_info.first_bank(),
+                    vault: settle_token.mint_info.first_vault(),
+                    token_account: get_associated_token_address(
+                        &self.owner(),
+                        &settle_token.mint_info.mint,
+                    ),
+                    token_program: Token::id(),
+                    admin: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::AdminPerpWithdrawFees {},
+            ),
+        }
+    }
+
+    pub async fn admin_perp_withdraw_fees(
+        &self,
+        market_index: PerpMarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.admin_perp_withdraw_fees_instruction(market_index);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+}
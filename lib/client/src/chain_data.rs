@@ -1,2 +1,273 @@
 pub use crate::chain_data_fetcher::AccountFetcher;
 pub use mango_feeds_connector::chain_data::*;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Leading bytes of every snapshot file, to reject unrelated files early
+const SNAPSHOT_MAGIC: [u8; 8] = *b"MNGOSNAP";
+
+/// Bump when the serialized layout of `SnapshotFile` changes
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotAccount {
+    pub pubkey: Pubkey,
+    pub slot: u64,
+    pub write_version: u64,
+    pub account: Account,
+}
+
+/// On-disk representation of account state, written by `ChainDataSnapshot::save_to`
+/// and `snapshot_source` and read back on startup.
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotFile {
+    /// Best chain slot at the time the snapshot was taken
+    pub slot: u64,
+    /// Newest rooted slot at the time the snapshot was taken
+    pub rooted_slot: u64,
+    pub accounts: Vec<SnapshotAccount>,
+}
+
+impl SnapshotFile {
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        // Write to a temporary file first so readers never see a partial snapshot
+        let tmp_path = path.with_extension("tmp");
+        {
+            let file = File::create(&tmp_path)
+                .with_context(|| format!("creating snapshot file {}", tmp_path.display()))?;
+            let mut writer = BufWriter::new(file);
+            writer.write_all(&SNAPSHOT_MAGIC)?;
+            writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+            bincode::serialize_into(&mut writer, self).context("serializing snapshot")?;
+            writer.flush()?;
+        }
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("moving snapshot file to {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("opening snapshot file {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        anyhow::ensure!(
+            magic == SNAPSHOT_MAGIC,
+            "{} is not a chain data snapshot",
+            path.display()
+        );
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        anyhow::ensure!(
+            version == SNAPSHOT_VERSION,
+            "snapshot {} has version {}, expected {}",
+            path.display(),
+            version,
+            SNAPSHOT_VERSION
+        );
+
+        bincode::deserialize_from(reader).context("deserializing snapshot")
+    }
+
+    /// Feed the snapshot contents into a ChainData instance.
+    ///
+    /// Only `rooted_slot` is registered as rooted. Newer account writes were on the best
+    /// chain when the snapshot was taken, so their slots are registered as processed and
+    /// linked on top of it: they stay live until the live chain forks away from them.
+    pub fn apply_to(self, chain: &mut ChainData) {
+        chain.update_slot(SlotData {
+            slot: self.rooted_slot,
+            parent: None,
+            status: SlotStatus::Rooted,
+            chain: 0,
+        });
+        let mut unrooted_slots = self
+            .accounts
+            .iter()
+            .map(|a| a.slot)
+            .chain(std::iter::once(self.slot))
+            .filter(|slot| *slot > self.rooted_slot)
+            .collect::<Vec<_>>();
+        unrooted_slots.sort_unstable();
+        unrooted_slots.dedup();
+        let mut parent = self.rooted_slot;
+        for slot in unrooted_slots {
+            chain.update_slot(SlotData {
+                slot,
+                parent: Some(parent),
+                status: SlotStatus::Processed,
+                chain: 0,
+            });
+            parent = slot;
+        }
+        for a in self.accounts {
+            chain.update_account(
+                a.pubkey,
+                AccountData {
+                    slot: a.slot,
+                    account: a.account.into(),
+                    write_version: a.write_version,
+                },
+            );
+        }
+    }
+}
+
+/// Persisting ChainData to disk, for quick restarts and replaying recorded state.
+pub trait ChainDataSnapshot: Sized {
+    fn save_to(&self, path: &Path) -> anyhow::Result<()>;
+    fn load_from(path: &Path) -> anyhow::Result<Self>;
+}
+
+impl ChainDataSnapshot for ChainData {
+    /// Stores the newest live write of every account.
+    fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        let snapshot = SnapshotFile {
+            slot: self.best_chain_slot(),
+            rooted_slot: self.newest_rooted_slot(),
+            accounts: self
+                .iter_accounts()
+                .map(|(pubkey, data)| SnapshotAccount {
+                    pubkey: *pubkey,
+                    slot: data.slot,
+                    write_version: data.write_version,
+                    account: data.account.clone().into(),
+                })
+                .collect(),
+        };
+        snapshot.write(path)
+    }
+
+    fn load_from(path: &Path) -> anyhow::Result<Self> {
+        let mut chain = ChainData::new();
+        SnapshotFile::read(path)?.apply_to(&mut chain);
+        Ok(chain)
+    }
+}
+
+/// Regularly reports the size of the chain data and how recently it saw a new slot
+pub fn start_metrics(chain: Arc<RwLock<ChainData>>, metrics: &mango_v4_metrics::Metrics) {
+    let mut interval = tokio::time::interval(Duration::from_secs(5));
+
+    let metric_slots_count =
+        metrics.register_u64("chain_data_slots_count", "Slots tracked by the chain data");
+    let metric_accounts_count =
+        metrics.register_u64("chain_data_accounts_count", "Accounts in the chain data");
+    let metric_account_write_count = metrics.register_u64(
+        "chain_data_account_write_count",
+        "Account writes stored in the chain data",
+    );
+    let metric_best_slot = metrics.register_u64(
+        "chain_data_best_slot",
+        "Newest slot on the best chain of the chain data",
+    );
+    let metric_seconds_since_new_slot = metrics.register_u64(
+        "chain_data_seconds_since_new_slot",
+        "Time since the best slot of the chain data last advanced",
+    );
+
+    tokio::spawn(async move {
+        let mut best_slot = 0;
+        let mut best_slot_seen_at = Instant::now();
+        loop {
+            interval.tick().await;
+            let chain_lock = chain.read().unwrap();
+            metric_slots_count.set(chain_lock.slots_count() as u64);
+            metric_accounts_count.set(chain_lock.accounts_count() as u64);
+            metric_account_write_count.set(chain_lock.account_writes_count() as u64);
+
+            let slot = chain_lock.best_chain_slot();
+            if slot != best_slot {
+                best_slot = slot;
+                best_slot_seen_at = Instant::now();
+            }
+            metric_best_slot.set(slot);
+            metric_seconds_since_new_slot.set(best_slot_seen_at.elapsed().as_secs());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::account::{AccountSharedData, ReadableAccount};
+
+    fn account(lamports: u64, data: Vec<u8>) -> AccountSharedData {
+        Account {
+            lamports,
+            data,
+            owner: mango_v4::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() -> anyhow::Result<()> {
+        let mut chain = ChainData::new();
+        let slot = |slot, parent, status| SlotData {
+            slot,
+            parent,
+            status,
+            chain: 0,
+        };
+        chain.update_slot(slot(5, None, SlotStatus::Rooted));
+        chain.update_slot(slot(6, Some(5), SlotStatus::Processed));
+        chain.update_slot(slot(7, Some(6), SlotStatus::Processed));
+
+        let rooted = Pubkey::new_unique();
+        let unrooted = Pubkey::new_unique();
+        let rewritten = Pubkey::new_unique();
+        let writes = [
+            (rooted, 4, 3, account(1, vec![1])),
+            (unrooted, 7, 9, account(2, vec![2, 2])),
+            (rewritten, 6, 1, account(3, vec![3])),
+            (rewritten, 7, 2, account(4, vec![4, 4, 4])),
+        ];
+        for (pubkey, slot, write_version, account) in writes.iter().cloned() {
+            chain.update_account(
+                pubkey,
+                AccountData {
+                    slot,
+                    write_version,
+                    account,
+                },
+            );
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("chain-data-{}.snapshot", Pubkey::new_unique()));
+        chain.save_to(&path)?;
+        let loaded = ChainData::load_from(&path);
+        std::fs::remove_file(&path)?;
+        let loaded = loaded?;
+
+        assert_eq!(loaded.best_chain_slot(), 7);
+        // only the rooted slot of the original is rooted after loading
+        assert_eq!(loaded.newest_rooted_slot(), 5);
+        assert_eq!(loaded.accounts_count(), 3);
+        for pubkey in [rooted, unrooted, rewritten] {
+            let expected = chain.account(&pubkey)?;
+            let actual = loaded.account(&pubkey)?;
+            assert_eq!(actual.slot, expected.slot);
+            assert_eq!(actual.write_version, expected.write_version);
+            assert_eq!(actual.account.lamports(), expected.account.lamports());
+            assert_eq!(actual.account.data(), expected.account.data());
+        }
+        let rewritten_data = loaded.account(&rewritten)?;
+        assert_eq!((rewritten_data.slot, rewritten_data.write_version), (7, 2));
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use anchor_lang::__private::bytemuck;
 use anchor_lang::prelude::System;
 use anchor_lang::{AccountDeserialize, Id};
 use anchor_spl::associated_token::get_associated_token_address;
-use anchor_spl::token::Token;
+use anchor_spl::token::{Token, TokenAccount};
 
 use fixed::types::I80F48;
 use futures::{stream, StreamExt, TryStreamExt};
@@ -17,9 +17,16 @@ use itertools::Itertools;
 
 use mango_v4::accounts_ix::{Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side};
 use mango_v4::accounts_zerocopy::KeyedAccountSharedData;
+use mango_v4::health::{HealthScenarioAction, HealthScenarioResult};
 use mango_v4::state::{
-    Bank, Group, MangoAccountValue, PerpMarketIndex, PlaceOrderType, SelfTradeBehavior,
-    Serum3MarketIndex, Side, TokenIndex, INSURANCE_TOKEN_INDEX,
+    Bank, Group, MangoAccountValue, OpenbookV2MarketIndex, PerpMarketIndex, PlaceOrderType,
+    SelfTradeBehavior, Serum3MarketIndex, Side, TokenConditionalSwapDisplayPriceStyle,
+    TokenConditionalSwapIntention, TokenIndex, INSURANCE_TOKEN_INDEX, QUOTE_TOKEN_INDEX,
+};
+
+use openbook_v2::state::{
+    PlaceOrderType as OpenbookV2PlaceOrderType, SelfTradeBehavior as OpenbookV2SelfTradeBehavior,
+    Side as OpenbookV2Side,
 };
 
 use solana_address_lookup_table_program::state::AddressLookupTable;
@@ -179,45 +186,152 @@ impl MangoClient {
         Ok(mango_account_tuples[index].0)
     }
 
-    pub async fn create_account(
-        client: &Client,
-        group: Pubkey,
-        owner: Arc<Keypair>,
-        payer: Arc<Keypair>, // pays the SOL for the new account
-        account_num: u32,
-        mango_account_name: &str,
-    ) -> anyhow::Result<(Pubkey, Signature)> {
-        let account = Pubkey::find_program_address(
+    pub fn mango_account_address(group: Pubkey, owner: Pubkey, account_num: u32) -> Pubkey {
+        Pubkey::find_program_address(
             &[
                 b"MangoAccount".as_ref(),
                 group.as_ref(),
-                owner.pubkey().as_ref(),
+                owner.as_ref(),
                 &account_num.to_le_bytes(),
             ],
             &mango_v4::id(),
         )
-        .0;
-        let ix = Instruction {
+        .0
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn account_create_instruction(
+        group: Pubkey,
+        owner: Pubkey,
+        payer: Pubkey,
+        account_num: u32,
+        name: String,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+    ) -> Instruction {
+        Instruction {
             program_id: mango_v4::id(),
             accounts: anchor_lang::ToAccountMetas::to_account_metas(
                 &mango_v4::accounts::AccountCreate {
                     group,
-                    owner: owner.pubkey(),
-                    account,
-                    payer: payer.pubkey(),
+                    owner,
+                    account: Self::mango_account_address(group, owner, account_num),
+                    payer,
                     system_program: System::id(),
                 },
                 None,
             ),
             data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountCreate {
                 account_num,
-                name: mango_account_name.to_owned(),
-                token_count: 8,
-                serum3_count: 4,
-                perp_count: 4,
-                perp_oo_count: 8,
+                name,
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
             }),
-        };
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn account_create_v2_instruction(
+        group: Pubkey,
+        owner: Pubkey,
+        payer: Pubkey,
+        account_num: u32,
+        name: String,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountCreateV2 {
+                    group,
+                    owner,
+                    account: Self::mango_account_address(group, owner, account_num),
+                    payer,
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountCreateV2 {
+                account_num,
+                name,
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
+                token_conditional_swap_count,
+            }),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn account_create_v3_instruction(
+        group: Pubkey,
+        owner: Pubkey,
+        payer: Pubkey,
+        account_num: u32,
+        name: String,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_conditional_order_count: u8,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountCreateV3 {
+                    group,
+                    owner,
+                    account: Self::mango_account_address(group, owner, account_num),
+                    payer,
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountCreateV3 {
+                account_num,
+                name,
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
+                token_conditional_swap_count,
+                openbook_v2_count,
+                perp_conditional_order_count,
+            }),
+        }
+    }
+
+    pub async fn create_account(
+        client: &Client,
+        group: Pubkey,
+        owner: Arc<Keypair>,
+        payer: Arc<Keypair>, // pays the SOL for the new account
+        account_num: u32,
+        mango_account_name: &str,
+    ) -> anyhow::Result<(Pubkey, Signature)> {
+        let account = Self::mango_account_address(group, owner.pubkey(), account_num);
+        let ix = Self::account_create_instruction(
+            group,
+            owner.pubkey(),
+            payer.pubkey(),
+            account_num,
+            mango_account_name.to_owned(),
+            8,
+            4,
+            4,
+            8,
+        );
 
         let txsig = TransactionBuilder {
             instructions: vec![ix],
@@ -291,11 +405,92 @@ impl MangoClient {
             .await
     }
 
+    /// The token's first bank
+    ///
+    /// All banks of a token share indexes, oracle and configuration, so any of them can be
+    /// used to value positions or compute health. Only vault-specific values like deposits
+    /// and borrows differ, see banks(), deposit_bank_and_vault() and borrow_bank().
     pub async fn first_bank(&self, token_index: TokenIndex) -> anyhow::Result<Bank> {
         let bank_address = self.context.mint_info(token_index).first_bank();
         account_fetcher_fetch_anchor_account(&*self.account_fetcher, &bank_address).await
     }
 
+    /// All banks of the token, in bank_num order
+    pub async fn banks(&self, token_index: TokenIndex) -> anyhow::Result<Vec<(Pubkey, Bank)>> {
+        let mut banks = vec![];
+        for bank_address in self.context.mint_info(token_index).banks() {
+            let bank =
+                account_fetcher_fetch_anchor_account(&*self.account_fetcher, bank_address).await?;
+            banks.push((*bank_address, bank));
+        }
+        Ok(banks)
+    }
+
+    /// Token amounts held in the vaults of all banks of the token, as (bank, vault, amount)
+    pub async fn bank_vault_amounts(
+        &self,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<Vec<(Pubkey, Pubkey, u64)>> {
+        let mut amounts = vec![];
+        for (bank, vault) in self.context.mint_info(token_index).banks_and_vaults() {
+            let vault_account: TokenAccount =
+                account_fetcher_fetch_anchor_account(&*self.account_fetcher, &vault).await?;
+            amounts.push((bank, vault, vault_account.amount));
+        }
+        Ok(amounts)
+    }
+
+    /// The bank and vault that deposits of the token should go to.
+    ///
+    /// Spreads deposits over the token's banks by choosing the vault with the fewest tokens.
+    pub async fn deposit_bank_and_vault(
+        &self,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<(Pubkey, Pubkey)> {
+        let mint_info = self.context.mint_info(token_index);
+        if mint_info.num_banks() == 1 {
+            return Ok((mint_info.first_bank(), mint_info.first_vault()));
+        }
+        let amounts = self.bank_vault_amounts(token_index).await?;
+        let (bank, vault, _) = amounts
+            .into_iter()
+            .min_by_key(|(_, _, amount)| *amount)
+            .unwrap();
+        Ok((bank, vault))
+    }
+
+    /// The bank and vault that withdrawals of the token should come from.
+    ///
+    /// Chooses the vault with the most tokens, since a withdraw fails if the
+    /// vault of the used bank can't cover it.
+    pub async fn withdraw_bank_and_vault(
+        &self,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<(Pubkey, Pubkey)> {
+        let mint_info = self.context.mint_info(token_index);
+        if mint_info.num_banks() == 1 {
+            return Ok((mint_info.first_bank(), mint_info.first_vault()));
+        }
+        let amounts = self.bank_vault_amounts(token_index).await?;
+        let (bank, vault, _) = amounts
+            .into_iter()
+            .max_by_key(|(_, _, amount)| *amount)
+            .unwrap();
+        Ok((bank, vault))
+    }
+
+    /// The bank that borrows of the token without a vault transfer should use.
+    ///
+    /// Net borrow limits apply per bank, so this chooses the bank with the fewest
+    /// net borrows in its window.
+    pub async fn borrow_bank(&self, token_index: TokenIndex) -> anyhow::Result<(Pubkey, Bank)> {
+        let banks = self.banks(token_index).await?;
+        Ok(banks
+            .into_iter()
+            .min_by_key(|(_, bank)| bank.net_borrows_in_window)
+            .unwrap())
+    }
+
     pub async fn derive_health_check_remaining_account_metas(
         &self,
         affected_tokens: Vec<TokenIndex>,
@@ -327,6 +522,279 @@ impl MangoClient {
             )
     }
 
+    /// Accounts for the token's insurance fund, if it has one: the fund and its vault.
+    ///
+    /// Bankruptcy instructions expect them as the first remaining accounts.
+    pub async fn derive_token_insurance_fund_remaining_account_metas(
+        &self,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<Vec<AccountMeta>> {
+        let bank = self.first_bank(token_index).await?;
+        if !bank.has_token_insurance_fund() {
+            return Ok(vec![]);
+        }
+        let vault = Pubkey::find_program_address(
+            &[
+                b"TokenInsuranceFundVault".as_ref(),
+                self.group().as_ref(),
+                &token_index.to_le_bytes(),
+            ],
+            &mango_v4::id(),
+        )
+        .0;
+        Ok(vec![
+            util::to_writable_account_meta(bank.token_insurance_fund),
+            util::to_writable_account_meta(vault),
+        ])
+    }
+
+    //
+    // Account
+    //
+
+    pub fn account_edit_instruction(
+        &self,
+        name_opt: Option<String>,
+        delegate_opt: Option<Pubkey>,
+        temporary_delegate_opt: Option<Pubkey>,
+        temporary_delegate_expiry_opt: Option<u64>,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountEdit {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountEdit {
+                name_opt,
+                delegate_opt,
+                temporary_delegate_opt,
+                temporary_delegate_expiry_opt,
+            }),
+        }
+    }
+
+    pub async fn account_edit(
+        &self,
+        name_opt: Option<String>,
+        delegate_opt: Option<Pubkey>,
+        temporary_delegate_opt: Option<Pubkey>,
+        temporary_delegate_expiry_opt: Option<u64>,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.account_edit_instruction(
+            name_opt,
+            delegate_opt,
+            temporary_delegate_opt,
+            temporary_delegate_expiry_opt,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    fn account_expand_accounts(&self) -> Vec<AccountMeta> {
+        anchor_lang::ToAccountMetas::to_account_metas(
+            &mango_v4::accounts::AccountExpand {
+                group: self.group(),
+                account: self.mango_account_address,
+                owner: self.owner(),
+                payer: self.client.fee_payer.pubkey(),
+                system_program: System::id(),
+            },
+            None,
+        )
+    }
+
+    /// Grows the account to the given sizes, the fee payer pays for the extra space
+    pub fn account_expand_instruction(
+        &self,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: self.account_expand_accounts(),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountExpand {
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
+            }),
+        }
+    }
+
+    pub fn account_expand_v2_instruction(
+        &self,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: self.account_expand_accounts(),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountExpandV2 {
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
+                token_conditional_swap_count,
+            }),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn account_expand_v3_instruction(
+        &self,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_conditional_order_count: u8,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: self.account_expand_accounts(),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountExpandV3 {
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
+                token_conditional_swap_count,
+                openbook_v2_count,
+                perp_conditional_order_count,
+            }),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn account_expand(
+        &self,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_conditional_order_count: u8,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.account_expand_v3_instruction(
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            perp_conditional_order_count,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Closes the account, the rent goes to the owner
+    pub fn account_close_instruction(&self, force_close: bool) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountClose {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    sol_destination: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountClose {
+                force_close,
+            }),
+        }
+    }
+
+    pub async fn account_close(&self, force_close: bool) -> anyhow::Result<Signature> {
+        let ix = self.account_close_instruction(force_close);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn account_buyback_fees_with_mngo_instruction(
+        &self,
+        max_buyback_usd: u64,
+    ) -> anyhow::Result<Instruction> {
+        let group: Group =
+            account_fetcher_fetch_anchor_account(&*self.account_fetcher, &self.group()).await?;
+        let mngo = self.context.token(group.mngo_token_index);
+        let fees = self.context.token(QUOTE_TOKEN_INDEX);
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountBuybackFeesWithMngo {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    dao_account: group.buyback_fees_swap_mango_account,
+                    mngo_bank: mngo.mint_info.first_bank(),
+                    mngo_oracle: mngo.mint_info.oracle,
+                    fees_bank: fees.mint_info.first_bank(),
+                    fees_oracle: fees.mint_info.oracle,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::AccountBuybackFeesWithMngo { max_buyback_usd },
+            ),
+        })
+    }
+
+    pub async fn account_buyback_fees_with_mngo(
+        &self,
+        max_buyback_usd: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .account_buyback_fees_with_mngo_instruction(max_buyback_usd)
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Logs the account's health and balances, useful for simulation
+    pub fn compute_account_data_instruction(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+    ) -> anyhow::Result<Instruction> {
+        let health_remaining_ams = self.context.derive_health_check_remaining_account_metas(
+            account.1,
+            vec![],
+            vec![],
+            vec![],
+        )?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::ComputeAccountData {
+                        group: self.group(),
+                        account: *account.0,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_ams.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::ComputeAccountData {}),
+        })
+    }
+
+    //
+    // Tokens
+    //
+
     pub async fn token_deposit(
         &self,
         mint: Pubkey,
@@ -337,6 +805,7 @@ impl MangoClient {
         let token_index = token.token_index;
         let mint_info = token.mint_info;
 
+        let (bank, vault) = self.deposit_bank_and_vault(token_index).await?;
         let health_check_metas = self
             .derive_health_check_remaining_account_metas(vec![token_index], vec![], vec![])
             .await?;
@@ -349,8 +818,8 @@ impl MangoClient {
                         group: self.group(),
                         account: self.mango_account_address,
                         owner: self.owner(),
-                        bank: mint_info.first_bank(),
-                        vault: mint_info.first_vault(),
+                        bank,
+                        vault,
                         oracle: mint_info.oracle,
                         token_account: get_associated_token_address(&self.owner(), &mint_info.mint),
                         token_authority: self.owner(),
@@ -379,6 +848,7 @@ impl MangoClient {
         let token_index = token.token_index;
         let mint_info = token.mint_info;
 
+        let (bank, vault) = self.withdraw_bank_and_vault(token_index).await?;
         let health_check_metas = self
             .derive_health_check_remaining_account_metas(vec![token_index], vec![], vec![])
             .await?;
@@ -398,8 +868,8 @@ impl MangoClient {
                             group: self.group(),
                             account: self.mango_account_address,
                             owner: self.owner(),
-                            bank: mint_info.first_bank(),
-                            vault: mint_info.first_vault(),
+                            bank,
+                            vault,
                             oracle: mint_info.oracle,
                             token_account: get_associated_token_address(
                                 &self.owner(),
@@ -421,27 +891,277 @@ impl MangoClient {
         self.send_and_confirm_owner_tx(ixs).await
     }
 
-    pub async fn bank_oracle_price(&self, token_index: TokenIndex) -> anyhow::Result<I80F48> {
-        let bank = self.first_bank(token_index).await?;
-        let mint_info = self.context.mint_info(token_index);
-        let oracle = self
-            .account_fetcher
-            .fetch_raw_account(&mint_info.oracle)
-            .await?;
-        let price = bank.oracle_price(
-            &KeyedAccountSharedData::new(mint_info.oracle, oracle.into()),
-            None,
+    /// Deposits into an existing token position of any account, without a health check
+    pub async fn token_deposit_into_existing_instruction(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+        mint: Pubkey,
+        amount: u64,
+        reduce_only: bool,
+    ) -> anyhow::Result<Instruction> {
+        let token = self.context.token_by_mint(&mint)?;
+        let token_index = token.token_index;
+        let mint_info = token.mint_info;
+
+        let (bank, vault) = self.deposit_bank_and_vault(token_index).await?;
+        let health_check_metas = self.context.derive_health_check_remaining_account_metas(
+            account.1,
+            vec![],
+            vec![],
+            vec![],
         )?;
-        Ok(price)
-    }
 
-    pub async fn perp_oracle_price(
-        &self,
-        perp_market_index: PerpMarketIndex,
-    ) -> anyhow::Result<I80F48> {
-        let perp = self.context.perp(perp_market_index);
-        let oracle = self
-            .account_fetcher
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::TokenDepositIntoExisting {
+                        group: self.group(),
+                        account: *account.0,
+                        bank,
+                        vault,
+                        oracle: mint_info.oracle,
+                        token_account: get_associated_token_address(&self.owner(), &mint_info.mint),
+                        token_authority: self.owner(),
+                        token_program: Token::id(),
+                    },
+                    None,
+                );
+                ams.extend(health_check_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenDepositIntoExisting {
+                    amount,
+                    reduce_only,
+                },
+            ),
+        })
+    }
+
+    pub async fn token_deposit_into_existing(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+        mint: Pubkey,
+        amount: u64,
+        reduce_only: bool,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .token_deposit_into_existing_instruction(account, mint, amount, reduce_only)
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// The banks and vaults a flash loan of the given native amounts should use
+    ///
+    /// Loaned tokens come out of the vault with the most tokens, tokens that are
+    /// only received go to the vault with the fewest.
+    pub async fn flash_loan_banks_and_vaults(
+        &self,
+        loans: &[(TokenIndex, u64)],
+    ) -> anyhow::Result<Vec<(Pubkey, Pubkey)>> {
+        let mut banks_and_vaults = vec![];
+        for &(token_index, amount) in loans {
+            let bank_and_vault = if amount > 0 {
+                self.withdraw_bank_and_vault(token_index).await?
+            } else {
+                self.deposit_bank_and_vault(token_index).await?
+            };
+            banks_and_vaults.push(bank_and_vault);
+        }
+        Ok(banks_and_vaults)
+    }
+
+    /// Loans the given native amounts out of the given banks and vaults into the owner's
+    /// associated token accounts. Must be followed by flash_loan_end_instruction().
+    pub fn flash_loan_begin_instruction(
+        &self,
+        loans: &[(TokenIndex, u64)],
+        banks_and_vaults: &[(Pubkey, Pubkey)],
+    ) -> Instruction {
+        let mint_infos = loans
+            .iter()
+            .map(|(token_index, _)| self.context.mint_info(*token_index))
+            .collect_vec();
+
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::FlashLoanBegin {
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        token_program: Token::id(),
+                        instructions: solana_sdk::sysvar::instructions::id(),
+                    },
+                    None,
+                );
+                ams.extend(
+                    banks_and_vaults
+                        .iter()
+                        .map(|(bank, _)| util::to_writable_account_meta(*bank)),
+                );
+                ams.extend(
+                    banks_and_vaults
+                        .iter()
+                        .map(|(_, vault)| util::to_writable_account_meta(*vault)),
+                );
+                ams.extend(mint_infos.iter().map(|mi| {
+                    util::to_writable_account_meta(get_associated_token_address(
+                        &self.owner(),
+                        &mi.mint,
+                    ))
+                }));
+                ams.push(util::to_readonly_account_meta(self.group()));
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::FlashLoanBegin {
+                loan_amounts: loans.iter().map(|(_, amount)| *amount).collect(),
+            }),
+        }
+    }
+
+    /// Ends a flash loan started with flash_loan_begin_instruction() for the same tokens,
+    /// banks and vaults, using flash_loan_end_v2
+    pub async fn flash_loan_end_instruction(
+        &self,
+        loan_tokens: &[TokenIndex],
+        banks_and_vaults: &[(Pubkey, Pubkey)],
+        flash_loan_type: mango_v4::accounts_ix::FlashLoanType,
+    ) -> anyhow::Result<Instruction> {
+        let mint_infos = loan_tokens
+            .iter()
+            .map(|token_index| self.context.mint_info(*token_index))
+            .collect_vec();
+
+        // The health accounts must contain the banks used in the begin instruction
+        let mut health_ams = self
+            .derive_health_check_remaining_account_metas(
+                loan_tokens.to_vec(),
+                loan_tokens.to_vec(),
+                vec![],
+            )
+            .await?;
+        for (token_index, (bank, _)) in loan_tokens.iter().zip(banks_and_vaults.iter()) {
+            self.context
+                .use_bank_in_health_account_metas(&mut health_ams, *token_index, *bank);
+        }
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::FlashLoanEnd {
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        token_program: Token::id(),
+                    },
+                    None,
+                );
+                ams.extend(health_ams);
+                ams.extend(
+                    banks_and_vaults
+                        .iter()
+                        .map(|(_, vault)| util::to_writable_account_meta(*vault)),
+                );
+                ams.extend(mint_infos.iter().map(|mi| {
+                    util::to_writable_account_meta(get_associated_token_address(
+                        &self.owner(),
+                        &mi.mint,
+                    ))
+                }));
+                ams.push(util::to_readonly_account_meta(self.group()));
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::FlashLoanEndV2 {
+                num_loans: loan_tokens.len().try_into()?,
+                flash_loan_type,
+            }),
+        })
+    }
+
+    /// Wraps the instructions in a flash loan of the given tokens
+    pub async fn flash_loan(
+        &self,
+        loans: &[(TokenIndex, u64)],
+        instructions: Vec<Instruction>,
+    ) -> anyhow::Result<Signature> {
+        let loan_tokens = loans
+            .iter()
+            .map(|(token_index, _)| *token_index)
+            .collect_vec();
+        let banks_and_vaults = self.flash_loan_banks_and_vaults(loans).await?;
+        let mut ixs = vec![self.flash_loan_begin_instruction(loans, &banks_and_vaults)];
+        ixs.extend(instructions);
+        ixs.push(
+            self.flash_loan_end_instruction(
+                &loan_tokens,
+                &banks_and_vaults,
+                mango_v4::accounts_ix::FlashLoanType::Unknown,
+            )
+            .await?,
+        );
+        self.send_and_confirm_owner_tx(ixs).await
+    }
+
+    pub fn token_update_index_and_rate_instruction(&self, token_index: TokenIndex) -> Instruction {
+        let token = self.context.token(token_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::TokenUpdateIndexAndRate {
+                        group: self.group(),
+                        mint_info: token.mint_info_address,
+                        oracle: token.mint_info.oracle,
+                        instructions: solana_sdk::sysvar::instructions::id(),
+                    },
+                    None,
+                );
+                ams.extend(
+                    token
+                        .mint_info
+                        .banks()
+                        .iter()
+                        .map(|bank| util::to_writable_account_meta(*bank)),
+                );
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenUpdateIndexAndRate {},
+            ),
+        }
+    }
+
+    pub async fn token_update_index_and_rate(
+        &self,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_update_index_and_rate_instruction(token_index);
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    pub async fn bank_oracle_price(&self, token_index: TokenIndex) -> anyhow::Result<I80F48> {
+        let bank = self.first_bank(token_index).await?;
+        let mint_info = self.context.mint_info(token_index);
+        let oracle = self
+            .account_fetcher
+            .fetch_raw_account(&mint_info.oracle)
+            .await?;
+        let price = bank.oracle_price(
+            &KeyedAccountSharedData::new(mint_info.oracle, oracle.into()),
+            None,
+        )?;
+        Ok(price)
+    }
+
+    pub async fn perp_oracle_price(
+        &self,
+        perp_market_index: PerpMarketIndex,
+    ) -> anyhow::Result<I80F48> {
+        let perp = self.context.perp(perp_market_index);
+        let oracle = self
+            .account_fetcher
             .fetch_raw_account(&perp.market.oracle)
             .await?;
         let price = perp.market.oracle_price(
@@ -451,6 +1171,16 @@ impl MangoClient {
         Ok(price)
     }
 
+    /// Health of the account after the hypothetical actions, see HealthCache::scenario()
+    pub async fn health_scenario(
+        &self,
+        actions: &[HealthScenarioAction],
+    ) -> anyhow::Result<HealthScenarioResult> {
+        let account = self.mango_account().await?;
+        crate::health_cache::scenario(&self.context, &*self.account_fetcher, &account, actions)
+            .await
+    }
+
     //
     // Serum3
     //
@@ -502,7 +1232,7 @@ impl MangoClient {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn serum3_place_order_instruction(
+    pub async fn serum3_place_order_instruction(
         &self,
         account: &MangoAccountValue,
         market_index: Serum3MarketIndex,
@@ -530,10 +1260,12 @@ impl MangoClient {
             vec![],
         )?;
 
-        let payer_mint_info = match side {
-            Serum3Side::Bid => quote.mint_info,
-            Serum3Side::Ask => base.mint_info,
+        let payer_token_index = match side {
+            Serum3Side::Bid => quote.token_index,
+            Serum3Side::Ask => base.token_index,
         };
+        let payer_mint_info = self.context.mint_info(payer_token_index);
+        let (payer_bank, payer_vault) = self.withdraw_bank_and_vault(payer_token_index).await?;
 
         let ix = Instruction {
             program_id: mango_v4::id(),
@@ -543,8 +1275,8 @@ impl MangoClient {
                         group: self.group(),
                         account: self.mango_account_address,
                         open_orders,
-                        payer_bank: payer_mint_info.first_bank(),
-                        payer_vault: payer_mint_info.first_vault(),
+                        payer_bank,
+                        payer_vault,
                         payer_oracle: payer_mint_info.oracle,
                         serum_market: s3.address,
                         serum_program: s3.market.serum_program,
@@ -594,31 +1326,39 @@ impl MangoClient {
     ) -> anyhow::Result<Signature> {
         let account = self.mango_account().await?;
         let market_index = self.context.serum3_market_index(name);
-        let ix = self.serum3_place_order_instruction(
-            &account,
-            market_index,
-            side,
-            limit_price,
-            max_base_qty,
-            max_native_quote_qty_including_fees,
-            self_trade_behavior,
-            order_type,
-            client_order_id,
-            limit,
-        )?;
+        let ix = self
+            .serum3_place_order_instruction(
+                &account,
+                market_index,
+                side,
+                limit_price,
+                max_base_qty,
+                max_native_quote_qty_including_fees,
+                self_trade_behavior,
+                order_type,
+                client_order_id,
+                limit,
+            )
+            .await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
-    pub async fn serum3_settle_funds(&self, name: &str) -> anyhow::Result<Signature> {
-        let market_index = self.context.serum3_market_index(name);
+    /// Settles funds with serum3_settle_funds_v2, which also updates the oracle-based
+    /// tracking of the open orders
+    pub async fn serum3_settle_funds_v2_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: Serum3MarketIndex,
+        fees_to_dao: bool,
+    ) -> anyhow::Result<Instruction> {
         let s3 = self.context.serum3(market_index);
         let base = self.context.serum3_base_token(market_index);
         let quote = self.context.serum3_quote_token(market_index);
+        let open_orders = account.serum3_orders(market_index)?.open_orders;
+        let (quote_bank, quote_vault) = self.deposit_bank_and_vault(quote.token_index).await?;
+        let (base_bank, base_vault) = self.deposit_bank_and_vault(base.token_index).await?;
 
-        let account = self.mango_account().await?;
-        let open_orders = account.serum3_orders(market_index).unwrap().open_orders;
-
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: anchor_lang::ToAccountMetas::to_account_metas(
                 &mango_v4::accounts::Serum3SettleFundsV2 {
@@ -626,10 +1366,10 @@ impl MangoClient {
                         group: self.group(),
                         account: self.mango_account_address,
                         open_orders,
-                        quote_bank: quote.mint_info.first_bank(),
-                        quote_vault: quote.mint_info.first_vault(),
-                        base_bank: base.mint_info.first_bank(),
-                        base_vault: base.mint_info.first_vault(),
+                        quote_bank,
+                        quote_vault,
+                        base_bank,
+                        base_vault,
                         serum_market: s3.address,
                         serum_program: s3.market.serum_program,
                         serum_market_external: s3.market.serum_market_external,
@@ -647,9 +1387,17 @@ impl MangoClient {
                 None,
             ),
             data: anchor_lang::InstructionData::data(&mango_v4::instruction::Serum3SettleFundsV2 {
-                fees_to_dao: true,
+                fees_to_dao,
             }),
-        };
+        })
+    }
+
+    pub async fn serum3_settle_funds(&self, name: &str) -> anyhow::Result<Signature> {
+        let market_index = self.context.serum3_market_index(name);
+        let account = self.mango_account().await?;
+        let ix = self
+            .serum3_settle_funds_v2_instruction(&account, market_index, true)
+            .await?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
@@ -732,6 +1480,8 @@ impl MangoClient {
             .context
             .derive_health_check_remaining_account_metas(liqee.1, vec![], vec![], vec![])
             .unwrap();
+        let (quote_bank, quote_vault) = self.deposit_bank_and_vault(quote.token_index).await?;
+        let (base_bank, base_vault) = self.deposit_bank_and_vault(base.token_index).await?;
 
         let ix = Instruction {
             program_id: mango_v4::id(),
@@ -750,10 +1500,10 @@ impl MangoClient {
                         market_base_vault: s3.coin_vault,
                         market_quote_vault: s3.pc_vault,
                         market_vault_signer: s3.vault_signer,
-                        quote_bank: quote.mint_info.first_bank(),
-                        quote_vault: quote.mint_info.first_vault(),
-                        base_bank: base.mint_info.first_bank(),
-                        base_vault: base.mint_info.first_vault(),
+                        quote_bank,
+                        quote_vault,
+                        base_bank,
+                        base_vault,
                         token_program: Token::id(),
                     },
                     None,
@@ -807,318 +1557,1860 @@ impl MangoClient {
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
+    pub fn serum3_close_open_orders_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: Serum3MarketIndex,
+    ) -> anyhow::Result<Instruction> {
+        let s3 = self.context.serum3(market_index);
+        let open_orders = account.serum3_orders(market_index)?.open_orders;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::Serum3CloseOpenOrders {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    serum_market: s3.address,
+                    serum_program: s3.market.serum_program,
+                    serum_market_external: s3.market.serum_market_external,
+                    open_orders,
+                    sol_destination: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::Serum3CloseOpenOrders {},
+            ),
+        })
+    }
+
+    pub async fn serum3_close_open_orders(&self, name: &str) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let market_index = self.context.serum3_market_index(name);
+        let ix = self.serum3_close_open_orders_instruction(&account, market_index)?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
     //
-    // Perps
+    // Openbook v2
     //
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn perp_place_order_instruction(
+    pub fn openbook_v2_create_open_orders_instruction(
         &self,
-        account: &MangoAccountValue,
-        market_index: PerpMarketIndex,
-        side: Side,
-        price_lots: i64,
-        max_base_lots: i64,
-        max_quote_lots: i64,
-        client_order_id: u64,
-        order_type: PlaceOrderType,
-        reduce_only: bool,
-        expiry_timestamp: u64,
-        limit: u8,
-        self_trade_behavior: SelfTradeBehavior,
-    ) -> anyhow::Result<Instruction> {
-        let perp = self.context.perp(market_index);
-        let health_remaining_metas = self.context.derive_health_check_remaining_account_metas(
-            account,
-            vec![],
-            vec![],
-            vec![market_index],
-        )?;
+        market_index: OpenbookV2MarketIndex,
+        account_num: u32,
+    ) -> Instruction {
+        let ob = self.context.openbook_v2(market_index);
 
-        let ix = Instruction {
+        let open_orders = Pubkey::find_program_address(
+            &[
+                b"OpenOrders".as_ref(),
+                ob.address.as_ref(),
+                ob.market.openbook_v2_market_external.as_ref(),
+                &account_num.to_le_bytes(),
+            ],
+            &ob.market.openbook_v2_program,
+        )
+        .0;
+
+        Instruction {
             program_id: mango_v4::id(),
-            accounts: {
-                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpPlaceOrder {
-                        group: self.group(),
-                        account: self.mango_account_address,
-                        owner: self.owner(),
-                        perp_market: perp.address,
-                        bids: perp.market.bids,
-                        asks: perp.market.asks,
-                        event_queue: perp.market.event_queue,
-                        oracle: perp.market.oracle,
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::OpenbookV2CreateOpenOrders {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    openbook_v2_market: ob.address,
+                    openbook_v2_program: ob.market.openbook_v2_program,
+                    openbook_v2_market_external: ob.market.openbook_v2_market_external,
+                    open_orders,
+                    payer: self.owner(),
+                    system_program: System::id(),
+                    rent: sysvar::rent::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2CreateOpenOrders { account_num },
+            ),
+        }
+    }
+
+    pub async fn openbook_v2_create_open_orders(
+        &self,
+        name: &str,
+        account_num: u32,
+    ) -> anyhow::Result<Signature> {
+        let market_index = self.context.openbook_v2_market_index(name);
+        let ix = self.openbook_v2_create_open_orders_instruction(market_index, account_num);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn openbook_v2_close_open_orders_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: OpenbookV2MarketIndex,
+    ) -> anyhow::Result<Instruction> {
+        let ob = self.context.openbook_v2(market_index);
+        let open_orders = account.openbook_v2_orders(market_index)?.open_orders;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::OpenbookV2CloseOpenOrders {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    openbook_v2_market: ob.address,
+                    openbook_v2_program: ob.market.openbook_v2_program,
+                    openbook_v2_market_external: ob.market.openbook_v2_market_external,
+                    open_orders,
+                    sol_destination: self.owner(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2CloseOpenOrders {},
+            ),
+        })
+    }
+
+    pub async fn openbook_v2_close_open_orders(&self, name: &str) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let market_index = self.context.openbook_v2_market_index(name);
+        let ix = self.openbook_v2_close_open_orders_instruction(&account, market_index)?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn openbook_v2_place_order_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: OpenbookV2MarketIndex,
+        side: OpenbookV2Side,
+        limit_price: u64,
+        max_base_qty: u64,
+        max_native_quote_qty_including_fees: u64,
+        self_trade_behavior: OpenbookV2SelfTradeBehavior,
+        order_type: OpenbookV2PlaceOrderType,
+        client_order_id: u64,
+        limit: u16,
+    ) -> anyhow::Result<Instruction> {
+        let ob = self.context.openbook_v2(market_index);
+        let base = self.context.openbook_v2_base_token(market_index);
+        let quote = self.context.openbook_v2_quote_token(market_index);
+        let open_orders = account.openbook_v2_orders(market_index)?.open_orders;
+
+        let health_check_metas = self.context.derive_health_check_remaining_account_metas(
+            account,
+            vec![],
+            vec![],
+            vec![],
+        )?;
+
+        let payer_token_index = match side {
+            OpenbookV2Side::Bid => quote.token_index,
+            OpenbookV2Side::Ask => base.token_index,
+        };
+        let payer_mint_info = self.context.mint_info(payer_token_index);
+        let (payer_bank, payer_vault) = self.withdraw_bank_and_vault(payer_token_index).await?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::OpenbookV2PlaceOrder {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        authority: self.owner(),
+                        open_orders,
+                        openbook_v2_market: ob.address,
+                        openbook_v2_program: ob.market.openbook_v2_program,
+                        openbook_v2_market_external: ob.market.openbook_v2_market_external,
+                        bids: ob.bids,
+                        asks: ob.asks,
+                        event_queue: ob.event_queue,
+                        market_base_vault: ob.market_base_vault,
+                        market_quote_vault: ob.market_quote_vault,
+                        market_vault_signer: ob.market_authority,
+                        payer_bank,
+                        payer_vault,
+                        payer_oracle: payer_mint_info.oracle,
+                        token_program: Token::id(),
                     },
                     None,
                 );
-                ams.extend(health_remaining_metas.into_iter());
+                ams.extend(health_check_metas.into_iter());
                 ams
             },
-            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpPlaceOrderV2 {
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2PlaceOrder {
+                    side: side as u8,
+                    limit_price,
+                    max_base_qty,
+                    max_native_quote_qty_including_fees,
+                    self_trade_behavior: self_trade_behavior as u8,
+                    order_type: order_type as u8,
+                    client_order_id,
+                    limit,
+                },
+            ),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn openbook_v2_place_order(
+        &self,
+        name: &str,
+        side: OpenbookV2Side,
+        limit_price: u64,
+        max_base_qty: u64,
+        max_native_quote_qty_including_fees: u64,
+        self_trade_behavior: OpenbookV2SelfTradeBehavior,
+        order_type: OpenbookV2PlaceOrderType,
+        client_order_id: u64,
+        limit: u16,
+    ) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let market_index = self.context.openbook_v2_market_index(name);
+        let ix = self
+            .openbook_v2_place_order_instruction(
+                &account,
+                market_index,
                 side,
-                price_lots,
-                max_base_lots,
-                max_quote_lots,
-                client_order_id,
+                limit_price,
+                max_base_qty,
+                max_native_quote_qty_including_fees,
+                self_trade_behavior,
                 order_type,
-                reduce_only,
-                expiry_timestamp,
+                client_order_id,
                 limit,
-                self_trade_behavior,
-            }),
+            )
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn openbook_v2_place_taker_order_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: OpenbookV2MarketIndex,
+        side: OpenbookV2Side,
+        limit_price: u64,
+        max_base_qty: u64,
+        max_native_quote_qty_including_fees: u64,
+        self_trade_behavior: OpenbookV2SelfTradeBehavior,
+        client_order_id: u64,
+        limit: u16,
+    ) -> anyhow::Result<Instruction> {
+        let ob = self.context.openbook_v2(market_index);
+        let base = self.context.openbook_v2_base_token(market_index);
+        let quote = self.context.openbook_v2_quote_token(market_index);
+
+        let (payer, receiver) = match side {
+            OpenbookV2Side::Bid => (quote, base),
+            OpenbookV2Side::Ask => (base, quote),
         };
 
-        Ok(ix)
+        // The instruction creates both token positions before the health check
+        let health_check_metas = self.context.derive_health_check_remaining_account_metas(
+            account,
+            vec![payer.token_index, receiver.token_index],
+            vec![],
+            vec![],
+        )?;
+        let (payer_bank, payer_vault) = self.withdraw_bank_and_vault(payer.token_index).await?;
+        let (receiver_bank, receiver_vault) =
+            self.deposit_bank_and_vault(receiver.token_index).await?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::OpenbookV2PlaceTakeOrder {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        authority: self.owner(),
+                        openbook_v2_market: ob.address,
+                        openbook_v2_program: ob.market.openbook_v2_program,
+                        openbook_v2_market_external: ob.market.openbook_v2_market_external,
+                        bids: ob.bids,
+                        asks: ob.asks,
+                        event_queue: ob.event_queue,
+                        market_base_vault: ob.market_base_vault,
+                        market_quote_vault: ob.market_quote_vault,
+                        market_vault_signer: ob.market_authority,
+                        payer_bank,
+                        payer_vault,
+                        payer_oracle: payer.mint_info.oracle,
+                        receiver_bank,
+                        receiver_vault,
+                        token_program: Token::id(),
+                        system_program: System::id(),
+                    },
+                    None,
+                );
+                ams.extend(health_check_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2PlaceTakerOrder {
+                    side: side as u8,
+                    limit_price,
+                    max_base_qty,
+                    max_native_quote_qty_including_fees,
+                    self_trade_behavior: self_trade_behavior as u8,
+                    client_order_id,
+                    limit,
+                },
+            ),
+        })
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub async fn perp_place_order(
+    pub async fn openbook_v2_place_taker_order(
         &self,
-        market_index: PerpMarketIndex,
-        side: Side,
-        price_lots: i64,
-        max_base_lots: i64,
-        max_quote_lots: i64,
+        name: &str,
+        side: OpenbookV2Side,
+        limit_price: u64,
+        max_base_qty: u64,
+        max_native_quote_qty_including_fees: u64,
+        self_trade_behavior: OpenbookV2SelfTradeBehavior,
         client_order_id: u64,
-        order_type: PlaceOrderType,
-        reduce_only: bool,
-        expiry_timestamp: u64,
+        limit: u16,
+    ) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let market_index = self.context.openbook_v2_market_index(name);
+        let ix = self
+            .openbook_v2_place_taker_order_instruction(
+                &account,
+                market_index,
+                side,
+                limit_price,
+                max_base_qty,
+                max_native_quote_qty_including_fees,
+                self_trade_behavior,
+                client_order_id,
+                limit,
+            )
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    fn openbook_v2_cancel_order_accounts(
+        &self,
+        account: &MangoAccountValue,
+        market_index: OpenbookV2MarketIndex,
+    ) -> anyhow::Result<mango_v4::accounts::OpenbookV2CancelOrder> {
+        let ob = self.context.openbook_v2(market_index);
+        Ok(mango_v4::accounts::OpenbookV2CancelOrder {
+            group: self.group(),
+            account: self.mango_account_address,
+            authority: self.owner(),
+            open_orders: account.openbook_v2_orders(market_index)?.open_orders,
+            openbook_v2_market: ob.address,
+            openbook_v2_program: ob.market.openbook_v2_program,
+            openbook_v2_market_external: ob.market.openbook_v2_market_external,
+            bids: ob.bids,
+            asks: ob.asks,
+        })
+    }
+
+    pub fn openbook_v2_cancel_order_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: OpenbookV2MarketIndex,
+        side: OpenbookV2Side,
+        order_id: u128,
+    ) -> anyhow::Result<Instruction> {
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &self.openbook_v2_cancel_order_accounts(account, market_index)?,
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2CancelOrder {
+                    side: side as u8,
+                    order_id,
+                },
+            ),
+        })
+    }
+
+    pub async fn openbook_v2_cancel_order(
+        &self,
+        name: &str,
+        side: OpenbookV2Side,
+        order_id: u128,
+    ) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let market_index = self.context.openbook_v2_market_index(name);
+        let ix =
+            self.openbook_v2_cancel_order_instruction(&account, market_index, side, order_id)?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn openbook_v2_cancel_all_orders_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: OpenbookV2MarketIndex,
+        limit: u8,
+    ) -> anyhow::Result<Instruction> {
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &self.openbook_v2_cancel_order_accounts(account, market_index)?,
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2CancelAllOrders { limit },
+            ),
+        })
+    }
+
+    pub async fn openbook_v2_cancel_all_orders(
+        &self,
+        name: &str,
         limit: u8,
-        self_trade_behavior: SelfTradeBehavior,
     ) -> anyhow::Result<Signature> {
         let account = self.mango_account().await?;
-        let ix = self.perp_place_order_instruction(
-            &account,
-            market_index,
-            side,
-            price_lots,
-            max_base_lots,
-            max_quote_lots,
-            client_order_id,
-            order_type,
-            reduce_only,
-            expiry_timestamp,
-            limit,
-            self_trade_behavior,
-        )?;
+        let market_index = self.context.openbook_v2_market_index(name);
+        let ix = self.openbook_v2_cancel_all_orders_instruction(&account, market_index, limit)?;
         self.send_and_confirm_owner_tx(vec![ix]).await
     }
 
-    pub fn perp_cancel_all_orders_instruction(
+    pub async fn openbook_v2_settle_funds_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: OpenbookV2MarketIndex,
+        fees_to_dao: bool,
+    ) -> anyhow::Result<Instruction> {
+        let ob = self.context.openbook_v2(market_index);
+        let base = self.context.openbook_v2_base_token(market_index);
+        let quote = self.context.openbook_v2_quote_token(market_index);
+        let open_orders = account.openbook_v2_orders(market_index)?.open_orders;
+        let (quote_bank, quote_vault) = self.deposit_bank_and_vault(quote.token_index).await?;
+        let (base_bank, base_vault) = self.deposit_bank_and_vault(base.token_index).await?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::OpenbookV2SettleFunds {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    open_orders,
+                    openbook_v2_market: ob.address,
+                    openbook_v2_program: ob.market.openbook_v2_program,
+                    openbook_v2_market_external: ob.market.openbook_v2_market_external,
+                    market_base_vault: ob.market_base_vault,
+                    market_quote_vault: ob.market_quote_vault,
+                    market_vault_signer: ob.market_authority,
+                    quote_bank,
+                    quote_vault,
+                    base_bank,
+                    base_vault,
+                    quote_oracle: quote.mint_info.oracle,
+                    base_oracle: base.mint_info.oracle,
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2SettleFunds { fees_to_dao },
+            ),
+        })
+    }
+
+    pub async fn openbook_v2_settle_funds(&self, name: &str) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let market_index = self.context.openbook_v2_market_index(name);
+        let ix = self
+            .openbook_v2_settle_funds_instruction(&account, market_index, true)
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn openbook_v2_liq_force_cancel_orders_instruction(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: OpenbookV2MarketIndex,
+        limit: u8,
+    ) -> anyhow::Result<Instruction> {
+        let ob = self.context.openbook_v2(market_index);
+        let base = self.context.openbook_v2_base_token(market_index);
+        let quote = self.context.openbook_v2_quote_token(market_index);
+        let open_orders = liqee.1.openbook_v2_orders(market_index)?.open_orders;
+
+        let health_remaining_ams = self.context.derive_health_check_remaining_account_metas(
+            liqee.1,
+            vec![],
+            vec![],
+            vec![],
+        )?;
+        let (quote_bank, quote_vault) = self.deposit_bank_and_vault(quote.token_index).await?;
+        let (base_bank, base_vault) = self.deposit_bank_and_vault(base.token_index).await?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::OpenbookV2LiqForceCancelOrders {
+                        group: self.group(),
+                        account: *liqee.0,
+                        open_orders,
+                        openbook_v2_market: ob.address,
+                        openbook_v2_program: ob.market.openbook_v2_program,
+                        openbook_v2_market_external: ob.market.openbook_v2_market_external,
+                        bids: ob.bids,
+                        asks: ob.asks,
+                        event_queue: ob.event_queue,
+                        market_base_vault: ob.market_base_vault,
+                        market_quote_vault: ob.market_quote_vault,
+                        market_vault_signer: ob.market_authority,
+                        quote_bank,
+                        quote_vault,
+                        base_bank,
+                        base_vault,
+                        token_program: Token::id(),
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_ams.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::OpenbookV2LiqForceCancelOrders { limit },
+            ),
+        })
+    }
+
+    pub async fn openbook_v2_liq_force_cancel_orders(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: OpenbookV2MarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .openbook_v2_liq_force_cancel_orders_instruction(liqee, market_index, 5)
+            .await?;
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    //
+    // Perps
+    //
+
+    /// Places an order with perp_place_order_v2
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_place_order_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: PerpMarketIndex,
+        side: Side,
+        price_lots: i64,
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        reduce_only: bool,
+        expiry_timestamp: u64,
+        limit: u8,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Instruction> {
+        let perp = self.context.perp(market_index);
+        let health_remaining_metas = self.context.derive_health_check_remaining_account_metas(
+            account,
+            vec![],
+            vec![],
+            vec![market_index],
+        )?;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpPlaceOrder {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        perp_market: perp.address,
+                        bids: perp.market.bids,
+                        asks: perp.market.asks,
+                        event_queue: perp.market.event_queue,
+                        oracle: perp.market.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpPlaceOrderV2 {
+                side,
+                price_lots,
+                max_base_lots,
+                max_quote_lots,
+                client_order_id,
+                order_type,
+                reduce_only,
+                expiry_timestamp,
+                limit,
+                self_trade_behavior,
+            }),
+        };
+
+        Ok(ix)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn perp_place_order(
+        &self,
+        market_index: PerpMarketIndex,
+        side: Side,
+        price_lots: i64,
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        reduce_only: bool,
+        expiry_timestamp: u64,
+        limit: u8,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let ix = self.perp_place_order_instruction(
+            &account,
+            market_index,
+            side,
+            price_lots,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            order_type,
+            reduce_only,
+            expiry_timestamp,
+            limit,
+            self_trade_behavior,
+        )?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_cancel_all_orders_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        limit: u8,
+    ) -> anyhow::Result<Instruction> {
+        let perp = self.context.perp(market_index);
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpCancelAllOrders {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        perp_market: perp.address,
+                        bids: perp.market.bids,
+                        asks: perp.market.asks,
+                    },
+                    None,
+                )
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpCancelAllOrders {
+                limit,
+            }),
+        };
+        Ok(ix)
+    }
+
+    /// Places an oracle pegged order with perp_place_order_pegged_v2
+    ///
+    /// The program doesn't support an oracle staleness override yet, it's always -1.
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_place_order_pegged_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: PerpMarketIndex,
+        side: Side,
+        price_offset_lots: i64,
+        peg_limit: i64,
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        reduce_only: bool,
+        expiry_timestamp: u64,
+        limit: u8,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Instruction> {
+        let perp = self.context.perp(market_index);
+        let health_remaining_metas = self.context.derive_health_check_remaining_account_metas(
+            account,
+            vec![],
+            vec![],
+            vec![market_index],
+        )?;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpPlaceOrder {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        perp_market: perp.address,
+                        bids: perp.market.bids,
+                        asks: perp.market.asks,
+                        event_queue: perp.market.event_queue,
+                        oracle: perp.market.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpPlaceOrderPeggedV2 {
+                    side,
+                    price_offset_lots,
+                    peg_limit,
+                    max_base_lots,
+                    max_quote_lots,
+                    client_order_id,
+                    order_type,
+                    self_trade_behavior,
+                    reduce_only,
+                    expiry_timestamp,
+                    limit,
+                    max_oracle_staleness_slots: -1,
+                },
+            ),
+        };
+
+        Ok(ix)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn perp_place_order_pegged(
+        &self,
+        market_index: PerpMarketIndex,
+        side: Side,
+        price_offset_lots: i64,
+        peg_limit: i64,
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        reduce_only: bool,
+        expiry_timestamp: u64,
+        limit: u8,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let ix = self.perp_place_order_pegged_instruction(
+            &account,
+            market_index,
+            side,
+            price_offset_lots,
+            peg_limit,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            order_type,
+            reduce_only,
+            expiry_timestamp,
+            limit,
+            self_trade_behavior,
+        )?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_cancel_order_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        order_id: u128,
+    ) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpCancelOrder {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    perp_market: perp.address,
+                    bids: perp.market.bids,
+                    asks: perp.market.asks,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpCancelOrder {
+                order_id,
+            }),
+        }
+    }
+
+    pub async fn perp_cancel_order(
+        &self,
+        market_index: PerpMarketIndex,
+        order_id: u128,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_cancel_order_instruction(market_index, order_id);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_cancel_order_by_client_order_id_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        client_order_id: u64,
+    ) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpCancelOrderByClientOrderId {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    perp_market: perp.address,
+                    bids: perp.market.bids,
+                    asks: perp.market.asks,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpCancelOrderByClientOrderId { client_order_id },
+            ),
+        }
+    }
+
+    pub async fn perp_cancel_order_by_client_order_id(
+        &self,
+        market_index: PerpMarketIndex,
+        client_order_id: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix =
+            self.perp_cancel_order_by_client_order_id_instruction(market_index, client_order_id);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn perp_cancel_all_orders(
+        &self,
+        market_index: PerpMarketIndex,
+        limit: u8,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_cancel_all_orders_instruction(market_index, limit)?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_cancel_all_orders_by_side_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        side_option: Option<Side>,
+        limit: u8,
+    ) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpCancelAllOrdersBySide {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    perp_market: perp.address,
+                    bids: perp.market.bids,
+                    asks: perp.market.asks,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpCancelAllOrdersBySide { side_option, limit },
+            ),
+        }
+    }
+
+    pub async fn perp_cancel_all_orders_by_side(
+        &self,
+        market_index: PerpMarketIndex,
+        side_option: Option<Side>,
+        limit: u8,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_cancel_all_orders_by_side_instruction(market_index, side_option, limit);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Consumes up to `limit` events, `mango_accounts` must contain all accounts that
+    /// are referenced by these events
+    pub fn perp_consume_events_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        mango_accounts: impl IntoIterator<Item = Pubkey>,
+        limit: usize,
+    ) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpConsumeEvents {
+                        group: self.group(),
+                        perp_market: perp.address,
+                        event_queue: perp.market.event_queue,
+                    },
+                    None,
+                );
+                ams.extend(
+                    mango_accounts
+                        .into_iter()
+                        .map(util::to_writable_account_meta),
+                );
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpConsumeEvents {
+                limit,
+            }),
+        }
+    }
+
+    pub async fn perp_consume_events(
+        &self,
+        market_index: PerpMarketIndex,
+        mango_accounts: impl IntoIterator<Item = Pubkey>,
+        limit: usize,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_consume_events_instruction(market_index, mango_accounts, limit);
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    pub fn perp_update_funding_instruction(&self, market_index: PerpMarketIndex) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpUpdateFunding {
+                    group: self.group(),
+                    perp_market: perp.address,
+                    bids: perp.market.bids,
+                    asks: perp.market.asks,
+                    oracle: perp.market.oracle,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpUpdateFunding {}),
+        }
+    }
+
+    pub async fn perp_update_funding(
+        &self,
+        market_index: PerpMarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_update_funding_instruction(market_index);
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    /// Settles the account's positive pnl against the perp market's fees
+    pub fn perp_settle_fees_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        account: (&Pubkey, &MangoAccountValue),
+        max_settle_amount: u64,
+    ) -> anyhow::Result<Instruction> {
+        let perp = self.context.perp(market_index);
+        let settlement_token = self.context.token(perp.market.settle_token_index);
+
+        let health_remaining_ams = self.context.derive_health_check_remaining_account_metas(
+            account.1,
+            vec![],
+            vec![],
+            vec![],
+        )?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpSettleFees {
+                        group: self.group(),
+                        perp_market: perp.address,
+                        account: *account.0,
+                        oracle: perp.market.oracle,
+                        settle_bank: settlement_token.mint_info.first_bank(),
+                        settle_oracle: settlement_token.mint_info.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_ams.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpSettleFees {
+                max_settle_amount,
+            }),
+        })
+    }
+
+    pub async fn perp_settle_fees(
+        &self,
+        market_index: PerpMarketIndex,
+        account: (&Pubkey, &MangoAccountValue),
+        max_settle_amount: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_settle_fees_instruction(market_index, account, max_settle_amount)?;
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    /// Closes opposing positions of two accounts in a perp market in force-close mode
+    pub fn perp_force_close_position_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        account_a: &Pubkey,
+        account_b: &Pubkey,
+    ) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpForceClosePosition {
+                    group: self.group(),
+                    perp_market: perp.address,
+                    account_a: *account_a,
+                    account_b: *account_b,
+                    oracle: perp.market.oracle,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpForceClosePosition {},
+            ),
+        }
+    }
+
+    pub async fn perp_force_close_position(
+        &self,
+        market_index: PerpMarketIndex,
+        account_a: &Pubkey,
+        account_b: &Pubkey,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_force_close_position_instruction(market_index, account_a, account_b);
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    pub async fn perp_deactivate_position(
+        &self,
+        market_index: PerpMarketIndex,
+    ) -> anyhow::Result<Signature> {
+        let perp = self.context.perp(market_index);
+
+        let health_check_metas = self
+            .derive_health_check_remaining_account_metas(vec![], vec![], vec![])
+            .await?;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpDeactivatePosition {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        perp_market: perp.address,
+                    },
+                    None,
+                );
+                ams.extend(health_check_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpDeactivatePosition {},
+            ),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Moves settle token deposits into (positive amount) or out of (negative amount)
+    /// the isolated collateral of the perp position, making it isolated if needed.
+    pub async fn perp_isolated_margin_transfer(
+        &self,
+        market_index: PerpMarketIndex,
+        amount: i64,
+    ) -> anyhow::Result<Signature> {
+        let perp = self.context.perp(market_index);
+        let settle_token_index = perp.market.settle_token_index;
+        let settle_token = self.context.token(settle_token_index);
+
+        let health_check_metas = self
+            .derive_health_check_remaining_account_metas(
+                vec![settle_token_index],
+                vec![settle_token_index],
+                vec![market_index],
+            )
+            .await?;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpIsolatedMarginTransfer {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        perp_market: perp.address,
+                        settle_bank: settle_token.mint_info.first_bank(),
+                        settle_oracle: settle_token.mint_info.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_check_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpIsolatedMarginTransfer { amount },
+            ),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_settle_pnl_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        account_a: (&Pubkey, &MangoAccountValue),
+        account_b: (&Pubkey, &MangoAccountValue),
+    ) -> anyhow::Result<Instruction> {
+        let perp = self.context.perp(market_index);
+        let settlement_token = self.context.token(perp.market.settle_token_index);
+
+        let health_remaining_ams = self
+            .context
+            .derive_health_check_remaining_account_metas_two_accounts(
+                account_a.1,
+                account_b.1,
+                &[],
+                &[],
+            )
+            .unwrap();
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpSettlePnl {
+                        group: self.group(),
+                        settler: self.mango_account_address,
+                        settler_owner: self.owner(),
+                        perp_market: perp.address,
+                        account_a: *account_a.0,
+                        account_b: *account_b.0,
+                        oracle: perp.market.oracle,
+                        settle_bank: settlement_token.mint_info.first_bank(),
+                        settle_oracle: settlement_token.mint_info.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_ams.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpSettlePnl {}),
+        })
+    }
+
+    pub async fn perp_settle_pnl(
         &self,
         market_index: PerpMarketIndex,
-        limit: u8,
-    ) -> anyhow::Result<Instruction> {
+        account_a: (&Pubkey, &MangoAccountValue),
+        account_b: (&Pubkey, &MangoAccountValue),
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_settle_pnl_instruction(market_index, account_a, account_b)?;
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    pub async fn perp_liq_force_cancel_orders(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: PerpMarketIndex,
+    ) -> anyhow::Result<Signature> {
         let perp = self.context.perp(market_index);
 
+        let health_remaining_ams = self
+            .context
+            .derive_health_check_remaining_account_metas(liqee.1, vec![], vec![], vec![])
+            .unwrap();
+
         let ix = Instruction {
             program_id: mango_v4::id(),
             accounts: {
-                anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpCancelAllOrders {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpLiqForceCancelOrders {
                         group: self.group(),
-                        account: self.mango_account_address,
-                        owner: self.owner(),
+                        account: *liqee.0,
                         perp_market: perp.address,
                         bids: perp.market.bids,
                         asks: perp.market.asks,
                     },
                     None,
-                )
+                );
+                ams.extend(health_remaining_ams.into_iter());
+                ams
             },
-            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpCancelAllOrders {
-                limit,
-            }),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpLiqForceCancelOrders { limit: 5 },
+            ),
+        };
+        self.send_and_confirm_permissionless_tx(vec![ix]).await
+    }
+
+    pub async fn perp_liq_base_or_positive_pnl_instruction(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: PerpMarketIndex,
+        max_base_transfer: i64,
+        max_pnl_transfer: u64,
+    ) -> anyhow::Result<Instruction> {
+        let perp = self.context.perp(market_index);
+        let settle_token_info = self.context.token(perp.market.settle_token_index);
+
+        let health_remaining_ams = self
+            .derive_liquidation_health_check_remaining_account_metas(liqee.1, &[], &[])
+            .await
+            .unwrap();
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpLiqBaseOrPositivePnl {
+                        group: self.group(),
+                        perp_market: perp.address,
+                        oracle: perp.market.oracle,
+                        liqor: self.mango_account_address,
+                        liqor_owner: self.owner(),
+                        liqee: *liqee.0,
+                        settle_bank: settle_token_info.mint_info.first_bank(),
+                        settle_vault: settle_token_info.mint_info.first_vault(),
+                        settle_oracle: settle_token_info.mint_info.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_ams.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpLiqBaseOrPositivePnl {
+                    max_base_transfer,
+                    max_pnl_transfer,
+                },
+            ),
         };
         Ok(ix)
     }
 
-    pub async fn perp_deactivate_position(
+    /// The settle and insurance banks and vaults that perp liquidations pay tokens into
+    async fn perp_liq_settle_and_insurance_banks_and_vaults(
         &self,
+        settle_token_index: TokenIndex,
+    ) -> anyhow::Result<((Pubkey, Pubkey), (Pubkey, Pubkey))> {
+        let settle = self.deposit_bank_and_vault(settle_token_index).await?;
+        // The instructions expect the same bank when the settle token is the insurance token
+        let insurance = if settle_token_index == INSURANCE_TOKEN_INDEX {
+            settle
+        } else {
+            self.deposit_bank_and_vault(INSURANCE_TOKEN_INDEX).await?
+        };
+        Ok((settle, insurance))
+    }
+
+    /// Liquidates negative pnl and bankruptcy with perp_liq_negative_pnl_or_bankruptcy_v2
+    pub async fn perp_liq_negative_pnl_or_bankruptcy_instruction(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
         market_index: PerpMarketIndex,
-    ) -> anyhow::Result<Signature> {
+        max_liab_transfer: u64,
+    ) -> anyhow::Result<Instruction> {
+        let group = account_fetcher_fetch_anchor_account::<Group>(
+            &*self.account_fetcher,
+            &self.context.group,
+        )
+        .await?;
+
         let perp = self.context.perp(market_index);
+        let settle_token_info = self.context.token(perp.market.settle_token_index);
+        let insurance_token_info = self.context.token(INSURANCE_TOKEN_INDEX);
 
-        let health_check_metas = self
-            .derive_health_check_remaining_account_metas(vec![], vec![], vec![])
+        let insurance_fund_ams = self
+            .derive_token_insurance_fund_remaining_account_metas(perp.market.settle_token_index)
             .await?;
+        let ((settle_bank, settle_vault), (insurance_bank, insurance_bank_vault)) = self
+            .perp_liq_settle_and_insurance_banks_and_vaults(perp.market.settle_token_index)
+            .await?;
+
+        let health_remaining_ams = self
+            .derive_liquidation_health_check_remaining_account_metas(
+                liqee.1,
+                &[INSURANCE_TOKEN_INDEX],
+                &[],
+            )
+            .await
+            .unwrap();
 
         let ix = Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpDeactivatePosition {
+                    &mango_v4::accounts::PerpLiqNegativePnlOrBankruptcyV2 {
                         group: self.group(),
-                        account: self.mango_account_address,
-                        owner: self.owner(),
                         perp_market: perp.address,
+                        oracle: perp.market.oracle,
+                        liqor: self.mango_account_address,
+                        liqor_owner: self.owner(),
+                        liqee: *liqee.0,
+                        settle_bank,
+                        settle_vault,
+                        settle_oracle: settle_token_info.mint_info.oracle,
+                        insurance_vault: group.insurance_vault,
+                        insurance_bank,
+                        insurance_bank_vault,
+                        insurance_oracle: insurance_token_info.mint_info.oracle,
+                        token_program: Token::id(),
                     },
                     None,
                 );
-                ams.extend(health_check_metas.into_iter());
+                ams.extend(insurance_fund_ams);
+                ams.extend(health_remaining_ams.into_iter());
                 ams
             },
             data: anchor_lang::InstructionData::data(
-                &mango_v4::instruction::PerpDeactivatePosition {},
+                &mango_v4::instruction::PerpLiqNegativePnlOrBankruptcyV2 { max_liab_transfer },
             ),
         };
-        self.send_and_confirm_owner_tx(vec![ix]).await
+        Ok(ix)
     }
 
-    pub fn perp_settle_pnl_instruction(
+    pub async fn perp_liq_isolated_position_instruction(
         &self,
+        liqee: (&Pubkey, &MangoAccountValue),
         market_index: PerpMarketIndex,
-        account_a: (&Pubkey, &MangoAccountValue),
-        account_b: (&Pubkey, &MangoAccountValue),
+        max_base_transfer: i64,
     ) -> anyhow::Result<Instruction> {
+        let group = account_fetcher_fetch_anchor_account::<Group>(
+            &*self.account_fetcher,
+            &self.context.group,
+        )
+        .await?;
+
         let perp = self.context.perp(market_index);
-        let settlement_token = self.context.token(perp.market.settle_token_index);
+        let settle_token_info = self.context.token(perp.market.settle_token_index);
+        let insurance_token_info = self.context.token(INSURANCE_TOKEN_INDEX);
+
+        let insurance_fund_ams = self
+            .derive_token_insurance_fund_remaining_account_metas(perp.market.settle_token_index)
+            .await?;
+        let ((settle_bank, settle_vault), (insurance_bank, insurance_bank_vault)) = self
+            .perp_liq_settle_and_insurance_banks_and_vaults(perp.market.settle_token_index)
+            .await?;
 
+        // The liqor takes over the base position and may receive insurance fund tokens
+        let mut liqor_account = self.mango_account().await?;
+        liqor_account.ensure_perp_position(market_index, perp.market.settle_token_index)?;
         let health_remaining_ams = self
             .context
             .derive_health_check_remaining_account_metas_two_accounts(
-                account_a.1,
-                account_b.1,
-                &[],
+                &liqor_account,
+                liqee.1,
+                &[INSURANCE_TOKEN_INDEX],
                 &[],
-            )
-            .unwrap();
+            )?;
 
         Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpSettlePnl {
+                    &mango_v4::accounts::PerpLiqIsolatedPosition {
                         group: self.group(),
-                        settler: self.mango_account_address,
-                        settler_owner: self.owner(),
                         perp_market: perp.address,
-                        account_a: *account_a.0,
-                        account_b: *account_b.0,
                         oracle: perp.market.oracle,
-                        settle_bank: settlement_token.mint_info.first_bank(),
-                        settle_oracle: settlement_token.mint_info.oracle,
+                        liqor: self.mango_account_address,
+                        liqor_owner: self.owner(),
+                        liqee: *liqee.0,
+                        settle_bank,
+                        settle_vault,
+                        settle_oracle: settle_token_info.mint_info.oracle,
+                        insurance_vault: group.insurance_vault,
+                        insurance_bank,
+                        insurance_bank_vault,
+                        insurance_oracle: insurance_token_info.mint_info.oracle,
+                        token_program: Token::id(),
                     },
                     None,
                 );
+                ams.extend(insurance_fund_ams);
                 ams.extend(health_remaining_ams.into_iter());
                 ams
             },
-            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpSettlePnl {}),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpLiqIsolatedPosition { max_base_transfer },
+            ),
         })
     }
 
-    pub async fn perp_settle_pnl(
+    //
+    // Perp conditional orders
+    //
+
+    pub fn perp_conditional_order_create_instruction(
         &self,
         market_index: PerpMarketIndex,
-        account_a: (&Pubkey, &MangoAccountValue),
-        account_b: (&Pubkey, &MangoAccountValue),
-    ) -> anyhow::Result<Signature> {
-        let ix = self.perp_settle_pnl_instruction(market_index, account_a, account_b)?;
-        self.send_and_confirm_permissionless_tx(vec![ix]).await
+        order: mango_v4::instruction::PerpConditionalOrderCreate,
+    ) -> Instruction {
+        let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpConditionalOrderCreate {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    perp_market: perp.address,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&order),
+        }
     }
 
-    pub async fn perp_liq_force_cancel_orders(
+    pub async fn perp_conditional_order_create(
         &self,
-        liqee: (&Pubkey, &MangoAccountValue),
         market_index: PerpMarketIndex,
+        order: mango_v4::instruction::PerpConditionalOrderCreate,
     ) -> anyhow::Result<Signature> {
+        let ix = self.perp_conditional_order_create_instruction(market_index, order);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub fn perp_conditional_order_create_place_order_instruction(
+        &self,
+        market_index: PerpMarketIndex,
+        order: mango_v4::instruction::PerpConditionalOrderCreatePlaceOrder,
+    ) -> Instruction {
         let perp = self.context.perp(market_index);
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpConditionalOrderCreate {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    perp_market: perp.address,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&order),
+        }
+    }
 
-        let health_remaining_ams = self
-            .context
-            .derive_health_check_remaining_account_metas(liqee.1, vec![], vec![], vec![])
-            .unwrap();
+    pub async fn perp_conditional_order_create_place_order(
+        &self,
+        market_index: PerpMarketIndex,
+        order: mango_v4::instruction::PerpConditionalOrderCreatePlaceOrder,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.perp_conditional_order_create_place_order_instruction(market_index, order);
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn perp_conditional_order_cancel_instruction(
+        &self,
+        perp_conditional_order_id: u64,
+    ) -> anyhow::Result<Instruction> {
+        let account = self.mango_account().await?;
+        let (order_index, _) = account.perp_conditional_order_by_id(perp_conditional_order_id)?;
+
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpConditionalOrderCancel {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpConditionalOrderCancel {
+                    perp_conditional_order_index: order_index.try_into()?,
+                    perp_conditional_order_id,
+                },
+            ),
+        })
+    }
+
+    pub async fn perp_conditional_order_cancel(
+        &self,
+        perp_conditional_order_id: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .perp_conditional_order_cancel_instruction(perp_conditional_order_id)
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Places the order of a PlaceOrder conditional order of `account` into the book,
+    /// the trigger premium is paid to this client's account
+    pub fn perp_conditional_order_trigger_place_order_instruction(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+        perp_conditional_order_id: u64,
+        limit: u8,
+    ) -> anyhow::Result<Instruction> {
+        let (order_index, order) = account
+            .1
+            .perp_conditional_order_by_id(perp_conditional_order_id)?;
+        let perp = self.context.perp(order.perp_market_index);
+
+        let health_remaining_ams = self.context.derive_health_check_remaining_account_metas(
+            account.1,
+            vec![],
+            vec![],
+            vec![order.perp_market_index],
+        )?;
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpLiqForceCancelOrders {
+                    &mango_v4::accounts::PerpConditionalOrderTriggerPlaceOrder {
                         group: self.group(),
-                        account: *liqee.0,
+                        account: *account.0,
+                        triggerer: self.mango_account_address,
+                        triggerer_authority: self.owner(),
                         perp_market: perp.address,
                         bids: perp.market.bids,
                         asks: perp.market.asks,
+                        event_queue: perp.market.event_queue,
+                        oracle: perp.market.oracle,
                     },
                     None,
                 );
-                ams.extend(health_remaining_ams.into_iter());
+                ams.extend(health_remaining_ams);
                 ams
             },
             data: anchor_lang::InstructionData::data(
-                &mango_v4::instruction::PerpLiqForceCancelOrders { limit: 5 },
+                &mango_v4::instruction::PerpConditionalOrderTriggerPlaceOrder {
+                    perp_conditional_order_index: order_index.try_into()?,
+                    perp_conditional_order_id,
+                    limit,
+                },
             ),
-        };
-        self.send_and_confirm_permissionless_tx(vec![ix]).await
+        })
     }
 
-    pub async fn perp_liq_base_or_positive_pnl_instruction(
+    //
+    // Token conditional swaps
+    //
+
+    fn token_conditional_swap_create_accounts(
         &self,
-        liqee: (&Pubkey, &MangoAccountValue),
-        market_index: PerpMarketIndex,
-        max_base_transfer: i64,
-        max_pnl_transfer: u64,
-    ) -> anyhow::Result<Instruction> {
-        let perp = self.context.perp(market_index);
-        let settle_token_info = self.context.token(perp.market.settle_token_index);
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+    ) -> Vec<AccountMeta> {
+        anchor_lang::ToAccountMetas::to_account_metas(
+            &mango_v4::accounts::TokenConditionalSwapCreate {
+                group: self.group(),
+                account: self.mango_account_address,
+                authority: self.owner(),
+                buy_bank: self.context.mint_info(buy_token_index).first_bank(),
+                sell_bank: self.context.mint_info(sell_token_index).first_bank(),
+            },
+            None,
+        )
+    }
 
-        let health_remaining_ams = self
-            .derive_liquidation_health_check_remaining_account_metas(liqee.1, &[], &[])
-            .await
-            .unwrap();
+    /// Creates a token conditional swap with a fixed premium, using token_conditional_swap_create_v2
+    #[allow(clippy::too_many_arguments)]
+    pub fn token_conditional_swap_create_instruction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: self
+                .token_conditional_swap_create_accounts(buy_token_index, sell_token_index),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenConditionalSwapCreateV2 {
+                    max_buy,
+                    max_sell,
+                    expiry_timestamp,
+                    price_lower_limit,
+                    price_upper_limit,
+                    price_premium_rate,
+                    allow_creating_deposits,
+                    allow_creating_borrows,
+                    display_price_style,
+                    intention,
+                },
+            ),
+        }
+    }
 
-        let ix = Instruction {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn token_conditional_swap_create(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_conditional_swap_create_instruction(
+            buy_token_index,
+            sell_token_index,
+            max_buy,
+            max_sell,
+            expiry_timestamp,
+            price_lower_limit,
+            price_upper_limit,
+            price_premium_rate,
+            allow_creating_deposits,
+            allow_creating_borrows,
+            display_price_style,
+            intention,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Creates a token conditional swap that sells at most `interval_max_sell` every
+    /// `interval_seconds`, with a premium auction if `premium_auction_duration_seconds` is nonzero
+    #[allow(clippy::too_many_arguments)]
+    pub fn token_conditional_swap_create_twap_instruction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+        interval_seconds: u64,
+        interval_max_sell: u64,
+        premium_auction_duration_seconds: u64,
+    ) -> Instruction {
+        Instruction {
             program_id: mango_v4::id(),
-            accounts: {
-                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpLiqBaseOrPositivePnl {
-                        group: self.group(),
-                        perp_market: perp.address,
-                        oracle: perp.market.oracle,
-                        liqor: self.mango_account_address,
-                        liqor_owner: self.owner(),
-                        liqee: *liqee.0,
-                        settle_bank: settle_token_info.mint_info.first_bank(),
-                        settle_vault: settle_token_info.mint_info.first_vault(),
-                        settle_oracle: settle_token_info.mint_info.oracle,
-                    },
-                    None,
-                );
-                ams.extend(health_remaining_ams.into_iter());
-                ams
-            },
+            accounts: self
+                .token_conditional_swap_create_accounts(buy_token_index, sell_token_index),
             data: anchor_lang::InstructionData::data(
-                &mango_v4::instruction::PerpLiqBaseOrPositivePnl {
-                    max_base_transfer,
-                    max_pnl_transfer,
+                &mango_v4::instruction::TokenConditionalSwapCreateTwap {
+                    max_buy,
+                    max_sell,
+                    expiry_timestamp,
+                    price_lower_limit,
+                    price_upper_limit,
+                    price_premium_rate,
+                    allow_creating_deposits,
+                    allow_creating_borrows,
+                    display_price_style,
+                    intention,
+                    interval_seconds,
+                    interval_max_sell,
+                    premium_auction_duration_seconds,
                 },
             ),
-        };
-        Ok(ix)
+        }
     }
 
-    pub async fn perp_liq_negative_pnl_or_bankruptcy_instruction(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn token_conditional_swap_create_twap(
         &self,
-        liqee: (&Pubkey, &MangoAccountValue),
-        market_index: PerpMarketIndex,
-        max_liab_transfer: u64,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+        interval_seconds: u64,
+        interval_max_sell: u64,
+        premium_auction_duration_seconds: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_conditional_swap_create_twap_instruction(
+            buy_token_index,
+            sell_token_index,
+            max_buy,
+            max_sell,
+            expiry_timestamp,
+            price_lower_limit,
+            price_upper_limit,
+            price_premium_rate,
+            allow_creating_deposits,
+            allow_creating_borrows,
+            display_price_style,
+            intention,
+            interval_seconds,
+            interval_max_sell,
+            premium_auction_duration_seconds,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Creates a token conditional swap whose premium rises from zero to
+    /// `max_price_premium_rate` over `premium_auction_duration_seconds`
+    #[allow(clippy::too_many_arguments)]
+    pub fn token_conditional_swap_create_linear_auction_instruction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        max_price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+        premium_auction_duration_seconds: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: self
+                .token_conditional_swap_create_accounts(buy_token_index, sell_token_index),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenConditionalSwapCreateLinearAuction {
+                    max_buy,
+                    max_sell,
+                    expiry_timestamp,
+                    price_lower_limit,
+                    price_upper_limit,
+                    max_price_premium_rate,
+                    allow_creating_deposits,
+                    allow_creating_borrows,
+                    display_price_style,
+                    intention,
+                    premium_auction_duration_seconds,
+                },
+            ),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn token_conditional_swap_create_linear_auction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        max_price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+        premium_auction_duration_seconds: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self.token_conditional_swap_create_linear_auction_instruction(
+            buy_token_index,
+            sell_token_index,
+            max_buy,
+            max_sell,
+            expiry_timestamp,
+            price_lower_limit,
+            price_upper_limit,
+            max_price_premium_rate,
+            allow_creating_deposits,
+            allow_creating_borrows,
+            display_price_style,
+            intention,
+            premium_auction_duration_seconds,
+        );
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    pub async fn token_conditional_swap_cancel_instruction(
+        &self,
+        token_conditional_swap_id: u64,
     ) -> anyhow::Result<Instruction> {
-        let group = account_fetcher_fetch_anchor_account::<Group>(
-            &*self.account_fetcher,
-            &self.context.group,
-        )
-        .await?;
+        let account = self.mango_account().await?;
+        let (tcs_index, tcs) = account.token_conditional_swap_by_id(token_conditional_swap_id)?;
 
-        let perp = self.context.perp(market_index);
-        let settle_token_info = self.context.token(perp.market.settle_token_index);
-        let insurance_token_info = self.context.token(INSURANCE_TOKEN_INDEX);
+        Ok(Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenConditionalSwapCancel {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    buy_bank: self.context.mint_info(tcs.buy_token_index).first_bank(),
+                    sell_bank: self.context.mint_info(tcs.sell_token_index).first_bank(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenConditionalSwapCancel {
+                    token_conditional_swap_index: tcs_index.try_into()?,
+                    token_conditional_swap_id,
+                },
+            ),
+        })
+    }
+
+    pub async fn token_conditional_swap_cancel(
+        &self,
+        token_conditional_swap_id: u64,
+    ) -> anyhow::Result<Signature> {
+        let ix = self
+            .token_conditional_swap_cancel_instruction(token_conditional_swap_id)
+            .await?;
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    //
+    // Liquidation
+    //
 
+    pub async fn token_liq_with_token_instruction(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        asset_token_index: TokenIndex,
+        liab_token_index: TokenIndex,
+        max_liab_transfer: I80F48,
+    ) -> anyhow::Result<Instruction> {
         let health_remaining_ams = self
             .derive_liquidation_health_check_remaining_account_metas(
                 liqee.1,
-                &[INSURANCE_TOKEN_INDEX],
                 &[],
+                &[asset_token_index, liab_token_index],
             )
             .await
             .unwrap();
@@ -1127,44 +3419,32 @@ impl MangoClient {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::PerpLiqNegativePnlOrBankruptcyV2 {
+                    &mango_v4::accounts::TokenLiqWithToken {
                         group: self.group(),
-                        perp_market: perp.address,
-                        oracle: perp.market.oracle,
+                        liqee: *liqee.0,
                         liqor: self.mango_account_address,
                         liqor_owner: self.owner(),
-                        liqee: *liqee.0,
-                        settle_bank: settle_token_info.mint_info.first_bank(),
-                        settle_vault: settle_token_info.mint_info.first_vault(),
-                        settle_oracle: settle_token_info.mint_info.oracle,
-                        insurance_vault: group.insurance_vault,
-                        insurance_bank: insurance_token_info.mint_info.first_bank(),
-                        insurance_bank_vault: insurance_token_info.mint_info.first_vault(),
-                        insurance_oracle: insurance_token_info.mint_info.oracle,
-                        token_program: Token::id(),
                     },
                     None,
                 );
-                ams.extend(health_remaining_ams.into_iter());
+                ams.extend(health_remaining_ams);
                 ams
             },
-            data: anchor_lang::InstructionData::data(
-                &mango_v4::instruction::PerpLiqNegativePnlOrBankruptcyV2 { max_liab_transfer },
-            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::TokenLiqWithToken {
+                asset_token_index,
+                liab_token_index,
+                max_liab_transfer,
+            }),
         };
         Ok(ix)
     }
 
-    //
-    // Liquidation
-    //
-
-    pub async fn token_liq_with_token_instruction(
+    pub async fn token_force_close_borrows_with_token_instruction(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
         asset_token_index: TokenIndex,
         liab_token_index: TokenIndex,
-        max_liab_transfer: I80F48,
+        max_liab_transfer: u64,
     ) -> anyhow::Result<Instruction> {
         let health_remaining_ams = self
             .derive_liquidation_health_check_remaining_account_metas(
@@ -1172,31 +3452,31 @@ impl MangoClient {
                 &[],
                 &[asset_token_index, liab_token_index],
             )
-            .await
-            .unwrap();
+            .await?;
 
-        let ix = Instruction {
+        Ok(Instruction {
             program_id: mango_v4::id(),
             accounts: {
                 let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
-                    &mango_v4::accounts::TokenLiqWithToken {
+                    &mango_v4::accounts::TokenForceCloseBorrowsWithToken {
                         group: self.group(),
-                        liqee: *liqee.0,
                         liqor: self.mango_account_address,
                         liqor_owner: self.owner(),
+                        liqee: *liqee.0,
                     },
                     None,
                 );
                 ams.extend(health_remaining_ams);
                 ams
             },
-            data: anchor_lang::InstructionData::data(&mango_v4::instruction::TokenLiqWithToken {
-                asset_token_index,
-                liab_token_index,
-                max_liab_transfer,
-            }),
-        };
-        Ok(ix)
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenForceCloseBorrowsWithToken {
+                    asset_token_index,
+                    liab_token_index,
+                    max_liab_transfer,
+                },
+            ),
+        })
     }
 
     pub async fn token_liq_bankruptcy_instruction(
@@ -1207,9 +3487,23 @@ impl MangoClient {
     ) -> anyhow::Result<Instruction> {
         let quote_token_index = 0;
 
-        let quote_info = self.context.token(quote_token_index);
         let liab_info = self.context.token(liab_token_index);
 
+        // Insurance funds pay into these banks, which the instruction takes from the health accounts
+        let (quote_bank, quote_vault) = self.deposit_bank_and_vault(quote_token_index).await?;
+        let (liab_bank, liab_vault) = if liab_token_index == quote_token_index {
+            (quote_bank, quote_vault)
+        } else {
+            self.deposit_bank_and_vault(liab_token_index).await?
+        };
+
+        let mut insurance_fund_ams = self
+            .derive_token_insurance_fund_remaining_account_metas(liab_token_index)
+            .await?;
+        if !insurance_fund_ams.is_empty() {
+            insurance_fund_ams.push(util::to_writable_account_meta(liab_vault));
+        }
+
         let bank_remaining_ams = liab_info
             .mint_info
             .banks()
@@ -1217,7 +3511,7 @@ impl MangoClient {
             .map(|bank_pubkey| util::to_writable_account_meta(*bank_pubkey))
             .collect::<Vec<_>>();
 
-        let health_remaining_ams = self
+        let mut health_remaining_ams = self
             .derive_liquidation_health_check_remaining_account_metas(
                 liqee.1,
                 &[INSURANCE_TOKEN_INDEX],
@@ -1225,6 +3519,16 @@ impl MangoClient {
             )
             .await
             .unwrap();
+        self.context.use_bank_in_health_account_metas(
+            &mut health_remaining_ams,
+            quote_token_index,
+            quote_bank,
+        );
+        self.context.use_bank_in_health_account_metas(
+            &mut health_remaining_ams,
+            liab_token_index,
+            liab_bank,
+        );
 
         let group = account_fetcher_fetch_anchor_account::<Group>(
             &*self.account_fetcher,
@@ -1242,12 +3546,13 @@ impl MangoClient {
                         liqor: self.mango_account_address,
                         liqor_owner: self.owner(),
                         liab_mint_info: liab_info.mint_info_address,
-                        quote_vault: quote_info.mint_info.first_vault(),
+                        quote_vault,
                         insurance_vault: group.insurance_vault,
                         token_program: Token::id(),
                     },
                     None,
                 );
+                ams.extend(insurance_fund_ams);
                 ams.extend(bank_remaining_ams);
                 ams.extend(health_remaining_ams);
                 ams
@@ -1276,7 +3581,7 @@ impl MangoClient {
             .chain(&[tcs.buy_token_index, tcs.sell_token_index])
             .copied()
             .collect_vec();
-        let health_remaining_ams = self
+        let mut health_remaining_ams = self
             .derive_liquidation_health_check_remaining_account_metas(
                 liqee.1,
                 &affected_tokens,
@@ -1285,6 +3590,16 @@ impl MangoClient {
             .await
             .unwrap();
 
+        // Both sides of the swap may borrow and no vault tokens move
+        for token_index in [tcs.buy_token_index, tcs.sell_token_index] {
+            let (bank, _) = self.borrow_bank(token_index).await?;
+            self.context.use_bank_in_health_account_metas(
+                &mut health_remaining_ams,
+                token_index,
+                bank,
+            );
+        }
+
         let ix = Instruction {
             program_id: mango_v4::id(),
             accounts: {
@@ -1312,6 +3627,61 @@ impl MangoClient {
         Ok(ix)
     }
 
+    pub async fn perp_conditional_order_trigger_instruction(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        perp_conditional_order_id: u64,
+        max_base_lots: i64,
+    ) -> anyhow::Result<Instruction> {
+        let (order_index, order) = liqee
+            .1
+            .perp_conditional_order_by_id(perp_conditional_order_id)?;
+        let perp = self.context.perp(order.perp_market_index);
+
+        // The instruction creates the perp position on both accounts
+        let mut liqee_account = liqee.1.clone();
+        liqee_account
+            .ensure_perp_position(order.perp_market_index, perp.market.settle_token_index)?;
+        let mut liqor_account = self.mango_account().await?;
+        liqor_account
+            .ensure_perp_position(order.perp_market_index, perp.market.settle_token_index)?;
+        let health_remaining_ams = self
+            .context
+            .derive_health_check_remaining_account_metas_two_accounts(
+                &liqor_account,
+                &liqee_account,
+                &[],
+                &[],
+            )?;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpConditionalOrderTrigger {
+                        group: self.group(),
+                        liqee: *liqee.0,
+                        liqor: self.mango_account_address,
+                        liqor_authority: self.owner(),
+                        perp_market: perp.address,
+                        oracle: perp.market.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_ams);
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpConditionalOrderTrigger {
+                    perp_conditional_order_index: order_index.try_into().unwrap(),
+                    perp_conditional_order_id,
+                    max_base_lots,
+                },
+            ),
+        };
+        Ok(ix)
+    }
+
     // health region
 
     pub fn health_region_begin_instruction(
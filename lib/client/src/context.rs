@@ -5,8 +5,8 @@ use anchor_client::ClientError;
 use anchor_lang::__private::bytemuck::{self, Zeroable};
 
 use mango_v4::state::{
-    Bank, Group, MangoAccountValue, MintInfo, PerpMarket, PerpMarketIndex, Serum3Market,
-    Serum3MarketIndex, TokenIndex,
+    Bank, Group, MangoAccountValue, MintInfo, OpenbookV2Market, OpenbookV2MarketIndex, PerpMarket,
+    PerpMarketIndex, Serum3Market, Serum3MarketIndex, TokenIndex,
 };
 
 use fixed::types::I80F48;
@@ -51,6 +51,19 @@ pub struct Serum3MarketContext {
     pub pc_lot_size: u64,
 }
 
+pub struct OpenbookV2MarketContext {
+    pub address: Pubkey,
+    pub market: OpenbookV2Market,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+    pub market_authority: Pubkey,
+    pub base_lot_size: i64,
+    pub quote_lot_size: i64,
+}
+
 pub struct PerpMarketContext {
     pub address: Pubkey,
     /// PerpMarket snapshot is never updated, only use static parts!
@@ -66,6 +79,9 @@ pub struct MangoGroupContext {
     pub serum3_markets: HashMap<Serum3MarketIndex, Serum3MarketContext>,
     pub serum3_market_indexes_by_name: HashMap<String, Serum3MarketIndex>,
 
+    pub openbook_v2_markets: HashMap<OpenbookV2MarketIndex, OpenbookV2MarketContext>,
+    pub openbook_v2_market_indexes_by_name: HashMap<String, OpenbookV2MarketIndex>,
+
     pub perp_markets: HashMap<PerpMarketIndex, PerpMarketContext>,
     pub perp_market_indexes_by_name: HashMap<String, PerpMarketIndex>,
 
@@ -105,6 +121,22 @@ impl MangoGroupContext {
         self.token(self.serum3(market_index).market.quote_token_index)
     }
 
+    pub fn openbook_v2_market_index(&self, name: &str) -> OpenbookV2MarketIndex {
+        *self.openbook_v2_market_indexes_by_name.get(name).unwrap()
+    }
+
+    pub fn openbook_v2(&self, market_index: OpenbookV2MarketIndex) -> &OpenbookV2MarketContext {
+        self.openbook_v2_markets.get(&market_index).unwrap()
+    }
+
+    pub fn openbook_v2_base_token(&self, market_index: OpenbookV2MarketIndex) -> &TokenContext {
+        self.token(self.openbook_v2(market_index).market.base_token_index)
+    }
+
+    pub fn openbook_v2_quote_token(&self, market_index: OpenbookV2MarketIndex) -> &TokenContext {
+        self.token(self.openbook_v2(market_index).market.quote_token_index)
+    }
+
     pub fn token(&self, token_index: TokenIndex) -> &TokenContext {
         self.tokens.get(&token_index).unwrap()
     }
@@ -143,6 +175,10 @@ impl MangoGroupContext {
         //         because they are in metaplex?
         let bank_tuples = fetch_banks(rpc, program, group).await?;
         for (_, bank) in bank_tuples {
+            // all banks of a token share the static parts, use the first
+            if bank.bank_num != 0 {
+                continue;
+            }
             let token = tokens.get_mut(&bank.token_index).unwrap();
             token.name = bank.name().into();
             token.decimals = bank.mint_decimals;
@@ -189,6 +225,39 @@ impl MangoGroupContext {
             })
             .collect::<HashMap<_, _>>();
 
+        // openbook v2 markets
+        let openbook_v2_market_tuples = fetch_openbook_v2_markets(rpc, program, group).await?;
+        let openbook_v2_markets_external = stream::iter(openbook_v2_market_tuples.iter())
+            .then(|(_, o)| {
+                fetch_anchor_account::<openbook_v2::state::Market>(
+                    rpc,
+                    &o.openbook_v2_market_external,
+                )
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+        let openbook_v2_markets = openbook_v2_market_tuples
+            .iter()
+            .zip(openbook_v2_markets_external.iter())
+            .map(|((pk, o), market_external)| {
+                (
+                    o.market_index,
+                    OpenbookV2MarketContext {
+                        address: *pk,
+                        market: *o,
+                        bids: market_external.bids,
+                        asks: market_external.asks,
+                        event_queue: market_external.event_queue,
+                        market_base_vault: market_external.market_base_vault,
+                        market_quote_vault: market_external.market_quote_vault,
+                        market_authority: market_external.market_authority,
+                        base_lot_size: market_external.base_lot_size,
+                        quote_lot_size: market_external.quote_lot_size,
+                    },
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
         // perp markets
         let perp_market_tuples = fetch_perp_markets(rpc, program, group).await?;
         let perp_markets = perp_market_tuples
@@ -213,6 +282,10 @@ impl MangoGroupContext {
             .iter()
             .map(|(i, s)| (s.market.name().to_string(), *i))
             .collect::<HashMap<_, _>>();
+        let openbook_v2_market_indexes_by_name = openbook_v2_markets
+            .iter()
+            .map(|(i, o)| (o.market.name().to_string(), *i))
+            .collect::<HashMap<_, _>>();
         let perp_market_indexes_by_name = perp_markets
             .iter()
             .map(|(i, p)| (p.market.name().to_string(), *i))
@@ -232,6 +305,8 @@ impl MangoGroupContext {
             token_indexes_by_name,
             serum3_markets,
             serum3_market_indexes_by_name,
+            openbook_v2_markets,
+            openbook_v2_market_indexes_by_name,
             perp_markets,
             perp_market_indexes_by_name,
             address_lookup_tables,
@@ -270,6 +345,7 @@ impl MangoGroupContext {
         }
 
         let serum_oos = account.active_serum3_orders().map(|&s| s.open_orders);
+        let openbook_oos = account.active_openbook_v2_orders().map(|&s| s.open_orders);
         let perp_markets = account
             .active_perp_positions()
             .map(|&pa| self.perp_market_address(pa.market_index));
@@ -294,6 +370,7 @@ impl MangoGroupContext {
             .chain(perp_markets.map(to_account_meta))
             .chain(perp_oracles.map(to_account_meta))
             .chain(serum_oos.map(to_account_meta))
+            .chain(openbook_oos.map(to_account_meta))
             .collect())
     }
 
@@ -326,6 +403,10 @@ impl MangoGroupContext {
             .active_serum3_orders()
             .chain(account1.active_serum3_orders())
             .map(|&s| s.open_orders);
+        let openbook_oos = account2
+            .active_openbook_v2_orders()
+            .chain(account1.active_openbook_v2_orders())
+            .map(|&s| s.open_orders);
         let perp_market_indexes = account2
             .active_perp_positions()
             .chain(account1.active_perp_positions())
@@ -356,19 +437,59 @@ impl MangoGroupContext {
             .chain(perp_markets.map(to_account_meta))
             .chain(perp_oracles.map(to_account_meta))
             .chain(serum_oos.map(to_account_meta))
+            .chain(openbook_oos.map(to_account_meta))
             .collect())
     }
 
+    /// Replaces the first bank of a token in derived health account metas by another of its banks
+    ///
+    /// Needed when an instruction requires the bank it moves tokens through to also
+    /// be the token's bank in the health accounts.
+    pub fn use_bank_in_health_account_metas(
+        &self,
+        health_metas: &mut [AccountMeta],
+        token_index: TokenIndex,
+        bank: Pubkey,
+    ) {
+        let first_bank = self.mint_info(token_index).first_bank();
+        for meta in health_metas.iter_mut() {
+            if meta.pubkey == first_bank {
+                meta.pubkey = bank;
+            }
+        }
+    }
+
     pub async fn new_tokens_listed(&self, rpc: &RpcClientAsync) -> anyhow::Result<bool> {
         let mint_infos = fetch_mint_infos(rpc, mango_v4::id(), self.group).await?;
         Ok(mint_infos.len() > self.tokens.len())
     }
 
+    /// Whether a token got an additional bank since the context was created
+    ///
+    /// Instructions like token_update_index_and_rate need all banks of a token.
+    pub async fn new_banks_added(&self, rpc: &RpcClientAsync) -> anyhow::Result<bool> {
+        let mint_infos = fetch_mint_infos(rpc, mango_v4::id(), self.group).await?;
+        Ok(mint_infos.iter().any(|(_, mi)| {
+            self.tokens
+                .get(&mi.token_index)
+                .map(|t| t.mint_info.num_banks() < mi.num_banks())
+                .unwrap_or(false)
+        }))
+    }
+
     pub async fn new_serum3_markets_listed(&self, rpc: &RpcClientAsync) -> anyhow::Result<bool> {
         let serum3_markets = fetch_serum3_markets(rpc, mango_v4::id(), self.group).await?;
         Ok(serum3_markets.len() > self.serum3_markets.len())
     }
 
+    pub async fn new_openbook_v2_markets_listed(
+        &self,
+        rpc: &RpcClientAsync,
+    ) -> anyhow::Result<bool> {
+        let markets = fetch_openbook_v2_markets(rpc, mango_v4::id(), self.group).await?;
+        Ok(markets.len() > self.openbook_v2_markets.len())
+    }
+
     pub async fn new_perp_markets_listed(&self, rpc: &RpcClientAsync) -> anyhow::Result<bool> {
         let new_perp_markets = fetch_perp_markets(rpc, mango_v4::id(), self.group).await?;
         Ok(new_perp_markets.len() > self.perp_markets.len())
@@ -0,0 +1,222 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use jsonrpc_core::futures::StreamExt;
+use jsonrpc_core_client::transports::ws;
+use mango_v4::logs::*;
+
+use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_rpc::rpc_pubsub::RpcSolPubSubClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::*;
+
+use crate::AnyhowWrap;
+
+/// Decodes a "Program data: " log line into the event type T, if it is one
+pub fn decode_event<T: Discriminator + AnchorDeserialize>(log: &str) -> Option<T> {
+    let data = log.strip_prefix("Program data: ")?;
+    let bytes = base64::decode(data).ok()?;
+    if bytes.len() < 8 || bytes[..8] != T::discriminator() {
+        return None;
+    }
+    T::deserialize(&mut &bytes[8..]).ok()
+}
+
+macro_rules! mango_events {
+    ($($name:ident),* $(,)?) => {
+        /// All events the mango program emits, named after their log struct
+        pub enum MangoEvent {
+            $($name($name),)*
+        }
+
+        impl MangoEvent {
+            /// Decodes event data: the 8 byte discriminator followed by the serialized struct
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                if data.len() < 8 {
+                    return None;
+                }
+                let discriminator = &data[..8];
+                $(
+                    if discriminator == $name::discriminator() {
+                        return $name::deserialize(&mut &data[8..]).ok().map(MangoEvent::$name);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(MangoEvent::$name(_) => stringify!($name),)*
+                }
+            }
+        }
+    };
+}
+
+mango_events!(
+    PerpBalanceLog,
+    TokenBalanceLog,
+    FlashLoanLog,
+    FlashLoanLogV2,
+    WithdrawLog,
+    DepositLog,
+    FillLog,
+    FillLogV2,
+    FillLogV3,
+    PerpUpdateFundingLog,
+    PerpUpdateFundingLogV2,
+    UpdateIndexLog,
+    UpdateRateLog,
+    TokenLiqWithTokenLog,
+    Serum3OpenOrdersBalanceLog,
+    Serum3OpenOrdersBalanceLogV2,
+    OpenbookV2OpenOrdersBalanceLog,
+    WithdrawLoanOriginationFeeLog,
+    WithdrawLoanLog,
+    TokenLiqBankruptcyLog,
+    TokenInsuranceFundUseLog,
+    DeactivateTokenPositionLog,
+    DeactivatePerpPositionLog,
+    TokenMetaDataLog,
+    PerpMarketMetaDataLog,
+    Serum3RegisterMarketLog,
+    OpenbookV2RegisterMarketLog,
+    PerpLiqBaseOrPositivePnlLog,
+    PerpLiqBankruptcyLog,
+    PerpLiqNegativePnlOrBankruptcyLog,
+    PerpSettlePnlLog,
+    PerpSettleFeesLog,
+    AccountBuybackFeesWithMngoLog,
+    FilledPerpOrderLog,
+    PerpTakerTradeLog,
+    PerpForceClosePositionLog,
+    TokenForceCloseBorrowsWithTokenLog,
+    TokenConditionalSwapCreateLog,
+    TokenConditionalSwapCreateLogV2,
+    TokenConditionalSwapCreateLogV3,
+    TokenConditionalSwapTriggerLog,
+    TokenConditionalSwapTriggerLogV2,
+    TokenConditionalSwapStartLog,
+    TokenConditionalSwapCancelLog,
+    PerpConditionalOrderCreateLog,
+    PerpConditionalOrderCancelLog,
+    PerpConditionalOrderTriggerLog,
+    PerpConditionalOrderPlaceLog,
+    PerpIsolatedMarginTransferLog,
+    PerpLiqIsolatedPositionLog,
+);
+
+impl MangoEvent {
+    pub fn from_log_line(log: &str) -> Option<Self> {
+        let data = log.strip_prefix("Program data: ")?;
+        Self::decode(&base64::decode(data).ok()?)
+    }
+}
+
+/// Extracts all mango events from the log messages of a transaction.
+///
+/// Only data logged while the mango program is the innermost invoked program is
+/// considered, so events from other programs can't be mistaken for mango events.
+pub fn parse_transaction_logs(logs: &[String]) -> Vec<MangoEvent> {
+    let mango_program = mango_v4::id().to_string();
+    let mut program_stack: Vec<&str> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        let rest = match log.strip_prefix("Program ") {
+            Some(rest) => rest,
+            None => continue,
+        };
+        if let Some(program) = rest.split_once(" invoke [").map(|(program, _)| program) {
+            program_stack.push(program);
+        } else if rest.ends_with(" success") || rest.contains(" failed: ") {
+            program_stack.pop();
+        } else if program_stack.last() == Some(&mango_program.as_str()) {
+            if let Some(event) = MangoEvent::from_log_line(log) {
+                events.push(event);
+            }
+        }
+    }
+    events
+}
+
+/// The mango events of a single successful transaction
+pub struct TransactionEvents {
+    pub signature: Signature,
+    pub slot: u64,
+    pub events: Vec<MangoEvent>,
+}
+
+pub struct Config {
+    pub rpc_ws_url: String,
+    pub mango_group: Pubkey,
+    pub commitment: CommitmentConfig,
+}
+
+async fn feed_events(
+    config: &Config,
+    sender: &async_channel::Sender<TransactionEvents>,
+) -> anyhow::Result<()> {
+    let connect = ws::try_connect::<RpcSolPubSubClient>(&config.rpc_ws_url).map_err_anyhow()?;
+    let client = connect.await.map_err_anyhow()?;
+
+    // All mango instructions that emit events refer to the group account
+    let mut logs_sub = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![config.mango_group.to_string()]),
+            Some(RpcTransactionLogsConfig {
+                commitment: Some(config.commitment),
+            }),
+        )
+        .map_err_anyhow()?;
+
+    loop {
+        tokio::select! {
+            message = logs_sub.next() => {
+                if let Some(data) = message {
+                    let response = data.map_err_anyhow()?;
+                    if response.value.err.is_some() {
+                        continue;
+                    }
+                    let events = parse_transaction_logs(&response.value.logs);
+                    if events.is_empty() {
+                        continue;
+                    }
+                    let tx_events = TransactionEvents {
+                        signature: Signature::from_str(&response.value.signature)?,
+                        slot: response.context.slot,
+                        events,
+                    };
+                    if sender.send(tx_events).await.is_err() {
+                        // the receiver was dropped, nobody is interested anymore
+                        return Ok(());
+                    }
+                } else {
+                    warn!("logs stream closed");
+                    return Ok(());
+                }
+            },
+            _ = tokio::time::sleep(Duration::from_secs(60)) => {
+                warn!("logs websocket timeout");
+                return Ok(())
+            }
+        }
+    }
+}
+
+/// Subscribes to the events of all successful transactions involving the group.
+///
+/// Reconnects when the websocket connection fails. Events are lost while disconnected,
+/// and validators truncate very long transaction logs.
+pub fn subscribe(config: Config) -> async_channel::Receiver<TransactionEvents> {
+    let (sender, receiver) = async_channel::unbounded();
+    tokio::spawn(async move {
+        while !sender.is_closed() {
+            info!("connecting to solana logs websocket stream");
+            if let Err(err) = feed_events(&config, &sender).await {
+                warn!("logs websocket stream error: {err}");
+            }
+        }
+    });
+    receiver
+}
@@ -0,0 +1,429 @@
+use futures::{stream, StreamExt};
+use solana_sdk::{account::Account, account::AccountSharedData, pubkey::Pubkey};
+use tonic::transport::{ClientTlsConfig, Endpoint};
+use yellowstone_grpc_proto::prelude::{
+    geyser_client::GeyserClient, subscribe_request_filter_accounts_filter::Filter,
+    subscribe_request_filter_accounts_filter_memcmp::Data, subscribe_update::UpdateOneof,
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+    SubscribeRequestFilterAccountsFilter, SubscribeRequestFilterAccountsFilterMemcmp,
+    SubscribeRequestFilterSlots, SubscribeUpdateAccount, SubscribeUpdateSlot,
+};
+
+use anyhow::Context;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::*;
+
+use crate::account_update_stream::{AccountUpdate, Message};
+
+/// Size of a serum OpenOrders account
+const OPEN_ORDERS_SIZE: usize = 3228;
+
+/// Offset of the owner field in a serum OpenOrders account
+const OPEN_ORDERS_OWNER_OFFSET: usize = 45;
+
+/// "serum" + u64 that is Initialized (1) + OpenOrders (4)
+const OPEN_ORDERS_HEADER: [u8; 13] = [0x73, 0x65, 0x72, 0x75, 0x6d, 5, 0, 0, 0, 0, 0, 0, 0];
+
+pub struct Config {
+    /// Url of a Yellowstone-compatible Geyser gRPC endpoint
+    pub grpc_url: String,
+    /// Optional x-token header for authenticating against the endpoint
+    pub grpc_x_token: Option<String>,
+    /// All serum-like programs that may own OpenOrders accounts of the group
+    pub serum_programs: Vec<Pubkey>,
+    pub open_orders_authority: Pubkey,
+}
+
+fn subscribe_request(config: &Config, mango_oracles: &[Pubkey]) -> SubscribeRequest {
+    let mut accounts = HashMap::new();
+    accounts.insert(
+        "mango".to_string(),
+        SubscribeRequestFilterAccounts {
+            owner: vec![mango_v4::id().to_string()],
+            ..Default::default()
+        },
+    );
+    accounts.insert(
+        "oracles".to_string(),
+        SubscribeRequestFilterAccounts {
+            account: mango_oracles.iter().map(|pk| pk.to_string()).collect(),
+            ..Default::default()
+        },
+    );
+    // filter for only OpenOrders with v4 authority, like the websocket source
+    let memcmp = |offset: usize, bytes: Vec<u8>| SubscribeRequestFilterAccountsFilter {
+        filter: Some(Filter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+            offset: offset as u64,
+            data: Some(Data::Bytes(bytes)),
+        })),
+    };
+    accounts.insert(
+        "open_orders".to_string(),
+        SubscribeRequestFilterAccounts {
+            owner: config
+                .serum_programs
+                .iter()
+                .map(|pk| pk.to_string())
+                .collect(),
+            filters: vec![
+                SubscribeRequestFilterAccountsFilter {
+                    filter: Some(Filter::Datasize(OPEN_ORDERS_SIZE as u64)),
+                },
+                memcmp(0, OPEN_ORDERS_HEADER.to_vec()),
+                memcmp(
+                    OPEN_ORDERS_OWNER_OFFSET,
+                    config.open_orders_authority.to_bytes().to_vec(),
+                ),
+            ],
+            ..Default::default()
+        },
+    );
+
+    let mut slots = HashMap::new();
+    slots.insert("slots".to_string(), SubscribeRequestFilterSlots::default());
+
+    SubscribeRequest {
+        accounts,
+        slots,
+        ..Default::default()
+    }
+}
+
+/// Double checks the server side OpenOrders filters, in case an endpoint doesn't apply them
+fn is_relevant_account(config: &Config, account: &AccountSharedData) -> bool {
+    use solana_sdk::account::ReadableAccount;
+    if !config.serum_programs.contains(account.owner()) {
+        return true;
+    }
+    let data = account.data();
+    data.len() == OPEN_ORDERS_SIZE
+        && data[..OPEN_ORDERS_HEADER.len()] == OPEN_ORDERS_HEADER
+        && data[OPEN_ORDERS_OWNER_OFFSET..OPEN_ORDERS_OWNER_OFFSET + 32]
+            == config.open_orders_authority.to_bytes()
+}
+
+fn account_update(update: SubscribeUpdateAccount) -> anyhow::Result<AccountUpdate> {
+    let info = update
+        .account
+        .ok_or_else(|| anyhow::anyhow!("account update without account info"))?;
+    let pubkey = Pubkey::try_from(info.pubkey.as_slice()).context("account pubkey")?;
+    let owner = Pubkey::try_from(info.owner.as_slice()).context("account owner")?;
+    Ok(AccountUpdate {
+        pubkey,
+        slot: update.slot,
+        write_version: info.write_version,
+        account: Account {
+            lamports: info.lamports,
+            data: info.data,
+            owner,
+            executable: info.executable,
+            rent_epoch: info.rent_epoch,
+        }
+        .into(),
+    })
+}
+
+/// Translate into the websocket slot update format, which ChainData consumers already handle
+fn slot_update(update: SubscribeUpdateSlot) -> Option<solana_client::rpc_response::SlotUpdate> {
+    use solana_client::rpc_response::SlotUpdate;
+    let timestamp = 0;
+    match CommitmentLevel::from_i32(update.status)? {
+        CommitmentLevel::Processed => Some(SlotUpdate::CreatedBank {
+            slot: update.slot,
+            parent: update.parent?,
+            timestamp,
+        }),
+        CommitmentLevel::Confirmed => Some(SlotUpdate::OptimisticConfirmation {
+            slot: update.slot,
+            timestamp,
+        }),
+        CommitmentLevel::Finalized => Some(SlotUpdate::Root {
+            slot: update.slot,
+            timestamp,
+        }),
+    }
+}
+
+async fn feed_data(
+    config: &Config,
+    mango_oracles: Vec<Pubkey>,
+    sender: async_channel::Sender<Message>,
+) -> anyhow::Result<()> {
+    let mut endpoint = Endpoint::from_shared(config.grpc_url.clone())?;
+    if config.grpc_url.starts_with("https") {
+        endpoint = endpoint.tls_config(ClientTlsConfig::new())?;
+    }
+    let channel = endpoint.connect().await.context("connecting to geyser")?;
+
+    let x_token: Option<tonic::metadata::AsciiMetadataValue> = config
+        .grpc_x_token
+        .as_ref()
+        .map(|token| token.parse())
+        .transpose()
+        .context("invalid x-token")?;
+    let mut client = GeyserClient::with_interceptor(channel, move |mut req: tonic::Request<()>| {
+        if let Some(token) = &x_token {
+            req.metadata_mut().insert("x-token", token.clone());
+        }
+        Ok(req)
+    });
+
+    let request = subscribe_request(config, &mango_oracles);
+    let mut updates = client
+        .subscribe(stream::once(async move { request }))
+        .await
+        .context("geyser subscribe")?
+        .into_inner();
+
+    loop {
+        tokio::select! {
+            message = updates.next() => {
+                if let Some(update) = message {
+                    match update?.update_oneof {
+                        Some(UpdateOneof::Account(account)) => {
+                            let account = account_update(account)?;
+                            if is_relevant_account(config, &account.account) {
+                                sender.send(Message::Account(account)).await.expect("sending must succeed");
+                            }
+                        }
+                        Some(UpdateOneof::Slot(slot)) => {
+                            if let Some(slot_update) = slot_update(slot) {
+                                sender.send(Message::Slot(Arc::new(slot_update))).await.expect("sending must succeed");
+                            }
+                        }
+                        _ => {}
+                    }
+                } else {
+                    warn!("geyser stream closed");
+                    return Ok(());
+                }
+            },
+            _ = tokio::time::sleep(Duration::from_secs(60)) => {
+                warn!("geyser timeout");
+                return Ok(())
+            }
+        }
+    }
+}
+
+/// Like websocket_source::start(), but consuming a Geyser gRPC stream.
+///
+/// Unlike the websocket source, this supports OpenOrders accounts from multiple serum programs.
+pub fn start(config: Config, mango_oracles: Vec<Pubkey>, sender: async_channel::Sender<Message>) {
+    tokio::spawn(async move {
+        // if the stream disconnects, we get no data in a while etc, reconnect and try again
+        loop {
+            info!("connecting to geyser grpc stream");
+            let out = feed_data(&config, mango_oracles.clone(), sender.clone());
+            let result = out.await;
+            if let Err(err) = result {
+                warn!("geyser stream error: {err:?}");
+            }
+        }
+    });
+}
+
+/// A local Geyser gRPC server that streams whatever updates are pushed into it.
+///
+/// Useful for exercising geyser_source in tests without a real validator plugin.
+#[cfg(test)]
+mod mock {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::pin::Pin;
+    use tokio::sync::broadcast;
+    use tonic::{Request, Response, Status, Streaming};
+    use yellowstone_grpc_proto::prelude::{
+        geyser_server::{Geyser, GeyserServer},
+        SubscribeUpdate, SubscribeUpdateAccountInfo,
+    };
+
+    struct MockGeyser {
+        updates: broadcast::Sender<SubscribeUpdate>,
+    }
+
+    #[tonic::async_trait]
+    impl Geyser for MockGeyser {
+        type SubscribeStream =
+            Pin<Box<dyn futures::Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+
+        async fn subscribe(
+            &self,
+            _request: Request<Streaming<SubscribeRequest>>,
+        ) -> Result<Response<Self::SubscribeStream>, Status> {
+            // Subscription filters are ignored, every client receives every update
+            let updates = stream::unfold(self.updates.subscribe(), |mut rx| async move {
+                loop {
+                    match rx.recv().await {
+                        Ok(update) => return Some((Ok(update), rx)),
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            });
+            Ok(Response::new(Box::pin(updates)))
+        }
+    }
+
+    pub struct MockServer {
+        pub address: SocketAddr,
+        updates: broadcast::Sender<SubscribeUpdate>,
+    }
+
+    impl MockServer {
+        /// Serve on `address`, use port 0 to pick a free port
+        pub async fn start(address: SocketAddr) -> anyhow::Result<Self> {
+            let listener = tokio::net::TcpListener::bind(address).await?;
+            let address = listener.local_addr()?;
+            let (updates, _) = broadcast::channel(10000);
+            let service = GeyserServer::new(MockGeyser {
+                updates: updates.clone(),
+            });
+            tokio::spawn(async move {
+                let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+                if let Err(err) = tonic::transport::Server::builder()
+                    .add_service(service)
+                    .serve_with_incoming(incoming)
+                    .await
+                {
+                    warn!("mock geyser server error: {err:?}");
+                }
+            });
+            Ok(Self { address, updates })
+        }
+
+        pub fn url(&self) -> String {
+            format!("http://{}", self.address)
+        }
+
+        /// Updates sent before a client subscribed are lost, so wait for one first
+        pub async fn wait_for_subscriber(&self) {
+            while self.updates.receiver_count() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+
+        fn send(&self, update: UpdateOneof) {
+            // an error only means there are no subscribers yet
+            let _ = self.updates.send(SubscribeUpdate {
+                filters: vec![],
+                update_oneof: Some(update),
+            });
+        }
+
+        pub fn send_account(&self, update: &AccountUpdate) {
+            use solana_sdk::account::ReadableAccount;
+            self.send(UpdateOneof::Account(SubscribeUpdateAccount {
+                account: Some(SubscribeUpdateAccountInfo {
+                    pubkey: update.pubkey.to_bytes().to_vec(),
+                    lamports: update.account.lamports(),
+                    owner: update.account.owner().to_bytes().to_vec(),
+                    executable: update.account.executable(),
+                    rent_epoch: update.account.rent_epoch(),
+                    data: update.account.data().to_vec(),
+                    write_version: update.write_version,
+                    ..Default::default()
+                }),
+                slot: update.slot,
+                is_startup: false,
+            }));
+        }
+
+        pub fn send_slot(&self, slot: u64, parent: Option<u64>, status: CommitmentLevel) {
+            self.send(UpdateOneof::Slot(SubscribeUpdateSlot {
+                slot,
+                parent,
+                status: status as i32,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_data::ChainData;
+    use solana_sdk::account::ReadableAccount;
+
+    fn open_orders_account(authority: &Pubkey, serum_program: &Pubkey) -> AccountSharedData {
+        let mut data = vec![0u8; OPEN_ORDERS_SIZE];
+        data[..OPEN_ORDERS_HEADER.len()].copy_from_slice(&OPEN_ORDERS_HEADER);
+        data[OPEN_ORDERS_OWNER_OFFSET..OPEN_ORDERS_OWNER_OFFSET + 32]
+            .copy_from_slice(&authority.to_bytes());
+        Account {
+            lamports: 1,
+            data,
+            owner: *serum_program,
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_updates_reach_chain_data() -> anyhow::Result<()> {
+        let server = mock::MockServer::start("127.0.0.1:0".parse()?).await?;
+
+        let serum_program = Pubkey::new_unique();
+        let open_orders_authority = Pubkey::new_unique();
+        let (sender, receiver) = async_channel::unbounded();
+        start(
+            Config {
+                grpc_url: server.url(),
+                grpc_x_token: None,
+                serum_programs: vec![serum_program],
+                open_orders_authority,
+            },
+            vec![],
+            sender,
+        );
+        server.wait_for_subscriber().await;
+
+        // the mock ignores subscription filters, so this must be dropped client side
+        server.send_account(&AccountUpdate {
+            pubkey: Pubkey::new_unique(),
+            slot: 10,
+            write_version: 1,
+            account: open_orders_account(&Pubkey::new_unique(), &serum_program),
+        });
+        server.send_slot(10, Some(9), CommitmentLevel::Processed);
+        let open_orders = Pubkey::new_unique();
+        server.send_account(&AccountUpdate {
+            pubkey: open_orders,
+            slot: 10,
+            write_version: 1,
+            account: open_orders_account(&open_orders_authority, &serum_program),
+        });
+        let mango_account = Pubkey::new_unique();
+        server.send_account(&AccountUpdate {
+            pubkey: mango_account,
+            slot: 10,
+            write_version: 1,
+            account: Account {
+                lamports: 2,
+                data: vec![1, 2, 3],
+                owner: mango_v4::id(),
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into(),
+        });
+
+        let mut chain = ChainData::new();
+        for _ in 0..3 {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await??;
+            message.update_chain_data(&mut chain);
+        }
+        assert!(receiver.is_empty());
+
+        assert_eq!(chain.best_chain_slot(), 10);
+        let mango_data = chain.account(&mango_account)?;
+        assert_eq!(mango_data.slot, 10);
+        assert_eq!(mango_data.account.lamports(), 2);
+        assert_eq!(mango_data.account.data(), &[1, 2, 3]);
+        assert_eq!(*chain.account(&open_orders)?.account.owner(), serum_program);
+        Ok(())
+    }
+}
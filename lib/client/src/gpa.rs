@@ -1,6 +1,8 @@
 use anchor_lang::{AccountDeserialize, Discriminator};
 
-use mango_v4::state::{Bank, MangoAccount, MangoAccountValue, MintInfo, PerpMarket, Serum3Market};
+use mango_v4::state::{
+    Bank, MangoAccount, MangoAccountValue, MintInfo, OpenbookV2Market, PerpMarket, Serum3Market,
+};
 
 use solana_account_decoder::UiAccountEncoding;
 use solana_client::nonblocking::rpc_client::RpcClient as RpcClientAsync;
@@ -129,3 +131,19 @@ pub async fn fetch_perp_markets(
     )
     .await
 }
+
+pub async fn fetch_openbook_v2_markets(
+    rpc: &RpcClientAsync,
+    program: Pubkey,
+    group: Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, OpenbookV2Market)>> {
+    fetch_anchor_accounts::<OpenbookV2Market>(
+        rpc,
+        program,
+        vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            8,
+            group.to_bytes().to_vec(),
+        ))],
+    )
+    .await
+}
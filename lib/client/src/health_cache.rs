@@ -2,16 +2,19 @@ use crate::{AccountFetcher, MangoGroupContext};
 use anyhow::Context;
 use futures::{stream, StreamExt, TryStreamExt};
 use mango_v4::accounts_zerocopy::KeyedAccountSharedData;
-use mango_v4::health::{FixedOrderAccountRetriever, HealthCache};
+use mango_v4::health::{
+    FixedOrderAccountRetriever, HealthCache, HealthScenarioAction, HealthScenarioResult,
+};
 use mango_v4::state::MangoAccountValue;
 
 pub async fn new(
     context: &MangoGroupContext,
-    account_fetcher: &impl AccountFetcher,
+    account_fetcher: &(impl AccountFetcher + ?Sized),
     account: &MangoAccountValue,
 ) -> anyhow::Result<HealthCache> {
     let active_token_len = account.active_token_positions().count();
     let active_perp_len = account.active_perp_positions().count();
+    let active_serum3_len = account.active_serum3_orders().count();
 
     let metas =
         context.derive_health_check_remaining_account_metas(account, vec![], vec![], vec![])?;
@@ -31,6 +34,7 @@ pub async fn new(
         n_perps: active_perp_len,
         begin_perp: active_token_len * 2,
         begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_openbook_v2: active_token_len * 2 + active_perp_len * 2 + active_serum3_len,
         staleness_slot: None,
     };
     mango_v4::health::new_health_cache(&account.borrow(), &retriever).context("make health cache")
@@ -43,6 +47,7 @@ pub fn new_sync(
 ) -> anyhow::Result<HealthCache> {
     let active_token_len = account.active_token_positions().count();
     let active_perp_len = account.active_perp_positions().count();
+    let active_serum3_len = account.active_serum3_orders().count();
 
     let metas =
         context.derive_health_check_remaining_account_metas(account, vec![], vec![], vec![])?;
@@ -62,7 +67,55 @@ pub fn new_sync(
         n_perps: active_perp_len,
         begin_perp: active_token_len * 2,
         begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_openbook_v2: active_token_len * 2 + active_perp_len * 2 + active_serum3_len,
         staleness_slot: None,
     };
     mango_v4::health::new_health_cache(&account.borrow(), &retriever).context("make health cache")
 }
+
+/// Evaluates a what-if scenario for an account, see HealthCache::scenario().
+///
+/// Token and perp positions that the actions need are added to a copy of the account
+/// first. Serum3 actions need the account to already have open orders on the market.
+pub async fn scenario(
+    context: &MangoGroupContext,
+    account_fetcher: &(impl AccountFetcher + ?Sized),
+    account: &MangoAccountValue,
+    actions: &[HealthScenarioAction],
+) -> anyhow::Result<HealthScenarioResult> {
+    let mut account = account.clone();
+    for action in actions {
+        use HealthScenarioAction::*;
+        match *action {
+            TokenDeposit { token_index, .. }
+            | TokenWithdraw { token_index, .. }
+            | TokenPriceShock { token_index, .. } => {
+                account.ensure_token_position(token_index)?;
+            }
+            TokenSwap {
+                source_token_index,
+                target_token_index,
+                ..
+            } => {
+                account.ensure_token_position(source_token_index)?;
+                account.ensure_token_position(target_token_index)?;
+            }
+            PerpTrade {
+                perp_market_index, ..
+            }
+            | PerpPriceShock {
+                perp_market_index, ..
+            } => {
+                let settle_token_index = context.perp(perp_market_index).market.settle_token_index;
+                account.ensure_token_position(settle_token_index)?;
+                account.ensure_perp_position(perp_market_index, settle_token_index)?;
+            }
+            Serum3PlaceOrder { .. } => {}
+        }
+    }
+
+    let health_cache = new(context, account_fetcher, &account).await?;
+    health_cache
+        .scenario(actions)
+        .context("apply health scenario")
+}
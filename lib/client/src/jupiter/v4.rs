@@ -211,21 +211,25 @@ impl<'a> JupiterV4<'a> {
                 .position(|ix| !is_setup_ix(ix.program_id))
                 .unwrap();
 
-        let bank_ams = [
-            source_token.mint_info.first_bank(),
-            target_token.mint_info.first_bank(),
-        ]
-        .into_iter()
-        .map(util::to_writable_account_meta)
-        .collect::<Vec<_>>();
-
-        let vault_ams = [
-            source_token.mint_info.first_vault(),
-            target_token.mint_info.first_vault(),
-        ]
-        .into_iter()
-        .map(util::to_writable_account_meta)
-        .collect::<Vec<_>>();
+        // Loan from the fullest source vault and swap into the emptiest target vault
+        let (source_bank, source_vault) = self
+            .mango_client
+            .withdraw_bank_and_vault(source_token.token_index)
+            .await?;
+        let (target_bank, target_vault) = self
+            .mango_client
+            .deposit_bank_and_vault(target_token.token_index)
+            .await?;
+
+        let bank_ams = [source_bank, target_bank]
+            .into_iter()
+            .map(util::to_writable_account_meta)
+            .collect::<Vec<_>>();
+
+        let vault_ams = [source_vault, target_vault]
+            .into_iter()
+            .map(util::to_writable_account_meta)
+            .collect::<Vec<_>>();
 
         let token_ams = [source_token.mint_info.mint, target_token.mint_info.mint]
             .into_iter()
@@ -249,8 +253,8 @@ impl<'a> JupiterV4<'a> {
         let loan_amounts = vec![source_loan, 0u64];
         let num_loans: u8 = loan_amounts.len().try_into().unwrap();
 
-        // This relies on the fact that health account banks will be identical to the first_bank above!
-        let health_ams = self
+        // The health accounts must contain the banks used above
+        let mut health_ams = self
             .mango_client
             .derive_health_check_remaining_account_metas(
                 vec![source_token.token_index, target_token.token_index],
@@ -259,6 +263,17 @@ impl<'a> JupiterV4<'a> {
             )
             .await
             .context("building health accounts")?;
+        let context = &self.mango_client.context;
+        context.use_bank_in_health_account_metas(
+            &mut health_ams,
+            source_token.token_index,
+            source_bank,
+        );
+        context.use_bank_in_health_account_metas(
+            &mut health_ams,
+            target_token.token_index,
+            target_bank,
+        );
 
         let mut instructions = Vec::new();
 
@@ -5,14 +5,21 @@ pub use util::*;
 
 mod account_fetcher;
 pub mod account_update_stream;
+mod admin;
 pub mod chain_data;
 mod chain_data_fetcher;
 mod client;
 mod context;
+pub mod events;
+pub mod geyser_source;
 mod gpa;
 pub mod health_cache;
 pub mod jupiter;
 pub mod perp_pnl;
+pub mod report;
+#[cfg(feature = "simulator")]
+pub mod simulator;
 pub mod snapshot_source;
+pub mod stress_test;
 mod util;
 pub mod websocket_source;
@@ -95,11 +95,15 @@ pub async fn fetch_top(
             } else {
                 I80F48::ZERO
             };
-            let perp_max_settle = crate::health_cache::new(context, account_fetcher, &acc)
-                .await?
-                .perp_max_settle(perp_market.settle_token_index)?;
+            let health_cache = crate::health_cache::new(context, account_fetcher, &acc).await?;
+            let perp_max_settle = health_cache.perp_max_settle(perp_market.settle_token_index)?;
+            // pnl is in perp quote units, perp_max_settle in settle token units
+            let settle_token_price = health_cache
+                .token_info(perp_market.settle_token_index)?
+                .prices
+                .oracle;
             let settleable_pnl = if perp_max_settle > 0 {
-                (*pnl).max(-perp_max_settle)
+                (*pnl).max(-perp_max_settle * settle_token_price)
             } else {
                 I80F48::ZERO
             };
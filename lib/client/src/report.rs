@@ -0,0 +1,387 @@
+use anchor_lang::prelude::Pubkey;
+use fixed::types::I80F48;
+use mango_v4::health::HealthType;
+use mango_v4::state::{
+    Bank, PerpMarket, Serum3MarketIndex, Side, TokenIndex, FREE_ORDER_SLOT, QUOTE_TOKEN_INDEX,
+};
+use std::fmt;
+
+use crate::{
+    account_fetcher_fetch_anchor_account, account_fetcher_fetch_mango_account, health_cache,
+    AccountFetcher, MangoGroupContext,
+};
+
+pub struct TokenReport {
+    pub token_index: TokenIndex,
+    pub name: String,
+    pub balance: f64,
+    /// Oracle price in USD per ui token
+    pub price: f64,
+    pub value_usd: f64,
+    /// Current deposit APR, 0.05 means 5%
+    pub deposit_rate: f64,
+    /// Current borrow APR, including the loan fee rate
+    pub borrow_rate: f64,
+    /// Lifetime interest, in ui units
+    pub cumulative_deposit_interest: f64,
+    pub cumulative_borrow_interest: f64,
+}
+
+pub struct PerpReport {
+    pub perp_market_index: u16,
+    pub name: String,
+    pub isolated: bool,
+    /// Position size in ui base units
+    pub base_position: f64,
+    /// All prices in USD per ui base token
+    pub oracle_price: f64,
+    pub avg_entry_price: f64,
+    pub break_even_price: f64,
+    /// Unsettled pnl including unsettled funding, in USD
+    pub unrealized_pnl_usd: f64,
+    pub realized_trade_pnl_usd: f64,
+    pub realized_other_pnl_usd: f64,
+    /// Funding that wasn't applied to the position yet, positive when paid
+    pub unsettled_funding_usd: f64,
+    pub cumulative_long_funding_usd: f64,
+    pub cumulative_short_funding_usd: f64,
+    pub bids_base_lots: i64,
+    pub asks_base_lots: i64,
+}
+
+pub struct Serum3Report {
+    pub market_index: Serum3MarketIndex,
+    pub name: String,
+    pub open_orders: Pubkey,
+}
+
+pub struct PerpOrderReport {
+    pub perp_market_index: u16,
+    pub side: Side,
+    pub id: u128,
+    pub client_id: u64,
+}
+
+pub struct TokenConditionalSwapReport {
+    pub id: u64,
+    pub buy_token: String,
+    pub sell_token: String,
+    /// Remaining amounts in ui units
+    pub remaining_buy: f64,
+    pub remaining_sell: f64,
+    pub price_lower_limit: f64,
+    pub price_upper_limit: f64,
+    pub expiry_timestamp: u64,
+}
+
+pub struct AccountReport {
+    pub address: Pubkey,
+    pub name: String,
+    pub owner: Pubkey,
+    pub being_liquidated: bool,
+    pub net_deposits_usd: f64,
+    pub perp_spot_transfers_usd: f64,
+    /// Health values in USD
+    pub init_health: f64,
+    pub maint_health: f64,
+    pub liquidation_end_health: f64,
+    /// Health ratios in percent
+    pub init_health_ratio: f64,
+    pub maint_health_ratio: f64,
+    pub leverage: f64,
+    pub tokens: Vec<TokenReport>,
+    pub perps: Vec<PerpReport>,
+    pub serum3: Vec<Serum3Report>,
+    pub perp_orders: Vec<PerpOrderReport>,
+    pub token_conditional_swaps: Vec<TokenConditionalSwapReport>,
+}
+
+/// Collects balances, positions, orders and health of a mango account.
+///
+/// Banks and perp markets are fetched fresh, so interest rates and funding are current.
+pub async fn account_report(
+    context: &MangoGroupContext,
+    account_fetcher: &impl AccountFetcher,
+    address: Pubkey,
+) -> anyhow::Result<AccountReport> {
+    let account = account_fetcher_fetch_mango_account(account_fetcher, &address).await?;
+    let health = health_cache::new(context, account_fetcher, &account).await?;
+
+    let quote_decimals = context.token(QUOTE_TOKEN_INDEX).decimals;
+    // net_deposits and others are in native units of the quote token
+    let usd = |native: I80F48| -> f64 {
+        (native / I80F48::from(10u64.pow(quote_decimals.into()))).to_num()
+    };
+    // converts a native/native price into usd per ui token
+    let ui_price = |price: f64, decimals: u8| -> f64 {
+        price * 10f64.powi(decimals as i32 - quote_decimals as i32)
+    };
+
+    let mut tokens = vec![];
+    for position in account.active_token_positions() {
+        let token = context.token(position.token_index);
+        // All banks of a token share indexes and configuration, any of them can value the position
+        let bank: Bank =
+            account_fetcher_fetch_anchor_account(account_fetcher, &token.mint_info.first_bank())
+                .await?;
+        let native = position.native(&bank);
+        let price = health.token_info(position.token_index)?.prices.oracle;
+
+        // Interest rates are based on the utilization of all banks of the token
+        let mut deposits = bank.native_deposits();
+        let mut borrows = bank.native_borrows();
+        for other_bank_address in token.mint_info.banks().iter().skip(1) {
+            let other_bank: Bank =
+                account_fetcher_fetch_anchor_account(account_fetcher, other_bank_address).await?;
+            deposits += other_bank.native_deposits();
+            borrows += other_bank.native_borrows();
+        }
+        let utilization = if deposits > 0 {
+            borrows / deposits
+        } else {
+            I80F48::ZERO
+        };
+        let borrow_rate = bank.compute_interest_rate(utilization);
+        let deposit_rate = borrow_rate * utilization;
+
+        tokens.push(TokenReport {
+            token_index: position.token_index,
+            name: token.name.clone(),
+            balance: token.native_to_ui(native),
+            price: ui_price(price.to_num(), token.decimals),
+            value_usd: usd(native * price),
+            deposit_rate: deposit_rate.to_num(),
+            borrow_rate: (borrow_rate + bank.loan_fee_rate).to_num(),
+            cumulative_deposit_interest: position.cumulative_deposit_interest
+                / 10f64.powi(token.decimals.into()),
+            cumulative_borrow_interest: position.cumulative_borrow_interest
+                / 10f64.powi(token.decimals.into()),
+        });
+    }
+
+    let mut perps = vec![];
+    for position in account.active_perp_positions() {
+        let perp_address = context.perp_market_address(position.market_index);
+        let perp_market: PerpMarket =
+            account_fetcher_fetch_anchor_account(account_fetcher, &perp_address).await?;
+        let perp_info = health.perp_info(position.market_index)?;
+        // perp prices and pnl are in native settle token units
+        let price = perp_info.base_prices.oracle;
+        let settle_price = health
+            .token_info(perp_market.settle_token_index)?
+            .prices
+            .oracle;
+        let settle_usd = |settle_native: I80F48| usd(settle_native * settle_price);
+        let settle_ui_price =
+            |price: f64, decimals: u8| ui_price(price * settle_price.to_num::<f64>(), decimals);
+
+        let mut settled = position.clone();
+        settled.settle_funding(&perp_market);
+        let unrealized_pnl = settled.unsettled_pnl(&perp_market, price)?;
+
+        perps.push(PerpReport {
+            perp_market_index: position.market_index,
+            name: perp_market.name().to_string(),
+            isolated: position.is_isolated(),
+            base_position: position.base_position_native(&perp_market).to_num::<f64>()
+                / 10f64.powi(perp_market.base_decimals.into()),
+            oracle_price: settle_ui_price(price.to_num(), perp_market.base_decimals),
+            avg_entry_price: settle_ui_price(
+                position.avg_entry_price(&perp_market),
+                perp_market.base_decimals,
+            ),
+            break_even_price: settle_ui_price(
+                position.break_even_price(&perp_market),
+                perp_market.base_decimals,
+            ),
+            unrealized_pnl_usd: settle_usd(unrealized_pnl),
+            realized_trade_pnl_usd: settle_usd(position.realized_trade_pnl_native),
+            realized_other_pnl_usd: settle_usd(position.realized_other_pnl_native),
+            unsettled_funding_usd: settle_usd(position.unsettled_funding(&perp_market)),
+            cumulative_long_funding_usd: settle_usd(I80F48::from_num(
+                position.cumulative_long_funding,
+            )),
+            cumulative_short_funding_usd: settle_usd(I80F48::from_num(
+                position.cumulative_short_funding,
+            )),
+            bids_base_lots: perp_info.bids_base_lots,
+            asks_base_lots: perp_info.asks_base_lots,
+        });
+    }
+
+    let serum3 = account
+        .active_serum3_orders()
+        .map(|orders| Serum3Report {
+            market_index: orders.market_index,
+            name: context
+                .serum3(orders.market_index)
+                .market
+                .name()
+                .to_string(),
+            open_orders: orders.open_orders,
+        })
+        .collect();
+
+    let perp_orders = account
+        .all_perp_orders()
+        .filter(|order| order.market != FREE_ORDER_SLOT)
+        .map(|order| PerpOrderReport {
+            perp_market_index: order.market,
+            side: order.side_and_tree().side(),
+            id: order.id,
+            client_id: order.client_id,
+        })
+        .collect();
+
+    let token_conditional_swaps = account
+        .active_token_conditional_swaps()
+        .map(|tcs| {
+            let buy = context.token(tcs.buy_token_index);
+            let sell = context.token(tcs.sell_token_index);
+            TokenConditionalSwapReport {
+                id: tcs.id,
+                buy_token: buy.name.clone(),
+                sell_token: sell.name.clone(),
+                remaining_buy: buy
+                    .native_to_ui(I80F48::from(tcs.max_buy.saturating_sub(tcs.bought))),
+                remaining_sell: sell
+                    .native_to_ui(I80F48::from(tcs.max_sell.saturating_sub(tcs.sold))),
+                price_lower_limit: tcs.price_lower_limit,
+                price_upper_limit: tcs.price_upper_limit,
+                expiry_timestamp: tcs.expiry_timestamp,
+            }
+        })
+        .collect();
+
+    Ok(AccountReport {
+        address,
+        name: account.fixed.name().to_string(),
+        owner: account.fixed.owner,
+        being_liquidated: account.fixed.being_liquidated(),
+        net_deposits_usd: usd(I80F48::from(account.fixed.net_deposits)),
+        perp_spot_transfers_usd: usd(I80F48::from(account.fixed.perp_spot_transfers)),
+        init_health: usd(health.health(HealthType::Init)),
+        maint_health: usd(health.health(HealthType::Maint)),
+        liquidation_end_health: usd(health.health(HealthType::LiquidationEnd)),
+        init_health_ratio: health.health_ratio(HealthType::Init).to_num(),
+        maint_health_ratio: health.health_ratio(HealthType::Maint).to_num(),
+        leverage: health.leverage().to_num(),
+        tokens,
+        perps,
+        serum3,
+        perp_orders,
+        token_conditional_swaps,
+    })
+}
+
+impl fmt::Display for AccountReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "account {} \"{}\"", self.address, self.name)?;
+        writeln!(f, "  owner: {}", self.owner)?;
+        if self.being_liquidated {
+            writeln!(f, "  BEING LIQUIDATED")?;
+        }
+        writeln!(f, "  net deposits: ${:.2}", self.net_deposits_usd)?;
+        writeln!(
+            f,
+            "  perp-spot transfers: ${:.2}",
+            self.perp_spot_transfers_usd
+        )?;
+        writeln!(
+            f,
+            "  health: init ${:.2} ({:.2}%), maint ${:.2} ({:.2}%), liquidation end ${:.2}",
+            self.init_health,
+            self.init_health_ratio,
+            self.maint_health,
+            self.maint_health_ratio,
+            self.liquidation_end_health
+        )?;
+        writeln!(f, "  leverage: {:.2}", self.leverage)?;
+
+        writeln!(f, "tokens")?;
+        for t in &self.tokens {
+            writeln!(
+                f,
+                "  {:<8} {:>16.6} @ ${:<12.6} = ${:>12.2}  deposit {:.2}% borrow {:.2}%  interest +{:.6}/-{:.6}",
+                t.name,
+                t.balance,
+                t.price,
+                t.value_usd,
+                t.deposit_rate * 100.0,
+                t.borrow_rate * 100.0,
+                t.cumulative_deposit_interest,
+                t.cumulative_borrow_interest
+            )?;
+        }
+
+        writeln!(f, "perps")?;
+        for p in &self.perps {
+            writeln!(
+                f,
+                "  {:<10}{} {:>12.6} @ ${:.4}  entry ${:.4} break-even ${:.4}",
+                p.name,
+                if p.isolated { " (isolated)" } else { "" },
+                p.base_position,
+                p.oracle_price,
+                p.avg_entry_price,
+                p.break_even_price
+            )?;
+            writeln!(
+                f,
+                "    unrealized ${:.2} realized trade ${:.2} other ${:.2}",
+                p.unrealized_pnl_usd, p.realized_trade_pnl_usd, p.realized_other_pnl_usd
+            )?;
+            writeln!(
+                f,
+                "    funding: unsettled ${:.4} cumulative long ${:.4} short ${:.4}",
+                p.unsettled_funding_usd,
+                p.cumulative_long_funding_usd,
+                p.cumulative_short_funding_usd
+            )?;
+            if p.bids_base_lots != 0 || p.asks_base_lots != 0 {
+                writeln!(
+                    f,
+                    "    open orders: bids {} asks {} base lots",
+                    p.bids_base_lots, p.asks_base_lots
+                )?;
+            }
+        }
+
+        if !self.perp_orders.is_empty() {
+            writeln!(f, "perp orders")?;
+            for o in &self.perp_orders {
+                writeln!(
+                    f,
+                    "  market {} {:?} id {} client id {}",
+                    o.perp_market_index, o.side, o.id, o.client_id
+                )?;
+            }
+        }
+
+        if !self.serum3.is_empty() {
+            writeln!(f, "serum3 open orders")?;
+            for s in &self.serum3 {
+                writeln!(f, "  {:<12} {}", s.name, s.open_orders)?;
+            }
+        }
+
+        if !self.token_conditional_swaps.is_empty() {
+            writeln!(f, "token conditional swaps")?;
+            for tcs in &self.token_conditional_swaps {
+                writeln!(
+                    f,
+                    "  {} buy {:.6} {} for {:.6} {}, price {}..{}, expires {}",
+                    tcs.id,
+                    tcs.remaining_buy,
+                    tcs.buy_token,
+                    tcs.remaining_sell,
+                    tcs.sell_token,
+                    tcs.price_lower_limit,
+                    tcs.price_upper_limit,
+                    tcs.expiry_timestamp
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,834 @@
+//! Runs mango instructions against a local, in-process bank.
+//!
+//! The bank is populated from a ChainData snapshot and contains the mango program
+//! (built natively into this crate). That allows dry-running liquidations, rebalances
+//! and other bot actions deterministically, without a validator or RPC node.
+//!
+//! The Simulator is an AccountFetcher, so a MangoClient can use it to build
+//! instructions against the simulated state.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::Context;
+use fixed::types::I80F48;
+use itertools::Itertools;
+
+use mango_v4::state::{Bank, MangoAccount, MangoAccountValue, PerpMarketIndex, TokenIndex};
+
+use solana_program_test::{processor, BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData, ReadableAccount};
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use solana_sdk::{bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, sysvar};
+
+use crate::chain_data::ChainData;
+pub use crate::events::decode_event;
+use crate::{health_cache, AccountFetcher, MangoGroupContext};
+
+#[derive(Clone, Debug, Default)]
+pub struct SimulatorConfig {
+    /// Slot to start the bank at, defaults to the best slot of the snapshot
+    pub slot: Option<u64>,
+
+    /// Clock timestamp to use, defaults to the bank's genesis time
+    pub unix_timestamp: Option<i64>,
+
+    /// Compute limit per transaction, defaults to the bank's default
+    pub compute_max_units: Option<u64>,
+
+    /// Additional programs (name, id) to load from BPF_OUT_DIR/SBF_OUT_DIR, like serum
+    pub programs: Vec<(String, Pubkey)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TokenBalanceChange {
+    pub token_index: TokenIndex,
+    pub before: I80F48,
+    pub after: I80F48,
+}
+
+impl TokenBalanceChange {
+    pub fn delta(&self) -> I80F48 {
+        self.after - self.before
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PerpPositionChange {
+    pub perp_market_index: PerpMarketIndex,
+    pub base_lots_before: i64,
+    pub base_lots_after: i64,
+    pub quote_native_before: I80F48,
+    pub quote_native_after: I80F48,
+}
+
+/// Post-state of a mango account that was written by the simulated transaction
+#[derive(Clone, Debug)]
+pub struct SimulatedAccount {
+    pub address: Pubkey,
+    /// Created by the transaction, the changes are relative to an empty account
+    pub created: bool,
+    pub token_balance_changes: Vec<TokenBalanceChange>,
+    pub perp_position_changes: Vec<PerpPositionChange>,
+    pub init_health: I80F48,
+    pub maint_health: I80F48,
+    pub being_liquidated: bool,
+}
+
+/// A writable mango account whose changes the simulation could not determine
+#[derive(Clone, Debug)]
+pub struct UnreportedAccount {
+    pub address: Pubkey,
+    /// For example that the transaction closed the account or that it failed to deserialize
+    pub reason: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationResult {
+    /// None if the transaction succeeded, in which case its changes were applied to the bank
+    pub error: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub compute_units_consumed: u64,
+
+    /// The mango accounts that were passed as writable, with their changes
+    pub accounts: Vec<SimulatedAccount>,
+
+    /// The mango accounts that were passed as writable but have no changes in `accounts`
+    pub unreported_accounts: Vec<UnreportedAccount>,
+}
+
+impl SimulationResult {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<&SimulatedAccount> {
+        self.accounts.iter().find(|a| a.address == *address)
+    }
+
+    /// Decodes all events of type T (from mango_v4::logs) that were emitted
+    pub fn events<T: Discriminator + AnchorDeserialize>(&self) -> Vec<T> {
+        self.logs
+            .iter()
+            .filter_map(|log| decode_event::<T>(log))
+            .collect()
+    }
+}
+
+pub struct Simulator {
+    // The context owns the bank, it must be kept alive
+    context: tokio::sync::Mutex<ProgramTestContext>,
+    banks_client: Mutex<BanksClient>,
+    pub group_context: MangoGroupContext,
+}
+
+impl Simulator {
+    /// Starts a bank containing the mango program and all accounts in the snapshot
+    pub async fn new(
+        group_context: MangoGroupContext,
+        chain_data: &ChainData,
+        config: SimulatorConfig,
+    ) -> anyhow::Result<Self> {
+        let accounts = chain_data
+            .iter_accounts()
+            .map(|(pk, data)| (*pk, data.account.clone()))
+            .collect::<Vec<_>>();
+        let slot = config.slot.unwrap_or_else(|| chain_data.best_chain_slot());
+        Self::new_with_accounts(
+            group_context,
+            accounts,
+            SimulatorConfig {
+                slot: Some(slot),
+                ..config
+            },
+        )
+        .await
+    }
+
+    pub async fn new_with_accounts(
+        group_context: MangoGroupContext,
+        accounts: impl IntoIterator<Item = (Pubkey, AccountSharedData)>,
+        config: SimulatorConfig,
+    ) -> anyhow::Result<Self> {
+        let mut test = ProgramTest::new("mango_v4", mango_v4::id(), processor!(mango_v4::entry));
+        // Use the natively built mango program, even if a mango_v4.so is around
+        test.prefer_bpf(false);
+        if let Some(units) = config.compute_max_units {
+            test.set_compute_max_units(units);
+        }
+        for (name, id) in config.programs.iter() {
+            test.add_program(name, *id, None);
+        }
+
+        let program_ids = config
+            .programs
+            .iter()
+            .map(|(_, id)| *id)
+            .chain(std::iter::once(mango_v4::id()))
+            .collect::<HashSet<_>>();
+        for (pk, account) in accounts {
+            // Programs and sysvars come from the bank itself
+            let owner = account.owner();
+            if account.executable()
+                || program_ids.contains(&pk)
+                || *owner == sysvar::id()
+                || *owner == bpf_loader::id()
+                || *owner == bpf_loader_deprecated::id()
+                || *owner == bpf_loader_upgradeable::id()
+            {
+                continue;
+            }
+            test.add_account(pk, Account::from(account));
+        }
+
+        let mut context = test.start_with_context().await;
+        if let Some(slot) = config.slot {
+            // warping requires a slot in the future
+            let current_slot = context.banks_client.get_root_slot().await?;
+            if slot > current_slot {
+                context
+                    .warp_to_slot(slot)
+                    .map_err(|e| anyhow::anyhow!("warp to slot {}: {:?}", slot, e))?;
+            }
+        }
+        if let Some(unix_timestamp) = config.unix_timestamp {
+            let mut clock = context.banks_client.get_sysvar::<Clock>().await?;
+            clock.unix_timestamp = unix_timestamp;
+            context.set_sysvar(&clock);
+        }
+
+        let banks_client = context.banks_client.clone();
+        Ok(Self {
+            context: tokio::sync::Mutex::new(context),
+            banks_client: Mutex::new(banks_client),
+            group_context,
+        })
+    }
+
+    fn banks_client(&self) -> BanksClient {
+        self.banks_client.lock().unwrap().clone()
+    }
+
+    /// Overwrites an account in the bank, for example to change an oracle price
+    pub async fn set_account(&self, address: &Pubkey, account: &AccountSharedData) {
+        let mut context = self.context.lock().await;
+        context.set_account(address, account);
+    }
+
+    pub async fn set_unix_timestamp(&self, unix_timestamp: i64) -> anyhow::Result<()> {
+        let context = self.context.lock().await;
+        let mut clock = self.banks_client().get_sysvar::<Clock>().await?;
+        clock.unix_timestamp = unix_timestamp;
+        context.set_sysvar(&clock);
+        Ok(())
+    }
+
+    pub async fn account(&self, address: &Pubkey) -> anyhow::Result<Option<Account>> {
+        Ok(self.banks_client().get_account(*address).await?)
+    }
+
+    pub async fn mango_account(&self, address: &Pubkey) -> anyhow::Result<MangoAccountValue> {
+        let account = self
+            .account(address)
+            .await?
+            .with_context(|| format!("mango account {} not found", address))?;
+        mango_account_from_data(address, &account.data)
+    }
+
+    /// Runs the instructions in a single transaction.
+    ///
+    /// The simulator's payer pays the fees, `signers` must contain any other required signers.
+    /// On success, the changes are applied to the bank and later simulations build on them.
+    pub async fn simulate(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> anyhow::Result<SimulationResult> {
+        let mango_accounts = instructions
+            .iter()
+            .flat_map(|ix| ix.accounts.iter())
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .unique()
+            .collect::<Vec<_>>();
+
+        // Remember the pre-state of mango accounts. Other writable accounts may become
+        // mango accounts during the transaction.
+        let mut pre_accounts = vec![];
+        let mut unreported_accounts = vec![];
+        for address in mango_accounts {
+            match self.account(&address).await? {
+                Some(acc) if is_mango_account(&acc) => {
+                    match mango_account_from_data(&address, &acc.data) {
+                        Ok(account) => pre_accounts.push((address, Some(account))),
+                        Err(err) => unreported_accounts.push(UnreportedAccount {
+                            address,
+                            reason: format!("{:#}", err),
+                        }),
+                    }
+                }
+                _ => pre_accounts.push((address, None)),
+            }
+        }
+
+        let (result, metadata) = {
+            let context = self.context.lock().await;
+            let mut banks_client = self.banks_client();
+            let blockhash = banks_client.get_latest_blockhash().await?;
+
+            let mut all_signers: Vec<&Keypair> = vec![&context.payer];
+            all_signers.extend(signers.iter().copied());
+            let tx = Transaction::new_signed_with_payer(
+                instructions,
+                Some(&context.payer.pubkey()),
+                &all_signers,
+                blockhash,
+            );
+            let r = banks_client.process_transaction_with_metadata(tx).await?;
+            (r.result, r.metadata)
+        };
+
+        let mut accounts = vec![];
+        for (address, pre) in pre_accounts.iter() {
+            let post_data = match self.account(address).await? {
+                Some(acc) if is_mango_account(&acc) => acc.data,
+                _ => {
+                    if pre.is_some() {
+                        unreported_accounts.push(UnreportedAccount {
+                            address: *address,
+                            reason: "closed by the transaction".to_string(),
+                        });
+                    }
+                    continue;
+                }
+            };
+            let post = match mango_account_from_data(address, &post_data) {
+                Ok(post) => post,
+                Err(err) => {
+                    unreported_accounts.push(UnreportedAccount {
+                        address: *address,
+                        reason: format!("{:#}", err),
+                    });
+                    continue;
+                }
+            };
+            accounts.push(
+                self.simulated_account(*address, pre.as_ref(), &post)
+                    .await?,
+            );
+        }
+
+        let (logs, compute_units_consumed) = metadata
+            .map(|m| (m.log_messages, m.compute_units_consumed))
+            .unwrap_or_default();
+        Ok(SimulationResult {
+            error: result.err(),
+            logs,
+            compute_units_consumed,
+            accounts,
+            unreported_accounts,
+        })
+    }
+
+    async fn token_balance(
+        &self,
+        account: &MangoAccountValue,
+        token_index: TokenIndex,
+    ) -> anyhow::Result<I80F48> {
+        let position = match account.token_position(token_index) {
+            Ok(p) => p,
+            Err(_) => return Ok(I80F48::ZERO),
+        };
+        // Banks of a token share their indexes, so the first bank values the position
+        let bank: Bank = crate::account_fetcher_fetch_anchor_account(
+            self,
+            &self.group_context.mint_info(token_index).first_bank(),
+        )
+        .await?;
+        Ok(position.native(&bank))
+    }
+
+    async fn simulated_account(
+        &self,
+        address: Pubkey,
+        pre: Option<&MangoAccountValue>,
+        post: &MangoAccountValue,
+    ) -> anyhow::Result<SimulatedAccount> {
+        let token_indexes = pre
+            .iter()
+            .flat_map(|pre| pre.active_token_positions())
+            .chain(post.active_token_positions())
+            .map(|p| p.token_index)
+            .unique()
+            .collect::<Vec<_>>();
+        let mut token_balance_changes = vec![];
+        for token_index in token_indexes {
+            let before = match pre {
+                Some(pre) => self.token_balance(pre, token_index).await?,
+                None => I80F48::ZERO,
+            };
+            // Banks are read post-state, this way index changes don't show up as balance changes
+            let change = TokenBalanceChange {
+                token_index,
+                before,
+                after: self.token_balance(post, token_index).await?,
+            };
+            if change.delta() != 0 {
+                token_balance_changes.push(change);
+            }
+        }
+
+        let perp_market_indexes = pre
+            .iter()
+            .flat_map(|pre| pre.active_perp_positions())
+            .chain(post.active_perp_positions())
+            .map(|p| p.market_index)
+            .unique()
+            .collect::<Vec<_>>();
+        let perp_position_changes = perp_market_indexes
+            .into_iter()
+            .filter_map(|market_index| {
+                let before = pre.and_then(|pre| pre.perp_position(market_index).ok());
+                let after = post.perp_position(market_index).ok();
+                let change = PerpPositionChange {
+                    perp_market_index: market_index,
+                    base_lots_before: before.map(|p| p.base_position_lots()).unwrap_or(0),
+                    base_lots_after: after.map(|p| p.base_position_lots()).unwrap_or(0),
+                    quote_native_before: before
+                        .map(|p| p.quote_position_native())
+                        .unwrap_or(I80F48::ZERO),
+                    quote_native_after: after
+                        .map(|p| p.quote_position_native())
+                        .unwrap_or(I80F48::ZERO),
+                };
+                (change.base_lots_before != change.base_lots_after
+                    || change.quote_native_before != change.quote_native_after)
+                    .then_some(change)
+            })
+            .collect();
+
+        let health_cache = health_cache::new(&self.group_context, self, post)
+            .await
+            .with_context(|| format!("health cache for simulated account {}", address))?;
+
+        Ok(SimulatedAccount {
+            address,
+            created: pre.is_none(),
+            token_balance_changes,
+            perp_position_changes,
+            init_health: health_cache.health(mango_v4::health::HealthType::Init),
+            maint_health: health_cache.health(mango_v4::health::HealthType::Maint),
+            being_liquidated: post.being_liquidated(),
+        })
+    }
+}
+
+/// Whether the account is owned by mango and has the MangoAccount discriminator
+fn is_mango_account(account: &Account) -> bool {
+    account.owner == mango_v4::id()
+        && account.data.len() >= 8
+        && account.data[..8] == MangoAccount::discriminator()
+}
+
+fn mango_account_from_data(address: &Pubkey, data: &[u8]) -> anyhow::Result<MangoAccountValue> {
+    if data.len() < 8 || data[..8] != MangoAccount::discriminator() {
+        anyhow::bail!("not a mango account at {}", address);
+    }
+    MangoAccountValue::from_bytes(&data[8..])
+        .with_context(|| format!("deserializing mango account {}", address))
+}
+
+#[async_trait::async_trait]
+impl AccountFetcher for Simulator {
+    async fn fetch_raw_account(&self, address: &Pubkey) -> anyhow::Result<AccountSharedData> {
+        self.account(address)
+            .await?
+            .map(|a| a.into())
+            .with_context(|| format!("account {} not found in simulator", address))
+    }
+
+    async fn fetch_program_accounts(
+        &self,
+        _program: &Pubkey,
+        _discriminator: [u8; 8],
+    ) -> anyhow::Result<Vec<(Pubkey, AccountSharedData)>> {
+        anyhow::bail!("the simulator does not support fetching program accounts")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anchor_lang::prelude::System;
+    use anchor_lang::{Id, InstructionData, ToAccountMetas};
+    use anchor_spl::token::{spl_token, Token};
+    use mango_v4::accounts_ix::InterestRateParams;
+    use mango_v4::logs::{DepositLog, TokenBalanceLog};
+    use mango_v4::state::{MintInfo, OracleConfigParams, StablePriceModel};
+    use solana_sdk::instruction::AccountMeta;
+    use solana_sdk::program_option::COption;
+    use solana_sdk::program_pack::Pack;
+    use solana_sdk::system_program;
+
+    use super::*;
+    use crate::chain_data::{AccountData, SlotData, SlotStatus};
+    use crate::{account_fetcher_fetch_anchor_account, MangoClient, TokenContext};
+
+    fn find_pda(seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &mango_v4::id()).0
+    }
+
+    fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id: mango_v4::id(),
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    fn packed<T: Pack>(state: T, owner: Pubkey) -> AccountSharedData {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into()
+    }
+
+    fn group_context(group: Pubkey, tokens: Vec<TokenContext>) -> MangoGroupContext {
+        MangoGroupContext {
+            group,
+            token_indexes_by_name: tokens
+                .iter()
+                .map(|t| (t.name.clone(), t.token_index))
+                .collect(),
+            tokens: tokens.into_iter().map(|t| (t.token_index, t)).collect(),
+            serum3_markets: HashMap::new(),
+            serum3_market_indexes_by_name: HashMap::new(),
+            openbook_v2_markets: HashMap::new(),
+            openbook_v2_market_indexes_by_name: HashMap::new(),
+            perp_markets: HashMap::new(),
+            perp_market_indexes_by_name: HashMap::new(),
+            address_lookup_tables: vec![],
+        }
+    }
+
+    fn token_register(token_index: TokenIndex) -> mango_v4::instruction::TokenRegister {
+        mango_v4::instruction::TokenRegister {
+            token_index,
+            name: "USDC".to_string(),
+            oracle_config: OracleConfigParams {
+                conf_filter: 0.1,
+                max_staleness_slots: None,
+            },
+            interest_rate_params: InterestRateParams {
+                util0: 0.4,
+                rate0: 0.07,
+                util1: 0.8,
+                rate1: 0.9,
+                max_rate: 1.5,
+                adjustment_factor: 0.0,
+            },
+            loan_fee_rate: 0.0,
+            loan_origination_fee_rate: 0.0,
+            maint_asset_weight: 1.0,
+            init_asset_weight: 1.0,
+            maint_liab_weight: 1.0,
+            init_liab_weight: 1.0,
+            liquidation_fee: 0.0,
+            stable_price_delay_interval_seconds: StablePriceModel::default().delay_interval_seconds,
+            stable_price_delay_growth_limit: StablePriceModel::default().delay_growth_limit,
+            stable_price_growth_limit: StablePriceModel::default().stable_growth_limit,
+            min_vault_to_deposits_ratio: 0.2,
+            net_borrow_limit_window_size_ts: 24 * 60 * 60,
+            net_borrow_limit_per_window_quote: -1,
+            borrow_weight_scale_start_quote: f64::MAX,
+            deposit_weight_scale_start_quote: f64::MAX,
+            reduce_only: 0,
+            token_conditional_swap_taker_fee_rate: 0.0,
+            token_conditional_swap_maker_fee_rate: 0.0,
+            flash_loan_swap_fee_rate: 0.0,
+        }
+    }
+
+    /// Sets up a group with one token and an empty mango account by running the
+    /// actual instructions, then continues from a ChainData snapshot of the result.
+    #[tokio::test]
+    async fn test_simulate_deposit() -> anyhow::Result<()> {
+        let admin = Keypair::new();
+        let owner = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let owner_token_account = Pubkey::new_unique();
+        let token_index: TokenIndex = 0;
+        let deposit_amount = 1_000_000u64;
+
+        let initial_accounts = vec![
+            (
+                admin.pubkey(),
+                AccountSharedData::new(10_000_000_000, 0, &system_program::id()),
+            ),
+            (
+                mint,
+                packed(
+                    spl_token::state::Mint {
+                        mint_authority: COption::Some(admin.pubkey()),
+                        supply: deposit_amount,
+                        decimals: 6,
+                        is_initialized: true,
+                        freeze_authority: COption::None,
+                    },
+                    spl_token::id(),
+                ),
+            ),
+            (
+                owner_token_account,
+                packed(
+                    spl_token::state::Account {
+                        mint,
+                        owner: owner.pubkey(),
+                        amount: deposit_amount,
+                        state: spl_token::state::AccountState::Initialized,
+                        ..Default::default()
+                    },
+                    spl_token::id(),
+                ),
+            ),
+        ];
+
+        let group = MangoClient::group_for_admin(admin.pubkey(), 0);
+        let token_index_bytes = token_index.to_le_bytes();
+        let bank_num_bytes = 0u32.to_le_bytes();
+        let bank = find_pda(&[
+            b"Bank".as_ref(),
+            group.as_ref(),
+            &token_index_bytes,
+            &bank_num_bytes,
+        ]);
+        let vault = find_pda(&[
+            b"Vault".as_ref(),
+            group.as_ref(),
+            &token_index_bytes,
+            &bank_num_bytes,
+        ]);
+        let mint_info_address = find_pda(&[b"MintInfo".as_ref(), group.as_ref(), mint.as_ref()]);
+        let oracle = find_pda(&[b"StubOracle".as_ref(), group.as_ref(), mint.as_ref()]);
+        let mango_account = find_pda(&[
+            b"MangoAccount".as_ref(),
+            group.as_ref(),
+            owner.pubkey().as_ref(),
+            &0u32.to_le_bytes(),
+        ]);
+
+        let setup = Simulator::new_with_accounts(
+            group_context(group, vec![]),
+            initial_accounts,
+            SimulatorConfig::default(),
+        )
+        .await?;
+        let setup_ixs = vec![
+            (
+                MangoClient::group_create_instruction(
+                    admin.pubkey(),
+                    admin.pubkey(),
+                    mint,
+                    0,
+                    1,
+                    1,
+                ),
+                vec![&admin],
+            ),
+            (
+                instruction(
+                    mango_v4::accounts::StubOracleCreate {
+                        group,
+                        oracle,
+                        admin: admin.pubkey(),
+                        mint,
+                        payer: admin.pubkey(),
+                        system_program: System::id(),
+                    },
+                    mango_v4::instruction::StubOracleCreate { price: I80F48::ONE },
+                ),
+                vec![&admin],
+            ),
+            (
+                instruction(
+                    mango_v4::accounts::TokenRegister {
+                        group,
+                        admin: admin.pubkey(),
+                        mint,
+                        bank,
+                        vault,
+                        mint_info: mint_info_address,
+                        oracle,
+                        payer: admin.pubkey(),
+                        token_program: Token::id(),
+                        system_program: System::id(),
+                        rent: sysvar::rent::id(),
+                    },
+                    token_register(token_index),
+                ),
+                vec![&admin],
+            ),
+            (
+                instruction(
+                    mango_v4::accounts::AccountCreate {
+                        group,
+                        account: mango_account,
+                        owner: owner.pubkey(),
+                        payer: admin.pubkey(),
+                        system_program: System::id(),
+                    },
+                    mango_v4::instruction::AccountCreate {
+                        account_num: 0,
+                        token_count: 8,
+                        serum3_count: 0,
+                        perp_count: 0,
+                        perp_oo_count: 0,
+                        name: "test".to_string(),
+                    },
+                ),
+                vec![&admin, &owner],
+            ),
+        ];
+        let mut setup_results = vec![];
+        for (ix, signers) in setup_ixs {
+            let result = setup.simulate(&[ix], &signers).await?;
+            assert!(result.is_ok(), "setup failed: {:?}", result.logs);
+            setup_results.push(result);
+        }
+
+        // the mango account didn't exist before account_create
+        let create_result = setup_results.last().unwrap();
+        let created = create_result.account(&mango_account).unwrap();
+        assert!(created.created);
+        assert!(created.token_balance_changes.is_empty());
+        assert!(create_result.unreported_accounts.is_empty());
+
+        // Snapshot the setup into a ChainData, like a bot would have it
+        let slot = 100;
+        let mut chain_data = ChainData::new();
+        chain_data.update_slot(SlotData {
+            slot,
+            parent: None,
+            status: SlotStatus::Rooted,
+            chain: 0,
+        });
+        for pubkey in [
+            admin.pubkey(),
+            mint,
+            owner_token_account,
+            group,
+            oracle,
+            bank,
+            vault,
+            mint_info_address,
+            mango_account,
+        ] {
+            let account = setup
+                .account(&pubkey)
+                .await?
+                .with_context(|| format!("setup account {}", pubkey))?;
+            chain_data.update_account(
+                pubkey,
+                AccountData {
+                    slot,
+                    write_version: 0,
+                    account: account.into(),
+                },
+            );
+        }
+
+        let mint_info: MintInfo =
+            account_fetcher_fetch_anchor_account(&setup, &mint_info_address).await?;
+        let bank_state: Bank = account_fetcher_fetch_anchor_account(&setup, &bank).await?;
+        let context = group_context(
+            group,
+            vec![TokenContext {
+                token_index,
+                name: "USDC".to_string(),
+                mint_info,
+                mint_info_address,
+                decimals: 6,
+                bank: bank_state,
+            }],
+        );
+        let health_metas = context.derive_health_check_remaining_account_metas(
+            &setup.mango_account(&mango_account).await?,
+            vec![token_index],
+            vec![],
+            vec![],
+        )?;
+        let simulator = Simulator::new(context, &chain_data, SimulatorConfig::default()).await?;
+
+        let mut deposit = instruction(
+            mango_v4::accounts::TokenDeposit {
+                group,
+                account: mango_account,
+                owner: owner.pubkey(),
+                bank,
+                vault,
+                oracle,
+                token_account: owner_token_account,
+                token_authority: owner.pubkey(),
+                token_program: Token::id(),
+            },
+            mango_v4::instruction::TokenDeposit {
+                amount: deposit_amount,
+                reduce_only: false,
+            },
+        );
+        deposit.accounts.extend(
+            health_metas
+                .into_iter()
+                .map(|meta| AccountMeta::new_readonly(meta.pubkey, false)),
+        );
+        let result = simulator.simulate(&[deposit], &[&owner]).await?;
+        assert!(result.is_ok(), "deposit failed: {:?}", result.logs);
+
+        let account = result.account(&mango_account).unwrap();
+        assert_eq!(account.token_balance_changes.len(), 1);
+        let change = &account.token_balance_changes[0];
+        assert_eq!(change.token_index, token_index);
+        assert_eq!(change.before, I80F48::ZERO);
+        assert_eq!(change.delta(), I80F48::from(deposit_amount));
+        // the token has a weight of 1 at a price of 1
+        assert_eq!(account.init_health, I80F48::from(deposit_amount));
+        assert!(!account.being_liquidated);
+        assert!(!account.created);
+        assert!(result.unreported_accounts.is_empty());
+
+        let deposits = result.events::<DepositLog>();
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(deposits[0].mango_account, mango_account);
+        assert_eq!(deposits[0].signer, owner.pubkey());
+        assert_eq!(deposits[0].token_index, token_index);
+        assert_eq!(deposits[0].quantity, deposit_amount);
+        assert_eq!(deposits[0].price, I80F48::ONE.to_bits());
+        let balances = result.events::<TokenBalanceLog>();
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].mango_account, mango_account);
+
+        // the tokens moved from the owner into the vault
+        let vault_data = simulator.account(&vault).await?.unwrap();
+        let vault_state = spl_token::state::Account::unpack(&vault_data.data)?;
+        assert_eq!(vault_state.amount, deposit_amount);
+        let owner_data = simulator.account(&owner_token_account).await?.unwrap();
+        assert_eq!(
+            spl_token::state::Account::unpack(&owner_data.data)?.amount,
+            0
+        );
+
+        Ok(())
+    }
+}
@@ -12,12 +12,14 @@ use solana_sdk::{account::AccountSharedData, commitment_config::CommitmentConfig
 
 use anyhow::Context;
 use futures::{stream, StreamExt};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use tokio::time;
 use tracing::*;
 
 use crate::account_update_stream::{AccountUpdate, Message};
+use crate::chain_data::{SnapshotAccount, SnapshotFile};
 use crate::AnyhowWrap;
 
 pub fn is_mango_account<'a>(
@@ -48,6 +50,7 @@ impl AccountSnapshot {
         for a in rpc.value {
             self.accounts.push(AccountUpdate {
                 slot: rpc.context.slot,
+                write_version: 0,
                 pubkey: Pubkey::from_str(&a.pubkey).unwrap(),
                 account: a
                     .account
@@ -68,6 +71,7 @@ impl AccountSnapshot {
             if let Some(ui_account) = a {
                 self.accounts.push(AccountUpdate {
                     slot: rpc.context.slot,
+                    write_version: 0,
                     pubkey,
                     account: ui_account
                         .decode()
@@ -86,6 +90,42 @@ pub struct Config {
     pub parallel_rpc_requests: usize,
     pub snapshot_interval: Duration,
     pub min_slot: u64,
+    /// If set, accounts are loaded from this file on startup and every successful
+    /// rpc snapshot is written back to it
+    pub snapshot_file: Option<PathBuf>,
+}
+
+fn load_snapshot_file(path: &Path) -> anyhow::Result<Vec<AccountUpdate>> {
+    let snapshot = SnapshotFile::read(path)?;
+    Ok(snapshot
+        .accounts
+        .into_iter()
+        .map(|a| AccountUpdate {
+            pubkey: a.pubkey,
+            slot: a.slot,
+            write_version: a.write_version,
+            account: a.account.into(),
+        })
+        .collect())
+}
+
+async fn store_snapshot_file(path: PathBuf, accounts: &[AccountUpdate]) -> anyhow::Result<()> {
+    // the rpc snapshot is fetched with finalized commitment
+    let slot = accounts.iter().map(|a| a.slot).max().unwrap_or(0);
+    let snapshot = SnapshotFile {
+        slot,
+        rooted_slot: slot,
+        accounts: accounts
+            .iter()
+            .map(|a| SnapshotAccount {
+                pubkey: a.pubkey,
+                slot: a.slot,
+                write_version: a.write_version,
+                account: a.account.clone().into(),
+            })
+            .collect(),
+    };
+    tokio::task::spawn_blocking(move || snapshot.write(&path)).await?
 }
 
 async fn feed_snapshots(
@@ -203,6 +243,12 @@ async fn feed_snapshots(
         )?;
     }
 
+    if let Some(path) = &config.snapshot_file {
+        if let Err(err) = store_snapshot_file(path.clone(), &snapshot.accounts).await {
+            warn!("could not store snapshot file: {:?}", err);
+        }
+    }
+
     sender
         .send(Message::Snapshot(snapshot.accounts))
         .await
@@ -215,6 +261,25 @@ pub fn start(config: Config, mango_oracles: Vec<Pubkey>, sender: async_channel::
     let mut interval_between_snapshots = time::interval(config.snapshot_interval);
 
     tokio::spawn(async move {
+        // Warm start from the last stored snapshot, so consumers have data
+        // before the first rpc snapshot completes
+        if let Some(path) = config.snapshot_file.as_ref().filter(|p| p.exists()) {
+            match load_snapshot_file(path) {
+                Ok(accounts) => {
+                    info!(
+                        "loaded {} accounts from snapshot file {}",
+                        accounts.len(),
+                        path.display()
+                    );
+                    sender
+                        .send(Message::Snapshot(accounts))
+                        .await
+                        .expect("sending must succeed");
+                }
+                Err(err) => warn!("could not load snapshot file: {:?}", err),
+            }
+        }
+
         let rpc_client = http::connect_with_options::<MinimalClient>(&config.rpc_http_url, true)
             .await
             .expect("always Ok");
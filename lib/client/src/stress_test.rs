@@ -0,0 +1,320 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use fixed::types::I80F48;
+use futures::{stream, StreamExt};
+use mango_v4::health::{HealthCache, HealthScenarioAction, HealthType};
+use mango_v4::state::{
+    MangoAccount, MangoAccountValue, PerpMarketIndex, TokenIndex, QUOTE_TOKEN_INDEX,
+};
+use serde::Deserialize;
+use solana_sdk::account::ReadableAccount;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use tracing::*;
+
+use crate::{health_cache, AccountFetcher, MangoGroupContext};
+
+/// A set of oracle price changes that happen at the same time
+///
+/// Factors multiply the current price: 0.7 is a 30% drop. Scenarios are always
+/// given explicitly, nothing is derived from historical prices.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PriceScenario {
+    pub name: String,
+    /// Factor for all perp markets and all tokens that aren't stable tokens,
+    /// for modeling correlated moves
+    #[serde(default)]
+    pub all: Option<f64>,
+    /// Factors by token name, these take precedence over `all`
+    #[serde(default)]
+    pub tokens: HashMap<String, f64>,
+    /// Factors by perp market name, these take precedence over `all`
+    #[serde(default)]
+    pub perps: HashMap<String, f64>,
+}
+
+pub struct Config {
+    pub scenarios: Vec<PriceScenario>,
+    /// Tokens that `PriceScenario::all` doesn't apply to
+    pub stable_tokens: Vec<TokenIndex>,
+}
+
+pub struct AccountStress {
+    pub address: Pubkey,
+    /// In USD
+    pub maint_health: f64,
+    pub bad_debt: f64,
+    /// Whether the account was liquidatable before the scenario
+    pub was_liquidatable: bool,
+}
+
+pub struct ScenarioReport {
+    pub name: String,
+    /// Liquidatable accounts, sorted by ascending maint health
+    pub liquidatable: Vec<AccountStress>,
+    /// Number of accounts with bad debt, including ones with only isolated bad debt
+    pub bankrupt: usize,
+    /// In USD
+    pub total_bad_debt: f64,
+    /// Bad debt by token name and perp market name, in USD, largest first
+    pub token_bad_debt: Vec<(String, f64)>,
+    pub perp_bad_debt: Vec<(String, f64)>,
+}
+
+pub struct StressTestReport {
+    /// Accounts that were evaluated
+    pub accounts: usize,
+    /// Accounts whose health couldn't be computed, like when an oracle is stale
+    pub skipped: usize,
+    pub scenarios: Vec<ScenarioReport>,
+    /// How many liquidatable accounts Display lists per scenario
+    pub max_listed_accounts: usize,
+}
+
+fn scenario_actions(
+    context: &MangoGroupContext,
+    config: &Config,
+    scenario: &PriceScenario,
+) -> anyhow::Result<Vec<HealthScenarioAction>> {
+    for name in scenario.tokens.keys() {
+        anyhow::ensure!(
+            context.token_indexes_by_name.contains_key(name),
+            "scenario {}: unknown token {}",
+            scenario.name,
+            name
+        );
+    }
+    for name in scenario.perps.keys() {
+        anyhow::ensure!(
+            context.perp_market_indexes_by_name.contains_key(name),
+            "scenario {}: unknown perp market {}",
+            scenario.name,
+            name
+        );
+    }
+
+    let mut actions = vec![];
+    for token in context.tokens.values() {
+        let is_stable = config.stable_tokens.contains(&token.token_index);
+        let factor = match (scenario.tokens.get(&token.name), scenario.all) {
+            (Some(factor), _) => *factor,
+            (None, Some(factor)) if !is_stable => factor,
+            _ => continue,
+        };
+        actions.push(HealthScenarioAction::TokenPriceShock {
+            token_index: token.token_index,
+            factor: I80F48::from_num(factor),
+        });
+    }
+    for (perp_market_index, perp) in context.perp_markets.iter() {
+        let factor = match scenario
+            .perps
+            .get(perp.market.name())
+            .or(scenario.all.as_ref())
+        {
+            Some(factor) => *factor,
+            None => continue,
+        };
+        actions.push(HealthScenarioAction::PerpPriceShock {
+            perp_market_index: *perp_market_index,
+            factor: I80F48::from_num(factor),
+        });
+    }
+    Ok(actions)
+}
+
+/// Only keep the shocks for positions the account has
+fn actions_for_cache(
+    health_cache: &HealthCache,
+    actions: &[HealthScenarioAction],
+) -> Vec<HealthScenarioAction> {
+    actions
+        .iter()
+        .filter(|action| match action {
+            HealthScenarioAction::TokenPriceShock { token_index, .. } => {
+                health_cache.token_info(*token_index).is_ok()
+            }
+            HealthScenarioAction::PerpPriceShock {
+                perp_market_index, ..
+            } => health_cache.perp_info(*perp_market_index).is_ok(),
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Applies each price scenario to all accounts of the group and summarizes which accounts
+/// become liquidatable and how much bad debt would result.
+pub async fn run(
+    context: &MangoGroupContext,
+    account_fetcher: &impl AccountFetcher,
+    config: &Config,
+) -> anyhow::Result<StressTestReport> {
+    let scenario_actions = config
+        .scenarios
+        .iter()
+        .map(|scenario| scenario_actions(context, config, scenario))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let accounts = account_fetcher
+        .fetch_program_accounts(&mango_v4::id(), MangoAccount::discriminator())
+        .await?
+        .into_iter()
+        .filter_map(|(pk, acc)| {
+            let mango_account = MangoAccountValue::from_bytes(&acc.data()[8..]).ok()?;
+            (mango_account.fixed.group == context.group).then(|| (pk, mango_account))
+        })
+        .collect::<Vec<_>>();
+    info!("computing health of {} accounts", accounts.len());
+
+    let health_caches = stream::iter(accounts.iter())
+        .map(|(pk, account)| async move {
+            (
+                *pk,
+                health_cache::new(context, account_fetcher, account).await,
+            )
+        })
+        .buffer_unordered(16)
+        .collect::<Vec<_>>()
+        .await;
+    let mut skipped = 0;
+    let health_caches = health_caches
+        .into_iter()
+        .filter_map(|(pk, result)| match result {
+            Ok(health_cache) => Some((pk, health_cache)),
+            Err(err) => {
+                warn!("skipping account {pk}: {err:?}");
+                skipped += 1;
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let quote_decimals = context.token(QUOTE_TOKEN_INDEX).decimals;
+    let to_usd = |native: I80F48| native.to_num::<f64>() / 10f64.powi(quote_decimals.into());
+
+    let mut scenarios = vec![];
+    for (scenario, actions) in config.scenarios.iter().zip(scenario_actions.iter()) {
+        let mut liquidatable = vec![];
+        let mut bankrupt = 0;
+        let mut total_bad_debt = 0.0;
+        let mut token_bad_debt = HashMap::<TokenIndex, f64>::new();
+        let mut perp_bad_debt = HashMap::<PerpMarketIndex, f64>::new();
+
+        for (address, health_cache) in health_caches.iter() {
+            let shocked =
+                health_cache.cache_after_scenario(&actions_for_cache(health_cache, actions))?;
+            // isolated perp positions can have bad debt without the account being liquidatable
+            let bad_debt = shocked.bad_debt();
+            if bad_debt.total > 0 {
+                bankrupt += 1;
+                total_bad_debt += to_usd(bad_debt.total);
+                for (token_index, amount) in bad_debt.tokens {
+                    *token_bad_debt.entry(token_index).or_default() += to_usd(amount);
+                }
+                for (perp_market_index, amount) in bad_debt.perps {
+                    *perp_bad_debt.entry(perp_market_index).or_default() += to_usd(amount);
+                }
+            }
+            if !shocked.is_liquidatable() {
+                continue;
+            }
+            liquidatable.push(AccountStress {
+                address: *address,
+                maint_health: to_usd(shocked.health(HealthType::Maint)),
+                bad_debt: to_usd(bad_debt.total),
+                was_liquidatable: health_cache.is_liquidatable(),
+            });
+        }
+
+        liquidatable.sort_by(|a, b| a.maint_health.total_cmp(&b.maint_health));
+        let sorted_by_value = |values: Vec<(String, f64)>| {
+            let mut values = values
+                .into_iter()
+                .filter(|(_, v)| *v > 0.0)
+                .collect::<Vec<_>>();
+            values.sort_by(|a, b| b.1.total_cmp(&a.1));
+            values
+        };
+        scenarios.push(ScenarioReport {
+            name: scenario.name.clone(),
+            liquidatable,
+            bankrupt,
+            total_bad_debt,
+            token_bad_debt: sorted_by_value(
+                token_bad_debt
+                    .into_iter()
+                    .map(|(ti, v)| (context.token(ti).name.clone(), v))
+                    .collect(),
+            ),
+            perp_bad_debt: sorted_by_value(
+                perp_bad_debt
+                    .into_iter()
+                    .map(|(pi, v)| (context.perp(pi).market.name().to_string(), v))
+                    .collect(),
+            ),
+        });
+    }
+
+    Ok(StressTestReport {
+        accounts: health_caches.len(),
+        skipped,
+        scenarios,
+        max_listed_accounts: 20,
+    })
+}
+
+impl fmt::Display for StressTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "evaluated {} accounts, skipped {}",
+            self.accounts, self.skipped
+        )?;
+        for scenario in &self.scenarios {
+            let previously_liquidatable: HashSet<_> = scenario
+                .liquidatable
+                .iter()
+                .filter(|a| a.was_liquidatable)
+                .map(|a| a.address)
+                .collect();
+            writeln!(f, "scenario \"{}\"", scenario.name)?;
+            writeln!(
+                f,
+                "  liquidatable: {} ({} new), bankrupt: {}, bad debt: ${:.2}",
+                scenario.liquidatable.len(),
+                scenario.liquidatable.len() - previously_liquidatable.len(),
+                scenario.bankrupt,
+                scenario.total_bad_debt
+            )?;
+            for (name, value) in &scenario.token_bad_debt {
+                writeln!(f, "  bad debt in token {:<10} ${:.2}", name, value)?;
+            }
+            for (name, value) in &scenario.perp_bad_debt {
+                writeln!(f, "  bad debt in perp {:<11} ${:.2}", name, value)?;
+            }
+            for account in scenario.liquidatable.iter().take(self.max_listed_accounts) {
+                writeln!(
+                    f,
+                    "  {} maint health ${:.2} bad debt ${:.2}{}",
+                    account.address,
+                    account.maint_health,
+                    account.bad_debt,
+                    if account.was_liquidatable {
+                        " (already liquidatable)"
+                    } else {
+                        ""
+                    }
+                )?;
+            }
+            if scenario.liquidatable.len() > self.max_listed_accounts {
+                writeln!(
+                    f,
+                    "  ... and {} more",
+                    scenario.liquidatable.len() - self.max_listed_accounts
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
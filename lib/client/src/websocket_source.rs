@@ -20,8 +20,11 @@ use crate::AnyhowWrap;
 
 pub struct Config {
     pub rpc_ws_url: String,
-    pub serum_program: Pubkey,
+    /// OpenOrders accounts of this program are streamed, if set
+    pub serum_program: Option<Pubkey>,
     pub open_orders_authority: Pubkey,
+    /// Only mango program accounts matching all of these are streamed, all accounts if empty
+    pub mango_program_filters: Vec<RpcFilterType>,
 }
 
 async fn feed_data(
@@ -38,8 +41,9 @@ async fn feed_data(
         data_slice: None,
         min_context_slot: None,
     };
-    let all_accounts_config = RpcProgramAccountsConfig {
-        filters: None,
+    let mango_accounts_config = RpcProgramAccountsConfig {
+        filters: (!config.mango_program_filters.is_empty())
+            .then(|| config.mango_program_filters.clone()),
         with_context: Some(true),
         account_config: account_info_config.clone(),
     };
@@ -64,7 +68,7 @@ async fn feed_data(
     let mut mango_sub = client
         .program_subscribe(
             mango_v4::id().to_string(),
-            Some(all_accounts_config.clone()),
+            Some(mango_accounts_config.clone()),
         )
         .map_err_anyhow()?;
     let mut mango_oracles_sub_map = StreamMap::new();
@@ -84,12 +88,18 @@ async fn feed_data(
                 .map_err_anyhow()?,
         );
     }
-    let mut open_orders_sub = client
-        .program_subscribe(
-            config.serum_program.to_string(),
-            Some(open_orders_accounts_config.clone()),
-        )
-        .map_err_anyhow()?;
+    let mut open_orders_sub_map = StreamMap::new();
+    if let Some(serum_program) = config.serum_program {
+        open_orders_sub_map.insert(
+            serum_program,
+            client
+                .program_subscribe(
+                    serum_program.to_string(),
+                    Some(open_orders_accounts_config.clone()),
+                )
+                .map_err_anyhow()?,
+        );
+    }
     let mut slot_sub = client.slots_updates_subscribe().map_err_anyhow()?;
 
     loop {
@@ -103,7 +113,8 @@ async fn feed_data(
                     return Ok(());
                 }
             },
-            message = mango_oracles_sub_map.next() => {
+            // an empty StreamMap is immediately exhausted, don't treat that as a closed stream
+            message = mango_oracles_sub_map.next(), if !mango_oracles_sub_map.is_empty() => {
                 if let Some(data) = message {
                     let response = data.1.map_err_anyhow()?;
                     let response = solana_client::rpc_response::Response{ context: RpcResponseContext{ slot: response.context.slot, api_version: None }, value: RpcKeyedAccount{ pubkey: data.0.to_string(), account:  response.value} } ;
@@ -113,9 +124,9 @@ async fn feed_data(
                     return Ok(());
                 }
             },
-            message = open_orders_sub.next() => {
+            message = open_orders_sub_map.next(), if !open_orders_sub_map.is_empty() => {
                 if let Some(data) = message {
-                    let response = data.map_err_anyhow()?;
+                    let response = data.1.map_err_anyhow()?;
                     sender.send(Message::Account(AccountUpdate::from_rpc(response)?)).await.expect("sending must succeed");
                 } else {
                     warn!("serum stream closed");
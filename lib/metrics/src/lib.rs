@@ -0,0 +1,150 @@
+//! Prometheus metrics for the mango bots
+//!
+//! Each bot creates one `Metrics`, registers its metrics on it and serves them
+//! in the Prometheus text format on `/metrics`.
+
+use std::time::Instant;
+
+use prometheus::core::{AtomicU64, Collector, GenericGauge};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry,
+};
+use tracing::*;
+use warp::Filter;
+
+pub use prometheus;
+
+pub type MetricU64 = GenericGauge<AtomicU64>;
+pub type MetricI64 = IntGauge;
+
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+}
+
+impl Metrics {
+    /// All metric names get the `namespace_` prefix, like `liquidator_mango_accounts`
+    pub fn new(namespace: &str) -> Self {
+        Self {
+            registry: Registry::new_custom(Some(namespace.to_string()), None).unwrap(),
+        }
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Registers a metric, panics if the name is already taken
+    pub fn register<T: Collector + Clone + 'static>(&self, metric: T) -> T {
+        self.registry
+            .register(Box::new(metric.clone()))
+            .expect("metric names are unique");
+        metric
+    }
+
+    pub fn register_u64(&self, name: &str, help: &str) -> MetricU64 {
+        self.register(MetricU64::new(name, help).unwrap())
+    }
+
+    pub fn register_i64(&self, name: &str, help: &str) -> MetricI64 {
+        self.register(MetricI64::new(name, help).unwrap())
+    }
+
+    pub fn register_counter(&self, name: &str, help: &str) -> IntCounter {
+        self.register(IntCounter::new(name, help).unwrap())
+    }
+
+    pub fn register_counter_vec(&self, name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+        self.register(IntCounterVec::new(Opts::new(name, help), labels).unwrap())
+    }
+
+    pub fn register_gauge_vec(&self, name: &str, help: &str, labels: &[&str]) -> IntGaugeVec {
+        self.register(IntGaugeVec::new(Opts::new(name, help), labels).unwrap())
+    }
+
+    pub fn register_histogram(&self, name: &str, help: &str, buckets: Vec<f64>) -> Histogram {
+        self.register(
+            Histogram::with_opts(HistogramOpts::new(name, help).buckets(buckets)).unwrap(),
+        )
+    }
+
+    pub fn register_histogram_vec(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[&str],
+        buckets: Vec<f64>,
+    ) -> HistogramVec {
+        self.register(
+            HistogramVec::new(HistogramOpts::new(name, help).buckets(buckets), labels).unwrap(),
+        )
+    }
+
+    /// The current values, in the Prometheus text format
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::<u8>::new();
+        prometheus::TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+/// Serves the metrics on `/metrics` until the process exits
+pub async fn serve(metrics: Metrics, port: u16) {
+    let metrics_route = warp::path!("metrics").map(move || metrics.encode());
+    info!("metrics server starting on port {port}");
+    warp::serve(metrics_route).run(([0, 0, 0, 0], port)).await;
+}
+
+/// Creates the metrics and serves them in the background
+pub fn start(namespace: &str, port: u16) -> Metrics {
+    let metrics = Metrics::new(namespace);
+    tokio::spawn(serve(metrics.clone(), port));
+    metrics
+}
+
+/// Outcomes and latencies of sent transactions, by kind of transaction
+#[derive(Clone)]
+pub struct TransactionMetrics {
+    success: IntCounterVec,
+    failure: IntCounterVec,
+    duration: HistogramVec,
+}
+
+impl TransactionMetrics {
+    pub fn new(metrics: &Metrics) -> Self {
+        Self {
+            success: metrics.register_counter_vec(
+                "transactions_success",
+                "Transactions that were sent successfully",
+                &["kind"],
+            ),
+            failure: metrics.register_counter_vec(
+                "transactions_failure",
+                "Transactions that failed to send or confirm",
+                &["kind"],
+            ),
+            duration: metrics.register_histogram_vec(
+                "transaction_duration_seconds",
+                "Time from sending a transaction until it was confirmed or failed",
+                &["kind"],
+                vec![0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 15.0, 20.0, 30.0, 60.0],
+            ),
+        }
+    }
+
+    /// Records a transaction that was started at `started` and had `result`
+    pub fn record<T, E>(&self, kind: &str, started: Instant, result: &Result<T, E>) {
+        self.duration
+            .with_label_values(&[kind])
+            .observe(started.elapsed().as_secs_f64());
+        let counter = if result.is_ok() {
+            &self.success
+        } else {
+            &self.failure
+        };
+        counter.with_label_values(&[kind]).inc();
+    }
+}
@@ -39,4 +39,6 @@ pub struct OpenbookV2CloseOpenOrders<'info> {
     #[account(mut)]
     /// CHECK: target for account rent needs no checks
     pub sol_destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
@@ -3,7 +3,6 @@ use anchor_lang::prelude::*;
 use crate::state::*;
 
 #[derive(Accounts)]
-#[instruction(market_index: OpenbookV2MarketIndex)]
 pub struct OpenbookV2EditMarket<'info> {
     #[account(
         constraint = group.load()?.openbook_v2_supported(),
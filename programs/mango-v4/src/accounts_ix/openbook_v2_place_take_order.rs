@@ -52,13 +52,6 @@ pub struct OpenbookV2PlaceTakeOrder<'info> {
 
     #[account(mut)]
     /// CHECK: Validated by the openbook_v2 cpi call
-    pub market_request_queue: UncheckedAccount<'info>,
-
-    #[account(
-        mut,
-        constraint = market_base_vault.mint == payer_vault.mint,
-    )]
-    /// CHECK: Validated by the openbook_v2 cpi call
     pub market_base_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(mut)]
@@ -81,5 +74,15 @@ pub struct OpenbookV2PlaceTakeOrder<'info> {
     #[account(address = payer_bank.load()?.oracle)]
     pub payer_oracle: UncheckedAccount<'info>,
 
+    /// The bank that receives the funds from the order
+    // token_index and receiver_bank.vault == receiver_vault is validated inline at #3
+    #[account(mut, has_one = group)]
+    pub receiver_bank: AccountLoader<'info, Bank>,
+
+    /// The bank vault that receives the funds from the order
+    #[account(mut)]
+    pub receiver_vault: Box<Account<'info, TokenAccount>>,
+
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,25 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PerpConditionalOrderCreate<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::PerpConditionalOrderCreate) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen,
+        constraint = account.load()?.is_owner_or_delegate(authority.key()),
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+    pub authority: Signer<'info>,
+
+    #[account(
+        has_one = group,
+    )]
+    pub perp_market: AccountLoader<'info, PerpMarket>,
+}
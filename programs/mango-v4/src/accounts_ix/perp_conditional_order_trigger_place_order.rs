@@ -0,0 +1,49 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Places a PlaceOrder perp conditional order of `account` into the book.
+///
+/// The remaining accounts are the health accounts of `account`, like for PerpPlaceOrder.
+#[derive(Accounts)]
+pub struct PerpConditionalOrderTriggerPlaceOrder<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::PerpConditionalOrderTrigger) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = triggerer.load()?.is_operational() @ MangoError::AccountIsFrozen,
+        constraint = triggerer.load()?.is_owner_or_delegate(triggerer_authority.key()),
+    )]
+    pub triggerer: AccountLoader<'info, MangoAccountFixed>,
+    pub triggerer_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+        has_one = oracle,
+    )]
+    pub perp_market: AccountLoader<'info, PerpMarket>,
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
+    pub oracle: UncheckedAccount<'info>,
+}
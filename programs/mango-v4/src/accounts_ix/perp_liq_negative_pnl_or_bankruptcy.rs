@@ -51,14 +51,16 @@ pub struct PerpLiqNegativePnlOrBankruptcy<'info> {
     #[account(address = settle_bank.load()?.oracle)]
     pub settle_oracle: UncheckedAccount<'info>,
 
-    // future: this would be an insurance fund vault specific to a
-    // trustless token, separate from the shared one on the group
+    // the group insurance fund vault, used after any token insurance fund
     #[account(mut)]
     pub insurance_vault: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
 }
 
+// Remaining accounts:
+// - if the settle bank has a token insurance fund: the TokenInsuranceFund and its vault (writable)
+// - merged health accounts for liqor+liqee
 #[derive(Accounts)]
 pub struct PerpLiqNegativePnlOrBankruptcyV2<'info> {
     #[account(
@@ -107,8 +109,7 @@ pub struct PerpLiqNegativePnlOrBankruptcyV2<'info> {
     #[account(address = settle_bank.load()?.oracle)]
     pub settle_oracle: UncheckedAccount<'info>,
 
-    // future: this would be an insurance fund vault specific to a
-    // trustless token, separate from the shared one on the group
+    // the group insurance fund vault, used after any token insurance fund
     #[account(mut)]
     pub insurance_vault: Account<'info, TokenAccount>,
 
@@ -23,7 +23,9 @@ pub enum Serum3OrderType {
     ImmediateOrCancel = 1,
     PostOnly = 2,
 }
-#[derive(Clone, Copy, TryFromPrimitive, IntoPrimitive, AnchorSerialize, AnchorDeserialize)]
+#[derive(
+    Clone, Copy, Debug, TryFromPrimitive, IntoPrimitive, AnchorSerialize, AnchorDeserialize,
+)]
 #[repr(u8)]
 
 pub enum Serum3Side {
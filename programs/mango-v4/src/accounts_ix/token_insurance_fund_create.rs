@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::error::*;
+use crate::state::*;
+
+/// In addition to these accounts, all banks must be passed as remaining_accounts
+/// in MintInfo order.
+#[derive(Accounts)]
+#[instruction(token_index: TokenIndex)]
+pub struct TokenInsuranceFundCreate<'info> {
+    #[account(
+        has_one = admin,
+        constraint = group.load()?.is_ix_enabled(IxGate::TokenInsuranceFundCreate) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = group,
+        has_one = mint,
+        constraint = mint_info.load()?.token_index == token_index,
+    )]
+    pub mint_info: AccountLoader<'info, MintInfo>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        // using the token_index in this seed guards against creating two funds for a token
+        seeds = [b"TokenInsuranceFund".as_ref(), group.key().as_ref(), &token_index.to_le_bytes()],
+        bump,
+        payer = payer,
+        space = 8 + std::mem::size_of::<TokenInsuranceFund>(),
+    )]
+    pub insurance_fund: AccountLoader<'info, TokenInsuranceFund>,
+
+    #[account(
+        init,
+        seeds = [b"TokenInsuranceFundVault".as_ref(), group.key().as_ref(), &token_index.to_le_bytes()],
+        bump,
+        token::authority = group,
+        token::mint = mint,
+        payer = payer
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
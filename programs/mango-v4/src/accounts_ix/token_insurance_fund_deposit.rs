@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::error::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct TokenInsuranceFundDeposit<'info> {
+    #[account(
+        has_one = admin,
+        constraint = group.load()?.is_ix_enabled(IxGate::TokenInsuranceFundDeposit) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = group,
+        has_one = vault,
+    )]
+    pub insurance_fund: AccountLoader<'info, TokenInsuranceFund>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+    pub token_authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> TokenInsuranceFundDeposit<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.token_account.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.token_authority.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
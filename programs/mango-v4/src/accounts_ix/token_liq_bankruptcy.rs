@@ -7,6 +7,8 @@ use crate::error::*;
 use crate::state::*;
 
 // Remaining accounts:
+// - if the liab bank has a token insurance fund: the TokenInsuranceFund, its vault and
+//   the vault of the first liab bank (all writable)
 // - all banks for liab_mint_info (writable)
 // - merged health accounts for liqor+liqee
 #[derive(Accounts)]
@@ -43,8 +45,7 @@ pub struct TokenLiqBankruptcy<'info> {
     // better name would be "insurance_bank_vault"
     pub quote_vault: Account<'info, TokenAccount>,
 
-    // future: this would be an insurance fund vault specific to a
-    // trustless token, separate from the shared one on the group
+    // the group insurance fund vault, used after any token insurance fund
     #[account(mut)]
     pub insurance_vault: Account<'info, TokenAccount>,
 
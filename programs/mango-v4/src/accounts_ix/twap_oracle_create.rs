@@ -0,0 +1,33 @@
+use crate::{error::MangoError, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct TwapOracleCreate<'info> {
+    #[account(
+        has_one = admin,
+        constraint = group.load()?.is_ix_enabled(IxGate::TwapOracleCreate) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        init,
+        seeds = [b"TwapOracle".as_ref(), group.key().as_ref(), mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + std::mem::size_of::<TwapOracle>(),
+    )]
+    pub oracle: AccountLoader<'info, TwapOracle>,
+
+    pub admin: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Validated in the instruction: must be a raydium clmm pool containing mint
+    pub pool: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
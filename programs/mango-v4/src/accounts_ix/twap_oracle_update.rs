@@ -0,0 +1,21 @@
+use crate::{error::MangoError, state::*};
+use anchor_lang::prelude::*;
+
+/// Permissionless: samples the pool price into the oracle's average
+#[derive(Accounts)]
+pub struct TwapOracleUpdate<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::TwapOracleUpdate) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = pool,
+    )]
+    pub oracle: AccountLoader<'info, TwapOracle>,
+
+    /// CHECK: Must be the pool stored on the oracle, owner checked when reading
+    pub pool: UncheckedAccount<'info>,
+}
@@ -105,6 +105,24 @@ pub enum MangoError {
     WouldSelfTrade,
     #[msg("conditional token swap price is not in execution range")]
     TokenConditionalSwapPriceNotInRange,
+    #[msg("no free openbook v2 open orders index")]
+    NoFreeOpenbookV2OpenOrdersIndex,
+    #[msg("openbook v2 open orders exist already")]
+    OpenbookV2OpenOrdersExistAlready,
+    #[msg("there are open or unsettled openbook v2 orders")]
+    HasOpenOrUnsettledOpenbookV2Orders,
+    #[msg("perp conditional order price not in range")]
+    PerpConditionalOrderPriceNotInRange,
+    #[msg("conditional token swap interval amount is exhausted")]
+    TokenConditionalSwapIntervalNotReady,
+    #[msg("the perp position is isolated, which this instruction does not support")]
+    PerpPositionIsIsolated,
+    #[msg("the perp position is not isolated")]
+    PerpPositionIsNotIsolated,
+    #[msg("isolated perp position health must be positive or not decrease")]
+    IsolatedPerpHealthMustBePositiveOrIncrease,
+    #[msg("isolated perp position is not liquidatable")]
+    IsolatedPerpPositionNotLiquidatable,
 }
 
 impl MangoError {
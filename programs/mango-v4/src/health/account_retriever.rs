@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 use anchor_lang::ZeroCopy;
 
 use fixed::types::I80F48;
+use itertools::Itertools;
+use openbook_v2::state::OpenOrdersAccount;
 use serum_dex::state::OpenOrders;
 
 use std::cell::Ref;
@@ -9,6 +11,7 @@ use std::collections::HashMap;
 
 use crate::accounts_zerocopy::*;
 use crate::error::*;
+use crate::openbook_v2_cpi;
 use crate::serum3_cpi;
 use crate::state::{Bank, MangoAccountRef, PerpMarket, PerpMarketIndex, TokenIndex};
 
@@ -32,6 +35,12 @@ pub trait AccountRetriever {
 
     fn serum_oo(&self, active_serum_oo_index: usize, key: &Pubkey) -> Result<&OpenOrders>;
 
+    fn openbook_oo(
+        &self,
+        active_openbook_oo_index: usize,
+        key: &Pubkey,
+    ) -> Result<&OpenOrdersAccount>;
+
     fn perp_market_and_oracle_price(
         &self,
         group: &Pubkey,
@@ -47,12 +56,14 @@ pub trait AccountRetriever {
 /// 3. PerpMarket accounts, in the order of account.perps.iter_active_accounts()
 /// 4. PerpMarket oracle accounts, in the order of the perp market accounts
 /// 5. serum3 OpenOrders accounts, in the order of account.serum3.iter_active()
+/// 6. openbook v2 OpenOrders accounts, in the order of account.openbook_v2.iter_active()
 pub struct FixedOrderAccountRetriever<T: KeyedAccountReader> {
     pub ais: Vec<T>,
     pub n_banks: usize,
     pub n_perps: usize,
     pub begin_perp: usize,
     pub begin_serum3: usize,
+    pub begin_openbook_v2: usize,
     pub staleness_slot: Option<u64>,
 }
 
@@ -63,21 +74,26 @@ pub fn new_fixed_order_account_retriever<'a, 'info>(
     let active_token_len = account.active_token_positions().count();
     let active_serum3_len = account.active_serum3_orders().count();
     let active_perp_len = account.active_perp_positions().count();
+    let active_openbook_v2_len = account.active_openbook_v2_orders().count();
     let expected_ais = active_token_len * 2 // banks + oracles
         + active_perp_len * 2 // PerpMarkets + Oracles
-        + active_serum3_len; // open_orders
+        + active_serum3_len // serum3 open_orders
+        + active_openbook_v2_len; // openbook v2 open_orders
     require_msg_typed!(ais.len() == expected_ais, MangoError::InvalidHealthAccountCount,
-        "received {} accounts but expected {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} serum3 oos)",
+        "received {} accounts but expected {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} serum3 oos, {} openbook v2 oos)",
         ais.len(), expected_ais,
-        active_token_len, active_token_len, active_perp_len, active_perp_len, active_serum3_len
+        active_token_len, active_token_len, active_perp_len, active_perp_len, active_serum3_len,
+        active_openbook_v2_len
     );
 
+    let begin_serum3 = active_token_len * 2 + active_perp_len * 2;
     Ok(FixedOrderAccountRetriever {
         ais: AccountInfoRef::borrow_slice(ais)?,
         n_banks: active_token_len,
         n_perps: active_perp_len,
         begin_perp: active_token_len * 2,
-        begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_serum3,
+        begin_openbook_v2: begin_serum3 + active_serum3_len,
         staleness_slot: Some(Clock::get()?.slot),
     })
 }
@@ -191,6 +207,26 @@ impl<T: KeyedAccountReader> AccountRetriever for FixedOrderAccountRetriever<T> {
             )
         })
     }
+
+    fn openbook_oo(
+        &self,
+        active_openbook_oo_index: usize,
+        key: &Pubkey,
+    ) -> Result<&OpenOrdersAccount> {
+        let openbook_oo_index = self.begin_openbook_v2 + active_openbook_oo_index;
+        let ai = &self.ais[openbook_oo_index];
+        (|| {
+            require_keys_eq!(*key, *ai.key());
+            openbook_v2_cpi::load_open_orders(ai)
+        })()
+        .with_context(|| {
+            format!(
+                "loading openbook open orders with health account index {}, passed account {}",
+                openbook_oo_index,
+                ai.key(),
+            )
+        })
+    }
 }
 
 pub struct ScannedBanksAndOracles<'a, 'info> {
@@ -265,6 +301,7 @@ impl<'a, 'info> ScannedBanksAndOracles<'a, 'info> {
 /// - an unknown number of PerpMarket accounts
 /// - the same number of oracles in the same order as the perp markets
 /// - an unknown number of serum3 OpenOrders accounts
+/// - the openbook v2 OpenOrders accounts of the mango accounts whose health is computed
 /// and retrieves accounts needed for the health computation by doing a linear
 /// scan for each request.
 pub struct ScanningAccountRetriever<'a, 'info> {
@@ -272,6 +309,7 @@ pub struct ScanningAccountRetriever<'a, 'info> {
     perp_markets: Vec<AccountInfoRef<'a, 'info>>,
     perp_oracles: Vec<AccountInfoRef<'a, 'info>>,
     serum3_oos: Vec<AccountInfoRef<'a, 'info>>,
+    openbook_v2_oos: Vec<AccountInfoRef<'a, 'info>>,
     perp_index_map: HashMap<PerpMarketIndex, usize>,
 }
 
@@ -291,13 +329,20 @@ fn can_load_as<'a, T: ZeroCopy + Owner>(
 }
 
 impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
-    pub fn new(ais: &'a [AccountInfo<'info>], group: &Pubkey) -> Result<Self> {
-        Self::new_with_staleness(ais, group, Some(Clock::get()?.slot))
+    /// `accounts` are the mango accounts whose health will be computed. They determine
+    /// how many of the trailing `ais` are openbook v2 open orders accounts.
+    pub fn new(
+        ais: &'a [AccountInfo<'info>],
+        group: &Pubkey,
+        accounts: &[&MangoAccountRef],
+    ) -> Result<Self> {
+        Self::new_with_staleness(ais, group, accounts, Some(Clock::get()?.slot))
     }
 
     pub fn new_with_staleness(
         ais: &'a [AccountInfo<'info>],
         group: &Pubkey,
+        accounts: &[&MangoAccountRef],
         staleness_slot: Option<u64>,
     ) -> Result<Self> {
         // find all Bank accounts
@@ -351,6 +396,34 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
         let perp_oracles_start = perps_start + n_perps;
         let serum3_start = perp_oracles_start + n_perps;
 
+        // openbook v2 open orders accounts follow the serum3 ones, like in
+        // new_fixed_order_account_retriever their number is known from the accounts
+        let n_openbook_v2_oos = accounts
+            .iter()
+            .flat_map(|account| account.active_openbook_v2_orders().map(|oo| oo.open_orders))
+            .unique()
+            .count();
+        require_msg_typed!(
+            ais.len() >= serum3_start + n_openbook_v2_oos,
+            MangoError::InvalidHealthAccountCount,
+            "received {} accounts but expected at least {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} openbook v2 oos)",
+            ais.len(),
+            serum3_start + n_openbook_v2_oos,
+            n_banks,
+            n_banks,
+            n_perps,
+            n_perps,
+            n_openbook_v2_oos
+        );
+        let openbook_v2_start = ais.len() - n_openbook_v2_oos;
+        for (i, ai) in ais[openbook_v2_start..].iter().enumerate() {
+            require_msg!(
+                ai.owner == &openbook_v2::id(),
+                "health account index {} must be an openbook v2 open orders account",
+                openbook_v2_start + i
+            );
+        }
+
         Ok(Self {
             banks_and_oracles: ScannedBanksAndOracles {
                 banks: AccountInfoRefMut::borrow_slice(&ais[..n_banks])?,
@@ -360,7 +433,8 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
             },
             perp_markets: AccountInfoRef::borrow_slice(&ais[perps_start..perp_oracles_start])?,
             perp_oracles: AccountInfoRef::borrow_slice(&ais[perp_oracles_start..serum3_start])?,
-            serum3_oos: AccountInfoRef::borrow_slice(&ais[serum3_start..])?,
+            serum3_oos: AccountInfoRef::borrow_slice(&ais[serum3_start..openbook_v2_start])?,
+            openbook_v2_oos: AccountInfoRef::borrow_slice(&ais[openbook_v2_start..])?,
             perp_index_map,
         })
     }
@@ -408,6 +482,15 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
         serum3_cpi::load_open_orders(oo)
     }
 
+    pub fn scanned_openbook_oo(&self, key: &Pubkey) -> Result<&OpenOrdersAccount> {
+        let oo = self
+            .openbook_v2_oos
+            .iter()
+            .find(|ai| ai.key == key)
+            .ok_or_else(|| error_msg!("no openbook v2 open orders for key {}", key))?;
+        openbook_v2_cpi::load_open_orders(oo)
+    }
+
     pub fn into_banks_and_oracles(self) -> ScannedBanksAndOracles<'a, 'info> {
         self.banks_and_oracles
     }
@@ -435,15 +518,29 @@ impl<'a, 'info> AccountRetriever for ScanningAccountRetriever<'a, 'info> {
     fn serum_oo(&self, _account_index: usize, key: &Pubkey) -> Result<&OpenOrders> {
         self.scanned_serum_oo(key)
     }
+
+    fn openbook_oo(&self, _account_index: usize, key: &Pubkey) -> Result<&OpenOrdersAccount> {
+        self.scanned_openbook_oo(key)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::test::*;
     use super::*;
+    use crate::state::{MangoAccount, MangoAccountValue};
     use serum_dex::state::OpenOrders;
     use std::convert::identity;
 
+    fn mock_account_with_openbook_v2_oo(open_orders: Pubkey) -> MangoAccountValue {
+        let mut buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        buffer.extend_from_slice(&[0u8; 512]);
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+        account.expand_dynamic_content(3, 5, 4, 6, 0, 2, 0).unwrap();
+        account.create_openbook_v2_orders(2).unwrap().open_orders = open_orders;
+        account
+    }
+
     #[test]
     fn test_scanning_account_retriever() {
         let oracle1_price = 1.0;
@@ -461,6 +558,9 @@ mod tests {
         let oo1key = oo1.pubkey;
         oo1.data().native_pc_total = 20;
 
+        let mut oo2 = TestAccount::<OpenOrdersAccount>::new_zeroed();
+        let oo2key = oo2.pubkey;
+
         let mut perp1 = mock_perp_market(
             group,
             oracle2.pubkey,
@@ -492,10 +592,13 @@ mod tests {
             oracle2_account_info,
             oracle1_account_info,
             oo1.as_account_info(),
+            oo2.as_account_info(),
         ];
 
+        let account = mock_account_with_openbook_v2_oo(oo2key);
         let mut retriever =
-            ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
 
         assert_eq!(retriever.banks_and_oracles.banks.len(), 3);
         assert_eq!(retriever.banks_and_oracles.index_map.len(), 3);
@@ -504,6 +607,7 @@ mod tests {
         assert_eq!(retriever.perp_oracles.len(), 2);
         assert_eq!(retriever.perp_index_map.len(), 2);
         assert_eq!(retriever.serum3_oos.len(), 1);
+        assert_eq!(retriever.openbook_v2_oos.len(), 1);
 
         {
             let (b1, o1, opt_b2o2) = retriever.banks_mut_and_oracles(1, 4).unwrap();
@@ -543,6 +647,11 @@ mod tests {
 
         assert!(retriever.serum_oo(1, &Pubkey::default()).is_err());
 
+        retriever.openbook_oo(0, &oo2key).unwrap();
+        // the open orders accounts are not mixed up
+        assert!(retriever.openbook_oo(0, &oo1key).is_err());
+        assert!(retriever.serum_oo(0, &oo2key).is_err());
+
         let (perp, oracle_price) = retriever
             .perp_market_and_oracle_price(&group, 0, 9)
             .unwrap();
@@ -559,4 +668,67 @@ mod tests {
             .perp_market_and_oracle_price(&group, 1, 5)
             .is_err());
     }
+
+    #[test]
+    fn test_scanning_account_retriever_openbook_v2_split() {
+        let group = Pubkey::new_unique();
+        let (mut bank, mut oracle) = mock_bank_and_oracle(group, 1, 1.0, 0.2, 0.1);
+        let mut serum_oo = TestAccount::<OpenOrders>::new_zeroed();
+        let mut openbook_oo = TestAccount::<OpenOrdersAccount>::new_zeroed();
+        let account = mock_account_with_openbook_v2_oo(openbook_oo.pubkey);
+
+        // the trailing account is the account's openbook v2 open orders
+        {
+            let ais = vec![
+                bank.as_account_info(),
+                oracle.as_account_info(),
+                serum_oo.as_account_info(),
+                openbook_oo.as_account_info(),
+            ];
+            let retriever = ScanningAccountRetriever::new_with_staleness(
+                &ais,
+                &group,
+                &[&account.borrow()],
+                None,
+            )
+            .unwrap();
+            assert_eq!(retriever.serum3_oos.len(), 1);
+            assert_eq!(retriever.openbook_v2_oos.len(), 1);
+
+            // without openbook v2 positions, all trailing accounts are serum3 open orders
+            let retriever =
+                ScanningAccountRetriever::new_with_staleness(&ais[..3], &group, &[], None).unwrap();
+            assert_eq!(retriever.serum3_oos.len(), 1);
+            assert_eq!(retriever.openbook_v2_oos.len(), 0);
+        }
+
+        // a non-openbook account in the openbook v2 slot is rejected
+        {
+            let ais = vec![
+                bank.as_account_info(),
+                oracle.as_account_info(),
+                openbook_oo.as_account_info(),
+                serum_oo.as_account_info(),
+            ];
+            assert!(ScanningAccountRetriever::new_with_staleness(
+                &ais,
+                &group,
+                &[&account.borrow()],
+                None
+            )
+            .is_err());
+        }
+
+        // missing open orders accounts are rejected
+        {
+            let ais = vec![bank.as_account_info(), oracle.as_account_info()];
+            assert!(ScanningAccountRetriever::new_with_staleness(
+                &ais,
+                &group,
+                &[&account.borrow()],
+                None
+            )
+            .is_err());
+        }
+    }
 }
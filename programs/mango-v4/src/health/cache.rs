@@ -11,7 +11,7 @@
  * new_health_cache() function. With it, the different health types can be
  * computed.
  *
- * The HealthCache holds the data it needs in TokenInfo, Serum3Info and PerpInfo.
+ * The HealthCache holds the data it needs in TokenInfo, Serum3Info, OpenbookV2Info and PerpInfo.
  */
 
 use anchor_lang::prelude::*;
@@ -19,8 +19,10 @@ use anchor_lang::prelude::*;
 use fixed::types::I80F48;
 
 use crate::error::*;
+use crate::openbook_v2_cpi::OpenOrdersNative;
 use crate::state::{
-    Bank, MangoAccountRef, PerpMarket, PerpMarketIndex, PerpPosition, Serum3MarketIndex, TokenIndex,
+    Bank, MangoAccountRef, OpenbookV2MarketIndex, PerpMarket, PerpMarketIndex, PerpPosition,
+    Serum3MarketIndex, TokenIndex,
 };
 
 use super::*;
@@ -247,7 +249,17 @@ pub struct Serum3Info {
     pub has_zero_funds: bool,
 }
 
-impl Serum3Info {
+/// Reserved funds on a spot open orders account
+///
+/// Serum3Info and OpenbookV2Info contribute to health in the same way.
+pub(crate) trait SpotInfo {
+    fn reserved_base(&self) -> I80F48;
+    fn reserved_quote(&self) -> I80F48;
+
+    /// Index into TokenInfos _not_ a TokenIndex
+    fn base_info_index(&self) -> usize;
+    fn quote_info_index(&self) -> usize;
+
     #[inline(always)]
     fn all_reserved_as_base(
         &self,
@@ -258,7 +270,7 @@ impl Serum3Info {
         let quote_asset = quote_info.prices.asset(health_type);
         let base_liab = base_info.prices.liab(health_type);
         // OPTIMIZATION: These divisions can be extremely expensive (up to 5k CU each)
-        self.reserved_base + self.reserved_quote * quote_asset / base_liab
+        self.reserved_base() + self.reserved_quote() * quote_asset / base_liab
     }
 
     #[inline(always)]
@@ -271,7 +283,7 @@ impl Serum3Info {
         let base_asset = base_info.prices.asset(health_type);
         let quote_liab = quote_info.prices.liab(health_type);
         // OPTIMIZATION: These divisions can be extremely expensive (up to 5k CU each)
-        self.reserved_quote + self.reserved_base * base_asset / quote_liab
+        self.reserved_quote() + self.reserved_base() * base_asset / quote_liab
     }
 
     /// Compute the health contribution from active open orders.
@@ -313,8 +325,8 @@ impl Serum3Info {
             return I80F48::ZERO;
         }
 
-        let base_info = &token_infos[self.base_info_index];
-        let quote_info = &token_infos[self.quote_info_index];
+        let base_info = &token_infos[self.base_info_index()];
+        let quote_info = &token_infos[self.quote_info_index()];
 
         // How much would health increase if the reserved balance were applied to the passed
         // token info?
@@ -346,20 +358,83 @@ impl Serum3Info {
 
         let health_base = compute_health_effect(
             base_info,
-            &token_balances[self.base_info_index],
-            &token_max_reserved[self.base_info_index],
+            &token_balances[self.base_info_index()],
+            &token_max_reserved[self.base_info_index()],
             market_reserved.all_reserved_as_base,
         );
         let health_quote = compute_health_effect(
             quote_info,
-            &token_balances[self.quote_info_index],
-            &token_max_reserved[self.quote_info_index],
+            &token_balances[self.quote_info_index()],
+            &token_max_reserved[self.quote_info_index()],
             market_reserved.all_reserved_as_quote,
         );
         health_base.min(health_quote)
     }
 }
 
+impl SpotInfo for Serum3Info {
+    #[inline(always)]
+    fn reserved_base(&self) -> I80F48 {
+        self.reserved_base
+    }
+
+    #[inline(always)]
+    fn reserved_quote(&self) -> I80F48 {
+        self.reserved_quote
+    }
+
+    #[inline(always)]
+    fn base_info_index(&self) -> usize {
+        self.base_info_index
+    }
+
+    #[inline(always)]
+    fn quote_info_index(&self) -> usize {
+        self.quote_info_index
+    }
+}
+
+/// Information about reserved funds on openbook v2 open orders accounts.
+///
+/// Like for Serum3Info, the free funds are added directly to the token info.
+#[derive(Clone, AnchorDeserialize, AnchorSerialize, Debug)]
+pub struct OpenbookV2Info {
+    // reserved amounts as stored on the open orders
+    pub reserved_base: I80F48,
+    pub reserved_quote: I80F48,
+
+    // Index into TokenInfos _not_ a TokenIndex
+    pub base_info_index: usize,
+    pub quote_info_index: usize,
+
+    pub market_index: OpenbookV2MarketIndex,
+
+    /// The open orders account has no free or reserved funds
+    pub has_zero_funds: bool,
+}
+
+impl SpotInfo for OpenbookV2Info {
+    #[inline(always)]
+    fn reserved_base(&self) -> I80F48 {
+        self.reserved_base
+    }
+
+    #[inline(always)]
+    fn reserved_quote(&self) -> I80F48 {
+        self.reserved_quote
+    }
+
+    #[inline(always)]
+    fn base_info_index(&self) -> usize {
+        self.base_info_index
+    }
+
+    #[inline(always)]
+    fn quote_info_index(&self) -> usize {
+        self.quote_info_index
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct Serum3Reserved {
     /// base tokens when the serum3info.reserved_quote get converted to base and added to reserved_base
@@ -372,6 +447,10 @@ pub(crate) struct Serum3Reserved {
 ///
 /// Perp markets affect account health indirectly, though the token balance in the
 /// perp market's settle token. See `effective_token_balances()`.
+///
+/// Perp positions and oracles use the perp quote currency, but the quote amounts and
+/// prices stored here are converted to settle token native units using the settle
+/// token's oracle price.
 #[derive(Clone, AnchorDeserialize, AnchorSerialize, Debug)]
 pub struct PerpInfo {
     pub perp_market_index: PerpMarketIndex,
@@ -386,24 +465,39 @@ pub struct PerpInfo {
     pub base_lots: i64,
     pub bids_base_lots: i64,
     pub asks_base_lots: i64,
-    // in health-reference-token native units, no asset/liab factor needed
+    // in settle token native units, no asset/liab factor needed
     pub quote: I80F48,
+    // in settle token native per base native
     pub base_prices: Prices,
     pub has_open_orders: bool,
     pub has_open_fills: bool,
+    /// Isolated positions don't contribute to the account's cross-margined health,
+    /// see HealthCache::isolated_perp_health()
+    pub isolated: bool,
+    // in settle token native units, see Bank::isolated_collateral_native()
+    pub isolated_collateral: I80F48,
 }
 
 impl PerpInfo {
+    /// The base_prices must already be in settle token units. The perp position's quote
+    /// amounts get converted with the settle token oracle price.
+    ///
+    /// The isolated_collateral is in settle token native units.
     fn new(
         perp_position: &PerpPosition,
         perp_market: &PerpMarket,
         base_prices: Prices,
+        settle_token_price: I80F48,
+        isolated_collateral: I80F48,
     ) -> Result<Self> {
+        require_gt!(settle_token_price, 0);
         let base_lots = perp_position.base_position_lots() + perp_position.taker_base_lots;
 
         let unsettled_funding = perp_position.unsettled_funding(perp_market);
         let taker_quote = I80F48::from(perp_position.taker_quote_lots * perp_market.quote_lot_size);
-        let quote_current = perp_position.quote_position_native() - unsettled_funding + taker_quote;
+        let quote_current = (perp_position.quote_position_native() - unsettled_funding
+            + taker_quote)
+            / settle_token_price;
 
         Ok(Self {
             perp_market_index: perp_market.perp_market_index,
@@ -422,6 +516,8 @@ impl PerpInfo {
             base_prices,
             has_open_orders: perp_position.has_open_orders(),
             has_open_fills: perp_position.has_open_taker_fills(),
+            isolated: perp_position.is_isolated(),
+            isolated_collateral,
         })
     }
 
@@ -540,6 +636,7 @@ pub struct HealthCache {
     pub(crate) serum3_infos: Vec<Serum3Info>,
     pub(crate) perp_infos: Vec<PerpInfo>,
     pub(crate) being_liquidated: bool,
+    pub(crate) openbook_v2_infos: Vec<OpenbookV2Info>,
 }
 
 impl HealthCache {
@@ -632,7 +729,7 @@ impl HealthCache {
                 token_info.balance_spot,
             );
 
-            for perp_info in self.perp_infos.iter() {
+            for perp_info in self.cross_perp_infos() {
                 if perp_info.settle_token_index != token_info.token_index {
                     continue;
                 }
@@ -672,7 +769,7 @@ impl HealthCache {
 
         let token_balances = self.effective_token_balances(health_type);
         let (token_max_reserved, serum3_reserved) = self.compute_serum3_reservations(health_type);
-        for (serum3_info, reserved) in self.serum3_infos.iter().zip(serum3_reserved.iter()) {
+        for (serum3_info, reserved) in self.spot_infos().zip(serum3_reserved.iter()) {
             let contrib = serum3_info.health_contribution(
                 health_type,
                 &self.token_infos,
@@ -704,17 +801,21 @@ impl HealthCache {
             }
         }
 
-        for serum_info in self.serum3_infos.iter() {
-            let quote = &self.token_infos[serum_info.quote_info_index];
-            let base = &self.token_infos[serum_info.base_info_index];
-            assets += serum_info.reserved_base * base.prices.oracle;
-            assets += serum_info.reserved_quote * quote.prices.oracle;
+        for spot_info in self.spot_infos() {
+            let quote = &self.token_infos[spot_info.quote_info_index()];
+            let base = &self.token_infos[spot_info.base_info_index()];
+            assets += spot_info.reserved_base() * base.prices.oracle;
+            assets += spot_info.reserved_quote() * quote.prices.oracle;
         }
 
         for perp_info in self.perp_infos.iter() {
-            let quote_price = self.token_infos[perp_info.settle_token_index as usize]
+            let quote_price = self
+                .token_info(perp_info.settle_token_index)
+                .unwrap()
                 .prices
                 .oracle;
+            assets += perp_info.isolated_collateral * quote_price;
+
             let quote_position_value = perp_info.quote * quote_price;
             if perp_info.quote.is_negative() {
                 liabs -= quote_position_value;
@@ -814,6 +915,64 @@ impl HealthCache {
         quote_token_index: TokenIndex,
         reserved_quote_change: I80F48,
         free_quote_change: I80F48,
+    ) -> Result<()> {
+        self.adjust_spot_free(
+            base_token_index,
+            free_base_change,
+            quote_token_index,
+            free_quote_change,
+        )?;
+
+        // Apply it to the serum3 info
+        let market_entry = self
+            .serum3_infos
+            .iter_mut()
+            .find(|m| m.market_index == market_index)
+            .ok_or_else(|| error_msg!("serum3 market {} not found", market_index))?;
+        market_entry.reserved_base += reserved_base_change;
+        market_entry.reserved_quote += reserved_quote_change;
+        Ok(())
+    }
+
+    /// Changes the cached user account token and openbook v2 balances.
+    ///
+    /// WARNING: You must also call recompute_token_weights() after all bank
+    /// deposit/withdraw changes!
+    #[allow(clippy::too_many_arguments)]
+    pub fn adjust_openbook_v2_reserved(
+        &mut self,
+        market_index: OpenbookV2MarketIndex,
+        base_token_index: TokenIndex,
+        reserved_base_change: I80F48,
+        free_base_change: I80F48,
+        quote_token_index: TokenIndex,
+        reserved_quote_change: I80F48,
+        free_quote_change: I80F48,
+    ) -> Result<()> {
+        self.adjust_spot_free(
+            base_token_index,
+            free_base_change,
+            quote_token_index,
+            free_quote_change,
+        )?;
+
+        // Apply it to the openbook v2 info
+        let market_entry = self
+            .openbook_v2_infos
+            .iter_mut()
+            .find(|m| m.market_index == market_index)
+            .ok_or_else(|| error_msg!("openbook v2 market {} not found", market_index))?;
+        market_entry.reserved_base += reserved_base_change;
+        market_entry.reserved_quote += reserved_quote_change;
+        Ok(())
+    }
+
+    fn adjust_spot_free(
+        &mut self,
+        base_token_index: TokenIndex,
+        free_base_change: I80F48,
+        quote_token_index: TokenIndex,
+        free_quote_change: I80F48,
     ) -> Result<()> {
         let base_entry_index = self.token_info_index(base_token_index)?;
         let quote_entry_index = self.token_info_index(quote_token_index)?;
@@ -827,15 +986,6 @@ impl HealthCache {
             let quote_entry = &mut self.token_infos[quote_entry_index];
             quote_entry.balance_spot += free_quote_change;
         }
-
-        // Apply it to the serum3 info
-        let market_entry = self
-            .serum3_infos
-            .iter_mut()
-            .find(|m| m.market_index == market_index)
-            .ok_or_else(|| error_msg!("serum3 market {} not found", market_index))?;
-        market_entry.reserved_base += reserved_base_change;
-        market_entry.reserved_quote += reserved_quote_change;
         Ok(())
     }
 
@@ -844,12 +994,36 @@ impl HealthCache {
         perp_position: &PerpPosition,
         perp_market: &PerpMarket,
     ) -> Result<()> {
+        let settle_token_price = self
+            .token_info(perp_market.settle_token_index)?
+            .prices
+            .oracle;
         let perp_entry = self
             .perp_infos
             .iter_mut()
             .find(|m| m.perp_market_index == perp_market.perp_market_index)
             .ok_or_else(|| error_msg!("perp market {} not found", perp_market.perp_market_index))?;
-        *perp_entry = PerpInfo::new(perp_position, perp_market, perp_entry.base_prices.clone())?;
+        *perp_entry = PerpInfo::new(
+            perp_position,
+            perp_market,
+            perp_entry.base_prices.clone(),
+            settle_token_price,
+            perp_entry.isolated_collateral,
+        )?;
+        Ok(())
+    }
+
+    /// Changes the cached isolated collateral of a perp position.
+    ///
+    /// recompute_perp_info() keeps the previous collateral because it has no access
+    /// to the settle bank's deposit index.
+    pub fn set_isolated_collateral(
+        &mut self,
+        perp_market_index: PerpMarketIndex,
+        isolated_collateral: I80F48,
+    ) -> Result<()> {
+        let entry_index = self.perp_info_index(perp_market_index)?;
+        self.perp_infos[entry_index].isolated_collateral = isolated_collateral;
         Ok(())
     }
 
@@ -886,34 +1060,38 @@ impl HealthCache {
         self.serum3_infos.iter().any(|si| !si.has_zero_funds)
     }
 
+    pub fn has_openbook_v2_open_orders_funds(&self) -> bool {
+        self.openbook_v2_infos.iter().any(|oi| !oi.has_zero_funds)
+    }
+
     pub fn has_perp_open_orders(&self) -> bool {
-        self.perp_infos.iter().any(|p| p.has_open_orders)
+        self.cross_perp_infos().any(|p| p.has_open_orders)
     }
 
     pub fn has_perp_base_positions(&self) -> bool {
-        self.perp_infos.iter().any(|p| p.base_lots != 0)
+        self.cross_perp_infos().any(|p| p.base_lots != 0)
     }
 
     pub fn has_perp_open_fills(&self) -> bool {
-        self.perp_infos.iter().any(|p| p.has_open_fills)
+        self.cross_perp_infos().any(|p| p.has_open_fills)
     }
 
     pub fn has_perp_positive_pnl_no_base(&self) -> bool {
-        self.perp_infos
-            .iter()
+        self.cross_perp_infos()
             .any(|p| p.base_lots == 0 && p.quote > 0)
     }
 
     pub fn has_perp_negative_pnl_no_base(&self) -> bool {
-        self.perp_infos
-            .iter()
+        self.cross_perp_infos()
             .any(|p| p.base_lots == 0 && p.quote < 0)
     }
 
     /// Phase1 is spot/perp order cancellation and spot settlement since
     /// neither of these come at a cost to the liqee
     pub fn has_phase1_liquidatable(&self) -> bool {
-        self.has_serum3_open_orders_funds() || self.has_perp_open_orders()
+        self.has_serum3_open_orders_funds()
+            || self.has_openbook_v2_open_orders_funds()
+            || self.has_perp_open_orders()
     }
 
     pub fn require_after_phase1_liquidation(&self) -> Result<()> {
@@ -921,6 +1099,10 @@ impl HealthCache {
             !self.has_serum3_open_orders_funds(),
             MangoError::HasOpenOrUnsettledSerum3Orders
         );
+        require!(
+            !self.has_openbook_v2_open_orders_funds(),
+            MangoError::HasOpenOrUnsettledOpenbookV2Orders
+        );
         require!(!self.has_perp_open_orders(), MangoError::HasOpenPerpOrders);
         Ok(())
     }
@@ -979,27 +1161,106 @@ impl HealthCache {
             && self.has_phase3_liquidatable()
     }
 
+    /// The perp infos that are part of the cross-margined account health
+    fn cross_perp_infos(&self) -> impl Iterator<Item = &PerpInfo> {
+        self.perp_infos.iter().filter(|p| !p.isolated)
+    }
+
+    /// Health of an isolated perp position, in the same units as health().
+    ///
+    /// This is the isolated collateral plus the position's health unsettled pnl, weighted
+    /// like a balance of the settle token. It's fully independent of the rest of the account.
+    pub fn isolated_perp_health(
+        &self,
+        perp_market_index: PerpMarketIndex,
+        health_type: HealthType,
+    ) -> Result<I80F48> {
+        let perp_info = self.perp_info(perp_market_index)?;
+        require!(perp_info.isolated, MangoError::PerpPositionIsNotIsolated);
+        let settle_token_info = self.token_info(perp_info.settle_token_index)?;
+        let balance = perp_info.isolated_collateral + perp_info.health_unsettled_pnl(health_type);
+        Ok(settle_token_info.health_contribution(health_type, balance))
+    }
+
+    /// Init health of the perp position if it is isolated, None otherwise
+    pub fn isolated_perp_init_health_opt(
+        &self,
+        perp_market_index: PerpMarketIndex,
+    ) -> Result<Option<I80F48>> {
+        if !self.perp_info(perp_market_index)?.isolated {
+            return Ok(None);
+        }
+        Ok(Some(self.isolated_perp_health(
+            perp_market_index,
+            HealthType::Init,
+        )?))
+    }
+
+    /// Like MangoAccount::check_health_post(), but for an isolated perp position.
+    ///
+    /// Does nothing if the position is not isolated.
+    pub fn check_isolated_perp_health_post(
+        &self,
+        perp_market_index: PerpMarketIndex,
+        pre_init_health_opt: Option<I80F48>,
+    ) -> Result<()> {
+        let post_init_health = match self.isolated_perp_init_health_opt(perp_market_index)? {
+            Some(health) => health,
+            None => return Ok(()),
+        };
+        msg!(
+            "isolated perp {} post_init_health: {}",
+            perp_market_index,
+            post_init_health
+        );
+        let health_does_not_decrease = pre_init_health_opt
+            .map(|pre| post_init_health >= pre)
+            .unwrap_or(false);
+        require!(
+            post_init_health >= 0 || health_does_not_decrease,
+            MangoError::IsolatedPerpHealthMustBePositiveOrIncrease
+        );
+        Ok(())
+    }
+
+    /// All serum3 infos followed by all openbook v2 infos
+    pub(crate) fn spot_infos(&self) -> impl Iterator<Item = &dyn SpotInfo> {
+        self.serum3_infos
+            .iter()
+            .map(|info| info as &dyn SpotInfo)
+            .chain(
+                self.openbook_v2_infos
+                    .iter()
+                    .map(|info| info as &dyn SpotInfo),
+            )
+    }
+
+    /// Computes the reserved amounts for all spot markets.
+    ///
+    /// The returned Serum3Reserved are in the order of spot_infos(): serum3 markets
+    /// first, then openbook v2 markets.
     pub(crate) fn compute_serum3_reservations(
         &self,
         health_type: HealthType,
     ) -> (Vec<TokenMaxReserved>, Vec<Serum3Reserved>) {
         let mut token_max_reserved = vec![TokenMaxReserved::default(); self.token_infos.len()];
 
-        // For each serum market, compute what happened if reserved_base was converted to quote
+        // For each spot market, compute what happened if reserved_base was converted to quote
         // or reserved_quote was converted to base.
-        let mut serum3_reserved = Vec::with_capacity(self.serum3_infos.len());
+        let mut serum3_reserved =
+            Vec::with_capacity(self.serum3_infos.len() + self.openbook_v2_infos.len());
 
-        for info in self.serum3_infos.iter() {
-            let quote_info = &self.token_infos[info.quote_info_index];
-            let base_info = &self.token_infos[info.base_info_index];
+        for info in self.spot_infos() {
+            let quote_info = &self.token_infos[info.quote_info_index()];
+            let base_info = &self.token_infos[info.base_info_index()];
 
             let all_reserved_as_base =
                 info.all_reserved_as_base(health_type, quote_info, base_info);
             let all_reserved_as_quote =
                 info.all_reserved_as_quote(health_type, quote_info, base_info);
 
-            token_max_reserved[info.base_info_index].max_serum_reserved += all_reserved_as_base;
-            token_max_reserved[info.quote_info_index].max_serum_reserved += all_reserved_as_quote;
+            token_max_reserved[info.base_info_index()].max_serum_reserved += all_reserved_as_base;
+            token_max_reserved[info.quote_info_index()].max_serum_reserved += all_reserved_as_quote;
 
             serum3_reserved.push(Serum3Reserved {
                 all_reserved_as_base,
@@ -1034,7 +1295,7 @@ impl HealthCache {
     ) -> Vec<TokenBalance> {
         let mut token_balances = vec![TokenBalance::default(); self.token_infos.len()];
 
-        for perp_info in self.perp_infos.iter() {
+        for perp_info in self.cross_perp_infos() {
             let settle_token_index = self.token_info_index(perp_info.settle_token_index).unwrap();
             let perp_settle_token = &mut token_balances[settle_token_index];
             let health_unsettled = perp_info.health_unsettled_pnl(health_type);
@@ -1062,7 +1323,7 @@ impl HealthCache {
         }
 
         let (token_max_reserved, serum3_reserved) = self.compute_serum3_reservations(health_type);
-        for (serum3_info, reserved) in self.serum3_infos.iter().zip(serum3_reserved.iter()) {
+        for (serum3_info, reserved) in self.spot_infos().zip(serum3_reserved.iter()) {
             let contrib = serum3_info.health_contribution(
                 health_type,
                 &self.token_infos,
@@ -1129,20 +1390,19 @@ impl HealthCache {
     ) -> Result<I80F48> {
         let target_token_info_index = self.token_info_index(token_index)?;
         let total_reserved = self
-            .serum3_infos
-            .iter()
+            .spot_infos()
             .filter_map(|info| {
-                if info.quote_info_index == target_token_info_index {
+                if info.quote_info_index() == target_token_info_index {
                     Some(info.all_reserved_as_quote(
                         health_type,
-                        &self.token_infos[info.quote_info_index],
-                        &self.token_infos[info.base_info_index],
+                        &self.token_infos[info.quote_info_index()],
+                        &self.token_infos[info.base_info_index()],
                     ))
-                } else if info.base_info_index == target_token_info_index {
+                } else if info.base_info_index() == target_token_info_index {
                     Some(info.all_reserved_as_base(
                         health_type,
-                        &self.token_infos[info.quote_info_index],
-                        &self.token_infos[info.base_info_index],
+                        &self.token_infos[info.quote_info_index()],
+                        &self.token_infos[info.base_info_index()],
                     ))
                 } else {
                     None
@@ -1196,6 +1456,8 @@ fn new_health_cache_impl(
 ) -> Result<HealthCache> {
     // token contribution from token accounts
     let mut token_infos = vec![];
+    // parallel to token_infos, for converting isolated perp collateral
+    let mut deposit_indexes = vec![];
 
     for (i, position) in account.active_token_positions().enumerate() {
         let bank_oracle_result =
@@ -1228,6 +1490,7 @@ fn new_health_cache_impl(
             prices,
             balance_spot: native,
         });
+        deposit_indexes.push(bank.deposit_index);
     }
 
     // Fill the TokenInfo balance with free funds in serum3 oo accounts and build Serum3Infos.
@@ -1264,6 +1527,34 @@ fn new_health_cache_impl(
         });
     }
 
+    // Same for openbook v2 open orders accounts.
+    let mut openbook_v2_infos = vec![];
+    for (i, openbook_account) in account.active_openbook_v2_orders().enumerate() {
+        let oo = retriever.openbook_oo(i, &openbook_account.open_orders)?;
+        let amounts = OpenOrdersNative::from_oo_for_orders(oo, openbook_account);
+
+        // find the TokenInfos for the market's base and quote tokens
+        let base_info_index =
+            find_token_info_index(&token_infos, openbook_account.base_token_index)?;
+        let quote_info_index =
+            find_token_info_index(&token_infos, openbook_account.quote_token_index)?;
+
+        // add the amounts that are freely settleable immediately to token balances
+        let base_info = &mut token_infos[base_info_index];
+        base_info.balance_spot += I80F48::from(amounts.base_free);
+        let quote_info = &mut token_infos[quote_info_index];
+        quote_info.balance_spot += I80F48::from(amounts.quote_free);
+
+        openbook_v2_infos.push(OpenbookV2Info {
+            reserved_base: I80F48::from(amounts.base_reserved),
+            reserved_quote: I80F48::from(amounts.quote_reserved),
+            base_info_index,
+            quote_info_index,
+            market_index: openbook_account.market_index,
+            has_zero_funds: amounts.has_zero_funds(),
+        });
+    }
+
     // health contribution from perp accounts
     let mut perp_infos = Vec::with_capacity(account.active_perp_positions().count());
     for (i, perp_position) in account.active_perp_positions().enumerate() {
@@ -1272,13 +1563,23 @@ fn new_health_cache_impl(
             i,
             perp_position.market_index,
         )?;
+
+        // Perp prices are in the perp quote currency, health needs them in settle token units
+        let settle_info_index =
+            find_token_info_index(&token_infos, perp_market.settle_token_index)?;
+        let settle_token_price = token_infos[settle_info_index].prices.oracle;
+        require_gt!(settle_token_price, 0);
+        let isolated_collateral =
+            perp_position.isolated_collateral_indexed * deposit_indexes[settle_info_index];
         perp_infos.push(PerpInfo::new(
             perp_position,
             perp_market,
             Prices {
-                oracle: oracle_price,
-                stable: perp_market.stable_price(),
+                oracle: oracle_price / settle_token_price,
+                stable: perp_market.stable_price() / settle_token_price,
             },
+            settle_token_price,
+            isolated_collateral,
         )?);
     }
 
@@ -1287,6 +1588,7 @@ fn new_health_cache_impl(
         serum3_infos,
         perp_infos,
         being_liquidated: account.fixed.being_liquidated(),
+        openbook_v2_infos,
     })
 }
 
@@ -1388,7 +1690,9 @@ mod tests {
             oo1.as_account_info(),
         ];
 
-        let retriever = ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
 
         // for bank1/oracle1
         // including open orders (scenario: bids execute)
@@ -1405,6 +1709,227 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_health_openbook_v2() {
+        let mut buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        buffer.extend_from_slice(&[0u8; 512]);
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+        account.expand_dynamic_content(3, 5, 4, 6, 0, 2, 0).unwrap();
+
+        let group = Pubkey::new_unique();
+
+        let (mut bank1, mut oracle1) = mock_bank_and_oracle(group, 0, 1.0, 0.2, 0.1);
+        let (mut bank2, mut oracle2) = mock_bank_and_oracle(group, 4, 5.0, 0.5, 0.3);
+        bank1
+            .data()
+            .deposit(
+                account.ensure_token_position(0).unwrap().0,
+                I80F48::from(100),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+        bank2
+            .data()
+            .withdraw_without_fee(
+                account.ensure_token_position(4).unwrap().0,
+                I80F48::from(10),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+
+        let mut oo1 = TestAccount::<openbook_v2::state::OpenOrdersAccount>::new_zeroed();
+        let openbook_account = account.create_openbook_v2_orders(2).unwrap();
+        openbook_account.open_orders = oo1.pubkey;
+        openbook_account.base_token_index = 4;
+        openbook_account.quote_token_index = 0;
+        openbook_account.base_lot_size = 5;
+        openbook_account.quote_lot_size = 2;
+        {
+            let position = &mut oo1.data().position;
+            position.bids_quote_lots = 10;
+            position.asks_base_lots = 3;
+            position.quote_free_native = 1;
+            position.base_free_native = 3;
+            position.referrer_rebates_available = 2;
+        }
+
+        let ais = vec![
+            bank1.as_account_info(),
+            bank2.as_account_info(),
+            oracle1.as_account_info(),
+            oracle2.as_account_info(),
+            oo1.as_account_info(),
+        ];
+
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
+        let health_cache = new_health_cache(&account.borrow(), &retriever).unwrap();
+        assert_eq!(health_cache.openbook_v2_infos.len(), 1);
+        assert!(health_cache.has_openbook_v2_open_orders_funds());
+        assert!(health_cache.has_phase1_liquidatable());
+
+        // for bank1/oracle1
+        // including open orders (scenario: bids execute)
+        let openbook1 = 1.0 + (20.0 + 15.0 * 5.0);
+        let health1 = (100.0 + openbook1) * 0.8;
+        // for bank2/oracle2
+        let health2 = (-10.0 + 3.0) * 5.0 * 1.5;
+        assert!(health_eq(
+            health_cache.health(HealthType::Init),
+            health1 + health2
+        ));
+    }
+
+    // A perp market that settles in a token that isn't worth 1 USD
+    #[test]
+    fn test_health_perp_settle_token() {
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+
+        let group = Pubkey::new_unique();
+
+        let (mut bank1, mut oracle1) = mock_bank_and_oracle(group, 0, 1.0, 0.2, 0.1);
+        let (mut bank2, mut oracle2) = mock_bank_and_oracle(group, 4, 5.0, 0.5, 0.3);
+        let (_bank3, mut perp_oracle) = mock_bank_and_oracle(group, 5, 10.0, 0.0, 0.0);
+        bank1
+            .data()
+            .deposit(
+                account.ensure_token_position(0).unwrap().0,
+                I80F48::from(100),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+        bank2
+            .data()
+            .deposit(
+                account.ensure_token_position(4).unwrap().0,
+                I80F48::from(10),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+
+        let mut perp1 =
+            mock_perp_market(group, perp_oracle.pubkey, 10.0, 9, (0.2, 0.1), (0.05, 0.02));
+        perp1.data().settle_token_index = 4;
+        let perpaccount = account.ensure_perp_position(9, 4).unwrap().0;
+        // 2 lots of 10 base at a price of 7.5 quote
+        perpaccount.record_trade(perp1.data(), 2, -I80F48::from(150));
+
+        let ais = vec![
+            bank1.as_account_info(),
+            bank2.as_account_info(),
+            oracle1.as_account_info(),
+            oracle2.as_account_info(),
+            perp1.as_account_info(),
+            perp_oracle.as_account_info(),
+        ];
+
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
+        let health_cache = new_health_cache(&account.borrow(), &retriever).unwrap();
+
+        // perp amounts and prices are converted to the settle token, which is worth 5 quote
+        let perp_info = health_cache.perp_info(9).unwrap();
+        assert_eq!(perp_info.quote, I80F48::from(-30));
+        assert_eq!(perp_info.base_prices.oracle, I80F48::from(2));
+
+        // the 200 quote base position counts as 160 quote, so 10 quote pnl,
+        // which is 9.5 after the overall weight, or 1.9 settle tokens
+        let health1 = 100.0 * 0.8;
+        let health2 = (10.0 + 1.9) * 5.0 * 0.5;
+        assert!(health_eq(
+            health_cache.health(HealthType::Init),
+            health1 + health2
+        ));
+
+        // marked to market: 100 + 10 * 5 + 200 - 150
+        let (assets, liabs) = health_cache.assets_and_liabs();
+        assert!(health_eq(assets - liabs, 200.0));
+    }
+
+    #[test]
+    fn test_health_isolated_perp() {
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+
+        let group = Pubkey::new_unique();
+
+        let (mut bank1, mut oracle1) = mock_bank_and_oracle(group, 0, 1.0, 0.2, 0.1);
+        let (_bank2, mut perp_oracle) = mock_bank_and_oracle(group, 5, 10.0, 0.0, 0.0);
+        bank1
+            .data()
+            .deposit(
+                account.ensure_token_position(0).unwrap().0,
+                I80F48::from(100),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+
+        let mut perp1 =
+            mock_perp_market(group, perp_oracle.pubkey, 10.0, 9, (0.2, 0.1), (0.05, 0.02));
+        let perpaccount = account.ensure_perp_position(9, 0).unwrap().0;
+        perpaccount.isolated = 1;
+        bank1
+            .data()
+            .deposit_isolated_collateral(perpaccount, I80F48::from(20))
+            .unwrap();
+        // 2 lots of 10 base at a price of 7.5 quote
+        perpaccount.record_trade(perp1.data(), 2, -I80F48::from(150));
+
+        let ais = vec![
+            bank1.as_account_info(),
+            oracle1.as_account_info(),
+            perp1.as_account_info(),
+            perp_oracle.as_account_info(),
+        ];
+
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
+        let health_cache = new_health_cache(&account.borrow(), &retriever).unwrap();
+
+        // the isolated position doesn't contribute to cross health
+        assert!(health_eq(
+            health_cache.health(HealthType::Init),
+            100.0 * 0.8
+        ));
+        assert!(!health_cache.has_perp_base_positions());
+
+        // collateral 20 plus 10 pnl weighted to 9.5
+        assert!(health_eq(
+            health_cache
+                .isolated_perp_health(9, HealthType::Init)
+                .unwrap(),
+            (20.0 + 9.5) * 0.8
+        ));
+
+        // marked to market: 100 + 20 + 200 - 150
+        let (assets, liabs) = health_cache.assets_and_liabs();
+        assert!(health_eq(assets - liabs, 170.0));
+
+        // negative isolated health doesn't affect cross health
+        account.perp_position_mut(9).unwrap().quote_position_native = I80F48::from(-190);
+        let health_cache = new_health_cache(&account.borrow(), &retriever).unwrap();
+        assert!(health_eq(
+            health_cache.health(HealthType::Init),
+            100.0 * 0.8
+        ));
+        assert!(health_eq(
+            health_cache
+                .isolated_perp_health(9, HealthType::Init)
+                .unwrap(),
+            (20.0 - 30.0) * 1.2
+        ));
+        assert!(health_cache
+            .check_isolated_perp_health_post(9, None)
+            .is_err());
+        assert!(health_cache
+            .check_isolated_perp_health_post(9, Some(I80F48::from(-13)))
+            .is_ok());
+    }
+
     #[derive(Default)]
     struct BankSettings {
         deposits: u64,
@@ -1515,7 +2040,9 @@ mod tests {
             oo2.as_account_info(),
         ];
 
-        let retriever = ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
 
         assert!(health_eq(
             compute_health(&account.borrow(), HealthType::Init, &retriever).unwrap(),
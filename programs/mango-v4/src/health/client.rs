@@ -4,12 +4,94 @@ use anchor_lang::prelude::*;
 
 use fixed::types::I80F48;
 
+use crate::accounts_ix::Serum3Side;
 use crate::error::*;
 use crate::state::Side as PerpOrderSide;
-use crate::state::{Bank, MangoAccountValue, PerpMarketIndex};
+use crate::state::{Bank, MangoAccountValue, PerpMarketIndex, Serum3MarketIndex, TokenIndex};
 
 use super::*;
 
+/// A hypothetical change to an account, see HealthCache::cache_after_scenario()
+///
+/// Amounts are in native units and prices in native/native. Bank side effects like
+/// loan origination fees, net borrow limits or deposit weight scaling are not modeled.
+#[derive(Clone, Debug)]
+pub enum HealthScenarioAction {
+    TokenDeposit {
+        token_index: TokenIndex,
+        amount: I80F48,
+    },
+    /// Withdraws, borrowing if the balance is insufficient
+    TokenWithdraw {
+        token_index: TokenIndex,
+        amount: I80F48,
+    },
+    /// Swap `amount` source tokens, receiving `price` target native per source native
+    TokenSwap {
+        source_token_index: TokenIndex,
+        target_token_index: TokenIndex,
+        amount: I80F48,
+        price: I80F48,
+    },
+    /// Place an order that reserves `amount` quote tokens for bids or base tokens for asks
+    Serum3PlaceOrder {
+        market_index: Serum3MarketIndex,
+        side: Serum3Side,
+        amount: I80F48,
+    },
+    /// A fill of `base_lots` at `price` (settle token native per base native), without fees
+    PerpTrade {
+        perp_market_index: PerpMarketIndex,
+        side: PerpOrderSide,
+        base_lots: i64,
+        price: I80F48,
+    },
+    /// Multiply the oracle and stable prices of a token by `factor`
+    TokenPriceShock {
+        token_index: TokenIndex,
+        factor: I80F48,
+    },
+    /// Multiply the oracle and stable prices of a perp market by `factor`
+    PerpPriceShock {
+        perp_market_index: PerpMarketIndex,
+        factor: I80F48,
+    },
+}
+
+/// Oracle prices at which maint health would become negative
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LiquidationPrices {
+    /// Closest price below the current one, None if there is none down to 1/1024 of it
+    pub lower: Option<I80F48>,
+    /// Closest price above the current one, None if there is none up to 1024x of it
+    pub upper: Option<I80F48>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HealthScenarioResult {
+    pub init_health: I80F48,
+    pub maint_health: I80F48,
+    pub init_health_ratio: I80F48,
+    pub maint_health_ratio: I80F48,
+    pub leverage: I80F48,
+    /// See HealthCache::token_liquidation_prices()
+    pub token_liquidation_prices: Vec<(TokenIndex, LiquidationPrices)>,
+    /// See HealthCache::perp_liquidation_prices()
+    pub perp_liquidation_prices: Vec<(PerpMarketIndex, LiquidationPrices)>,
+    pub cache: HealthCache,
+}
+
+/// Liabilities of an account that its assets can't cover, see HealthCache::bad_debt()
+#[derive(Clone, Debug, Default)]
+pub struct BadDebt {
+    /// In native quote
+    pub total: I80F48,
+    /// The part of `total` attributed to each token, in native quote
+    pub tokens: Vec<(TokenIndex, I80F48)>,
+    /// The part of `total` attributed to each perp market, in native quote
+    pub perps: Vec<(PerpMarketIndex, I80F48)>,
+}
+
 impl HealthCache {
     pub fn is_liquidatable(&self) -> bool {
         if self.being_liquidated {
@@ -244,6 +326,8 @@ impl HealthCache {
         Ok(amount)
     }
 
+    /// The price is in settle token native per base native, like PerpInfo::base_prices.
+    ///
     /// NOTE: keep getMaxSourceForTokenSwap in ts/client in sync with changes here
     pub fn max_perp_for_health_ratio(
         &self,
@@ -448,6 +532,323 @@ impl HealthCache {
     }
 }
 
+impl HealthCache {
+    fn apply_scenario_action(&mut self, action: &HealthScenarioAction) -> Result<()> {
+        use HealthScenarioAction::*;
+        match *action {
+            TokenDeposit {
+                token_index,
+                amount,
+            } => {
+                let index = self.token_info_index(token_index)?;
+                self.token_infos[index].balance_spot += amount;
+            }
+            TokenWithdraw {
+                token_index,
+                amount,
+            } => {
+                let index = self.token_info_index(token_index)?;
+                self.token_infos[index].balance_spot -= amount;
+            }
+            TokenSwap {
+                source_token_index,
+                target_token_index,
+                amount,
+                price,
+            } => {
+                let source_index = self.token_info_index(source_token_index)?;
+                let target_index = self.token_info_index(target_token_index)?;
+                self.token_infos[source_index].balance_spot -= amount;
+                self.token_infos[target_index].balance_spot += amount * price;
+            }
+            Serum3PlaceOrder {
+                market_index,
+                side,
+                amount,
+            } => {
+                let info = self
+                    .serum3_infos
+                    .iter()
+                    .find(|m| m.market_index == market_index)
+                    .ok_or_else(|| error_msg!("serum3 market {} not found", market_index))?;
+                let base_token_index = self.token_infos[info.base_info_index].token_index;
+                let quote_token_index = self.token_infos[info.quote_info_index].token_index;
+                let (base_change, quote_change) = match side {
+                    Serum3Side::Bid => (I80F48::ZERO, amount),
+                    Serum3Side::Ask => (amount, I80F48::ZERO),
+                };
+                self.adjust_serum3_reserved(
+                    market_index,
+                    base_token_index,
+                    base_change,
+                    -base_change,
+                    quote_token_index,
+                    quote_change,
+                    -quote_change,
+                )?;
+            }
+            PerpTrade {
+                perp_market_index,
+                side,
+                base_lots,
+                price,
+            } => {
+                let index = self.perp_info_index(perp_market_index)?;
+                let perp_info = &mut self.perp_infos[index];
+                let direction: i64 = match side {
+                    PerpOrderSide::Bid => 1,
+                    PerpOrderSide::Ask => -1,
+                };
+                perp_info.base_lots += direction * base_lots;
+                perp_info.quote -= I80F48::from(direction)
+                    * I80F48::from(base_lots)
+                    * I80F48::from(perp_info.base_lot_size)
+                    * price;
+            }
+            TokenPriceShock {
+                token_index,
+                factor,
+            } => {
+                let index = self.token_info_index(token_index)?;
+                let prices = &mut self.token_infos[index].prices;
+                prices.oracle *= factor;
+                prices.stable *= factor;
+            }
+            PerpPriceShock {
+                perp_market_index,
+                factor,
+            } => {
+                let index = self.perp_info_index(perp_market_index)?;
+                let prices = &mut self.perp_infos[index].base_prices;
+                prices.oracle *= factor;
+                prices.stable *= factor;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return a copy of the current cache where all actions were applied in order.
+    ///
+    /// Actions may only refer to tokens, serum3 markets and perp markets that the
+    /// cache already has entries for.
+    pub fn cache_after_scenario(&self, actions: &[HealthScenarioAction]) -> Result<Self> {
+        let mut cache = self.clone();
+        for action in actions {
+            cache.apply_scenario_action(action)?;
+        }
+        Ok(cache)
+    }
+
+    /// Applies the actions and summarizes health and risk of the resulting account
+    pub fn scenario(&self, actions: &[HealthScenarioAction]) -> Result<HealthScenarioResult> {
+        let cache = self.cache_after_scenario(actions)?;
+        let token_liquidation_prices = cache
+            .token_infos
+            .iter()
+            .map(|info| {
+                Ok((
+                    info.token_index,
+                    cache.token_liquidation_prices(info.token_index)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let perp_liquidation_prices = cache
+            .perp_infos
+            .iter()
+            .map(|info| {
+                Ok((
+                    info.perp_market_index,
+                    cache.perp_liquidation_prices(info.perp_market_index)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(HealthScenarioResult {
+            init_health: cache.health(HealthType::Init),
+            maint_health: cache.health(HealthType::Maint),
+            init_health_ratio: cache.health_ratio(HealthType::Init),
+            maint_health_ratio: cache.health_ratio(HealthType::Maint),
+            leverage: cache.leverage(),
+            token_liquidation_prices,
+            perp_liquidation_prices,
+            cache,
+        })
+    }
+
+    /// Oracle prices of the token at which the account would become liquidatable,
+    /// holding all other prices fixed.
+    ///
+    /// The token price affects the token balance, serum3 reserved funds and all perp
+    /// positions settling in the token. The stable price is kept, it doesn't affect
+    /// maint health.
+    pub fn token_liquidation_prices(&self, token_index: TokenIndex) -> Result<LiquidationPrices> {
+        let index = self.token_info_index(token_index)?;
+        liquidation_prices(self.token_infos[index].prices.oracle, |price| {
+            let mut cache = self.clone();
+            cache.token_infos[index].prices.oracle = price;
+            Ok(cache.health(HealthType::Maint))
+        })
+    }
+
+    /// Oracle prices of the perp market at which the account would become liquidatable,
+    /// holding all other prices fixed.
+    ///
+    /// For isolated positions this is where the isolated maint health reaches zero,
+    /// see isolated_perp_health().
+    pub fn perp_liquidation_prices(
+        &self,
+        perp_market_index: PerpMarketIndex,
+    ) -> Result<LiquidationPrices> {
+        let index = self.perp_info_index(perp_market_index)?;
+        let isolated = self.perp_infos[index].isolated;
+        liquidation_prices(self.perp_infos[index].base_prices.oracle, |price| {
+            let mut cache = self.clone();
+            cache.perp_infos[index].base_prices.oracle = price;
+            if isolated {
+                cache.isolated_perp_health(perp_market_index, HealthType::Maint)
+            } else {
+                Ok(cache.health(HealthType::Maint))
+            }
+        })
+    }
+
+    /// The unweighted value by which the account's liabilities exceed its assets at oracle
+    /// prices, i.e. what would be socialized if it were liquidated perfectly.
+    ///
+    /// Cross margined bad debt is attributed to token borrows and negative perp pnl
+    /// proportionally to their value. Isolated perp positions only count when they are
+    /// underwater themselves and their excess collateral doesn't back anything else.
+    pub fn bad_debt(&self) -> BadDebt {
+        let mut assets = I80F48::ZERO;
+        let mut liabs = I80F48::ZERO;
+        let mut token_liabs = vec![];
+        let mut perp_liabs = vec![];
+        let mut isolated_bad_debt = vec![];
+
+        for token_info in self.token_infos.iter() {
+            let value = token_info.balance_spot * token_info.prices.oracle;
+            if value.is_negative() {
+                liabs -= value;
+                token_liabs.push((token_info.token_index, -value));
+            } else {
+                assets += value;
+            }
+        }
+
+        for spot_info in self.spot_infos() {
+            let quote = &self.token_infos[spot_info.quote_info_index()];
+            let base = &self.token_infos[spot_info.base_info_index()];
+            assets += spot_info.reserved_base() * base.prices.oracle;
+            assets += spot_info.reserved_quote() * quote.prices.oracle;
+        }
+
+        for perp_info in self.perp_infos.iter() {
+            let settle_price = self
+                .token_info(perp_info.settle_token_index)
+                .unwrap()
+                .prices
+                .oracle;
+            let pnl = perp_info.quote
+                + I80F48::from(perp_info.base_lots * perp_info.base_lot_size)
+                    * perp_info.base_prices.oracle;
+            if perp_info.isolated {
+                let value = (perp_info.isolated_collateral + pnl) * settle_price;
+                if value.is_negative() {
+                    isolated_bad_debt.push((perp_info.perp_market_index, -value));
+                }
+                continue;
+            }
+            let value = pnl * settle_price;
+            if value.is_negative() {
+                liabs -= value;
+                perp_liabs.push((perp_info.perp_market_index, -value));
+            } else {
+                assets += value;
+            }
+        }
+
+        let cross_bad_debt = (liabs - assets).max(I80F48::ZERO);
+        let share = |liab: I80F48| {
+            if cross_bad_debt.is_zero() {
+                I80F48::ZERO
+            } else {
+                cross_bad_debt * liab / liabs
+            }
+        };
+        let tokens = token_liabs
+            .into_iter()
+            .map(|(token_index, liab)| (token_index, share(liab)))
+            .collect();
+        let mut perps = perp_liabs
+            .into_iter()
+            .map(|(perp_market_index, liab)| (perp_market_index, share(liab)))
+            .collect::<Vec<_>>();
+        let mut total = cross_bad_debt;
+        for (perp_market_index, bad_debt) in isolated_bad_debt {
+            total += bad_debt;
+            perps.push((perp_market_index, bad_debt));
+        }
+
+        BadDebt {
+            total,
+            tokens,
+            perps,
+        }
+    }
+}
+
+/// Prices closest to `price` where `health_at_price` becomes negative.
+///
+/// Scans away from the current price in factor-of-two steps and binary searches the first
+/// step where health turns negative. That finds the closest crossing as long as health is
+/// monotonic within a step, which holds well in practice: token balances and perp positions
+/// make maint health piecewise linear and concave in a single price.
+fn liquidation_prices(
+    price: I80F48,
+    health_at_price: impl Fn(I80F48) -> Result<I80F48>,
+) -> Result<LiquidationPrices> {
+    let current_health = health_at_price(price)?;
+    if current_health < 0 {
+        return Ok(LiquidationPrices {
+            lower: Some(price),
+            upper: Some(price),
+        });
+    }
+    if price <= 0 {
+        return Ok(LiquidationPrices::default());
+    }
+    let min_step = price * I80F48::from_num(0.0000001);
+
+    let first_crossing = |steps: &mut dyn Iterator<Item = I80F48>| -> Result<Option<I80F48>> {
+        let mut left = price;
+        let mut left_health = current_health;
+        for right in steps {
+            let right_health = health_at_price(right)?;
+            if right_health < 0 {
+                return binary_search(
+                    left,
+                    left_health,
+                    right,
+                    I80F48::ZERO,
+                    min_step,
+                    &health_at_price,
+                )
+                .map(Some);
+            }
+            left = right;
+            left_health = right_health;
+        }
+        Ok(None)
+    };
+
+    // Giving up at some point avoids overflow with absurd prices. Zero is avoided because
+    // serum3 reserved funds are converted at the price ratio.
+    let max_doublings = 10;
+    let lower = first_crossing(&mut (1..=max_doublings).map(|i| price / I80F48::from(1u32 << i)))?;
+    let upper = first_crossing(&mut (1..=max_doublings).map(|i| price * I80F48::from(1u32 << i)))?;
+
+    Ok(LiquidationPrices { lower, upper })
+}
+
 fn scan_right_until_less_than(
     start: I80F48,
     target: I80F48,
@@ -620,6 +1021,16 @@ mod tests {
         }
     }
 
+    fn price_near(a: Option<I80F48>, b: f64, tolerance: f64) -> bool {
+        match a {
+            Some(a) if (a.to_num::<f64>() - b).abs() < tolerance => true,
+            _ => {
+                println!("price is {:?}, but expected {}", a, b);
+                false
+            }
+        }
+    }
+
     fn default_token_info(x: f64, price: f64) -> TokenInfo {
         TokenInfo {
             token_index: 0,
@@ -652,6 +1063,8 @@ mod tests {
             base_prices: Prices::new_single_price(I80F48::from_num(price)),
             has_open_orders: false,
             has_open_fills: false,
+            isolated: false,
+            isolated_collateral: I80F48::ZERO,
         }
     }
 
@@ -691,6 +1104,7 @@ mod tests {
             serum3_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
 
         assert_eq!(health_cache.health(HealthType::Init), I80F48::ZERO);
@@ -1061,6 +1475,7 @@ mod tests {
                 ..default_perp_info(0.3, 2.0)
             }],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
 
         assert_eq!(health_cache.health(HealthType::Init), I80F48::ZERO);
@@ -1203,7 +1618,9 @@ mod tests {
             oracle1_ai,
         ];
 
-        let retriever = ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
 
         assert!(health_eq(
             compute_health(&account.borrow(), HealthType::Init, &retriever).unwrap(),
@@ -1244,7 +1661,8 @@ mod tests {
             oo1.as_account_info(),
         ];
 
-        let retriever = ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[], None).unwrap();
         let result = retriever.perp_market_and_oracle_price(&group, 0, 9);
         assert!(result.is_err());
     }
@@ -1292,7 +1710,9 @@ mod tests {
             oracle1_ai,
         ];
 
-        let retriever = ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+        let retriever =
+            ScanningAccountRetriever::new_with_staleness(&ais, &group, &[&account.borrow()], None)
+                .unwrap();
 
         assert!(health_eq(
             compute_health(&account.borrow(), HealthType::Init, &retriever).unwrap(),
@@ -1345,6 +1765,7 @@ mod tests {
             serum3_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
 
         assert_eq!(health_cache.health(HealthType::Init), I80F48::ZERO);
@@ -1438,6 +1859,7 @@ mod tests {
                 ..default_perp_info(0.3, 2.0)
             }],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
 
         {
@@ -1487,6 +1909,7 @@ mod tests {
             serum3_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
         assert!(leverage_eq(&health_cache, 0.0));
 
@@ -1507,6 +1930,7 @@ mod tests {
             serum3_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
 
         assert!(leverage_eq(&health_cache, 9.0));
@@ -1533,6 +1957,7 @@ mod tests {
                 ..default_perp_info(0.1, 1.1)
             }],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
         assert!(leverage_eq(&health_cache, 9.9));
 
@@ -1560,8 +1985,329 @@ mod tests {
             }],
             perp_infos: vec![],
             being_liquidated: false,
+            openbook_v2_infos: vec![],
         };
 
         assert!(leverage_eq(&health_cache, 2.0));
     }
+
+    #[test]
+    fn test_health_scenario() {
+        let health_cache = HealthCache {
+            token_infos: vec![
+                TokenInfo {
+                    token_index: 0,
+                    balance_spot: I80F48::from(10),
+                    ..default_token_info(0.0, 1.0)
+                },
+                TokenInfo {
+                    token_index: 1,
+                    ..default_token_info(0.2, 2.0)
+                },
+            ],
+            serum3_infos: vec![Serum3Info {
+                reserved_base: I80F48::ZERO,
+                reserved_quote: I80F48::ZERO,
+                base_info_index: 1,
+                quote_info_index: 0,
+                market_index: 0,
+                has_zero_funds: true,
+            }],
+            perp_infos: vec![PerpInfo {
+                perp_market_index: 0,
+                ..default_perp_info(0.1, 5.0)
+            }],
+            being_liquidated: false,
+            openbook_v2_infos: vec![],
+        };
+        assert!(health_eq(health_cache.health(HealthType::Maint), 10.0));
+
+        let token_actions = [
+            HealthScenarioAction::TokenWithdraw {
+                token_index: 1,
+                amount: I80F48::from(2),
+            },
+            HealthScenarioAction::TokenSwap {
+                source_token_index: 0,
+                target_token_index: 1,
+                amount: I80F48::from(2),
+                price: I80F48::from_num(0.5),
+            },
+            HealthScenarioAction::TokenPriceShock {
+                token_index: 1,
+                factor: I80F48::from(2),
+            },
+        ];
+        let result = health_cache.scenario(&token_actions).unwrap();
+        // 8 - 1 * 4 * 1.2
+        assert!(health_eq(result.maint_health, 3.2));
+        assert!(health_eq(result.init_health, 3.2));
+        // the original is unchanged
+        assert!(health_eq(health_cache.health(HealthType::Maint), 10.0));
+
+        let liq_prices = |token_index: TokenIndex| {
+            result
+                .token_liquidation_prices
+                .iter()
+                .find(|(ti, _)| *ti == token_index)
+                .unwrap()
+                .1
+        };
+        // 8 * p - 4.8 = 0
+        assert!(price_near(liq_prices(0).lower, 0.6, 0.02));
+        assert_eq!(liq_prices(0).upper, None);
+        // 8 - 1.2 * p = 0
+        assert_eq!(liq_prices(1).lower, None);
+        assert!(price_near(liq_prices(1).upper, 8.0 / 1.2, 0.1));
+
+        // perp trades affect the settle token health
+        let mut perp_actions = token_actions.to_vec();
+        perp_actions.push(HealthScenarioAction::PerpTrade {
+            perp_market_index: 0,
+            side: PerpOrderSide::Bid,
+            base_lots: 2,
+            price: I80F48::from(5),
+        });
+        let result = health_cache.scenario(&perp_actions).unwrap();
+        assert_eq!(result.cache.perp_infos[0].base_lots, 2);
+        assert!(health_eq(result.cache.perp_infos[0].quote, -10.0));
+        // perp health is 2 * 5 * 0.9 - 10
+        assert!(health_eq(result.maint_health, 2.2));
+
+        perp_actions.push(HealthScenarioAction::PerpPriceShock {
+            perp_market_index: 0,
+            factor: I80F48::from_num(0.5),
+        });
+        let result = health_cache.scenario(&perp_actions).unwrap();
+        // perp health is 2 * 2.5 * 0.9 - 10
+        assert!(health_eq(result.maint_health, -2.3));
+        assert!(result.cache.is_liquidatable());
+
+        // serum orders move funds from free to reserved
+        let result = health_cache
+            .scenario(&[HealthScenarioAction::Serum3PlaceOrder {
+                market_index: 0,
+                side: Serum3Side::Bid,
+                amount: I80F48::from(3),
+            }])
+            .unwrap();
+        assert!(health_eq(result.cache.token_infos[0].balance_spot, 7.0));
+        assert!(health_eq(result.cache.serum3_infos[0].reserved_quote, 3.0));
+
+        assert!(health_cache
+            .scenario(&[HealthScenarioAction::TokenDeposit {
+                token_index: 5,
+                amount: I80F48::ONE,
+            }])
+            .is_err());
+    }
+
+    #[test]
+    fn test_token_liquidation_prices() {
+        let health_cache = HealthCache {
+            token_infos: vec![
+                TokenInfo {
+                    token_index: 0,
+                    balance_spot: I80F48::from(10000),
+                    ..default_token_info(0.0, 1.0)
+                },
+                TokenInfo {
+                    token_index: 1,
+                    balance_spot: I80F48::from(-3000),
+                    // the stable price doesn't matter for maint health
+                    prices: Prices {
+                        oracle: I80F48::from(2),
+                        stable: I80F48::from(5),
+                    },
+                    ..default_token_info(0.2, 2.0)
+                },
+            ],
+            serum3_infos: vec![],
+            perp_infos: vec![],
+            being_liquidated: false,
+            openbook_v2_infos: vec![],
+        };
+        assert!(health_eq(health_cache.health(HealthType::Maint), 2800.0));
+
+        // 10000 * p - 7200 = 0
+        let prices = health_cache.token_liquidation_prices(0).unwrap();
+        assert!(price_near(prices.lower, 0.72, 0.001));
+        assert_eq!(prices.upper, None);
+
+        // 10000 - 3600 * p = 0
+        let prices = health_cache.token_liquidation_prices(1).unwrap();
+        assert_eq!(prices.lower, None);
+        assert!(price_near(prices.upper, 10000.0 / 3600.0, 0.001));
+
+        // liquidatable accounts are liquidatable at the current price
+        let mut liquidatable = health_cache.clone();
+        liquidatable.token_infos[0].balance_spot = I80F48::from(7000);
+        let prices = liquidatable.token_liquidation_prices(1).unwrap();
+        assert!(price_near(prices.lower, 2.0, 0.000001));
+        assert!(price_near(prices.upper, 2.0, 0.000001));
+
+        // a price too far away isn't found
+        let mut far = health_cache.clone();
+        far.token_infos[0].balance_spot = I80F48::from(10000000);
+        let prices = far.token_liquidation_prices(1).unwrap();
+        assert_eq!(prices.upper, None);
+
+        assert!(health_cache.token_liquidation_prices(5).is_err());
+    }
+
+    #[test]
+    fn test_serum3_liquidation_prices() {
+        // borrowed quote, with base reserved on an ask
+        let health_cache = HealthCache {
+            token_infos: vec![
+                TokenInfo {
+                    token_index: 0,
+                    balance_spot: I80F48::from(-1000),
+                    ..default_token_info(0.0, 1.0)
+                },
+                TokenInfo {
+                    token_index: 1,
+                    ..default_token_info(0.2, 2.0)
+                },
+            ],
+            serum3_infos: vec![Serum3Info {
+                reserved_base: I80F48::from(1000),
+                reserved_quote: I80F48::ZERO,
+                base_info_index: 1,
+                quote_info_index: 0,
+                market_index: 0,
+                has_zero_funds: false,
+            }],
+            perp_infos: vec![],
+            being_liquidated: false,
+            openbook_v2_infos: vec![],
+        };
+        // the worst case is keeping the reserved base: 1000 * 0.8 * 2 - 1000
+        assert!(health_eq(health_cache.health(HealthType::Maint), 600.0));
+
+        // 800 * p - 1000 = 0
+        let prices = health_cache.token_liquidation_prices(1).unwrap();
+        assert!(price_near(prices.lower, 1.25, 0.001));
+        assert_eq!(prices.upper, None);
+
+        // 1600 - 1000 * p = 0
+        let prices = health_cache.token_liquidation_prices(0).unwrap();
+        assert_eq!(prices.lower, None);
+        assert!(price_near(prices.upper, 1.6, 0.001));
+    }
+
+    #[test]
+    fn test_perp_liquidation_prices() {
+        let base_cache = HealthCache {
+            token_infos: vec![TokenInfo {
+                token_index: 0,
+                balance_spot: I80F48::from(100),
+                ..default_token_info(0.0, 1.0)
+            }],
+            serum3_infos: vec![],
+            perp_infos: vec![PerpInfo {
+                perp_market_index: 0,
+                ..default_perp_info(0.1, 5.0)
+            }],
+            being_liquidated: false,
+            openbook_v2_infos: vec![],
+        };
+
+        // long: 100 + 100 * 0.9 * p - 500 = 0
+        let mut health_cache = base_cache.clone();
+        health_cache.perp_infos[0].base_lots = 100;
+        health_cache.perp_infos[0].quote = I80F48::from(-500);
+        assert!(health_eq(health_cache.health(HealthType::Maint), 50.0));
+        let prices = health_cache.perp_liquidation_prices(0).unwrap();
+        assert!(price_near(prices.lower, 400.0 / 90.0, 0.01));
+        assert_eq!(prices.upper, None);
+
+        // the settle token balance stays positive at any settle token price
+        let prices = health_cache.token_liquidation_prices(0).unwrap();
+        assert_eq!(prices, LiquidationPrices::default());
+
+        // short: 100 + 550 - 100 * 1.1 * p = 0
+        // positive pnl at lower prices gets the overall asset weight and doesn't matter
+        let mut health_cache = base_cache.clone();
+        health_cache.perp_infos[0].base_lots = -100;
+        health_cache.perp_infos[0].quote = I80F48::from(550);
+        assert!(health_eq(health_cache.health(HealthType::Maint), 100.0));
+        let prices = health_cache.perp_liquidation_prices(0).unwrap();
+        assert_eq!(prices.lower, None);
+        assert!(price_near(prices.upper, 650.0 / 110.0, 0.01));
+
+        // open bids make the worst case a bigger long, bought at the oracle price
+        // 200 + 200 * 0.9 * p - 100 * p - 500 = 0
+        let mut health_cache = base_cache.clone();
+        health_cache.token_infos[0].balance_spot = I80F48::from(200);
+        health_cache.perp_infos[0].base_lots = 100;
+        health_cache.perp_infos[0].bids_base_lots = 100;
+        health_cache.perp_infos[0].quote = I80F48::from(-500);
+        assert!(health_eq(health_cache.health(HealthType::Maint), 100.0));
+        let prices = health_cache.perp_liquidation_prices(0).unwrap();
+        assert!(price_near(prices.lower, 3.75, 0.01));
+
+        // isolated: only the isolated collateral backs the position
+        // 60 + 100 * 0.9 * p - 500 = 0
+        let mut health_cache = base_cache.clone();
+        health_cache.perp_infos[0].base_lots = 100;
+        health_cache.perp_infos[0].quote = I80F48::from(-500);
+        health_cache.perp_infos[0].isolated = true;
+        health_cache.perp_infos[0].isolated_collateral = I80F48::from(60);
+        assert!(health_eq(health_cache.health(HealthType::Maint), 100.0));
+        let prices = health_cache.perp_liquidation_prices(0).unwrap();
+        assert!(price_near(prices.lower, 440.0 / 90.0, 0.01));
+        assert_eq!(prices.upper, None);
+    }
+
+    #[test]
+    fn test_bad_debt() {
+        let mut health_cache = HealthCache {
+            token_infos: vec![
+                TokenInfo {
+                    token_index: 0,
+                    balance_spot: I80F48::from(100),
+                    ..default_token_info(0.0, 1.0)
+                },
+                TokenInfo {
+                    token_index: 1,
+                    balance_spot: I80F48::from(-40),
+                    ..default_token_info(0.2, 2.0)
+                },
+            ],
+            serum3_infos: vec![],
+            perp_infos: vec![
+                PerpInfo {
+                    perp_market_index: 0,
+                    quote: I80F48::from(-30),
+                    ..default_perp_info(0.1, 5.0)
+                },
+                PerpInfo {
+                    perp_market_index: 1,
+                    quote: I80F48::from(-20),
+                    isolated: true,
+                    isolated_collateral: I80F48::from(30),
+                    ..default_perp_info(0.1, 5.0)
+                },
+            ],
+            being_liquidated: false,
+            openbook_v2_infos: vec![],
+        };
+
+        // assets cover 80 + 30 and the isolated position is fine
+        let bad_debt = health_cache.bad_debt();
+        assert_eq!(bad_debt.total, I80F48::ZERO);
+        assert_eq!(bad_debt.tokens, vec![(1, I80F48::ZERO)]);
+        assert_eq!(bad_debt.perps, vec![(0, I80F48::ZERO)]);
+
+        // liabs of 120 + 30 against assets of 100, and 15 in the isolated position
+        health_cache.token_infos[1].balance_spot = I80F48::from(-60);
+        health_cache.perp_infos[1].isolated_collateral = I80F48::from(5);
+        let bad_debt = health_cache.bad_debt();
+        assert!(health_eq(bad_debt.total, 65.0));
+        assert!(health_eq(bad_debt.tokens[0].1, 40.0));
+        assert!(health_eq(bad_debt.perps[0].1, 10.0));
+        assert_eq!(bad_debt.perps[1].0, 1);
+        assert!(health_eq(bad_debt.perps[1].1, 15.0));
+    }
 }
@@ -2,6 +2,7 @@
 
 use anchor_lang::prelude::*;
 use fixed::types::I80F48;
+use openbook_v2::state::OpenOrdersAccount;
 use serum_dex::state::OpenOrders;
 use std::cell::RefCell;
 use std::mem::size_of;
@@ -18,6 +19,7 @@ pub trait MyZeroCopy: anchor_lang::ZeroCopy + Owner {}
 impl MyZeroCopy for StubOracle {}
 impl MyZeroCopy for Bank {}
 impl MyZeroCopy for PerpMarket {}
+impl MyZeroCopy for OpenOrdersAccount {}
 
 #[derive(Clone)]
 pub struct TestAccount<T> {
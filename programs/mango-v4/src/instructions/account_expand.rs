@@ -10,6 +10,8 @@ pub fn account_expand(
     perp_count: u8,
     perp_oo_count: u8,
     token_conditional_swap_count: u8,
+    openbook_v2_count: u8,
+    perp_conditional_order_count: u8,
 ) -> Result<()> {
     let new_space = MangoAccount::space(
         token_count,
@@ -17,6 +19,8 @@ pub fn account_expand(
         perp_count,
         perp_oo_count,
         token_conditional_swap_count,
+        openbook_v2_count,
+        perp_conditional_order_count,
     );
     let new_rent_minimum = Rent::get()?.minimum_balance(new_space);
 
@@ -49,6 +53,8 @@ pub fn account_expand(
         perp_count,
         perp_oo_count,
         token_conditional_swap_count,
+        openbook_v2_count,
+        perp_conditional_order_count,
     )?;
 
     Ok(())
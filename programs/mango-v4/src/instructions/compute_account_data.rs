@@ -7,7 +7,8 @@ pub fn compute_account_data(ctx: Context<ComputeAccountData>) -> Result<()> {
 
     let account = ctx.accounts.account.load_full()?;
 
-    let account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, &group_pk)?;
+    let account_retriever =
+        ScanningAccountRetriever::new(ctx.remaining_accounts, &group_pk, &[&account.borrow()])?;
 
     let health_cache = new_health_cache(&account.borrow(), &account_retriever)?;
     let init_health = health_cache.health(HealthType::Init);
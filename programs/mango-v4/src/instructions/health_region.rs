@@ -83,8 +83,9 @@ pub fn health_region_begin<'key, 'accounts, 'remaining, 'info>(
     account.fixed.set_in_health_region(true);
 
     let group = account.fixed.group;
-    let account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, &group)
-        .context("create account retriever")?;
+    let account_retriever =
+        ScanningAccountRetriever::new(ctx.remaining_accounts, &group, &[&account.borrow()])
+            .context("create account retriever")?;
 
     // Compute pre-health and store it on the account
     let health_cache = new_health_cache(&account.borrow(), &account_retriever)?;
@@ -105,8 +106,9 @@ pub fn health_region_end<'key, 'accounts, 'remaining, 'info>(
     account.fixed.set_in_health_region(false);
 
     let group = account.fixed.group;
-    let account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, &group)
-        .context("create account retriever")?;
+    let account_retriever =
+        ScanningAccountRetriever::new(ctx.remaining_accounts, &group, &[&account.borrow()])
+            .context("create account retriever")?;
     let health_cache = new_health_cache(&account.borrow(), &account_retriever)?;
 
     let pre_init_health = I80F48::from(account.fixed.health_region_begin_init_health);
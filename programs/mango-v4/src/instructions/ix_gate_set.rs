@@ -82,6 +82,16 @@ pub fn ix_gate_set(ctx: Context<IxGateSet>, ix_gate: u128) -> Result<()> {
     log_if_changed(&group, ix_gate, IxGate::OpenbookV2SettleFunds);
     log_if_changed(&group, ix_gate, IxGate::AdminTokenWithdrawFees);
     log_if_changed(&group, ix_gate, IxGate::AdminPerpWithdrawFees);
+    log_if_changed(&group, ix_gate, IxGate::TwapOracleCreate);
+    log_if_changed(&group, ix_gate, IxGate::TwapOracleUpdate);
+    log_if_changed(&group, ix_gate, IxGate::TokenInsuranceFundCreate);
+    log_if_changed(&group, ix_gate, IxGate::TokenInsuranceFundDeposit);
+    log_if_changed(&group, ix_gate, IxGate::TokenInsuranceFundWithdraw);
+    log_if_changed(&group, ix_gate, IxGate::PerpConditionalOrderCreate);
+    log_if_changed(&group, ix_gate, IxGate::PerpConditionalOrderCancel);
+    log_if_changed(&group, ix_gate, IxGate::PerpConditionalOrderTrigger);
+    log_if_changed(&group, ix_gate, IxGate::PerpIsolatedMarginTransfer);
+    log_if_changed(&group, ix_gate, IxGate::PerpLiqIsolatedPosition);
 
     group.ix_gate = ix_gate;
 
@@ -17,18 +17,35 @@ pub use group_edit::*;
 pub use group_withdraw_insurance_fund::*;
 pub use health_region::*;
 pub use ix_gate_set::*;
+pub use openbook_v2_cancel_all_orders::*;
+pub use openbook_v2_cancel_order::*;
+pub use openbook_v2_close_open_orders::*;
+pub use openbook_v2_create_open_orders::*;
+pub use openbook_v2_deregister_market::*;
+pub use openbook_v2_edit_market::*;
+pub use openbook_v2_liq_force_cancel_orders::*;
+pub use openbook_v2_place_order::*;
+pub use openbook_v2_place_take_order::*;
+pub use openbook_v2_register_market::*;
+pub use openbook_v2_settle_funds::*;
 pub use perp_cancel_all_orders::*;
 pub use perp_cancel_all_orders_by_side::*;
 pub use perp_cancel_order::*;
 pub use perp_cancel_order_by_client_order_id::*;
 pub use perp_close_market::*;
+pub use perp_conditional_order_cancel::*;
+pub use perp_conditional_order_create::*;
+pub use perp_conditional_order_trigger::*;
+pub use perp_conditional_order_trigger_place_order::*;
 pub use perp_consume_events::*;
 pub use perp_create_market::*;
 pub use perp_deactivate_position::*;
 pub use perp_edit_market::*;
 pub use perp_force_close_position::*;
+pub use perp_isolated_margin_transfer::*;
 pub use perp_liq_base_or_positive_pnl::*;
 pub use perp_liq_force_cancel_orders::*;
+pub use perp_liq_isolated_position::*;
 pub use perp_liq_negative_pnl_or_bankruptcy::*;
 pub use perp_place_order::*;
 pub use perp_settle_fees::*;
@@ -55,12 +72,17 @@ pub use token_deposit::*;
 pub use token_deregister::*;
 pub use token_edit::*;
 pub use token_force_close_borrows_with_token::*;
+pub use token_insurance_fund_create::*;
+pub use token_insurance_fund_deposit::*;
+pub use token_insurance_fund_withdraw::*;
 pub use token_liq_bankruptcy::*;
 pub use token_liq_with_token::*;
 pub use token_register::*;
 pub use token_register_trustless::*;
 pub use token_update_index_and_rate::*;
 pub use token_withdraw::*;
+pub use twap_oracle_create::*;
+pub use twap_oracle_update::*;
 
 mod account_buyback_fees_with_mngo;
 mod account_close;
@@ -81,18 +103,35 @@ mod group_edit;
 mod group_withdraw_insurance_fund;
 mod health_region;
 mod ix_gate_set;
+mod openbook_v2_cancel_all_orders;
+mod openbook_v2_cancel_order;
+mod openbook_v2_close_open_orders;
+mod openbook_v2_create_open_orders;
+mod openbook_v2_deregister_market;
+mod openbook_v2_edit_market;
+mod openbook_v2_liq_force_cancel_orders;
+mod openbook_v2_place_order;
+mod openbook_v2_place_take_order;
+mod openbook_v2_register_market;
+mod openbook_v2_settle_funds;
 mod perp_cancel_all_orders;
 mod perp_cancel_all_orders_by_side;
 mod perp_cancel_order;
 mod perp_cancel_order_by_client_order_id;
 mod perp_close_market;
+mod perp_conditional_order_cancel;
+mod perp_conditional_order_create;
+mod perp_conditional_order_trigger;
+mod perp_conditional_order_trigger_place_order;
 mod perp_consume_events;
 mod perp_create_market;
 mod perp_deactivate_position;
 mod perp_edit_market;
 mod perp_force_close_position;
+mod perp_isolated_margin_transfer;
 mod perp_liq_base_or_positive_pnl;
 mod perp_liq_force_cancel_orders;
+mod perp_liq_isolated_position;
 mod perp_liq_negative_pnl_or_bankruptcy;
 mod perp_place_order;
 mod perp_settle_fees;
@@ -119,9 +158,14 @@ mod token_deposit;
 mod token_deregister;
 mod token_edit;
 mod token_force_close_borrows_with_token;
+mod token_insurance_fund_create;
+mod token_insurance_fund_deposit;
+mod token_insurance_fund_withdraw;
 mod token_liq_bankruptcy;
 mod token_liq_with_token;
 mod token_register;
 mod token_register_trustless;
 mod token_update_index_and_rate;
 mod token_withdraw;
+mod twap_oracle_create;
+mod twap_oracle_update;
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use openbook_v2::state::Side;
+
+use crate::error::*;
+use crate::state::*;
+
+use super::OpenOrdersAmounts;
+use crate::accounts_ix::*;
+use crate::logs::OpenbookV2OpenOrdersBalanceLog;
+use crate::openbook_v2_cpi::load_open_orders_native;
+
+/// Cancels a single order by id.
+///
+/// Openbook v2 order ids are unique across both sides of the book, so the side is not
+/// needed for the cancel itself.
+pub fn openbook_v2_cancel_order(
+    ctx: Context<OpenbookV2CancelOrder>,
+    _side: Side,
+    order_id: u128,
+) -> Result<()> {
+    let group = ctx.accounts.group.load()?;
+    require!(
+        group.is_ix_enabled(IxGate::OpenbookV2CancelOrder),
+        MangoError::IxIsDisabled
+    );
+
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+
+    //
+    // Validation
+    //
+    {
+        let account = ctx.accounts.account.load_full()?;
+        // account constraint #1
+        require!(
+            account
+                .fixed
+                .is_owner_or_delegate(ctx.accounts.authority.key()),
+            MangoError::SomeError
+        );
+
+        // Validate open_orders #2
+        require!(
+            account
+                .openbook_v2_orders(openbook_market.market_index)?
+                .open_orders
+                == ctx.accounts.open_orders.key(),
+            MangoError::SomeError
+        );
+    }
+
+    //
+    // Cancel
+    //
+    cpi_cancel_order(ctx.accounts, &group, order_id)?;
+
+    let market_external = ctx.accounts.openbook_v2_market_external.load()?;
+    let after_oo = load_open_orders_native(ctx.accounts.open_orders.as_ref(), &market_external)?;
+    emit!(OpenbookV2OpenOrdersBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        market_index: openbook_market.market_index,
+        base_token_index: openbook_market.base_token_index,
+        quote_token_index: openbook_market.quote_token_index,
+        base_total: after_oo.native_base_total(),
+        base_free: after_oo.native_base_free(),
+        quote_total: after_oo.native_quote_total(),
+        quote_free: after_oo.native_quote_free(),
+        referrer_rebates_available: after_oo.native_rebates(),
+    });
+
+    Ok(())
+}
+
+fn cpi_cancel_order(ctx: &OpenbookV2CancelOrder, group: &Group, order_id: u128) -> Result<()> {
+    let cpi_accounts = openbook_v2::cpi::accounts::CancelOrder {
+        signer: ctx.group.to_account_info(),
+        open_orders_account: ctx.open_orders.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        bids: ctx.bids.to_account_info(),
+        asks: ctx.asks.to_account_info(),
+    };
+
+    let seeds = group_seeds!(group);
+    let signer_seeds = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.openbook_v2_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    openbook_v2::cpi::cancel_order(cpi_ctx, order_id)
+}
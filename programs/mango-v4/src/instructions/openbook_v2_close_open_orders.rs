@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::MangoError;
+use crate::state::*;
+
+pub fn openbook_v2_close_open_orders(ctx: Context<OpenbookV2CloseOpenOrders>) -> Result<()> {
+    //
+    // Validation
+    //
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account
+            .fixed
+            .is_owner_or_delegate(ctx.accounts.authority.key()),
+        MangoError::SomeError
+    );
+
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+
+    // Validate open_orders #2
+    require!(
+        account
+            .openbook_v2_orders(openbook_market.market_index)?
+            .open_orders
+            == ctx.accounts.open_orders.key(),
+        MangoError::SomeError
+    );
+
+    //
+    // close OO
+    //
+    cpi_close_open_orders(ctx.accounts, &openbook_market)?;
+
+    // Reduce the in_use_count on the token positions - they no longer need to be forced open.
+    // We cannot immediately dust tiny positions because we don't have the banks.
+    let (base_position, _) = account.token_position_mut(openbook_market.base_token_index)?;
+    base_position.decrement_in_use();
+    let (quote_position, _) = account.token_position_mut(openbook_market.quote_token_index)?;
+    quote_position.decrement_in_use();
+
+    // Deactivate the openbook v2 open orders account itself
+    account.deactivate_openbook_v2_orders(openbook_market.market_index)?;
+
+    Ok(())
+}
+
+fn cpi_close_open_orders(
+    ctx: &OpenbookV2CloseOpenOrders,
+    openbook_market: &OpenbookV2Market,
+) -> Result<()> {
+    let cpi_accounts = openbook_v2::cpi::accounts::CloseOpenOrdersAccount {
+        owner: ctx.openbook_v2_market.to_account_info(),
+        open_orders_account: ctx.open_orders.to_account_info(),
+        sol_destination: ctx.sol_destination.to_account_info(),
+        system_program: ctx.system_program.to_account_info(),
+    };
+
+    // the open orders account is owned by the mango openbook v2 market
+    let seeds = openbook_v2_market_seeds!(openbook_market);
+    let signer_seeds = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.openbook_v2_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    openbook_v2::cpi::close_open_orders_account(cpi_ctx)
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn openbook_v2_create_open_orders(
+    ctx: Context<OpenbookV2CreateOpenOrders>,
+    account_num: u32,
+) -> Result<()> {
+    cpi_init_open_orders(ctx.accounts, account_num)?;
+
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+    let (base_lot_size, quote_lot_size) = {
+        let market_external = ctx.accounts.openbook_v2_market_external.load()?;
+        (
+            market_external.base_lot_size,
+            market_external.quote_lot_size,
+        )
+    };
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account
+            .fixed
+            .is_owner_or_delegate(ctx.accounts.authority.key()),
+        MangoError::SomeError
+    );
+
+    let openbook_account = account.create_openbook_v2_orders(openbook_market.market_index)?;
+    openbook_account.open_orders = ctx.accounts.open_orders.key();
+    openbook_account.base_token_index = openbook_market.base_token_index;
+    openbook_account.quote_token_index = openbook_market.quote_token_index;
+    openbook_account.base_lot_size = base_lot_size;
+    openbook_account.quote_lot_size = quote_lot_size;
+
+    // Make it so that the token_account_map for the base and quote currency
+    // stay permanently blocked. Otherwise users may end up in situations where
+    // they can't settle a market because they don't have free token_account_map!
+    let (quote_position, _, _) =
+        account.ensure_token_position(openbook_market.quote_token_index)?;
+    quote_position.increment_in_use();
+    let (base_position, _, _) = account.ensure_token_position(openbook_market.base_token_index)?;
+    base_position.increment_in_use();
+
+    Ok(())
+}
+
+fn cpi_init_open_orders(ctx: &OpenbookV2CreateOpenOrders, account_num: u32) -> Result<()> {
+    // The mango openbook v2 market owns the open orders account and the group
+    // is its delegate. That way the group can sign for placing orders with the
+    // bank vaults.
+    let openbook_market = ctx.openbook_v2_market.load()?;
+    let cpi_accounts = openbook_v2::cpi::accounts::CreateOpenOrdersAccount {
+        payer: ctx.payer.to_account_info(),
+        owner: ctx.openbook_v2_market.to_account_info(),
+        delegate_account: Some(ctx.group.to_account_info()),
+        open_orders_account: ctx.open_orders.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        system_program: ctx.system_program.to_account_info(),
+    };
+
+    let seeds = openbook_v2_market_seeds!(openbook_market);
+    let signer_seeds = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.openbook_v2_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    openbook_v2::cpi::create_open_orders_account(cpi_ctx, account_num)
+}
@@ -0,0 +1,33 @@
+use crate::{accounts_ix::*, error::MangoError};
+use anchor_lang::prelude::*;
+
+pub fn openbook_v2_edit_market(
+    ctx: Context<OpenbookV2EditMarket>,
+    reduce_only_opt: Option<bool>,
+    force_close_opt: Option<bool>,
+) -> Result<()> {
+    let mut openbook_market = ctx.accounts.market.load_mut()?;
+
+    if let Some(reduce_only) = reduce_only_opt {
+        msg!(
+            "Reduce only: old - {:?}, new - {:?}",
+            openbook_market.reduce_only,
+            u8::from(reduce_only)
+        );
+        openbook_market.reduce_only = u8::from(reduce_only);
+    };
+
+    if let Some(force_close) = force_close_opt {
+        if force_close {
+            require!(openbook_market.is_reduce_only(), MangoError::SomeError);
+        }
+        msg!(
+            "Force close: old - {:?}, new - {:?}",
+            openbook_market.force_close,
+            u8::from(force_close)
+        );
+        openbook_market.force_close = u8::from(force_close);
+    };
+
+    Ok(())
+}
@@ -0,0 +1,470 @@
+use crate::accounts_zerocopy::AccountInfoRef;
+use crate::error::*;
+use crate::health::*;
+use crate::i80f48::ClampToInt;
+use crate::state::*;
+
+use crate::accounts_ix::*;
+use crate::logs::{OpenbookV2OpenOrdersBalanceLog, TokenBalanceLog};
+use crate::openbook_v2_cpi::{load_open_orders_native, OpenOrdersNative};
+use anchor_lang::prelude::*;
+
+use fixed::types::I80F48;
+use openbook_v2::state::{PlaceOrderType, SelfTradeBehavior, Side};
+
+use super::{OODifference, OpenOrdersAmounts, VaultDifference};
+
+impl OpenOrdersAmounts for OpenOrdersNative {
+    fn native_base_reserved(&self) -> u64 {
+        self.base_reserved
+    }
+    fn native_quote_reserved(&self) -> u64 {
+        self.quote_reserved
+    }
+    fn native_base_free(&self) -> u64 {
+        self.base_free
+    }
+    fn native_quote_free(&self) -> u64 {
+        self.quote_free
+    }
+    fn native_base_total(&self) -> u64 {
+        self.base_total()
+    }
+    fn native_quote_total(&self) -> u64 {
+        self.quote_total()
+    }
+    fn native_rebates(&self) -> u64 {
+        self.referrer_rebates_available
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn openbook_v2_place_order(
+    ctx: Context<OpenbookV2PlaceOrder>,
+    side: Side,
+    limit_price: u64,
+    max_base_qty: u64,
+    max_native_quote_qty_including_fees: u64,
+    self_trade_behavior: SelfTradeBehavior,
+    order_type: PlaceOrderType,
+    client_order_id: u64,
+    limit: u16,
+) -> Result<()> {
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+    require!(
+        !openbook_market.is_reduce_only(),
+        MangoError::MarketInReduceOnlyMode
+    );
+
+    //
+    // Validation
+    //
+    let receiver_token_index;
+    {
+        let account = ctx.accounts.account.load_full()?;
+        // account constraint #1
+        require!(
+            account
+                .fixed
+                .is_owner_or_delegate(ctx.accounts.authority.key()),
+            MangoError::SomeError
+        );
+
+        // Validate open_orders #2
+        require!(
+            account
+                .openbook_v2_orders(openbook_market.market_index)?
+                .open_orders
+                == ctx.accounts.open_orders.key(),
+            MangoError::SomeError
+        );
+
+        // Validate bank and vault #3
+        let payer_bank = ctx.accounts.payer_bank.load()?;
+        require_keys_eq!(payer_bank.vault, ctx.accounts.payer_vault.key());
+        let payer_token_index = match side {
+            Side::Bid => openbook_market.quote_token_index,
+            Side::Ask => openbook_market.base_token_index,
+        };
+        require_eq!(payer_bank.token_index, payer_token_index);
+
+        receiver_token_index = match side {
+            Side::Bid => openbook_market.base_token_index,
+            Side::Ask => openbook_market.quote_token_index,
+        };
+    }
+
+    //
+    // Pre-health computation
+    //
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let retriever = new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+    let mut health_cache =
+        new_health_cache(&account.borrow(), &retriever).context("pre-withdraw init health")?;
+    let pre_health_opt = if !account.fixed.is_in_health_region() {
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        Some(pre_init_health)
+    } else {
+        None
+    };
+
+    // Check if the bank for the token whose balance is increased is in reduce-only mode
+    let receiver_bank_reduce_only = {
+        // The token position already exists, but we need the active_index.
+        let (_, _, active_index) = account.ensure_token_position(receiver_token_index)?;
+        let group_key = ctx.accounts.group.key();
+        let receiver_bank = retriever
+            .bank_and_oracle(&group_key, active_index, receiver_token_index)?
+            .0;
+        receiver_bank.are_deposits_reduce_only()
+    };
+
+    drop(retriever);
+
+    //
+    // Before-order tracking
+    //
+
+    let before_vault = ctx.accounts.payer_vault.amount;
+
+    let (base_lot_size, quote_lot_size, before_oo) = {
+        let market_external = ctx.accounts.openbook_v2_market_external.load()?;
+        let before_oo =
+            load_open_orders_native(ctx.accounts.open_orders.as_ref(), &market_external)?;
+        (
+            market_external.base_lot_size,
+            market_external.quote_lot_size,
+            before_oo,
+        )
+    };
+
+    // Provide a readable error message in case the vault doesn't have enough tokens
+    {
+        let needed_amount = match side {
+            Side::Ask => max_base_qty
+                .checked_mul(
+                    u64::try_from(base_lot_size).map_err(|_| error!(MangoError::MathError))?,
+                )
+                .ok_or_else(|| error!(MangoError::MathError))?
+                .saturating_sub(before_oo.native_base_free()),
+            Side::Bid => {
+                max_native_quote_qty_including_fees.saturating_sub(before_oo.native_quote_free())
+            }
+        };
+        if before_vault < needed_amount {
+            return err!(MangoError::InsufficentBankVaultFunds).with_context(|| {
+                format!(
+                    "bank vault does not have enough tokens, need {} but have {}",
+                    needed_amount, before_vault
+                )
+            });
+        }
+    }
+
+    //
+    // Apply the order to openbook
+    //
+    let price_lots: i64 = limit_price.try_into().map_err(|_| {
+        error_msg_typed!(
+            MangoError::MathError,
+            "limit price {} too large",
+            limit_price
+        )
+    })?;
+    let max_base_lots: i64 = max_base_qty.try_into().map_err(|_| {
+        error_msg_typed!(
+            MangoError::MathError,
+            "max base qty {} too large",
+            max_base_qty
+        )
+    })?;
+    let quote_lot_size =
+        u64::try_from(quote_lot_size).map_err(|_| error!(MangoError::MathError))?;
+    let max_quote_lots_including_fees: i64 = (max_native_quote_qty_including_fees / quote_lot_size)
+        .try_into()
+        .map_err(|_| error!(MangoError::MathError))?;
+    let order = openbook_v2::PlaceOrderArgs {
+        side,
+        price_lots,
+        max_base_lots,
+        max_quote_lots_including_fees,
+        client_order_id,
+        order_type,
+        expiry_timestamp: 0,
+        self_trade_behavior,
+        limit: limit.into(),
+    };
+    cpi_place_order(ctx.accounts, side, order)?;
+
+    //
+    // After-order tracking
+    //
+    let after_oo = {
+        let market_external = ctx.accounts.openbook_v2_market_external.load()?;
+        load_open_orders_native(ctx.accounts.open_orders.as_ref(), &market_external)?
+    };
+    let oo_difference = OODifference::new(&before_oo, &after_oo);
+
+    emit!(OpenbookV2OpenOrdersBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        market_index: openbook_market.market_index,
+        base_token_index: openbook_market.base_token_index,
+        quote_token_index: openbook_market.quote_token_index,
+        base_total: after_oo.native_base_total(),
+        base_free: after_oo.native_base_free(),
+        quote_total: after_oo.native_quote_total(),
+        quote_free: after_oo.native_quote_free(),
+        referrer_rebates_available: after_oo.native_rebates(),
+    });
+
+    ctx.accounts.payer_vault.reload()?;
+    let after_vault = ctx.accounts.payer_vault.amount;
+
+    // Placing an order cannot increase vault balance
+    require_gte!(before_vault, after_vault);
+
+    let mut payer_bank = ctx.accounts.payer_bank.load_mut()?;
+
+    // Enforce min vault to deposits ratio
+    let withdrawn_from_vault = I80F48::from(before_vault - after_vault);
+    let position_native = account
+        .token_position_mut(payer_bank.token_index)?
+        .0
+        .native(&payer_bank);
+
+    // Charge the difference in vault balance to the user's account
+    let vault_difference = {
+        apply_vault_difference(
+            ctx.accounts.account.key(),
+            &mut account.borrow_mut(),
+            openbook_market.market_index,
+            &mut payer_bank,
+            after_vault,
+            before_vault,
+        )?
+    };
+
+    if withdrawn_from_vault > position_native {
+        require_msg_typed!(
+            !payer_bank.are_borrows_reduce_only(),
+            MangoError::TokenInReduceOnlyMode,
+            "the payer tokens cannot be borrowed"
+        );
+        let oracle_price =
+            payer_bank.oracle_price(&AccountInfoRef::borrow(&ctx.accounts.payer_oracle)?, None)?;
+        payer_bank.enforce_min_vault_to_deposits_ratio((*ctx.accounts.payer_vault).as_ref())?;
+        payer_bank.check_net_borrows(oracle_price)?;
+    }
+
+    vault_difference.adjust_health_cache_token_balance(&mut health_cache, &payer_bank)?;
+    oo_difference.adjust_health_cache_openbook_v2_state(&mut health_cache, &openbook_market)?;
+
+    // Check the receiver's reduce only flag.
+    //
+    // Note that all orders on the book executing can still cause a net deposit. That's because
+    // the total spot potential amount assumes all reserved amounts convert at the current
+    // oracle price.
+    if receiver_bank_reduce_only {
+        let balance = health_cache.token_info(receiver_token_index)?.balance_spot;
+        let potential =
+            health_cache.total_serum3_potential(HealthType::Maint, receiver_token_index)?;
+        require_msg_typed!(
+            balance + potential < 1,
+            MangoError::TokenInReduceOnlyMode,
+            "receiver bank does not accept deposits"
+        );
+    }
+
+    //
+    // Health check
+    //
+    if let Some(pre_init_health) = pre_health_opt {
+        account.check_health_post(&health_cache, pre_init_health)?;
+    }
+
+    Ok(())
+}
+
+/// Called in openbook_v2_apply_settle_changes() and place_order to adjust token positions
+/// after changing the vault balances
+/// Also logs changes to token balances
+fn apply_vault_difference(
+    account_pk: Pubkey,
+    account: &mut MangoAccountRefMut,
+    openbook_market_index: OpenbookV2MarketIndex,
+    bank: &mut Bank,
+    vault_after: u64,
+    vault_before: u64,
+) -> Result<VaultDifference> {
+    let needed_change = I80F48::from(vault_after) - I80F48::from(vault_before);
+
+    let (position, _) = account.token_position_mut(bank.token_index)?;
+    let native_before = position.native(bank);
+    let now_ts = Clock::get()?.unix_timestamp.try_into().unwrap();
+    if needed_change >= 0 {
+        bank.deposit(position, needed_change, now_ts)?;
+    } else {
+        bank.withdraw_without_fee(position, -needed_change, now_ts)?;
+    }
+    let native_after = position.native(bank);
+    let native_change = native_after - native_before;
+    let new_borrows = native_change
+        .max(native_after)
+        .min(I80F48::ZERO)
+        .abs()
+        .to_num::<u64>();
+
+    let indexed_position = position.indexed_position;
+    let market = account
+        .openbook_v2_orders_mut(openbook_market_index)
+        .unwrap();
+    let borrows_without_fee = if bank.token_index == market.base_token_index {
+        &mut market.base_borrows_without_fee
+    } else if bank.token_index == market.quote_token_index {
+        &mut market.quote_borrows_without_fee
+    } else {
+        return Err(error_msg!(
+            "assert failed: apply_vault_difference called with bad token index"
+        ));
+    };
+
+    // Only for place: Add to potential borrow amount
+    let old_value = *borrows_without_fee;
+    *borrows_without_fee = old_value + new_borrows;
+
+    // Only for settle/liq_force_cancel: Reduce the potential borrow amounts
+    if needed_change > 0 {
+        *borrows_without_fee = (*borrows_without_fee).saturating_sub(needed_change.to_num::<u64>());
+    }
+
+    emit!(TokenBalanceLog {
+        mango_group: bank.group,
+        mango_account: account_pk,
+        token_index: bank.token_index,
+        indexed_position: indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    Ok(VaultDifference::new(bank.token_index, native_change))
+}
+
+/// Uses the changes in OpenOrdersAccount and vaults to adjust the user token position,
+/// collect fees and optionally adjusts the HealthCache.
+#[allow(clippy::too_many_arguments)]
+pub fn openbook_v2_apply_settle_changes(
+    group: &Group,
+    account_pk: Pubkey,
+    account: &mut MangoAccountRefMut,
+    base_bank: &mut Bank,
+    quote_bank: &mut Bank,
+    openbook_market: &OpenbookV2Market,
+    before_base_vault: u64,
+    before_quote_vault: u64,
+    before_oo: &OpenOrdersNative,
+    after_base_vault: u64,
+    after_quote_vault: u64,
+    after_oo: &OpenOrdersNative,
+    health_cache: Option<&mut HealthCache>,
+    fees_to_dao: bool,
+    quote_oracle: Option<&AccountInfo>,
+) -> Result<()> {
+    let mut received_fees = 0;
+    if fees_to_dao {
+        // Example: rebates go from 100 -> 10. That means we credit 90 in fees.
+        received_fees = before_oo
+            .native_rebates()
+            .saturating_sub(after_oo.native_rebates());
+        quote_bank.collected_fees_native += I80F48::from(received_fees);
+
+        // Credit the buyback_fees at the current value of the quote token.
+        if let Some(quote_oracle_ai) = quote_oracle {
+            let clock = Clock::get()?;
+            let now_ts = clock.unix_timestamp.try_into().unwrap();
+
+            let quote_oracle_price = quote_bank
+                .oracle_price(&AccountInfoRef::borrow(quote_oracle_ai)?, Some(clock.slot))?;
+            let quote_asset_price = quote_oracle_price.min(quote_bank.stable_price());
+            account
+                .fixed
+                .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+            let fees_in_usd = I80F48::from(received_fees) * quote_asset_price;
+            account
+                .fixed
+                .accrue_buyback_fees(fees_in_usd.clamp_to_u64());
+        }
+    }
+
+    // Don't count the referrer rebate fees as part of the vault change that should be
+    // credited to the user.
+    let after_quote_vault_adjusted = after_quote_vault - received_fees;
+
+    // Settle cannot decrease vault balances
+    require_gte!(after_base_vault, before_base_vault);
+    require_gte!(after_quote_vault_adjusted, before_quote_vault);
+
+    // Credit the difference in vault balances to the user's account
+    let base_difference = apply_vault_difference(
+        account_pk,
+        account,
+        openbook_market.market_index,
+        base_bank,
+        after_base_vault,
+        before_base_vault,
+    )?;
+    let quote_difference = apply_vault_difference(
+        account_pk,
+        account,
+        openbook_market.market_index,
+        quote_bank,
+        after_quote_vault_adjusted,
+        before_quote_vault,
+    )?;
+
+    if let Some(health_cache) = health_cache {
+        base_difference.adjust_health_cache_token_balance(health_cache, base_bank)?;
+        quote_difference.adjust_health_cache_token_balance(health_cache, quote_bank)?;
+
+        OODifference::new(before_oo, after_oo)
+            .adjust_health_cache_openbook_v2_state(health_cache, openbook_market)?;
+    }
+
+    Ok(())
+}
+
+fn cpi_place_order(
+    ctx: &OpenbookV2PlaceOrder,
+    side: Side,
+    order: openbook_v2::PlaceOrderArgs,
+) -> Result<()> {
+    let group = ctx.group.load()?;
+    let market_vault = match side {
+        Side::Bid => ctx.market_quote_vault.to_account_info(),
+        Side::Ask => ctx.market_base_vault.to_account_info(),
+    };
+    let cpi_accounts = openbook_v2::cpi::accounts::PlaceOrder {
+        signer: ctx.group.to_account_info(),
+        open_orders_account: ctx.open_orders.to_account_info(),
+        open_orders_admin: None,
+        user_token_account: ctx.payer_vault.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        bids: ctx.bids.to_account_info(),
+        asks: ctx.asks.to_account_info(),
+        event_queue: ctx.event_queue.to_account_info(),
+        market_vault,
+        oracle_a: None,
+        oracle_b: None,
+        token_program: ctx.token_program.to_account_info(),
+    };
+
+    let seeds = group_seeds!(group);
+    let signer_seeds = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.openbook_v2_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    openbook_v2::cpi::place_order(cpi_ctx, order)?;
+    Ok(())
+}
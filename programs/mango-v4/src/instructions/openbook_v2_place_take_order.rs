@@ -0,0 +1,298 @@
+use crate::accounts_zerocopy::AccountInfoRef;
+use crate::error::*;
+use crate::health::*;
+use crate::state::*;
+
+use crate::accounts_ix::*;
+use crate::logs::{LoanOriginationFeeInstruction, TokenBalanceLog, WithdrawLoanLog};
+use anchor_lang::prelude::*;
+
+use fixed::types::I80F48;
+use openbook_v2::state::{PlaceOrderType, SelfTradeBehavior, Side};
+
+/// Places an order that is matched against the book immediately and never rests on it.
+///
+/// Since nothing remains on an open orders account, the filled amounts move between
+/// the payer and receiver bank vaults directly and no open orders account is needed.
+///
+/// Openbook can't detect a take order matching the account's own resting orders. Unless
+/// `self_trade_behavior` is DecrementTake, the order is rejected while the account has an
+/// open orders account on the market.
+#[allow(clippy::too_many_arguments)]
+pub fn openbook_v2_place_take_order(
+    ctx: Context<OpenbookV2PlaceTakeOrder>,
+    side: Side,
+    limit_price: u64,
+    max_base_qty: u64,
+    max_native_quote_qty_including_fees: u64,
+    self_trade_behavior: SelfTradeBehavior,
+    limit: u16,
+) -> Result<()> {
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+    require!(
+        !openbook_market.is_reduce_only(),
+        MangoError::MarketInReduceOnlyMode
+    );
+
+    //
+    // Validation
+    //
+    let (payer_token_index, receiver_token_index) = match side {
+        Side::Bid => (
+            openbook_market.quote_token_index,
+            openbook_market.base_token_index,
+        ),
+        Side::Ask => (
+            openbook_market.base_token_index,
+            openbook_market.quote_token_index,
+        ),
+    };
+    {
+        let account = ctx.accounts.account.load_full()?;
+        // account constraint #1
+        require!(
+            account
+                .fixed
+                .is_owner_or_delegate(ctx.accounts.authority.key()),
+            MangoError::SomeError
+        );
+
+        // Validate banks and vaults #3
+        let payer_bank = ctx.accounts.payer_bank.load()?;
+        require_keys_eq!(payer_bank.vault, ctx.accounts.payer_vault.key());
+        require_eq!(payer_bank.token_index, payer_token_index);
+        let receiver_bank = ctx.accounts.receiver_bank.load()?;
+        require_keys_eq!(receiver_bank.vault, ctx.accounts.receiver_vault.key());
+        require_eq!(receiver_bank.token_index, receiver_token_index);
+
+        if !matches!(self_trade_behavior, SelfTradeBehavior::DecrementTake) {
+            require_msg_typed!(
+                account
+                    .borrow()
+                    .openbook_v2_orders(openbook_market.market_index)
+                    .is_err(),
+                MangoError::WouldSelfTrade,
+                "take orders can't avoid self trades while the account has open orders on the market"
+            );
+        }
+    }
+
+    //
+    // Pre-health computation
+    //
+    let mut account = ctx.accounts.account.load_full_mut()?;
+
+    // The token positions must exist before the health computation, since the
+    // client passes the banks for both of them
+    account.ensure_token_position(payer_token_index)?;
+    account.ensure_token_position(receiver_token_index)?;
+
+    let pre_health_opt = if !account.fixed.is_in_health_region() {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache =
+            new_health_cache(&account.borrow(), &retriever).context("pre-withdraw init health")?;
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        Some(pre_init_health)
+    } else {
+        None
+    };
+
+    //
+    // Before-order tracking
+    //
+    let before_payer_vault = ctx.accounts.payer_vault.amount;
+    let before_receiver_vault = ctx.accounts.receiver_vault.amount;
+
+    // Provide a readable error message in case the vault doesn't have enough tokens
+    let quote_lot_size = {
+        let market_external = ctx.accounts.openbook_v2_market_external.load()?;
+        let needed_amount = match side {
+            Side::Ask => max_base_qty * u64::try_from(market_external.base_lot_size).unwrap(),
+            Side::Bid => max_native_quote_qty_including_fees,
+        };
+        if before_payer_vault < needed_amount {
+            return err!(MangoError::InsufficentBankVaultFunds).with_context(|| {
+                format!(
+                    "bank vault does not have enough tokens, need {} but have {}",
+                    needed_amount, before_payer_vault
+                )
+            });
+        }
+        market_external.quote_lot_size
+    };
+
+    //
+    // Apply the order to openbook
+    //
+    let price_lots: i64 = limit_price.try_into().map_err(|_| {
+        error_msg_typed!(
+            MangoError::MathError,
+            "limit price {} too large",
+            limit_price
+        )
+    })?;
+    let max_base_lots: i64 = max_base_qty.try_into().map_err(|_| {
+        error_msg_typed!(
+            MangoError::MathError,
+            "max base qty {} too large",
+            max_base_qty
+        )
+    })?;
+    let quote_lot_size =
+        u64::try_from(quote_lot_size).map_err(|_| error!(MangoError::MathError))?;
+    let max_quote_lots_including_fees: i64 = (max_native_quote_qty_including_fees / quote_lot_size)
+        .try_into()
+        .map_err(|_| error!(MangoError::MathError))?;
+    let order = openbook_v2::PlaceTakeOrderArgs {
+        side,
+        price_lots,
+        max_base_lots,
+        max_quote_lots_including_fees,
+        order_type: PlaceOrderType::Market,
+        limit: limit.into(),
+    };
+    cpi_place_take_order(ctx.accounts, side, order)?;
+
+    //
+    // After-order tracking
+    //
+    ctx.accounts.payer_vault.reload()?;
+    ctx.accounts.receiver_vault.reload()?;
+    let after_payer_vault = ctx.accounts.payer_vault.amount;
+    let after_receiver_vault = ctx.accounts.receiver_vault.amount;
+
+    // Taking cannot increase the payer vault or decrease the receiver vault
+    require_gte!(before_payer_vault, after_payer_vault);
+    require_gte!(after_receiver_vault, before_receiver_vault);
+
+    let now_ts = Clock::get()?.unix_timestamp.try_into().unwrap();
+
+    // Charge the payer, including loan origination fees: the loan is immediately materialized
+    {
+        let mut payer_bank = ctx.accounts.payer_bank.load_mut()?;
+        let withdraw_amount = I80F48::from(before_payer_vault - after_payer_vault);
+        let (position, _) = account.token_position_mut(payer_bank.token_index)?;
+        let position_native = position.native(&payer_bank);
+        let withdraw_result = payer_bank.withdraw_with_fee(position, withdraw_amount, now_ts)?;
+        let indexed_position = position.indexed_position;
+
+        emit!(TokenBalanceLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: ctx.accounts.account.key(),
+            token_index: payer_bank.token_index,
+            indexed_position: indexed_position.to_bits(),
+            deposit_index: payer_bank.deposit_index.to_bits(),
+            borrow_index: payer_bank.borrow_index.to_bits(),
+        });
+
+        if withdraw_amount > position_native {
+            require_msg_typed!(
+                !payer_bank.are_borrows_reduce_only(),
+                MangoError::TokenInReduceOnlyMode,
+                "the payer tokens cannot be borrowed"
+            );
+            let oracle_price = payer_bank
+                .oracle_price(&AccountInfoRef::borrow(&ctx.accounts.payer_oracle)?, None)?;
+            payer_bank.enforce_min_vault_to_deposits_ratio((*ctx.accounts.payer_vault).as_ref())?;
+            payer_bank.check_net_borrows(oracle_price)?;
+
+            if withdraw_result.has_loan() {
+                emit!(WithdrawLoanLog {
+                    mango_group: ctx.accounts.group.key(),
+                    mango_account: ctx.accounts.account.key(),
+                    token_index: payer_bank.token_index,
+                    loan_amount: withdraw_result.loan_amount.to_bits(),
+                    loan_origination_fee: withdraw_result.loan_origination_fee.to_bits(),
+                    instruction: LoanOriginationFeeInstruction::OpenbookV2PlaceTakeOrder,
+                    price: Some(oracle_price.to_bits()),
+                });
+            }
+        }
+    }
+
+    // Credit the receiver
+    {
+        let mut receiver_bank = ctx.accounts.receiver_bank.load_mut()?;
+        let deposit_amount = I80F48::from(after_receiver_vault - before_receiver_vault);
+        let (position, _) = account.token_position_mut(receiver_bank.token_index)?;
+        receiver_bank.deposit(position, deposit_amount, now_ts)?;
+
+        // Check the receiver's reduce only flag.
+        if receiver_bank.are_deposits_reduce_only() {
+            require_msg_typed!(
+                position.native(&receiver_bank) < 1,
+                MangoError::TokenInReduceOnlyMode,
+                "receiver bank does not accept deposits"
+            );
+        }
+
+        emit!(TokenBalanceLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: ctx.accounts.account.key(),
+            token_index: receiver_bank.token_index,
+            indexed_position: position.indexed_position.to_bits(),
+            deposit_index: receiver_bank.deposit_index.to_bits(),
+            borrow_index: receiver_bank.borrow_index.to_bits(),
+        });
+    }
+
+    //
+    // Health check
+    //
+    if let Some(pre_init_health) = pre_health_opt {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache =
+            new_health_cache(&account.borrow(), &retriever).context("post-order init health")?;
+        account.check_health_post(&health_cache, pre_init_health)?;
+    }
+
+    Ok(())
+}
+
+fn cpi_place_take_order(
+    ctx: &OpenbookV2PlaceTakeOrder,
+    side: Side,
+    order: openbook_v2::PlaceTakeOrderArgs,
+) -> Result<()> {
+    let group = ctx.group.load()?;
+    let (user_base_account, user_quote_account) = match side {
+        Side::Bid => (
+            ctx.receiver_vault.to_account_info(),
+            ctx.payer_vault.to_account_info(),
+        ),
+        Side::Ask => (
+            ctx.payer_vault.to_account_info(),
+            ctx.receiver_vault.to_account_info(),
+        ),
+    };
+    let cpi_accounts = openbook_v2::cpi::accounts::PlaceTakeOrder {
+        signer: ctx.group.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        market_authority: ctx.market_vault_signer.to_account_info(),
+        bids: ctx.bids.to_account_info(),
+        asks: ctx.asks.to_account_info(),
+        market_base_vault: ctx.market_base_vault.to_account_info(),
+        market_quote_vault: ctx.market_quote_vault.to_account_info(),
+        event_queue: ctx.event_queue.to_account_info(),
+        user_base_account,
+        user_quote_account,
+        oracle_a: None,
+        oracle_b: None,
+        token_program: ctx.token_program.to_account_info(),
+        system_program: ctx.system_program.to_account_info(),
+        open_orders_admin: None,
+        referrer: None,
+    };
+
+    let seeds = group_seeds!(group);
+    let signer_seeds = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.openbook_v2_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    openbook_v2::cpi::place_take_order(cpi_ctx, order)?;
+    Ok(())
+}
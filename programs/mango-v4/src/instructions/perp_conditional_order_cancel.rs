@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::logs::PerpConditionalOrderCancelLog;
+use crate::state::*;
+
+pub fn perp_conditional_order_cancel(
+    ctx: Context<PerpConditionalOrderCancel>,
+    perp_conditional_order_index: usize,
+    perp_conditional_order_id: u64,
+) -> Result<()> {
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let order = account.perp_conditional_order_mut_by_index(perp_conditional_order_index)?;
+
+    // If the conditional order is already inactive, this just is a noop
+    if !order.has_data() {
+        return Ok(());
+    }
+
+    require_eq!(order.id, perp_conditional_order_id);
+    *order = PerpConditionalOrder::default();
+
+    emit!(PerpConditionalOrderCancelLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        id: perp_conditional_order_id,
+    });
+
+    Ok(())
+}
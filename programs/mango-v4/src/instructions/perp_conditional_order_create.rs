@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::logs::PerpConditionalOrderCreateLog;
+use crate::state::*;
+
+pub fn perp_conditional_order_create(
+    ctx: Context<PerpConditionalOrderCreate>,
+    perp_conditional_order: PerpConditionalOrder,
+) -> Result<()> {
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    if perp_conditional_order.is_expired(now_ts) {
+        msg!("Already expired, ignoring");
+        return Ok(());
+    }
+
+    perp_conditional_order.check_valid()?;
+
+    let perp_market = ctx.accounts.perp_market.load()?;
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+
+    let id = account.fixed.next_perp_conditional_order_id;
+    account.fixed.next_perp_conditional_order_id =
+        account.fixed.next_perp_conditional_order_id.wrapping_add(1);
+
+    let order = account.free_perp_conditional_order_mut()?;
+    *order = perp_conditional_order;
+    order.id = id;
+    order.perp_market_index = perp_market.perp_market_index;
+    order.executed_base_lots = 0;
+    order.has_data = 1;
+
+    emit!(PerpConditionalOrderCreateLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        id,
+        perp_market_index: order.perp_market_index,
+        side: order.side,
+        max_base_lots: order.max_base_lots,
+        expiry_timestamp: order.expiry_timestamp,
+        price_lower_limit: order.price_lower_limit,
+        price_upper_limit: order.price_upper_limit,
+        price_premium_rate: order.price_premium_rate,
+        reduce_only: order.reduce_only(),
+        intention: order.intention,
+        execution: order.execution,
+        order_type: order.order_type,
+        price_lots: order.price_lots,
+        peg_to_oracle: order.peg_to_oracle(),
+        max_quote_lots: order.max_quote_lots,
+        trigger_premium: order.trigger_premium,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::*;
+use crate::logs::{
+    emit_perp_balances, PerpConditionalOrderCancelLog, PerpConditionalOrderTriggerLog,
+};
+use crate::state::*;
+
+/// Executes a perp conditional order of the liqee against the liqor.
+///
+/// The liqor takes the opposite side of the trade, at the oracle price adjusted
+/// by the order's premium. Like for token_conditional_swap_trigger, the remaining
+/// accounts must contain the health accounts of both the liqee and the liqor.
+pub fn perp_conditional_order_trigger(
+    ctx: Context<PerpConditionalOrderTrigger>,
+    perp_conditional_order_index: usize,
+    perp_conditional_order_id: u64,
+    max_base_lots: i64,
+) -> Result<()> {
+    let group_pk = &ctx.accounts.group.key();
+    let liqee_key = ctx.accounts.liqee.key();
+    let liqor_key = ctx.accounts.liqor.key();
+    require_keys_neq!(liqee_key, liqor_key);
+
+    let mut liqor = ctx.accounts.liqor.load_full_mut()?;
+    require_msg_typed!(
+        !liqor.fixed.being_liquidated(),
+        MangoError::BeingLiquidated,
+        "liqor account"
+    );
+
+    let mut liqee = ctx.accounts.liqee.load_full_mut()?;
+
+    let order = *liqee.perp_conditional_order_by_index(perp_conditional_order_index)?;
+    require!(order.has_data(), MangoError::SomeError);
+    require_eq!(order.id, perp_conditional_order_id);
+    require_msg!(
+        order.execution() == PerpConditionalOrderExecution::TradeWithTriggerer,
+        "the conditional order must be executed with perp_conditional_order_trigger_place_order"
+    );
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+
+    // Possibly wipe the order and exit, if it's already expired
+    if order.is_expired(now_ts) {
+        *liqee.perp_conditional_order_mut_by_index(perp_conditional_order_index)? =
+            PerpConditionalOrder::default();
+
+        msg!("PerpConditionalOrder is expired, removing");
+        emit!(PerpConditionalOrderCancelLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: liqee_key,
+            id: perp_conditional_order_id,
+        });
+
+        return Ok(());
+    }
+
+    let (perp_market_index, settle_token_index) = {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        (
+            perp_market.perp_market_index,
+            perp_market.settle_token_index,
+        )
+    };
+    require_eq!(order.perp_market_index, perp_market_index);
+
+    // Both accounts need a perp position before their health is computed
+    liqee.ensure_perp_position(perp_market_index, settle_token_index)?;
+    liqor.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    let mut liqee_health_cache = {
+        let account_retriever = ScanningAccountRetriever::new(
+            ctx.remaining_accounts,
+            group_pk,
+            &[&liqor.borrow(), &liqee.borrow()],
+        )
+        .context("create account retriever")?;
+        new_health_cache(&liqee.borrow(), &account_retriever)
+            .context("create liqee health cache")?
+    };
+    let liqee_pre_init_health = liqee.check_health_pre(&liqee_health_cache)?;
+    let liqee_pre_isolated_health =
+        liqee_health_cache.isolated_perp_init_health_opt(perp_market_index)?;
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+
+    // The oracle decides whether the order may execute at all, it must not be stale
+    let oracle_price = perp_market.oracle_price(
+        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        Some(Clock::get()?.slot),
+    )?;
+    let oracle_price_f64 = oracle_price.to_num::<f64>();
+    require!(
+        order.price_in_range(oracle_price_f64),
+        MangoError::PerpConditionalOrderPriceNotInRange
+    );
+    let premium_price = I80F48::from_num(order.premium_price(oracle_price_f64));
+
+    let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
+    require!(
+        !liqee_perp_position.has_open_taker_fills(),
+        MangoError::HasOpenPerpTakerFills
+    );
+    liqee_perp_position.settle_funding(&perp_market);
+    let liqee_base_lots = liqee_perp_position.base_position_lots();
+
+    let liqor_perp_position = liqor.perp_position_mut(perp_market_index)?;
+    liqor_perp_position.settle_funding(&perp_market);
+    let liqor_base_lots = liqor_perp_position.base_position_lots();
+
+    //
+    // Determine the trade size
+    //
+    let market_reduce_only = perp_market.is_reduce_only();
+    let mut base_lots = max_base_lots
+        .max(0)
+        .min(order.max_base_lots_for_position(liqee_base_lots, market_reduce_only));
+    if market_reduce_only {
+        // The liqor may not increase its position either
+        let liqor_reducible = match order.side() {
+            Side::Bid => liqor_base_lots.max(0),
+            Side::Ask => (-liqor_base_lots).max(0),
+        };
+        base_lots = base_lots.min(liqor_reducible);
+    }
+
+    let base_change = match order.side() {
+        Side::Bid => base_lots,
+        Side::Ask => -base_lots,
+    };
+    let quote_change = -I80F48::from(base_change * perp_market.base_lot_size) * premium_price;
+
+    //
+    // Execute the trade, this is essentially a forced trade between the two accounts
+    //
+    if base_change != 0 {
+        msg!(
+            "trading: {} base lots for {} quote",
+            base_change,
+            quote_change
+        );
+        let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
+        liqee_perp_position.record_trade(&mut perp_market, base_change, quote_change);
+        let liqor_perp_position = liqor.perp_position_mut(perp_market_index)?;
+        liqor_perp_position.record_trade(&mut perp_market, -base_change, -quote_change);
+    }
+
+    // Check liqee health after the trade
+    let liqee_perp_position = liqee.perp_position(perp_market_index)?;
+    let liqee_post_base_lots = liqee_perp_position.base_position_lots();
+    liqee_health_cache.recompute_perp_info(liqee_perp_position, &perp_market)?;
+    liqee.check_health_post(&liqee_health_cache, liqee_pre_init_health)?;
+    liqee_health_cache
+        .check_isolated_perp_health_post(perp_market_index, liqee_pre_isolated_health)?;
+
+    // Update the order, dropping it if no further execution is possible
+    let closed = {
+        let order = liqee.perp_conditional_order_mut_by_index(perp_conditional_order_index)?;
+        order.executed_base_lots += base_lots;
+        require_msg_typed!(
+            order.executed_base_lots <= order.max_base_lots,
+            MangoError::SomeError,
+            "executed {} base lots, but the order allows only {}",
+            order.executed_base_lots,
+            order.max_base_lots
+        );
+
+        let closed =
+            order.max_base_lots_for_position(liqee_post_base_lots, market_reduce_only) == 0;
+        if closed {
+            *order = PerpConditionalOrder::default();
+        }
+        closed
+    };
+
+    emit_perp_balances(
+        ctx.accounts.group.key(),
+        liqee_key,
+        liqee.perp_position(perp_market_index)?,
+        &perp_market,
+    );
+    emit_perp_balances(
+        ctx.accounts.group.key(),
+        liqor_key,
+        liqor.perp_position(perp_market_index)?,
+        &perp_market,
+    );
+    emit!(PerpConditionalOrderTriggerLog {
+        mango_group: ctx.accounts.group.key(),
+        liqee: liqee_key,
+        liqor: liqor_key,
+        perp_conditional_order_id,
+        perp_market_index,
+        base_lots: base_change,
+        quote_native: quote_change.to_bits(),
+        oracle_price: oracle_price.to_bits(),
+        closed,
+    });
+
+    drop(perp_market);
+
+    // Check liqor health, liqee health was checked above
+    let account_retriever = ScanningAccountRetriever::new(
+        ctx.remaining_accounts,
+        group_pk,
+        &[&liqor.borrow(), &liqee.borrow()],
+    )
+    .context("create account retriever end")?;
+    let liqor_health_cache = new_health_cache(&liqor.borrow(), &account_retriever)
+        .context("create liqor health cache")?;
+    if !liqor.fixed.is_in_health_region() {
+        let liqor_health = liqor_health_cache.health(HealthType::Init);
+        require!(liqor_health >= 0, MangoError::HealthMustBePositive);
+    }
+    liqor_health_cache.check_isolated_perp_health_post(perp_market_index, None)?;
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::{new_fixed_order_account_retriever, new_health_cache};
+use crate::logs::{PerpConditionalOrderCancelLog, PerpConditionalOrderPlaceLog};
+use crate::state::*;
+
+use super::perp_place_order::reduce_only_max_base_lots;
+
+/// Places a PlaceOrder perp conditional order into the book, once the oracle price
+/// is in range, and pays the triggerer the trigger premium.
+pub fn perp_conditional_order_trigger_place_order(
+    ctx: Context<PerpConditionalOrderTriggerPlaceOrder>,
+    perp_conditional_order_index: usize,
+    perp_conditional_order_id: u64,
+    limit: u8,
+) -> Result<()> {
+    let account_pk = ctx.accounts.account.key();
+    let triggerer_pk = ctx.accounts.triggerer.key();
+    require_keys_neq!(account_pk, triggerer_pk);
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let now_slot = Clock::get()?.slot;
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let conditional_order =
+        *account.perp_conditional_order_by_index(perp_conditional_order_index)?;
+    require!(conditional_order.has_data(), MangoError::SomeError);
+    require_eq!(conditional_order.id, perp_conditional_order_id);
+    require_msg!(
+        conditional_order.execution() == PerpConditionalOrderExecution::PlaceOrder,
+        "the conditional order must be executed with perp_conditional_order_trigger"
+    );
+
+    let (perp_market_index, settle_token_index) = {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        (
+            perp_market.perp_market_index,
+            perp_market.settle_token_index,
+        )
+    };
+    require_eq!(conditional_order.perp_market_index, perp_market_index);
+
+    // Possibly wipe the conditional order and exit, if it's already expired
+    if conditional_order.is_expired(now_ts) {
+        *account.perp_conditional_order_mut_by_index(perp_conditional_order_index)? =
+            PerpConditionalOrder::default();
+
+        msg!("PerpConditionalOrder is expired, removing");
+        emit!(PerpConditionalOrderCancelLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: account_pk,
+            id: perp_conditional_order_id,
+        });
+
+        return Ok(());
+    }
+
+    // Update funding and check the trigger condition.
+    //
+    // Unlike when placing orders, the oracle must not be stale: it decides whether
+    // the order may be placed at all.
+    let oracle_price;
+    {
+        let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+        let book = Orderbook {
+            bids: ctx.accounts.bids.load_mut()?,
+            asks: ctx.accounts.asks.load_mut()?,
+        };
+
+        let oracle_state = perp_market.oracle_state(
+            &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+            Some(now_slot),
+        )?;
+        oracle_price = oracle_state.price;
+
+        perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts)?;
+    }
+
+    require!(
+        conditional_order.price_in_range(oracle_price.to_num::<f64>()),
+        MangoError::PerpConditionalOrderPriceNotInRange
+    );
+
+    // Placing the order is one-shot: it's removed no matter how much of it executes
+    *account.perp_conditional_order_mut_by_index(perp_conditional_order_index)? =
+        PerpConditionalOrder::default();
+
+    let mut order = conditional_order.to_order()?;
+    let market_reduce_only = ctx.accounts.perp_market.load()?.is_reduce_only();
+    let max_base_lots = if order.reduce_only || market_reduce_only {
+        match account.perp_position(perp_market_index) {
+            Ok(pp) => reduce_only_max_base_lots(pp, &order, market_reduce_only),
+            Err(_) => 0,
+        }
+    } else {
+        order.max_base_lots
+    };
+    if market_reduce_only {
+        require!(
+            order.reduce_only || max_base_lots == order.max_base_lots,
+            MangoError::MarketInReduceOnlyMode
+        )
+    };
+
+    // A reduce-only order has nothing to place if the position was closed or flipped
+    // in the meantime. Remove it without paying the trigger premium.
+    if max_base_lots == 0 {
+        msg!("PerpConditionalOrder has nothing to reduce, removing");
+        emit!(PerpConditionalOrderCancelLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: account_pk,
+            id: perp_conditional_order_id,
+        });
+
+        return Ok(());
+    }
+    order.max_base_lots = max_base_lots;
+
+    account.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    //
+    // Pre-health computation, _after_ perp position is created
+    //
+    // Health regions only check the cross-margin health when they end
+    let is_isolated = account.perp_position(perp_market_index)?.is_isolated();
+    require_msg_typed!(
+        !is_isolated || !account.fixed.is_in_health_region(),
+        MangoError::PerpPositionIsIsolated,
+        "isolated perp positions can't be traded inside a health region"
+    );
+
+    let pre_health_opt = if !account.fixed.is_in_health_region() {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache =
+            new_health_cache(&account.borrow(), &retriever).context("pre-trigger init health")?;
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        let pre_isolated_health = health_cache.isolated_perp_init_health_opt(perp_market_index)?;
+        Some((health_cache, pre_init_health, pre_isolated_health))
+    } else {
+        None
+    };
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+    let group = ctx.accounts.group.load()?;
+
+    account
+        .fixed
+        .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+
+    let order_id_opt = book.new_order(
+        order,
+        &mut perp_market,
+        &mut event_queue,
+        oracle_price,
+        &mut account.borrow_mut(),
+        &account_pk,
+        now_ts,
+        limit,
+    )?;
+
+    //
+    // Pay the premium to the triggerer
+    //
+    let premium = I80F48::from(conditional_order.trigger_premium);
+    if premium > 0 {
+        let perp_position = account.perp_position_mut(perp_market_index)?;
+        perp_position.record_perp_conditional_order_premium(-premium);
+
+        let mut triggerer = ctx.accounts.triggerer.load_full_mut()?;
+        let (triggerer_perp_position, _) =
+            triggerer.ensure_perp_position(perp_market_index, settle_token_index)?;
+        triggerer_perp_position.record_perp_conditional_order_premium(premium);
+    }
+
+    //
+    // Health check
+    //
+    if let Some((mut health_cache, pre_init_health, pre_isolated_health)) = pre_health_opt {
+        let perp_position = account.perp_position(perp_market_index)?;
+        health_cache.recompute_perp_info(perp_position, &perp_market)?;
+        account.check_health_post(&health_cache, pre_init_health)?;
+        health_cache.check_isolated_perp_health_post(perp_market_index, pre_isolated_health)?;
+    }
+
+    emit!(PerpConditionalOrderPlaceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: account_pk,
+        triggerer: triggerer_pk,
+        id: perp_conditional_order_id,
+        perp_market_index,
+        oracle_price: oracle_price.to_bits(),
+        trigger_premium: conditional_order.trigger_premium,
+        order_id: order_id_opt,
+    });
+
+    Ok(())
+}
@@ -92,7 +92,8 @@ pub fn perp_create_market(
         init_overall_asset_weight: I80F48::from_num(init_overall_asset_weight),
         positive_pnl_liquidation_fee: I80F48::from_num(positive_pnl_liquidation_fee),
         fees_withdrawn: 0,
-        reserved: [0; 1880],
+        fees_settled_quote: I80F48::ZERO,
+        reserved: [0; 1864],
     };
 
     if let Ok(oracle_price) =
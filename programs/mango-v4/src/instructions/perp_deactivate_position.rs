@@ -34,6 +34,10 @@ pub fn perp_deactivate_position(ctx: Context<PerpDeactivatePosition>) -> Result<
         perp_position.taker_base_lots == 0 && perp_position.taker_quote_lots == 0,
         "perp position still has events on event queue"
     );
+    require_msg!(
+        perp_position.isolated_collateral_indexed == 0,
+        "perp position still has isolated collateral"
+    );
 
     account.deactivate_perp_position_and_log(
         perp_market.perp_market_index,
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::health::{new_fixed_order_account_retriever, new_health_cache};
+use crate::logs::{PerpIsolatedMarginTransferLog, TokenBalanceLog};
+use crate::state::*;
+
+/// Moves settle token deposits into (amount > 0) or out of (amount < 0) the
+/// isolated collateral of a perp position.
+///
+/// A perp position becomes isolated when collateral is first added to it. That is
+/// only possible while the position is empty.
+pub fn perp_isolated_margin_transfer(
+    ctx: Context<PerpIsolatedMarginTransfer>,
+    amount: i64,
+) -> Result<()> {
+    require_msg!(amount != 0, "transfer amount must not be zero");
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        MangoError::SomeError
+    );
+    require_msg!(
+        !account.fixed.is_in_health_region(),
+        "isolated margin can't be changed inside a health region"
+    );
+
+    let perp_market = ctx.accounts.perp_market.load()?;
+    let perp_market_index = perp_market.perp_market_index;
+    let settle_token_index = perp_market.settle_token_index;
+
+    //
+    // Create the perp position if needed and switch it to isolated mode
+    //
+    {
+        let (perp_position, _) =
+            account.ensure_perp_position(perp_market_index, settle_token_index)?;
+        if !perp_position.is_isolated() {
+            require_msg_typed!(
+                amount > 0,
+                MangoError::PerpPositionIsNotIsolated,
+                "collateral can only be withdrawn from isolated positions"
+            );
+            require_msg!(
+                perp_position.can_become_isolated(),
+                "the perp position must be empty to become isolated"
+            );
+            perp_position.isolated = 1;
+        }
+    }
+
+    //
+    // Pre-health computation, _after_ the perp position is created
+    //
+    let retriever = new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+    let mut health_cache =
+        new_health_cache(&account.borrow(), &retriever).context("pre-transfer health")?;
+    let pre_init_health = account.check_health_pre(&health_cache)?;
+    let pre_isolated_health = health_cache.isolated_perp_init_health_opt(perp_market_index)?;
+    drop(retriever);
+
+    let mut bank = ctx.accounts.settle_bank.load_mut()?;
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+
+    // The settle token position was ensured together with the perp position
+    let token_position = account.token_position_mut(settle_token_index)?.0;
+    let native_before = token_position.native(&bank);
+    let amount_i80f48 = I80F48::from(amount);
+    if amount > 0 {
+        // Collateral must come out of existing deposits, it may not be borrowed
+        require_msg!(
+            native_before >= amount_i80f48,
+            "settle token deposits {} are less than the transfer amount {}",
+            native_before,
+            amount
+        );
+        bank.withdraw_without_fee(token_position, amount_i80f48, now_ts)?;
+    } else {
+        bank.deposit(token_position, -amount_i80f48, now_ts)?;
+    }
+    let native_after = token_position.native(&bank);
+
+    emit!(TokenBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        token_index: settle_token_index,
+        indexed_position: token_position.indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    // The collateral remains a deposit in the settle bank, see Bank::deposit_isolated_collateral()
+    let perp_position = account.perp_position_mut(perp_market_index)?;
+    if amount > 0 {
+        bank.deposit_isolated_collateral(perp_position, amount_i80f48)?;
+    } else {
+        bank.withdraw_isolated_collateral(perp_position, -amount_i80f48)?;
+    }
+    let collateral_after = bank.isolated_collateral_native(perp_position);
+
+    emit!(PerpIsolatedMarginTransferLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        perp_market_index,
+        amount,
+        isolated_collateral_indexed: perp_position.isolated_collateral_indexed.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+    });
+
+    //
+    // Health check
+    //
+    health_cache.adjust_token_balance(&bank, native_after - native_before)?;
+    health_cache.recompute_perp_info(account.perp_position(perp_market_index)?, &perp_market)?;
+    health_cache.set_isolated_collateral(perp_market_index, collateral_after)?;
+    account.check_health_post(&health_cache, pre_init_health)?;
+    health_cache.check_isolated_perp_health_post(perp_market_index, pre_isolated_health)?;
+
+    Ok(())
+}
@@ -20,6 +20,8 @@ use crate::logs::{emit_perp_balances, PerpLiqBaseOrPositivePnlLog, TokenBalanceL
 ///
 /// Taking over pnl while health_unsettled_pnl() is negative never increases liqee health.
 /// That's why it's relegated to the separate liq_negative_pnl_or_bankruptcy instruction instead.
+///
+/// The max_pnl_transfer is in settle token native units.
 pub fn perp_liq_base_or_positive_pnl(
     ctx: Context<PerpLiqBaseOrPositivePnl>,
     mut max_base_transfer: i64,
@@ -49,8 +51,12 @@ pub fn perp_liq_base_or_positive_pnl(
 
     // Initial liqee health check
     let mut liqee_health_cache = {
-        let account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, group_pk)
-            .context("create account retriever")?;
+        let account_retriever = ScanningAccountRetriever::new(
+            ctx.remaining_accounts,
+            group_pk,
+            &[&liqor.borrow(), &liqee.borrow()],
+        )
+        .context("create account retriever")?;
         new_health_cache(&liqee.borrow(), &account_retriever)
             .context("create liqee health cache")?
     };
@@ -75,6 +81,11 @@ pub fn perp_liq_base_or_positive_pnl(
 
     // Fetch perp positions for accounts, creating for the liqor if needed
     let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
+    // Isolated positions are liquidated with perp_liq_isolated_position
+    require!(
+        !liqee_perp_position.is_isolated(),
+        MangoError::PerpPositionIsIsolated
+    );
     require!(
         !liqee_perp_position.has_open_taker_fills(),
         MangoError::HasOpenPerpTakerFills
@@ -181,8 +192,12 @@ pub fn perp_liq_base_or_positive_pnl(
 
     // Check liqor's health
     if !liqor.fixed.is_in_health_region() {
-        let account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, group_pk)
-            .context("create account retriever end")?;
+        let account_retriever = ScanningAccountRetriever::new(
+            ctx.remaining_accounts,
+            group_pk,
+            &[&liqor.borrow(), &liqee.borrow()],
+        )
+        .context("create account retriever end")?;
         let liqor_health = compute_health(&liqor.borrow(), HealthType::Init, &account_retriever)
             .context("compute liqor health")?;
         require!(liqor_health >= 0, MangoError::HealthMustBePositive);
@@ -222,6 +237,8 @@ pub(crate) fn liquidation_action(
         .token_info(settle_token_index)?
         .prices
         .oracle;
+    // Like the health cache, this computation works in settle token native units. The perp
+    // position tracks perp quote native units, amounts are converted when applying changes.
     let oracle_price = perp_info.base_prices.oracle;
     let base_lot_size = I80F48::from(perp_market.base_lot_size);
     let oracle_price_per_lot = base_lot_size * oracle_price;
@@ -531,7 +548,10 @@ pub(crate) fn liquidation_action(
     //
     assert!(base_reduction <= liqee_base_lots.abs());
     let base_transfer = direction * base_reduction;
-    let quote_transfer = -I80F48::from(base_transfer) * oracle_price_per_lot * base_fee_factor;
+    let quote_transfer = -I80F48::from(base_transfer)
+        * oracle_price_per_lot
+        * base_fee_factor
+        * settle_token_oracle_price;
     if base_transfer != 0 {
         msg!(
             "transfering: {} base lots and {} quote",
@@ -546,6 +566,7 @@ pub(crate) fn liquidation_action(
     // Let the liqor take over positive pnl until the account health is positive,
     // but only while the health_unsettled_pnl is positive (otherwise it would decrease liqee health!)
     //
+    let pnl_transfer_quote = pnl_transfer * settle_token_oracle_price;
     let limit_transfer = if pnl_transfer > 0 {
         // Allow taking over *more* than the liqee_positive_settle_limit. In exchange, the liqor
         // also can't settle fully immediately and just takes over a fractional chunk of the limit.
@@ -558,22 +579,26 @@ pub(crate) fn liquidation_action(
             // take care, liqee_limit may be i64::MAX
             let liqee_limit: i128 = liqee_positive_settle_limit.into();
             let liqee_pnl = liqee_perp_position
-                .unsettled_pnl(perp_market, oracle_price)?
+                .unsettled_pnl(perp_market, oracle_price * settle_token_oracle_price)?
                 .max(I80F48::ONE);
-            let settle = pnl_transfer.floor().to_num::<i128>();
+            let settle = pnl_transfer_quote.floor().to_num::<i128>();
             let total = liqee_pnl.ceil().to_num::<i128>();
             let liqor_limit: i64 = (liqee_limit * settle / total).try_into().unwrap();
-            I80F48::from(liqor_limit).min(pnl_transfer).max(I80F48::ONE)
+            I80F48::from(liqor_limit)
+                .min(pnl_transfer_quote)
+                .max(I80F48::ONE)
         };
 
         // The liqor pays less than the full amount to receive the positive pnl
         let token_transfer = pnl_transfer * spot_gain_per_settled;
 
-        liqor_perp_position.record_liquidation_pnl_takeover(pnl_transfer, limit_transfer);
-        liqee_perp_position.record_settle(pnl_transfer);
+        liqor_perp_position.record_liquidation_pnl_takeover(pnl_transfer_quote, limit_transfer);
+        liqee_perp_position.record_settle(pnl_transfer_quote);
 
         // Update the accounts' perp_spot_transfer statistics.
-        let transfer_i64 = token_transfer.round_to_zero().to_num::<i64>();
+        let transfer_i64 = (token_transfer * settle_token_oracle_price)
+            .round_to_zero()
+            .to_num::<i64>();
         liqor_perp_position.perp_spot_transfers -= transfer_i64;
         liqee_perp_position.perp_spot_transfers += transfer_i64;
         liqor.fixed.perp_spot_transfers -= transfer_i64;
@@ -601,7 +626,12 @@ pub(crate) fn liquidation_action(
     let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
     liqee_health_cache.recompute_perp_info(liqee_perp_position, &perp_market)?;
 
-    Ok((base_transfer, quote_transfer, pnl_transfer, limit_transfer))
+    Ok((
+        base_transfer,
+        quote_transfer,
+        pnl_transfer_quote,
+        limit_transfer,
+    ))
 }
 
 #[cfg(test)]
@@ -672,8 +702,13 @@ mod tests {
                 setup.perp_market.as_account_info(),
                 setup.perp_oracle.as_account_info(),
             ];
-            let retriever =
-                ScanningAccountRetriever::new_with_staleness(&ais, &setup.group, None).unwrap();
+            let retriever = ScanningAccountRetriever::new_with_staleness(
+                &ais,
+                &setup.group,
+                &[&setup.liqee.borrow()],
+                None,
+            )
+            .unwrap();
 
             health::new_health_cache(&setup.liqee.borrow(), &retriever).unwrap()
         }
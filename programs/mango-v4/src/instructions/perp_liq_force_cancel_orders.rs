@@ -23,9 +23,15 @@ pub fn perp_liq_force_cancel_orders(
     // Early return if if liquidation is not allowed or if market is not in force close
     //
     let liquidatable = account.check_liquidatable(&health_cache)?;
-    let can_force_cancel = !account.fixed.is_operational()
-        || liquidatable == CheckLiquidatable::Liquidatable
-        || perp_market.is_force_close();
+    let perp_market_index = perp_market.perp_market_index;
+    let can_force_cancel = if account.perp_position(perp_market_index)?.is_isolated() {
+        // Isolated positions are only liquidatable based on their own health
+        health_cache.isolated_perp_health(perp_market_index, HealthType::Maint)? < 0
+    } else {
+        liquidatable == CheckLiquidatable::Liquidatable
+    };
+    let can_force_cancel =
+        can_force_cancel || !account.fixed.is_operational() || perp_market.is_force_close();
     if !can_force_cancel {
         return Ok(());
     }
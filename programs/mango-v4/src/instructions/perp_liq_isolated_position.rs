@@ -0,0 +1,389 @@
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use fixed::types::I80F48;
+
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::*;
+use crate::i80f48::ClampToInt;
+use crate::state::*;
+
+use crate::accounts_ix::*;
+use crate::logs::{
+    emit_perp_balances, PerpLiqIsolatedPositionLog, TokenBalanceLog, TokenInsuranceFundUseLog,
+};
+
+use super::perp_liq_negative_pnl_or_bankruptcy::cover_loss_with_insurance;
+
+/// Liquidates an isolated perp position whose isolated maint health is negative.
+///
+/// The liqor takes over up to max_base_transfer of the liqee's base position at oracle
+/// price, adjusted by the market's base_liquidation_fee. Only as much base is transferred
+/// as is needed to bring the isolated liquidation end health back to zero. The rest of
+/// the liqee's account is not involved: only the position's isolated collateral backs
+/// its losses.
+///
+/// Once the base position is fully closed, the liqor takes over the negative pnl:
+/// - in exchange for the isolated collateral,
+/// - then in exchange for tokens from the settle token's insurance fund and the group
+///   insurance fund, like in perp_liq_negative_pnl_or_bankruptcy.
+/// Losses that none of these can cover are socialized among the market's open interest.
+pub fn perp_liq_isolated_position(
+    ctx: Context<PerpLiqIsolatedPosition>,
+    mut max_base_transfer: i64,
+) -> Result<()> {
+    // Ensure max_base_transfer can be negated
+    max_base_transfer = max_base_transfer.max(i64::MIN + 1);
+
+    let mango_group = ctx.accounts.group.key();
+
+    let now_slot = Clock::get()?.slot;
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+
+    let perp_market_index;
+    let settle_token_index;
+    let insurance_token_oracle_price;
+    {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        perp_market_index = perp_market.perp_market_index;
+        settle_token_index = perp_market.settle_token_index;
+
+        let insurance_bank = ctx.accounts.insurance_bank.load()?;
+        // The liqee isn't guaranteed to have an insurance fund token position
+        insurance_token_oracle_price = insurance_bank.oracle_price(
+            &AccountInfoRef::borrow(&ctx.accounts.insurance_oracle)?,
+            Some(now_slot),
+        )?;
+    }
+
+    require_keys_neq!(ctx.accounts.liqor.key(), ctx.accounts.liqee.key());
+    let mut liqor = ctx.accounts.liqor.load_full_mut()?;
+    // account constraint #1
+    require!(
+        liqor
+            .fixed
+            .is_owner_or_delegate(ctx.accounts.liqor_owner.key()),
+        MangoError::SomeError
+    );
+    require_msg_typed!(
+        !liqor.fixed.being_liquidated(),
+        MangoError::BeingLiquidated,
+        "liqor account"
+    );
+
+    let mut liqee = ctx.accounts.liqee.load_full_mut()?;
+
+    // The settle token's insurance fund is an optional prefix of the remaining accounts
+    let (token_insurance_fund_opt, health_ais) =
+        TokenInsuranceFundAccounts::from_remaining_accounts(
+            ctx.remaining_accounts,
+            &mango_group,
+            settle_token_index,
+        )?;
+    TokenInsuranceFundAccounts::verify_for_bank(
+        token_insurance_fund_opt.as_ref(),
+        &ctx.accounts.settle_bank.load()?,
+    )?;
+    let token_insurance_fund_amount = token_insurance_fund_opt
+        .as_ref()
+        .map(|fund| fund.vault.amount)
+        .unwrap_or(0);
+
+    // Initial liqee isolated health check
+    //
+    // Like the health cache, the liquidation limits are computed in settle token native
+    // units. The perp position tracks perp quote native units.
+    let settle_token_oracle_price;
+    let isolated_liq_end_balance;
+    let oracle_price_per_lot;
+    {
+        let account_retriever = ScanningAccountRetriever::new(
+            health_ais,
+            &mango_group,
+            &[&liqor.borrow(), &liqee.borrow()],
+        )
+        .context("create account retriever")?;
+        let liqee_health_cache = new_health_cache(&liqee.borrow(), &account_retriever)
+            .context("create liqee health cache")?;
+        let isolated_maint_health =
+            liqee_health_cache.isolated_perp_health(perp_market_index, HealthType::Maint)?;
+        msg!("liqee isolated maint health: {}", isolated_maint_health);
+        require!(
+            isolated_maint_health < 0,
+            MangoError::IsolatedPerpPositionNotLiquidatable
+        );
+        settle_token_oracle_price = liqee_health_cache
+            .token_info(settle_token_index)?
+            .prices
+            .oracle;
+        let perp_info = liqee_health_cache.perp_info(perp_market_index)?;
+        isolated_liq_end_balance = perp_info.isolated_collateral
+            + perp_info.health_unsettled_pnl(HealthType::LiquidationEnd);
+        oracle_price_per_lot = I80F48::from(perp_info.base_lot_size) * perp_info.base_prices.oracle;
+    }
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+
+    // Get oracle price for market. Price is validated inside
+    let oracle_price = perp_market.oracle_price(
+        &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?,
+        None, // checked in health
+    )?;
+
+    //
+    // Step 1: Take over the liqee's base in exchange for quote at the oracle price, with the
+    // liquidation fee going to the liqor
+    //
+    let base_transfer;
+    let quote_transfer;
+    {
+        // Fetch perp positions for accounts, creating for the liqor if needed
+        let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
+        require!(
+            !liqee_perp_position.has_open_orders_or_fills(),
+            MangoError::HasOpenPerpOrders
+        );
+
+        let liqor_perp_position = liqor
+            .ensure_perp_position(perp_market_index, settle_token_index)?
+            .0;
+
+        liqee_perp_position.settle_funding(&perp_market);
+        liqor_perp_position.settle_funding(&perp_market);
+
+        // Each lot the base position gets closer to 0, the "unweighted health unsettled pnl"
+        // increases by uhupnl_per_lot. See perp_liq_base_or_positive_pnl.
+        let liqee_base_lots = liqee_perp_position.base_position_lots();
+        let (direction, base_fee_factor, uhupnl_per_lot) = if liqee_base_lots > 0 {
+            require_msg!(
+                max_base_transfer >= 0,
+                "max_base_transfer can't be negative when liqee's base_position is positive"
+            );
+            let base_fee_factor = I80F48::ONE - perp_market.base_liquidation_fee;
+            let uhupnl_per_lot =
+                oracle_price_per_lot * (-perp_market.init_base_asset_weight + base_fee_factor);
+            (-1, base_fee_factor, uhupnl_per_lot)
+        } else if liqee_base_lots < 0 {
+            require_msg!(
+                max_base_transfer <= 0,
+                "max_base_transfer can't be positive when liqee's base_position is negative"
+            );
+            let base_fee_factor = I80F48::ONE + perp_market.base_liquidation_fee;
+            let uhupnl_per_lot =
+                oracle_price_per_lot * (perp_market.init_base_liab_weight - base_fee_factor);
+            (1, base_fee_factor, uhupnl_per_lot)
+        } else {
+            (0, I80F48::ONE, I80F48::ONE)
+        };
+        require_gt!(uhupnl_per_lot, 0);
+
+        // While the isolated collateral plus hupnl is negative, hupnl == uhupnl and every lot
+        // of base reduction increases it by uhupnl_per_lot. Stop at liquidation end health 0.
+        let max_base_for_health = if isolated_liq_end_balance < 0 {
+            (-isolated_liq_end_balance / uhupnl_per_lot)
+                .ceil()
+                .clamp_to_i64()
+        } else {
+            0
+        };
+
+        base_transfer = direction
+            * liqee_base_lots
+                .abs()
+                .min(max_base_transfer.abs())
+                .min(max_base_for_health);
+        quote_transfer = -I80F48::from(base_transfer)
+            * I80F48::from(perp_market.base_lot_size)
+            * oracle_price
+            * base_fee_factor;
+        if base_transfer != 0 {
+            msg!(
+                "transfering: {} base lots and {} quote",
+                base_transfer,
+                quote_transfer
+            );
+            liqee_perp_position.record_trade(&mut perp_market, base_transfer, quote_transfer);
+            liqor_perp_position.record_trade(&mut perp_market, -base_transfer, -quote_transfer);
+        }
+    }
+
+    //
+    // Step 2: Once the base position is closed, the liqor takes over the negative pnl.
+    //
+    // It's paid out of the isolated collateral first and then out of the insurance funds.
+    // What remains is socialized.
+    //
+    let mut collateral_transfer = I80F48::ZERO;
+    let mut token_insurance_transfer = 0;
+    let mut insurance_transfer = 0;
+    let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
+    let liqee_pnl = liqee_perp_position.quote_position_native() / settle_token_oracle_price;
+    if liqee_perp_position.base_position_lots() == 0 && liqee_pnl < 0 {
+        let mut settle_bank = ctx.accounts.settle_bank.load_mut()?;
+
+        collateral_transfer =
+            (-liqee_pnl).min(settle_bank.isolated_collateral_native(liqee_perp_position));
+        if collateral_transfer > 0 {
+            settle_bank.withdraw_isolated_collateral(liqee_perp_position, collateral_transfer)?;
+            let liqor_token_position = liqor.token_position_mut(settle_token_index)?.0;
+            settle_bank.deposit(liqor_token_position, collateral_transfer, now_ts)?;
+
+            let collateral_transfer_quote = collateral_transfer * settle_token_oracle_price;
+            let liqor_perp_position = liqor.perp_position_mut(perp_market_index)?;
+            liqee_perp_position.record_settle(-collateral_transfer_quote);
+            liqor_perp_position.record_liquidation_quote_change(-collateral_transfer_quote);
+
+            // Update the accounts' perp_spot_transfer statistics.
+            let transfer_i64 = collateral_transfer_quote.round_to_zero().to_num::<i64>();
+            liqor_perp_position.perp_spot_transfers += transfer_i64;
+            liqee_perp_position.perp_spot_transfers -= transfer_i64;
+            liqor.fixed.perp_spot_transfers += transfer_i64;
+            liqee.fixed.perp_spot_transfers -= transfer_i64;
+
+            msg!(
+                "liquidated pnl for isolated collateral = {}",
+                collateral_transfer
+            );
+        }
+
+        let remaining_loss = -liqee_pnl - collateral_transfer;
+        if remaining_loss > 0 {
+            let mut insurance_bank_opt =
+                if ctx.accounts.settle_bank.key() != ctx.accounts.insurance_bank.key() {
+                    Some(ctx.accounts.insurance_bank.load_mut()?)
+                } else {
+                    None
+                };
+            (token_insurance_transfer, insurance_transfer) = cover_loss_with_insurance(
+                mango_group,
+                &mut perp_market,
+                &mut settle_bank,
+                settle_token_oracle_price,
+                insurance_bank_opt.as_mut().map(|v| v.deref_mut()),
+                insurance_token_oracle_price,
+                &ctx.accounts.insurance_vault,
+                token_insurance_fund_amount,
+                &mut liqor.borrow_mut(),
+                ctx.accounts.liqor.key(),
+                liqee.perp_position_mut(perp_market_index)?,
+                ctx.accounts.liqee.key(),
+                now_ts,
+                remaining_loss,
+                remaining_loss,
+            )?;
+        }
+    }
+
+    // Execute the insurance fund transfers if needed
+    if token_insurance_transfer > 0 {
+        let token_insurance_fund = token_insurance_fund_opt.as_ref().unwrap();
+        let group = ctx.accounts.group.load()?;
+        let group_seeds = group_seeds!(group);
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: token_insurance_fund.vault.to_account_info(),
+                to: ctx.accounts.settle_vault.to_account_info(),
+                authority: ctx.accounts.group.to_account_info(),
+            },
+        );
+        token::transfer(
+            transfer_ctx.with_signer(&[group_seeds]),
+            token_insurance_transfer,
+        )?;
+        token_insurance_fund.record_use(token_insurance_transfer)?;
+
+        emit!(TokenInsuranceFundUseLog {
+            mango_group,
+            liqee: ctx.accounts.liqee.key(),
+            token_index: settle_token_index,
+            perp_market_index: Some(perp_market_index),
+            amount: token_insurance_transfer,
+        });
+    }
+    if insurance_transfer > 0 {
+        let group = ctx.accounts.group.load()?;
+        let group_seeds = group_seeds!(group);
+        token::transfer(
+            ctx.accounts.transfer_ctx().with_signer(&[group_seeds]),
+            insurance_transfer,
+        )?;
+    }
+
+    //
+    // Log positions afterwards
+    //
+    if collateral_transfer > 0 || token_insurance_transfer > 0 {
+        let settle_bank = ctx.accounts.settle_bank.load()?;
+        let liqor_token_position = liqor.token_position(settle_token_index)?;
+        emit!(TokenBalanceLog {
+            mango_group,
+            mango_account: ctx.accounts.liqor.key(),
+            token_index: settle_token_index,
+            indexed_position: liqor_token_position.indexed_position.to_bits(),
+            deposit_index: settle_bank.deposit_index.to_bits(),
+            borrow_index: settle_bank.borrow_index.to_bits(),
+        });
+    }
+
+    if insurance_transfer > 0 {
+        let insurance_bank = ctx.accounts.insurance_bank.load()?;
+        let liqor_token_position = liqor.token_position(insurance_bank.token_index)?;
+        emit!(TokenBalanceLog {
+            mango_group,
+            mango_account: ctx.accounts.liqor.key(),
+            token_index: insurance_bank.token_index,
+            indexed_position: liqor_token_position.indexed_position.to_bits(),
+            deposit_index: insurance_bank.deposit_index.to_bits(),
+            borrow_index: insurance_bank.borrow_index.to_bits(),
+        });
+    }
+
+    emit_perp_balances(
+        mango_group,
+        ctx.accounts.liqor.key(),
+        liqor.perp_position(perp_market_index)?,
+        &perp_market,
+    );
+    emit_perp_balances(
+        mango_group,
+        ctx.accounts.liqee.key(),
+        liqee.perp_position(perp_market_index)?,
+        &perp_market,
+    );
+
+    if base_transfer != 0 || collateral_transfer != 0 {
+        emit!(PerpLiqIsolatedPositionLog {
+            mango_group,
+            perp_market_index,
+            liqor: ctx.accounts.liqor.key(),
+            liqee: ctx.accounts.liqee.key(),
+            base_transfer,
+            quote_transfer: quote_transfer.to_bits(),
+            collateral_transfer: collateral_transfer.to_bits(),
+            price: oracle_price.to_bits(),
+        });
+    }
+
+    drop(perp_market);
+
+    // Check liqor's health, including its own position in this market if it is isolated
+    let account_retriever = ScanningAccountRetriever::new(
+        health_ais,
+        &mango_group,
+        &[&liqor.borrow(), &liqee.borrow()],
+    )
+    .context("create account retriever end")?;
+    let liqor_health_cache = new_health_cache(&liqor.borrow(), &account_retriever)
+        .context("create liqor health cache")?;
+    if !liqor.fixed.is_in_health_region() {
+        let liqor_health = liqor_health_cache.health(HealthType::Init);
+        require!(liqor_health >= 0, MangoError::HealthMustBePositive);
+    }
+    liqor_health_cache.check_isolated_perp_health_post(perp_market_index, None)?;
+
+    Ok(())
+}
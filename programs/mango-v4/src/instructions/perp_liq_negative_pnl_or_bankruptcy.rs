@@ -11,9 +11,11 @@ use crate::error::*;
 use crate::health::*;
 use crate::logs::{
     emit_perp_balances, PerpLiqBankruptcyLog, PerpLiqNegativePnlOrBankruptcyLog, TokenBalanceLog,
+    TokenInsuranceFundUseLog,
 };
 use crate::state::*;
 
+/// The max_liab_transfer is in settle token native units.
 pub fn perp_liq_negative_pnl_or_bankruptcy(
     ctx: Context<PerpLiqNegativePnlOrBankruptcyV2>,
     max_liab_transfer: u64,
@@ -42,6 +44,7 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
             &AccountInfoRef::borrow(&ctx.accounts.settle_oracle)?,
             Some(now_slot),
         )?;
+        require_gt!(settle_token_oracle_price, 0);
         drop(settle_bank); // could be the same as insurance_bank
 
         let insurance_bank = ctx.accounts.insurance_bank.load()?;
@@ -69,8 +72,28 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
         "liqor account"
     );
 
-    let retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, &mango_group)
-        .context("create account retriever")?;
+    // The settle token's insurance fund is an optional prefix of the remaining accounts
+    let (token_insurance_fund_opt, health_ais) =
+        TokenInsuranceFundAccounts::from_remaining_accounts(
+            ctx.remaining_accounts,
+            &mango_group,
+            settle_token_index,
+        )?;
+    TokenInsuranceFundAccounts::verify_for_bank(
+        token_insurance_fund_opt.as_ref(),
+        &ctx.accounts.settle_bank.load()?,
+    )?;
+    let token_insurance_fund_amount = token_insurance_fund_opt
+        .as_ref()
+        .map(|fund| fund.vault.amount)
+        .unwrap_or(0);
+
+    let retriever = ScanningAccountRetriever::new(
+        health_ais,
+        &mango_group,
+        &[&liqor.borrow(), &liqee.borrow()],
+    )
+    .context("create account retriever")?;
     let mut liqee_health_cache = new_health_cache(&liqee.borrow(), &retriever)?;
     drop(retriever);
     let liqee_liq_end_health = liqee_health_cache.health(HealthType::LiquidationEnd);
@@ -84,7 +107,11 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
 
     // check positions exist/create them, done early for nicer error messages
     {
-        liqee.perp_position(perp_market_index)?;
+        // Isolated positions are liquidated with perp_liq_isolated_position
+        require!(
+            !liqee.perp_position(perp_market_index)?.is_isolated(),
+            MangoError::PerpPositionIsIsolated
+        );
         liqee.token_position(settle_token_index)?;
         liqor.ensure_perp_position(perp_market_index, settle_token_index)?;
         liqor.ensure_token_position(settle_token_index)?;
@@ -92,7 +119,7 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
 
     let mut perp_market = ctx.accounts.perp_market.load_mut()?;
 
-    let (settlement, insurance_transfer) = {
+    let (settlement, token_insurance_transfer, insurance_transfer) = {
         let mut settle_bank = ctx.accounts.settle_bank.load_mut()?;
         let mut insurance_bank_opt =
             if ctx.accounts.settle_bank.key() != ctx.accounts.insurance_bank.key() {
@@ -109,6 +136,7 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
             insurance_bank_opt.as_mut().map(|v| v.deref_mut()),
             insurance_token_oracle_price,
             &ctx.accounts.insurance_vault,
+            token_insurance_fund_amount,
             &mut liqor.borrow_mut(),
             ctx.accounts.liqor.key(),
             &mut liqee.borrow_mut(),
@@ -120,7 +148,33 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
         )?
     };
 
-    // Execute the insurance fund transfer if needed
+    // Execute the insurance fund transfers if needed
+    if token_insurance_transfer > 0 {
+        let token_insurance_fund = token_insurance_fund_opt.as_ref().unwrap();
+        let group = ctx.accounts.group.load()?;
+        let group_seeds = group_seeds!(group);
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: token_insurance_fund.vault.to_account_info(),
+                to: ctx.accounts.settle_vault.to_account_info(),
+                authority: ctx.accounts.group.to_account_info(),
+            },
+        );
+        token::transfer(
+            transfer_ctx.with_signer(&[group_seeds]),
+            token_insurance_transfer,
+        )?;
+        token_insurance_fund.record_use(token_insurance_transfer)?;
+
+        emit!(TokenInsuranceFundUseLog {
+            mango_group,
+            liqee: ctx.accounts.liqee.key(),
+            token_index: settle_token_index,
+            perp_market_index: Some(perp_market_index),
+            amount: token_insurance_transfer,
+        });
+    }
     if insurance_transfer > 0 {
         let group = ctx.accounts.group.load()?;
         let group_seeds = group_seeds!(group);
@@ -133,7 +187,7 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
     //
     // Log positions afterwards
     //
-    if settlement > 0 {
+    if settlement > 0 || token_insurance_transfer > 0 {
         let settle_bank = ctx.accounts.settle_bank.load()?;
         let liqor_token_position = liqor.token_position(settle_token_index)?;
         emit!(TokenBalanceLog {
@@ -195,8 +249,11 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
 
     // Check liqor's health
     if !liqor.fixed.is_in_health_region() {
-        let account_retriever =
-            ScanningAccountRetriever::new(ctx.remaining_accounts, &mango_group)?;
+        let account_retriever = ScanningAccountRetriever::new(
+            health_ais,
+            &mango_group,
+            &[&liqor.borrow(), &liqee.borrow()],
+        )?;
         let liqor_health = compute_health(&liqor.borrow(), HealthType::Init, &account_retriever)
             .context("compute liqor health")?;
         require!(liqor_health >= 0, MangoError::HealthMustBePositive);
@@ -214,6 +271,7 @@ pub(crate) fn liquidation_action(
     insurance_bank_opt: Option<&mut Bank>,
     insurance_token_oracle_price: I80F48,
     insurance_vault: &TokenAccount,
+    token_insurance_fund_amount: u64,
     liqor: &mut MangoAccountRefMut,
     liqor_key: Pubkey,
     liqee: &mut MangoAccountRefMut,
@@ -222,7 +280,7 @@ pub(crate) fn liquidation_action(
     liqee_liq_end_health: I80F48,
     now_ts: u64,
     max_liab_transfer: u64,
-) -> Result<(I80F48, u64)> {
+) -> Result<(I80F48, u64, u64)> {
     let perp_market_index = perp_market.perp_market_index;
     let settle_token_index = perp_market.settle_token_index;
     let liqee_max_settle = liqee_health_cache.perp_max_settle(settle_token_index)?;
@@ -235,6 +293,9 @@ pub(crate) fn liquidation_action(
     // The only limitation is the liqee's perp_max_settle and its perp pnl settle limit.
     // This does not change liqee health.
     //
+    // Amounts here are in settle token native units. The perp positions use perp quote
+    // native units and changes to them are converted with the settle token oracle price.
+    //
     let settlement;
     let max_settlement_liqee;
     let mut liqee_pnl;
@@ -244,13 +305,15 @@ pub(crate) fn liquidation_action(
         liqee_perp_position.settle_funding(&perp_market);
         liqor_perp_position.settle_funding(&perp_market);
 
-        liqee_pnl = liqee_perp_position.unsettled_pnl(&perp_market, perp_oracle_price)?;
-        require_gt!(0, liqee_pnl, MangoError::ProfitabilityMismatch);
+        let liqee_pnl_quote = liqee_perp_position.unsettled_pnl(&perp_market, perp_oracle_price)?;
+        require_gt!(0, liqee_pnl_quote, MangoError::ProfitabilityMismatch);
+        liqee_pnl = liqee_pnl_quote / settle_token_oracle_price;
 
         // Get settleable pnl on the liqee
         liqee_perp_position.update_settle_limit(&perp_market, now_ts);
-        let liqee_settleable_pnl =
-            liqee_perp_position.apply_pnl_settle_limit(&perp_market, liqee_pnl);
+        let liqee_settleable_pnl = liqee_perp_position
+            .apply_pnl_settle_limit(&perp_market, liqee_pnl_quote)
+            / settle_token_oracle_price;
 
         max_settlement_liqee = liqee_max_settle
             .min(-liqee_settleable_pnl)
@@ -259,11 +322,12 @@ pub(crate) fn liquidation_action(
             .min(I80F48::from(max_liab_transfer))
             .max(I80F48::ZERO);
         if settlement > 0 {
-            liqor_perp_position.record_liquidation_quote_change(-settlement);
-            liqee_perp_position.record_settle(-settlement);
+            let settlement_quote = settlement * settle_token_oracle_price;
+            liqor_perp_position.record_liquidation_quote_change(-settlement_quote);
+            liqee_perp_position.record_settle(-settlement_quote);
 
             // Update the accounts' perp_spot_transfer statistics.
-            let settlement_i64 = settlement.round_to_zero().to_num::<i64>();
+            let settlement_i64 = settlement_quote.round_to_zero().to_num::<i64>();
             liqor_perp_position.perp_spot_transfers += settlement_i64;
             liqee_perp_position.perp_spot_transfers -= settlement_i64;
             liqor.fixed.perp_spot_transfers += settlement_i64;
@@ -296,11 +360,13 @@ pub(crate) fn liquidation_action(
     // to reduce the negative pnl.
     //
     // Remaining pnl that brings the account into negative init health is either:
-    // - taken by the liqor in exchange for spot from the insurance fund, or
+    // - taken by the liqor in exchange for settle tokens from the settle token's insurance fund,
+    // - taken by the liqor in exchange for spot from the group insurance fund, or
     // - wiped away and socialized among all perp participants (this does not involve the liqor)
     //
-    let insurance_transfer;
-    if settlement == max_settlement_liqee && liqee_pnl < 0 {
+    let (token_insurance_transfer, insurance_transfer) = if settlement == max_settlement_liqee
+        && liqee_pnl < 0
+    {
         // Preparation that's needed for both, insurance fund based pnl takeover and socialized loss
 
         let liqee_settle_token_balance = liqee_health_token_balances
@@ -309,7 +375,8 @@ pub(crate) fn liquidation_action(
         let liqee_perp_position = liqee.perp_position_mut(perp_market_index)?;
 
         // recompute for safety
-        liqee_pnl = liqee_perp_position.unsettled_pnl(&perp_market, perp_oracle_price)?;
+        liqee_pnl = liqee_perp_position.unsettled_pnl(&perp_market, perp_oracle_price)?
+            / settle_token_oracle_price;
 
         // Each unit of pnl increase (towards 0) increases health, but the amount depends on whether
         // the health token position is negative or positive.
@@ -328,92 +395,183 @@ pub(crate) fn liquidation_action(
 
         let max_liab_transfer_from_liqee = (-liqee_pnl).min(max_for_health).max(I80F48::ZERO);
 
-        let max_liab_transfer_to_liqor = max_liab_transfer_from_liqee
-            .min(max_liab_transfer)
-            .max(I80F48::ZERO);
+        cover_loss_with_insurance(
+            group_key,
+            perp_market,
+            settle_bank,
+            settle_token_oracle_price,
+            insurance_bank_opt,
+            insurance_token_oracle_price,
+            insurance_vault,
+            token_insurance_fund_amount,
+            liqor,
+            liqor_key,
+            liqee_perp_position,
+            liqee_key,
+            now_ts,
+            max_liab_transfer_from_liqee,
+            max_liab_transfer,
+        )?
+    } else {
+        (0, 0)
+    };
 
-        // Check if the insurance fund can be used to reimburse the liqor for taking on negative pnl
+    Ok((settlement, token_insurance_transfer, insurance_transfer))
+}
 
-        // Available insurance fund coverage
-        let insurance_vault_amount = if perp_market.elligible_for_group_insurance_fund() {
-            insurance_vault.amount
-        } else {
-            0
-        };
+/// Lets the liqor take over up to `max_liab_transfer` of the liqee's negative perp pnl in
+/// exchange for tokens from the settle token's insurance fund and then the group insurance
+/// fund. If both funds are exhausted, the rest of `max_liab_transfer_from_liqee` is
+/// socialized among the market's open interest.
+///
+/// Amounts are in settle token native units. Returns the token insurance fund and group
+/// insurance fund transfers, which the caller must execute.
+pub(crate) fn cover_loss_with_insurance(
+    group_key: Pubkey,
+    perp_market: &mut PerpMarket,
+    settle_bank: &mut Bank,
+    settle_token_oracle_price: I80F48,
+    insurance_bank_opt: Option<&mut Bank>,
+    insurance_token_oracle_price: I80F48,
+    insurance_vault: &TokenAccount,
+    token_insurance_fund_amount: u64,
+    liqor: &mut MangoAccountRefMut,
+    liqor_key: Pubkey,
+    liqee_perp_position: &mut PerpPosition,
+    liqee_key: Pubkey,
+    now_ts: u64,
+    max_liab_transfer_from_liqee: I80F48,
+    max_liab_transfer: I80F48,
+) -> Result<(u64, u64)> {
+    let perp_market_index = perp_market.perp_market_index;
+    let settle_token_index = perp_market.settle_token_index;
 
-        let liquidation_fee_factor = I80F48::ONE + perp_market.base_liquidation_fee;
-        let settle_token_price_with_fee = settle_token_oracle_price * liquidation_fee_factor;
-
-        // Amount given to the liqor from the insurance fund
-        insurance_transfer = (max_liab_transfer_to_liqor * settle_token_price_with_fee
-            / insurance_token_oracle_price)
-            .ceil()
-            .to_num::<u64>()
-            .min(insurance_vault_amount);
-
-        let insurance_transfer_i80f48 = I80F48::from(insurance_transfer);
-        let insurance_fund_exhausted = insurance_transfer == insurance_vault_amount;
-
-        // Amount of negative perp pnl transfered to the liqor
-        let insurance_liab_transfer = (insurance_transfer_i80f48 * insurance_token_oracle_price
-            / settle_token_price_with_fee)
-            .min(max_liab_transfer_to_liqor);
-
-        // Try using the insurance fund if possible
-        if insurance_transfer > 0 {
-            let insurance_bank = insurance_bank_opt.unwrap_or(settle_bank);
-            require_keys_eq!(insurance_bank.mint, insurance_vault.mint);
-
-            // moving insurance assets into the insurance bank vault happens outside
-            // of this function to ensure this is unittestable!
-
-            // credit the liqor with quote tokens
-            let (liqor_quote, _, _) = liqor.ensure_token_position(insurance_bank.token_index)?;
-            insurance_bank.deposit(liqor_quote, insurance_transfer_i80f48, now_ts)?;
-
-            // transfer perp quote loss from the liqee to the liqor
-            let liqor_perp_position = liqor.perp_position_mut(perp_market_index)?;
-            liqee_perp_position.record_settle(-insurance_liab_transfer);
-            liqor_perp_position.record_liquidation_quote_change(-insurance_liab_transfer);
-
-            msg!(
-                "bankruptcy: {} pnl for {} insurance",
-                insurance_liab_transfer,
-                insurance_transfer
-            );
-        }
+    let max_liab_transfer_to_liqor = max_liab_transfer_from_liqee
+        .min(max_liab_transfer)
+        .max(I80F48::ZERO);
 
-        // Socialize loss if the insurance fund is exhausted
-
-        // At this point, we don't care about the liqor's requested max_liab_tranfer
-        let remaining_liab = max_liab_transfer_from_liqee - insurance_liab_transfer;
-        let mut socialized_loss = I80F48::ZERO;
-        let (starting_long_funding, starting_short_funding) =
-            (perp_market.long_funding, perp_market.short_funding);
-        if insurance_fund_exhausted && remaining_liab > 0 {
-            perp_market.socialize_loss(-remaining_liab)?;
-            liqee_perp_position.record_settle(-remaining_liab);
-            socialized_loss = remaining_liab;
-            msg!("socialized loss: {}", socialized_loss);
-        }
+    let liquidation_fee_factor = I80F48::ONE + perp_market.base_liquidation_fee;
+    let settle_token_price_with_fee = settle_token_oracle_price * liquidation_fee_factor;
 
-        emit!(PerpLiqBankruptcyLog {
-            mango_group: group_key,
-            liqee: liqee_key,
-            liqor: liqor_key,
-            perp_market_index: perp_market.perp_market_index,
-            insurance_transfer: insurance_transfer_i80f48.to_bits(),
-            socialized_loss: socialized_loss.to_bits(),
-            starting_long_funding: starting_long_funding.to_bits(),
-            starting_short_funding: starting_short_funding.to_bits(),
-            ending_long_funding: perp_market.long_funding.to_bits(),
-            ending_short_funding: perp_market.short_funding.to_bits(),
-        });
+    // The settle token's insurance fund is used first. It reimburses the liqor in
+    // settle tokens, so no price conversion is needed.
+
+    // Amount given to the liqor from the token insurance fund
+    let token_insurance_transfer = (max_liab_transfer_to_liqor * liquidation_fee_factor)
+        .ceil()
+        .to_num::<u64>()
+        .min(token_insurance_fund_amount);
+
+    let token_insurance_transfer_i80f48 = I80F48::from(token_insurance_transfer);
+    let token_insurance_fund_exhausted = token_insurance_transfer == token_insurance_fund_amount;
+
+    // Amount of negative perp pnl transfered to the liqor
+    let token_insurance_liab_transfer =
+        (token_insurance_transfer_i80f48 / liquidation_fee_factor).min(max_liab_transfer_to_liqor);
+
+    if token_insurance_transfer > 0 {
+        // moving tokens from the token insurance fund into the settle bank vault happens
+        // outside of this function to ensure this is unittestable!
+
+        // credit the liqor with settle tokens
+        let (liqor_settle, _, _) = liqor.ensure_token_position(settle_token_index)?;
+        settle_bank.deposit(liqor_settle, token_insurance_transfer_i80f48, now_ts)?;
+
+        // transfer perp quote loss from the liqee to the liqor
+        let token_insurance_liab_transfer_quote =
+            token_insurance_liab_transfer * settle_token_oracle_price;
+        let liqor_perp_position = liqor.perp_position_mut(perp_market_index)?;
+        liqee_perp_position.record_settle(-token_insurance_liab_transfer_quote);
+        liqor_perp_position.record_liquidation_quote_change(-token_insurance_liab_transfer_quote);
+
+        msg!(
+            "bankruptcy: {} pnl for {} token insurance",
+            token_insurance_liab_transfer,
+            token_insurance_transfer
+        );
+    }
+
+    let max_liab_transfer_to_liqor = max_liab_transfer_to_liqor - token_insurance_liab_transfer;
+
+    // Check if the insurance fund can be used to reimburse the liqor for taking on negative pnl
+
+    // Available insurance fund coverage
+    let insurance_vault_amount = if perp_market.elligible_for_group_insurance_fund() {
+        insurance_vault.amount
     } else {
-        insurance_transfer = 0;
+        0
     };
 
-    Ok((settlement, insurance_transfer))
+    // Amount given to the liqor from the insurance fund
+    let insurance_transfer = (max_liab_transfer_to_liqor * settle_token_price_with_fee
+        / insurance_token_oracle_price)
+        .ceil()
+        .to_num::<u64>()
+        .min(insurance_vault_amount);
+
+    let insurance_transfer_i80f48 = I80F48::from(insurance_transfer);
+    let insurance_fund_exhausted = insurance_transfer == insurance_vault_amount;
+
+    // Amount of negative perp pnl transfered to the liqor
+    let insurance_liab_transfer = (insurance_transfer_i80f48 * insurance_token_oracle_price
+        / settle_token_price_with_fee)
+        .min(max_liab_transfer_to_liqor);
+
+    // Try using the insurance fund if possible
+    if insurance_transfer > 0 {
+        let insurance_bank = insurance_bank_opt.unwrap_or(settle_bank);
+        require_keys_eq!(insurance_bank.mint, insurance_vault.mint);
+
+        // moving insurance assets into the insurance bank vault happens outside
+        // of this function to ensure this is unittestable!
+
+        // credit the liqor with quote tokens
+        let (liqor_quote, _, _) = liqor.ensure_token_position(insurance_bank.token_index)?;
+        insurance_bank.deposit(liqor_quote, insurance_transfer_i80f48, now_ts)?;
+
+        // transfer perp quote loss from the liqee to the liqor
+        let insurance_liab_transfer_quote = insurance_liab_transfer * settle_token_oracle_price;
+        let liqor_perp_position = liqor.perp_position_mut(perp_market_index)?;
+        liqee_perp_position.record_settle(-insurance_liab_transfer_quote);
+        liqor_perp_position.record_liquidation_quote_change(-insurance_liab_transfer_quote);
+
+        msg!(
+            "bankruptcy: {} pnl for {} insurance",
+            insurance_liab_transfer,
+            insurance_transfer
+        );
+    }
+
+    // Socialize loss if the insurance funds are exhausted
+
+    // At this point, we don't care about the liqor's requested max_liab_tranfer
+    let remaining_liab =
+        max_liab_transfer_from_liqee - token_insurance_liab_transfer - insurance_liab_transfer;
+    let mut socialized_loss = I80F48::ZERO;
+    let (starting_long_funding, starting_short_funding) =
+        (perp_market.long_funding, perp_market.short_funding);
+    if token_insurance_fund_exhausted && insurance_fund_exhausted && remaining_liab > 0 {
+        let remaining_liab_quote = remaining_liab * settle_token_oracle_price;
+        perp_market.socialize_loss(-remaining_liab_quote)?;
+        liqee_perp_position.record_settle(-remaining_liab_quote);
+        socialized_loss = remaining_liab_quote;
+        msg!("socialized loss: {}", socialized_loss);
+    }
+
+    emit!(PerpLiqBankruptcyLog {
+        mango_group: group_key,
+        liqee: liqee_key,
+        liqor: liqor_key,
+        perp_market_index: perp_market.perp_market_index,
+        insurance_transfer: insurance_transfer_i80f48.to_bits(),
+        socialized_loss: socialized_loss.to_bits(),
+        starting_long_funding: starting_long_funding.to_bits(),
+        starting_short_funding: starting_short_funding.to_bits(),
+        ending_long_funding: perp_market.long_funding.to_bits(),
+        ending_short_funding: perp_market.short_funding.to_bits(),
+    });
+
+    Ok((token_insurance_transfer, insurance_transfer))
 }
 
 #[cfg(test)]
@@ -435,6 +593,7 @@ mod tests {
         liqee: MangoAccountValue,
         liqor: MangoAccountValue,
         insurance_vault: spl_token::state::Account,
+        token_insurance_amount: u64,
     }
 
     impl TestSetup {
@@ -486,6 +645,7 @@ mod tests {
                 liqee,
                 liqor,
                 insurance_vault,
+                token_insurance_amount: 0,
             }
         }
 
@@ -505,8 +665,13 @@ mod tests {
                     setup.perp_market.as_account_info(),
                     setup.perp_oracle.as_account_info(),
                 ];
-                let retriever =
-                    ScanningAccountRetriever::new_with_staleness(&ais, &setup.group, None).unwrap();
+                let retriever = ScanningAccountRetriever::new_with_staleness(
+                    &ais,
+                    &setup.group,
+                    &[&setup.liqee.borrow()],
+                    None,
+                )
+                .unwrap();
 
                 liqee_health_cache =
                     health::new_health_cache(&setup.liqee.borrow(), &retriever).unwrap();
@@ -549,6 +714,7 @@ mod tests {
                 Some(setup.insurance_bank.data()),
                 insurance_price,
                 &insurance_vault,
+                setup.token_insurance_amount,
                 &mut setup.liqor.borrow_mut(),
                 Pubkey::new_unique(),
                 &mut setup.liqee.borrow_mut(),
@@ -603,8 +769,8 @@ mod tests {
                 // perp_settle_health = 40 * 2.0 * 0.9 - 36 = 36
                 // max settle = 36 / (0.9 * 2.0) = 20
                 (40.0, -50.0, -36.0, 6, 100),
-                (true, 30.0, -40.0),
-                (10.0, -10.0, 0.0, 0.0),
+                (true, 30.0, -30.0), // 10 settle tokens are 20 perp quote
+                (10.0, -20.0, 0.0, 0.0),
                 10,
             ),
             (
@@ -613,9 +779,9 @@ mod tests {
                 // perp_settle_health = 40 * 2.0 * 0.9 - 36 = 36
                 // max settle = 36 / (0.9 * 2.0) = 20
                 (40.0, -50.0, -36.0, 100, 100),
-                (true, 20.0, -21.0),
-                (20.0, -29.0, 6.0, 0.0),
-                29, // limited by max_liab_transfer
+                (true, 20.0, -6.0),
+                (20.0, -44.0, 2.0, 0.0),
+                22, // limited by max_liab_transfer
             ),
             (
                 "settle 3 (+insurance)",
@@ -623,66 +789,67 @@ mod tests {
                 // perp_settle_health = 40 * 2.0 * 0.9 - 36 = 36
                 // max settle = 36 / (0.9 * 2.0) = 20
                 (40.0, -50.0, -36.0, 100, 10), // limited by settleable pnl
-                (true, 30.0, -31.0),
-                (10.0, -19.0, 6.0, 0.0),
+                (true, 35.0, -30.0),
+                (5.0, -20.0, 4.0, 0.0),
                 19,
             ),
             (
                 "settle 4 (+socialized loss)",
                 (0.9, 2.0, 3.0),
                 // perp_settle_health = 5 * 2.0 * 0.9 + 30 = 39
-                // max settle = 5 + (39 - 9) / (2*1.1) = 18.63
-                (5.0, -20.0, 30.0, 0, 100),
-                (true, -13.63, 0.0),
-                (18.63, -18.63, 0.0, 1.36), // socialized loss
+                // max settle = 5 + (39 - 9) / (2*1.1) = 18.64
+                // the -40 perp quote are -20 settle tokens, 1.36 of them are socialized
+                (5.0, -40.0, 30.0, 0, 100),
+                (true, -13.64, 0.0),
+                (18.64, -37.27, 0.0, 2.73), // socialized loss
                 100,
             ),
             (
                 "bankruptcy, no insurance 1",
                 (0.9, 2.0, 1.0),
                 (0.0, -5.0, 2.2, 0, 0),
-                (true, 0.0, -1.0), // -1 * 2.0 * 1.1 = 2.2
-                (0.0, 0.0, 0.0, 4.0),
+                (true, 0.0, -2.0), // -1 * 2.0 * 1.1 = 2.2
+                (0.0, 0.0, 0.0, 3.0),
                 0,
             ),
             (
                 "bankruptcy, no insurance 2",
                 (0.9, 2.0, 1.0),
-                (4.0, -5.0, -3.6, 0, 0), // health token balance goes from -1 to +2
-                (true, 4.0, -2.0),
-                (0.0, 0.0, 0.0, 3.0),
+                (4.0, -5.0, -3.6, 0, 0), // health token balance goes from 1.5 to 2
+                (true, 4.0, -4.0),
+                (0.0, 0.0, 0.0, 1.0),
                 0,
             ),
             (
                 "bankruptcy, no insurance 3",
                 (0.9, 2.0, 1.0),
                 (4.0, -5.0, -3.6, 0, 0),
-                (true, 4.0, -2.0),
-                (0.0, 0.0, 0.0, 3.0),
+                (true, 4.0, -4.0),
+                (0.0, 0.0, 0.0, 1.0),
                 100, // liqor being willing to take over changes nothing
             ),
             (
                 "bankruptcy, with insurance 1",
                 (0.9, 2.0, 3.0),
                 (40.0, -50.0, -36.0, 6, 0),
-                (true, 40.0, -20.0),
-                (0.0, -9.0, 6.0, 21.0), // 6 * 3.0 / 2.0 = 9 taken over, rest socialized loss
+                (true, 40.0, -40.0),
+                (0.0, -10.0, 4.0, 0.0), // 5 settle tokens taken over for ceil(5 * 2.0 / 3.0) = 4 insurance
                 100,
             ),
             (
                 "bankruptcy, with insurance 2",
                 (0.9, 2.0, 3.0),
                 (40.0, -50.0, -36.0, 6, 0),
-                (true, 40.0, -47.0),
-                (0.0, -3.0, 2.0, 0.0),
+                (true, 40.0, -44.0),
+                (0.0, -6.0, 2.0, 0.0),
                 3, // liqor is limited, don't socialize loss since insurance not exhausted!
             ),
             (
                 "bankruptcy, with insurance 3",
                 (0.9, 2.0, 3.0),
                 (40.0, -50.0, -36.0, 1000, 0), // insurance fund is big enough to cover fully
-                (true, 40.0, -20.0),
-                (0.0, -30.0, 20.0, 0.0),
+                (true, 40.0, -40.0),
+                (0.0, -10.0, 4.0, 0.0),
                 100,
             ),
             (
@@ -692,17 +859,17 @@ mod tests {
                 // max settle = 36 / (0.9 * 2.0) = 20
                 (40.0, -50.0, -36.0, 6, 100),
                 (true, 20.0, 0.0),
-                (20.0, -29.0, 6.0, 21.0),
+                (20.0, -50.0, 4.0, 0.0),
                 40,
             ),
             (
                 "everything 2",
                 (0.9, 2.0, 3.0),
-                // perp_settle_health = 10 * 2.0 * 0.9 + 9 = 19
+                // perp_settle_health = 10 * 2.0 * 0.9 + 9 = 27
                 // max settle = 10 + 9 / (1.1 * 2.0) = 14.1
                 (10.0, -50.0, 9.0, 6, 100),
                 (true, -4.1, 0.0), // perp position always goes to 0 because we use the same weights for init and maint
-                (14.1, -14.1 - 9.0, 6.0, 50.0 - 14.1 - 9.0),
+                (14.1, -(14.1 + 9.0) * 2.0, 6.0, 50.0 - (14.1 + 9.0) * 2.0),
                 40,
             ),
         ];
@@ -806,4 +973,120 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_liq_bankruptcy_token_insurance_fund() {
+        let test_cases = vec![
+            (
+                "token insurance covers fully",
+                (-100.0, 6, 100),
+                (0.0, 25.0, -50.0, 0.0, 0.0),
+                100,
+            ),
+            (
+                "token insurance, then group insurance, then socialized loss",
+                (-100.0, 2, 2),
+                (0.0, 2.0, -10.0, 2.0, 40.0), // 2 + 2 * 3.0 / 2.0 = 5 settle tokens taken over
+                100,
+            ),
+            (
+                "token insurance exhausted, no group insurance",
+                (-36.0, 0, 1),
+                (-40.0, 1.0, -2.0, 0.0, 8.0),
+                100,
+            ),
+            (
+                "liqor is limited, token insurance not exhausted",
+                (-36.0, 6, 100),
+                (-44.0, 3.0, -6.0, 0.0, 0.0),
+                3,
+            ),
+        ];
+
+        for (
+            name,
+            (init_other, insurance_amount, token_insurance_amount),
+            (
+                exp_liqee_perp,
+                exp_liqor_settle_token,
+                exp_liqor_perp,
+                exp_liqor_insurance,
+                exp_socialized_loss,
+            ),
+            max_liab_transfer,
+        ) in test_cases
+        {
+            println!("test: {name}");
+            let mut setup = TestSetup::new();
+            {
+                let t = setup.settle_bank.data();
+                t.init_asset_weight = I80F48::from_num(0.9);
+                t.init_liab_weight = I80F48::from_num(1.1);
+                t.maint_asset_weight = I80F48::from_num(0.9);
+                t.maint_liab_weight = I80F48::from_num(1.1);
+                t.stable_price_model.stable_price = 2.0;
+                setup.settle_oracle.data().price = I80F48::from_num(2.0);
+
+                let t = setup.insurance_bank.data();
+                t.stable_price_model.stable_price = 3.0;
+                setup.insurance_oracle.data().price = I80F48::from_num(3.0);
+
+                let p = setup.perp_market.data();
+                p.init_overall_asset_weight = I80F48::from_num(0.0);
+                p.open_interest = 1;
+
+                setup.insurance_vault.amount = insurance_amount;
+                setup.token_insurance_amount = token_insurance_amount;
+            }
+            {
+                let p = perp_p(&mut setup.liqee);
+                p.quote_position_native = I80F48::from_num(-50.0);
+
+                let settle_bank = setup.settle_bank.data();
+                settle_bank
+                    .change_without_fee(settle_p(&mut setup.liqee), I80F48::from_num(40.0), 0)
+                    .unwrap();
+
+                let other_bank = setup.other_bank.data();
+                other_bank
+                    .change_without_fee(other_p(&mut setup.liqee), I80F48::from_num(init_other), 0)
+                    .unwrap();
+            }
+
+            let mut result = setup.run(max_liab_transfer).unwrap();
+
+            // the liqee's tokens are never touched
+            let settle_bank = result.settle_bank.data();
+            assert_eq_f!(settle_p(&mut result.liqee).native(settle_bank), 40.0, 0.01);
+            assert_eq_f!(
+                settle_p(&mut result.liqor).native(settle_bank),
+                exp_liqor_settle_token,
+                0.01
+            );
+
+            let insurance_bank = result.insurance_bank.data();
+            assert_eq_f!(
+                insurance_p(&mut result.liqor).native(insurance_bank),
+                exp_liqor_insurance,
+                0.01
+            );
+
+            assert_eq_f!(
+                perp_p(&mut result.liqee).quote_position_native,
+                exp_liqee_perp,
+                0.1
+            );
+            assert_eq_f!(
+                perp_p(&mut result.liqor).quote_position_native,
+                exp_liqor_perp,
+                0.1
+            );
+
+            assert_eq_f!(
+                result.perp_market.data().long_funding,
+                exp_socialized_loss,
+                0.1
+            );
+        }
+    }
 }
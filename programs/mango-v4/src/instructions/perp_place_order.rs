@@ -64,13 +64,22 @@ pub fn perp_place_order(
     //
     // Pre-health computation, _after_ perp position is created
     //
+    // Health regions only check the cross-margin health when they end
+    let is_isolated = account.perp_position(perp_market_index)?.is_isolated();
+    require_msg_typed!(
+        !is_isolated || !account.fixed.is_in_health_region(),
+        MangoError::PerpPositionIsIsolated,
+        "isolated perp positions can't be traded inside a health region"
+    );
+
     let pre_health_opt = if !account.fixed.is_in_health_region() {
         let retriever =
             new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
         let health_cache =
             new_health_cache(&account.borrow(), &retriever).context("pre-withdraw init health")?;
         let pre_init_health = account.check_health_pre(&health_cache)?;
-        Some((health_cache, pre_init_health))
+        let pre_isolated_health = health_cache.isolated_perp_init_health_opt(perp_market_index)?;
+        Some((health_cache, pre_init_health, pre_isolated_health))
     } else {
         None
     };
@@ -118,16 +127,21 @@ pub fn perp_place_order(
     //
     // Health check
     //
-    if let Some((mut health_cache, pre_init_health)) = pre_health_opt {
+    if let Some((mut health_cache, pre_init_health, pre_isolated_health)) = pre_health_opt {
         let perp_position = account.perp_position(perp_market_index)?;
         health_cache.recompute_perp_info(perp_position, &perp_market)?;
         account.check_health_post(&health_cache, pre_init_health)?;
+        health_cache.check_isolated_perp_health_post(perp_market_index, pre_isolated_health)?;
     }
 
     Ok(order_id_opt)
 }
 
-fn reduce_only_max_base_lots(pp: &PerpPosition, order: &Order, market_reduce_only: bool) -> i64 {
+pub(crate) fn reduce_only_max_base_lots(
+    pp: &PerpPosition,
+    order: &Order,
+    market_reduce_only: bool,
+) -> i64 {
     let effective_pos = pp.effective_base_position_lots();
     msg!(
         "reduce only: current effective position: {} lots",
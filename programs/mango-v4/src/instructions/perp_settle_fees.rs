@@ -10,6 +10,10 @@ use crate::state::*;
 use crate::accounts_ix::*;
 use crate::logs::{emit_perp_balances, PerpSettleFeesLog, TokenBalanceLog};
 
+/// Settles the account's negative perp pnl against the market's accrued fees.
+///
+/// max_settle_amount is in perp quote native units, like fees_accrued. The settle token
+/// amount taken from the account is converted with the settle token oracle price.
 pub fn perp_settle_fees(ctx: Context<PerpSettleFees>, max_settle_amount: u64) -> Result<()> {
     // max_settle_amount must greater than zero
     require!(
@@ -37,6 +41,7 @@ pub fn perp_settle_fees(ctx: Context<PerpSettleFees>, max_settle_amount: u64) ->
         &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?,
         None, // staleness checked in health
     )?;
+    require_gt!(settle_token_oracle_price, 0);
 
     // Fetch perp positions for accounts
     let perp_position = account.perp_position_mut(perp_market.perp_market_index)?;
@@ -59,15 +64,27 @@ pub fn perp_settle_fees(ctx: Context<PerpSettleFees>, max_settle_amount: u64) ->
         return Ok(());
     }
 
+    // Isolated positions pay out of their isolated collateral and can't borrow
+    let isolated_collateral = if perp_position.is_isolated() {
+        Some(settle_bank.isolated_collateral_native(perp_position))
+    } else {
+        None
+    };
+    let max_settle_isolated = isolated_collateral
+        .map(|collateral| collateral * settle_token_oracle_price)
+        .unwrap_or(I80F48::MAX);
+
     // Settle for the maximum possible capped to max_settle_amount
     let settlement = settleable_pnl
         .abs()
         .min(perp_market.fees_accrued.abs())
-        .min(I80F48::from(max_settle_amount));
+        .min(I80F48::from(max_settle_amount))
+        .min(max_settle_isolated);
     require!(settlement >= 0, MangoError::SettlementAmountMustBePositive);
 
     perp_position.record_settle(-settlement); // settle the negative pnl on the user perp position
     perp_market.fees_accrued -= settlement;
+    perp_market.fees_settled_quote += settlement;
 
     emit_perp_balances(
         ctx.accounts.group.key(),
@@ -88,26 +105,30 @@ pub fn perp_settle_fees(ctx: Context<PerpSettleFees>, max_settle_amount: u64) ->
     perp_position.perp_spot_transfers -= settlement_i64;
     account.fixed.perp_spot_transfers -= settlement_i64;
 
-    // Transfer token balances
-    let token_position = account
-        .token_position_mut(perp_market.settle_token_index)?
-        .0;
-    settle_bank.withdraw_without_fee(
-        token_position,
-        settlement,
-        Clock::get()?.unix_timestamp.try_into().unwrap(),
-    )?;
+    // Transfer token balances, the settlement is in perp quote native units
+    let token_settlement = settlement / settle_token_oracle_price;
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    if let Some(collateral) = isolated_collateral {
+        let perp_position = account.perp_position_mut(perp_market.perp_market_index)?;
+        settle_bank
+            .withdraw_isolated_collateral(perp_position, token_settlement.min(collateral))?;
+    } else {
+        let token_position = account
+            .token_position_mut(perp_market.settle_token_index)?
+            .0;
+        settle_bank.withdraw_without_fee(token_position, token_settlement, now_ts)?;
+
+        emit!(TokenBalanceLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: ctx.accounts.account.key(),
+            token_index: perp_market.settle_token_index,
+            indexed_position: token_position.indexed_position.to_bits(),
+            deposit_index: settle_bank.deposit_index.to_bits(),
+            borrow_index: settle_bank.borrow_index.to_bits(),
+        });
+    }
     // Update the settled balance on the market itself
-    perp_market.fees_settled += settlement;
-
-    emit!(TokenBalanceLog {
-        mango_group: ctx.accounts.group.key(),
-        mango_account: ctx.accounts.account.key(),
-        token_index: perp_market.settle_token_index,
-        indexed_position: token_position.indexed_position.to_bits(),
-        deposit_index: settle_bank.deposit_index.to_bits(),
-        borrow_index: settle_bank.borrow_index.to_bits(),
-    });
+    perp_market.fees_settled += token_settlement;
 
     emit!(PerpSettleFeesLog {
         mango_group: ctx.accounts.group.key(),
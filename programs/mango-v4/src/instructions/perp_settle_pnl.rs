@@ -40,11 +40,19 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
     let a_maint_health;
     let b_max_settle;
     {
-        let retriever =
-            ScanningAccountRetriever::new(ctx.remaining_accounts, &ctx.accounts.group.key())
-                .context("create account retriever")?;
-        b_max_settle = new_health_cache(&account_b.borrow(), &retriever)?
-            .perp_max_settle(settle_token_index)?;
+        let retriever = ScanningAccountRetriever::new(
+            ctx.remaining_accounts,
+            &ctx.accounts.group.key(),
+            &[&account_a.borrow(), &account_b.borrow()],
+        )
+        .context("create account retriever")?;
+        // Isolated positions can only settle losses out of their isolated collateral
+        let b_cache = new_health_cache(&account_b.borrow(), &retriever)?;
+        b_max_settle = if account_b.perp_position(perp_market_index)?.is_isolated() {
+            b_cache.perp_info(perp_market_index)?.isolated_collateral
+        } else {
+            b_cache.perp_max_settle(settle_token_index)?
+        };
         let a_cache = new_health_cache(&account_a.borrow(), &retriever)?;
         a_liq_end_health = a_cache.health(HealthType::LiquidationEnd);
         a_maint_health = a_cache.health(HealthType::Maint);
@@ -68,6 +76,7 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
         &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?,
         None, // staleness checked in health
     )?;
+    require_gt!(settle_token_oracle_price, 0);
 
     // Fetch perp position and pnl
     let a_perp_position = account_a.perp_position_mut(perp_market_index)?;
@@ -126,9 +135,10 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
         b_max_settle
     );
 
-    // Settle for the maximum possible capped to target's settle health
-    let settlement = a_settleable_pnl
-        .min(-b_settleable_pnl)
+    // Settle for the maximum possible capped to target's settle health.
+    // The pnl is in perp quote native units, but settlement happens in the settle token.
+    let settlement = (a_settleable_pnl / settle_token_oracle_price)
+        .min(-b_settleable_pnl / settle_token_oracle_price)
         .min(b_max_settle)
         .max(I80F48::ZERO);
     require_msg_typed!(
@@ -142,8 +152,9 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
 
     let fee = perp_market.compute_settle_fee(settlement, a_liq_end_health, a_maint_health)?;
 
-    a_perp_position.record_settle(settlement);
-    b_perp_position.record_settle(-settlement);
+    let settlement_quote = settlement * settle_token_oracle_price;
+    a_perp_position.record_settle(settlement_quote);
+    b_perp_position.record_settle(-settlement_quote);
     emit_perp_balances(
         ctx.accounts.group.key(),
         ctx.accounts.account_a.key(),
@@ -162,8 +173,10 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
     // Applying the fee here means that it decreases the displayed perp pnl.
     // Think about it like this: a's pnl reduces by `settlement` and spot increases by `settlement - fee`.
     // That means that it managed to extract `settlement - fee` from perp interactions.
-    let settlement_i64 = settlement.round_to_zero().to_num::<i64>();
-    let fee_i64 = fee.round_to_zero().to_num::<i64>();
+    let settlement_i64 = settlement_quote.round_to_zero().to_num::<i64>();
+    let fee_i64 = (fee * settle_token_oracle_price)
+        .round_to_zero()
+        .to_num::<i64>();
     (a_perp_position.perp_spot_transfers += settlement_i64 - fee_i64);
     (b_perp_position.perp_spot_transfers -= settlement_i64);
     (account_a.fixed.perp_spot_transfers += settlement_i64 - fee_i64);
@@ -171,13 +184,25 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
 
     // Transfer token balances
     // The fee is paid by the account with positive unsettled pnl
-    let a_token_position = account_a.token_position_mut(settle_token_index)?.0;
-    let b_token_position = account_b.token_position_mut(settle_token_index)?.0;
-    settle_bank.deposit(a_token_position, settlement - fee, now_ts)?;
-    // Don't charge loan origination fees on borrows created via settling:
-    // Even small loan origination fees could accumulate if a perp position is
-    // settled back and forth repeatedly.
-    settle_bank.withdraw_without_fee(b_token_position, settlement, now_ts)?;
+    //
+    // Isolated positions settle into and out of their isolated collateral instead.
+    if a_perp_position.is_isolated() {
+        settle_bank.deposit_isolated_collateral(a_perp_position, settlement - fee)?;
+    } else {
+        let a_token_position = account_a.token_position_mut(settle_token_index)?.0;
+        settle_bank.deposit(a_token_position, settlement - fee, now_ts)?;
+    }
+    if b_perp_position.is_isolated() {
+        settle_bank.withdraw_isolated_collateral(b_perp_position, settlement)?;
+    } else {
+        let b_token_position = account_b.token_position_mut(settle_token_index)?.0;
+        // Don't charge loan origination fees on borrows created via settling:
+        // Even small loan origination fees could accumulate if a perp position is
+        // settled back and forth repeatedly.
+        settle_bank.withdraw_without_fee(b_token_position, settlement, now_ts)?;
+    }
+    let a_token_position = account_a.token_position(settle_token_index)?;
+    let b_token_position = account_b.token_position(settle_token_index)?;
 
     emit!(TokenBalanceLog {
         mango_group: ctx.accounts.group.key(),
@@ -329,7 +329,7 @@ pub struct OODifference {
 }
 
 impl OODifference {
-    pub fn new(before_oo: &OpenOrdersSlim, after_oo: &OpenOrdersSlim) -> Self {
+    pub fn new(before_oo: &impl OpenOrdersAmounts, after_oo: &impl OpenOrdersAmounts) -> Self {
         Self {
             reserved_base_change: I80F48::from(after_oo.native_base_reserved())
                 - I80F48::from(before_oo.native_base_reserved()),
@@ -357,6 +357,22 @@ impl OODifference {
             self.free_quote_change,
         )
     }
+
+    pub fn adjust_health_cache_openbook_v2_state(
+        &self,
+        health_cache: &mut HealthCache,
+        market: &OpenbookV2Market,
+    ) -> Result<()> {
+        health_cache.adjust_openbook_v2_reserved(
+            market.market_index,
+            market.base_token_index,
+            self.reserved_base_change,
+            self.free_base_change,
+            market.quote_token_index,
+            self.reserved_quote_change,
+            self.free_quote_change,
+        )
+    }
 }
 
 pub struct VaultDifference {
@@ -365,6 +381,13 @@ pub struct VaultDifference {
 }
 
 impl VaultDifference {
+    pub fn new(token_index: TokenIndex, native_change: I80F48) -> Self {
+        Self {
+            token_index,
+            native_change,
+        }
+    }
+
     pub fn adjust_health_cache_token_balance(
         &self,
         health_cache: &mut HealthCache,
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::accounts_ix::*;
-use crate::logs::TokenConditionalSwapCreateLogV2;
+use crate::logs::TokenConditionalSwapCreateLogV3;
 use crate::state::*;
 
 #[allow(clippy::too_many_arguments)]
@@ -48,6 +48,9 @@ pub fn token_conditional_swap_create(
     tcs.has_data = 1;
     tcs.bought = 0;
     tcs.sold = 0;
+    tcs.interval_start_timestamp = 0;
+    tcs.interval_sold = 0;
+    tcs.premium_auction_start_timestamp = 0;
 
     require_neq!(tcs.buy_token_index, tcs.sell_token_index);
     require_gte!(tcs.price_premium_rate, 0.0);
@@ -55,8 +58,11 @@ pub fn token_conditional_swap_create(
     require_gte!(tcs.taker_fee_rate, 0.0);
     require_gte!(tcs.price_lower_limit, 0.0);
     require_gte!(tcs.price_upper_limit, 0.0);
+    if tcs.is_twap() {
+        require_gt!(tcs.interval_max_sell, 0);
+    }
 
-    emit!(TokenConditionalSwapCreateLogV2 {
+    emit!(TokenConditionalSwapCreateLogV3 {
         mango_group: ctx.accounts.group.key(),
         mango_account: ctx.accounts.account.key(),
         id,
@@ -74,6 +80,9 @@ pub fn token_conditional_swap_create(
         allow_creating_deposits: tcs.allow_creating_deposits(),
         display_price_style: tcs.display_price_style,
         intention: tcs.intention,
+        interval_seconds: tcs.interval_seconds,
+        interval_max_sell: tcs.interval_max_sell,
+        premium_auction_duration_seconds: tcs.premium_auction_duration_seconds,
     });
 
     Ok(())
@@ -5,11 +5,11 @@ use crate::accounts_ix::*;
 use crate::error::*;
 use crate::health::*;
 use crate::i80f48::ClampToInt;
-use crate::logs::TokenConditionalSwapCancelLog;
 use crate::logs::{
     LoanOriginationFeeInstruction, TokenBalanceLog, TokenConditionalSwapTriggerLogV2,
     WithdrawLoanLog,
 };
+use crate::logs::{TokenConditionalSwapCancelLog, TokenConditionalSwapStartLog};
 use crate::state::*;
 
 /// If init health is reduced below this number, the tcs is considered done.
@@ -38,11 +38,15 @@ pub fn token_conditional_swap_trigger(
         "liqor account"
     );
 
-    let mut account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, group_pk)
-        .context("create account retriever")?;
-
     let mut liqee = ctx.accounts.liqee.load_full_mut()?;
 
+    let mut account_retriever = ScanningAccountRetriever::new(
+        ctx.remaining_accounts,
+        group_pk,
+        &[&liqor.borrow(), &liqee.borrow()],
+    )
+    .context("create account retriever")?;
+
     let tcs = liqee.token_conditional_swap_by_index(token_conditional_swap_index)?;
     require!(tcs.has_data(), MangoError::SomeError);
     require_eq!(tcs.id, token_conditional_swap_id);
@@ -196,7 +200,7 @@ fn action(
 ) -> Result<(I80F48, I80F48)> {
     let liqee_pre_init_health = liqee.check_health_pre(&liqee_health_cache)?;
 
-    let tcs = liqee
+    let mut tcs = liqee
         .token_conditional_swap_by_index(token_conditional_swap_index)?
         .clone();
     require!(tcs.has_data(), MangoError::SomeError);
@@ -206,12 +210,38 @@ fn action(
 
     // amount of sell token native per buy token native
     let price = buy_token_price.to_num::<f64>() / sell_token_price.to_num::<f64>();
+
+    // Linear premium auctions start on the first trigger with the price in range
+    // and reset when triggered with the price out of range.
+    if tcs.update_premium_auction(price, now_ts) {
+        liqee
+            .token_conditional_swap_mut_by_index(token_conditional_swap_index)?
+            .premium_auction_start_timestamp = tcs.premium_auction_start_timestamp;
+        emit!(TokenConditionalSwapStartLog {
+            mango_group: liqee.fixed.group,
+            mango_account: liqee_key,
+            token_conditional_swap_id: tcs.id,
+            start_timestamp: tcs.premium_auction_start_timestamp,
+        });
+        if !tcs.premium_auction_started() {
+            msg!("price is out of range, reset the premium auction");
+            return Ok((I80F48::ZERO, I80F48::ZERO));
+        }
+    }
+
     require!(
         tcs.price_in_range(price),
         MangoError::TokenConditionalSwapPriceNotInRange
     );
 
-    let premium_price = tcs.premium_price(price);
+    // Time-weighted tcs can only sell what's left in the current interval
+    let available_sell_in_interval = tcs.available_sell_in_interval(now_ts);
+    require!(
+        available_sell_in_interval > 0,
+        MangoError::TokenConditionalSwapIntervalNotReady
+    );
+
+    let premium_price = tcs.premium_price(price, now_ts);
     let maker_price = tcs.maker_price(premium_price);
     let maker_price_i80f48 = I80F48::from_num(maker_price);
 
@@ -235,7 +265,7 @@ fn action(
         &tcs,
         maker_price_i80f48,
         max_buy_token_to_liqee,
-        max_sell_token_to_liqor,
+        max_sell_token_to_liqor.min(available_sell_in_interval),
         pre_liqee_buy_token,
         pre_liqee_sell_token,
         pre_liqor_buy_token,
@@ -390,6 +420,7 @@ fn action(
         let tcs = liqee.token_conditional_swap_mut_by_index(token_conditional_swap_index)?;
         tcs.bought += buy_token_amount;
         tcs.sold += sell_token_amount_from_liqee;
+        tcs.record_interval_sell(now_ts, sell_token_amount_from_liqee);
         assert!(tcs.bought <= tcs.max_buy);
         assert!(tcs.sold <= tcs.max_sell);
 
@@ -679,7 +710,7 @@ mod tests {
             liqee_buffer.extend_from_slice(&[0u8; 256]);
             let mut liqee = MangoAccountValue::from_bytes(&liqee_buffer).unwrap();
             {
-                liqee.expand_dynamic_content(3, 5, 4, 6, 1).unwrap();
+                liqee.expand_dynamic_content(3, 5, 4, 6, 1, 0, 0).unwrap();
                 liqee.ensure_token_position(0).unwrap();
                 liqee.ensure_token_position(1).unwrap();
             }
@@ -733,6 +764,17 @@ mod tests {
             buy_max: u64,
             sell_price: f64,
             sell_max: u64,
+        ) -> Result<(I80F48, I80F48)> {
+            self.trigger_at(buy_price, buy_max, sell_price, sell_max, 0)
+        }
+
+        fn trigger_at(
+            &mut self,
+            buy_price: f64,
+            buy_max: u64,
+            sell_price: f64,
+            sell_max: u64,
+            now_ts: u64,
         ) -> Result<(I80F48, I80F48)> {
             let mut setup = self.clone();
 
@@ -742,8 +784,13 @@ mod tests {
                 setup.asset_oracle.as_account_info(),
                 setup.liab_oracle.as_account_info(),
             ];
-            let retriever =
-                ScanningAccountRetriever::new_with_staleness(&ais, &setup.group, None).unwrap();
+            let retriever = ScanningAccountRetriever::new_with_staleness(
+                &ais,
+                &setup.group,
+                &[&setup.liqee.borrow()],
+                None,
+            )
+            .unwrap();
             let mut liqee_health_cache =
                 crate::health::new_health_cache(&setup.liqee.borrow(), &retriever).unwrap();
 
@@ -760,7 +807,7 @@ mod tests {
                 self.asset_bank.data(),
                 I80F48::from_num(sell_price),
                 sell_max,
-                0,
+                now_ts,
             )
         }
     }
@@ -921,4 +968,147 @@ mod tests {
 
         assert_eq!(setup.asset_bank.data().collected_fees_native, 77);
     }
+
+    #[test]
+    fn test_token_conditional_swap_trigger_twap() {
+        let mut setup = TestSetup::new();
+
+        let asset_pos = 100_000_000;
+        setup
+            .asset_bank
+            .data()
+            .deposit(
+                &mut setup.liqee.token_position_mut(0).unwrap().0,
+                I80F48::from(asset_pos),
+                0,
+            )
+            .unwrap();
+
+        let tcs = TokenConditionalSwap {
+            max_buy: 1000,
+            max_sell: 1000,
+            price_lower_limit: 1.0,
+            price_upper_limit: 3.0,
+            buy_token_index: 1,
+            sell_token_index: 0,
+            has_data: 1,
+            allow_creating_borrows: 1,
+            allow_creating_deposits: 1,
+            interval_seconds: 100,
+            interval_max_sell: 300,
+            ..Default::default()
+        };
+        *setup.liqee.free_token_conditional_swap_mut().unwrap() = tcs.clone();
+
+        // The first slice can be taken in pieces
+        let (buy_change, sell_change) = setup.trigger_at(1.0, 1000, 1.0, 200, 1000).unwrap();
+        assert_eq!(buy_change.round(), 200);
+        assert_eq!(sell_change.round(), -200);
+
+        let (buy_change, sell_change) = setup.trigger_at(1.0, 1000, 1.0, 1000, 1050).unwrap();
+        assert_eq!(buy_change.round(), 100);
+        assert_eq!(sell_change.round(), -100);
+
+        let tcs = setup
+            .liqee
+            .token_conditional_swap_by_index(0)
+            .unwrap()
+            .clone();
+        assert_eq!(tcs.interval_start_timestamp, 1000);
+        assert_eq!(tcs.interval_sold, 300);
+        assert_eq!(tcs.sold, 300);
+
+        // No more execution until the next slice unlocks
+        assert!(setup.trigger_at(1.0, 1000, 1.0, 1000, 1099).is_err());
+
+        let (buy_change, sell_change) = setup.trigger_at(1.0, 1000, 1.0, 1000, 1100).unwrap();
+        assert_eq!(buy_change.round(), 300);
+        assert_eq!(sell_change.round(), -300);
+        assert_eq!(setup.liqee.active_token_conditional_swaps().count(), 1);
+        let tcs = setup
+            .liqee
+            .token_conditional_swap_by_index(0)
+            .unwrap()
+            .clone();
+        assert_eq!(tcs.interval_start_timestamp, 1100);
+        assert_eq!(tcs.interval_sold, 300);
+
+        // The last slice is limited by the remaining amount and closes the tcs
+        setup.trigger_at(1.0, 1000, 1.0, 1000, 1200).unwrap();
+        setup.trigger_at(1.0, 1000, 1.0, 1000, 1300).unwrap();
+        assert_eq!(setup.liqee.active_token_conditional_swaps().count(), 0);
+        assert_eq!(setup.liqee_liab_pos().round(), 1000);
+        assert_eq!(setup.liqee_asset_pos().round(), asset_pos - 1000);
+    }
+
+    #[test]
+    fn test_token_conditional_swap_trigger_premium_auction() {
+        let mut setup = TestSetup::new();
+
+        let asset_pos = 100_000_000;
+        setup
+            .asset_bank
+            .data()
+            .deposit(
+                &mut setup.liqee.token_position_mut(0).unwrap().0,
+                I80F48::from(asset_pos),
+                0,
+            )
+            .unwrap();
+
+        let tcs = TokenConditionalSwap {
+            max_buy: 1000,
+            max_sell: 1000,
+            price_lower_limit: 1.0,
+            price_upper_limit: 3.0,
+            price_premium_rate: 0.1,
+            buy_token_index: 1,
+            sell_token_index: 0,
+            has_data: 1,
+            allow_creating_borrows: 1,
+            allow_creating_deposits: 1,
+            premium_auction_duration_seconds: 100,
+            ..Default::default()
+        };
+        *setup.liqee.free_token_conditional_swap_mut().unwrap() = tcs.clone();
+
+        // Out of range triggers don't start the auction
+        assert!(setup.trigger_at(5.0, 25, 1.0, 1000, 900).is_err());
+
+        // The first trigger in range starts the auction, at zero premium
+        let (buy_change, sell_change) = setup.trigger_at(1.0, 25, 1.0, 1000, 1000).unwrap();
+        assert_eq!(buy_change.round(), 25);
+        assert_eq!(sell_change.round(), -25);
+        assert_eq!(
+            setup
+                .liqee
+                .token_conditional_swap_by_index(0)
+                .unwrap()
+                .premium_auction_start_timestamp,
+            1000
+        );
+
+        // Halfway through the auction the premium is half the max
+        let (buy_change, sell_change) = setup.trigger_at(1.0, 25, 1.0, 1000, 1050).unwrap();
+        assert_eq!(buy_change.round(), 25);
+        assert_eq!(sell_change.round(), -26); // floor(25 * 1.05)
+
+        // After the auction duration the max premium applies
+        let (buy_change, sell_change) = setup.trigger_at(1.0, 25, 1.0, 1000, 2000).unwrap();
+        assert_eq!(buy_change.round(), 25);
+        assert_eq!(sell_change.round(), -27); // floor(25 * 1.1)
+
+        // Triggering with the price out of range resets the auction without trading
+        let (buy_change, sell_change) = setup.trigger_at(5.0, 25, 1.0, 1000, 2100).unwrap();
+        assert_eq!(buy_change, 0);
+        assert_eq!(sell_change, 0);
+        let tcs = setup.liqee.token_conditional_swap_by_index(0).unwrap();
+        assert!(!tcs.premium_auction_started());
+        assert_eq!(tcs.bought, 75);
+
+        // Back in range, the auction restarts from zero premium
+        let (buy_change, sell_change) = setup.trigger_at(1.0, 25, 1.0, 1000, 3000).unwrap();
+        assert_eq!(buy_change.round(), 25);
+        assert_eq!(sell_change.round(), -25);
+    }
 }
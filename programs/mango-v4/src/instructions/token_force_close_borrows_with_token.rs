@@ -18,9 +18,6 @@ pub fn token_force_close_borrows_with_token(
 
     require_neq!(asset_token_index, liab_token_index, MangoError::SomeError);
 
-    let mut account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, group_pk)
-        .context("create account retriever")?;
-
     require_keys_neq!(ctx.accounts.liqor.key(), ctx.accounts.liqee.key());
     let mut liqor = ctx.accounts.liqor.load_full_mut()?;
     // account constraint #1
@@ -38,6 +35,13 @@ pub fn token_force_close_borrows_with_token(
 
     let mut liqee = ctx.accounts.liqee.load_full_mut()?;
 
+    let mut account_retriever = ScanningAccountRetriever::new(
+        ctx.remaining_accounts,
+        group_pk,
+        &[&liqor.borrow(), &liqee.borrow()],
+    )
+    .context("create account retriever")?;
+
     //
     // Transfer liab_token from liqor to liqee to close the borrows.
     // Transfer corresponding amount of asset_token from liqee to liqor.
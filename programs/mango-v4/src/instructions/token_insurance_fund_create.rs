@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn token_insurance_fund_create(
+    ctx: Context<TokenInsuranceFundCreate>,
+    token_index: TokenIndex,
+) -> Result<()> {
+    let mut fund = ctx.accounts.insurance_fund.load_init()?;
+    fund.group = ctx.accounts.group.key();
+    fund.token_index = token_index;
+    fund.bump = *ctx
+        .bumps
+        .get("insurance_fund")
+        .ok_or(MangoError::SomeError)?;
+    fund.vault_bump = *ctx.bumps.get("vault").ok_or(MangoError::SomeError)?;
+    fund.mint = ctx.accounts.mint.key();
+    fund.vault = ctx.accounts.vault.key();
+    fund.total_used_native = 0;
+
+    // Bankruptcy instructions check the banks to know that the fund must be used
+    let mint_info = ctx.accounts.mint_info.load()?;
+    mint_info.verify_banks_ais(ctx.remaining_accounts)?;
+    for ai in ctx.remaining_accounts.iter() {
+        let mut bank = ai.load_mut::<Bank>()?;
+        bank.token_insurance_fund = ctx.accounts.insurance_fund.key();
+    }
+
+    msg!(
+        "created insurance fund {} for token {}",
+        ctx.accounts.insurance_fund.key(),
+        token_index
+    );
+
+    Ok(())
+}
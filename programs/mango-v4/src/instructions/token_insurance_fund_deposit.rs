@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::accounts_ix::*;
+
+pub fn token_insurance_fund_deposit(
+    ctx: Context<TokenInsuranceFundDeposit>,
+    amount: u64,
+) -> Result<()> {
+    token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::accounts_ix::*;
+use crate::group_seeds;
+
+pub fn token_insurance_fund_withdraw(
+    ctx: Context<TokenInsuranceFundWithdraw>,
+    amount: u64,
+) -> Result<()> {
+    let group = ctx.accounts.group.load()?;
+
+    let group_seeds = group_seeds!(group);
+    token::transfer(
+        ctx.accounts.transfer_ctx().with_signer(&[group_seeds]),
+        amount.min(ctx.accounts.vault.amount),
+    )?;
+
+    Ok(())
+}
@@ -9,7 +9,8 @@ use crate::state::*;
 
 use crate::accounts_ix::*;
 use crate::logs::{
-    LoanOriginationFeeInstruction, TokenBalanceLog, TokenLiqBankruptcyLog, WithdrawLoanLog,
+    LoanOriginationFeeInstruction, TokenBalanceLog, TokenInsuranceFundUseLog,
+    TokenLiqBankruptcyLog, WithdrawLoanLog,
 };
 
 pub fn token_liq_bankruptcy(
@@ -19,10 +20,25 @@ pub fn token_liq_bankruptcy(
     let group = ctx.accounts.group.load()?;
     let group_pk = &ctx.accounts.group.key();
 
-    // split remaining accounts into banks and health
+    // split remaining accounts into the optional token insurance fund, banks and health
     let liab_mint_info = ctx.accounts.liab_mint_info.load()?;
     let liab_token_index = liab_mint_info.token_index;
-    let (bank_ais, health_ais) = &ctx.remaining_accounts.split_at(liab_mint_info.num_banks());
+    let (token_insurance_fund_opt, remaining_ais) =
+        TokenInsuranceFundAccounts::from_remaining_accounts(
+            ctx.remaining_accounts,
+            group_pk,
+            liab_token_index,
+        )?;
+    let (liab_vault_opt, remaining_ais) = if token_insurance_fund_opt.is_some() {
+        require_msg!(
+            !remaining_ais.is_empty(),
+            "token insurance fund vault must be followed by the liab bank vault"
+        );
+        (Some(&remaining_ais[0]), &remaining_ais[1..])
+    } else {
+        (None, remaining_ais)
+    };
+    let (bank_ais, health_ais) = &remaining_ais.split_at(liab_mint_info.num_banks());
     liab_mint_info.verify_banks_ais(bank_ais)?;
 
     require_keys_neq!(ctx.accounts.liqor.key(), ctx.accounts.liqee.key());
@@ -41,9 +57,10 @@ pub fn token_liq_bankruptcy(
         "liqor account"
     );
 
-    let mut account_retriever = ScanningAccountRetriever::new(health_ais, group_pk)?;
-
     let mut liqee = ctx.accounts.liqee.load_full_mut()?;
+    let mut account_retriever =
+        ScanningAccountRetriever::new(health_ais, group_pk, &[&liqor.borrow(), &liqee.borrow()])?;
+
     let mut liqee_health_cache = new_health_cache(&liqee.borrow(), &account_retriever)
         .context("create liqee health cache")?;
     liqee_health_cache.require_after_phase2_liquidation()?;
@@ -53,6 +70,7 @@ pub fn token_liq_bankruptcy(
     let (liab_bank, liab_oracle_price, opt_quote_bank_and_price) =
         account_retriever.banks_mut_and_oracles(liab_token_index, INSURANCE_TOKEN_INDEX)?;
     assert!(liab_is_insurance_token == opt_quote_bank_and_price.is_none());
+    TokenInsuranceFundAccounts::verify_for_bank(token_insurance_fund_opt.as_ref(), liab_bank)?;
 
     let mut liab_deposit_index = liab_bank.deposit_index;
     let liab_borrow_index = liab_bank.borrow_index;
@@ -74,6 +92,57 @@ pub fn token_liq_bankruptcy(
     // guaranteed positive
     let mut remaining_liab_loss = (-initial_liab_native).min(-liqee_liab_health_balance);
 
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let mut liqee_liab_active = true;
+
+    // The liab token's own insurance fund is used first. It pays in the liab token, directly
+    // into the liqee's position, without a fee or the liqor being involved.
+    if let (Some(token_insurance_fund), Some(liab_vault_ai)) =
+        (token_insurance_fund_opt.as_ref(), liab_vault_opt)
+    {
+        // account constraint #3
+        require_keys_eq!(liab_bank.vault, liab_vault_ai.key());
+
+        let token_insurance_transfer = remaining_liab_loss
+            .ceil()
+            .to_num::<u64>()
+            .min(token_insurance_fund.vault.amount);
+        if token_insurance_transfer > 0 {
+            let before_liab_native = liqee_liab.native(liab_bank);
+            liqee_liab_active = liab_bank.deposit_with_dusting(
+                liqee_liab,
+                I80F48::from(token_insurance_transfer),
+                now_ts,
+            )?;
+            remaining_liab_loss = (remaining_liab_loss
+                - (liqee_liab.native(liab_bank) - before_liab_native))
+                .max(I80F48::ZERO);
+
+            let group_seeds = group_seeds!(group);
+            let transfer_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: token_insurance_fund.vault.to_account_info(),
+                    to: liab_vault_ai.clone(),
+                    authority: ctx.accounts.group.to_account_info(),
+                },
+            );
+            token::transfer(
+                transfer_ctx.with_signer(&[group_seeds]),
+                token_insurance_transfer,
+            )?;
+            token_insurance_fund.record_use(token_insurance_transfer)?;
+
+            emit!(TokenInsuranceFundUseLog {
+                mango_group: ctx.accounts.group.key(),
+                liqee: ctx.accounts.liqee.key(),
+                token_index: liab_token_index,
+                perp_market_index: None,
+                amount: token_insurance_transfer,
+            });
+        }
+    }
+
     // We pay for the liab token in quote. Example: SOL is at $20 and USDC is at $2, then for a liab
     // of 3 SOL, we'd pay 3 * 20 / 2 * (1+fee) = 30 * (1+fee) USDC.
     let liab_to_quote_with_fee =
@@ -105,15 +174,13 @@ pub fn token_liq_bankruptcy(
     // liquidators to exploit the insurance fund for 1 native token each call.
     let liab_transfer = insurance_transfer_i80f48 / liab_to_quote_with_fee;
 
-    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
-
-    let mut liqee_liab_active = true;
     if insurance_transfer > 0 {
         // liqee gets liab assets (enable dusting to prevent a case where the position is brought
         // to +I80F48::DELTA)
+        let before_liab_native = liqee_liab.native(liab_bank);
         liqee_liab_active = liab_bank.deposit_with_dusting(liqee_liab, liab_transfer, now_ts)?;
         // update correctly even if dusting happened
-        remaining_liab_loss -= liqee_liab.native(liab_bank) - initial_liab_native;
+        remaining_liab_loss -= liqee_liab.native(liab_bank) - before_liab_native;
 
         // move insurance assets into quote bank
         let group_seeds = group_seeds!(group);
@@ -210,7 +277,8 @@ pub fn token_liq_bankruptcy(
     drop(account_retriever);
 
     // Socialize loss if there's more loss and noone else could use the
-    // insurance fund to cover it.
+    // insurance funds to cover it. The token insurance fund is exhausted if there's
+    // remaining loss at this point.
     let mut socialized_loss = I80F48::ZERO;
     let starting_deposit_index = liab_deposit_index;
     if insurance_fund_exhausted && remaining_liab_loss.is_positive() {
@@ -18,9 +18,6 @@ pub fn token_liq_with_token(
     let group_pk = &ctx.accounts.group.key();
 
     require!(asset_token_index != liab_token_index, MangoError::SomeError);
-    let mut account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, group_pk)
-        .context("create account retriever")?;
-
     require_keys_neq!(ctx.accounts.liqor.key(), ctx.accounts.liqee.key());
     let mut liqor = ctx.accounts.liqor.load_full_mut()?;
     // account constraint #1
@@ -38,6 +35,13 @@ pub fn token_liq_with_token(
 
     let mut liqee = ctx.accounts.liqee.load_full_mut()?;
 
+    let mut account_retriever = ScanningAccountRetriever::new(
+        ctx.remaining_accounts,
+        group_pk,
+        &[&liqor.borrow(), &liqee.borrow()],
+    )
+    .context("create account retriever")?;
+
     // Initial liqee health check
     let mut liqee_health_cache = new_health_cache(&liqee.borrow(), &account_retriever)
         .context("create liqee health cache")?;
@@ -443,8 +447,13 @@ mod tests {
                 setup.perp_oracle_asset.as_account_info(),
                 setup.perp_oracle_liab.as_account_info(),
             ];
-            let retriever =
-                ScanningAccountRetriever::new_with_staleness(&ais, &setup.group, None).unwrap();
+            let retriever = ScanningAccountRetriever::new_with_staleness(
+                &ais,
+                &setup.group,
+                &[&setup.liqee.borrow()],
+                None,
+            )
+            .unwrap();
 
             health::new_health_cache(&setup.liqee.borrow(), &retriever).unwrap()
         }
@@ -464,8 +473,13 @@ mod tests {
                 setup.perp_oracle_asset.as_account_info(),
                 setup.perp_oracle_liab.as_account_info(),
             ];
-            let mut retriever =
-                ScanningAccountRetriever::new_with_staleness(&ais, &setup.group, None).unwrap();
+            let mut retriever = ScanningAccountRetriever::new_with_staleness(
+                &ais,
+                &setup.group,
+                &[&setup.liqee.borrow()],
+                None,
+            )
+            .unwrap();
 
             let mut liqee_health_cache =
                 health::new_health_cache(&setup.liqee.borrow(), &retriever).unwrap();
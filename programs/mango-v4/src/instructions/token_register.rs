@@ -108,7 +108,11 @@ pub fn token_register(
         token_conditional_swap_taker_fee_rate,
         token_conditional_swap_maker_fee_rate,
         flash_loan_swap_fee_rate,
-        reserved: [0; 2092],
+        token_insurance_fund: Pubkey::default(),
+        padding2: Default::default(),
+        other_banks_indexed_deposits: I80F48::ZERO,
+        other_banks_indexed_borrows: I80F48::ZERO,
+        reserved: [0; 2024],
     };
 
     if let Ok(oracle_price) =
@@ -80,7 +80,11 @@ pub fn token_register_trustless(
         token_conditional_swap_taker_fee_rate: 0.0005,
         token_conditional_swap_maker_fee_rate: 0.0005,
         flash_loan_swap_fee_rate: 0.0005,
-        reserved: [0; 2092],
+        token_insurance_fund: Pubkey::default(),
+        padding2: Default::default(),
+        other_banks_indexed_deposits: I80F48::ZERO,
+        other_banks_indexed_borrows: I80F48::ZERO,
+        reserved: [0; 2024],
     };
     require_gt!(bank.max_rate, MINIMUM_MAX_RATE);
 
@@ -135,6 +135,11 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
             bank.avg_utilization = new_avg_utilization;
 
             bank.stable_price_model = stable_price_model;
+
+            // Lets each bank see the token-wide deposits and borrows, needed for
+            // the deposit and borrow weight scaling in health computations
+            bank.other_banks_indexed_deposits = indexed_total_deposits - bank.indexed_deposits;
+            bank.other_banks_indexed_borrows = indexed_total_borrows - bank.indexed_borrows;
         }
     }
 
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::AccountInfoRef;
+use crate::error::*;
+use crate::state::*;
+use fixed::types::I80F48;
+
+pub fn twap_oracle_create(
+    ctx: Context<TwapOracleCreate>,
+    window_secs: u64,
+    max_sample_deviation: f32,
+) -> Result<()> {
+    require_gt!(window_secs, 0);
+    require_msg!(
+        max_sample_deviation > 0.0 && max_sample_deviation.is_finite(),
+        "max_sample_deviation must be positive"
+    );
+
+    let mint = ctx.accounts.mint.key();
+    let pool = RaydiumClmmPool::from_account(&AccountInfoRef::borrow(ctx.accounts.pool.as_ref())?)?;
+    let invert = if pool.token_mint_0 == mint {
+        0
+    } else if pool.token_mint_1 == mint {
+        1
+    } else {
+        return Err(error_msg!(
+            "pool {} does not contain mint {}",
+            ctx.accounts.pool.key(),
+            mint
+        ));
+    };
+
+    let clock = Clock::get()?;
+    let mut oracle = ctx.accounts.oracle.load_init()?;
+    oracle.group = ctx.accounts.group.key();
+    oracle.mint = mint;
+    oracle.pool = ctx.accounts.pool.key();
+    oracle.window_secs = window_secs;
+    oracle.max_sample_deviation = I80F48::from_num(max_sample_deviation);
+    oracle.invert = invert;
+
+    // Start out with the current pool price as the average
+    let price = oracle.sample_price_from_pool(pool.price)?;
+    oracle.twap_price = price;
+    oracle.last_sample_price = price;
+    oracle.last_update_ts = clock.unix_timestamp;
+    oracle.last_update_slot = clock.slot;
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::AccountInfoRef;
+use crate::state::*;
+
+pub fn twap_oracle_update(ctx: Context<TwapOracleUpdate>) -> Result<()> {
+    let pool = RaydiumClmmPool::from_account(&AccountInfoRef::borrow(ctx.accounts.pool.as_ref())?)?;
+
+    let clock = Clock::get()?;
+    let mut oracle = ctx.accounts.oracle.load_mut()?;
+    let price = oracle.sample_price_from_pool(pool.price)?;
+    oracle.update(price, clock.unix_timestamp, clock.slot);
+
+    Ok(())
+}
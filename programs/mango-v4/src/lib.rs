@@ -20,6 +20,7 @@ pub mod events;
 pub mod health;
 pub mod i80f48;
 pub mod logs;
+pub mod openbook_v2_cpi;
 pub mod serum3_cpi;
 pub mod state;
 pub mod types;
@@ -31,9 +32,10 @@ pub mod instructions;
 compile_error!("compiling the program entrypoint without 'enable-gpl' makes no sense, enable it or use the 'cpi' or 'client' features");
 
 use state::{
-    OpenbookV2MarketIndex, OracleConfigParams, PerpMarketIndex, PlaceOrderType, SelfTradeBehavior,
-    Serum3MarketIndex, Side, TokenConditionalSwap, TokenConditionalSwapDisplayPriceStyle,
-    TokenConditionalSwapIntention, TokenIndex,
+    OpenbookV2MarketIndex, OracleConfigParams, PerpConditionalOrder, PerpConditionalOrderExecution,
+    PerpMarketIndex, PlaceOrderType, SelfTradeBehavior, Serum3MarketIndex, Side,
+    TokenConditionalSwap, TokenConditionalSwapDisplayPriceStyle, TokenConditionalSwapIntention,
+    TokenIndex,
 };
 
 declare_id!("4MangoMjqJ2firMokCjjGgoK8d4MXcrgL7XJaL3w6fVg");
@@ -108,6 +110,33 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    pub fn token_insurance_fund_create(
+        ctx: Context<TokenInsuranceFundCreate>,
+        token_index: TokenIndex,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_insurance_fund_create(ctx, token_index)?;
+        Ok(())
+    }
+
+    pub fn token_insurance_fund_deposit(
+        ctx: Context<TokenInsuranceFundDeposit>,
+        amount: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_insurance_fund_deposit(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn token_insurance_fund_withdraw(
+        ctx: Context<TokenInsuranceFundWithdraw>,
+        amount: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_insurance_fund_withdraw(ctx, amount)?;
+        Ok(())
+    }
+
     pub fn ix_gate_set(ctx: Context<IxGateSet>, ix_gate: u128) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::ix_gate_set(ctx, ix_gate)?;
@@ -298,6 +327,8 @@ pub mod mango_v4 {
             perp_count,
             perp_oo_count,
             0,
+            0,
+            0,
             name,
         )?;
         Ok(())
@@ -325,6 +356,39 @@ pub mod mango_v4 {
             perp_count,
             perp_oo_count,
             token_conditional_swap_count,
+            0,
+            0,
+            name,
+        )?;
+        Ok(())
+    }
+
+    pub fn account_create_v3(
+        ctx: Context<AccountCreateV3>,
+        account_num: u32,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_conditional_order_count: u8,
+        name: String,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::account_create(
+            &ctx.accounts.account,
+            *ctx.bumps.get("account").ok_or(MangoError::SomeError)?,
+            ctx.accounts.group.key(),
+            ctx.accounts.owner.key(),
+            account_num,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            perp_conditional_order_count,
             name,
         )?;
         Ok(())
@@ -338,7 +402,16 @@ pub mod mango_v4 {
         perp_oo_count: u8,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
-        instructions::account_expand(ctx, token_count, serum3_count, perp_count, perp_oo_count, 0)?;
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            0,
+            0,
+            0,
+        )?;
         Ok(())
     }
 
@@ -358,6 +431,32 @@ pub mod mango_v4 {
             perp_count,
             perp_oo_count,
             token_conditional_swap_count,
+            0,
+            0,
+        )?;
+        Ok(())
+    }
+
+    pub fn account_expand_v3(
+        ctx: Context<AccountExpand>,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_conditional_order_count: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            perp_conditional_order_count,
         )?;
         Ok(())
     }
@@ -435,6 +534,22 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    pub fn twap_oracle_create(
+        ctx: Context<TwapOracleCreate>,
+        window_secs: u64,
+        max_sample_deviation: f32,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::twap_oracle_create(ctx, window_secs, max_sample_deviation)?;
+        Ok(())
+    }
+
+    pub fn twap_oracle_update(ctx: Context<TwapOracleUpdate>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::twap_oracle_update(ctx)?;
+        Ok(())
+    }
+
     pub fn token_deposit(ctx: Context<TokenDeposit>, amount: u64, reduce_only: bool) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::token_deposit(ctx, amount, reduce_only)?;
@@ -848,6 +963,17 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    /// Moves settle token deposits into (positive amount) or out of (negative amount)
+    /// the isolated collateral of a perp position, making it isolated if needed.
+    pub fn perp_isolated_margin_transfer(
+        ctx: Context<PerpIsolatedMarginTransfer>,
+        amount: i64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_isolated_margin_transfer(ctx, amount)?;
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn perp_place_order(
         ctx: Context<PerpPlaceOrder>,
@@ -1202,6 +1328,15 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    pub fn perp_liq_isolated_position(
+        ctx: Context<PerpLiqIsolatedPosition>,
+        max_base_transfer: i64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_liq_isolated_position(ctx, max_base_transfer)?;
+        Ok(())
+    }
+
     pub fn perp_liq_negative_pnl_or_bankruptcy(
         ctx: Context<PerpLiqNegativePnlOrBankruptcy>,
         max_liab_transfer: u64,
@@ -1259,27 +1394,107 @@ pub mod mango_v4 {
         display_price_style: TokenConditionalSwapDisplayPriceStyle,
         intention: TokenConditionalSwapIntention,
     ) -> Result<()> {
-        let tcs = TokenConditionalSwap {
-            id: u64::MAX, // set inside
+        let tcs = new_token_conditional_swap(
+            ctx.accounts.buy_bank.load()?.token_index,
+            ctx.accounts.sell_bank.load()?.token_index,
             max_buy,
             max_sell,
-            bought: 0,
-            sold: 0,
             expiry_timestamp,
             price_lower_limit,
             price_upper_limit,
             price_premium_rate,
-            taker_fee_rate: 0.0, // set inside
-            maker_fee_rate: 0.0, // set inside
-            buy_token_index: ctx.accounts.buy_bank.load()?.token_index,
-            sell_token_index: ctx.accounts.sell_bank.load()?.token_index,
-            has_data: 1,
-            allow_creating_deposits: u8::from(allow_creating_deposits),
-            allow_creating_borrows: u8::from(allow_creating_borrows),
-            display_price_style: display_price_style.into(),
-            intention: intention.into(),
-            reserved: [0; 111],
-        };
+            allow_creating_deposits,
+            allow_creating_borrows,
+            display_price_style,
+            intention,
+            None,
+            None,
+        );
+
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_conditional_swap_create(ctx, tcs)?;
+        Ok(())
+    }
+
+    /// Like token_conditional_swap_create_v2, but executes in slices of at most
+    /// interval_max_sell native sell tokens, one slice per interval_seconds.
+    ///
+    /// Passing interval_seconds = 0 creates a regular tcs. If
+    /// premium_auction_duration_seconds is nonzero, price_premium_rate is the maximum
+    /// premium of a linear auction, see token_conditional_swap_create_linear_auction.
+    pub fn token_conditional_swap_create_twap(
+        ctx: Context<TokenConditionalSwapCreate>,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+        interval_seconds: u64,
+        interval_max_sell: u64,
+        premium_auction_duration_seconds: u64,
+    ) -> Result<()> {
+        let tcs = new_token_conditional_swap(
+            ctx.accounts.buy_bank.load()?.token_index,
+            ctx.accounts.sell_bank.load()?.token_index,
+            max_buy,
+            max_sell,
+            expiry_timestamp,
+            price_lower_limit,
+            price_upper_limit,
+            price_premium_rate,
+            allow_creating_deposits,
+            allow_creating_borrows,
+            display_price_style,
+            intention,
+            Some((interval_seconds, interval_max_sell)),
+            Some(premium_auction_duration_seconds),
+        );
+
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_conditional_swap_create(ctx, tcs)?;
+        Ok(())
+    }
+
+    /// Like token_conditional_swap_create_v2, but the premium is a linear auction.
+    ///
+    /// The auction starts when the tcs is first triggered with the price in range: the
+    /// premium then rises from zero to max_price_premium_rate over
+    /// premium_auction_duration_seconds. Triggering with the price out of range resets it.
+    pub fn token_conditional_swap_create_linear_auction(
+        ctx: Context<TokenConditionalSwapCreate>,
+        max_buy: u64,
+        max_sell: u64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        max_price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+        premium_auction_duration_seconds: u64,
+    ) -> Result<()> {
+        let tcs = new_token_conditional_swap(
+            ctx.accounts.buy_bank.load()?.token_index,
+            ctx.accounts.sell_bank.load()?.token_index,
+            max_buy,
+            max_sell,
+            expiry_timestamp,
+            price_lower_limit,
+            price_upper_limit,
+            max_price_premium_rate,
+            allow_creating_deposits,
+            allow_creating_borrows,
+            display_price_style,
+            intention,
+            None,
+            Some(premium_auction_duration_seconds),
+        );
 
         #[cfg(feature = "enable-gpl")]
         instructions::token_conditional_swap_create(ctx, tcs)?;
@@ -1319,6 +1534,163 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_conditional_order_create(
+        ctx: Context<PerpConditionalOrderCreate>,
+        side: Side,
+        max_base_lots: i64,
+        expiry_timestamp: u64,
+
+        // The perp oracle price, in native quote per native base, must be within
+        // these limits for the order to be triggered.
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+
+        // Fraction of the oracle price that is paid to the triggerer, see
+        // TokenConditionalSwap::price_premium_rate.
+        price_premium_rate: f64,
+        reduce_only: bool,
+        intention: TokenConditionalSwapIntention,
+    ) -> Result<()> {
+        let order = PerpConditionalOrder {
+            id: u64::MAX, // set inside
+            max_base_lots,
+            executed_base_lots: 0,
+            expiry_timestamp,
+            price_lower_limit,
+            price_upper_limit,
+            price_premium_rate,
+            perp_market_index: PerpMarketIndex::MAX, // set inside
+            has_data: 1,
+            side: side.into(),
+            reduce_only: u8::from(reduce_only),
+            intention: intention.into(),
+            execution: PerpConditionalOrderExecution::TradeWithTriggerer.into(),
+            ..PerpConditionalOrder::default()
+        };
+
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_conditional_order_create(ctx, order)?;
+        Ok(())
+    }
+
+    /// Creates a perp conditional order that places an order into the book once
+    /// the oracle price is in range, like a stop or take-profit order.
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_conditional_order_create_place_order(
+        ctx: Context<PerpConditionalOrderCreate>,
+        side: Side,
+
+        // The perp oracle price, in native quote per native base, must be within
+        // these limits for the order to be placed.
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+
+        // The price in lots of the order that is placed once triggered.
+        // If peg_to_oracle is set, this is the offset to the oracle price instead.
+        price_lots: i64,
+        peg_to_oracle: bool,
+
+        // Only used with peg_to_oracle, see perp_place_order_pegged.
+        peg_limit: i64,
+
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        self_trade_behavior: SelfTradeBehavior,
+        reduce_only: bool,
+
+        // Time in force, in seconds, of the order that is placed once triggered.
+        // Send 0 if it should never expire.
+        time_in_force: u16,
+
+        // Amount of settle token native paid to whoever places the order.
+        trigger_premium: u64,
+
+        // Timestamp after which the conditional order can no longer be triggered.
+        expiry_timestamp: u64,
+        intention: TokenConditionalSwapIntention,
+    ) -> Result<()> {
+        let order = PerpConditionalOrder {
+            id: u64::MAX, // set inside
+            max_base_lots,
+            executed_base_lots: 0,
+            expiry_timestamp,
+            price_lower_limit,
+            price_upper_limit,
+            price_lots,
+            peg_limit,
+            max_quote_lots,
+            client_order_id,
+            trigger_premium,
+            perp_market_index: PerpMarketIndex::MAX, // set inside
+            time_in_force,
+            has_data: 1,
+            side: side.into(),
+            reduce_only: u8::from(reduce_only),
+            intention: intention.into(),
+            execution: PerpConditionalOrderExecution::PlaceOrder.into(),
+            order_type: order_type.into(),
+            self_trade_behavior: self_trade_behavior.into(),
+            peg_to_oracle: u8::from(peg_to_oracle),
+            ..PerpConditionalOrder::default()
+        };
+
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_conditional_order_create(ctx, order)?;
+        Ok(())
+    }
+
+    pub fn perp_conditional_order_cancel(
+        ctx: Context<PerpConditionalOrderCancel>,
+        perp_conditional_order_index: u8,
+        perp_conditional_order_id: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_conditional_order_cancel(
+            ctx,
+            perp_conditional_order_index.into(),
+            perp_conditional_order_id,
+        )?;
+        Ok(())
+    }
+
+    pub fn perp_conditional_order_trigger(
+        ctx: Context<PerpConditionalOrderTrigger>,
+        perp_conditional_order_index: u8,
+        perp_conditional_order_id: u64,
+        max_base_lots: i64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_conditional_order_trigger(
+            ctx,
+            perp_conditional_order_index.into(),
+            perp_conditional_order_id,
+            max_base_lots,
+        )?;
+        Ok(())
+    }
+
+    pub fn perp_conditional_order_trigger_place_order(
+        ctx: Context<PerpConditionalOrderTriggerPlaceOrder>,
+        perp_conditional_order_index: u8,
+        perp_conditional_order_id: u64,
+
+        // Maximum number of orders from the book to fill, see perp_place_order.
+        limit: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_conditional_order_trigger_place_order(
+            ctx,
+            perp_conditional_order_index.into(),
+            perp_conditional_order_id,
+            limit,
+        )?;
+        Ok(())
+    }
+
     pub fn alt_set(ctx: Context<AltSet>, index: u8) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::alt_set(ctx, index)?;
@@ -1350,6 +1722,8 @@ pub mod mango_v4 {
         market_index: OpenbookV2MarketIndex,
         name: String,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_register_market(ctx, market_index, name)?;
         Ok(())
     }
 
@@ -1358,10 +1732,14 @@ pub mod mango_v4 {
         reduce_only_opt: Option<bool>,
         force_close_opt: Option<bool>,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_edit_market(ctx, reduce_only_opt, force_close_opt)?;
         Ok(())
     }
 
     pub fn openbook_v2_deregister_market(ctx: Context<OpenbookV2DeregisterMarket>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_deregister_market(ctx)?;
         Ok(())
     }
 
@@ -1369,10 +1747,14 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2CreateOpenOrders>,
         account_num: u32,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_create_open_orders(ctx, account_num)?;
         Ok(())
     }
 
     pub fn openbook_v2_close_open_orders(ctx: Context<OpenbookV2CloseOpenOrders>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_close_open_orders(ctx)?;
         Ok(())
     }
 
@@ -1388,6 +1770,21 @@ pub mod mango_v4 {
         client_order_id: u64,
         limit: u16,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_place_order(
+            ctx,
+            openbook_v2::state::Side::try_from(side)
+                .map_err(|_| error_msg!("bad side {}", side))?,
+            limit_price,
+            max_base_qty,
+            max_native_quote_qty_including_fees,
+            openbook_v2::state::SelfTradeBehavior::try_from(self_trade_behavior)
+                .map_err(|_| error_msg!("bad self trade behavior {}", self_trade_behavior))?,
+            openbook_v2::state::PlaceOrderType::try_from(order_type)
+                .map_err(|_| error_msg!("bad order type {}", order_type))?,
+            client_order_id,
+            limit,
+        )?;
         Ok(())
     }
 
@@ -1399,9 +1796,21 @@ pub mod mango_v4 {
         max_base_qty: u64,
         max_native_quote_qty_including_fees: u64,
         self_trade_behavior: u8, // openbook_v2::state::SelfTradeBehavior
-        client_order_id: u64,
+        client_order_id: u64,    // unused: take orders never rest on the book
         limit: u16,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_place_take_order(
+            ctx,
+            openbook_v2::state::Side::try_from(side)
+                .map_err(|_| error_msg!("bad side {}", side))?,
+            limit_price,
+            max_base_qty,
+            max_native_quote_qty_including_fees,
+            openbook_v2::state::SelfTradeBehavior::try_from(self_trade_behavior)
+                .map_err(|_| error_msg!("bad self trade behavior {}", self_trade_behavior))?,
+            limit,
+        )?;
         Ok(())
     }
 
@@ -1410,6 +1819,13 @@ pub mod mango_v4 {
         side: u8, // openbook_v2::state::Side
         order_id: u128,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_cancel_order(
+            ctx,
+            openbook_v2::state::Side::try_from(side)
+                .map_err(|_| error_msg!("bad side {}", side))?,
+            order_id,
+        )?;
         Ok(())
     }
 
@@ -1417,6 +1833,8 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2SettleFunds>,
         fees_to_dao: bool,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_settle_funds(ctx, fees_to_dao)?;
         Ok(())
     }
 
@@ -1424,6 +1842,8 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2LiqForceCancelOrders>,
         limit: u8,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_liq_force_cancel_orders(ctx, limit)?;
         Ok(())
     }
 
@@ -1431,6 +1851,8 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2CancelOrder>,
         limit: u8,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_cancel_all_orders(ctx, limit)?;
         Ok(())
     }
 
@@ -1445,6 +1867,58 @@ pub mod mango_v4 {
     }
 }
 
+/// Builds the tcs for the token_conditional_swap_create* instructions
+///
+/// interval_opt is (interval_seconds, interval_max_sell) for time-weighted execution
+/// and premium_auction_duration_seconds_opt makes the premium a linear auction.
+#[allow(clippy::too_many_arguments)]
+fn new_token_conditional_swap(
+    buy_token_index: TokenIndex,
+    sell_token_index: TokenIndex,
+    max_buy: u64,
+    max_sell: u64,
+    expiry_timestamp: u64,
+    price_lower_limit: f64,
+    price_upper_limit: f64,
+    price_premium_rate: f64,
+    allow_creating_deposits: bool,
+    allow_creating_borrows: bool,
+    display_price_style: TokenConditionalSwapDisplayPriceStyle,
+    intention: TokenConditionalSwapIntention,
+    interval_opt: Option<(u64, u64)>,
+    premium_auction_duration_seconds_opt: Option<u64>,
+) -> TokenConditionalSwap {
+    let (interval_seconds, interval_max_sell) = interval_opt.unwrap_or((0, 0));
+    TokenConditionalSwap {
+        id: u64::MAX, // set inside
+        max_buy,
+        max_sell,
+        bought: 0,
+        sold: 0,
+        expiry_timestamp,
+        price_lower_limit,
+        price_upper_limit,
+        price_premium_rate,
+        taker_fee_rate: 0.0, // set inside
+        maker_fee_rate: 0.0, // set inside
+        buy_token_index,
+        sell_token_index,
+        has_data: 1,
+        allow_creating_deposits: u8::from(allow_creating_deposits),
+        allow_creating_borrows: u8::from(allow_creating_borrows),
+        display_price_style: display_price_style.into(),
+        intention: intention.into(),
+        padding: Default::default(),
+        interval_seconds,
+        interval_max_sell,
+        interval_start_timestamp: 0,
+        interval_sold: 0,
+        premium_auction_duration_seconds: premium_auction_duration_seconds_opt.unwrap_or(0),
+        premium_auction_start_timestamp: 0,
+        reserved: [0; 56],
+    }
+}
+
 #[derive(Clone)]
 pub struct Mango;
 
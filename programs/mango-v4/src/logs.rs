@@ -283,6 +283,20 @@ pub struct Serum3OpenOrdersBalanceLogV2 {
     pub referrer_rebates_accrued: u64,
 }
 
+#[event]
+pub struct OpenbookV2OpenOrdersBalanceLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub market_index: u16,
+    pub base_token_index: u16,
+    pub quote_token_index: u16,
+    pub base_total: u64,
+    pub base_free: u64,
+    pub quote_total: u64,
+    pub quote_free: u64,
+    pub referrer_rebates_available: u64,
+}
+
 #[derive(PartialEq, Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
 #[repr(u8)]
 pub enum LoanOriginationFeeInstruction {
@@ -294,6 +308,10 @@ pub enum LoanOriginationFeeInstruction {
     Serum3SettleFunds,
     TokenWithdraw,
     TokenConditionalSwapTrigger,
+    OpenbookV2LiqForceCancelOrders,
+    OpenbookV2PlaceOrder,
+    OpenbookV2SettleFunds,
+    OpenbookV2PlaceTakeOrder,
 }
 
 #[event]
@@ -331,6 +349,16 @@ pub struct TokenLiqBankruptcyLog {
     pub ending_liab_deposit_index: i128,
 }
 
+#[event]
+pub struct TokenInsuranceFundUseLog {
+    pub mango_group: Pubkey,
+    pub liqee: Pubkey,
+    pub token_index: u16,
+    /// Set when the payout covered a perp market bankruptcy
+    pub perp_market_index: Option<u16>,
+    pub amount: u64,
+}
+
 #[event]
 pub struct DeactivateTokenPositionLog {
     pub mango_group: Pubkey,
@@ -384,6 +412,17 @@ pub struct Serum3RegisterMarketLog {
     pub serum_program_external: Pubkey,
 }
 
+#[event]
+pub struct OpenbookV2RegisterMarketLog {
+    pub mango_group: Pubkey,
+    pub openbook_market: Pubkey,
+    pub market_index: u16,
+    pub base_token_index: u16,
+    pub quote_token_index: u16,
+    pub openbook_program: Pubkey,
+    pub openbook_market_external: Pubkey,
+}
+
 #[event]
 pub struct PerpLiqBaseOrPositivePnlLog {
     pub mango_group: Pubkey,
@@ -535,6 +574,30 @@ pub struct TokenConditionalSwapCreateLogV2 {
     pub intention: u8,
 }
 
+#[event]
+pub struct TokenConditionalSwapCreateLogV3 {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub id: u64,
+    pub max_buy: u64,
+    pub max_sell: u64,
+    pub expiry_timestamp: u64,
+    pub price_lower_limit: f64,
+    pub price_upper_limit: f64,
+    pub price_premium_rate: f64,
+    pub taker_fee_rate: f32,
+    pub maker_fee_rate: f32,
+    pub buy_token_index: u16,
+    pub sell_token_index: u16,
+    pub allow_creating_deposits: bool,
+    pub allow_creating_borrows: bool,
+    pub display_price_style: u8,
+    pub intention: u8,
+    pub interval_seconds: u64,
+    pub interval_max_sell: u64,
+    pub premium_auction_duration_seconds: u64,
+}
+
 #[event]
 pub struct TokenConditionalSwapTriggerLog {
     pub mango_group: Pubkey,
@@ -571,9 +634,93 @@ pub struct TokenConditionalSwapTriggerLogV2 {
     pub intention: u8,
 }
 
+#[event]
+pub struct TokenConditionalSwapStartLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub token_conditional_swap_id: u64,
+    pub start_timestamp: u64, // 0 if the premium auction was reset
+}
+
 #[event]
 pub struct TokenConditionalSwapCancelLog {
     pub mango_group: Pubkey,
     pub mango_account: Pubkey,
     pub id: u64,
 }
+
+#[event]
+pub struct PerpConditionalOrderCreateLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub id: u64,
+    pub perp_market_index: u16,
+    pub side: u8,
+    pub max_base_lots: i64,
+    pub expiry_timestamp: u64,
+    pub price_lower_limit: f64,
+    pub price_upper_limit: f64,
+    pub price_premium_rate: f64,
+    pub reduce_only: bool,
+    pub intention: u8,
+    pub execution: u8,
+    pub order_type: u8,
+    pub price_lots: i64,
+    pub peg_to_oracle: bool,
+    pub max_quote_lots: i64,
+    pub trigger_premium: u64,
+}
+
+#[event]
+pub struct PerpConditionalOrderCancelLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub id: u64,
+}
+
+#[event]
+pub struct PerpConditionalOrderTriggerLog {
+    pub mango_group: Pubkey,
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub perp_conditional_order_id: u64,
+    pub perp_market_index: u16,
+    pub base_lots: i64,     // signed base change of the liqee
+    pub quote_native: i128, // I80F48, quote change of the liqee
+    pub oracle_price: i128, // I80F48
+    pub closed: bool,
+}
+
+#[event]
+pub struct PerpConditionalOrderPlaceLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub triggerer: Pubkey,
+    pub id: u64,
+    pub perp_market_index: u16,
+    pub oracle_price: i128, // I80F48
+    pub trigger_premium: u64,
+    pub order_id: Option<u128>,
+}
+
+#[event]
+pub struct PerpIsolatedMarginTransferLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub perp_market_index: u16,
+    pub amount: i64, // positive when moving collateral into the position
+    pub isolated_collateral_indexed: i128, // I80F48, after the transfer
+    pub deposit_index: i128, // I80F48
+}
+
+#[event]
+pub struct PerpLiqIsolatedPositionLog {
+    pub mango_group: Pubkey,
+    pub perp_market_index: u16,
+    pub liqor: Pubkey,
+    pub liqee: Pubkey,
+    pub base_transfer: i64,
+    pub quote_transfer: i128,
+    pub collateral_transfer: i128, // I80F48, settle token native taken from the collateral
+    pub price: i128,
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use openbook_v2::state::{Market, OpenOrdersAccount};
+
+use crate::accounts_zerocopy::*;
+use crate::state::*;
+
+pub fn load_open_orders(acc: &impl AccountReader) -> Result<&OpenOrdersAccount> {
+    acc.load::<OpenOrdersAccount>()
+}
+
+/// Loads the open orders account and converts its amounts to native using the
+/// lot sizes of the market
+pub fn load_open_orders_native(acc: &AccountInfo, market: &Market) -> Result<OpenOrdersNative> {
+    let oo_ai = AccountInfoRef::borrow(acc)?;
+    let oo = load_open_orders(&oo_ai)?;
+    Ok(OpenOrdersNative::from_oo(
+        oo,
+        market.base_lot_size,
+        market.quote_lot_size,
+    ))
+}
+
+/// Native token amounts on an openbook v2 open orders account.
+///
+/// Openbook v2 tracks the amounts locked in orders in lots, while free amounts are
+/// tracked in native units. This converts everything to native, like serum3 open
+/// orders store it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenOrdersNative {
+    pub base_free: u64,
+    pub base_reserved: u64,
+    pub quote_free: u64,
+    pub quote_reserved: u64,
+    pub referrer_rebates_available: u64,
+}
+
+impl OpenOrdersNative {
+    pub fn from_oo(oo: &OpenOrdersAccount, base_lot_size: i64, quote_lot_size: i64) -> Self {
+        let position = &oo.position;
+        // asks lock up base tokens, bids lock up quote tokens
+        let base_reserved = position.asks_base_lots * base_lot_size;
+        let quote_reserved = position.bids_quote_lots * quote_lot_size;
+        Self {
+            base_free: position.base_free_native,
+            base_reserved: base_reserved.try_into().unwrap(),
+            quote_free: position.quote_free_native,
+            quote_reserved: quote_reserved.try_into().unwrap(),
+            referrer_rebates_available: position.referrer_rebates_available,
+        }
+    }
+
+    pub fn from_oo_for_orders(oo: &OpenOrdersAccount, orders: &OpenbookV2Orders) -> Self {
+        Self::from_oo(oo, orders.base_lot_size, orders.quote_lot_size)
+    }
+
+    pub fn base_total(&self) -> u64 {
+        self.base_free + self.base_reserved
+    }
+
+    pub fn quote_total(&self) -> u64 {
+        self.quote_free + self.quote_reserved
+    }
+
+    /// The open orders account has no free or reserved funds
+    pub fn has_zero_funds(&self) -> bool {
+        self.base_total() == 0 && self.quote_total() == 0 && self.referrer_rebates_available == 0
+    }
+}
@@ -1,4 +1,4 @@
-use super::{OracleConfig, TokenIndex, TokenPosition};
+use super::{OracleConfig, PerpPosition, TokenIndex, TokenPosition};
 use crate::accounts_zerocopy::KeyedAccountReader;
 use crate::error::*;
 use crate::i80f48::ClampToInt;
@@ -148,8 +148,22 @@ pub struct Bank {
 
     pub flash_loan_swap_fee_rate: f32,
 
+    /// The TokenInsuranceFund for this token, or the default pubkey if there is none.
+    ///
+    /// Bankruptcy instructions must use the fund when it exists.
+    pub token_insurance_fund: Pubkey,
+
+    pub padding2: [u8; 4],
+
+    /// Sum of indexed_deposits/indexed_borrows of all other banks of the same token
+    ///
+    /// Updated in token_update_index_and_rate. Together with this bank's own values this
+    /// approximates the token-wide deposits and borrows, see native_deposits_all_banks().
+    pub other_banks_indexed_deposits: I80F48,
+    pub other_banks_indexed_borrows: I80F48,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 2092],
+    pub reserved: [u8; 2024],
 }
 const_assert_eq!(
     size_of::<Bank>(),
@@ -181,7 +195,10 @@ const_assert_eq!(
         + 6
         + 8
         + 3 * 4
-        + 2092
+        + 32
+        + 4
+        + 16 * 2
+        + 2024
 );
 const_assert_eq!(size_of::<Bank>(), 3064);
 const_assert_eq!(size_of::<Bank>() % 8, 0);
@@ -260,7 +277,13 @@ impl Bank {
             token_conditional_swap_taker_fee_rate: 0.0,
             token_conditional_swap_maker_fee_rate: 0.0,
             flash_loan_swap_fee_rate: 0.0,
-            reserved: [0; 2092],
+            token_insurance_fund: existing_bank.token_insurance_fund,
+            padding2: [0; 4],
+            other_banks_indexed_deposits: existing_bank.indexed_deposits
+                + existing_bank.other_banks_indexed_deposits,
+            other_banks_indexed_borrows: existing_bank.indexed_borrows
+                + existing_bank.other_banks_indexed_borrows,
+            reserved: [0; 2024],
         }
     }
 
@@ -307,6 +330,10 @@ impl Bank {
         self.force_close == 1
     }
 
+    pub fn has_token_insurance_fund(&self) -> bool {
+        self.token_insurance_fund != Pubkey::default()
+    }
+
     #[inline(always)]
     pub fn native_borrows(&self) -> I80F48 {
         self.borrow_index * self.indexed_borrows
@@ -317,6 +344,21 @@ impl Bank {
         self.deposit_index * self.indexed_deposits
     }
 
+    /// Deposits of the token summed over all its banks
+    ///
+    /// The contributions of other banks are only as fresh as the last
+    /// token_update_index_and_rate call.
+    #[inline(always)]
+    pub fn native_deposits_all_banks(&self) -> I80F48 {
+        self.deposit_index * (self.indexed_deposits + self.other_banks_indexed_deposits)
+    }
+
+    /// Borrows of the token summed over all its banks, see native_deposits_all_banks()
+    #[inline(always)]
+    pub fn native_borrows_all_banks(&self) -> I80F48 {
+        self.borrow_index * (self.indexed_borrows + self.other_banks_indexed_borrows)
+    }
+
     /// Prevent borrowing away the full bank vault.
     /// Keep some in reserve to satisfy non-borrow withdraws.
     pub fn enforce_min_vault_to_deposits_ratio(&self, vault_ai: &AccountInfo) -> Result<()> {
@@ -667,6 +709,64 @@ impl Bank {
         }
     }
 
+    /// Native isolated collateral of a perp position settling in this bank's token
+    #[inline(always)]
+    pub fn isolated_collateral_native(&self, perp_position: &PerpPosition) -> I80F48 {
+        self.deposit_index * perp_position.isolated_collateral_indexed
+    }
+
+    /// Adds `native_amount` to the isolated collateral of a perp position.
+    ///
+    /// Isolated collateral is a deposit like a token position: it is part of
+    /// indexed_deposits, counts toward utilization and earns deposit interest.
+    /// Callers are responsible for taking the amount out of a token position or vault.
+    ///
+    /// native_amount must be >= 0
+    pub fn deposit_isolated_collateral(
+        &mut self,
+        perp_position: &mut PerpPosition,
+        native_amount: I80F48,
+    ) -> Result<()> {
+        require_gte!(native_amount, 0);
+        // Round up like deposit_internal(), so the full amount can be withdrawn again
+        let mut indexed_change = native_amount / self.deposit_index;
+        if indexed_change * self.deposit_index < native_amount {
+            indexed_change += I80F48::DELTA;
+        }
+        self.indexed_deposits += indexed_change;
+        perp_position.isolated_collateral_indexed += indexed_change;
+        Ok(())
+    }
+
+    /// Removes `native_amount` from the isolated collateral of a perp position.
+    ///
+    /// Isolated collateral can't be borrowed against, so the amount must not exceed
+    /// the position's collateral. Withdrawing all of it zeroes the collateral exactly.
+    ///
+    /// native_amount must be >= 0
+    pub fn withdraw_isolated_collateral(
+        &mut self,
+        perp_position: &mut PerpPosition,
+        native_amount: I80F48,
+    ) -> Result<()> {
+        require_gte!(native_amount, 0);
+        let collateral = self.isolated_collateral_native(perp_position);
+        require_msg!(
+            native_amount <= collateral,
+            "can't withdraw {} from isolated collateral of {}",
+            native_amount,
+            collateral
+        );
+        let indexed_change = if native_amount == collateral {
+            perp_position.isolated_collateral_indexed
+        } else {
+            (native_amount / self.deposit_index).min(perp_position.isolated_collateral_indexed)
+        };
+        self.indexed_deposits -= indexed_change;
+        perp_position.isolated_collateral_indexed -= indexed_change;
+        Ok(())
+    }
+
     /// Update the bank's net_borrows fields.
     ///
     /// If oracle_price is set, also do a net borrows check and error if the threshold is exceeded.
@@ -898,7 +998,7 @@ impl Bank {
         I80F48::from_num(self.stable_price_model.stable_price)
     }
 
-    /// Returns the init asset weight, adjusted for the number of deposits on the bank.
+    /// Returns the init asset weight, adjusted for the number of deposits on all banks of the token.
     ///
     /// If max_collateral is 0, then the scaled init weight will be 0.
     /// Otherwise the weight is unadjusted until max_collateral and then scaled down
@@ -909,7 +1009,8 @@ impl Bank {
             return self.init_asset_weight;
         }
         // The next line is around 500 CU
-        let deposits_quote = self.native_deposits().to_num::<f64>() * price.to_num::<f64>();
+        let deposits_quote =
+            self.native_deposits_all_banks().to_num::<f64>() * price.to_num::<f64>();
         if deposits_quote <= self.deposit_weight_scale_start_quote {
             self.init_asset_weight
         } else {
@@ -925,7 +1026,7 @@ impl Bank {
             return self.init_liab_weight;
         }
         // The next line is around 500 CU
-        let borrows_quote = self.native_borrows().to_num::<f64>() * price.to_num::<f64>();
+        let borrows_quote = self.native_borrows_all_banks().to_num::<f64>() * price.to_num::<f64>();
         if borrows_quote <= self.borrow_weight_scale_start_quote {
             self.init_liab_weight
         } else if self.borrow_weight_scale_start_quote == 0.0 {
@@ -1160,4 +1261,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    pub fn test_isolated_collateral() -> Result<()> {
+        let mut bank = Bank::zeroed();
+        bank.deposit_index = I80F48::from_num(1.3);
+        bank.borrow_index = I80F48::from_num(1.3);
+
+        let mut account = TokenPosition::default();
+        let mut perp_position = PerpPosition::default();
+
+        // moving deposits into isolated collateral keeps them in the bank's deposits
+        bank.deposit(&mut account, I80F48::from(100), 0).unwrap();
+        let deposits = bank.native_deposits();
+        bank.withdraw_without_fee(&mut account, I80F48::from(40), 0)
+            .unwrap();
+        bank.deposit_isolated_collateral(&mut perp_position, I80F48::from(40))
+            .unwrap();
+        assert!(bank.isolated_collateral_native(&perp_position) >= 40);
+        assert!((bank.native_deposits() - deposits).abs() < 0.000001);
+
+        // the collateral earns deposit interest
+        bank.deposit_index = I80F48::from_num(2.6);
+        assert!(
+            (bank.isolated_collateral_native(&perp_position) - I80F48::from(80)).abs() < 0.000001
+        );
+
+        // it can't be withdrawn below zero and withdrawing all of it zeroes it exactly
+        bank.withdraw_isolated_collateral(&mut perp_position, I80F48::from(81))
+            .unwrap_err();
+        let collateral = bank.isolated_collateral_native(&perp_position);
+        bank.withdraw_isolated_collateral(&mut perp_position, collateral)
+            .unwrap();
+        assert_eq!(perp_position.isolated_collateral_indexed, I80F48::ZERO);
+        assert_eq!(bank.indexed_deposits, account.indexed_position);
+
+        Ok(())
+    }
 }
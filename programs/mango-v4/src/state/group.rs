@@ -12,17 +12,11 @@ pub type TokenIndex = u16;
 /// incorrect assumption.
 pub const QUOTE_TOKEN_INDEX: TokenIndex = 0;
 
-/// The token index used for the insurance fund.
+/// The token index used for the group insurance fund.
 ///
-/// We should eventually generalize insurance funds.
+/// Tokens can additionally have their own TokenInsuranceFund, which is used first.
 pub const INSURANCE_TOKEN_INDEX: TokenIndex = 0;
 
-/// The token index used for settling perp markets.
-///
-/// We should eventually generalize to make the whole perp quote (and settle) token
-/// configurable.
-pub const PERP_SETTLE_TOKEN_INDEX: TokenIndex = 0;
-
 /// The token index used in AccountBuybackFeesWithMngo to exchange for MNGO
 pub const FEE_BUYBACK_QUOTE_TOKEN_INDEX: TokenIndex = 0;
 
@@ -209,6 +203,16 @@ pub enum IxGate {
     OpenbookV2SettleFunds = 64,
     AdminTokenWithdrawFees = 65,
     AdminPerpWithdrawFees = 66,
+    TwapOracleCreate = 67,
+    TwapOracleUpdate = 68,
+    TokenInsuranceFundCreate = 69,
+    TokenInsuranceFundDeposit = 70,
+    TokenInsuranceFundWithdraw = 71,
+    PerpConditionalOrderCreate = 72,
+    PerpConditionalOrderCancel = 73,
+    PerpConditionalOrderTrigger = 74,
+    PerpIsolatedMarginTransfer = 75,
+    PerpLiqIsolatedPosition = 76,
     // NOTE: Adding new variants requires matching changes in ts and the ix_gate_set instruction.
 }
 
@@ -17,6 +17,7 @@ use crate::logs::{DeactivatePerpPositionLog, DeactivateTokenPositionLog};
 use super::BookSideOrderTree;
 use super::FillEvent;
 use super::LeafNode;
+use super::OpenbookV2MarketIndex;
 use super::PerpMarket;
 use super::PerpMarketIndex;
 use super::PerpOpenOrder;
@@ -25,7 +26,7 @@ use super::TokenConditionalSwap;
 use super::TokenIndex;
 use super::FREE_ORDER_SLOT;
 use super::{dynamic_account::*, Group};
-use super::{PerpPosition, Serum3Orders, TokenPosition};
+use super::{OpenbookV2Orders, PerpPosition, Serum3Orders, TokenPosition};
 use super::{Side, SideAndOrderTree};
 
 type BorshVecLength = u32;
@@ -126,7 +127,7 @@ pub struct MangoAccount {
     pub perps: Vec<PerpPosition>,
     pub padding7: u32,
     pub perp_open_orders: Vec<PerpOpenOrder>,
-    // WARNING: This does not have further fields, like tcs, intentionally:
+    // WARNING: This does not have further fields, like tcs or openbook_v2, intentionally:
     // There are existing accounts that don't have them and adding them here
     // would break backwards compatibility.
 }
@@ -172,6 +173,8 @@ impl MangoAccount {
         perp_count: u8,
         perp_oo_count: u8,
         token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_conditional_order_count: u8,
     ) -> usize {
         8 + size_of::<MangoAccountFixed>()
             + Self::dynamic_size(
@@ -180,6 +183,8 @@ impl MangoAccount {
                 perp_count,
                 perp_oo_count,
                 token_conditional_swap_count,
+                openbook_v2_count,
+                perp_conditional_order_count,
             )
     }
 
@@ -217,7 +222,7 @@ impl MangoAccount {
             + BORSH_VEC_PADDING_BYTES
     }
 
-    pub fn dynamic_size(
+    pub fn dynamic_openbook_v2_vec_offset(
         token_count: u8,
         serum3_count: u8,
         perp_count: u8,
@@ -231,6 +236,45 @@ impl MangoAccount {
             perp_oo_count,
         ) + (BORSH_VEC_SIZE_BYTES
             + size_of::<TokenConditionalSwap>() * usize::from(token_conditional_swap_count))
+            + BORSH_VEC_PADDING_BYTES
+    }
+
+    pub fn dynamic_perp_conditional_order_vec_offset(
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+    ) -> usize {
+        Self::dynamic_openbook_v2_vec_offset(
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+        ) + (BORSH_VEC_SIZE_BYTES + size_of::<OpenbookV2Orders>() * usize::from(openbook_v2_count))
+            + BORSH_VEC_PADDING_BYTES
+    }
+
+    pub fn dynamic_size(
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_conditional_order_count: u8,
+    ) -> usize {
+        Self::dynamic_perp_conditional_order_vec_offset(
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+        ) + (BORSH_VEC_SIZE_BYTES
+            + size_of::<PerpConditionalOrder>() * usize::from(perp_conditional_order_count))
     }
 }
 
@@ -256,11 +300,13 @@ pub struct MangoAccountFixed {
     pub next_token_conditional_swap_id: u64,
     pub temporary_delegate: Pubkey,
     pub temporary_delegate_expiry: u64,
-    pub reserved: [u8; 160],
+    /// Next id to use when adding a perp conditional order
+    pub next_perp_conditional_order_id: u64,
+    pub reserved: [u8; 152],
 }
 const_assert_eq!(
     size_of::<MangoAccountFixed>(),
-    32 * 4 + 8 + 8 * 8 + 32 + 8 + 160
+    32 * 4 + 8 + 8 * 8 + 32 + 8 + 8 + 152
 );
 const_assert_eq!(size_of::<MangoAccountFixed>(), 400);
 const_assert_eq!(size_of::<MangoAccountFixed>() % 8, 0);
@@ -383,6 +429,8 @@ pub struct MangoAccountDynamicHeader {
     pub perp_count: u8,
     pub perp_oo_count: u8,
     pub token_conditional_swap_count: u8,
+    pub openbook_v2_count: u8,
+    pub perp_conditional_order_count: u8,
 }
 
 impl DynamicHeader for MangoAccountDynamicHeader {
@@ -439,12 +487,55 @@ impl DynamicHeader for MangoAccountDynamicHeader {
                     0
                 };
 
+                let openbook_v2_vec_offset = MangoAccount::dynamic_openbook_v2_vec_offset(
+                    token_count,
+                    serum3_count,
+                    perp_count,
+                    perp_oo_count,
+                    token_conditional_swap_count,
+                );
+                let openbook_v2_count =
+                    if dynamic_data.len() > openbook_v2_vec_offset + BORSH_VEC_SIZE_BYTES {
+                        u8::try_from(BorshVecLength::from_le_bytes(*array_ref![
+                            dynamic_data,
+                            openbook_v2_vec_offset,
+                            BORSH_VEC_SIZE_BYTES
+                        ]))
+                        .unwrap()
+                    } else {
+                        0
+                    };
+
+                let perp_conditional_order_vec_offset =
+                    MangoAccount::dynamic_perp_conditional_order_vec_offset(
+                        token_count,
+                        serum3_count,
+                        perp_count,
+                        perp_oo_count,
+                        token_conditional_swap_count,
+                        openbook_v2_count,
+                    );
+                let perp_conditional_order_count = if dynamic_data.len()
+                    > perp_conditional_order_vec_offset + BORSH_VEC_SIZE_BYTES
+                {
+                    u8::try_from(BorshVecLength::from_le_bytes(*array_ref![
+                        dynamic_data,
+                        perp_conditional_order_vec_offset,
+                        BORSH_VEC_SIZE_BYTES
+                    ]))
+                    .unwrap()
+                } else {
+                    0
+                };
+
                 Ok(Self {
                     token_count,
                     serum3_count,
                     perp_count,
                     perp_oo_count,
                     token_conditional_swap_count,
+                    openbook_v2_count,
+                    perp_conditional_order_count,
                 })
             }
             _ => err!(MangoError::NotImplementedError).context("unexpected header version number"),
@@ -509,6 +600,30 @@ impl MangoAccountDynamicHeader {
             + raw_index * size_of::<TokenConditionalSwap>()
     }
 
+    // offset into dynamic data where 1st OpenbookV2Orders would be found
+    fn openbook_v2_offset(&self, raw_index: usize) -> usize {
+        MangoAccount::dynamic_openbook_v2_vec_offset(
+            self.token_count,
+            self.serum3_count,
+            self.perp_count,
+            self.perp_oo_count,
+            self.token_conditional_swap_count,
+        ) + BORSH_VEC_SIZE_BYTES
+            + raw_index * size_of::<OpenbookV2Orders>()
+    }
+
+    fn perp_conditional_order_offset(&self, raw_index: usize) -> usize {
+        MangoAccount::dynamic_perp_conditional_order_vec_offset(
+            self.token_count,
+            self.serum3_count,
+            self.perp_count,
+            self.perp_oo_count,
+            self.token_conditional_swap_count,
+            self.openbook_v2_count,
+        ) + BORSH_VEC_SIZE_BYTES
+            + raw_index * size_of::<PerpConditionalOrder>()
+    }
+
     pub fn token_count(&self) -> usize {
         self.token_count.into()
     }
@@ -524,6 +639,12 @@ impl MangoAccountDynamicHeader {
     pub fn token_conditional_swap_count(&self) -> usize {
         self.token_conditional_swap_count.into()
     }
+    pub fn openbook_v2_count(&self) -> usize {
+        self.openbook_v2_count.into()
+    }
+    pub fn perp_conditional_order_count(&self) -> usize {
+        self.perp_conditional_order_count.into()
+    }
 
     pub fn zero() -> Self {
         Self {
@@ -532,11 +653,16 @@ impl MangoAccountDynamicHeader {
             perp_count: 0,
             perp_oo_count: 0,
             token_conditional_swap_count: 0,
+            openbook_v2_count: 0,
+            perp_conditional_order_count: 0,
         }
     }
 
     fn expected_health_accounts(&self) -> usize {
-        self.token_count() * 2 + self.serum3_count() + self.perp_count() * 2
+        self.token_count() * 2
+            + self.serum3_count()
+            + self.perp_count() * 2
+            + self.openbook_v2_count()
     }
 
     /// Error if this header isn't a valid resize from `prev`
@@ -575,6 +701,19 @@ impl MangoAccountDynamicHeader {
             require_gte!(64, self.token_conditional_swap_count);
         }
 
+        require_gte!(self.openbook_v2_count, prev.openbook_v2_count);
+        if self.openbook_v2_count > prev.openbook_v2_count {
+            require_gte!(4, self.openbook_v2_count);
+        }
+
+        require_gte!(
+            self.perp_conditional_order_count,
+            prev.perp_conditional_order_count
+        );
+        if self.perp_conditional_order_count > prev.perp_conditional_order_count {
+            require_gte!(32, self.perp_conditional_order_count);
+        }
+
         let new_health_accounts = self.expected_health_accounts();
         let prev_health_accounts = prev.expected_health_accounts();
         if new_health_accounts > prev_health_accounts {
@@ -720,6 +859,42 @@ impl<
             .filter(|serum3_order| serum3_order.is_active())
     }
 
+    pub fn openbook_v2_orders(
+        &self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<&OpenbookV2Orders> {
+        self.all_openbook_v2_orders()
+            .find(|p| p.is_active_for_market(market_index))
+            .ok_or_else(|| {
+                error_msg!(
+                    "openbook v2 orders for market index {} not found",
+                    market_index
+                )
+            })
+    }
+
+    pub(crate) fn openbook_v2_orders_by_raw_index_unchecked(
+        &self,
+        raw_index: usize,
+    ) -> &OpenbookV2Orders {
+        get_helper(self.dynamic(), self.header().openbook_v2_offset(raw_index))
+    }
+
+    pub fn openbook_v2_orders_by_raw_index(&self, raw_index: usize) -> Result<&OpenbookV2Orders> {
+        require_gt!(self.header().openbook_v2_count(), raw_index);
+        Ok(self.openbook_v2_orders_by_raw_index_unchecked(raw_index))
+    }
+
+    pub fn all_openbook_v2_orders(&self) -> impl Iterator<Item = &OpenbookV2Orders> + '_ {
+        (0..self.header().openbook_v2_count())
+            .map(|i| self.openbook_v2_orders_by_raw_index_unchecked(i))
+    }
+
+    pub fn active_openbook_v2_orders(&self) -> impl Iterator<Item = &OpenbookV2Orders> + '_ {
+        self.all_openbook_v2_orders()
+            .filter(|openbook_v2_order| openbook_v2_order.is_active())
+    }
+
     pub fn perp_position(&self, market_index: PerpMarketIndex) -> Result<&PerpPosition> {
         self.all_perp_positions()
             .find(|p| p.is_active_for_market(market_index))
@@ -819,6 +994,41 @@ impl<
             .ok_or_else(|| error_msg!("no free token conditional swap index"))
     }
 
+    fn perp_conditional_order_by_index_unchecked(&self, index: usize) -> &PerpConditionalOrder {
+        get_helper(
+            self.dynamic(),
+            self.header().perp_conditional_order_offset(index),
+        )
+    }
+
+    pub fn perp_conditional_order_by_index(&self, index: usize) -> Result<&PerpConditionalOrder> {
+        require_gt!(self.header().perp_conditional_order_count(), index);
+        Ok(self.perp_conditional_order_by_index_unchecked(index))
+    }
+
+    pub fn perp_conditional_order_by_id(&self, id: u64) -> Result<(usize, &PerpConditionalOrder)> {
+        let index = self
+            .all_perp_conditional_orders()
+            .position(|v| v.has_data() && v.id == id)
+            .ok_or_else(|| error_msg!("perp conditional order with id {} not found", id))?;
+        Ok((index, self.perp_conditional_order_by_index_unchecked(index)))
+    }
+
+    pub fn all_perp_conditional_orders(&self) -> impl Iterator<Item = &PerpConditionalOrder> {
+        (0..self.header().perp_conditional_order_count())
+            .map(|i| self.perp_conditional_order_by_index_unchecked(i))
+    }
+
+    pub fn active_perp_conditional_orders(&self) -> impl Iterator<Item = &PerpConditionalOrder> {
+        self.all_perp_conditional_orders().filter(|p| p.has_data())
+    }
+
+    pub fn perp_conditional_order_free_index(&self) -> Result<usize> {
+        self.all_perp_conditional_orders()
+            .position(|&v| !v.has_data())
+            .ok_or_else(|| error_msg!("no free perp conditional order index"))
+    }
+
     pub fn borrow(&self) -> MangoAccountRef {
         MangoAccountRef {
             header: self.header(),
@@ -1019,6 +1229,67 @@ impl<
             .ok_or_else(|| error_msg!("serum3 orders for market index {} not found", market_index))
     }
 
+    // get mut OpenbookV2Orders at raw_index
+    pub fn openbook_v2_orders_mut_by_raw_index(
+        &mut self,
+        raw_index: usize,
+    ) -> &mut OpenbookV2Orders {
+        let offset = self.header().openbook_v2_offset(raw_index);
+        get_helper_mut(self.dynamic_mut(), offset)
+    }
+
+    pub fn create_openbook_v2_orders(
+        &mut self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<&mut OpenbookV2Orders> {
+        if self.openbook_v2_orders(market_index).is_ok() {
+            return err!(MangoError::OpenbookV2OpenOrdersExistAlready);
+        }
+
+        let raw_index_opt = self.all_openbook_v2_orders().position(|p| !p.is_active());
+        if let Some(raw_index) = raw_index_opt {
+            *(self.openbook_v2_orders_mut_by_raw_index(raw_index)) = OpenbookV2Orders {
+                market_index: market_index as OpenbookV2MarketIndex,
+                ..OpenbookV2Orders::default()
+            };
+            Ok(self.openbook_v2_orders_mut_by_raw_index(raw_index))
+        } else {
+            err!(MangoError::NoFreeOpenbookV2OpenOrdersIndex)
+        }
+    }
+
+    pub fn deactivate_openbook_v2_orders(
+        &mut self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<()> {
+        let raw_index = self
+            .all_openbook_v2_orders()
+            .position(|p| p.is_active_for_market(market_index))
+            .ok_or_else(|| {
+                error_msg!("openbook v2 open orders index {} not found", market_index)
+            })?;
+        self.openbook_v2_orders_mut_by_raw_index(raw_index)
+            .market_index = OpenbookV2MarketIndex::MAX;
+        Ok(())
+    }
+
+    pub fn openbook_v2_orders_mut(
+        &mut self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<&mut OpenbookV2Orders> {
+        let raw_index_opt = self
+            .all_openbook_v2_orders()
+            .position(|p| p.is_active_for_market(market_index));
+        raw_index_opt
+            .map(|raw_index| self.openbook_v2_orders_mut_by_raw_index(raw_index))
+            .ok_or_else(|| {
+                error_msg!(
+                    "openbook v2 orders for market index {} not found",
+                    market_index
+                )
+            })
+    }
+
     // get mut PerpPosition at raw_index
     pub fn perp_position_mut_by_raw_index(&mut self, raw_index: usize) -> &mut PerpPosition {
         let offset = self.header().perp_offset(raw_index);
@@ -1240,6 +1511,21 @@ impl<
         Ok(tcs)
     }
 
+    pub fn perp_conditional_order_mut_by_index(
+        &mut self,
+        index: usize,
+    ) -> Result<&mut PerpConditionalOrder> {
+        let count: usize = self.header().perp_conditional_order_count.into();
+        require_gt!(count, index);
+        let offset = self.header().perp_conditional_order_offset(index);
+        Ok(get_helper_mut(self.dynamic_mut(), offset))
+    }
+
+    pub fn free_perp_conditional_order_mut(&mut self) -> Result<&mut PerpConditionalOrder> {
+        let index = self.perp_conditional_order_free_index()?;
+        self.perp_conditional_order_mut_by_index(index)
+    }
+
     pub fn check_health_pre(&mut self, health_cache: &HealthCache) -> Result<I80F48> {
         let pre_init_health = health_cache.health(HealthType::Init);
         msg!("pre_init_health: {}", pre_init_health);
@@ -1368,6 +1654,18 @@ impl<
         self.write_borsh_vec_length(offset, count)
     }
 
+    fn write_openbook_v2_length(&mut self) {
+        let offset = self.header().openbook_v2_offset(0);
+        let count = self.header().openbook_v2_count;
+        self.write_borsh_vec_length(offset, count)
+    }
+
+    fn write_perp_conditional_order_length(&mut self) {
+        let offset = self.header().perp_conditional_order_offset(0);
+        let count = self.header().perp_conditional_order_count;
+        self.write_borsh_vec_length(offset, count)
+    }
+
     pub fn expand_dynamic_content(
         &mut self,
         new_token_count: u8,
@@ -1375,6 +1673,8 @@ impl<
         new_perp_count: u8,
         new_perp_oo_count: u8,
         new_token_conditional_swap_count: u8,
+        new_openbook_v2_count: u8,
+        new_perp_conditional_order_count: u8,
     ) -> Result<()> {
         let new_header = MangoAccountDynamicHeader {
             token_count: new_token_count,
@@ -1382,6 +1682,8 @@ impl<
             perp_count: new_perp_count,
             perp_oo_count: new_perp_oo_count,
             token_conditional_swap_count: new_token_conditional_swap_count,
+            openbook_v2_count: new_openbook_v2_count,
+            perp_conditional_order_count: new_perp_conditional_order_count,
         };
         let old_header = self.header().clone();
 
@@ -1391,6 +1693,36 @@ impl<
 
         // expand dynamic components by first moving existing positions, and then setting new ones to defaults
 
+        // perp conditional orders
+        if old_header.perp_conditional_order_count() > 0 {
+            unsafe {
+                sol_memmove(
+                    &mut dynamic[new_header.perp_conditional_order_offset(0)],
+                    &mut dynamic[old_header.perp_conditional_order_offset(0)],
+                    size_of::<PerpConditionalOrder>() * old_header.perp_conditional_order_count(),
+                );
+            }
+        }
+        for i in old_header.perp_conditional_order_count..new_perp_conditional_order_count {
+            *get_helper_mut(dynamic, new_header.perp_conditional_order_offset(i.into())) =
+                PerpConditionalOrder::default();
+        }
+
+        // openbook v2 positions
+        if old_header.openbook_v2_count() > 0 {
+            unsafe {
+                sol_memmove(
+                    &mut dynamic[new_header.openbook_v2_offset(0)],
+                    &mut dynamic[old_header.openbook_v2_offset(0)],
+                    size_of::<OpenbookV2Orders>() * old_header.openbook_v2_count(),
+                );
+            }
+        }
+        for i in old_header.openbook_v2_count..new_openbook_v2_count {
+            *get_helper_mut(dynamic, new_header.openbook_v2_offset(i.into())) =
+                OpenbookV2Orders::default();
+        }
+
         // token conditional swaps
         if old_header.token_conditional_swap_count() > 0 {
             unsafe {
@@ -1472,6 +1804,8 @@ impl<
         self.write_perp_length();
         self.write_perp_oo_length();
         self.write_token_conditional_swap_length();
+        self.write_openbook_v2_length();
+        self.write_perp_conditional_order_length();
 
         Ok(())
     }
@@ -1546,12 +1880,16 @@ mod tests {
 
         // The MangoAccount struct is missing some dynamic fields, add space for them
         let tcs_length = 2;
+        let openbook_v2_length = 3;
+        let perp_conditional_order_length = 2;
         let expected_space = MangoAccount::space(
             account.tokens.len() as u8,
             account.serum3.len() as u8,
             account.perps.len() as u8,
             account.perp_open_orders.len() as u8,
             tcs_length,
+            openbook_v2_length,
+            perp_conditional_order_length,
         );
         bytes.extend(vec![0u8; expected_space - bytes.len()]);
 
@@ -1559,12 +1897,24 @@ mod tests {
         let (fixed, dynamic) = bytes.split_at_mut(size_of::<MangoAccountFixed>());
         let mut header = MangoAccountDynamicHeader::from_bytes(dynamic).unwrap();
         header.token_conditional_swap_count = tcs_length;
+        header.openbook_v2_count = openbook_v2_length;
+        header.perp_conditional_order_count = perp_conditional_order_length;
         let mut account = MangoAccountRefMut {
             header: &mut header,
             fixed: bytemuck::from_bytes_mut(fixed),
             dynamic,
         };
         account.write_token_conditional_swap_length();
+        account.write_openbook_v2_length();
+        account.write_perp_conditional_order_length();
+        for i in 0..openbook_v2_length {
+            *account.openbook_v2_orders_mut_by_raw_index(i.into()) = OpenbookV2Orders::default();
+        }
+        for i in 0..perp_conditional_order_length {
+            *account
+                .perp_conditional_order_mut_by_index(i.into())
+                .unwrap() = PerpConditionalOrder::default();
+        }
 
         MangoAccountValue::from_bytes(&bytes).unwrap()
     }
@@ -1600,9 +1950,12 @@ mod tests {
             b.extend([0u8; 8]);
             b
         };
+        // the openbook v2 and perp conditional order sections each add padding and Vec size
+        // when empty
         assert_eq!(
             8 + account_bytes_with_tcs.len(),
-            MangoAccount::space(8, 8, 4, 8, 0)
+            MangoAccount::space(8, 8, 4, 8, 0, 0, 0)
+                - 2 * (BORSH_VEC_PADDING_BYTES + BORSH_VEC_SIZE_BYTES)
         );
 
         let account2 = MangoAccountValue::from_bytes(&account_bytes_without_tcs).unwrap();
@@ -1661,6 +2014,51 @@ mod tests {
         assert_eq!(account3.all_token_conditional_swaps().count(), 0);
     }
 
+    #[test]
+    fn test_serialization_match_openbook_v2() {
+        let mut account = MangoAccount::default_for_tests();
+        account.tokens.resize(8, TokenPosition::default());
+        account.serum3.resize(8, Serum3Orders::default());
+        account.perps.resize(4, PerpPosition::default());
+        account.perp_open_orders.resize(8, PerpOpenOrder::default());
+
+        let account_bytes_with_tcs = {
+            let mut b = AnchorSerialize::try_to_vec(&account).unwrap();
+            // tcs adds 4 bytes of padding and 4 bytes of Vec size
+            b.extend([0u8; 8]);
+            b
+        };
+        let account_bytes_with_openbook_v2 = {
+            let mut b = account_bytes_with_tcs.clone();
+            // openbook v2 adds 4 bytes of padding and 4 bytes of Vec size
+            b.extend([0u8; 8]);
+            b
+        };
+        let account_bytes_with_perp_conditional_orders = {
+            let mut b = account_bytes_with_openbook_v2.clone();
+            // perp conditional orders add 4 bytes of padding and 4 bytes of Vec size
+            b.extend([0u8; 8]);
+            b
+        };
+        assert_eq!(
+            8 + account_bytes_with_perp_conditional_orders.len(),
+            MangoAccount::space(8, 8, 4, 8, 0, 0, 0)
+        );
+
+        let account3 = MangoAccountValue::from_bytes(&account_bytes_with_tcs).unwrap();
+        assert_eq!(account3.all_openbook_v2_orders().count(), 0);
+
+        let account4 = MangoAccountValue::from_bytes(&account_bytes_with_openbook_v2).unwrap();
+        assert_eq!(account4.all_token_conditional_swaps().count(), 0);
+        assert_eq!(account4.all_openbook_v2_orders().count(), 0);
+        assert_eq!(account4.all_perp_conditional_orders().count(), 0);
+
+        let account5 =
+            MangoAccountValue::from_bytes(&account_bytes_with_perp_conditional_orders).unwrap();
+        assert_eq!(account5.all_openbook_v2_orders().count(), 0);
+        assert_eq!(account5.all_perp_conditional_orders().count(), 0);
+    }
+
     #[test]
     fn test_token_positions() {
         let mut account = make_test_account();
@@ -1770,6 +2168,120 @@ mod tests {
         assert!(account.serum3_orders_mut(7).is_err());
     }
 
+    #[test]
+    fn test_openbook_v2_orders() {
+        let mut account = make_test_account();
+        assert_eq!(account.all_openbook_v2_orders().count(), 3);
+        assert!(account.openbook_v2_orders(1).is_err());
+        assert!(account.openbook_v2_orders_mut(3).is_err());
+        assert_eq!(
+            account
+                .openbook_v2_orders_by_raw_index_unchecked(0)
+                .market_index,
+            OpenbookV2MarketIndex::MAX
+        );
+
+        assert_eq!(
+            account.create_openbook_v2_orders(1).unwrap().market_index,
+            1
+        );
+        assert_eq!(
+            account.create_openbook_v2_orders(7).unwrap().market_index,
+            7
+        );
+        assert_eq!(
+            account.create_openbook_v2_orders(42).unwrap().market_index,
+            42
+        );
+        assert!(account.create_openbook_v2_orders(7).is_err());
+        assert!(account.create_openbook_v2_orders(9).is_err());
+        assert_eq!(account.active_openbook_v2_orders().count(), 3);
+
+        assert!(account.deactivate_openbook_v2_orders(7).is_ok());
+        assert_eq!(
+            account
+                .openbook_v2_orders_by_raw_index_unchecked(1)
+                .market_index,
+            OpenbookV2MarketIndex::MAX
+        );
+        assert!(account.create_openbook_v2_orders(8).is_ok());
+        assert_eq!(
+            account
+                .openbook_v2_orders_by_raw_index_unchecked(1)
+                .market_index,
+            8
+        );
+
+        assert_eq!(account.active_openbook_v2_orders().count(), 3);
+        assert!(account.deactivate_openbook_v2_orders(1).is_ok());
+        assert!(account.openbook_v2_orders(1).is_err());
+        assert!(account.openbook_v2_orders_mut(1).is_err());
+        assert!(account.openbook_v2_orders(8).is_ok());
+        assert!(account.openbook_v2_orders(42).is_ok());
+        assert_eq!(account.active_openbook_v2_orders().count(), 2);
+
+        assert_eq!(account.openbook_v2_orders_mut(42).unwrap().market_index, 42);
+        assert_eq!(account.openbook_v2_orders_mut(8).unwrap().market_index, 8);
+        assert!(account.openbook_v2_orders_mut(7).is_err());
+    }
+
+    #[test]
+    fn test_expand_openbook_v2() {
+        let mut account = make_test_account();
+        account.create_serum3_orders(5).unwrap();
+        account.create_openbook_v2_orders(2).unwrap().open_orders = Pubkey::new_unique();
+        let tcs_id = {
+            let tcs = account.free_token_conditional_swap_mut().unwrap();
+            tcs.id = 17;
+            tcs.has_data = 1;
+            tcs.id
+        };
+        let oo_key = account.openbook_v2_orders(2).unwrap().open_orders;
+
+        let new_space = MangoAccount::space(4, 5, 4, 6, 3, 4, 2);
+        account
+            .dynamic
+            .resize(new_space - 8 - size_of::<MangoAccountFixed>(), 0);
+        account.expand_dynamic_content(4, 5, 4, 6, 3, 4, 2).unwrap();
+
+        assert_eq!(account.all_openbook_v2_orders().count(), 4);
+        assert_eq!(account.active_openbook_v2_orders().count(), 1);
+        assert_eq!(account.openbook_v2_orders(2).unwrap().open_orders, oo_key);
+        assert!(account.serum3_orders(5).is_ok());
+        assert_eq!(account.token_conditional_swap_by_id(tcs_id).unwrap().0, 0);
+
+        // can't shrink the openbook v2 section
+        assert!(account.expand_dynamic_content(4, 5, 4, 6, 3, 3, 2).is_err());
+    }
+
+    #[test]
+    fn test_expand_perp_conditional_orders() {
+        let mut account = make_test_account();
+        account.create_openbook_v2_orders(2).unwrap().open_orders = Pubkey::new_unique();
+        {
+            let order = account.free_perp_conditional_order_mut().unwrap();
+            order.id = 7;
+            order.has_data = 1;
+        }
+
+        let oo_key = account.openbook_v2_orders(2).unwrap().open_orders;
+
+        let new_space = MangoAccount::space(3, 5, 4, 6, 2, 3, 4);
+        account
+            .dynamic
+            .resize(new_space - 8 - size_of::<MangoAccountFixed>(), 0);
+        account.expand_dynamic_content(3, 5, 4, 6, 2, 3, 4).unwrap();
+
+        assert_eq!(account.all_perp_conditional_orders().count(), 4);
+        assert_eq!(account.active_perp_conditional_orders().count(), 1);
+        assert_eq!(account.perp_conditional_order_by_id(7).unwrap().0, 0);
+        assert_eq!(account.perp_conditional_order_free_index().unwrap(), 1);
+        assert_eq!(account.openbook_v2_orders(2).unwrap().open_orders, oo_key);
+
+        // can't shrink the perp conditional order section
+        assert!(account.expand_dynamic_content(3, 5, 4, 6, 2, 3, 3).is_err());
+    }
+
     #[test]
     fn test_perp_positions() {
         let mut account = make_test_account();
@@ -165,6 +165,73 @@ impl Default for Serum3Orders {
     }
 }
 
+#[zero_copy]
+#[derive(AnchorSerialize, AnchorDeserialize, Derivative)]
+#[derivative(Debug)]
+pub struct OpenbookV2Orders {
+    pub open_orders: Pubkey,
+
+    /// Tracks the amount of borrows that have flowed into the openbook open orders account.
+    /// These borrows did not have the loan origination fee applied, and that may happen
+    /// later (in openbook_v2_settle_funds) if we can guarantee that the funds were used.
+    /// In particular a place-on-book, cancel, settle should not cost fees.
+    pub base_borrows_without_fee: u64,
+    pub quote_borrows_without_fee: u64,
+
+    pub market_index: OpenbookV2MarketIndex,
+
+    /// Store the base/quote token index, so health computations don't need
+    /// to get passed the static OpenbookV2Market to find which tokens a market
+    /// uses and look up the correct oracles.
+    pub base_token_index: TokenIndex,
+    pub quote_token_index: TokenIndex,
+
+    #[derivative(Debug = "ignore")]
+    pub padding: [u8; 2],
+
+    /// Lot sizes of the external market, copied on open orders creation.
+    /// The openbook v2 open orders account tracks reserved amounts in lots and
+    /// health computations need to convert them to native without loading the market.
+    pub base_lot_size: i64,
+    pub quote_lot_size: i64,
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 48],
+}
+const_assert_eq!(
+    size_of::<OpenbookV2Orders>(),
+    32 + 8 * 2 + 2 * 3 + 2 + 8 * 2 + 48
+);
+const_assert_eq!(size_of::<OpenbookV2Orders>(), 120);
+const_assert_eq!(size_of::<OpenbookV2Orders>() % 8, 0);
+
+impl OpenbookV2Orders {
+    pub fn is_active(&self) -> bool {
+        self.market_index != OpenbookV2MarketIndex::MAX
+    }
+
+    pub fn is_active_for_market(&self, market_index: OpenbookV2MarketIndex) -> bool {
+        self.market_index == market_index
+    }
+}
+
+impl Default for OpenbookV2Orders {
+    fn default() -> Self {
+        Self {
+            open_orders: Pubkey::default(),
+            market_index: OpenbookV2MarketIndex::MAX,
+            base_token_index: TokenIndex::MAX,
+            quote_token_index: TokenIndex::MAX,
+            base_lot_size: 0,
+            quote_lot_size: 0,
+            reserved: [0; 48],
+            padding: Default::default(),
+            base_borrows_without_fee: 0,
+            quote_borrows_without_fee: 0,
+        }
+    }
+}
+
 #[zero_copy]
 #[derive(AnchorSerialize, AnchorDeserialize, Derivative)]
 #[derivative(Debug)]
@@ -278,12 +345,44 @@ pub struct PerpPosition {
     /// price and current price of the base position is the overall pnl.
     pub realized_pnl_for_position_native: I80F48,
 
+    /// Whether the position is in isolated margin mode, see isolated_collateral_indexed
+    pub isolated: u8,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 88],
+    pub padding2: [u8; 7],
+
+    /// Settle token set aside to back an isolated position, indexed by the settle
+    /// bank's deposit_index. Use Bank::isolated_collateral_native() to get native units.
+    ///
+    /// Isolated positions are not part of the account's cross-margined health. Instead,
+    /// this collateral plus the position's health unsettled pnl must stay positive
+    /// and the position gets liquidated on its own. The collateral stays part of the
+    /// settle bank's indexed_deposits and earns deposit interest.
+    pub isolated_collateral_indexed: I80F48,
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 64],
 }
 const_assert_eq!(
     size_of::<PerpPosition>(),
-    2 + 2 + 4 + 8 + 8 + 16 + 8 + 16 * 2 + 8 * 2 + 8 * 2 + 8 * 5 + 8 + 2 * 16 + 8 + 16 + 88
+    2 + 2
+        + 4
+        + 8
+        + 8
+        + 16
+        + 8
+        + 16 * 2
+        + 8 * 2
+        + 8 * 2
+        + 8 * 5
+        + 8
+        + 2 * 16
+        + 8
+        + 16
+        + 1
+        + 7
+        + 16
+        + 64
 );
 const_assert_eq!(size_of::<PerpPosition>(), 304);
 const_assert_eq!(size_of::<PerpPosition>() % 8, 0);
@@ -314,7 +413,10 @@ impl Default for PerpPosition {
             settle_pnl_limit_settled_in_current_window_native: 0,
             settle_pnl_limit_realized_trade: 0,
             realized_pnl_for_position_native: I80F48::ZERO,
-            reserved: [0; 88],
+            isolated: 0,
+            padding2: Default::default(),
+            isolated_collateral_indexed: I80F48::ZERO,
+            reserved: [0; 64],
         }
     }
 }
@@ -347,6 +449,18 @@ impl PerpPosition {
         self.market_index == market_index
     }
 
+    pub fn is_isolated(&self) -> bool {
+        self.isolated == 1
+    }
+
+    /// Whether the position is empty enough to switch into isolated margin mode
+    pub fn can_become_isolated(&self) -> bool {
+        self.base_position_lots == 0
+            && self.quote_position_native == 0
+            && !self.has_open_orders_or_fills()
+            && self.isolated_collateral_indexed == 0
+    }
+
     // Return base position in native units for a perp market
     pub fn base_position_native(&self, market: &PerpMarket) -> I80F48 {
         I80F48::from(self.base_position_lots * market.base_lot_size)
@@ -788,6 +902,13 @@ impl PerpPosition {
         self.realized_other_pnl_native += change;
     }
 
+    /// Tracks the premium paid (negative) or received (positive) when a perp conditional
+    /// order is placed into the book. Like other realized pnl it is immediately settleable.
+    pub fn record_perp_conditional_order_premium(&mut self, change: I80F48) {
+        self.change_quote_position(change);
+        self.realized_other_pnl_native += change;
+    }
+
     /// Adds to the quote position and adds a recurring ("realized trade") settle limit
     pub fn record_liquidation_pnl_takeover(&mut self, change: I80F48, recurring_limit: I80F48) {
         self.change_quote_position(change);
@@ -60,6 +60,18 @@ impl MintInfo {
         &self.banks[..self.num_banks()]
     }
 
+    pub fn vaults(&self) -> &[Pubkey] {
+        &self.vaults[..self.num_banks()]
+    }
+
+    /// Bank and vault addresses, in bank_num order
+    pub fn banks_and_vaults(&self) -> impl Iterator<Item = (Pubkey, Pubkey)> + '_ {
+        self.banks()
+            .iter()
+            .copied()
+            .zip(self.vaults().iter().copied())
+    }
+
     pub fn verify_banks_ais(&self, all_bank_ais: &[AccountInfo]) -> Result<()> {
         require_msg!(
             all_bank_ais.iter().map(|ai| ai.key).eq(self.banks().iter()),
@@ -8,10 +8,12 @@ pub use mint_info::*;
 pub use openbook_v2_market::*;
 pub use oracle::*;
 pub use orderbook::*;
+pub use perp_conditional_order::*;
 pub use perp_market::*;
 pub use serum3_market::*;
 pub use stable_price::*;
 pub use token_conditional_swap::*;
+pub use token_insurance_fund::*;
 
 mod bank;
 mod dynamic_account;
@@ -23,7 +25,9 @@ mod mint_info;
 mod openbook_v2_market;
 mod oracle;
 mod orderbook;
+mod perp_conditional_order;
 mod perp_market;
 mod serum3_market;
 mod stable_price;
 mod token_conditional_swap;
+mod token_insurance_fund;
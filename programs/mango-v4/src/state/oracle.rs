@@ -55,6 +55,14 @@ pub mod switchboard_v2_mainnet_oracle {
     use solana_program::declare_id;
     declare_id!("DtmE9D2CSB4L5D6A15mraeEjrGMm6auWVzgaD8hK2tZM");
 }
+pub mod pyth_solana_receiver {
+    use solana_program::declare_id;
+    declare_id!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+}
+pub mod raydium_clmm {
+    use solana_program::declare_id;
+    declare_id!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+}
 
 #[zero_copy]
 #[derive(AnchorDeserialize, AnchorSerialize, Debug)]
@@ -89,6 +97,10 @@ pub enum OracleType {
     Stub,
     SwitchboardV1,
     SwitchboardV2,
+    /// Pyth pull oracle: a PriceUpdateV2 account owned by the pyth solana receiver
+    PythV2,
+    /// A TwapOracle account, averaging the price of a raydium clmm pool
+    Twap,
 }
 
 pub struct OracleState {
@@ -167,6 +179,185 @@ const_assert_eq!(size_of::<StubOracle>(), 32 + 32 + 16 + 8 + 8 + 16 + 104);
 const_assert_eq!(size_of::<StubOracle>(), 216);
 const_assert_eq!(size_of::<StubOracle>() % 8, 0);
 
+/// Oracle that tracks a time weighted average of the price of a raydium
+/// concentrated liquidity pool.
+///
+/// The pool must pair the oracle's mint with a token that has the same value and
+/// decimals as the group's quote token, since the pool price is used as-is.
+///
+/// Anyone may update the oracle. Each sample is weighted by the time elapsed since the
+/// previous update, and clamped to within max_sample_deviation of the average, so a
+/// short lived manipulation of the pool price can only move the average a limited
+/// amount. The deviation reported to the confidence check is the difference between
+/// the latest (unclamped) sample and the average.
+#[account(zero_copy)]
+pub struct TwapOracle {
+    // ABI: Clients rely on this being at offset 8
+    pub group: Pubkey,
+    // ABI: Clients rely on this being at offset 40
+    pub mint: Pubkey,
+    /// The raydium clmm pool the price is sampled from
+    pub pool: Pubkey,
+    /// Time weighted average price, in native quote per native base
+    pub twap_price: I80F48,
+    /// Pool price at the last update, in native quote per native base
+    pub last_sample_price: I80F48,
+    /// Samples are clamped to twap_price * (1 +- max_sample_deviation)
+    pub max_sample_deviation: I80F48,
+    pub last_update_ts: i64,
+    pub last_update_slot: u64,
+    /// Averaging window in seconds: a sample that stayed in effect this long fully
+    /// replaces the average
+    pub window_secs: u64,
+    /// 1 if mint is the pool's token_1, meaning the pool price needs to be inverted
+    pub invert: u8,
+    pub padding: [u8; 7],
+    pub reserved: [u8; 112],
+}
+const_assert_eq!(
+    size_of::<TwapOracle>(),
+    3 * 32 + 3 * 16 + 3 * 8 + 1 + 7 + 112
+);
+const_assert_eq!(size_of::<TwapOracle>(), 288);
+const_assert_eq!(size_of::<TwapOracle>() % 8, 0);
+
+impl TwapOracle {
+    /// Adds a new sample of the pool price, in native quote per native base
+    pub fn update(&mut self, sample_price: I80F48, now_ts: i64, now_slot: u64) {
+        let elapsed = I80F48::from(now_ts.saturating_sub(self.last_update_ts).max(0));
+        let weight = if self.window_secs == 0 {
+            I80F48::ONE
+        } else {
+            (elapsed / I80F48::from(self.window_secs)).min(I80F48::ONE)
+        };
+        // The sample stands for the time since the last update. Clamping it bounds
+        // how far a single manipulated sample can move the average.
+        let max_deviation = self.twap_price * self.max_sample_deviation;
+        let clamped_price = sample_price
+            .max(self.twap_price - max_deviation)
+            .min(self.twap_price + max_deviation);
+        self.twap_price += (clamped_price - self.twap_price) * weight;
+        self.last_sample_price = sample_price;
+        self.last_update_ts = now_ts;
+        self.last_update_slot = now_slot;
+    }
+
+    /// Converts a raydium clmm pool price to the price of the oracle's mint
+    pub fn sample_price_from_pool(&self, pool_price: I80F48) -> Result<I80F48> {
+        if self.invert == 1 {
+            require_gt!(pool_price, 0);
+            Ok(I80F48::ONE / pool_price)
+        } else {
+            Ok(pool_price)
+        }
+    }
+}
+
+/// The relevant fields of a raydium clmm PoolState account
+pub struct RaydiumClmmPool {
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    /// Price in native token_1 per native token_0
+    pub price: I80F48,
+}
+
+impl RaydiumClmmPool {
+    pub const DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+    const TOKEN_MINT_0_OFFSET: usize = 73;
+    const TOKEN_MINT_1_OFFSET: usize = 105;
+    const SQRT_PRICE_X64_OFFSET: usize = 253;
+
+    pub fn from_account(acc_info: &impl AccountReader) -> Result<Self> {
+        require_keys_eq!(*acc_info.owner(), raydium_clmm::ID);
+        Self::parse(acc_info.data())
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require_msg!(
+            data.len() >= Self::SQRT_PRICE_X64_OFFSET + 16 && data[0..8] == Self::DISCRIMINATOR,
+            "not a raydium clmm pool account"
+        );
+        let pubkey_at =
+            |offset: usize| Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap());
+        let sqrt_price_x64 = u128::from_le_bytes(
+            data[Self::SQRT_PRICE_X64_OFFSET..Self::SQRT_PRICE_X64_OFFSET + 16]
+                .try_into()
+                .unwrap(),
+        );
+        // Q64.64 to I80F48, dropping the lowest fractional bits
+        let sqrt_price = I80F48::from_bits((sqrt_price_x64 >> 16) as i128);
+        let price = sqrt_price
+            .checked_mul(sqrt_price)
+            .ok_or_else(|| error_msg!("raydium clmm pool price overflow"))?;
+        Ok(Self {
+            token_mint_0: pubkey_at(Self::TOKEN_MINT_0_OFFSET),
+            token_mint_1: pubkey_at(Self::TOKEN_MINT_1_OFFSET),
+            price,
+        })
+    }
+}
+
+/// The relevant fields of a pyth solana receiver PriceUpdateV2 account
+pub struct PythPriceUpdateV2 {
+    /// Partially verified updates were checked by fewer guardians than required
+    pub fully_verified: bool,
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub posted_slot: u64,
+}
+
+impl PythPriceUpdateV2 {
+    pub const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require_msg!(
+            data.len() >= 8 && data[0..8] == Self::DISCRIMINATOR,
+            "not a pyth price update v2 account"
+        );
+        let read = |offset: usize, len: usize| -> Result<&[u8]> {
+            data.get(offset..offset + len)
+                .ok_or_else(|| error_msg!("pyth price update v2 account is too short"))
+        };
+
+        // skip the discriminator and write_authority
+        let mut offset = 8 + 32;
+
+        // verification_level is a borsh enum: Partial { num_signatures: u8 } or Full
+        let fully_verified = match read(offset, 1)?[0] {
+            0 => {
+                offset += 2;
+                false
+            }
+            1 => {
+                offset += 1;
+                true
+            }
+            v => return Err(error_msg!("unknown pyth verification level {}", v)),
+        };
+
+        // price_message, starting with the feed_id
+        offset += 32;
+        let price = i64::from_le_bytes(read(offset, 8)?.try_into().unwrap());
+        let conf = u64::from_le_bytes(read(offset + 8, 8)?.try_into().unwrap());
+        let exponent = i32::from_le_bytes(read(offset + 16, 4)?.try_into().unwrap());
+        let publish_time = i64::from_le_bytes(read(offset + 20, 8)?.try_into().unwrap());
+        // skip prev_publish_time, ema_price, ema_conf
+        let posted_slot = u64::from_le_bytes(read(offset + 52, 8)?.try_into().unwrap());
+
+        Ok(Self {
+            fully_verified,
+            price,
+            conf,
+            exponent,
+            publish_time,
+            posted_slot,
+        })
+    }
+}
+
 pub fn determine_oracle_type(acc_info: &impl KeyedAccountReader) -> Result<OracleType> {
     let data = acc_info.data();
 
@@ -179,6 +370,12 @@ pub fn determine_oracle_type(acc_info: &impl KeyedAccountReader) -> Result<Oracl
     // note: disc is not public, hence the copy pasta
     else if data[0..8] == [217, 230, 65, 101, 201, 162, 27, 125] {
         return Ok(OracleType::SwitchboardV2);
+    } else if data[0..8] == PythPriceUpdateV2::DISCRIMINATOR
+        && acc_info.owner() == &pyth_solana_receiver::ID
+    {
+        return Ok(OracleType::PythV2);
+    } else if data[0..8] == TwapOracle::discriminator() {
+        return Ok(OracleType::Twap);
     }
     // note: this is the only known way of checking this
     else if acc_info.owner() == &switchboard_v1_devnet_oracle::ID
@@ -326,6 +523,40 @@ pub fn oracle_state_unchecked(
                 oracle_type: OracleType::SwitchboardV1,
             }
         }
+        OracleType::PythV2 => {
+            let update = PythPriceUpdateV2::parse(data)?;
+            require_msg_typed!(
+                update.fully_verified,
+                MangoError::UnexpectedOracle,
+                "pyth price update {} is only partially verified",
+                acc_info.key()
+            );
+
+            let decimals = (update.exponent as i8) + QUOTE_DECIMALS - (base_decimals as i8);
+            let decimal_adj = power_of_ten(decimals);
+            let price = I80F48::from_num(update.price) * decimal_adj;
+            let deviation = I80F48::from_num(update.conf) * decimal_adj;
+            require_gte!(price, 0);
+            OracleState {
+                price,
+                // Pull updates don't carry the publisher slot. The posting slot is later,
+                // so staleness checks don't account for the delay before posting.
+                last_update_slot: update.posted_slot,
+                deviation,
+                oracle_type: OracleType::PythV2,
+            }
+        }
+        OracleType::Twap => {
+            let twap = acc_info.load::<TwapOracle>()?;
+            let price = twap.twap_price;
+            require_gte!(price, 0);
+            OracleState {
+                price,
+                last_update_slot: twap.last_update_slot,
+                deviation: (twap.last_sample_price - price).abs(),
+                oracle_type: OracleType::Twap,
+            }
+        }
     })
 }
 
@@ -374,6 +605,158 @@ mod tests {
         Ok(())
     }
 
+    fn pyth_v2_bytes(verification_level: &[u8], price: i64, conf: u64, exponent: i32) -> Vec<u8> {
+        let mut data = PythPriceUpdateV2::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0u8; 32]); // write_authority
+        data.extend_from_slice(verification_level);
+        data.extend_from_slice(&[1u8; 32]); // feed_id
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&conf.to_le_bytes());
+        data.extend_from_slice(&exponent.to_le_bytes());
+        data.extend_from_slice(&1000i64.to_le_bytes()); // publish_time
+        data.extend_from_slice(&999i64.to_le_bytes()); // prev_publish_time
+        data.extend_from_slice(&price.to_le_bytes()); // ema_price
+        data.extend_from_slice(&conf.to_le_bytes()); // ema_conf
+        data.extend_from_slice(&77u64.to_le_bytes()); // posted_slot
+        data
+    }
+
+    #[test]
+    pub fn test_pyth_v2() -> Result<()> {
+        let key = Pubkey::new_unique();
+
+        let mut data = pyth_v2_bytes(&[1], 12345678, 1000, -8);
+        let data = RefCell::new(&mut data[..]);
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &pyth_solana_receiver::ID,
+            data: data.borrow(),
+        };
+        assert!(determine_oracle_type(ai).unwrap() == OracleType::PythV2);
+
+        // base and quote both have 6 decimals
+        let state = oracle_state_unchecked(ai, 6)?;
+        assert!(state.oracle_type == OracleType::PythV2);
+        assert_eq!(state.last_update_slot, 77);
+        assert!((state.price.to_num::<f64>() - 0.12345678).abs() < 1e-10);
+        assert!((state.deviation.to_num::<f64>() - 0.00001).abs() < 1e-10);
+
+        // the owner must be the pyth receiver program
+        let wrong_owner = Pubkey::new_unique();
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &wrong_owner,
+            data: data.borrow(),
+        };
+        assert!(determine_oracle_type(ai).is_err());
+
+        // partially verified updates are rejected
+        let mut data = pyth_v2_bytes(&[0, 3], 12345678, 1000, -8);
+        let parsed = PythPriceUpdateV2::parse(&data)?;
+        assert!(!parsed.fully_verified);
+        assert_eq!(parsed.price, 12345678);
+        assert_eq!(parsed.posted_slot, 77);
+        let data = RefCell::new(&mut data[..]);
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &pyth_solana_receiver::ID,
+            data: data.borrow(),
+        };
+        assert!(oracle_state_unchecked(ai, 6).is_err());
+
+        // truncated data fails to parse
+        let data = pyth_v2_bytes(&[1], 12345678, 1000, -8);
+        assert!(PythPriceUpdateV2::parse(&data[..data.len() - 4]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_raydium_clmm_pool() -> Result<()> {
+        let mint0 = Pubkey::new_unique();
+        let mint1 = Pubkey::new_unique();
+        let mut data = vec![0u8; 300];
+        data[0..8].copy_from_slice(&RaydiumClmmPool::DISCRIMINATOR);
+        data[73..105].copy_from_slice(mint0.as_ref());
+        data[105..137].copy_from_slice(mint1.as_ref());
+        // sqrt price of 1.5
+        let sqrt_price_x64: u128 = 3 << 63;
+        data[253..269].copy_from_slice(&sqrt_price_x64.to_le_bytes());
+
+        let pool = RaydiumClmmPool::parse(&data)?;
+        assert_eq!(pool.token_mint_0, mint0);
+        assert_eq!(pool.token_mint_1, mint1);
+        assert_eq!(pool.price, I80F48::from_num(2.25));
+
+        data[0] = 0;
+        assert!(RaydiumClmmPool::parse(&data).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_twap_oracle() -> Result<()> {
+        let mut twap: TwapOracle = bytemuck::Zeroable::zeroed();
+        twap.window_secs = 100;
+        twap.max_sample_deviation = I80F48::from_num(0.5);
+        twap.twap_price = I80F48::from(10);
+        twap.last_sample_price = I80F48::from(10);
+        twap.last_update_ts = 1000;
+        twap.last_update_slot = 5;
+
+        // a sample taken without time passing does not affect the average
+        twap.update(I80F48::from(12), 1000, 6);
+        assert_eq!(twap.twap_price, I80F48::from(10));
+        assert_eq!(twap.last_sample_price, I80F48::from(12));
+        assert_eq!(twap.last_update_slot, 6);
+
+        // a sample after a quarter of the window moves the average a quarter of the way
+        twap.update(I80F48::from(14), 1025, 7);
+        assert_eq!(twap.twap_price, I80F48::from(11));
+
+        // a sample after a full window replaces the average
+        twap.update(I80F48::from(12), 2000, 8);
+        assert_eq!(twap.twap_price, I80F48::from(12));
+
+        // outlier samples are clamped, even when they have full weight
+        twap.update(I80F48::from(1000), 3000, 9);
+        assert_eq!(twap.twap_price, I80F48::from(18));
+        assert_eq!(twap.last_sample_price, I80F48::from(1000));
+        twap.update(I80F48::from(1), 4000, 10);
+        assert_eq!(twap.twap_price, I80F48::from(9));
+        twap.update(I80F48::from(20), 5000, 11);
+        assert_eq!(twap.twap_price, I80F48::from_num(13.5));
+
+        // inverted pools
+        twap.invert = 1;
+        assert_eq!(
+            twap.sample_price_from_pool(I80F48::from(4))?,
+            I80F48::from_num(0.25)
+        );
+        assert!(twap.sample_price_from_pool(I80F48::ZERO).is_err());
+
+        // loading through the generic oracle functions
+        twap.twap_price = I80F48::from(20);
+        twap.last_sample_price = I80F48::from(22);
+        let mut data = TwapOracle::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&twap));
+        let data = RefCell::new(&mut data[..]);
+        let key = Pubkey::new_unique();
+        let owner = crate::id();
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &owner,
+            data: data.borrow(),
+        };
+        assert!(determine_oracle_type(ai).unwrap() == OracleType::Twap);
+        let state = oracle_state_unchecked(ai, 6)?;
+        assert_eq!(state.price, I80F48::from(20));
+        assert_eq!(state.deviation, I80F48::from(2));
+        assert_eq!(state.last_update_slot, 11);
+
+        Ok(())
+    }
+
     #[test]
     pub fn lookup_test() {
         for idx in -12..0 {
@@ -0,0 +1,409 @@
+use anchor_lang::prelude::*;
+
+use derivative::Derivative;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use crate::error::*;
+use crate::state::*;
+
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+#[repr(u8)]
+pub enum PerpConditionalOrderExecution {
+    /// The triggerer takes the opposite side of the trade directly, at the oracle
+    /// price adjusted by price_premium_rate. The orderbook is not involved.
+    TradeWithTriggerer,
+    /// The order described by order_type, price_lots etc is placed into the orderbook
+    /// on behalf of the account and the triggerer receives trigger_premium (e.g. stop orders).
+    PlaceOrder,
+}
+
+/// A perp order that stays dormant in the MangoAccount until the perp oracle price
+/// is in a range.
+///
+/// Depending on `execution` a triggerer then either trades with the account directly,
+/// like for a TokenConditionalSwap, or places an order into the orderbook on behalf
+/// of the account.
+#[zero_copy]
+#[derive(AnchorDeserialize, AnchorSerialize, Derivative)]
+#[derivative(Debug)]
+pub struct PerpConditionalOrder {
+    pub id: u64,
+
+    /// maximum amount of base lots to trade
+    pub max_base_lots: i64,
+
+    /// how many base lots were already traded, only used for TradeWithTriggerer
+    pub executed_base_lots: i64,
+
+    /// timestamp until which the conditional order is valid
+    pub expiry_timestamp: u64,
+
+    /// The perp oracle price must be >= lower_limit and <= upper_limit for execution.
+    ///
+    /// Prices are in native quote per native base, like perp oracle prices.
+    ///
+    /// Example: Stop loss on a SOL-PERP long opened at 20 USDC/SOL, closing
+    /// the position below 18 USDC/SOL: side=Ask, reduce_only, price_lower_limit=0
+    /// and price_upper_limit=toNative(18).
+    pub price_lower_limit: f64,
+
+    /// Parallel to price_lower_limit, but an upper limit.
+    pub price_upper_limit: f64,
+
+    /// The premium to pay over oracle price to incentivize execution, only used
+    /// for TradeWithTriggerer
+    pub price_premium_rate: f64,
+
+    /// Limit price of the placed order in lots, only used for PlaceOrder.
+    ///
+    /// If peg_to_oracle is set, this is instead the offset to the oracle price
+    /// and the order is placed as an oracle pegged order. Ignored for market orders.
+    pub price_lots: i64,
+
+    /// Only used for oracle pegged orders, see perp_place_order_pegged
+    pub peg_limit: i64,
+
+    /// Only used for PlaceOrder
+    pub max_quote_lots: i64,
+
+    /// Only used for PlaceOrder
+    pub client_order_id: u64,
+
+    /// Amount of perp quote native paid to the triggerer when the order is placed,
+    /// only used for PlaceOrder
+    pub trigger_premium: u64,
+
+    pub perp_market_index: PerpMarketIndex,
+
+    /// Time in force of the placed order in seconds, 0 meaning forever
+    pub time_in_force: u16,
+
+    pub has_data: u8,
+
+    /// The side the account trades on, stores a Side enum value
+    pub side: u8,
+
+    /// may execution only reduce the perp base position?
+    pub reduce_only: u8,
+
+    /// The intention the user had when placing this order, display-only
+    ///
+    /// Stores a TokenConditionalSwapIntention enum value
+    pub intention: u8,
+
+    /// Stores a PerpConditionalOrderExecution enum value
+    pub execution: u8,
+
+    /// Stores a PlaceOrderType enum value
+    pub order_type: u8,
+
+    /// Stores a SelfTradeBehavior enum value
+    pub self_trade_behavior: u8,
+
+    pub peg_to_oracle: u8,
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 20],
+}
+
+const_assert_eq!(
+    size_of::<PerpConditionalOrder>(),
+    8 * 4 + 8 * 3 + 8 * 5 + 2 * 2 + 1 * 8 + 20
+);
+const_assert_eq!(size_of::<PerpConditionalOrder>(), 128);
+const_assert_eq!(size_of::<PerpConditionalOrder>() % 8, 0);
+
+impl Default for PerpConditionalOrder {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            max_base_lots: 0,
+            executed_base_lots: 0,
+            expiry_timestamp: u64::MAX,
+            price_lower_limit: 0.0,
+            price_upper_limit: 0.0,
+            price_premium_rate: 0.0,
+            price_lots: 0,
+            peg_limit: -1,
+            max_quote_lots: 0,
+            client_order_id: 0,
+            trigger_premium: 0,
+            perp_market_index: PerpMarketIndex::MAX,
+            time_in_force: 0,
+            has_data: 0,
+            side: Side::Bid.into(),
+            reduce_only: 0,
+            intention: TokenConditionalSwapIntention::Unknown.into(),
+            execution: PerpConditionalOrderExecution::TradeWithTriggerer.into(),
+            order_type: PlaceOrderType::Market.into(),
+            self_trade_behavior: SelfTradeBehavior::DecrementTake.into(),
+            peg_to_oracle: 0,
+            reserved: [0; 20],
+        }
+    }
+}
+
+impl PerpConditionalOrder {
+    /// Whether the entry is in use
+    ///
+    /// Note that it's possible for an entry to be in use but be expired
+    pub fn has_data(&self) -> bool {
+        self.has_data == 1
+    }
+
+    pub fn is_expired(&self, now_ts: u64) -> bool {
+        now_ts >= self.expiry_timestamp
+    }
+
+    pub fn side(&self) -> Side {
+        Side::try_from(self.side).unwrap()
+    }
+
+    pub fn reduce_only(&self) -> bool {
+        self.reduce_only == 1
+    }
+
+    pub fn execution(&self) -> PerpConditionalOrderExecution {
+        PerpConditionalOrderExecution::try_from(self.execution).unwrap()
+    }
+
+    pub fn order_type(&self) -> PlaceOrderType {
+        PlaceOrderType::try_from(self.order_type).unwrap()
+    }
+
+    pub fn self_trade_behavior(&self) -> SelfTradeBehavior {
+        SelfTradeBehavior::try_from(self.self_trade_behavior).unwrap()
+    }
+
+    pub fn peg_to_oracle(&self) -> bool {
+        self.peg_to_oracle == 1
+    }
+
+    pub fn remaining_base_lots(&self) -> i64 {
+        self.max_base_lots - self.executed_base_lots
+    }
+
+    pub fn price_in_range(&self, price: f64) -> bool {
+        price >= self.price_lower_limit && price <= self.price_upper_limit
+    }
+
+    /// Oracle price adjusted for the premium, in native quote per native base
+    ///
+    /// Buying pays more than the oracle price, selling receives less.
+    pub fn premium_price(&self, oracle_price: f64) -> f64 {
+        match self.side() {
+            Side::Bid => oracle_price * (1.0 + self.price_premium_rate),
+            Side::Ask => oracle_price * (1.0 - self.price_premium_rate),
+        }
+    }
+
+    /// The remaining base lots that may be traded, taking the current base position
+    /// and the market's reduce-only status into account.
+    ///
+    /// Note that the account health might further restrict execution.
+    pub fn max_base_lots_for_position(
+        &self,
+        base_position_lots: i64,
+        market_reduce_only: bool,
+    ) -> i64 {
+        let remaining = self.remaining_base_lots().max(0);
+        if !self.reduce_only() && !market_reduce_only {
+            return remaining;
+        }
+        let reducible = match self.side() {
+            Side::Bid => (-base_position_lots).max(0),
+            Side::Ask => base_position_lots.max(0),
+        };
+        remaining.min(reducible)
+    }
+
+    /// Checks the stored values, used when creating a conditional order
+    pub fn check_valid(&self) -> Result<()> {
+        Side::try_from(self.side).map_err(|_| error_msg!("bad side {}", self.side))?;
+        TokenConditionalSwapIntention::try_from(self.intention)
+            .map_err(|_| error_msg!("bad intention {}", self.intention))?;
+        let execution = PerpConditionalOrderExecution::try_from(self.execution)
+            .map_err(|_| error_msg!("bad execution {}", self.execution))?;
+        require_gt!(self.max_base_lots, 0);
+        require_gte!(self.price_lower_limit, 0.0);
+        require_gte!(self.price_upper_limit, 0.0);
+        require_gte!(self.price_upper_limit, self.price_lower_limit);
+
+        match execution {
+            PerpConditionalOrderExecution::TradeWithTriggerer => {
+                require_gte!(self.price_premium_rate, 0.0);
+                require_gt!(1.0, self.price_premium_rate);
+            }
+            PerpConditionalOrderExecution::PlaceOrder => {
+                let order_type = PlaceOrderType::try_from(self.order_type)
+                    .map_err(|_| error_msg!("bad order type {}", self.order_type))?;
+                SelfTradeBehavior::try_from(self.self_trade_behavior).map_err(|_| {
+                    error_msg!("bad self trade behavior {}", self.self_trade_behavior)
+                })?;
+                require_gte!(self.max_quote_lots, 0);
+                if !self.peg_to_oracle() {
+                    require_gte!(self.price_lots, 0);
+                } else {
+                    require_msg!(
+                        order_type != PlaceOrderType::Market
+                            && order_type != PlaceOrderType::ImmediateOrCancel,
+                        "oracle pegged conditional orders must be able to rest on the book"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The order to place into the book when a PlaceOrder conditional order is triggered
+    pub fn to_order(&self) -> Result<Order> {
+        let order_type = self.order_type();
+        let params = if self.peg_to_oracle() {
+            OrderParams::OraclePegged {
+                price_offset_lots: self.price_lots,
+                order_type: order_type.to_post_order_type()?,
+                peg_limit: self.peg_limit,
+                max_oracle_staleness_slots: -1,
+            }
+        } else {
+            match order_type {
+                PlaceOrderType::Market => OrderParams::Market,
+                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel {
+                    price_lots: self.price_lots,
+                },
+                _ => OrderParams::Fixed {
+                    price_lots: self.price_lots,
+                    order_type: order_type.to_post_order_type()?,
+                },
+            }
+        };
+        Ok(Order {
+            side: self.side(),
+            max_base_lots: self.max_base_lots,
+            max_quote_lots: self.max_quote_lots,
+            client_order_id: self.client_order_id,
+            reduce_only: self.reduce_only(),
+            time_in_force: self.time_in_force,
+            self_trade_behavior: self.self_trade_behavior(),
+            params,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perp_conditional_order_premium_price() {
+        let mut order = PerpConditionalOrder {
+            price_premium_rate: 0.01,
+            ..PerpConditionalOrder::default()
+        };
+        assert_eq!(order.premium_price(100.0), 101.0);
+
+        order.side = Side::Ask.into();
+        assert_eq!(order.premium_price(100.0), 99.0);
+    }
+
+    #[test]
+    fn test_perp_conditional_order_max_base_lots_for_position() {
+        let mut order = PerpConditionalOrder {
+            max_base_lots: 10,
+            executed_base_lots: 3,
+            side: Side::Ask.into(),
+            ..PerpConditionalOrder::default()
+        };
+        assert_eq!(order.max_base_lots_for_position(5, false), 7);
+        assert_eq!(order.max_base_lots_for_position(-5, false), 7);
+        assert_eq!(order.max_base_lots_for_position(5, true), 5);
+        assert_eq!(order.max_base_lots_for_position(-5, true), 0);
+
+        order.reduce_only = 1;
+        assert_eq!(order.max_base_lots_for_position(20, false), 7);
+        assert_eq!(order.max_base_lots_for_position(5, false), 5);
+        assert_eq!(order.max_base_lots_for_position(-5, false), 0);
+
+        order.side = Side::Bid.into();
+        assert_eq!(order.max_base_lots_for_position(-5, false), 5);
+        assert_eq!(order.max_base_lots_for_position(5, false), 0);
+    }
+
+    #[test]
+    fn test_perp_conditional_order_price_in_range() {
+        let order = PerpConditionalOrder {
+            price_lower_limit: 90.0,
+            price_upper_limit: 100.0,
+            ..PerpConditionalOrder::default()
+        };
+        assert!(!order.price_in_range(89.0));
+        assert!(order.price_in_range(90.0));
+        assert!(order.price_in_range(100.0));
+        assert!(!order.price_in_range(101.0));
+    }
+
+    #[test]
+    fn test_perp_conditional_order_check_valid_place_order() {
+        let valid = PerpConditionalOrder {
+            price_lower_limit: 0.0,
+            price_upper_limit: 100.0,
+            price_lots: 90,
+            max_base_lots: 1,
+            max_quote_lots: i64::MAX,
+            execution: PerpConditionalOrderExecution::PlaceOrder.into(),
+            order_type: PlaceOrderType::Limit.into(),
+            ..PerpConditionalOrder::default()
+        };
+        assert!(valid.check_valid().is_ok());
+
+        let mut order = valid;
+        order.side = 5;
+        assert!(order.check_valid().is_err());
+
+        let mut order = valid;
+        order.execution = 5;
+        assert!(order.check_valid().is_err());
+
+        let mut order = valid;
+        order.price_lots = -10;
+        assert!(order.check_valid().is_err());
+
+        let mut order = valid;
+        order.peg_to_oracle = 1;
+        order.price_lots = -10;
+        assert!(order.check_valid().is_ok());
+        order.order_type = PlaceOrderType::Market.into();
+        assert!(order.check_valid().is_err());
+    }
+
+    #[test]
+    fn test_perp_conditional_order_check_valid_price_range() {
+        let valid = PerpConditionalOrder {
+            price_lower_limit: 90.0,
+            price_upper_limit: 100.0,
+            max_base_lots: 1,
+            ..PerpConditionalOrder::default()
+        };
+        assert!(valid.check_valid().is_ok());
+
+        let mut order = valid;
+        order.price_upper_limit = 90.0;
+        assert!(order.check_valid().is_ok());
+
+        // an inverted range could never trigger
+        order.price_upper_limit = 89.0;
+        assert!(order.check_valid().is_err());
+    }
+}
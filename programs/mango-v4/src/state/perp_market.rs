@@ -126,8 +126,10 @@ pub struct PerpMarket {
     /// Fees accrued in native quote currency
     /// these are increased when new fees are paid and decreased when perp_settle_fees is called
     pub fees_accrued: I80F48,
-    /// Fees settled in native quote currency
-    /// these are increased when perp_settle_fees is called, and never decreased
+    /// Fees settled in native settle token, converted at the settle token oracle price
+    /// these are increased when perp_settle_fees is called, and never decreased.
+    /// This is what admin_perp_withdraw_fees withdraws, see fees_settled_quote for the
+    /// same amounts in quote units.
     pub fees_settled: I80F48,
 
     /// Fee (in quote native) to charge for ioc orders
@@ -176,7 +178,11 @@ pub struct PerpMarket {
     // This ensures that fees_settled is strictly increasing for stats gathering purposes
     pub fees_withdrawn: u64,
 
-    pub reserved: [u8; 1880],
+    /// Fees settled in native quote currency, the amounts that perp_settle_fees removed
+    /// from fees_accrued. Only counts settlements since this field was introduced.
+    pub fees_settled_quote: I80F48,
+
+    pub reserved: [u8; 1864],
 }
 
 const_assert_eq!(
@@ -213,7 +219,8 @@ const_assert_eq!(
         + 7
         + 3 * 16
         + 8
-        + 1880
+        + 16
+        + 1864
 );
 const_assert_eq!(size_of::<PerpMarket>(), 2808);
 const_assert_eq!(size_of::<PerpMarket>() % 8, 0);
@@ -495,7 +502,8 @@ impl PerpMarket {
             init_overall_asset_weight: I80F48::ONE,
             positive_pnl_liquidation_fee: I80F48::ZERO,
             fees_withdrawn: 0,
-            reserved: [0; 1880],
+            fees_settled_quote: I80F48::ZERO,
+            reserved: [0; 1864],
         }
     }
 }
@@ -99,13 +99,37 @@ pub struct TokenConditionalSwap {
     /// Stores a TokenConditionalSwapIntention enum value
     pub intention: u8,
 
+    pub padding: [u8; 7],
+
+    /// If nonzero, the tcs executes in slices: at most interval_max_sell native sell
+    /// tokens can be sold per interval and the next slice unlocks interval_seconds
+    /// after the current slice started.
+    pub interval_seconds: u64,
+
+    /// Maximum amount of native sell tokens to sell per interval, see interval_seconds
+    pub interval_max_sell: u64,
+
+    /// Timestamp of the first execution in the current interval
+    pub interval_start_timestamp: u64,
+
+    /// How many native sell tokens were already sold in the current interval
+    pub interval_sold: u64,
+
+    /// If nonzero, the premium is a linear auction: it starts at zero when the tcs
+    /// is first triggered while the price is in range and rises to price_premium_rate
+    /// over this many seconds. The auction restarts if the price leaves the range.
+    pub premium_auction_duration_seconds: u64,
+
+    /// Timestamp at which the premium auction started, 0 if it hasn't started
+    pub premium_auction_start_timestamp: u64,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 111],
+    pub reserved: [u8; 56],
 }
 
 const_assert_eq!(
     size_of::<TokenConditionalSwap>(),
-    8 * 6 + 8 * 3 + 2 * 4 + 2 * 2 + 1 * 5 + 111
+    8 * 6 + 8 * 3 + 2 * 4 + 2 * 2 + 1 * 5 + 7 + 8 * 4 + 8 * 2 + 56
 );
 const_assert_eq!(size_of::<TokenConditionalSwap>(), 200);
 const_assert_eq!(size_of::<TokenConditionalSwap>() % 8, 0);
@@ -131,7 +155,14 @@ impl Default for TokenConditionalSwap {
             allow_creating_deposits: 0,
             display_price_style: TokenConditionalSwapDisplayPriceStyle::SellTokenPerBuyToken.into(),
             intention: TokenConditionalSwapIntention::Unknown.into(),
-            reserved: [0; 111],
+            padding: Default::default(),
+            interval_seconds: 0,
+            interval_max_sell: 0,
+            interval_start_timestamp: 0,
+            interval_sold: 0,
+            premium_auction_duration_seconds: 0,
+            premium_auction_start_timestamp: 0,
+            reserved: [0; 56],
         }
     }
 }
@@ -168,11 +199,55 @@ impl TokenConditionalSwap {
         self.max_sell - self.sold
     }
 
-    /// Base price adjusted for the premium
+    /// Whether the premium is a linear auction, see premium_auction_duration_seconds
+    pub fn is_premium_auction(&self) -> bool {
+        self.premium_auction_duration_seconds > 0
+    }
+
+    pub fn premium_auction_started(&self) -> bool {
+        self.premium_auction_start_timestamp > 0
+    }
+
+    /// Starts the premium auction if the price is in range and resets it if it isn't
+    ///
+    /// Returns true if the auction state changed. Does nothing for tcs without auction.
+    pub fn update_premium_auction(&mut self, price: f64, now_ts: u64) -> bool {
+        if !self.is_premium_auction() {
+            return false;
+        }
+        let in_range = self.price_in_range(price);
+        if in_range && !self.premium_auction_started() {
+            self.premium_auction_start_timestamp = now_ts;
+            true
+        } else if !in_range && self.premium_auction_started() {
+            self.premium_auction_start_timestamp = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The premium rate at the given time
+    ///
+    /// For linear auctions this rises from zero to price_premium_rate over the
+    /// auction duration. Auctions that haven't started have no premium.
+    pub fn current_premium_rate(&self, now_ts: u64) -> f64 {
+        if !self.is_premium_auction() {
+            return self.price_premium_rate;
+        }
+        if !self.premium_auction_started() {
+            return 0.0;
+        }
+        let elapsed = now_ts.saturating_sub(self.premium_auction_start_timestamp);
+        let progress = (elapsed as f64 / self.premium_auction_duration_seconds as f64).min(1.0);
+        self.price_premium_rate * progress
+    }
+
+    /// Base price adjusted for the premium at the given time
     ///
     /// Base price is the amount of sell_token to pay for one buy_token.
-    pub fn premium_price(&self, base_price: f64) -> f64 {
-        base_price * (1.0 + self.price_premium_rate)
+    pub fn premium_price(&self, base_price: f64, now_ts: u64) -> f64 {
+        base_price * (1.0 + self.current_premium_rate(now_ts))
     }
 
     /// Premium price adjusted for the maker fee
@@ -197,6 +272,49 @@ impl TokenConditionalSwap {
             .to_num()
     }
 
+    /// Whether the tcs executes in time-weighted slices, see interval_seconds
+    pub fn is_twap(&self) -> bool {
+        self.interval_seconds > 0
+    }
+
+    fn interval_has_ended(&self, now_ts: u64) -> bool {
+        now_ts
+            >= self
+                .interval_start_timestamp
+                .saturating_add(self.interval_seconds)
+    }
+
+    /// The sell amount that can still be sold in the current interval
+    ///
+    /// Returns u64::MAX for tcs that don't execute in slices.
+    pub fn available_sell_in_interval(&self, now_ts: u64) -> u64 {
+        if !self.is_twap() {
+            u64::MAX
+        } else if self.interval_has_ended(now_ts) {
+            self.interval_max_sell
+        } else {
+            self.interval_max_sell.saturating_sub(self.interval_sold)
+        }
+    }
+
+    /// Timestamp at which the next slice unlocks, if the current one is exhausted
+    pub fn next_interval_start_timestamp(&self) -> u64 {
+        self.interval_start_timestamp
+            .saturating_add(self.interval_seconds)
+    }
+
+    /// Track the sold amount for the interval limit, starting a new interval if needed
+    pub fn record_interval_sell(&mut self, now_ts: u64, sell_amount: u64) {
+        if !self.is_twap() || sell_amount == 0 {
+            return;
+        }
+        if self.interval_has_ended(now_ts) {
+            self.interval_start_timestamp = now_ts;
+            self.interval_sold = 0;
+        }
+        self.interval_sold += sell_amount;
+    }
+
     pub fn price_in_range(&self, price: f64) -> bool {
         price >= self.price_lower_limit && price <= self.price_upper_limit
     }
@@ -231,3 +349,62 @@ impl TokenConditionalSwap {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_premium_auction_rate() {
+        let mut tcs = TokenConditionalSwap {
+            price_premium_rate: 0.1,
+            ..Default::default()
+        };
+        assert_eq!(tcs.current_premium_rate(1000), 0.1);
+        assert_eq!(tcs.premium_price(2.0, 1000), 2.2);
+
+        tcs.premium_auction_duration_seconds = 100;
+        assert_eq!(tcs.current_premium_rate(1000), 0.0);
+
+        tcs.premium_auction_start_timestamp = 1000;
+        assert_eq!(tcs.current_premium_rate(1000), 0.0);
+        assert!((tcs.current_premium_rate(1050) - 0.05).abs() < 1e-12);
+        assert_eq!(tcs.current_premium_rate(1100), 0.1);
+        assert_eq!(tcs.current_premium_rate(5000), 0.1);
+    }
+
+    #[test]
+    fn test_update_premium_auction() {
+        let mut tcs = TokenConditionalSwap {
+            price_lower_limit: 1.0,
+            price_upper_limit: 2.0,
+            price_premium_rate: 0.1,
+            ..Default::default()
+        };
+
+        // no auction: nothing changes
+        assert!(!tcs.update_premium_auction(1.5, 1000));
+        assert!(!tcs.premium_auction_started());
+
+        tcs.premium_auction_duration_seconds = 100;
+
+        // out of range: stays unstarted
+        assert!(!tcs.update_premium_auction(3.0, 1000));
+        assert!(!tcs.premium_auction_started());
+
+        // first in-range update starts it, later ones keep the start time
+        assert!(tcs.update_premium_auction(1.5, 1010));
+        assert_eq!(tcs.premium_auction_start_timestamp, 1010);
+        assert!(!tcs.update_premium_auction(1.2, 1050));
+        assert_eq!(tcs.premium_auction_start_timestamp, 1010);
+        assert!((tcs.current_premium_rate(1060) - 0.05).abs() < 1e-12);
+
+        // leaving the range resets it, coming back restarts from zero premium
+        assert!(tcs.update_premium_auction(0.5, 1070));
+        assert!(!tcs.premium_auction_started());
+        assert_eq!(tcs.current_premium_rate(1080), 0.0);
+        assert!(tcs.update_premium_auction(1.5, 1200));
+        assert_eq!(tcs.premium_auction_start_timestamp, 1200);
+        assert_eq!(tcs.current_premium_rate(1200), 0.0);
+    }
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::state::*;
+
+/// An insurance fund for a single token.
+///
+/// Bankruptcies in the token, or in perp markets that settle in the token, are
+/// covered by this fund before the group insurance fund is used and before any
+/// remaining loss gets socialized.
+///
+/// The vault is owned by the group and holds tokens of the fund's mint.
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct TokenInsuranceFund {
+    // ABI: Clients rely on this being at offset 8
+    pub group: Pubkey,
+    // ABI: Clients rely on this being at offset 40
+    pub token_index: TokenIndex,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub padding: [u8; 4],
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+
+    /// Total native tokens paid out of the fund to cover bankruptcies
+    pub total_used_native: u64,
+
+    pub reserved: [u8; 128],
+}
+const_assert_eq!(
+    size_of::<TokenInsuranceFund>(),
+    32 + 2 + 1 + 1 + 4 + 2 * 32 + 8 + 128
+);
+const_assert_eq!(size_of::<TokenInsuranceFund>(), 240);
+const_assert_eq!(size_of::<TokenInsuranceFund>() % 8, 0);
+
+/// The token insurance fund accounts that bankruptcy instructions accept as an
+/// optional prefix of their remaining accounts.
+pub struct TokenInsuranceFundAccounts<'a, 'info> {
+    pub fund: &'a AccountInfo<'info>,
+    pub vault: Account<'info, TokenAccount>,
+}
+
+impl<'a, 'info> TokenInsuranceFundAccounts<'a, 'info> {
+    /// If the remaining accounts start with a TokenInsuranceFund for `token_index`, returns
+    /// it together with its vault, as well as the rest of the remaining accounts.
+    ///
+    /// Callers that don't pass a fund get None and the unchanged remaining accounts.
+    pub fn from_remaining_accounts(
+        ais: &'a [AccountInfo<'info>],
+        group: &Pubkey,
+        token_index: TokenIndex,
+    ) -> Result<(Option<Self>, &'a [AccountInfo<'info>])> {
+        let is_fund = ais
+            .first()
+            .map(|ai| ai.load::<TokenInsuranceFund>().is_ok())
+            .unwrap_or(false);
+        if !is_fund {
+            return Ok((None, ais));
+        }
+        require_msg!(
+            ais.len() >= 2,
+            "token insurance fund must be followed by its vault"
+        );
+
+        let fund_ai = &ais[0];
+        let vault_ai = &ais[1];
+        {
+            let fund = fund_ai.load::<TokenInsuranceFund>()?;
+            require_keys_eq!(fund.group, *group);
+            require_msg_typed!(
+                fund.token_index == token_index,
+                MangoError::InvalidBank,
+                "token insurance fund is for token {}, expected {}",
+                fund.token_index,
+                token_index
+            );
+            require_keys_eq!(fund.vault, vault_ai.key());
+        }
+        let vault = Account::<TokenAccount>::try_from(vault_ai)?;
+
+        Ok((
+            Some(Self {
+                fund: fund_ai,
+                vault,
+            }),
+            &ais[2..],
+        ))
+    }
+
+    /// Checks that a fund was passed if and only if the bank has one.
+    pub fn verify_for_bank(fund_opt: Option<&Self>, bank: &Bank) -> Result<()> {
+        match fund_opt {
+            Some(fund) => require_keys_eq!(fund.fund.key(), bank.token_insurance_fund),
+            None => require_msg!(
+                !bank.has_token_insurance_fund(),
+                "the token insurance fund {} for token {} must be passed",
+                bank.token_insurance_fund,
+                bank.token_index
+            ),
+        }
+        Ok(())
+    }
+
+    /// Records a payout from the fund. The token transfer happens separately.
+    pub fn record_use(&self, amount: u64) -> Result<()> {
+        let mut fund = self.fund.load_mut::<TokenInsuranceFund>()?;
+        fund.total_used_native = fund.total_used_native.saturating_add(amount);
+        Ok(())
+    }
+}
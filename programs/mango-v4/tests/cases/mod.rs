@@ -28,7 +28,10 @@ mod test_liq_perps_force_cancel;
 mod test_liq_perps_positive_pnl;
 mod test_liq_tokens;
 mod test_margin_trade;
+mod test_openbook_v2;
 mod test_perp;
+mod test_perp_conditional_order;
+mod test_perp_isolated_margin;
 mod test_perp_settle;
 mod test_perp_settle_fees;
 mod test_position_lifetime;
@@ -37,3 +40,4 @@ mod test_serum;
 mod test_stale_oracles;
 mod test_token_conditional_swap;
 mod test_token_update_index_and_rate;
+mod test_twap_oracle;
@@ -608,3 +608,196 @@ async fn test_bankrupt_tokens_insurance_fund() -> Result<(), TransportError> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_bankrupt_tokens_token_insurance_fund() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(85_000); // TokenLiqWithToken needs 84k
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..2];
+
+    //
+    // SETUP: Create a group and an account to fill the vaults
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let borrow_token = &tokens[0];
+    let collateral_token = &tokens[1];
+
+    let vault_amount = 100000;
+    let vault_account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        mints,
+        vault_amount,
+        1,
+    )
+    .await;
+
+    //
+    // SETUP: Create the token insurance fund for the borrowed token
+    //
+    let fund_accounts = send_tx(
+        solana,
+        TokenInsuranceFundCreateInstruction {
+            group,
+            admin,
+            mint: borrow_token.mint.pubkey,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+    let insurance_fund = fund_accounts.insurance_fund;
+    let fund_vault = fund_accounts.vault;
+
+    let bank: Bank = solana.get_account(borrow_token.bank).await;
+    assert_eq!(bank.token_insurance_fund, insurance_fund);
+
+    send_tx(
+        solana,
+        TokenInsuranceFundDepositInstruction {
+            amount: 150,
+            group,
+            admin,
+            insurance_fund,
+            token_account: payer_mint_accounts[0],
+            token_authority: payer,
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        TokenInsuranceFundWithdrawInstruction {
+            amount: 50,
+            group,
+            admin,
+            insurance_fund,
+            destination: payer_mint_accounts[0],
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(solana.token_account_balance(fund_vault).await, 100);
+
+    //
+    // SETUP: Make an account with some collateral and some borrows
+    //
+    let account = send_tx(
+        solana,
+        AccountCreateInstruction {
+            account_num: 0,
+            group,
+            owner,
+            payer,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+    .account;
+
+    send_tx(
+        solana,
+        TokenDepositInstruction {
+            amount: 1020,
+            reduce_only: false,
+            account,
+            owner,
+            token_account: payer_mint_accounts[1],
+            token_authority: payer.clone(),
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 350,
+            allow_borrow: true,
+            account,
+            owner,
+            token_account: payer_mint_accounts[0],
+            bank_index: 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // SETUP: Change the oracle to make health go very negative and eat all collateral
+    //
+    set_bank_stub_oracle_price(solana, group, borrow_token, admin, 20.0).await;
+
+    send_tx(
+        solana,
+        TokenLiqWithTokenInstruction {
+            liqee: account,
+            liqor: vault_account,
+            liqor_owner: owner,
+            asset_token_index: collateral_token.index,
+            asset_bank_index: 1,
+            liab_token_index: borrow_token.index,
+            liab_bank_index: 1,
+            max_liab_transfer: I80F48::from_num(100000.0),
+        },
+    )
+    .await
+    .unwrap();
+    assert!(account_position_closed(solana, account, collateral_token.bank).await);
+    let borrow_after_liq = -350.0f64 + (1020.0 / 20.0 / 1.02);
+    assert_eq!(
+        account_position(solana, account, borrow_token.bank).await,
+        borrow_after_liq.round() as i64
+    );
+
+    //
+    // TEST: the token insurance fund pays first, the rest is socialized
+    //
+    let liab_vault_before = solana.token_account_balance(borrow_token.vault).await;
+    let vault_before = account_position(solana, vault_account, borrow_token.bank).await;
+    send_tx(
+        solana,
+        TokenLiqBankruptcyInstruction {
+            liqee: account,
+            liqor: vault_account,
+            liqor_owner: owner,
+            liab_mint_info: borrow_token.mint_info,
+            max_liab_transfer: I80F48::from_num(100000.0),
+        },
+    )
+    .await
+    .unwrap();
+    assert!(account_position_closed(solana, account, borrow_token.bank).await);
+    assert_eq!(solana.token_account_balance(fund_vault).await, 0);
+    assert_eq!(
+        solana.token_account_balance(borrow_token.vault).await,
+        liab_vault_before + 100
+    );
+    assert_eq!(
+        account_position(solana, vault_account, borrow_token.bank).await,
+        vault_before + (borrow_after_liq.round() as i64) + 100
+    );
+    let fund: TokenInsuranceFund = solana.get_account(insurance_fund).await;
+    assert_eq!(fund.total_used_native, 100);
+
+    Ok(())
+}
@@ -0,0 +1,533 @@
+#![allow(dead_code)]
+use super::*;
+
+use openbook_v2::state::{PlaceOrderType, SelfTradeBehavior, Side};
+use std::sync::Arc;
+
+struct OpenbookV2OrderPlacer {
+    solana: Arc<SolanaCookie>,
+    openbook_v2: Arc<OpenbookV2Cookie>,
+    account: Pubkey,
+    owner: TestKeypair,
+    openbook_v2_market: Pubkey,
+    open_orders: Pubkey,
+    next_client_order_id: u64,
+}
+
+impl OpenbookV2OrderPlacer {
+    fn inc_client_order_id(&mut self) -> u64 {
+        let id = self.next_client_order_id;
+        self.next_client_order_id += 1;
+        id
+    }
+
+    async fn find_order_id_for_client_order_id(&self, client_order_id: u64) -> Option<u128> {
+        let open_orders = self.openbook_v2.load_open_orders(self.open_orders).await;
+        open_orders
+            .find_order_with_client_order_id(client_order_id)
+            .map(|order| order.id)
+    }
+
+    async fn try_place(
+        &mut self,
+        side: Side,
+        limit_price: f64,
+        max_base: u64,
+    ) -> Result<mango_v4::accounts::OpenbookV2PlaceOrder, TransportError> {
+        let client_order_id = self.inc_client_order_id();
+        send_tx(
+            &self.solana,
+            OpenbookV2PlaceOrderInstruction {
+                side,
+                limit_price: (limit_price * 100.0 / 10.0) as u64, // in quote_lot (10) per base lot (100)
+                max_base_qty: max_base / 100,                     // in base lot (100)
+                max_native_quote_qty_including_fees: (limit_price * (max_base as f64)).ceil()
+                    as u64,
+                self_trade_behavior: SelfTradeBehavior::AbortTransaction,
+                order_type: PlaceOrderType::Limit,
+                client_order_id,
+                limit: 10,
+                account: self.account,
+                owner: self.owner,
+                openbook_v2_market: self.openbook_v2_market,
+            },
+        )
+        .await
+    }
+
+    async fn bid(&mut self, limit_price: f64, max_base: u64) -> Option<u128> {
+        self.try_place(Side::Bid, limit_price, max_base)
+            .await
+            .unwrap();
+        self.find_order_id_for_client_order_id(self.next_client_order_id - 1)
+            .await
+    }
+
+    async fn ask(&mut self, limit_price: f64, max_base: u64) -> Option<u128> {
+        self.try_place(Side::Ask, limit_price, max_base)
+            .await
+            .unwrap();
+        self.find_order_id_for_client_order_id(self.next_client_order_id - 1)
+            .await
+    }
+
+    async fn cancel(&self, side: Side, order_id: u128) {
+        send_tx(
+            &self.solana,
+            OpenbookV2CancelOrderInstruction {
+                side,
+                order_id,
+                account: self.account,
+                owner: self.owner,
+                openbook_v2_market: self.openbook_v2_market,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn settle(&self) {
+        send_tx(
+            &self.solana,
+            OpenbookV2SettleFundsInstruction {
+                account: self.account,
+                owner: self.owner,
+                openbook_v2_market: self.openbook_v2_market,
+                fees_to_dao: true,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn mango_openbook_v2_orders(&self) -> OpenbookV2Orders {
+        let account_data = get_mango_account(&self.solana, self.account).await;
+        let orders = account_data
+            .active_openbook_v2_orders()
+            .find(|s| s.open_orders == self.open_orders)
+            .unwrap();
+        orders.clone()
+    }
+}
+
+async fn expand_for_openbook_v2(
+    solana: &SolanaCookie,
+    group: Pubkey,
+    owner: TestKeypair,
+    payer: TestKeypair,
+    account_num: u32,
+) {
+    send_tx(
+        solana,
+        AccountExpandInstruction {
+            account_num,
+            token_count: 8,
+            serum3_count: 4,
+            perp_count: 4,
+            perp_oo_count: 16,
+            token_conditional_swap_count: 1,
+            openbook_v2_count: 2,
+            group,
+            owner,
+            payer,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_openbook_v2_basics() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(200_000);
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group and an account
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let base_token = &tokens[0];
+    let quote_token = &tokens[1];
+
+    //
+    // SETUP: Create openbook v2 market
+    //
+    let openbook_v2_market_cookie = context
+        .openbook_v2
+        .list_spot_market(&base_token.mint, &quote_token.mint)
+        .await;
+
+    //
+    // TEST: Register an openbook v2 market
+    //
+    let openbook_v2_market = send_tx(
+        solana,
+        OpenbookV2RegisterMarketInstruction {
+            group,
+            admin,
+            openbook_v2_program: context.openbook_v2.program_id,
+            openbook_v2_market_external: openbook_v2_market_cookie.market,
+            market_index: 0,
+            base_bank: base_token.bank,
+            quote_bank: quote_token.bank,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .openbook_v2_market;
+
+    //
+    // SETUP: Create account
+    //
+    let deposit_amount = 1000;
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    expand_for_openbook_v2(solana, group, owner, payer, 0).await;
+
+    //
+    // TEST: Create an open orders account
+    //
+    let open_orders = send_tx(
+        solana,
+        OpenbookV2CreateOpenOrdersInstruction {
+            account,
+            openbook_v2_market,
+            account_num: 0,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .open_orders;
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(
+        account_data
+            .active_openbook_v2_orders()
+            .map(|v| (v.open_orders, v.market_index))
+            .collect::<Vec<_>>(),
+        [(open_orders, 0)]
+    );
+
+    let mut order_placer = OpenbookV2OrderPlacer {
+        solana: solana.clone(),
+        openbook_v2: context.openbook_v2.clone(),
+        account,
+        owner,
+        openbook_v2_market,
+        open_orders,
+        next_client_order_id: 0,
+    };
+
+    //
+    // TEST: An order size that overflows the native base amount is rejected
+    //
+    let result = send_tx(
+        solana,
+        OpenbookV2PlaceOrderInstruction {
+            side: Side::Ask,
+            limit_price: 10,
+            max_base_qty: u64::MAX,
+            max_native_quote_qty_including_fees: 1000,
+            self_trade_behavior: SelfTradeBehavior::AbortTransaction,
+            order_type: PlaceOrderType::Limit,
+            client_order_id: 0,
+            limit: 10,
+            account,
+            owner,
+            openbook_v2_market,
+        },
+    )
+    .await;
+    assert_mango_error(
+        &result,
+        MangoError::MathError.into(),
+        "max base qty overflows".into(),
+    );
+
+    //
+    // TEST: Place an order
+    //
+    let order_id = order_placer.bid(1.0, 100).await.unwrap();
+    check_prev_instruction_post_health(&solana, account).await;
+
+    let native0 = account_position(solana, account, base_token.bank).await;
+    let native1 = account_position(solana, account, quote_token.bank).await;
+    assert_eq!(native0, 1000);
+    assert_eq!(native1, 900);
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(0)
+            .unwrap()
+            .in_use_count,
+        1
+    );
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(1)
+            .unwrap()
+            .in_use_count,
+        1
+    );
+    let openbook_v2_orders = order_placer.mango_openbook_v2_orders().await;
+    assert_eq!(openbook_v2_orders.base_borrows_without_fee, 0);
+    assert_eq!(openbook_v2_orders.quote_borrows_without_fee, 0);
+
+    assert!(order_id != 0);
+
+    //
+    // TEST: Cancel the order
+    //
+    order_placer.cancel(Side::Bid, order_id).await;
+    assert!(order_placer
+        .find_order_id_for_client_order_id(order_placer.next_client_order_id - 1)
+        .await
+        .is_none());
+
+    //
+    // TEST: Settle, moving the freed up funds back
+    //
+    order_placer.settle().await;
+
+    let native0 = account_position(solana, account, base_token.bank).await;
+    let native1 = account_position(solana, account, quote_token.bank).await;
+    assert_eq!(native0, 1000);
+    assert_eq!(native1, 1000);
+
+    //
+    // TEST: Close the open orders account
+    //
+    send_tx(
+        solana,
+        OpenbookV2CloseOpenOrdersInstruction {
+            account,
+            openbook_v2_market,
+            owner,
+            sol_destination: payer.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(account_data.active_openbook_v2_orders().count(), 0);
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(0)
+            .unwrap()
+            .in_use_count,
+        0
+    );
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(1)
+            .unwrap()
+            .in_use_count,
+        0
+    );
+
+    //
+    // TEST: Deregister the openbook v2 market
+    //
+    send_tx(
+        solana,
+        OpenbookV2DeregisterMarketInstruction {
+            group,
+            admin,
+            openbook_v2_market_external: openbook_v2_market_cookie.market,
+            sol_destination: payer.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_openbook_v2_liq_force_cancel() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(200_000);
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..2];
+
+    //
+    // SETUP: Create a group and an account to fill the vaults
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let base_token = &tokens[0];
+    let quote_token = &tokens[1];
+
+    // deposit some funds, to the vaults aren't empty
+    create_funded_account(&solana, group, owner, 0, &context.users[1], mints, 10000, 0).await;
+
+    //
+    // SETUP: Create openbook v2 market
+    //
+    let openbook_v2_market_cookie = context
+        .openbook_v2
+        .list_spot_market(&base_token.mint, &quote_token.mint)
+        .await;
+
+    let openbook_v2_market = send_tx(
+        solana,
+        OpenbookV2RegisterMarketInstruction {
+            group,
+            admin,
+            openbook_v2_program: context.openbook_v2.program_id,
+            openbook_v2_market_external: openbook_v2_market_cookie.market,
+            market_index: 0,
+            base_bank: base_token.bank,
+            quote_bank: quote_token.bank,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .openbook_v2_market;
+
+    //
+    // SETUP: Make an account and deposit some quote
+    //
+    let deposit_amount = 1000;
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        &mints[1..2],
+        deposit_amount,
+        0,
+    )
+    .await;
+    expand_for_openbook_v2(solana, group, owner, payer, 1).await;
+
+    //
+    // SETUP: Create an open orders account and an order
+    //
+    let open_orders = send_tx(
+        solana,
+        OpenbookV2CreateOpenOrdersInstruction {
+            account,
+            openbook_v2_market,
+            account_num: 1,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .open_orders;
+
+    let mut order_placer = OpenbookV2OrderPlacer {
+        solana: solana.clone(),
+        openbook_v2: context.openbook_v2.clone(),
+        account,
+        owner,
+        openbook_v2_market,
+        open_orders,
+        next_client_order_id: 0,
+    };
+
+    // short some base
+    order_placer.ask(1.0, 500).await.unwrap();
+
+    //
+    // TEST: Change the oracle to make health go negative
+    //
+    set_bank_stub_oracle_price(solana, group, base_token, admin, 10.0).await;
+
+    // can't withdraw
+    assert!(send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 1,
+            allow_borrow: false,
+            account,
+            owner,
+            token_account: payer_mint_accounts[1],
+            bank_index: 0,
+        }
+    )
+    .await
+    .is_err());
+
+    //
+    // TEST: force cancel orders, making the account healthy again
+    //
+    send_tx(
+        solana,
+        OpenbookV2LiqForceCancelOrdersInstruction {
+            account,
+            openbook_v2_market,
+            limit: 10,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert!(order_placer
+        .find_order_id_for_client_order_id(0)
+        .await
+        .is_none());
+
+    // can withdraw again
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 2,
+            allow_borrow: false,
+            account,
+            owner,
+            token_account: payer_mint_accounts[1],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    Ok(())
+}
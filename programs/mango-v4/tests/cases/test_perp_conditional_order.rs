@@ -0,0 +1,611 @@
+use super::*;
+
+#[tokio::test]
+async fn test_perp_conditional_order() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, accounts and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let deposit_amount = 1000;
+    let liqee = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let liqor = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+
+    send_tx(
+        solana,
+        AccountExpandInstruction {
+            account_num: 0,
+            token_count: 8,
+            serum3_count: 4,
+            perp_count: 4,
+            perp_oo_count: 16,
+            token_conditional_swap_count: 1,
+            perp_conditional_order_count: 2,
+            group,
+            owner,
+            payer,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let mango_v4::accounts::PerpCreateMarket { perp_market, .. } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[0]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let create_order = PerpConditionalOrderCreateInstruction {
+        account: liqee,
+        owner,
+        perp_market,
+        side: Side::Bid,
+        max_base_lots: 2,
+        expiry_timestamp: u64::MAX,
+        price_lower_limit: 0.0,
+        price_upper_limit: 0.9,
+        price_premium_rate: 0.01,
+        reduce_only: false,
+    };
+
+    //
+    // TEST: Create and cancel a conditional order
+    //
+    send_tx(solana, create_order.clone()).await.unwrap();
+
+    let account_data = get_mango_account(solana, liqee).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 1);
+    let order = account_data.perp_conditional_order_by_index(0).unwrap();
+    assert_eq!(order.id, 0);
+    assert_eq!(order.perp_market_index, 0);
+    assert_eq!(order.side(), Side::Bid);
+
+    send_tx(
+        solana,
+        PerpConditionalOrderCancelInstruction {
+            account: liqee,
+            owner,
+            index: 0,
+            id: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, liqee).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 0);
+
+    //
+    // TEST: The order can't be triggered while the oracle is outside the price range
+    //
+    send_tx(solana, create_order.clone()).await.unwrap();
+
+    let result = send_tx(
+        solana,
+        PerpConditionalOrderTriggerInstruction {
+            liqee,
+            liqor,
+            liqor_owner: owner,
+            index: 0,
+            max_base_lots: i64::MAX,
+        },
+    )
+    .await;
+    assert_mango_error(
+        &result,
+        MangoError::PerpConditionalOrderPriceNotInRange.into(),
+        "oracle is above the upper limit".into(),
+    );
+
+    //
+    // TEST: Partial execution at the premium price
+    //
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[0], admin, 0.8).await;
+
+    send_tx(
+        solana,
+        PerpConditionalOrderTriggerInstruction {
+            liqee,
+            liqor,
+            liqor_owner: owner,
+            index: 0,
+            max_base_lots: 1,
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, liqee).await;
+
+    let account_data = get_mango_account(solana, liqee).await;
+    let order = account_data.perp_conditional_order_by_index(0).unwrap();
+    assert!(order.has_data());
+    assert_eq!(order.executed_base_lots, 1);
+    let perp_position = account_data.perp_position(0).unwrap();
+    assert_eq!(perp_position.base_position_lots(), 1);
+    assert!(assert_equal(
+        perp_position.quote_position_native(),
+        -80.0 * 1.01,
+        0.001
+    ));
+
+    let liqor_data = get_mango_account(solana, liqor).await;
+    let perp_position = liqor_data.perp_position(0).unwrap();
+    assert_eq!(perp_position.base_position_lots(), -1);
+    assert!(assert_equal(
+        perp_position.quote_position_native(),
+        80.0 * 1.01,
+        0.001
+    ));
+
+    //
+    // TEST: Full execution removes the order
+    //
+    send_tx(
+        solana,
+        PerpConditionalOrderTriggerInstruction {
+            liqee,
+            liqor,
+            liqor_owner: owner,
+            index: 0,
+            max_base_lots: i64::MAX,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, liqee).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 0);
+    assert_eq!(
+        account_data.perp_position(0).unwrap().base_position_lots(),
+        2
+    );
+    let liqor_data = get_mango_account(solana, liqor).await;
+    assert_eq!(
+        liqor_data.perp_position(0).unwrap().base_position_lots(),
+        -2
+    );
+
+    //
+    // TEST: Reduce-only orders are limited by the position and expired orders get removed
+    //
+    let now_ts = solana.get_clock().await.unix_timestamp as u64;
+    send_tx(
+        solana,
+        PerpConditionalOrderCreateInstruction {
+            side: Side::Ask,
+            max_base_lots: 5,
+            price_upper_limit: 1.0,
+            reduce_only: true,
+            expiry_timestamp: now_ts + 10,
+            ..create_order.clone()
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        PerpConditionalOrderTriggerInstruction {
+            liqee,
+            liqor,
+            liqor_owner: owner,
+            index: 0,
+            max_base_lots: 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, liqee).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 1);
+    assert_eq!(
+        account_data.perp_position(0).unwrap().base_position_lots(),
+        1
+    );
+
+    solana.advance_clock_to(now_ts as i64 + 11).await;
+
+    send_tx(
+        solana,
+        PerpConditionalOrderTriggerInstruction {
+            liqee,
+            liqor,
+            liqor_owner: owner,
+            index: 0,
+            max_base_lots: i64::MAX,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, liqee).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 0);
+    assert_eq!(
+        account_data.perp_position(0).unwrap().base_position_lots(),
+        1
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_perp_conditional_order_place_order() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, accounts and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let deposit_amount = 1000;
+    let account_0 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let account_1 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let triggerer =
+        create_funded_account(&solana, group, owner, 2, &context.users[1], &[], 0, 0).await;
+
+    send_tx(
+        solana,
+        AccountExpandInstruction {
+            account_num: 0,
+            token_count: 8,
+            serum3_count: 4,
+            perp_count: 4,
+            perp_oo_count: 16,
+            token_conditional_swap_count: 1,
+            perp_conditional_order_count: 2,
+            group,
+            owner,
+            payer,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let mango_v4::accounts::PerpCreateMarket { perp_market, .. } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[0]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Create and cancel a stop-loss order
+    //
+    send_tx(
+        solana,
+        PerpConditionalOrderCreatePlaceOrderInstruction {
+            account: account_0,
+            owner,
+            perp_market,
+            side: Side::Ask,
+            price_upper_limit: 0.8,
+            max_base_lots: 1,
+            ..PerpConditionalOrderCreatePlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 1);
+    let order = account_data.perp_conditional_order_by_index(0).unwrap();
+    assert_eq!(order.id, 0);
+    assert_eq!(order.perp_market_index, 0);
+    assert_eq!(order.execution(), PerpConditionalOrderExecution::PlaceOrder);
+    assert_eq!(order.price_upper_limit, 0.8);
+
+    send_tx(
+        solana,
+        PerpConditionalOrderCancelInstruction {
+            account: account_0,
+            owner,
+            index: 0,
+            id: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 0);
+
+    //
+    // TEST: A stop-market bid can't be triggered before the oracle is in range
+    //
+    send_tx(
+        solana,
+        PerpConditionalOrderCreatePlaceOrderInstruction {
+            account: account_0,
+            owner,
+            perp_market,
+            side: Side::Bid,
+            price_lower_limit: 1.15,
+            max_base_lots: 1,
+            trigger_premium: 5,
+            ..PerpConditionalOrderCreatePlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let result = send_tx(
+        solana,
+        PerpConditionalOrderTriggerPlaceOrderInstruction {
+            account: account_0,
+            triggerer,
+            triggerer_owner: owner,
+            index: 0,
+        },
+    )
+    .await;
+    assert_mango_error(
+        &result,
+        MangoError::PerpConditionalOrderPriceNotInRange.into(),
+        "oracle is below the price limit".into(),
+    );
+
+    //
+    // TEST: Once the oracle is in range, the order is placed and the triggerer gets the premium
+    //
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[0], admin, 1.2).await;
+
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_1,
+            perp_market,
+            owner,
+            side: Side::Ask,
+            price_lots: 12,
+            max_base_lots: 1,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        PerpConditionalOrderTriggerPlaceOrderInstruction {
+            account: account_0,
+            triggerer,
+            triggerer_owner: owner,
+            index: 0,
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    send_tx(
+        solana,
+        PerpConsumeEventsInstruction {
+            perp_market,
+            mango_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 0);
+    let perp_position = account_data.perp_position(0).unwrap();
+    assert_eq!(perp_position.base_position_lots(), 1);
+    assert!(assert_equal(
+        perp_position.quote_position_native(),
+        -120.0 - 5.0,
+        0.001
+    ));
+    assert!(assert_equal(
+        perp_position.realized_other_pnl_native,
+        -5.0,
+        0.001
+    ));
+
+    let triggerer_data = get_mango_account(solana, triggerer).await;
+    let perp_position = triggerer_data.perp_position(0).unwrap();
+    assert_eq!(perp_position.base_position_lots(), 0);
+    assert!(assert_equal(
+        perp_position.quote_position_native(),
+        5.0,
+        0.001
+    ));
+
+    //
+    // TEST: A reduce-only order with nothing to reduce is removed without paying the premium
+    //
+    send_tx(
+        solana,
+        PerpConditionalOrderCreatePlaceOrderInstruction {
+            account: account_0,
+            owner,
+            perp_market,
+            side: Side::Bid,
+            max_base_lots: 1,
+            reduce_only: true,
+            trigger_premium: 5,
+            ..PerpConditionalOrderCreatePlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(
+        solana,
+        PerpConditionalOrderTriggerPlaceOrderInstruction {
+            account: account_0,
+            triggerer,
+            triggerer_owner: owner,
+            index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 0);
+    let perp_position = account_data.perp_position(0).unwrap();
+    assert_eq!(perp_position.base_position_lots(), 1);
+    assert_eq!(perp_position.bids_base_lots, 0);
+    assert!(assert_equal(
+        perp_position.quote_position_native(),
+        -120.0 - 5.0,
+        0.001
+    ));
+
+    let triggerer_data = get_mango_account(solana, triggerer).await;
+    assert!(assert_equal(
+        triggerer_data
+            .perp_position(0)
+            .unwrap()
+            .quote_position_native(),
+        5.0,
+        0.001
+    ));
+
+    //
+    // TEST: Expired conditional orders get removed instead of placed
+    //
+    let now_ts = solana.get_clock().await.unix_timestamp as u64;
+    send_tx(
+        solana,
+        PerpConditionalOrderCreatePlaceOrderInstruction {
+            account: account_0,
+            owner,
+            perp_market,
+            side: Side::Ask,
+            max_base_lots: 1,
+            trigger_premium: 5,
+            expiry_timestamp: now_ts + 2,
+            ..PerpConditionalOrderCreatePlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    solana.advance_clock_to(now_ts as i64 + 3).await;
+
+    send_tx(
+        solana,
+        PerpConditionalOrderTriggerPlaceOrderInstruction {
+            account: account_0,
+            triggerer,
+            triggerer_owner: owner,
+            index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_conditional_orders().count(), 0);
+    assert_eq!(
+        account_data.perp_position(0).unwrap().base_position_lots(),
+        1
+    );
+
+    Ok(())
+}
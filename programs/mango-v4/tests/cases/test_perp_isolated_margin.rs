@@ -0,0 +1,382 @@
+use super::*;
+
+#[tokio::test]
+async fn test_perp_isolated_margin() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, accounts and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        zero_token_is_quote: true,
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let settle_bank = tokens[0].bank;
+
+    let deposit_amount = 1000;
+    let account_0 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let account_1 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let liqor = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+
+    let mango_v4::accounts::PerpCreateMarket { perp_market, .. } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            taker_fee: 0.01,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[1]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Moving collateral into a new perp position makes it isolated
+    //
+    let bank_deposits_before = solana
+        .get_account::<Bank>(settle_bank)
+        .await
+        .native_deposits();
+    send_tx(
+        solana,
+        PerpIsolatedMarginTransferInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            amount: 100,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    let pp = account_data.perp_position(0).unwrap();
+    let bank_data = solana.get_account::<Bank>(settle_bank).await;
+    assert!(pp.is_isolated());
+    assert_eq!(bank_data.isolated_collateral_native(pp), 100);
+    assert_eq!(
+        account_position(solana, account_0, settle_bank).await,
+        deposit_amount as i64 - 100
+    );
+    // the collateral is still part of the bank's deposits
+    assert!(assert_equal(
+        bank_data.native_deposits(),
+        bank_deposits_before.to_num::<f64>(),
+        0.1
+    ));
+
+    //
+    // TEST: Can't withdraw more than the isolated collateral
+    //
+    let result = send_tx(
+        solana,
+        PerpIsolatedMarginTransferInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            amount: -101,
+        },
+    )
+    .await;
+    assert!(result.is_err());
+
+    //
+    // TEST: The isolated collateral limits the position size, even though the
+    // rest of the account could support it
+    //
+    let result = send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots: 10,
+            max_base_lots: 30,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await;
+    assert_mango_error(
+        &result,
+        MangoError::IsolatedPerpHealthMustBePositiveOrIncrease.into(),
+        "isolated health too low".to_string(),
+    );
+
+    //
+    // SETUP: Open a position that is backed by the isolated collateral
+    //
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots: 10,
+            max_base_lots: 10,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_1,
+            perp_market,
+            owner,
+            side: Side::Ask,
+            price_lots: 10,
+            max_base_lots: 10,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        PerpConsumeEventsInstruction {
+            perp_market,
+            mango_accounts: vec![account_0, account_1],
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(
+        account_data.perp_position(0).unwrap().base_position_lots(),
+        10
+    );
+
+    //
+    // TEST: A healthy isolated position can't be liquidated
+    //
+    let result = send_tx(
+        solana,
+        PerpLiqIsolatedPositionInstruction {
+            liqor,
+            liqor_owner: owner,
+            liqee: account_0,
+            perp_market,
+            max_base_transfer: i64::MAX,
+        },
+    )
+    .await;
+    assert_mango_error(
+        &result,
+        MangoError::IsolatedPerpPositionNotLiquidatable.into(),
+        "isolated position is healthy".to_string(),
+    );
+
+    //
+    // TEST: Fees are settled out of the isolated collateral, not the account's deposits
+    //
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[1], admin, 0.98).await;
+
+    send_tx(
+        solana,
+        PerpSettleFeesInstruction {
+            account: account_0,
+            perp_market,
+            max_settle_amount: u64::MAX,
+        },
+    )
+    .await
+    .unwrap();
+
+    // account_1's taker fee was 10
+    let account_data = get_mango_account(solana, account_0).await;
+    let pp = account_data.perp_position(0).unwrap();
+    let collateral = solana
+        .get_account::<Bank>(settle_bank)
+        .await
+        .isolated_collateral_native(pp);
+    assert!(assert_equal(collateral, 90.0, 0.001));
+    assert_eq!(
+        account_position(solana, account_0, settle_bank).await,
+        deposit_amount as i64 - 100
+    );
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    assert!(assert_equal(perp_market_data.fees_accrued, 0.0, 0.001));
+
+    //
+    // TEST: Losses of the isolated position are settled out of its collateral
+    //
+    send_tx(
+        solana,
+        PerpSettlePnlInstruction {
+            settler: liqor,
+            settler_owner: owner,
+            account_a: account_1,
+            account_b: account_0,
+            perp_market,
+        },
+    )
+    .await
+    .unwrap();
+
+    // the remaining pnl at 0.98 was 980 - 1000 + 10
+    let account_data = get_mango_account(solana, account_0).await;
+    let pp = account_data.perp_position(0).unwrap();
+    let collateral = solana
+        .get_account::<Bank>(settle_bank)
+        .await
+        .isolated_collateral_native(pp);
+    assert!(assert_equal(collateral, 80.0, 0.001));
+    assert_eq!(
+        account_position(solana, account_0, settle_bank).await,
+        deposit_amount as i64 - 100
+    );
+
+    //
+    // TEST: When the price drops, only the isolated position is liquidated, and only
+    // as far as needed to bring its liquidation end health back to zero
+    //
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[1], admin, 0.92).await;
+
+    send_tx(
+        solana,
+        PerpLiqIsolatedPositionInstruction {
+            liqor,
+            liqor_owner: owner,
+            liqee: account_0,
+            perp_market,
+            max_base_transfer: i64::MAX,
+        },
+    )
+    .await
+    .unwrap();
+
+    // liq end balance: 80 + 1000 * 0.92 * 0.95 - 980 = -26
+    // each lot gains 100 * 0.92 * (0.988 - 0.95) = 3.496, so 8 lots are needed
+    let account_data = get_mango_account(solana, account_0).await;
+    let pp = account_data.perp_position(0).unwrap();
+    assert_eq!(pp.base_position_lots(), 2);
+    assert!(assert_equal(
+        pp.quote_position_native(),
+        -980.0 + 800.0 * 0.92 * 0.988,
+        0.01
+    ));
+    let liqor_data = get_mango_account(solana, liqor).await;
+    assert_eq!(liqor_data.perp_position(0).unwrap().base_position_lots(), 8);
+
+    let result = send_tx(
+        solana,
+        PerpLiqIsolatedPositionInstruction {
+            liqor,
+            liqor_owner: owner,
+            liqee: account_0,
+            perp_market,
+            max_base_transfer: i64::MAX,
+        },
+    )
+    .await;
+    assert_mango_error(
+        &result,
+        MangoError::IsolatedPerpPositionNotLiquidatable.into(),
+        "isolated position is healthy again".to_string(),
+    );
+
+    //
+    // TEST: Once the base position is closed, the negative pnl is taken over by the liqor
+    // in exchange for the isolated collateral. The loss beyond it is socialized instead
+    // of being taken from the account.
+    //
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[1], admin, 0.7).await;
+
+    let liqor_settle_before = account_position_f64(solana, liqor, settle_bank).await;
+    send_tx(
+        solana,
+        PerpLiqIsolatedPositionInstruction {
+            liqor,
+            liqor_owner: owner,
+            liqee: account_0,
+            perp_market,
+            max_base_transfer: i64::MAX,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    let pp = account_data.perp_position(0).unwrap();
+    let collateral = solana
+        .get_account::<Bank>(settle_bank)
+        .await
+        .isolated_collateral_native(pp);
+    assert_eq!(pp.base_position_lots(), 0);
+    assert_eq!(collateral, 0);
+    assert!(pp.quote_position_native().abs() < 0.001);
+    assert_eq!(
+        account_position(solana, account_0, settle_bank).await,
+        deposit_amount as i64 - 100
+    );
+
+    let liqor_data = get_mango_account(solana, liqor).await;
+    assert_eq!(
+        liqor_data.perp_position(0).unwrap().base_position_lots(),
+        10
+    );
+    assert!(assert_equal_f64_f64(
+        account_position_f64(solana, liqor, settle_bank).await - liqor_settle_before,
+        80.0,
+        0.01
+    ));
+
+    Ok(())
+}
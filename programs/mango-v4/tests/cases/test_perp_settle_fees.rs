@@ -425,6 +425,11 @@ async fn test_perp_settle_fees() -> Result<(), TransportError> {
             initial_fees,
             "Fees have been fully settled"
         );
+        assert_eq!(
+            perp_market.fees_settled_quote.round(),
+            initial_fees,
+            "Fees settled in quote units match the reduction of fees accrued"
+        );
     }
 
     Ok(())
@@ -118,6 +118,7 @@ async fn test_token_conditional_swap() -> Result<(), TransportError> {
             group,
             owner,
             payer,
+            ..Default::default()
         },
     )
     .await
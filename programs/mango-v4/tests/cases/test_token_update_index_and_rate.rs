@@ -97,3 +97,63 @@ async fn test_token_update_index_and_rate() -> Result<(), TransportError> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_token_update_index_and_rate_multiple_banks() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..1];
+
+    //
+    // SETUP: Create a group and deposit into both banks of the token
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    create_funded_account(&solana, group, owner, 0, &context.users[1], mints, 10000, 0).await;
+    create_funded_account(&solana, group, owner, 1, &context.users[1], mints, 3000, 1).await;
+
+    //
+    // TEST: Updating the index makes the token-wide totals visible on each bank
+    //
+
+    send_tx(
+        solana,
+        TokenUpdateIndexAndRateInstruction {
+            mint_info: tokens[0].mint_info,
+        },
+    )
+    .await
+    .unwrap();
+
+    let bank0 = solana.get_account::<Bank>(tokens[0].bank).await;
+    let bank1 = solana.get_account::<Bank>(tokens[0].bank1).await;
+
+    assert_eq!(bank0.other_banks_indexed_deposits, bank1.indexed_deposits);
+    assert_eq!(bank1.other_banks_indexed_deposits, bank0.indexed_deposits);
+    assert!(assert_equal(bank0.native_deposits(), 10000.0, 0.1));
+    assert!(assert_equal(bank1.native_deposits(), 3000.0, 0.1));
+    assert!(assert_equal(
+        bank0.native_deposits_all_banks(),
+        13000.0,
+        0.1
+    ));
+    assert!(assert_equal(
+        bank1.native_deposits_all_banks(),
+        13000.0,
+        0.1
+    ));
+
+    Ok(())
+}
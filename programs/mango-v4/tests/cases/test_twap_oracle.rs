@@ -0,0 +1,169 @@
+use super::*;
+
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::instruction::Instruction;
+
+/// Writes a minimal raydium clmm pool account, with only the fields the
+/// TwapOracle reads
+fn set_raydium_clmm_pool(
+    solana: &SolanaCookie,
+    pool: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    sqrt_price_x64: u128,
+) {
+    let mut data = vec![0u8; 1544];
+    data[0..8].copy_from_slice(&RaydiumClmmPool::DISCRIMINATOR);
+    data[73..105].copy_from_slice(token_mint_0.as_ref());
+    data[105..137].copy_from_slice(token_mint_1.as_ref());
+    data[253..269].copy_from_slice(&sqrt_price_x64.to_le_bytes());
+    let account = Account {
+        lamports: solana.rent.minimum_balance(data.len()),
+        data,
+        owner: raydium_clmm::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    solana
+        .context
+        .borrow_mut()
+        .set_account(&pool, &AccountSharedData::from(account));
+}
+
+#[tokio::test]
+async fn test_twap_oracle() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..3];
+
+    let GroupWithTokens { group, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    // pool price of 2.25 token_1 per token_0
+    let pool = Pubkey::new_unique();
+    set_raydium_clmm_pool(solana, pool, mints[0].pubkey, mints[1].pubkey, 3 << 63);
+
+    //
+    // TEST: The pool must contain the mint
+    //
+    let result = send_tx(
+        solana,
+        TwapOracleCreateInstruction {
+            group,
+            mint: mints[2].pubkey,
+            pool,
+            window_secs: 1,
+            max_sample_deviation: 0.1,
+            admin,
+            payer,
+        },
+    )
+    .await;
+    assert!(result.is_err());
+
+    //
+    // TEST: Create oracles for both tokens of the pool
+    //
+    let oracle0 = send_tx(
+        solana,
+        TwapOracleCreateInstruction {
+            group,
+            mint: mints[0].pubkey,
+            pool,
+            window_secs: 1,
+            max_sample_deviation: 0.1,
+            admin,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .oracle;
+    let oracle1 = send_tx(
+        solana,
+        TwapOracleCreateInstruction {
+            group,
+            mint: mints[1].pubkey,
+            pool,
+            window_secs: 1,
+            max_sample_deviation: 0.1,
+            admin,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .oracle;
+
+    let data0: TwapOracle = solana.get_account(oracle0).await;
+    assert_eq!(data0.group, group);
+    assert_eq!(data0.mint, mints[0].pubkey);
+    assert_eq!(data0.pool, pool);
+    assert_eq!(data0.invert, 0);
+    assert_eq!(data0.twap_price, I80F48::from_num(2.25));
+    assert_eq!(data0.last_sample_price, I80F48::from_num(2.25));
+
+    let data1: TwapOracle = solana.get_account(oracle1).await;
+    assert_eq!(data1.invert, 1);
+    assert_eq!(data1.twap_price, I80F48::ONE / I80F48::from_num(2.25));
+
+    //
+    // TEST: A large pool price change only moves the average up to the max deviation
+    //
+    // pool price of 4
+    set_raydium_clmm_pool(solana, pool, mints[0].pubkey, mints[1].pubkey, 2 << 64);
+    solana.advance_clock().await;
+
+    send_tx(solana, TwapOracleUpdateInstruction { oracle: oracle0 })
+        .await
+        .unwrap();
+
+    let data0_after: TwapOracle = solana.get_account(oracle0).await;
+    assert!(data0_after.last_update_ts > data0.last_update_ts);
+    assert!(data0_after.last_update_slot > data0.last_update_slot);
+    assert_eq!(data0_after.last_sample_price, I80F48::from(4));
+    // the full window passed, so the clamped sample replaces the average
+    assert_eq!(
+        data0_after.twap_price,
+        data0.twap_price * (I80F48::ONE + data0.max_sample_deviation)
+    );
+
+    //
+    // TEST: Updating requires the oracle's pool
+    //
+    let other_pool = Pubkey::new_unique();
+    set_raydium_clmm_pool(
+        solana,
+        other_pool,
+        mints[0].pubkey,
+        mints[1].pubkey,
+        3 << 63,
+    );
+    let instruction = Instruction {
+        program_id: mango_v4::id(),
+        accounts: anchor_lang::ToAccountMetas::to_account_metas(
+            &mango_v4::accounts::TwapOracleUpdate {
+                group,
+                oracle: oracle0,
+                pool: other_pool,
+            },
+            None,
+        ),
+        data: anchor_lang::InstructionData::data(&mango_v4::instruction::TwapOracleUpdate {}),
+    };
+    assert!(solana
+        .process_transaction(&[instruction], None)
+        .await
+        .is_err());
+
+    Ok(())
+}
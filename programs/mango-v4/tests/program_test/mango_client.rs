@@ -224,6 +224,7 @@ async fn derive_health_check_remaining_account_metas(
     }
 
     let serum_oos = account.active_serum3_orders().map(|&s| s.open_orders);
+    let openbook_oos = account.active_openbook_v2_orders().map(|&s| s.open_orders);
 
     let to_account_meta = |pubkey| AccountMeta {
         pubkey,
@@ -242,6 +243,7 @@ async fn derive_health_check_remaining_account_metas(
         .chain(perp_markets.map(to_account_meta))
         .chain(perp_oracles.into_iter().map(to_account_meta))
         .chain(serum_oos.map(to_account_meta))
+        .chain(openbook_oos.map(to_account_meta))
         .collect()
 }
 
@@ -290,6 +292,10 @@ async fn derive_liquidation_remaining_account_metas(
         .active_serum3_orders()
         .chain(liqor.active_serum3_orders())
         .map(|&s| s.open_orders);
+    let openbook_oos = liqee
+        .active_openbook_v2_orders()
+        .chain(liqor.active_openbook_v2_orders())
+        .map(|&s| s.open_orders);
 
     let to_account_meta = |pubkey| AccountMeta {
         pubkey,
@@ -308,6 +314,7 @@ async fn derive_liquidation_remaining_account_metas(
         .chain(perp_markets.into_iter().map(to_account_meta))
         .chain(perp_oracles.into_iter().map(to_account_meta))
         .chain(serum_oos.map(to_account_meta))
+        .chain(openbook_oos.map(to_account_meta))
         .collect()
 }
 
@@ -1597,6 +1604,91 @@ impl ClientInstruction for StubOracleCloseInstruction {
     }
 }
 
+pub struct TwapOracleCreateInstruction {
+    pub group: Pubkey,
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub window_secs: u64,
+    pub max_sample_deviation: f32,
+    pub admin: TestKeypair,
+    pub payer: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TwapOracleCreateInstruction {
+    type Accounts = mango_v4::accounts::TwapOracleCreate;
+    type Instruction = mango_v4::instruction::TwapOracleCreate;
+
+    async fn to_instruction(
+        &self,
+        _loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            window_secs: self.window_secs,
+            max_sample_deviation: self.max_sample_deviation,
+        };
+
+        let oracle = Pubkey::find_program_address(
+            &[
+                b"TwapOracle".as_ref(),
+                self.group.as_ref(),
+                self.mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            oracle,
+            admin: self.admin.pubkey(),
+            mint: self.mint,
+            pool: self.pool,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.payer, self.admin]
+    }
+}
+
+pub struct TwapOracleUpdateInstruction {
+    pub oracle: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TwapOracleUpdateInstruction {
+    type Accounts = mango_v4::accounts::TwapOracleUpdate;
+    type Instruction = mango_v4::instruction::TwapOracleUpdate;
+
+    async fn to_instruction(
+        &self,
+        loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {};
+
+        let oracle: TwapOracle = loader.load(&self.oracle).await.unwrap();
+
+        let accounts = Self::Accounts {
+            group: oracle.group,
+            oracle: self.oracle,
+            pool: oracle.pool,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
 pub struct GroupCreateInstruction {
     pub creator: TestKeypair,
     pub payer: TestKeypair,
@@ -1892,11 +1984,13 @@ pub struct AccountExpandInstruction {
     pub perp_count: u8,
     pub perp_oo_count: u8,
     pub token_conditional_swap_count: u8,
+    pub openbook_v2_count: u8,
+    pub perp_conditional_order_count: u8,
 }
 #[async_trait::async_trait(?Send)]
 impl ClientInstruction for AccountExpandInstruction {
     type Accounts = mango_v4::accounts::AccountExpand;
-    type Instruction = mango_v4::instruction::AccountExpandV2;
+    type Instruction = mango_v4::instruction::AccountExpandV3;
     async fn to_instruction(
         &self,
         _account_loader: impl ClientAccountLoader + 'async_trait,
@@ -1908,6 +2002,8 @@ impl ClientInstruction for AccountExpandInstruction {
             perp_count: self.perp_count,
             perp_oo_count: self.perp_oo_count,
             token_conditional_swap_count: self.token_conditional_swap_count,
+            openbook_v2_count: self.openbook_v2_count,
+            perp_conditional_order_count: self.perp_conditional_order_count,
         };
 
         let account = Pubkey::find_program_address(
@@ -2719,183 +2815,907 @@ impl ClientInstruction for Serum3LiqForceCancelOrdersInstruction {
     }
 }
 
-pub struct TokenForceCloseBorrowsWithTokenInstruction {
-    pub liqee: Pubkey,
-    pub liqor: Pubkey,
-    pub liqor_owner: TestKeypair,
+pub struct OpenbookV2RegisterMarketInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub payer: TestKeypair,
 
-    pub asset_token_index: TokenIndex,
-    pub asset_bank_index: usize,
-    pub liab_token_index: TokenIndex,
-    pub liab_bank_index: usize,
-    pub max_liab_transfer: u64,
+    pub openbook_v2_program: Pubkey,
+    pub openbook_v2_market_external: Pubkey,
+
+    pub base_bank: Pubkey,
+    pub quote_bank: Pubkey,
+
+    pub market_index: OpenbookV2MarketIndex,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenForceCloseBorrowsWithTokenInstruction {
-    type Accounts = mango_v4::accounts::TokenForceCloseBorrowsWithToken;
-    type Instruction = mango_v4::instruction::TokenForceCloseBorrowsWithToken;
+impl ClientInstruction for OpenbookV2RegisterMarketInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2RegisterMarket;
+    type Instruction = mango_v4::instruction::OpenbookV2RegisterMarket;
     async fn to_instruction(
         &self,
-        account_loader: impl ClientAccountLoader + 'async_trait,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
         let instruction = Self::Instruction {
-            asset_token_index: self.asset_token_index,
-            liab_token_index: self.liab_token_index,
-            max_liab_transfer: self.max_liab_transfer,
+            market_index: self.market_index,
+            name: "UUU/usdc".to_string(),
         };
 
-        let liqee = account_loader
-            .load_mango_account(&self.liqee)
-            .await
-            .unwrap();
-        let liqor = account_loader
-            .load_mango_account(&self.liqor)
-            .await
-            .unwrap();
-        let health_check_metas = derive_liquidation_remaining_account_metas(
-            &account_loader,
-            &liqee,
-            &liqor,
-            self.asset_token_index,
-            self.asset_bank_index,
-            self.liab_token_index,
-            self.liab_bank_index,
+        let openbook_v2_market = Pubkey::find_program_address(
+            &[
+                b"OpenbookV2Market".as_ref(),
+                self.group.as_ref(),
+                self.openbook_v2_market_external.as_ref(),
+            ],
+            &program_id,
         )
-        .await;
+        .0;
+
+        let index_reservation = Pubkey::find_program_address(
+            &[
+                b"OpenbookV2Index".as_ref(),
+                self.group.as_ref(),
+                &self.market_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
 
         let accounts = Self::Accounts {
-            group: liqee.fixed.group,
-            liqee: self.liqee,
-            liqor: self.liqor,
-            liqor_owner: self.liqor_owner.pubkey(),
+            group: self.group,
+            admin: self.admin.pubkey(),
+            openbook_v2_program: self.openbook_v2_program,
+            openbook_v2_market_external: self.openbook_v2_market_external,
+            openbook_v2_market,
+            index_reservation,
+            quote_bank: self.quote_bank,
+            base_bank: self.base_bank,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
         };
 
-        let mut instruction = make_instruction(program_id, &accounts, &instruction);
-        instruction.accounts.extend(health_check_metas.into_iter());
-
+        let instruction = make_instruction(program_id, &accounts, &instruction);
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.liqor_owner]
+        vec![self.admin, self.payer]
     }
 }
 
-pub struct TokenLiqWithTokenInstruction {
-    pub liqee: Pubkey,
-    pub liqor: Pubkey,
-    pub liqor_owner: TestKeypair,
-
-    pub asset_token_index: TokenIndex,
-    pub asset_bank_index: usize,
-    pub liab_token_index: TokenIndex,
-    pub liab_bank_index: usize,
-    pub max_liab_transfer: I80F48,
+pub struct OpenbookV2DeregisterMarketInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub openbook_v2_market_external: Pubkey,
+    pub sol_destination: Pubkey,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenLiqWithTokenInstruction {
-    type Accounts = mango_v4::accounts::TokenLiqWithToken;
-    type Instruction = mango_v4::instruction::TokenLiqWithToken;
+impl ClientInstruction for OpenbookV2DeregisterMarketInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2DeregisterMarket;
+    type Instruction = mango_v4::instruction::OpenbookV2DeregisterMarket;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
-        let instruction = Self::Instruction {
-            asset_token_index: self.asset_token_index,
-            liab_token_index: self.liab_token_index,
-            max_liab_transfer: self.max_liab_transfer,
-        };
+        let instruction = Self::Instruction {};
 
-        let liqee = account_loader
-            .load_mango_account(&self.liqee)
-            .await
-            .unwrap();
-        let liqor = account_loader
-            .load_mango_account(&self.liqor)
-            .await
-            .unwrap();
-        let health_check_metas = derive_liquidation_remaining_account_metas(
-            &account_loader,
-            &liqee,
-            &liqor,
-            self.asset_token_index,
-            self.asset_bank_index,
-            self.liab_token_index,
-            self.liab_bank_index,
+        let openbook_v2_market = Pubkey::find_program_address(
+            &[
+                b"OpenbookV2Market".as_ref(),
+                self.group.as_ref(),
+                self.openbook_v2_market_external.as_ref(),
+            ],
+            &program_id,
         )
-        .await;
+        .0;
+        let openbook_v2_market_data: OpenbookV2Market =
+            account_loader.load(&openbook_v2_market).await.unwrap();
+
+        let index_reservation = Pubkey::find_program_address(
+            &[
+                b"OpenbookV2Index".as_ref(),
+                self.group.as_ref(),
+                &openbook_v2_market_data.market_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
 
         let accounts = Self::Accounts {
-            group: liqee.fixed.group,
-            liqee: self.liqee,
-            liqor: self.liqor,
-            liqor_owner: self.liqor_owner.pubkey(),
+            group: self.group,
+            admin: self.admin.pubkey(),
+            openbook_v2_market,
+            index_reservation,
+            sol_destination: self.sol_destination,
+            token_program: Token::id(),
         };
 
-        let mut instruction = make_instruction(program_id, &accounts, &instruction);
-        instruction.accounts.extend(health_check_metas.into_iter());
-
+        let instruction = make_instruction(program_id, &accounts, &instruction);
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.liqor_owner]
+        vec![self.admin]
     }
 }
 
-pub struct TokenLiqBankruptcyInstruction {
-    pub liqee: Pubkey,
-    pub liqor: Pubkey,
-    pub liqor_owner: TestKeypair,
-
-    pub max_liab_transfer: I80F48,
-    pub liab_mint_info: Pubkey,
+pub struct OpenbookV2CreateOpenOrdersInstruction {
+    pub account: Pubkey,
+    pub openbook_v2_market: Pubkey,
+    pub account_num: u32,
+    pub owner: TestKeypair,
+    pub payer: TestKeypair,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenLiqBankruptcyInstruction {
-    type Accounts = mango_v4::accounts::TokenLiqBankruptcy;
-    type Instruction = mango_v4::instruction::TokenLiqBankruptcy;
+impl ClientInstruction for OpenbookV2CreateOpenOrdersInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2CreateOpenOrders;
+    type Instruction = mango_v4::instruction::OpenbookV2CreateOpenOrders;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
         let instruction = Self::Instruction {
-            max_liab_transfer: self.max_liab_transfer,
+            account_num: self.account_num,
         };
 
-        let liab_mint_info: MintInfo = account_loader.load(&self.liab_mint_info).await.unwrap();
-        let liqee = account_loader
-            .load_mango_account(&self.liqee)
-            .await
-            .unwrap();
-        let liqor = account_loader
-            .load_mango_account(&self.liqor)
-            .await
-            .unwrap();
-        let health_check_metas = derive_liquidation_remaining_account_metas(
-            &account_loader,
-            &liqee,
-            &liqor,
-            QUOTE_TOKEN_INDEX,
-            0,
-            liab_mint_info.token_index,
-            0,
-        )
-        .await;
-
-        let group_key = liqee.fixed.group;
-        let group: Group = account_loader.load(&group_key).await.unwrap();
-
-        let quote_mint_info = Pubkey::find_program_address(
+        let account: MangoAccount = account_loader.load(&self.account).await.unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = Pubkey::find_program_address(
             &[
-                b"MintInfo".as_ref(),
-                liqee.fixed.group.as_ref(),
-                group.insurance_mint.as_ref(),
+                b"OpenOrders".as_ref(),
+                self.openbook_v2_market.as_ref(),
+                openbook_v2_market.openbook_v2_market_external.as_ref(),
+                &self.account_num.to_le_bytes(),
             ],
-            &program_id,
+            &openbook_v2_market.openbook_v2_program,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: account.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            open_orders,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
+            rent: sysvar::rent::Rent::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner, self.payer]
+    }
+}
+
+pub struct OpenbookV2CloseOpenOrdersInstruction {
+    pub account: Pubkey,
+    pub openbook_v2_market: Pubkey,
+    pub owner: TestKeypair,
+    pub sol_destination: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2CloseOpenOrdersInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2CloseOpenOrders;
+    type Instruction = mango_v4::instruction::OpenbookV2CloseOpenOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {};
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            open_orders,
+            sol_destination: self.sol_destination,
+            system_program: System::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2PlaceOrderInstruction {
+    pub side: openbook_v2::state::Side,
+    pub limit_price: u64,
+    pub max_base_qty: u64,
+    pub max_native_quote_qty_including_fees: u64,
+    pub self_trade_behavior: openbook_v2::state::SelfTradeBehavior,
+    pub order_type: openbook_v2::state::PlaceOrderType,
+    pub client_order_id: u64,
+    pub limit: u16,
+
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+
+    pub openbook_v2_market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2PlaceOrderInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2PlaceOrder;
+    type Instruction = mango_v4::instruction::OpenbookV2PlaceOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side as u8,
+            limit_price: self.limit_price,
+            max_base_qty: self.max_base_qty,
+            max_native_quote_qty_including_fees: self.max_native_quote_qty_including_fees,
+            self_trade_behavior: self.self_trade_behavior as u8,
+            order_type: self.order_type as u8,
+            client_order_id: self.client_order_id,
+            limit: self.limit,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let quote_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.quote_token_index,
+        )
+        .await;
+        let base_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.base_token_index,
+        )
+        .await;
+
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let payer_info = &match self.side {
+            openbook_v2::state::Side::Bid => &quote_info,
+            openbook_v2::state::Side::Ask => &base_info,
+        };
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            bids: market_external.bids,
+            asks: market_external.asks,
+            event_queue: market_external.event_queue,
+            market_base_vault: market_external.market_base_vault,
+            market_quote_vault: market_external.market_quote_vault,
+            market_vault_signer: market_external.market_authority,
+            payer_bank: payer_info.first_bank(),
+            payer_vault: payer_info.first_vault(),
+            payer_oracle: payer_info.oracle,
+            token_program: Token::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2CancelOrderInstruction {
+    pub side: openbook_v2::state::Side,
+    pub order_id: u128,
+
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+
+    pub openbook_v2_market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2CancelOrderInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2CancelOrder;
+    type Instruction = mango_v4::instruction::OpenbookV2CancelOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side as u8,
+            order_id: self.order_id,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            bids: market_external.bids,
+            asks: market_external.asks,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2SettleFundsInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+
+    pub openbook_v2_market: Pubkey,
+    pub fees_to_dao: bool,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2SettleFundsInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2SettleFunds;
+    type Instruction = mango_v4::instruction::OpenbookV2SettleFunds;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            fees_to_dao: self.fees_to_dao,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let quote_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.quote_token_index,
+        )
+        .await;
+        let base_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.base_token_index,
+        )
+        .await;
+
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            market_base_vault: market_external.market_base_vault,
+            market_quote_vault: market_external.market_quote_vault,
+            market_vault_signer: market_external.market_authority,
+            quote_bank: quote_info.first_bank(),
+            quote_vault: quote_info.first_vault(),
+            base_bank: base_info.first_bank(),
+            base_vault: base_info.first_vault(),
+            quote_oracle: quote_info.oracle,
+            base_oracle: base_info.oracle,
+            token_program: Token::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2LiqForceCancelOrdersInstruction {
+    pub account: Pubkey,
+    pub openbook_v2_market: Pubkey,
+    pub limit: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2LiqForceCancelOrdersInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2LiqForceCancelOrders;
+    type Instruction = mango_v4::instruction::OpenbookV2LiqForceCancelOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction { limit: self.limit };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let quote_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.quote_token_index,
+        )
+        .await;
+        let base_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.base_token_index,
+        )
+        .await;
+
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            bids: market_external.bids,
+            asks: market_external.asks,
+            event_queue: market_external.event_queue,
+            market_base_vault: market_external.market_base_vault,
+            market_quote_vault: market_external.market_quote_vault,
+            market_vault_signer: market_external.market_authority,
+            quote_bank: quote_info.first_bank(),
+            quote_vault: quote_info.first_vault(),
+            base_bank: base_info.first_bank(),
+            base_vault: base_info.first_vault(),
+            token_program: Token::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
+pub struct TokenForceCloseBorrowsWithTokenInstruction {
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+
+    pub asset_token_index: TokenIndex,
+    pub asset_bank_index: usize,
+    pub liab_token_index: TokenIndex,
+    pub liab_bank_index: usize,
+    pub max_liab_transfer: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenForceCloseBorrowsWithTokenInstruction {
+    type Accounts = mango_v4::accounts::TokenForceCloseBorrowsWithToken;
+    type Instruction = mango_v4::instruction::TokenForceCloseBorrowsWithToken;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            asset_token_index: self.asset_token_index,
+            liab_token_index: self.liab_token_index,
+            max_liab_transfer: self.max_liab_transfer,
+        };
+
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            self.asset_token_index,
+            self.asset_bank_index,
+            self.liab_token_index,
+            self.liab_bank_index,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: liqee.fixed.group,
+            liqee: self.liqee,
+            liqor: self.liqor,
+            liqor_owner: self.liqor_owner.pubkey(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.liqor_owner]
+    }
+}
+
+pub struct TokenInsuranceFundCreateInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub mint: Pubkey,
+    pub payer: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenInsuranceFundCreateInstruction {
+    type Accounts = mango_v4::accounts::TokenInsuranceFundCreate;
+    type Instruction = mango_v4::instruction::TokenInsuranceFundCreate;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+
+        let mint_info_key = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                self.group.as_ref(),
+                self.mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let mint_info: MintInfo = account_loader.load(&mint_info_key).await.unwrap();
+        let token_index = mint_info.token_index;
+
+        let instruction = Self::Instruction { token_index };
+
+        let insurance_fund = Pubkey::find_program_address(
+            &[
+                b"TokenInsuranceFund".as_ref(),
+                self.group.as_ref(),
+                &token_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+        let vault = Pubkey::find_program_address(
+            &[
+                b"TokenInsuranceFundVault".as_ref(),
+                self.group.as_ref(),
+                &token_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            mint_info: mint_info_key,
+            mint: self.mint,
+            insurance_fund,
+            vault,
+            payer: self.payer.pubkey(),
+            token_program: Token::id(),
+            system_program: System::id(),
+            rent: sysvar::rent::Rent::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        let mut bank_ams = mint_info
+            .banks()
+            .iter()
+            .map(|bank| AccountMeta {
+                pubkey: *bank,
+                is_signer: false,
+                is_writable: true,
+            })
+            .collect::<Vec<_>>();
+        instruction.accounts.append(&mut bank_ams);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin, self.payer]
+    }
+}
+
+pub struct TokenInsuranceFundDepositInstruction {
+    pub amount: u64,
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub insurance_fund: Pubkey,
+    pub token_account: Pubkey,
+    pub token_authority: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenInsuranceFundDepositInstruction {
+    type Accounts = mango_v4::accounts::TokenInsuranceFundDeposit;
+    type Instruction = mango_v4::instruction::TokenInsuranceFundDeposit;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            amount: self.amount,
+        };
+
+        let fund: TokenInsuranceFund = account_loader.load(&self.insurance_fund).await.unwrap();
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            insurance_fund: self.insurance_fund,
+            vault: fund.vault,
+            token_account: self.token_account,
+            token_authority: self.token_authority.pubkey(),
+            token_program: Token::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin, self.token_authority]
+    }
+}
+
+pub struct TokenInsuranceFundWithdrawInstruction {
+    pub amount: u64,
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub insurance_fund: Pubkey,
+    pub destination: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenInsuranceFundWithdrawInstruction {
+    type Accounts = mango_v4::accounts::TokenInsuranceFundWithdraw;
+    type Instruction = mango_v4::instruction::TokenInsuranceFundWithdraw;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            amount: self.amount,
+        };
+
+        let fund: TokenInsuranceFund = account_loader.load(&self.insurance_fund).await.unwrap();
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            insurance_fund: self.insurance_fund,
+            vault: fund.vault,
+            destination: self.destination,
+            token_program: Token::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
+pub struct TokenLiqWithTokenInstruction {
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+
+    pub asset_token_index: TokenIndex,
+    pub asset_bank_index: usize,
+    pub liab_token_index: TokenIndex,
+    pub liab_bank_index: usize,
+    pub max_liab_transfer: I80F48,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenLiqWithTokenInstruction {
+    type Accounts = mango_v4::accounts::TokenLiqWithToken;
+    type Instruction = mango_v4::instruction::TokenLiqWithToken;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            asset_token_index: self.asset_token_index,
+            liab_token_index: self.liab_token_index,
+            max_liab_transfer: self.max_liab_transfer,
+        };
+
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            self.asset_token_index,
+            self.asset_bank_index,
+            self.liab_token_index,
+            self.liab_bank_index,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: liqee.fixed.group,
+            liqee: self.liqee,
+            liqor: self.liqor,
+            liqor_owner: self.liqor_owner.pubkey(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.liqor_owner]
+    }
+}
+
+pub struct TokenLiqBankruptcyInstruction {
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+
+    pub max_liab_transfer: I80F48,
+    pub liab_mint_info: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenLiqBankruptcyInstruction {
+    type Accounts = mango_v4::accounts::TokenLiqBankruptcy;
+    type Instruction = mango_v4::instruction::TokenLiqBankruptcy;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            max_liab_transfer: self.max_liab_transfer,
+        };
+
+        let liab_mint_info: MintInfo = account_loader.load(&self.liab_mint_info).await.unwrap();
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            QUOTE_TOKEN_INDEX,
+            0,
+            liab_mint_info.token_index,
+            0,
+        )
+        .await;
+
+        let group_key = liqee.fixed.group;
+        let group: Group = account_loader.load(&group_key).await.unwrap();
+
+        let quote_mint_info = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                liqee.fixed.group.as_ref(),
+                group.insurance_mint.as_ref(),
+            ],
+            &program_id,
         )
         .0;
         let quote_mint_info: MintInfo = account_loader.load(&quote_mint_info).await.unwrap();
@@ -2918,6 +3738,27 @@ impl ClientInstruction for TokenLiqBankruptcyInstruction {
         };
 
         let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        let liab_bank: Bank = account_loader
+            .load(&liab_mint_info.first_bank())
+            .await
+            .unwrap();
+        if liab_bank.has_token_insurance_fund() {
+            let fund: TokenInsuranceFund = account_loader
+                .load(&liab_bank.token_insurance_fund)
+                .await
+                .unwrap();
+            for pubkey in [
+                liab_bank.token_insurance_fund,
+                fund.vault,
+                liab_mint_info.first_vault(),
+            ] {
+                instruction.accounts.push(AccountMeta {
+                    pubkey,
+                    is_signer: false,
+                    is_writable: true,
+                });
+            }
+        }
         let mut bank_ams = liab_mint_info
             .banks()
             .iter()
@@ -3315,15 +4156,71 @@ impl ClientInstruction for PerpDeactivatePositionInstruction {
         let program_id = mango_v4::id();
         let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
 
-        let instruction = Self::Instruction {};
+        let instruction = Self::Instruction {};
+        let accounts = Self::Accounts {
+            group: perp_market.group,
+            account: self.account,
+            perp_market: self.perp_market,
+            owner: self.owner.pubkey(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct PerpIsolatedMarginTransferInstruction {
+    pub account: Pubkey,
+    pub perp_market: Pubkey,
+    pub owner: TestKeypair,
+    pub amount: i64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpIsolatedMarginTransferInstruction {
+    type Accounts = mango_v4::accounts::PerpIsolatedMarginTransfer;
+    type Instruction = mango_v4::instruction::PerpIsolatedMarginTransfer;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            amount: self.amount,
+        };
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            Some(perp_market.perp_market_index),
+        )
+        .await;
+
+        let settle_mint_info =
+            get_mint_info_by_token_index(&account_loader, &account, perp_market.settle_token_index)
+                .await;
+
         let accounts = Self::Accounts {
-            group: perp_market.group,
+            group: account.fixed.group,
             account: self.account,
-            perp_market: self.perp_market,
             owner: self.owner.pubkey(),
+            perp_market: self.perp_market,
+            settle_bank: settle_mint_info.first_bank(),
+            settle_oracle: settle_mint_info.oracle,
         };
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas);
 
-        let instruction = make_instruction(program_id, &accounts, &instruction);
         (accounts, instruction)
     }
 
@@ -3939,6 +4836,100 @@ impl ClientInstruction for PerpLiqBaseOrPositivePnlInstruction {
     }
 }
 
+pub struct PerpLiqIsolatedPositionInstruction {
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+    pub liqee: Pubkey,
+    pub perp_market: Pubkey,
+    pub max_base_transfer: i64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpLiqIsolatedPositionInstruction {
+    type Accounts = mango_v4::accounts::PerpLiqIsolatedPosition;
+    type Instruction = mango_v4::instruction::PerpLiqIsolatedPosition;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            max_base_transfer: self.max_base_transfer,
+        };
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            TokenIndex::MAX,
+            0,
+            TokenIndex::MAX,
+            0,
+        )
+        .await;
+
+        let group = account_loader
+            .load::<Group>(&perp_market.group)
+            .await
+            .unwrap();
+        let settle_mint_info =
+            get_mint_info_by_token_index(&account_loader, &liqee, perp_market.settle_token_index)
+                .await;
+        let insurance_mint_info =
+            get_mint_info_by_token_index(&account_loader, &liqee, QUOTE_TOKEN_INDEX).await;
+
+        let accounts = Self::Accounts {
+            group: perp_market.group,
+            perp_market: self.perp_market,
+            oracle: perp_market.oracle,
+            liqor: self.liqor,
+            liqor_owner: self.liqor_owner.pubkey(),
+            liqee: self.liqee,
+            settle_bank: settle_mint_info.first_bank(),
+            settle_vault: settle_mint_info.first_vault(),
+            settle_oracle: settle_mint_info.oracle,
+            insurance_vault: group.insurance_vault,
+            insurance_bank: insurance_mint_info.first_bank(),
+            insurance_bank_vault: insurance_mint_info.first_vault(),
+            insurance_oracle: insurance_mint_info.oracle,
+            token_program: Token::id(),
+        };
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        let settle_bank: Bank = account_loader
+            .load(&settle_mint_info.first_bank())
+            .await
+            .unwrap();
+        if settle_bank.has_token_insurance_fund() {
+            let fund: TokenInsuranceFund = account_loader
+                .load(&settle_bank.token_insurance_fund)
+                .await
+                .unwrap();
+            for pubkey in [settle_bank.token_insurance_fund, fund.vault] {
+                instruction.accounts.push(AccountMeta {
+                    pubkey,
+                    is_signer: false,
+                    is_writable: true,
+                });
+            }
+        }
+        instruction.accounts.extend(health_check_metas);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.liqor_owner]
+    }
+}
+
 pub struct PerpLiqNegativePnlOrBankruptcyInstruction {
     pub liqor: Pubkey,
     pub liqor_owner: TestKeypair,
@@ -4004,6 +4995,23 @@ impl ClientInstruction for PerpLiqNegativePnlOrBankruptcyInstruction {
             token_program: Token::id(),
         };
         let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        let settle_bank: Bank = account_loader
+            .load(&settle_mint_info.first_bank())
+            .await
+            .unwrap();
+        if settle_bank.has_token_insurance_fund() {
+            let fund: TokenInsuranceFund = account_loader
+                .load(&settle_bank.token_insurance_fund)
+                .await
+                .unwrap();
+            for pubkey in [settle_bank.token_insurance_fund, fund.vault] {
+                instruction.accounts.push(AccountMeta {
+                    pubkey,
+                    is_signer: false,
+                    is_writable: true,
+                });
+            }
+        }
         instruction.accounts.extend(health_check_metas);
 
         (accounts, instruction)
@@ -4259,63 +5267,256 @@ impl ClientInstruction for AltExtendInstruction {
     type Instruction = mango_v4::instruction::AltExtend;
     async fn to_instruction(
         &self,
-        _account_loader: impl ClientAccountLoader + 'async_trait,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            index: self.index,
+            new_addresses: self.new_addresses.clone(),
+        };
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            payer: self.payer.pubkey(),
+            address_lookup_table: self.address_lookup_table,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin, self.payer]
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenConditionalSwapCreateInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub buy_mint: Pubkey,
+    pub sell_mint: Pubkey,
+    pub max_buy: u64,
+    pub max_sell: u64,
+    pub price_lower_limit: f64,
+    pub price_upper_limit: f64,
+    pub price_premium_rate: f64,
+    pub allow_creating_deposits: bool,
+    pub allow_creating_borrows: bool,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenConditionalSwapCreateInstruction {
+    type Accounts = mango_v4::accounts::TokenConditionalSwapCreate;
+    type Instruction = mango_v4::instruction::TokenConditionalSwapCreateV2;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            max_buy: self.max_buy,
+            max_sell: self.max_sell,
+            expiry_timestamp: u64::MAX,
+            price_lower_limit: self.price_lower_limit,
+            price_upper_limit: self.price_upper_limit,
+            price_premium_rate: self.price_premium_rate,
+            allow_creating_deposits: self.allow_creating_deposits,
+            allow_creating_borrows: self.allow_creating_borrows,
+            display_price_style: TokenConditionalSwapDisplayPriceStyle::SellTokenPerBuyToken,
+            intention: TokenConditionalSwapIntention::Unknown,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+
+        let buy_mint_info_address = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                account.fixed.group.as_ref(),
+                self.buy_mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let sell_mint_info_address = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                account.fixed.group.as_ref(),
+                self.sell_mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let buy_mint_info: MintInfo = account_loader.load(&buy_mint_info_address).await.unwrap();
+        let sell_mint_info: MintInfo = account_loader.load(&sell_mint_info_address).await.unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            buy_bank: buy_mint_info.first_bank(),
+            sell_bank: sell_mint_info.first_bank(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenConditionalSwapCancelInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub index: u8,
+    pub id: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenConditionalSwapCancelInstruction {
+    type Accounts = mango_v4::accounts::TokenConditionalSwapCancel;
+    type Instruction = mango_v4::instruction::TokenConditionalSwapCancel;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            token_conditional_swap_index: self.index,
+            token_conditional_swap_id: self.id,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let tcs = account.token_conditional_swap_by_id(self.id).unwrap().1;
+
+        let buy_mint_info =
+            get_mint_info_by_token_index(&account_loader, &account, tcs.buy_token_index).await;
+        let sell_mint_info =
+            get_mint_info_by_token_index(&account_loader, &account, tcs.sell_token_index).await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            buy_bank: buy_mint_info.first_bank(),
+            sell_bank: sell_mint_info.first_bank(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenConditionalSwapTriggerInstruction {
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+    pub index: u8,
+    pub max_buy_token_to_liqee: u64,
+    pub max_sell_token_to_liqor: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenConditionalSwapTriggerInstruction {
+    type Accounts = mango_v4::accounts::TokenConditionalSwapTrigger;
+    type Instruction = mango_v4::instruction::TokenConditionalSwapTrigger;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
+
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+
+        let tcs = liqee
+            .token_conditional_swap_by_index(self.index.into())
+            .unwrap()
+            .clone();
+
         let instruction = Self::Instruction {
-            index: self.index,
-            new_addresses: self.new_addresses.clone(),
+            token_conditional_swap_index: self.index,
+            token_conditional_swap_id: tcs.id,
+            max_buy_token_to_liqee: self.max_buy_token_to_liqee,
+            max_sell_token_to_liqor: self.max_sell_token_to_liqor,
         };
 
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            tcs.buy_token_index,
+            0,
+            tcs.sell_token_index,
+            0,
+        )
+        .await;
+
         let accounts = Self::Accounts {
-            group: self.group,
-            admin: self.admin.pubkey(),
-            payer: self.payer.pubkey(),
-            address_lookup_table: self.address_lookup_table,
+            group: liqee.fixed.group,
+            liqee: self.liqee,
+            liqor: self.liqor,
+            liqor_authority: self.liqor_owner.pubkey(),
         };
 
-        let instruction = make_instruction(program_id, &accounts, &instruction);
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.admin, self.payer]
+        vec![self.liqor_owner]
     }
 }
 
 #[derive(Clone)]
-pub struct TokenConditionalSwapCreateInstruction {
+pub struct PerpConditionalOrderCreateInstruction {
     pub account: Pubkey,
     pub owner: TestKeypair,
-    pub buy_mint: Pubkey,
-    pub sell_mint: Pubkey,
-    pub max_buy: u64,
-    pub max_sell: u64,
+    pub perp_market: Pubkey,
+    pub side: Side,
+    pub max_base_lots: i64,
+    pub expiry_timestamp: u64,
     pub price_lower_limit: f64,
     pub price_upper_limit: f64,
     pub price_premium_rate: f64,
-    pub allow_creating_deposits: bool,
-    pub allow_creating_borrows: bool,
+    pub reduce_only: bool,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenConditionalSwapCreateInstruction {
-    type Accounts = mango_v4::accounts::TokenConditionalSwapCreate;
-    type Instruction = mango_v4::instruction::TokenConditionalSwapCreateV2;
+impl ClientInstruction for PerpConditionalOrderCreateInstruction {
+    type Accounts = mango_v4::accounts::PerpConditionalOrderCreate;
+    type Instruction = mango_v4::instruction::PerpConditionalOrderCreate;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
         let instruction = Self::Instruction {
-            max_buy: self.max_buy,
-            max_sell: self.max_sell,
-            expiry_timestamp: u64::MAX,
+            side: self.side,
+            max_base_lots: self.max_base_lots,
+            expiry_timestamp: self.expiry_timestamp,
             price_lower_limit: self.price_lower_limit,
             price_upper_limit: self.price_upper_limit,
             price_premium_rate: self.price_premium_rate,
-            allow_creating_deposits: self.allow_creating_deposits,
-            allow_creating_borrows: self.allow_creating_borrows,
-            display_price_style: TokenConditionalSwapDisplayPriceStyle::SellTokenPerBuyToken,
+            reduce_only: self.reduce_only,
             intention: TokenConditionalSwapIntention::Unknown,
         };
 
@@ -4324,33 +5525,11 @@ impl ClientInstruction for TokenConditionalSwapCreateInstruction {
             .await
             .unwrap();
 
-        let buy_mint_info_address = Pubkey::find_program_address(
-            &[
-                b"MintInfo".as_ref(),
-                account.fixed.group.as_ref(),
-                self.buy_mint.as_ref(),
-            ],
-            &program_id,
-        )
-        .0;
-        let sell_mint_info_address = Pubkey::find_program_address(
-            &[
-                b"MintInfo".as_ref(),
-                account.fixed.group.as_ref(),
-                self.sell_mint.as_ref(),
-            ],
-            &program_id,
-        )
-        .0;
-        let buy_mint_info: MintInfo = account_loader.load(&buy_mint_info_address).await.unwrap();
-        let sell_mint_info: MintInfo = account_loader.load(&sell_mint_info_address).await.unwrap();
-
         let accounts = Self::Accounts {
             group: account.fixed.group,
             account: self.account,
             authority: self.owner.pubkey(),
-            buy_bank: buy_mint_info.first_bank(),
-            sell_bank: sell_mint_info.first_bank(),
+            perp_market: self.perp_market,
         };
 
         let instruction = make_instruction(program_id, &accounts, &instruction);
@@ -4363,43 +5542,35 @@ impl ClientInstruction for TokenConditionalSwapCreateInstruction {
 }
 
 #[derive(Clone)]
-pub struct TokenConditionalSwapCancelInstruction {
+pub struct PerpConditionalOrderCancelInstruction {
     pub account: Pubkey,
     pub owner: TestKeypair,
     pub index: u8,
     pub id: u64,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenConditionalSwapCancelInstruction {
-    type Accounts = mango_v4::accounts::TokenConditionalSwapCancel;
-    type Instruction = mango_v4::instruction::TokenConditionalSwapCancel;
+impl ClientInstruction for PerpConditionalOrderCancelInstruction {
+    type Accounts = mango_v4::accounts::PerpConditionalOrderCancel;
+    type Instruction = mango_v4::instruction::PerpConditionalOrderCancel;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
         let instruction = Self::Instruction {
-            token_conditional_swap_index: self.index,
-            token_conditional_swap_id: self.id,
+            perp_conditional_order_index: self.index,
+            perp_conditional_order_id: self.id,
         };
 
         let account = account_loader
             .load_mango_account(&self.account)
             .await
             .unwrap();
-        let tcs = account.token_conditional_swap_by_id(self.id).unwrap().1;
-
-        let buy_mint_info =
-            get_mint_info_by_token_index(&account_loader, &account, tcs.buy_token_index).await;
-        let sell_mint_info =
-            get_mint_info_by_token_index(&account_loader, &account, tcs.sell_token_index).await;
 
         let accounts = Self::Accounts {
             group: account.fixed.group,
             account: self.account,
             authority: self.owner.pubkey(),
-            buy_bank: buy_mint_info.first_bank(),
-            sell_bank: sell_mint_info.first_bank(),
         };
 
         let instruction = make_instruction(program_id, &accounts, &instruction);
@@ -4412,52 +5583,60 @@ impl ClientInstruction for TokenConditionalSwapCancelInstruction {
 }
 
 #[derive(Clone)]
-pub struct TokenConditionalSwapTriggerInstruction {
+pub struct PerpConditionalOrderTriggerInstruction {
     pub liqee: Pubkey,
     pub liqor: Pubkey,
     pub liqor_owner: TestKeypair,
     pub index: u8,
-    pub max_buy_token_to_liqee: u64,
-    pub max_sell_token_to_liqor: u64,
+    pub max_base_lots: i64,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenConditionalSwapTriggerInstruction {
-    type Accounts = mango_v4::accounts::TokenConditionalSwapTrigger;
-    type Instruction = mango_v4::instruction::TokenConditionalSwapTrigger;
+impl ClientInstruction for PerpConditionalOrderTriggerInstruction {
+    type Accounts = mango_v4::accounts::PerpConditionalOrderTrigger;
+    type Instruction = mango_v4::instruction::PerpConditionalOrderTrigger;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
 
-        let liqee = account_loader
+        let mut liqee = account_loader
             .load_mango_account(&self.liqee)
             .await
             .unwrap();
-        let liqor = account_loader
+        let mut liqor = account_loader
             .load_mango_account(&self.liqor)
             .await
             .unwrap();
 
-        let tcs = liqee
-            .token_conditional_swap_by_index(self.index.into())
-            .unwrap()
-            .clone();
+        let order = *liqee
+            .perp_conditional_order_by_index(self.index.into())
+            .unwrap();
 
         let instruction = Self::Instruction {
-            token_conditional_swap_index: self.index,
-            token_conditional_swap_id: tcs.id,
-            max_buy_token_to_liqee: self.max_buy_token_to_liqee,
-            max_sell_token_to_liqor: self.max_sell_token_to_liqor,
+            perp_conditional_order_index: self.index,
+            perp_conditional_order_id: order.id,
+            max_base_lots: self.max_base_lots,
         };
 
+        let perp_market_address =
+            get_perp_market_address_by_index(liqee.fixed.group, order.perp_market_index);
+        let perp_market: PerpMarket = account_loader.load(&perp_market_address).await.unwrap();
+
+        // The instruction creates the perp positions on both accounts
+        liqee
+            .ensure_perp_position(order.perp_market_index, perp_market.settle_token_index)
+            .unwrap();
+        liqor
+            .ensure_perp_position(order.perp_market_index, perp_market.settle_token_index)
+            .unwrap();
         let health_check_metas = derive_liquidation_remaining_account_metas(
             &account_loader,
             &liqee,
             &liqor,
-            tcs.buy_token_index,
+            TokenIndex::MAX,
             0,
-            tcs.sell_token_index,
+            TokenIndex::MAX,
             0,
         )
         .await;
@@ -4467,10 +5646,12 @@ impl ClientInstruction for TokenConditionalSwapTriggerInstruction {
             liqee: self.liqee,
             liqor: self.liqor,
             liqor_authority: self.liqor_owner.pubkey(),
+            perp_market: perp_market_address,
+            oracle: perp_market.oracle,
         };
 
         let mut instruction = make_instruction(program_id, &accounts, &instruction);
-        instruction.accounts.extend(health_check_metas.into_iter());
+        instruction.accounts.extend(health_check_metas);
         (accounts, instruction)
     }
 
@@ -4478,3 +5659,159 @@ impl ClientInstruction for TokenConditionalSwapTriggerInstruction {
         vec![self.liqor_owner]
     }
 }
+
+#[derive(Clone)]
+pub struct PerpConditionalOrderCreatePlaceOrderInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub perp_market: Pubkey,
+    pub side: Side,
+    pub price_lower_limit: f64,
+    pub price_upper_limit: f64,
+    pub price_lots: i64,
+    pub peg_to_oracle: bool,
+    pub peg_limit: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots: i64,
+    pub client_order_id: u64,
+    pub order_type: PlaceOrderType,
+    pub reduce_only: bool,
+    pub trigger_premium: u64,
+    pub expiry_timestamp: u64,
+}
+impl Default for PerpConditionalOrderCreatePlaceOrderInstruction {
+    fn default() -> Self {
+        Self {
+            account: Pubkey::default(),
+            owner: TestKeypair::default(),
+            perp_market: Pubkey::default(),
+            side: Side::Bid,
+            price_lower_limit: 0.0,
+            price_upper_limit: f64::MAX,
+            price_lots: 0,
+            peg_to_oracle: false,
+            peg_limit: -1,
+            max_base_lots: i64::MAX,
+            max_quote_lots: i64::MAX,
+            client_order_id: 0,
+            order_type: PlaceOrderType::Market,
+            reduce_only: false,
+            trigger_premium: 0,
+            expiry_timestamp: u64::MAX,
+        }
+    }
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpConditionalOrderCreatePlaceOrderInstruction {
+    type Accounts = mango_v4::accounts::PerpConditionalOrderCreate;
+    type Instruction = mango_v4::instruction::PerpConditionalOrderCreatePlaceOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side,
+            price_lower_limit: self.price_lower_limit,
+            price_upper_limit: self.price_upper_limit,
+            price_lots: self.price_lots,
+            peg_to_oracle: self.peg_to_oracle,
+            peg_limit: self.peg_limit,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots: self.max_quote_lots,
+            client_order_id: self.client_order_id,
+            order_type: self.order_type,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
+            reduce_only: self.reduce_only,
+            time_in_force: 0,
+            trigger_premium: self.trigger_premium,
+            expiry_timestamp: self.expiry_timestamp,
+            intention: TokenConditionalSwapIntention::Unknown,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            perp_market: self.perp_market,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct PerpConditionalOrderTriggerPlaceOrderInstruction {
+    pub account: Pubkey,
+    pub triggerer: Pubkey,
+    pub triggerer_owner: TestKeypair,
+    pub index: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpConditionalOrderTriggerPlaceOrderInstruction {
+    type Accounts = mango_v4::accounts::PerpConditionalOrderTriggerPlaceOrder;
+    type Instruction = mango_v4::instruction::PerpConditionalOrderTriggerPlaceOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let order = *account
+            .perp_conditional_order_by_index(self.index.into())
+            .unwrap();
+
+        let instruction = Self::Instruction {
+            perp_conditional_order_index: self.index,
+            perp_conditional_order_id: order.id,
+            limit: 10,
+        };
+
+        let perp_market_address =
+            get_perp_market_address_by_index(account.fixed.group, order.perp_market_index);
+        let perp_market: PerpMarket = account_loader.load(&perp_market_address).await.unwrap();
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            Some(order.perp_market_index),
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            triggerer: self.triggerer,
+            triggerer_authority: self.triggerer_owner.pubkey(),
+            perp_market: perp_market_address,
+            bids: perp_market.bids,
+            asks: perp_market.asks,
+            event_queue: perp_market.event_queue,
+            oracle: perp_market.oracle,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.triggerer_owner]
+    }
+}
@@ -12,6 +12,7 @@ use spl_token::{state::*, *};
 
 pub use cookies::*;
 pub use mango_client::*;
+pub use openbook_setup::*;
 pub use serum::*;
 pub use solana::*;
 pub use utils::*;
@@ -19,6 +20,7 @@ pub use utils::*;
 pub mod cookies;
 pub mod mango_client;
 pub mod mango_setup;
+pub mod openbook_setup;
 pub mod serum;
 pub mod solana;
 pub mod utils;
@@ -225,6 +227,16 @@ impl TestContextBuilder {
         serum_program_id
     }
 
+    pub fn add_openbook_v2_program(&mut self) -> Pubkey {
+        let openbook_v2_program_id = openbook_v2::id();
+        self.test.add_program(
+            "openbook_v2",
+            openbook_v2_program_id,
+            processor!(openbook_v2::entry),
+        );
+        openbook_v2_program_id
+    }
+
     pub fn add_margin_trade_program(&mut self) -> MarginTradeCookie {
         let program = Pubkey::from_str("J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix").unwrap();
         let token_account = TestKeypair::new();
@@ -259,6 +271,7 @@ impl TestContextBuilder {
         let mints = self.create_mints();
         let users = self.create_users(&mints);
         let serum_program_id = self.add_serum_program();
+        let openbook_v2_program_id = self.add_openbook_v2_program();
 
         let solana = self.start().await;
 
@@ -266,12 +279,17 @@ impl TestContextBuilder {
             solana: solana.clone(),
             program_id: serum_program_id,
         });
+        let openbook_v2 = Arc::new(OpenbookV2Cookie {
+            solana: solana.clone(),
+            program_id: openbook_v2_program_id,
+        });
 
         TestContext {
             solana: solana.clone(),
             mints,
             users,
             serum,
+            openbook_v2,
         }
     }
 
@@ -296,6 +314,7 @@ pub struct TestContext {
     pub mints: Vec<MintCookie>,
     pub users: Vec<UserCookie>,
     pub serum: Arc<SerumCookie>,
+    pub openbook_v2: Arc<OpenbookV2Cookie>,
 }
 
 impl TestContext {
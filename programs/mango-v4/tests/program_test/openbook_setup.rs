@@ -0,0 +1,130 @@
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use anchor_lang::prelude::System;
+use anchor_lang::Id;
+use anchor_spl::token::Token;
+use openbook_v2::state::{BookSide, EventQueue, OpenOrdersAccount, OracleConfigParams};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use super::*;
+
+#[derive(Clone, Debug)]
+pub struct OpenbookV2MarketCookie {
+    pub market: Pubkey,
+    pub market_authority: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+    pub base_mint: MintCookie,
+    pub quote_mint: MintCookie,
+}
+
+pub struct OpenbookV2Cookie {
+    pub solana: Arc<solana::SolanaCookie>,
+    pub program_id: Pubkey,
+}
+
+impl OpenbookV2Cookie {
+    pub async fn list_spot_market(
+        &self,
+        base_mint: &MintCookie,
+        quote_mint: &MintCookie,
+    ) -> OpenbookV2MarketCookie {
+        let market = TestKeypair::new();
+        let market_authority = Pubkey::find_program_address(
+            &[b"Market".as_ref(), market.pubkey().as_ref()],
+            &self.program_id,
+        )
+        .0;
+
+        let bids = self
+            .solana
+            .create_account_for_type::<BookSide>(&self.program_id)
+            .await;
+        let asks = self
+            .solana
+            .create_account_for_type::<BookSide>(&self.program_id)
+            .await;
+        let event_queue = self
+            .solana
+            .create_account_for_type::<EventQueue>(&self.program_id)
+            .await;
+
+        let market_base_vault = spl_associated_token_account::get_associated_token_address(
+            &market_authority,
+            &base_mint.pubkey,
+        );
+        let market_quote_vault = spl_associated_token_account::get_associated_token_address(
+            &market_authority,
+            &quote_mint.pubkey,
+        );
+
+        let accounts = openbook_v2::accounts::CreateMarket {
+            market: market.pubkey(),
+            market_authority,
+            bids,
+            asks,
+            event_queue,
+            payer: self.solana.context.borrow().payer.pubkey(),
+            market_base_vault,
+            market_quote_vault,
+            base_mint: base_mint.pubkey,
+            quote_mint: quote_mint.pubkey,
+            system_program: System::id(),
+            token_program: Token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            oracle_a: None,
+            oracle_b: None,
+            collect_fee_admin: Pubkey::new_unique(),
+            open_orders_admin: None,
+            consume_events_admin: None,
+            close_market_admin: None,
+        };
+        let instruction = openbook_v2::instruction::CreateMarket {
+            name: "UUU/usdc".to_string(),
+            oracle_config: OracleConfigParams {
+                conf_filter: 0.1,
+                max_staleness_slots: None,
+            },
+            quote_lot_size: quote_mint.quote_lot as i64,
+            base_lot_size: base_mint.base_lot as i64,
+            maker_fee: 0,
+            taker_fee: 0,
+            time_expiry: 0,
+        };
+        let create_market_instruction = Instruction {
+            program_id: self.program_id,
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(&accounts, None),
+            data: anchor_lang::InstructionData::data(&instruction),
+        };
+
+        self.solana
+            .process_transaction(&[create_market_instruction], Some(&[market]))
+            .await
+            .unwrap();
+
+        OpenbookV2MarketCookie {
+            market: market.pubkey(),
+            market_authority,
+            bids,
+            asks,
+            event_queue,
+            market_base_vault,
+            market_quote_vault,
+            base_mint: base_mint.clone(),
+            quote_mint: quote_mint.clone(),
+        }
+    }
+
+    pub async fn load_open_orders(&self, open_orders: Pubkey) -> OpenOrdersAccount {
+        self.solana
+            .get_account::<OpenOrdersAccount>(open_orders)
+            .await
+    }
+}
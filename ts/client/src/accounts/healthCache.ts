@@ -146,7 +146,14 @@ export class HealthCache {
       const perpMarket = group.getPerpMarketByMarketIndex(
         perpPosition.marketIndex,
       );
-      return PerpInfo.fromPerpPosition(perpMarket, perpPosition);
+      const settleBank = group.getFirstBankByTokenIndex(
+        perpMarket.settleTokenIndex,
+      );
+      return PerpInfo.fromPerpPosition(
+        perpMarket,
+        perpPosition,
+        settleBank.price,
+      );
     });
 
     return new HealthCache(tokenInfos, serum3Infos, perpInfos);
@@ -653,7 +660,16 @@ export class HealthCache {
   getOrCreatePerpInfoIndex(perpMarket: PerpMarket): number {
     const index = this.findPerpInfoIndex(perpMarket.perpMarketIndex);
     if (index == -1) {
-      this.perpInfos.push(PerpInfo.emptyFromPerpMarket(perpMarket));
+      const settleInfoIndex = this.findTokenInfoIndex(
+        perpMarket.settleTokenIndex,
+      );
+      const settleTokenPrice =
+        settleInfoIndex == -1
+          ? ONE_I80F48()
+          : this.tokenInfos[settleInfoIndex].prices.oracle;
+      this.perpInfos.push(
+        PerpInfo.emptyFromPerpMarket(perpMarket, settleTokenPrice),
+      );
     }
     return this.findPerpInfoIndex(perpMarket.perpMarketIndex);
   }
@@ -1731,9 +1747,14 @@ export class PerpInfo {
     );
   }
 
+  /**
+   * Quote amounts and prices get converted to settle token native units
+   * using the settle token oracle price.
+   */
   static fromPerpPosition(
     perpMarket: PerpMarket,
     perpPosition: PerpPosition,
+    settleTokenPrice: I80F48 = ONE_I80F48(),
   ): PerpInfo {
     const baseLots = perpPosition.basePositionLots.add(
       perpPosition.takerBaseLots,
@@ -1745,7 +1766,8 @@ export class PerpInfo {
     );
     const quoteCurrent = perpPosition.quotePositionNative
       .sub(unsettledFunding)
-      .add(takerQuote);
+      .add(takerQuote)
+      .div(settleTokenPrice);
 
     return new PerpInfo(
       perpMarket.perpMarketIndex,
@@ -1762,8 +1784,10 @@ export class PerpInfo {
       perpPosition.asksBaseLots,
       quoteCurrent,
       new Prices(
-        perpMarket.price,
-        I80F48.fromNumber(perpMarket.stablePriceModel.stablePrice),
+        perpMarket.price.div(settleTokenPrice),
+        I80F48.fromNumber(perpMarket.stablePriceModel.stablePrice).div(
+          settleTokenPrice,
+        ),
       ),
       perpPosition.hasOpenOrders(),
     );
@@ -1891,7 +1915,10 @@ export class PerpInfo {
     return this.quote.add(worstCase);
   }
 
-  static emptyFromPerpMarket(perpMarket: PerpMarket): PerpInfo {
+  static emptyFromPerpMarket(
+    perpMarket: PerpMarket,
+    settleTokenPrice: I80F48 = ONE_I80F48(),
+  ): PerpInfo {
     return new PerpInfo(
       perpMarket.perpMarketIndex,
       perpMarket.settleTokenIndex,
@@ -1907,8 +1934,10 @@ export class PerpInfo {
       new BN(0),
       ZERO_I80F48(),
       new Prices(
-        perpMarket.price,
-        I80F48.fromNumber(perpMarket.stablePriceModel.stablePrice),
+        perpMarket.price.div(settleTokenPrice),
+        I80F48.fromNumber(perpMarket.stablePriceModel.stablePrice).div(
+          settleTokenPrice,
+        ),
       ),
       false,
     );
@@ -935,9 +935,13 @@ export class MangoAccount {
   ): number {
     const perpMarket = group.getPerpMarketByMarketIndex(perpMarketIndex);
     const hc = HealthCache.fromMangoAccount(group, this);
+    // the health cache uses perp prices in settle token units
+    const settleBank = group.getFirstBankByTokenIndex(
+      perpMarket.settleTokenIndex,
+    );
     const baseLots = hc.getMaxPerpForHealthRatio(
       perpMarket,
-      perpMarket.price,
+      perpMarket.price.div(settleBank.price),
       PerpOrderSide.bid,
       I80F48.fromNumber(2),
     );
@@ -962,9 +966,13 @@ export class MangoAccount {
   ): number {
     const perpMarket = group.getPerpMarketByMarketIndex(perpMarketIndex);
     const hc = HealthCache.fromMangoAccount(group, this);
+    // the health cache uses perp prices in settle token units
+    const settleBank = group.getFirstBankByTokenIndex(
+      perpMarket.settleTokenIndex,
+    );
     const baseLots = hc.getMaxPerpForHealthRatio(
       perpMarket,
-      perpMarket.price,
+      perpMarket.price.div(settleBank.price),
       PerpOrderSide.ask,
       I80F48.fromNumber(2),
     );
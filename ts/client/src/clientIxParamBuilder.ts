@@ -260,6 +260,16 @@ export interface IxGateParams {
   OpenbookV2SettleFunds: boolean;
   AdminTokenWithdrawFees: boolean;
   AdminPerpWithdrawFees: boolean;
+  TwapOracleCreate: boolean;
+  TwapOracleUpdate: boolean;
+  TokenInsuranceFundCreate: boolean;
+  TokenInsuranceFundDeposit: boolean;
+  TokenInsuranceFundWithdraw: boolean;
+  PerpConditionalOrderCreate: boolean;
+  PerpConditionalOrderCancel: boolean;
+  PerpConditionalOrderTrigger: boolean;
+  PerpIsolatedMarginTransfer: boolean;
+  PerpLiqIsolatedPosition: boolean;
 }
 
 // Default with all ixs enabled, use with buildIxGate
@@ -334,6 +344,16 @@ export const TrueIxGateParams: IxGateParams = {
   OpenbookV2SettleFunds: true,
   AdminTokenWithdrawFees: true,
   AdminPerpWithdrawFees: true,
+  TwapOracleCreate: true,
+  TwapOracleUpdate: true,
+  TokenInsuranceFundCreate: true,
+  TokenInsuranceFundDeposit: true,
+  TokenInsuranceFundWithdraw: true,
+  PerpConditionalOrderCreate: true,
+  PerpConditionalOrderCancel: true,
+  PerpConditionalOrderTrigger: true,
+  PerpIsolatedMarginTransfer: true,
+  PerpLiqIsolatedPosition: true,
 };
 
 // build ix gate e.g. buildIxGate(Builder(TrueIxGateParams).TokenDeposit(false).build()).toNumber(),
@@ -418,6 +438,16 @@ export function buildIxGate(p: IxGateParams): BN {
   toggleIx(ixGate, p, 'OpenbookV2SettleFunds', 63);
   toggleIx(ixGate, p, 'AdminTokenWithdrawFees', 65);
   toggleIx(ixGate, p, 'AdminPerpWithdrawFees', 66);
+  toggleIx(ixGate, p, 'TwapOracleCreate', 67);
+  toggleIx(ixGate, p, 'TwapOracleUpdate', 68);
+  toggleIx(ixGate, p, 'TokenInsuranceFundCreate', 69);
+  toggleIx(ixGate, p, 'TokenInsuranceFundDeposit', 70);
+  toggleIx(ixGate, p, 'TokenInsuranceFundWithdraw', 71);
+  toggleIx(ixGate, p, 'PerpConditionalOrderCreate', 72);
+  toggleIx(ixGate, p, 'PerpConditionalOrderCancel', 73);
+  toggleIx(ixGate, p, 'PerpConditionalOrderTrigger', 74);
+  toggleIx(ixGate, p, 'PerpIsolatedMarginTransfer', 75);
+  toggleIx(ixGate, p, 'PerpLiqIsolatedPosition', 76);
 
   return ixGate;
 }
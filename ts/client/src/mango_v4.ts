@@ -314,6 +314,213 @@ export type MangoV4 = {
         }
       ]
     },
+    {
+      "name": "tokenInsuranceFundCreate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "mintInfo",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "mint"
+          ]
+        },
+        {
+          "name": "mint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceFund",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "TokenInsuranceFund"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              }
+            ]
+          }
+        },
+        {
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "TokenInsuranceFundVault"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenIndex",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "tokenInsuranceFundDeposit",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "insuranceFund",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "vault"
+          ]
+        },
+        {
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenAuthority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "tokenInsuranceFundWithdraw",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "insuranceFund",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "vault"
+          ]
+        },
+        {
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
     {
       "name": "ixGateSet",
       "accounts": [
@@ -1299,7 +1506,7 @@ export type MangoV4 = {
       ]
     },
     {
-      "name": "accountExpand",
+      "name": "accountCreateV3",
       "accounts": [
         {
           "name": "group",
@@ -1310,10 +1517,30 @@ export type MangoV4 = {
           "name": "account",
           "isMut": true,
           "isSigner": false,
-          "relations": [
-            "group",
-            "owner"
-          ]
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "MangoAccount"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "owner"
+              },
+              {
+                "kind": "arg",
+                "type": "u32",
+                "path": "account_num"
+              }
+            ]
+          }
         },
         {
           "name": "owner",
@@ -1332,6 +1559,10 @@ export type MangoV4 = {
         }
       ],
       "args": [
+        {
+          "name": "accountNum",
+          "type": "u32"
+        },
         {
           "name": "tokenCount",
           "type": "u8"
@@ -1347,11 +1578,27 @@ export type MangoV4 = {
         {
           "name": "perpOoCount",
           "type": "u8"
+        },
+        {
+          "name": "tokenConditionalSwapCount",
+          "type": "u8"
+        },
+        {
+          "name": "openbookV2Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpConditionalOrderCount",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
         }
       ]
     },
     {
-      "name": "accountExpandV2",
+      "name": "accountExpand",
       "accounts": [
         {
           "name": "group",
@@ -1399,15 +1646,11 @@ export type MangoV4 = {
         {
           "name": "perpOoCount",
           "type": "u8"
-        },
-        {
-          "name": "tokenConditionalSwapCount",
-          "type": "u8"
         }
       ]
     },
     {
-      "name": "accountEdit",
+      "name": "accountExpandV2",
       "accounts": [
         {
           "name": "group",
@@ -1427,8 +1670,128 @@ export type MangoV4 = {
           "name": "owner",
           "isMut": false,
           "isSigner": true
-        }
-      ],
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenCount",
+          "type": "u8"
+        },
+        {
+          "name": "serum3Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpOoCount",
+          "type": "u8"
+        },
+        {
+          "name": "tokenConditionalSwapCount",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "accountExpandV3",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "owner"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenCount",
+          "type": "u8"
+        },
+        {
+          "name": "serum3Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpOoCount",
+          "type": "u8"
+        },
+        {
+          "name": "tokenConditionalSwapCount",
+          "type": "u8"
+        },
+        {
+          "name": "openbookV2Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpConditionalOrderCount",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "accountEdit",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "owner"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        }
+      ],
       "args": [
         {
           "name": "nameOpt",
@@ -1769,6 +2132,104 @@ export type MangoV4 = {
         }
       ]
     },
+    {
+      "name": "twapOracleCreate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "oracle",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "TwapOracle"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "account": "Mint",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "mint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "windowSecs",
+          "type": "u64"
+        },
+        {
+          "name": "maxSampleDeviation",
+          "type": "f32"
+        }
+      ]
+    },
+    {
+      "name": "twapOracleUpdate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "pool"
+          ]
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
     {
       "name": "tokenDeposit",
       "accounts": [
@@ -3845,7 +4306,11 @@ export type MangoV4 = {
       "args": []
     },
     {
-      "name": "perpPlaceOrder",
+      "name": "perpIsolatedMarginTransfer",
+      "docs": [
+        "Moves settle token deposits into (positive amount) or out of (negative amount)",
+        "the isolated collateral of a perp position, making it isolated if needed."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -3867,51 +4332,101 @@ export type MangoV4 = {
         },
         {
           "name": "perpMarket",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false,
           "relations": [
-            "group",
-            "bids",
-            "asks",
-            "event_queue",
-            "oracle"
+            "group"
           ]
         },
         {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "eventQueue",
+          "name": "settleBank",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "oracle",
+          "name": "settleOracle",
           "isMut": false,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "side",
-          "type": {
-            "defined": "Side"
-          }
-        },
-        {
-          "name": "priceLots",
+          "name": "amount",
           "type": "i64"
-        },
+        }
+      ]
+    },
+    {
+      "name": "perpPlaceOrder",
+      "accounts": [
         {
-          "name": "maxBaseLots",
-          "type": "i64"
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "bids",
+            "asks",
+            "event_queue",
+            "oracle"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
+        },
+        {
+          "name": "priceLots",
+          "type": "i64"
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64"
         },
         {
           "name": "maxQuoteLots",
@@ -4819,6 +5334,106 @@ export type MangoV4 = {
         }
       ]
     },
+    {
+      "name": "perpLiqIsolatedPosition",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "insurance_vault"
+          ]
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "oracle"
+          ]
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "liqor",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "liqorOwner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "liqee",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "settleVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "insuranceBankVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "maxBaseTransfer",
+          "type": "i64"
+        }
+      ]
+    },
     {
       "name": "perpLiqNegativePnlOrBankruptcy",
       "accounts": [
@@ -5160,7 +5775,15 @@ export type MangoV4 = {
       ]
     },
     {
-      "name": "tokenConditionalSwapCancel",
+      "name": "tokenConditionalSwapCreateTwap",
+      "docs": [
+        "Like token_conditional_swap_create_v2, but executes in slices of at most",
+        "interval_max_sell native sell tokens, one slice per interval_seconds.",
+        "",
+        "Passing interval_seconds = 0 creates a regular tcs. If",
+        "premium_auction_duration_seconds is nonzero, price_premium_rate is the maximum",
+        "premium of a linear auction, see token_conditional_swap_create_linear_auction."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -5182,18 +5805,15 @@ export type MangoV4 = {
         },
         {
           "name": "buyBank",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false,
-          "docs": [
-            "The bank's token_index is checked at #1"
-          ],
           "relations": [
             "group"
           ]
         },
         {
           "name": "sellBank",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false,
           "relations": [
             "group"
@@ -5202,17 +5822,72 @@ export type MangoV4 = {
       ],
       "args": [
         {
-          "name": "tokenConditionalSwapIndex",
-          "type": "u8"
+          "name": "maxBuy",
+          "type": "u64"
         },
         {
-          "name": "tokenConditionalSwapId",
+          "name": "maxSell",
+          "type": "u64"
+        },
+        {
+          "name": "expiryTimestamp",
+          "type": "u64"
+        },
+        {
+          "name": "priceLowerLimit",
+          "type": "f64"
+        },
+        {
+          "name": "priceUpperLimit",
+          "type": "f64"
+        },
+        {
+          "name": "pricePremiumRate",
+          "type": "f64"
+        },
+        {
+          "name": "allowCreatingDeposits",
+          "type": "bool"
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool"
+        },
+        {
+          "name": "displayPriceStyle",
+          "type": {
+            "defined": "TokenConditionalSwapDisplayPriceStyle"
+          }
+        },
+        {
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
+        },
+        {
+          "name": "intervalSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "intervalMaxSell",
+          "type": "u64"
+        },
+        {
+          "name": "premiumAuctionDurationSeconds",
           "type": "u64"
         }
       ]
     },
     {
-      "name": "tokenConditionalSwapTrigger",
+      "name": "tokenConditionalSwapCreateLinearAuction",
+      "docs": [
+        "Like token_conditional_swap_create_v2, but the premium is a linear auction.",
+        "",
+        "The auction starts when the tcs is first triggered with the price in range: the",
+        "premium then rises from zero to max_price_premium_rate over",
+        "premium_auction_duration_seconds. Triggering with the price out of range resets it."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -5220,7 +5895,7 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "liqee",
+          "name": "account",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -5228,109 +5903,181 @@ export type MangoV4 = {
           ]
         },
         {
-          "name": "liqor",
-          "isMut": true,
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "buyBank",
+          "isMut": false,
           "isSigner": false,
           "relations": [
             "group"
           ]
         },
         {
-          "name": "liqorAuthority",
+          "name": "sellBank",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         }
       ],
       "args": [
         {
-          "name": "tokenConditionalSwapIndex",
-          "type": "u8"
+          "name": "maxBuy",
+          "type": "u64"
         },
         {
-          "name": "tokenConditionalSwapId",
+          "name": "maxSell",
           "type": "u64"
         },
         {
-          "name": "maxBuyTokenToLiqee",
+          "name": "expiryTimestamp",
           "type": "u64"
         },
         {
-          "name": "maxSellTokenToLiqor",
+          "name": "priceLowerLimit",
+          "type": "f64"
+        },
+        {
+          "name": "priceUpperLimit",
+          "type": "f64"
+        },
+        {
+          "name": "maxPricePremiumRate",
+          "type": "f64"
+        },
+        {
+          "name": "allowCreatingDeposits",
+          "type": "bool"
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool"
+        },
+        {
+          "name": "displayPriceStyle",
+          "type": {
+            "defined": "TokenConditionalSwapDisplayPriceStyle"
+          }
+        },
+        {
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
+        },
+        {
+          "name": "premiumAuctionDurationSeconds",
           "type": "u64"
         }
       ]
     },
     {
-      "name": "altSet",
+      "name": "tokenConditionalSwapCancel",
       "accounts": [
         {
           "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "admin"
+            "group"
           ]
         },
         {
-          "name": "admin",
+          "name": "authority",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "addressLookupTable",
+          "name": "buyBank",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "docs": [
+            "The bank's token_index is checked at #1"
+          ],
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "sellBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         }
       ],
       "args": [
         {
-          "name": "index",
+          "name": "tokenConditionalSwapIndex",
           "type": "u8"
+        },
+        {
+          "name": "tokenConditionalSwapId",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "altExtend",
+      "name": "tokenConditionalSwapTrigger",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "liqee",
+          "isMut": true,
           "isSigner": false,
           "relations": [
-            "admin"
+            "group"
           ]
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
+          "name": "liqor",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "payer",
+          "name": "liqorAuthority",
           "isMut": false,
           "isSigner": true
-        },
-        {
-          "name": "addressLookupTable",
-          "isMut": true,
-          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "index",
+          "name": "tokenConditionalSwapIndex",
           "type": "u8"
         },
         {
-          "name": "newAddresses",
-          "type": {
-            "vec": "publicKey"
-          }
+          "name": "tokenConditionalSwapId",
+          "type": "u64"
+        },
+        {
+          "name": "maxBuyTokenToLiqee",
+          "type": "u64"
+        },
+        {
+          "name": "maxSellTokenToLiqor",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "computeAccountData",
+      "name": "perpConditionalOrderCreate",
       "accounts": [
         {
           "name": "group",
@@ -5339,134 +6086,71 @@ export type MangoV4 = {
         },
         {
           "name": "account",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "openbookV2RegisterMarket",
-      "docs": [
-        "",
-        "OpenbookV2",
-        ""
-      ],
-      "accounts": [
-        {
-          "name": "group",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "admin"
+            "group"
           ]
         },
         {
-          "name": "admin",
+          "name": "authority",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "openbookV2Program",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "openbookV2MarketExternal",
+          "name": "perpMarket",
           "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "openbookV2Market",
-          "isMut": true,
           "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "OpenbookV2Market"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "openbook_v2_market_external"
-              }
-            ]
+          "relations": [
+            "group"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
+          "type": {
+            "defined": "Side"
           }
         },
         {
-          "name": "indexReservation",
-          "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "OpenbookV2Index"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "group"
-              },
-              {
-                "kind": "arg",
-                "type": "u16",
-                "path": "market_index"
-              }
-            ]
-          }
+          "name": "maxBaseLots",
+          "type": "i64"
         },
         {
-          "name": "quoteBank",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "baseBank",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "priceLowerLimit",
+          "type": "f64"
         },
         {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
+          "name": "priceUpperLimit",
+          "type": "f64"
         },
         {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
+          "name": "pricePremiumRate",
+          "type": "f64"
+        },
         {
-          "name": "marketIndex",
-          "type": "u16"
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "name",
-          "type": "string"
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
         }
       ]
     },
     {
-      "name": "openbookV2EditMarket",
+      "name": "perpConditionalOrderCreatePlaceOrder",
+      "docs": [
+        "Creates a perp conditional order that places an order into the book once",
+        "the oracle price is in range, like a stop or take-profit order."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -5474,13 +6158,21 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "admin",
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "authority",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "market",
-          "isMut": true,
+          "name": "perpMarket",
+          "isMut": false,
           "isSigner": false,
           "relations": [
             "group"
@@ -5489,66 +6181,81 @@ export type MangoV4 = {
       ],
       "args": [
         {
-          "name": "reduceOnlyOpt",
+          "name": "side",
           "type": {
-            "option": "bool"
+            "defined": "Side"
           }
         },
         {
-          "name": "forceCloseOpt",
+          "name": "priceLowerLimit",
+          "type": "f64"
+        },
+        {
+          "name": "priceUpperLimit",
+          "type": "f64"
+        },
+        {
+          "name": "priceLots",
+          "type": "i64"
+        },
+        {
+          "name": "pegToOracle",
+          "type": "bool"
+        },
+        {
+          "name": "pegLimit",
+          "type": "i64"
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64"
+        },
+        {
+          "name": "maxQuoteLots",
+          "type": "i64"
+        },
+        {
+          "name": "clientOrderId",
+          "type": "u64"
+        },
+        {
+          "name": "orderType",
           "type": {
-            "option": "bool"
+            "defined": "PlaceOrderType"
           }
-        }
-      ]
-    },
-    {
-      "name": "openbookV2DeregisterMarket",
-      "accounts": [
+        },
         {
-          "name": "group",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "admin"
-          ]
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "SelfTradeBehavior"
+          }
         },
         {
-          "name": "admin",
-          "isMut": false,
-          "isSigner": true
+          "name": "reduceOnly",
+          "type": "bool"
         },
         {
-          "name": "openbookV2Market",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "timeInForce",
+          "type": "u16"
         },
         {
-          "name": "indexReservation",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "triggerPremium",
+          "type": "u64"
         },
         {
-          "name": "solDestination",
-          "isMut": true,
-          "isSigner": false
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
         }
-      ],
-      "args": []
+      ]
     },
     {
-      "name": "openbookV2CreateOpenOrders",
+      "name": "perpConditionalOrderCancel",
       "accounts": [
         {
           "name": "group",
@@ -5567,86 +6274,21 @@ export type MangoV4 = {
           "name": "authority",
           "isMut": false,
           "isSigner": true
-        },
-        {
-          "name": "openbookV2Market",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group",
-            "openbook_v2_program",
-            "openbook_v2_market_external"
-          ]
-        },
-        {
-          "name": "openbookV2Program",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "openbookV2MarketExternal",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "openOrders",
-          "isMut": true,
-          "isSigner": false,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "type": "string",
-                "value": "OpenOrders"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "openbook_v2_market"
-              },
-              {
-                "kind": "account",
-                "type": "publicKey",
-                "path": "openbook_v2_market_external"
-              },
-              {
-                "kind": "arg",
-                "type": "u32",
-                "path": "account_num"
-              }
-            ],
-            "programId": {
-              "kind": "account",
-              "type": "publicKey",
-              "path": "openbook_v2_program"
-            }
-          }
-        },
-        {
-          "name": "payer",
-          "isMut": true,
-          "isSigner": true
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "rent",
-          "isMut": false,
-          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "accountNum",
-          "type": "u32"
+          "name": "perpConditionalOrderIndex",
+          "type": "u8"
+        },
+        {
+          "name": "perpConditionalOrderId",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "openbookV2CloseOpenOrders",
+      "name": "perpConditionalOrderTrigger",
       "accounts": [
         {
           "name": "group",
@@ -5654,7 +6296,7 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -5662,45 +6304,50 @@ export type MangoV4 = {
           ]
         },
         {
-          "name": "authority",
+          "name": "liqor",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "liqorAuthority",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "openbookV2Market",
-          "isMut": false,
+          "name": "perpMarket",
+          "isMut": true,
           "isSigner": false,
           "relations": [
             "group",
-            "openbook_v2_program",
-            "openbook_v2_market_external"
+            "oracle"
           ]
         },
         {
-          "name": "openbookV2Program",
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "openbookV2MarketExternal",
-          "isMut": false,
-          "isSigner": false
+          "name": "perpConditionalOrderIndex",
+          "type": "u8"
         },
         {
-          "name": "openOrders",
-          "isMut": true,
-          "isSigner": false
+          "name": "perpConditionalOrderId",
+          "type": "u64"
         },
         {
-          "name": "solDestination",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxBaseLots",
+          "type": "i64"
         }
-      ],
-      "args": []
+      ]
     },
     {
-      "name": "openbookV2PlaceOrder",
+      "name": "perpConditionalOrderTriggerPlaceOrder",
       "accounts": [
         {
           "name": "group",
@@ -5716,33 +6363,28 @@ export type MangoV4 = {
           ]
         },
         {
-          "name": "authority",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "openOrders",
+          "name": "triggerer",
           "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "openbookV2Market",
-          "isMut": false,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "openbookV2Program",
+          "name": "triggererAuthority",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "openbookV2MarketExternal",
+          "name": "perpMarket",
           "isMut": true,
           "isSigner": false,
           "relations": [
+            "group",
             "bids",
             "asks",
-            "event_queue"
+            "event_queue",
+            "oracle"
           ]
         },
         {
@@ -5761,87 +6403,97 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "marketBaseVault",
-          "isMut": true,
+          "name": "oracle",
+          "isMut": false,
           "isSigner": false
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "marketQuoteVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "perpConditionalOrderIndex",
+          "type": "u8"
         },
         {
-          "name": "marketVaultSigner",
-          "isMut": false,
-          "isSigner": false
+          "name": "perpConditionalOrderId",
+          "type": "u64"
         },
         {
-          "name": "payerBank",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The bank that pays for the order, if necessary"
-          ],
-          "relations": [
-            "group"
-          ]
-        },
+          "name": "limit",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "altSet",
+      "accounts": [
         {
-          "name": "payerVault",
+          "name": "group",
           "isMut": true,
           "isSigner": false,
-          "docs": [
-            "The bank vault that pays for the order, if necessary"
+          "relations": [
+            "admin"
           ]
         },
         {
-          "name": "payerOracle",
+          "name": "admin",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
+          "name": "addressLookupTable",
+          "isMut": true,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "side",
+          "name": "index",
           "type": "u8"
-        },
+        }
+      ]
+    },
+    {
+      "name": "altExtend",
+      "accounts": [
         {
-          "name": "limitPrice",
-          "type": "u64"
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
         },
         {
-          "name": "maxBaseQty",
-          "type": "u64"
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "maxNativeQuoteQtyIncludingFees",
-          "type": "u64"
+          "name": "payer",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "selfTradeBehavior",
-          "type": "u8"
-        },
+          "name": "addressLookupTable",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
         {
-          "name": "orderType",
+          "name": "index",
           "type": "u8"
         },
         {
-          "name": "clientOrderId",
-          "type": "u64"
-        },
-        {
-          "name": "limit",
-          "type": "u16"
+          "name": "newAddresses",
+          "type": {
+            "vec": "publicKey"
+          }
         }
       ]
     },
     {
-      "name": "openbookV2PlaceTakerOrder",
+      "name": "computeAccountData",
       "accounts": [
         {
           "name": "group",
@@ -5850,140 +6502,134 @@ export type MangoV4 = {
         },
         {
           "name": "account",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "authority",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "openbookV2Market",
           "isMut": false,
           "isSigner": false,
           "relations": [
-            "group",
-            "openbook_v2_program",
-            "openbook_v2_market_external"
+            "group"
           ]
-        },
-        {
-          "name": "openbookV2Program",
-          "isMut": false,
-          "isSigner": false
-        },
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "openbookV2RegisterMarket",
+      "docs": [
+        "",
+        "OpenbookV2",
+        ""
+      ],
+      "accounts": [
         {
-          "name": "openbookV2MarketExternal",
+          "name": "group",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "bids",
-            "asks",
-            "event_queue"
+            "admin"
           ]
         },
         {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "eventQueue",
-          "isMut": true,
+          "name": "openbookV2Program",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "marketRequestQueue",
-          "isMut": true,
+          "name": "openbookV2MarketExternal",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "marketBaseVault",
+          "name": "openbookV2Market",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "OpenbookV2Market"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "openbook_v2_market_external"
+              }
+            ]
+          }
         },
         {
-          "name": "marketQuoteVault",
+          "name": "indexReservation",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "OpenbookV2Index"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "market_index"
+              }
+            ]
+          }
         },
         {
-          "name": "marketVaultSigner",
+          "name": "quoteBank",
           "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "payerBank",
-          "isMut": true,
           "isSigner": false,
-          "docs": [
-            "The bank that pays for the order, if necessary"
-          ],
           "relations": [
             "group"
           ]
         },
         {
-          "name": "payerVault",
-          "isMut": true,
+          "name": "baseBank",
+          "isMut": false,
           "isSigner": false,
-          "docs": [
-            "The bank vault that pays for the order, if necessary"
+          "relations": [
+            "group"
           ]
         },
         {
-          "name": "payerOracle",
-          "isMut": false,
-          "isSigner": false
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
         },
         {
-          "name": "tokenProgram",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "side",
-          "type": "u8"
-        },
-        {
-          "name": "limitPrice",
-          "type": "u64"
-        },
-        {
-          "name": "maxBaseQty",
-          "type": "u64"
-        },
-        {
-          "name": "maxNativeQuoteQtyIncludingFees",
-          "type": "u64"
-        },
-        {
-          "name": "selfTradeBehavior",
-          "type": "u8"
-        },
-        {
-          "name": "clientOrderId",
-          "type": "u64"
+          "name": "marketIndex",
+          "type": "u16"
         },
         {
-          "name": "limit",
-          "type": "u16"
+          "name": "name",
+          "type": "string"
         }
       ]
     },
     {
-      "name": "openbookV2CancelOrder",
+      "name": "openbookV2EditMarket",
       "accounts": [
         {
           "name": "group",
@@ -5991,71 +6637,81 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "authority",
+          "name": "admin",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "openOrders",
+          "name": "market",
           "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "openbookV2Market",
-          "isMut": false,
           "isSigner": false,
           "relations": [
-            "group",
-            "openbook_v2_program",
-            "openbook_v2_market_external"
+            "group"
           ]
-        },
+        }
+      ],
+      "args": [
         {
-          "name": "openbookV2Program",
-          "isMut": false,
-          "isSigner": false
+          "name": "reduceOnlyOpt",
+          "type": {
+            "option": "bool"
+          }
         },
         {
-          "name": "openbookV2MarketExternal",
+          "name": "forceCloseOpt",
+          "type": {
+            "option": "bool"
+          }
+        }
+      ]
+    },
+    {
+      "name": "openbookV2DeregisterMarket",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
           "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "openbookV2Market",
+          "isMut": true,
           "isSigner": false,
           "relations": [
-            "bids",
-            "asks"
+            "group"
           ]
         },
         {
-          "name": "bids",
+          "name": "indexReservation",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "asks",
+          "name": "solDestination",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "side",
-          "type": "u8"
         },
         {
-          "name": "orderId",
-          "type": "u128"
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "openbookV2SettleFunds",
+      "name": "openbookV2CreateOpenOrders",
       "accounts": [
         {
           "name": "group",
@@ -6075,11 +6731,6 @@ export type MangoV4 = {
           "isMut": false,
           "isSigner": true
         },
-        {
-          "name": "openOrders",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "openbookV2Market",
           "isMut": false,
@@ -6097,29 +6748,76 @@ export type MangoV4 = {
         },
         {
           "name": "openbookV2MarketExternal",
-          "isMut": true,
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "marketBaseVault",
+          "name": "openOrders",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "OpenOrders"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "openbook_v2_market"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "openbook_v2_market_external"
+              },
+              {
+                "kind": "arg",
+                "type": "u32",
+                "path": "account_num"
+              }
+            ],
+            "programId": {
+              "kind": "account",
+              "type": "publicKey",
+              "path": "openbook_v2_program"
+            }
+          }
         },
         {
-          "name": "marketQuoteVault",
+          "name": "payer",
           "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "marketVaultSigner",
+          "name": "rent",
           "isMut": false,
-          "isSigner": false,
-          "docs": [
-            "needed for the automatic settle_funds call"
-          ]
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "accountNum",
+          "type": "u32"
+        }
+      ]
+    },
+    {
+      "name": "openbookV2CloseOpenOrders",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "quoteBank",
+          "name": "account",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -6127,48 +6825,50 @@ export type MangoV4 = {
           ]
         },
         {
-          "name": "quoteVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "baseBank",
-          "isMut": true,
+          "name": "openbookV2Market",
+          "isMut": false,
           "isSigner": false,
           "relations": [
-            "group"
+            "group",
+            "openbook_v2_program",
+            "openbook_v2_market_external"
           ]
         },
         {
-          "name": "baseVault",
-          "isMut": true,
+          "name": "openbookV2Program",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "quoteOracle",
+          "name": "openbookV2MarketExternal",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "baseOracle",
-          "isMut": false,
+          "name": "openOrders",
+          "isMut": true,
           "isSigner": false
         },
         {
-          "name": "tokenProgram",
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": [
-        {
-          "name": "feesToDao",
-          "type": "bool"
-        }
-      ]
+      "args": []
     },
     {
-      "name": "openbookV2LiqForceCancelOrders",
+      "name": "openbookV2PlaceOrder",
       "accounts": [
         {
           "name": "group",
@@ -6183,6 +6883,11 @@ export type MangoV4 = {
             "group"
           ]
         },
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
         {
           "name": "openOrders",
           "isMut": true,
@@ -6205,7 +6910,7 @@ export type MangoV4 = {
         },
         {
           "name": "openbookV2MarketExternal",
-          "isMut": false,
+          "isMut": true,
           "isSigner": false,
           "relations": [
             "bids",
@@ -6244,29 +6949,27 @@ export type MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "quoteBank",
+          "name": "payerBank",
           "isMut": true,
           "isSigner": false,
+          "docs": [
+            "The bank that pays for the order, if necessary"
+          ],
           "relations": [
             "group"
           ]
         },
         {
-          "name": "quoteVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "baseBank",
+          "name": "payerVault",
           "isMut": true,
           "isSigner": false,
-          "relations": [
-            "group"
+          "docs": [
+            "The bank vault that pays for the order, if necessary"
           ]
         },
         {
-          "name": "baseVault",
-          "isMut": true,
+          "name": "payerOracle",
+          "isMut": false,
           "isSigner": false
         },
         {
@@ -6277,13 +6980,41 @@ export type MangoV4 = {
       ],
       "args": [
         {
-          "name": "limit",
+          "name": "side",
+          "type": "u8"
+        },
+        {
+          "name": "limitPrice",
+          "type": "u64"
+        },
+        {
+          "name": "maxBaseQty",
+          "type": "u64"
+        },
+        {
+          "name": "maxNativeQuoteQtyIncludingFees",
+          "type": "u64"
+        },
+        {
+          "name": "selfTradeBehavior",
+          "type": "u8"
+        },
+        {
+          "name": "orderType",
           "type": "u8"
+        },
+        {
+          "name": "clientOrderId",
+          "type": "u64"
+        },
+        {
+          "name": "limit",
+          "type": "u16"
         }
       ]
     },
     {
-      "name": "openbookV2CancelAllOrders",
+      "name": "openbookV2PlaceTakerOrder",
       "accounts": [
         {
           "name": "group",
@@ -6303,11 +7034,6 @@ export type MangoV4 = {
           "isMut": false,
           "isSigner": true
         },
-        {
-          "name": "openOrders",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "openbookV2Market",
           "isMut": false,
@@ -6325,11 +7051,12 @@ export type MangoV4 = {
         },
         {
           "name": "openbookV2MarketExternal",
-          "isMut": false,
+          "isMut": true,
           "isSigner": false,
           "relations": [
             "bids",
-            "asks"
+            "asks",
+            "event_queue"
           ]
         },
         {
@@ -6341,17 +7068,482 @@ export type MangoV4 = {
           "name": "asks",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
+        },
         {
-          "name": "limit",
-          "type": "u8"
-        }
-      ]
-    },
-    {
-      "name": "benchmark",
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketBaseVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketQuoteVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketVaultSigner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "payerBank",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The bank that pays for the order, if necessary"
+          ],
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "payerVault",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The bank vault that pays for the order, if necessary"
+          ]
+        },
+        {
+          "name": "payerOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "receiverBank",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The bank that receives the funds from the order"
+          ],
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "receiverVault",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The bank vault that receives the funds from the order"
+          ]
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
+          "type": "u8"
+        },
+        {
+          "name": "limitPrice",
+          "type": "u64"
+        },
+        {
+          "name": "maxBaseQty",
+          "type": "u64"
+        },
+        {
+          "name": "maxNativeQuoteQtyIncludingFees",
+          "type": "u64"
+        },
+        {
+          "name": "selfTradeBehavior",
+          "type": "u8"
+        },
+        {
+          "name": "clientOrderId",
+          "type": "u64"
+        },
+        {
+          "name": "limit",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "openbookV2CancelOrder",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "openOrders",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2Market",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "openbook_v2_program",
+            "openbook_v2_market_external"
+          ]
+        },
+        {
+          "name": "openbookV2Program",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2MarketExternal",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "bids",
+            "asks"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
+          "type": "u8"
+        },
+        {
+          "name": "orderId",
+          "type": "u128"
+        }
+      ]
+    },
+    {
+      "name": "openbookV2SettleFunds",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "openOrders",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2Market",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "openbook_v2_program",
+            "openbook_v2_market_external"
+          ]
+        },
+        {
+          "name": "openbookV2Program",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2MarketExternal",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketBaseVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketQuoteVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketVaultSigner",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "needed for the automatic settle_funds call"
+          ]
+        },
+        {
+          "name": "quoteBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "quoteVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "baseBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "baseVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "quoteOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "baseOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "feesToDao",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "openbookV2LiqForceCancelOrders",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "openOrders",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2Market",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "openbook_v2_program",
+            "openbook_v2_market_external"
+          ]
+        },
+        {
+          "name": "openbookV2Program",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2MarketExternal",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "bids",
+            "asks",
+            "event_queue"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketBaseVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketQuoteVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "marketVaultSigner",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "quoteBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "quoteVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "baseBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "baseVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "limit",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "openbookV2CancelAllOrders",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "openOrders",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2Market",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "openbook_v2_program",
+            "openbook_v2_market_external"
+          ]
+        },
+        {
+          "name": "openbookV2Program",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "openbookV2MarketExternal",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "bids",
+            "asks"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "limit",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "benchmark",
       "docs": [
         "",
         "benchmark",
@@ -6673,12 +7865,48 @@ export type MangoV4 = {
             "name": "flashLoanSwapFeeRate",
             "type": "f32"
           },
+          {
+            "name": "tokenInsuranceFund",
+            "docs": [
+              "The TokenInsuranceFund for this token, or the default pubkey if there is none.",
+              "",
+              "Bankruptcy instructions must use the fund when it exists."
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "padding2",
+            "type": {
+              "array": [
+                "u8",
+                4
+              ]
+            }
+          },
+          {
+            "name": "otherBanksIndexedDeposits",
+            "docs": [
+              "Sum of indexed_deposits/indexed_borrows of all other banks of the same token",
+              "",
+              "Updated in token_update_index_and_rate. Together with this bank's own values this",
+              "approximates the token-wide deposits and borrows, see native_deposits_all_banks()."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "otherBanksIndexedBorrows",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                2092
+                2024
               ]
             }
           }
@@ -7206,6 +8434,110 @@ export type MangoV4 = {
         ]
       }
     },
+    {
+      "name": "twapOracle",
+      "docs": [
+        "Oracle that tracks a time weighted average of the price of a raydium",
+        "concentrated liquidity pool.",
+        "",
+        "The pool must pair the oracle's mint with a token that has the same value and",
+        "decimals as the group's quote token, since the pool price is used as-is.",
+        "",
+        "Anyone may update the oracle. Each sample is weighted by the time elapsed since the",
+        "previous update, and clamped to within max_sample_deviation of the average, so a",
+        "short lived manipulation of the pool price can only move the average a limited",
+        "amount. The deviation reported to the confidence check is the difference between",
+        "the latest (unclamped) sample and the average."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "group",
+            "type": "publicKey"
+          },
+          {
+            "name": "mint",
+            "type": "publicKey"
+          },
+          {
+            "name": "pool",
+            "docs": [
+              "The raydium clmm pool the price is sampled from"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "twapPrice",
+            "docs": [
+              "Time weighted average price, in native quote per native base"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "lastSamplePrice",
+            "docs": [
+              "Pool price at the last update, in native quote per native base"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "maxSampleDeviation",
+            "docs": [
+              "Samples are clamped to twap_price * (1 +- max_sample_deviation)"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "lastUpdateTs",
+            "type": "i64"
+          },
+          {
+            "name": "lastUpdateSlot",
+            "type": "u64"
+          },
+          {
+            "name": "windowSecs",
+            "docs": [
+              "Averaging window in seconds: a sample that stayed in effect this long fully",
+              "replaces the average"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "invert",
+            "docs": [
+              "1 if mint is the pool's token_1, meaning the pool price needs to be inverted"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                7
+              ]
+            }
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                112
+              ]
+            }
+          }
+        ]
+      }
+    },
     {
       "name": "bookSide",
       "type": {
@@ -7569,8 +8901,10 @@ export type MangoV4 = {
           {
             "name": "feesSettled",
             "docs": [
-              "Fees settled in native quote currency",
-              "these are increased when perp_settle_fees is called, and never decreased"
+              "Fees settled in native settle token, converted at the settle token oracle price",
+              "these are increased when perp_settle_fees is called, and never decreased.",
+              "This is what admin_perp_withdraw_fees withdraws, see fees_settled_quote for the",
+              "same amounts in quote units."
             ],
             "type": {
               "defined": "I80F48"
@@ -7684,12 +9018,22 @@ export type MangoV4 = {
             "name": "feesWithdrawn",
             "type": "u64"
           },
+          {
+            "name": "feesSettledQuote",
+            "docs": [
+              "Fees settled in native quote currency, the amounts that perp_settle_fees removed",
+              "from fees_accrued. Only counts settlements since this field was introduced."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                1880
+                1864
               ]
             }
           }
@@ -7804,6 +9148,72 @@ export type MangoV4 = {
           }
         ]
       }
+    },
+    {
+      "name": "tokenInsuranceFund",
+      "docs": [
+        "An insurance fund for a single token.",
+        "",
+        "Bankruptcies in the token, or in perp markets that settle in the token, are",
+        "covered by this fund before the group insurance fund is used and before any",
+        "remaining loss gets socialized.",
+        "",
+        "The vault is owned by the group and holds tokens of the fund's mint."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "group",
+            "type": "publicKey"
+          },
+          {
+            "name": "tokenIndex",
+            "type": "u16"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "vaultBump",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                4
+              ]
+            }
+          },
+          {
+            "name": "mint",
+            "type": "publicKey"
+          },
+          {
+            "name": "vault",
+            "type": "publicKey"
+          },
+          {
+            "name": "totalUsedNative",
+            "docs": [
+              "Total native tokens paid out of the fund to cover bankruptcies"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                128
+              ]
+            }
+          }
+        ]
+      }
     }
   ],
   "types": [
@@ -7987,22 +9397,66 @@ export type MangoV4 = {
               "Includes TokenPosition and free Serum3OpenOrders balances.",
               "Does not include perp upnl or Serum3 reserved amounts."
             ],
-            "type": {
-              "defined": "I80F48"
-            }
+            "type": {
+              "defined": "I80F48"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "Serum3Info",
+      "docs": [
+        "Information about reserved funds on Serum3 open orders accounts.",
+        "",
+        "Note that all \"free\" funds on open orders accounts are added directly",
+        "to the token info. This is only about dealing with the reserved funds",
+        "that might end up as base OR quote tokens, depending on whether the",
+        "open orders execute on not."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "reservedBase",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "reservedQuote",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "baseInfoIndex",
+            "type": "u64"
+          },
+          {
+            "name": "quoteInfoIndex",
+            "type": "u64"
+          },
+          {
+            "name": "marketIndex",
+            "type": "u16"
+          },
+          {
+            "name": "hasZeroFunds",
+            "docs": [
+              "The open orders account has no free or reserved funds"
+            ],
+            "type": "bool"
           }
         ]
       }
     },
     {
-      "name": "Serum3Info",
+      "name": "OpenbookV2Info",
       "docs": [
-        "Information about reserved funds on Serum3 open orders accounts.",
+        "Information about reserved funds on openbook v2 open orders accounts.",
         "",
-        "Note that all \"free\" funds on open orders accounts are added directly",
-        "to the token info. This is only about dealing with the reserved funds",
-        "that might end up as base OR quote tokens, depending on whether the",
-        "open orders execute on not."
+        "Like for Serum3Info, the free funds are added directly to the token info."
       ],
       "type": {
         "kind": "struct",
@@ -8047,7 +9501,11 @@ export type MangoV4 = {
         "Stores information about perp market positions and their open orders.",
         "",
         "Perp markets affect account health indirectly, though the token balance in the",
-        "perp market's settle token. See `effective_token_balances()`."
+        "perp market's settle token. See `effective_token_balances()`.",
+        "",
+        "Perp positions and oracles use the perp quote currency, but the quote amounts and",
+        "prices stored here are converted to settle token native units using the settle",
+        "token's oracle price."
       ],
       "type": {
         "kind": "struct",
@@ -8131,6 +9589,20 @@ export type MangoV4 = {
           {
             "name": "hasOpenFills",
             "type": "bool"
+          },
+          {
+            "name": "isolated",
+            "docs": [
+              "Isolated positions don't contribute to the account's cross-margined health,",
+              "see HealthCache::isolated_perp_health()"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "isolatedCollateral",
+            "type": {
+              "defined": "I80F48"
+            }
           }
         ]
       }
@@ -8182,6 +9654,14 @@ export type MangoV4 = {
           {
             "name": "beingLiquidated",
             "type": "bool"
+          },
+          {
+            "name": "openbookV2Infos",
+            "type": {
+              "vec": {
+                "defined": "OpenbookV2Info"
+              }
+            }
           }
         ]
       }
@@ -8386,6 +9866,80 @@ export type MangoV4 = {
         ]
       }
     },
+    {
+      "name": "OpenbookV2Orders",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "openOrders",
+            "type": "publicKey"
+          },
+          {
+            "name": "baseBorrowsWithoutFee",
+            "docs": [
+              "Tracks the amount of borrows that have flowed into the openbook open orders account.",
+              "These borrows did not have the loan origination fee applied, and that may happen",
+              "later (in openbook_v2_settle_funds) if we can guarantee that the funds were used.",
+              "In particular a place-on-book, cancel, settle should not cost fees."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "quoteBorrowsWithoutFee",
+            "type": "u64"
+          },
+          {
+            "name": "marketIndex",
+            "type": "u16"
+          },
+          {
+            "name": "baseTokenIndex",
+            "docs": [
+              "Store the base/quote token index, so health computations don't need",
+              "to get passed the static OpenbookV2Market to find which tokens a market",
+              "uses and look up the correct oracles."
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "quoteTokenIndex",
+            "type": "u16"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                2
+              ]
+            }
+          },
+          {
+            "name": "baseLotSize",
+            "docs": [
+              "Lot sizes of the external market, copied on open orders creation.",
+              "The openbook v2 open orders account tracks reserved amounts in lots and",
+              "health computations need to convert them to native without loading the market."
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "quoteLotSize",
+            "type": "i64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                48
+              ]
+            }
+          }
+        ]
+      }
+    },
     {
       "name": "PerpPosition",
       "type": {
@@ -8609,12 +10163,43 @@ export type MangoV4 = {
               "defined": "I80F48"
             }
           },
+          {
+            "name": "isolated",
+            "docs": [
+              "Whether the position is in isolated margin mode, see isolated_collateral_indexed"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "padding2",
+            "type": {
+              "array": [
+                "u8",
+                7
+              ]
+            }
+          },
+          {
+            "name": "isolatedCollateralIndexed",
+            "docs": [
+              "Settle token set aside to back an isolated position, indexed by the settle",
+              "bank's deposit_index. Use Bank::isolated_collateral_native() to get native units.",
+              "",
+              "Isolated positions are not part of the account's cross-margined health. Instead,",
+              "this collateral plus the position's health unsettled pnl must stay positive",
+              "and the position gets liquidated on its own. The collateral stays part of the",
+              "settle bank's indexed_deposits and earns deposit interest."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                88
+                64
               ]
             }
           }
@@ -8763,12 +10348,19 @@ export type MangoV4 = {
             "name": "temporaryDelegateExpiry",
             "type": "u64"
           },
+          {
+            "name": "nextPerpConditionalOrderId",
+            "docs": [
+              "Next id to use when adding a perp conditional order"
+            ],
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                160
+                152
               ]
             }
           }
@@ -9287,27 +10879,207 @@ export type MangoV4 = {
             }
           },
           {
-            "name": "timestamp",
-            "type": "u64"
+            "name": "timestamp",
+            "type": "u64"
+          },
+          {
+            "name": "seqNum",
+            "type": "u64"
+          },
+          {
+            "name": "owner",
+            "type": "publicKey"
+          },
+          {
+            "name": "quantity",
+            "type": "i64"
+          },
+          {
+            "name": "padding1",
+            "type": {
+              "array": [
+                "u8",
+                144
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpConditionalOrder",
+      "docs": [
+        "A perp order that stays dormant in the MangoAccount until the perp oracle price",
+        "is in a range.",
+        "",
+        "Depending on `execution` a triggerer then either trades with the account directly,",
+        "like for a TokenConditionalSwap, or places an order into the orderbook on behalf",
+        "of the account."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "id",
+            "type": "u64"
+          },
+          {
+            "name": "maxBaseLots",
+            "docs": [
+              "maximum amount of base lots to trade"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "executedBaseLots",
+            "docs": [
+              "how many base lots were already traded, only used for TradeWithTriggerer"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "timestamp until which the conditional order is valid"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "priceLowerLimit",
+            "docs": [
+              "The perp oracle price must be >= lower_limit and <= upper_limit for execution.",
+              "",
+              "Prices are in native quote per native base, like perp oracle prices.",
+              "",
+              "Example: Stop loss on a SOL-PERP long opened at 20 USDC/SOL, closing",
+              "the position below 18 USDC/SOL: side=Ask, reduce_only, price_lower_limit=0",
+              "and price_upper_limit=toNative(18)."
+            ],
+            "type": "f64"
+          },
+          {
+            "name": "priceUpperLimit",
+            "docs": [
+              "Parallel to price_lower_limit, but an upper limit."
+            ],
+            "type": "f64"
+          },
+          {
+            "name": "pricePremiumRate",
+            "docs": [
+              "The premium to pay over oracle price to incentivize execution, only used",
+              "for TradeWithTriggerer"
+            ],
+            "type": "f64"
+          },
+          {
+            "name": "priceLots",
+            "docs": [
+              "Limit price of the placed order in lots, only used for PlaceOrder.",
+              "",
+              "If peg_to_oracle is set, this is instead the offset to the oracle price",
+              "and the order is placed as an oracle pegged order. Ignored for market orders."
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "pegLimit",
+            "docs": [
+              "Only used for oracle pegged orders, see perp_place_order_pegged"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "maxQuoteLots",
+            "docs": [
+              "Only used for PlaceOrder"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "clientOrderId",
+            "docs": [
+              "Only used for PlaceOrder"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "triggerPremium",
+            "docs": [
+              "Amount of perp quote native paid to the triggerer when the order is placed,",
+              "only used for PlaceOrder"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "perpMarketIndex",
+            "type": "u16"
+          },
+          {
+            "name": "timeInForce",
+            "docs": [
+              "Time in force of the placed order in seconds, 0 meaning forever"
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "hasData",
+            "type": "u8"
+          },
+          {
+            "name": "side",
+            "docs": [
+              "The side the account trades on, stores a Side enum value"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "reduceOnly",
+            "docs": [
+              "may execution only reduce the perp base position?"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "intention",
+            "docs": [
+              "The intention the user had when placing this order, display-only",
+              "",
+              "Stores a TokenConditionalSwapIntention enum value"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "execution",
+            "docs": [
+              "Stores a PerpConditionalOrderExecution enum value"
+            ],
+            "type": "u8"
           },
           {
-            "name": "seqNum",
-            "type": "u64"
+            "name": "orderType",
+            "docs": [
+              "Stores a PlaceOrderType enum value"
+            ],
+            "type": "u8"
           },
           {
-            "name": "owner",
-            "type": "publicKey"
+            "name": "selfTradeBehavior",
+            "docs": [
+              "Stores a SelfTradeBehavior enum value"
+            ],
+            "type": "u8"
           },
           {
-            "name": "quantity",
-            "type": "i64"
+            "name": "pegToOracle",
+            "type": "u8"
           },
           {
-            "name": "padding1",
+            "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                144
+                20
               ]
             }
           }
@@ -9567,12 +11339,67 @@ export type MangoV4 = {
             ],
             "type": "u8"
           },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                7
+              ]
+            }
+          },
+          {
+            "name": "intervalSeconds",
+            "docs": [
+              "If nonzero, the tcs executes in slices: at most interval_max_sell native sell",
+              "tokens can be sold per interval and the next slice unlocks interval_seconds",
+              "after the current slice started."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "intervalMaxSell",
+            "docs": [
+              "Maximum amount of native sell tokens to sell per interval, see interval_seconds"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "intervalStartTimestamp",
+            "docs": [
+              "Timestamp of the first execution in the current interval"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "intervalSold",
+            "docs": [
+              "How many native sell tokens were already sold in the current interval"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "premiumAuctionDurationSeconds",
+            "docs": [
+              "If nonzero, the premium is a linear auction: it starts at zero when the tcs",
+              "is first triggered while the price is in range and rises to price_premium_rate",
+              "over this many seconds. The auction restarts if the price leaves the range."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "premiumAuctionStartTimestamp",
+            "docs": [
+              "Timestamp at which the premium auction started, 0 if it hasn't started"
+            ],
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                111
+                56
               ]
             }
           }
@@ -9757,6 +11584,18 @@ export type MangoV4 = {
           },
           {
             "name": "TokenConditionalSwapTrigger"
+          },
+          {
+            "name": "OpenbookV2LiqForceCancelOrders"
+          },
+          {
+            "name": "OpenbookV2PlaceOrder"
+          },
+          {
+            "name": "OpenbookV2SettleFunds"
+          },
+          {
+            "name": "OpenbookV2PlaceTakeOrder"
           }
         ]
       }
@@ -9981,6 +11820,36 @@ export type MangoV4 = {
           },
           {
             "name": "AdminPerpWithdrawFees"
+          },
+          {
+            "name": "TwapOracleCreate"
+          },
+          {
+            "name": "TwapOracleUpdate"
+          },
+          {
+            "name": "TokenInsuranceFundCreate"
+          },
+          {
+            "name": "TokenInsuranceFundDeposit"
+          },
+          {
+            "name": "TokenInsuranceFundWithdraw"
+          },
+          {
+            "name": "PerpConditionalOrderCreate"
+          },
+          {
+            "name": "PerpConditionalOrderCancel"
+          },
+          {
+            "name": "PerpConditionalOrderTrigger"
+          },
+          {
+            "name": "PerpIsolatedMarginTransfer"
+          },
+          {
+            "name": "PerpLiqIsolatedPosition"
           }
         ]
       }
@@ -10018,6 +11887,12 @@ export type MangoV4 = {
           },
           {
             "name": "SwitchboardV2"
+          },
+          {
+            "name": "PythV2"
+          },
+          {
+            "name": "Twap"
           }
         ]
       }
@@ -10234,104 +12109,425 @@ export type MangoV4 = {
       }
     },
     {
-      "name": "OrderTreeType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Bids"
-          },
-          {
-            "name": "Asks"
-          }
-        ]
-      }
+      "name": "OrderTreeType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Bids"
+          },
+          {
+            "name": "Asks"
+          }
+        ]
+      }
+    },
+    {
+      "name": "EventType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Fill"
+          },
+          {
+            "name": "Out"
+          },
+          {
+            "name": "Liquidate"
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpConditionalOrderExecution",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "TradeWithTriggerer"
+          },
+          {
+            "name": "PlaceOrder"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TokenConditionalSwapDisplayPriceStyle",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "SellTokenPerBuyToken"
+          },
+          {
+            "name": "BuyTokenPerSellToken"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TokenConditionalSwapIntention",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Unknown"
+          },
+          {
+            "name": "StopLoss"
+          },
+          {
+            "name": "TakeProfit"
+          }
+        ]
+      }
+    }
+  ],
+  "events": [
+    {
+      "name": "MangoAccountData",
+      "fields": [
+        {
+          "name": "healthCache",
+          "type": {
+            "defined": "HealthCache"
+          },
+          "index": false
+        },
+        {
+          "name": "initHealth",
+          "type": {
+            "defined": "I80F48"
+          },
+          "index": false
+        },
+        {
+          "name": "maintHealth",
+          "type": {
+            "defined": "I80F48"
+          },
+          "index": false
+        },
+        {
+          "name": "equity",
+          "type": {
+            "defined": "Equity"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpBalanceLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "basePosition",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quotePosition",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "longSettledFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortSettledFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "longFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortFunding",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TokenBalanceLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "indexedPosition",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "depositIndex",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "borrowIndex",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "EventType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Fill"
+      "name": "FlashLoanLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenLoanDetails",
+          "type": {
+            "vec": {
+              "defined": "FlashLoanTokenDetail"
+            }
           },
-          {
-            "name": "Out"
+          "index": false
+        },
+        {
+          "name": "flashLoanType",
+          "type": {
+            "defined": "FlashLoanType"
           },
-          {
-            "name": "Liquidate"
-          }
-        ]
-      }
+          "index": false
+        }
+      ]
     },
     {
-      "name": "TokenConditionalSwapDisplayPriceStyle",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "SellTokenPerBuyToken"
+      "name": "FlashLoanLogV2",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenLoanDetails",
+          "type": {
+            "vec": {
+              "defined": "FlashLoanTokenDetailV2"
+            }
           },
-          {
-            "name": "BuyTokenPerSellToken"
-          }
-        ]
-      }
+          "index": false
+        },
+        {
+          "name": "flashLoanType",
+          "type": {
+            "defined": "FlashLoanType"
+          },
+          "index": false
+        }
+      ]
     },
     {
-      "name": "TokenConditionalSwapIntention",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Unknown"
-          },
-          {
-            "name": "StopLoss"
-          },
-          {
-            "name": "TakeProfit"
-          }
-        ]
-      }
-    }
-  ],
-  "events": [
+      "name": "WithdrawLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
     {
-      "name": "MangoAccountData",
+      "name": "DepositLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "FillLog",
       "fields": [
         {
-          "name": "healthCache",
-          "type": {
-            "defined": "HealthCache"
-          },
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "takerSide",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerSlot",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerOut",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maker",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "initHealth",
-          "type": {
-            "defined": "I80F48"
-          },
+          "name": "makerOrderId",
+          "type": "u128",
           "index": false
         },
         {
-          "name": "maintHealth",
-          "type": {
-            "defined": "I80F48"
-          },
+          "name": "makerFee",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "equity",
-          "type": {
-            "defined": "Equity"
-          },
+          "name": "makerTimestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "taker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "takerOrderId",
+          "type": "u128",
+          "index": false
+        },
+        {
+          "name": "takerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "takerFee",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "i64",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpBalanceLog",
+      "name": "FillLogV2",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10339,49 +12535,84 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "takerSide",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerSlot",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerOut",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maker",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "makerClientOrderId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "basePosition",
-          "type": "i64",
+          "name": "makerFee",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "quotePosition",
-          "type": "i128",
+          "name": "makerTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "longSettledFunding",
-          "type": "i128",
+          "name": "taker",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "shortSettledFunding",
-          "type": "i128",
+          "name": "takerClientOrderId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "longFunding",
-          "type": "i128",
+          "name": "takerFee",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "shortFunding",
-          "type": "i128",
+          "name": "price",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "i64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenBalanceLog",
+      "name": "FillLogV3",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10389,65 +12620,94 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "takerSide",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "indexedPosition",
-          "type": "i128",
+          "name": "makerSlot",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "depositIndex",
-          "type": "i128",
+          "name": "makerOut",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "borrowIndex",
-          "type": "i128",
+          "name": "timestamp",
+          "type": "u64",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "FlashLoanLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maker",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "makerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "makerFee",
+          "type": "f32",
+          "index": false
+        },
+        {
+          "name": "makerTimestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "taker",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenLoanDetails",
-          "type": {
-            "vec": {
-              "defined": "FlashLoanTokenDetail"
-            }
-          },
+          "name": "takerClientOrderId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "flashLoanType",
-          "type": {
-            "defined": "FlashLoanType"
-          },
+          "name": "takerFee",
+          "type": "f32",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "makerClosedPnl",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "takerClosedPnl",
+          "type": "f64",
           "index": false
         }
       ]
     },
     {
-      "name": "FlashLoanLogV2",
+      "name": "PerpUpdateFundingLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10455,30 +12715,59 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "tokenLoanDetails",
-          "type": {
-            "vec": {
-              "defined": "FlashLoanTokenDetailV2"
-            }
-          },
+          "name": "longFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "oracleSlot",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "stablePrice",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "feesAccrued",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "feesSettled",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "openInterest",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "flashLoanType",
-          "type": {
-            "defined": "FlashLoanType"
-          },
+          "name": "instantaneousFundingRate",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "WithdrawLog",
+      "name": "PerpUpdateFundingLogV2",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10486,69 +12775,71 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "signer",
-          "type": "publicKey",
+          "name": "longFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "shortFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
+          "name": "price",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "oracleSlot",
           "type": "u64",
           "index": false
         },
         {
-          "name": "price",
+          "name": "oracleConfidence",
           "type": "i128",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "DepositLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "oracleType",
+          "type": {
+            "defined": "OracleType"
+          },
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "stablePrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "signer",
-          "type": "publicKey",
+          "name": "feesAccrued",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "feesSettled",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "u64",
+          "name": "openInterest",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "price",
+          "name": "instantaneousFundingRate",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "FillLog",
+      "name": "UpdateIndexLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10556,89 +12847,69 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
+          "name": "tokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
-          "index": false
-        },
-        {
-          "name": "makerSlot",
-          "type": "u8",
-          "index": false
-        },
-        {
-          "name": "makerOut",
-          "type": "bool",
-          "index": false
-        },
-        {
-          "name": "timestamp",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "seqNum",
-          "type": "u64",
+          "name": "depositIndex",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "maker",
-          "type": "publicKey",
+          "name": "borrowIndex",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerOrderId",
-          "type": "u128",
+          "name": "avgUtilization",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerFee",
+          "name": "price",
           "type": "i128",
           "index": false
         },
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "stablePrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "taker",
-          "type": "publicKey",
+          "name": "collectedFees",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerOrderId",
-          "type": "u128",
+          "name": "loanFeeRate",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "totalBorrows",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerFee",
+          "name": "totalDeposits",
           "type": "i128",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i64",
+          "name": "borrowRate",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "i64",
+          "name": "depositRate",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "FillLogV2",
+      "name": "UpdateRateLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10646,84 +12917,84 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
+          "name": "tokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
-          "index": false
-        },
-        {
-          "name": "makerSlot",
-          "type": "u8",
+          "name": "rate0",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerOut",
-          "type": "bool",
+          "name": "rate1",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "timestamp",
-          "type": "u64",
+          "name": "maxRate",
+          "type": "i128",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "TokenLiqWithTokenLog",
+      "fields": [
         {
-          "name": "seqNum",
-          "type": "u64",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "maker",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerClientOrderId",
-          "type": "u64",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerFee",
-          "type": "f32",
+          "name": "assetTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "liabTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "taker",
-          "type": "publicKey",
+          "name": "assetTransfer",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "liabTransfer",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerFee",
-          "type": "f32",
+          "name": "assetPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i64",
+          "name": "liabPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "i64",
+          "name": "bankruptcy",
+          "type": "bool",
           "index": false
         }
       ]
     },
     {
-      "name": "FillLogV3",
+      "name": "Serum3OpenOrdersBalanceLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10731,94 +13002,104 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerSlot",
-          "type": "u8",
+          "name": "quoteTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerOut",
-          "type": "bool",
+          "name": "baseTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "timestamp",
+          "name": "baseFree",
           "type": "u64",
           "index": false
         },
         {
-          "name": "seqNum",
+          "name": "quoteTotal",
           "type": "u64",
           "index": false
         },
         {
-          "name": "maker",
-          "type": "publicKey",
+          "name": "quoteFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "makerClientOrderId",
+          "name": "referrerRebatesAccrued",
           "type": "u64",
           "index": false
+        }
+      ]
+    },
+    {
+      "name": "Serum3OpenOrdersBalanceLogV2",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
         },
         {
-          "name": "makerFee",
-          "type": "f32",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "taker",
-          "type": "publicKey",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "quoteTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "takerFee",
-          "type": "f32",
+          "name": "baseTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i64",
+          "name": "baseFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "i64",
+          "name": "quoteTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "makerClosedPnl",
-          "type": "f64",
+          "name": "quoteFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "takerClosedPnl",
-          "type": "f64",
+          "name": "referrerRebatesAccrued",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpUpdateFundingLog",
+      "name": "OpenbookV2OpenOrdersBalanceLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10826,59 +13107,54 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "longFunding",
-          "type": "i128",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "shortFunding",
-          "type": "i128",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i128",
+          "name": "quoteTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "oracleSlot",
+          "name": "baseTotal",
           "type": "u64",
           "index": false
         },
         {
-          "name": "stablePrice",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "feesAccrued",
-          "type": "i128",
+          "name": "baseFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "feesSettled",
-          "type": "i128",
+          "name": "quoteTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "openInterest",
-          "type": "i64",
+          "name": "quoteFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "instantaneousFundingRate",
-          "type": "i128",
+          "name": "referrerRebatesAvailable",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpUpdateFundingLogV2",
+      "name": "WithdrawLoanOriginationFeeLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10886,71 +13162,75 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "longFunding",
-          "type": "i128",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "shortFunding",
-          "type": "i128",
+          "name": "tokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "price",
+          "name": "loanOriginationFee",
           "type": "i128",
           "index": false
         },
         {
-          "name": "oracleSlot",
-          "type": "u64",
+          "name": "instruction",
+          "type": {
+            "defined": "LoanOriginationFeeInstruction"
+          },
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "WithdrawLoanLog",
+      "fields": [
         {
-          "name": "oracleConfidence",
-          "type": "i128",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "oracleType",
-          "type": {
-            "defined": "OracleType"
-          },
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "stablePrice",
-          "type": "i128",
+          "name": "tokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "feesAccrued",
+          "name": "loanAmount",
           "type": "i128",
           "index": false
         },
         {
-          "name": "feesSettled",
+          "name": "loanOriginationFee",
           "type": "i128",
           "index": false
         },
         {
-          "name": "openInterest",
-          "type": "i64",
+          "name": "instruction",
+          "type": {
+            "defined": "LoanOriginationFeeInstruction"
+          },
           "index": false
         },
         {
-          "name": "instantaneousFundingRate",
-          "type": "i128",
+          "name": "price",
+          "type": {
+            "option": "i128"
+          },
           "index": false
         }
       ]
     },
     {
-      "name": "UpdateIndexLog",
+      "name": "TokenLiqBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -10958,69 +13238,59 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "depositIndex",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "borrowIndex",
-          "type": "i128",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "avgUtilization",
-          "type": "i128",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i128",
+          "name": "liabTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "stablePrice",
+          "name": "initialLiabNative",
           "type": "i128",
           "index": false
         },
         {
-          "name": "collectedFees",
+          "name": "liabPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "loanFeeRate",
-          "type": "i128",
+          "name": "insuranceTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "totalBorrows",
+          "name": "insuranceTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "totalDeposits",
+          "name": "socializedLoss",
           "type": "i128",
           "index": false
         },
         {
-          "name": "borrowRate",
+          "name": "startingLiabDepositIndex",
           "type": "i128",
           "index": false
         },
         {
-          "name": "depositRate",
+          "name": "endingLiabDepositIndex",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "UpdateRateLog",
+      "name": "TokenInsuranceFundUseLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11028,29 +13298,34 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "rate0",
-          "type": "i128",
+          "name": "tokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "rate1",
-          "type": "i128",
+          "name": "perpMarketIndex",
+          "docs": [
+            "Set when the payout covered a perp market bankruptcy"
+          ],
+          "type": {
+            "option": "u16"
+          },
           "index": false
         },
         {
-          "name": "maxRate",
-          "type": "i128",
+          "name": "amount",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenLiqWithTokenLog",
+      "name": "DeactivateTokenPositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11058,54 +13333,74 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "cumulativeDepositInterest",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "cumulativeBorrowInterest",
+          "type": "f64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "DeactivatePerpPositionLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "assetTokenIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "liabTokenIndex",
+          "name": "marketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "assetTransfer",
-          "type": "i128",
+          "name": "cumulativeLongFunding",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "liabTransfer",
-          "type": "i128",
+          "name": "cumulativeShortFunding",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "assetPrice",
-          "type": "i128",
+          "name": "makerVolume",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "liabPrice",
-          "type": "i128",
+          "name": "takerVolume",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "bankruptcy",
-          "type": "bool",
+          "name": "perpSpotTransfers",
+          "type": "i64",
           "index": false
         }
       ]
     },
     {
-      "name": "Serum3OpenOrdersBalanceLog",
+      "name": "TokenMetaDataLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11113,49 +13408,74 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "mint",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "baseTokenIndex",
+          "name": "tokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "quoteTokenIndex",
-          "type": "u16",
+          "name": "mintDecimals",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "oracle",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mintInfo",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpMarketMetaDataLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "perpMarket",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "baseTotal",
-          "type": "u64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "baseFree",
-          "type": "u64",
+          "name": "baseDecimals",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "quoteTotal",
-          "type": "u64",
+          "name": "baseLotSize",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "quoteFree",
-          "type": "u64",
+          "name": "quoteLotSize",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "referrerRebatesAccrued",
-          "type": "u64",
+          "name": "oracle",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "Serum3OpenOrdersBalanceLogV2",
+      "name": "Serum3RegisterMarketLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11163,7 +13483,7 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "serumMarket",
           "type": "publicKey",
           "index": false
         },
@@ -11183,34 +13503,19 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "baseTotal",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "baseFree",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "quoteTotal",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "quoteFree",
-          "type": "u64",
+          "name": "serumProgram",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "referrerRebatesAccrued",
-          "type": "u64",
+          "name": "serumProgramExternal",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "WithdrawLoanOriginationFeeLog",
+      "name": "OpenbookV2RegisterMarketLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11218,31 +13523,39 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "openbookMarket",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
+          "name": "marketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "loanOriginationFee",
-          "type": "i128",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "instruction",
-          "type": {
-            "defined": "LoanOriginationFeeInstruction"
-          },
+          "name": "quoteTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "openbookProgram",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "openbookMarketExternal",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "WithdrawLoanLog",
+      "name": "PerpLiqBaseOrPositivePnlLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11250,43 +13563,49 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "loanAmount",
+          "name": "baseTransfer",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quoteTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "loanOriginationFee",
+          "name": "pnlTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "instruction",
-          "type": {
-            "defined": "LoanOriginationFeeInstruction"
-          },
+          "name": "pnlSettleLimitTransfer",
+          "type": "i128",
           "index": false
         },
         {
           "name": "price",
-          "type": {
-            "option": "i128"
-          },
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenLiqBankruptcyLog",
+      "name": "PerpLiqBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11304,49 +13623,44 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liabTokenIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "initialLiabNative",
+          "name": "insuranceTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "liabPrice",
+          "name": "socializedLoss",
           "type": "i128",
           "index": false
         },
         {
-          "name": "insuranceTokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "insuranceTransfer",
+          "name": "startingLongFunding",
           "type": "i128",
           "index": false
         },
         {
-          "name": "socializedLoss",
+          "name": "startingShortFunding",
           "type": "i128",
           "index": false
         },
         {
-          "name": "startingLiabDepositIndex",
+          "name": "endingLongFunding",
           "type": "i128",
           "index": false
         },
         {
-          "name": "endingLiabDepositIndex",
+          "name": "endingShortFunding",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "DeactivateTokenPositionLog",
+      "name": "PerpLiqNegativePnlOrBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11354,29 +13668,29 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "cumulativeDepositInterest",
-          "type": "f64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "cumulativeBorrowInterest",
-          "type": "f64",
+          "name": "settlement",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "DeactivatePerpPositionLog",
+      "name": "PerpSettlePnlLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11384,44 +13698,39 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "mangoAccountA",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "cumulativeLongFunding",
-          "type": "f64",
+          "name": "mangoAccountB",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "cumulativeShortFunding",
-          "type": "f64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerVolume",
-          "type": "u64",
+          "name": "settlement",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerVolume",
-          "type": "u64",
+          "name": "settler",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "perpSpotTransfers",
-          "type": "i64",
+          "name": "fee",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenMetaDataLog",
+      "name": "PerpSettleFeesLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11429,34 +13738,24 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mint",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "mintDecimals",
-          "type": "u8",
-          "index": false
-        },
-        {
-          "name": "oracle",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "mintInfo",
-          "type": "publicKey",
+          "name": "settlement",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpMarketMetaDataLog",
+      "name": "AccountBuybackFeesWithMngoLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11464,39 +13763,54 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "perpMarket",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "buybackFees",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "baseDecimals",
-          "type": "u8",
+          "name": "buybackMngo",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "baseLotSize",
-          "type": "i64",
+          "name": "mngoBuybackPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quoteLotSize",
-          "type": "i64",
+          "name": "oraclePrice",
+          "type": "i128",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "FilledPerpOrderLog",
+      "fields": [
         {
-          "name": "oracle",
+          "name": "mangoGroup",
           "type": "publicKey",
           "index": false
+        },
+        {
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
         }
       ]
     },
     {
-      "name": "Serum3RegisterMarketLog",
+      "name": "PerpTakerTradeLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11504,39 +13818,54 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "serumMarket",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "marketIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "baseTokenIndex",
-          "type": "u16",
+          "name": "takerSide",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "quoteTokenIndex",
-          "type": "u16",
+          "name": "totalBaseLotsTaken",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "serumProgram",
-          "type": "publicKey",
+          "name": "totalBaseLotsDecremented",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "totalQuoteLotsTaken",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "totalQuoteLotsDecremented",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "takerFeesPaid",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "serumProgramExternal",
-          "type": "publicKey",
+          "name": "feePenalty",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpLiqBaseOrPositivePnlLog",
+      "name": "PerpForceClosePositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11549,12 +13878,12 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "accountA",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "accountB",
           "type": "publicKey",
           "index": false
         },
@@ -11568,16 +13897,6 @@ export type MangoV4 = {
           "type": "i128",
           "index": false
         },
-        {
-          "name": "pnlTransfer",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "pnlSettleLimitTransfer",
-          "type": "i128",
-          "index": false
-        },
         {
           "name": "price",
           "type": "i128",
@@ -11586,7 +13905,7 @@ export type MangoV4 = {
       ]
     },
     {
-      "name": "PerpLiqBankruptcyLog",
+      "name": "TokenForceCloseBorrowsWithTokenLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11594,54 +13913,54 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
+          "name": "assetTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "insuranceTransfer",
-          "type": "i128",
+          "name": "liabTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "socializedLoss",
+          "name": "assetTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "startingLongFunding",
+          "name": "liabTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "startingShortFunding",
+          "name": "assetPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "endingLongFunding",
+          "name": "liabPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "endingShortFunding",
+          "name": "feeFactor",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpLiqNegativePnlOrBankruptcyLog",
+      "name": "TokenConditionalSwapCreateLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11649,69 +13968,79 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqor",
-          "type": "publicKey",
+          "name": "id",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "maxBuy",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
+          "name": "maxSell",
+          "type": "u64",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpSettlePnlLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "expiryTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "mangoAccountA",
-          "type": "publicKey",
+          "name": "priceLowerLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "mangoAccountB",
-          "type": "publicKey",
+          "name": "priceUpperLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
+          "name": "pricePremiumRate",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "takerFeeRate",
+          "type": "f32",
+          "index": false
+        },
+        {
+          "name": "makerFeeRate",
+          "type": "f32",
+          "index": false
+        },
+        {
+          "name": "buyTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
+          "name": "sellTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "settler",
-          "type": "publicKey",
+          "name": "allowCreatingDeposits",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "fee",
-          "type": "i128",
+          "name": "allowCreatingBorrows",
+          "type": "bool",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpSettleFeesLog",
+      "name": "TokenConditionalSwapCreateLogV2",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11724,74 +14053,84 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "id",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
+          "name": "maxBuy",
+          "type": "u64",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "AccountBuybackFeesWithMngoLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "maxSell",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "expiryTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "buybackFees",
-          "type": "i128",
+          "name": "priceLowerLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "buybackMngo",
-          "type": "i128",
+          "name": "priceUpperLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "mngoBuybackPrice",
-          "type": "i128",
+          "name": "pricePremiumRate",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "oraclePrice",
-          "type": "i128",
+          "name": "takerFeeRate",
+          "type": "f32",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "FilledPerpOrderLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "makerFeeRate",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
+          "name": "buyTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "seqNum",
-          "type": "u64",
+          "name": "sellTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "allowCreatingDeposits",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "displayPriceStyle",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "intention",
+          "type": "u8",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpTakerTradeLog",
+      "name": "TokenConditionalSwapCreateLogV3",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11804,89 +14143,99 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "id",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
+          "name": "maxBuy",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "totalBaseLotsTaken",
-          "type": "i64",
+          "name": "maxSell",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "totalBaseLotsDecremented",
-          "type": "i64",
+          "name": "expiryTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "totalQuoteLotsTaken",
-          "type": "i64",
+          "name": "priceLowerLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "totalQuoteLotsDecremented",
-          "type": "i64",
+          "name": "priceUpperLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "takerFeesPaid",
-          "type": "i128",
+          "name": "pricePremiumRate",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "feePenalty",
-          "type": "i128",
+          "name": "takerFeeRate",
+          "type": "f32",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpForceClosePositionLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "makerFeeRate",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
+          "name": "buyTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "accountA",
-          "type": "publicKey",
+          "name": "sellTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "accountB",
-          "type": "publicKey",
+          "name": "allowCreatingDeposits",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "displayPriceStyle",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "intention",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "baseTransfer",
-          "type": "i64",
+          "name": "intervalSeconds",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "quoteTransfer",
-          "type": "i128",
+          "name": "intervalMaxSell",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i128",
+          "name": "premiumAuctionDurationSeconds",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenForceCloseBorrowsWithTokenLog",
+      "name": "TokenConditionalSwapTriggerLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11894,54 +14243,69 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "assetTokenIndex",
+          "name": "tokenConditionalSwapId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "buyTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "liabTokenIndex",
+          "name": "sellTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "assetTransfer",
-          "type": "i128",
+          "name": "buyAmount",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "liabTransfer",
-          "type": "i128",
+          "name": "sellAmount",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "assetPrice",
-          "type": "i128",
+          "name": "makerFee",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "liabPrice",
+          "name": "takerFee",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "buyTokenPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "feeFactor",
+          "name": "sellTokenPrice",
           "type": "i128",
           "index": false
+        },
+        {
+          "name": "closed",
+          "type": "bool",
+          "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapCreateLog",
+      "name": "TokenConditionalSwapTriggerLogV2",
       "fields": [
         {
           "name": "mangoGroup",
@@ -11949,79 +14313,104 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "id",
+          "name": "liqor",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenConditionalSwapId",
           "type": "u64",
           "index": false
         },
         {
-          "name": "maxBuy",
+          "name": "buyTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "sellTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "buyAmount",
           "type": "u64",
           "index": false
         },
         {
-          "name": "maxSell",
+          "name": "sellAmount",
           "type": "u64",
           "index": false
         },
         {
-          "name": "expiryTimestamp",
+          "name": "makerFee",
           "type": "u64",
           "index": false
         },
         {
-          "name": "priceLowerLimit",
-          "type": "f64",
+          "name": "takerFee",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "priceUpperLimit",
-          "type": "f64",
+          "name": "buyTokenPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "pricePremiumRate",
-          "type": "f64",
+          "name": "sellTokenPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerFeeRate",
-          "type": "f32",
+          "name": "closed",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "makerFeeRate",
-          "type": "f32",
+          "name": "displayPriceStyle",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
+          "name": "intention",
+          "type": "u8",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TokenConditionalSwapStartLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellTokenIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "allowCreatingDeposits",
-          "type": "bool",
+          "name": "tokenConditionalSwapId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "allowCreatingBorrows",
-          "type": "bool",
+          "name": "startTimestamp",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapCreateLogV2",
+      "name": "TokenConditionalSwapCancelLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -12037,17 +14426,42 @@ export type MangoV4 = {
           "name": "id",
           "type": "u64",
           "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpConditionalOrderCreateLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
         },
         {
-          "name": "maxBuy",
-          "type": "u64",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "maxSell",
+          "name": "id",
           "type": "u64",
           "index": false
         },
+        {
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "side",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64",
+          "index": false
+        },
         {
           "name": "expiryTimestamp",
           "type": "u64",
@@ -12069,49 +14483,49 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "takerFeeRate",
-          "type": "f32",
+          "name": "reduceOnly",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "makerFeeRate",
-          "type": "f32",
+          "name": "intention",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
+          "name": "execution",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "sellTokenIndex",
-          "type": "u16",
+          "name": "orderType",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "allowCreatingDeposits",
-          "type": "bool",
+          "name": "priceLots",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "allowCreatingBorrows",
+          "name": "pegToOracle",
           "type": "bool",
           "index": false
         },
         {
-          "name": "displayPriceStyle",
-          "type": "u8",
+          "name": "maxQuoteLots",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "intention",
-          "type": "u8",
+          "name": "triggerPremium",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapTriggerLog",
+      "name": "PerpConditionalOrderCancelLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -12119,57 +14533,57 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "liqor",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenConditionalSwapId",
+          "name": "id",
           "type": "u64",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "PerpConditionalOrderTriggerLog",
+      "fields": [
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellTokenIndex",
-          "type": "u16",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "buyAmount",
-          "type": "u64",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellAmount",
+          "name": "perpConditionalOrderId",
           "type": "u64",
           "index": false
         },
         {
-          "name": "makerFee",
-          "type": "u64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "takerFee",
-          "type": "u64",
+          "name": "baseLots",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "buyTokenPrice",
+          "name": "quoteNative",
           "type": "i128",
           "index": false
         },
         {
-          "name": "sellTokenPrice",
+          "name": "oraclePrice",
           "type": "i128",
           "index": false
         },
@@ -12181,7 +14595,7 @@ export type MangoV4 = {
       ]
     },
     {
-      "name": "TokenConditionalSwapTriggerLogV2",
+      "name": "PerpConditionalOrderPlaceLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -12189,79 +14603,81 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "triggerer",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenConditionalSwapId",
+          "name": "id",
           "type": "u64",
           "index": false
         },
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "sellTokenIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "buyAmount",
-          "type": "u64",
+          "name": "oraclePrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "sellAmount",
+          "name": "triggerPremium",
           "type": "u64",
           "index": false
         },
         {
-          "name": "makerFee",
-          "type": "u64",
+          "name": "orderId",
+          "type": {
+            "option": "u128"
+          },
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "PerpIsolatedMarginTransferLog",
+      "fields": [
         {
-          "name": "takerFee",
-          "type": "u64",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "buyTokenPrice",
-          "type": "i128",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellTokenPrice",
-          "type": "i128",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "closed",
-          "type": "bool",
+          "name": "amount",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "displayPriceStyle",
-          "type": "u8",
+          "name": "isolatedCollateralIndexed",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "intention",
-          "type": "u8",
+          "name": "depositIndex",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapCancelLog",
+      "name": "PerpLiqIsolatedPositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -12269,13 +14685,38 @@ export type MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "id",
-          "type": "u64",
+          "name": "liqee",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "baseTransfer",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quoteTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "collateralTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
           "index": false
         }
       ]
@@ -12531,6 +14972,51 @@ export type MangoV4 = {
       "code": 6049,
       "name": "TokenConditionalSwapPriceNotInRange",
       "msg": "conditional token swap price is not in execution range"
+    },
+    {
+      "code": 6050,
+      "name": "NoFreeOpenbookV2OpenOrdersIndex",
+      "msg": "no free openbook v2 open orders index"
+    },
+    {
+      "code": 6051,
+      "name": "OpenbookV2OpenOrdersExistAlready",
+      "msg": "openbook v2 open orders exist already"
+    },
+    {
+      "code": 6052,
+      "name": "HasOpenOrUnsettledOpenbookV2Orders",
+      "msg": "there are open or unsettled openbook v2 orders"
+    },
+    {
+      "code": 6053,
+      "name": "PerpConditionalOrderPriceNotInRange",
+      "msg": "perp conditional order price not in range"
+    },
+    {
+      "code": 6054,
+      "name": "TokenConditionalSwapIntervalNotReady",
+      "msg": "conditional token swap interval amount is exhausted"
+    },
+    {
+      "code": 6055,
+      "name": "PerpPositionIsIsolated",
+      "msg": "the perp position is isolated, which this instruction does not support"
+    },
+    {
+      "code": 6056,
+      "name": "PerpPositionIsNotIsolated",
+      "msg": "the perp position is not isolated"
+    },
+    {
+      "code": 6057,
+      "name": "IsolatedPerpHealthMustBePositiveOrIncrease",
+      "msg": "isolated perp position health must be positive or not decrease"
+    },
+    {
+      "code": 6058,
+      "name": "IsolatedPerpPositionNotLiquidatable",
+      "msg": "isolated perp position is not liquidatable"
     }
   ]
 };
@@ -12804,22 +15290,220 @@ export const IDL: MangoV4 = {
           }
         },
         {
-          "name": "buybackFeesExpiryIntervalOpt",
-          "type": {
-            "option": "u64"
-          }
+          "name": "buybackFeesExpiryIntervalOpt",
+          "type": {
+            "option": "u64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "groupWithdrawInsuranceFund",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "insurance_vault",
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "tokenInsuranceFundCreate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "mintInfo",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "mint"
+          ]
+        },
+        {
+          "name": "mint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceFund",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "TokenInsuranceFund"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              }
+            ]
+          }
+        },
+        {
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "TokenInsuranceFundVault"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "arg",
+                "type": "u16",
+                "path": "token_index"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "rent",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenIndex",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "tokenInsuranceFundDeposit",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "insuranceFund",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "vault"
+          ]
+        },
+        {
+          "name": "vault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenAuthority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "groupWithdrawInsuranceFund",
+      "name": "tokenInsuranceFundWithdraw",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
           "isSigner": false,
           "relations": [
-            "insurance_vault",
             "admin"
           ]
         },
@@ -12829,7 +15513,16 @@ export const IDL: MangoV4 = {
           "isSigner": true
         },
         {
-          "name": "insuranceVault",
+          "name": "insuranceFund",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "vault"
+          ]
+        },
+        {
+          "name": "vault",
           "isMut": true,
           "isSigner": false
         },
@@ -13835,6 +16528,98 @@ export const IDL: MangoV4 = {
         }
       ]
     },
+    {
+      "name": "accountCreateV3",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "MangoAccount"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "owner"
+              },
+              {
+                "kind": "arg",
+                "type": "u32",
+                "path": "account_num"
+              }
+            ]
+          }
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "accountNum",
+          "type": "u32"
+        },
+        {
+          "name": "tokenCount",
+          "type": "u8"
+        },
+        {
+          "name": "serum3Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpOoCount",
+          "type": "u8"
+        },
+        {
+          "name": "tokenConditionalSwapCount",
+          "type": "u8"
+        },
+        {
+          "name": "openbookV2Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpConditionalOrderCount",
+          "type": "u8"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        }
+      ]
+    },
     {
       "name": "accountExpand",
       "accounts": [
@@ -13943,6 +16728,70 @@ export const IDL: MangoV4 = {
         }
       ]
     },
+    {
+      "name": "accountExpandV3",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "owner"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenCount",
+          "type": "u8"
+        },
+        {
+          "name": "serum3Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpCount",
+          "type": "u8"
+        },
+        {
+          "name": "perpOoCount",
+          "type": "u8"
+        },
+        {
+          "name": "tokenConditionalSwapCount",
+          "type": "u8"
+        },
+        {
+          "name": "openbookV2Count",
+          "type": "u8"
+        },
+        {
+          "name": "perpConditionalOrderCount",
+          "type": "u8"
+        }
+      ]
+    },
     {
       "name": "accountEdit",
       "accounts": [
@@ -14304,7 +17153,105 @@ export const IDL: MangoV4 = {
             "defined": "I80F48"
           }
         }
-      ]
+      ]
+    },
+    {
+      "name": "twapOracleCreate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "oracle",
+          "isMut": true,
+          "isSigner": false,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "type": "string",
+                "value": "TwapOracle"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "path": "group"
+              },
+              {
+                "kind": "account",
+                "type": "publicKey",
+                "account": "Mint",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "mint",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "windowSecs",
+          "type": "u64"
+        },
+        {
+          "name": "maxSampleDeviation",
+          "type": "f32"
+        }
+      ]
+    },
+    {
+      "name": "twapOracleUpdate",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "pool"
+          ]
+        },
+        {
+          "name": "pool",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
     },
     {
       "name": "tokenDeposit",
@@ -16253,60 +19200,306 @@ export const IDL: MangoV4 = {
           }
         },
         {
-          "name": "settlePnlLimitFactorOpt",
-          "type": {
-            "option": "f32"
-          }
+          "name": "settlePnlLimitFactorOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "settlePnlLimitWindowSizeTsOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "reduceOnlyOpt",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "resetStablePrice",
+          "type": "bool"
+        },
+        {
+          "name": "positivePnlLiquidationFeeOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "nameOpt",
+          "type": {
+            "option": "string"
+          }
+        },
+        {
+          "name": "forceCloseOpt",
+          "type": {
+            "option": "bool"
+          }
+        }
+      ]
+    },
+    {
+      "name": "perpCloseMarket",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "admin"
+          ]
+        },
+        {
+          "name": "admin",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "bids",
+            "asks",
+            "event_queue"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "solDestination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "perpDeactivatePosition",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "perpIsolatedMarginTransfer",
+      "docs": [
+        "Moves settle token deposits into (positive amount) or out of (negative amount)",
+        "the isolated collateral of a perp position, making it isolated if needed."
+      ],
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "i64"
+        }
+      ]
+    },
+    {
+      "name": "perpPlaceOrder",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "bids",
+            "asks",
+            "event_queue",
+            "oracle"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
+        },
+        {
+          "name": "priceLots",
+          "type": "i64"
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64"
+        },
+        {
+          "name": "maxQuoteLots",
+          "type": "i64"
         },
         {
-          "name": "settlePnlLimitWindowSizeTsOpt",
-          "type": {
-            "option": "u64"
-          }
+          "name": "clientOrderId",
+          "type": "u64"
         },
         {
-          "name": "reduceOnlyOpt",
+          "name": "orderType",
           "type": {
-            "option": "bool"
+            "defined": "PlaceOrderType"
           }
         },
         {
-          "name": "resetStablePrice",
+          "name": "reduceOnly",
           "type": "bool"
         },
         {
-          "name": "positivePnlLiquidationFeeOpt",
-          "type": {
-            "option": "f32"
-          }
-        },
-        {
-          "name": "nameOpt",
-          "type": {
-            "option": "string"
-          }
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "forceCloseOpt",
-          "type": {
-            "option": "bool"
-          }
+          "name": "limit",
+          "type": "u8"
         }
-      ]
+      ],
+      "returns": {
+        "option": "u128"
+      }
     },
     {
-      "name": "perpCloseMarket",
+      "name": "perpPlaceOrderV2",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
           "isSigner": false,
           "relations": [
-            "admin"
+            "group"
           ]
         },
         {
-          "name": "admin",
+          "name": "owner",
           "isMut": false,
           "isSigner": true
         },
@@ -16318,7 +19511,8 @@ export const IDL: MangoV4 = {
             "group",
             "bids",
             "asks",
-            "event_queue"
+            "event_queue",
+            "oracle"
           ]
         },
         {
@@ -16337,52 +19531,65 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "solDestination",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
+          "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
-    },
-    {
-      "name": "perpDeactivatePosition",
-      "accounts": [
+      "args": [
         {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
         },
         {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "priceLots",
+          "type": "i64"
         },
         {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
+          "name": "maxBaseLots",
+          "type": "i64"
         },
         {
-          "name": "perpMarket",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "maxQuoteLots",
+          "type": "i64"
+        },
+        {
+          "name": "clientOrderId",
+          "type": "u64"
+        },
+        {
+          "name": "orderType",
+          "type": {
+            "defined": "PlaceOrderType"
+          }
+        },
+        {
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "SelfTradeBehavior"
+          }
+        },
+        {
+          "name": "reduceOnly",
+          "type": "bool"
+        },
+        {
+          "name": "expiryTimestamp",
+          "type": "u64"
+        },
+        {
+          "name": "limit",
+          "type": "u8"
         }
       ],
-      "args": []
+      "returns": {
+        "option": "u128"
+      }
     },
     {
-      "name": "perpPlaceOrder",
+      "name": "perpPlaceOrderPegged",
       "accounts": [
         {
           "name": "group",
@@ -16443,7 +19650,11 @@ export const IDL: MangoV4 = {
           }
         },
         {
-          "name": "priceLots",
+          "name": "priceOffsetLots",
+          "type": "i64"
+        },
+        {
+          "name": "pegLimit",
           "type": "i64"
         },
         {
@@ -16475,6 +19686,10 @@ export const IDL: MangoV4 = {
         {
           "name": "limit",
           "type": "u8"
+        },
+        {
+          "name": "maxOracleStalenessSlots",
+          "type": "i32"
         }
       ],
       "returns": {
@@ -16482,7 +19697,7 @@ export const IDL: MangoV4 = {
       }
     },
     {
-      "name": "perpPlaceOrderV2",
+      "name": "perpPlaceOrderPeggedV2",
       "accounts": [
         {
           "name": "group",
@@ -16543,7 +19758,11 @@ export const IDL: MangoV4 = {
           }
         },
         {
-          "name": "priceLots",
+          "name": "priceOffsetLots",
+          "type": "i64"
+        },
+        {
+          "name": "pegLimit",
           "type": "i64"
         },
         {
@@ -16575,20 +19794,122 @@ export const IDL: MangoV4 = {
           "type": "bool"
         },
         {
-          "name": "expiryTimestamp",
+          "name": "expiryTimestamp",
+          "type": "u64"
+        },
+        {
+          "name": "limit",
+          "type": "u8"
+        },
+        {
+          "name": "maxOracleStalenessSlots",
+          "type": "i32"
+        }
+      ],
+      "returns": {
+        "option": "u128"
+      }
+    },
+    {
+      "name": "perpCancelOrder",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "bids",
+            "asks"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "orderId",
+          "type": "u128"
+        }
+      ]
+    },
+    {
+      "name": "perpCancelOrderByClientOrderId",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "bids",
+            "asks"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "clientOrderId",
           "type": "u64"
-        },
-        {
-          "name": "limit",
-          "type": "u8"
         }
-      ],
-      "returns": {
-        "option": "u128"
-      }
+      ]
     },
     {
-      "name": "perpPlaceOrderPegged",
+      "name": "perpCancelAllOrders",
       "accounts": [
         {
           "name": "group",
@@ -16615,9 +19936,7 @@ export const IDL: MangoV4 = {
           "relations": [
             "group",
             "bids",
-            "asks",
-            "event_queue",
-            "oracle"
+            "asks"
           ]
         },
         {
@@ -16629,74 +19948,74 @@ export const IDL: MangoV4 = {
           "name": "asks",
           "isMut": true,
           "isSigner": false
-        },
-        {
-          "name": "eventQueue",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "side",
-          "type": {
-            "defined": "Side"
-          }
-        },
+          "name": "limit",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "perpCancelAllOrdersBySide",
+      "accounts": [
         {
-          "name": "priceOffsetLots",
-          "type": "i64"
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "pegLimit",
-          "type": "i64"
+          "name": "account",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "maxBaseLots",
-          "type": "i64"
+          "name": "owner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "maxQuoteLots",
-          "type": "i64"
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "bids",
+            "asks"
+          ]
         },
         {
-          "name": "clientOrderId",
-          "type": "u64"
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
         },
         {
-          "name": "orderType",
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "sideOption",
           "type": {
-            "defined": "PlaceOrderType"
+            "option": {
+              "defined": "Side"
+            }
           }
         },
-        {
-          "name": "reduceOnly",
-          "type": "bool"
-        },
-        {
-          "name": "expiryTimestamp",
-          "type": "u64"
-        },
         {
           "name": "limit",
           "type": "u8"
-        },
-        {
-          "name": "maxOracleStalenessSlots",
-          "type": "i32"
         }
-      ],
-      "returns": {
-        "option": "u128"
-      }
+      ]
     },
     {
-      "name": "perpPlaceOrderPeggedV2",
+      "name": "perpConsumeEvents",
       "accounts": [
         {
           "name": "group",
@@ -16704,17 +20023,34 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "perpMarket",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group"
+            "group",
+            "event_queue"
           ]
         },
         {
-          "name": "owner",
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "limit",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "perpUpdateFunding",
+      "accounts": [
+        {
+          "name": "group",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
         },
         {
           "name": "perpMarket",
@@ -16724,7 +20060,6 @@ export const IDL: MangoV4 = {
             "group",
             "bids",
             "asks",
-            "event_queue",
             "oracle"
           ]
         },
@@ -16738,85 +20073,139 @@ export const IDL: MangoV4 = {
           "isMut": true,
           "isSigner": false
         },
-        {
-          "name": "eventQueue",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "oracle",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": [
+      "args": []
+    },
+    {
+      "name": "perpSettlePnl",
+      "accounts": [
         {
-          "name": "side",
-          "type": {
-            "defined": "Side"
-          }
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "priceOffsetLots",
-          "type": "i64"
+          "name": "settler",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "pegLimit",
-          "type": "i64"
+          "name": "settlerOwner",
+          "isMut": false,
+          "isSigner": true
         },
         {
-          "name": "maxBaseLots",
-          "type": "i64"
+          "name": "perpMarket",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "oracle"
+          ]
         },
         {
-          "name": "maxQuoteLots",
-          "type": "i64"
+          "name": "accountA",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "clientOrderId",
-          "type": "u64"
+          "name": "accountB",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "orderType",
-          "type": {
-            "defined": "PlaceOrderType"
-          }
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "selfTradeBehavior",
-          "type": {
-            "defined": "SelfTradeBehavior"
-          }
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "reduceOnly",
-          "type": "bool"
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "perpForceClosePosition",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
         },
         {
-          "name": "expiryTimestamp",
-          "type": "u64"
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "oracle"
+          ]
         },
         {
-          "name": "limit",
-          "type": "u8"
+          "name": "accountA",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "maxOracleStalenessSlots",
-          "type": "i32"
+          "name": "accountB",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         }
       ],
-      "returns": {
-        "option": "u128"
-      }
+      "args": []
     },
     {
-      "name": "perpCancelOrder",
+      "name": "perpSettleFees",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
           "isSigner": false
         },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "oracle"
+          ]
+        },
         {
           "name": "account",
           "isMut": true,
@@ -16826,40 +20215,33 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "owner",
+          "name": "oracle",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false
         },
         {
-          "name": "perpMarket",
+          "name": "settleBank",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group",
-            "bids",
-            "asks"
+            "group"
           ]
         },
         {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
+          "name": "settleOracle",
+          "isMut": false,
           "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "orderId",
-          "type": "u128"
+          "name": "maxSettleAmount",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "perpCancelOrderByClientOrderId",
+      "name": "perpLiqBaseOrPositivePnl",
       "accounts": [
         {
           "name": "group",
@@ -16867,7 +20249,21 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "oracle"
+          ]
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "liqor",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -16875,40 +20271,50 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "owner",
+          "name": "liqorOwner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "perpMarket",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group",
-            "bids",
-            "asks"
+            "group"
           ]
         },
         {
-          "name": "bids",
+          "name": "settleBank",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "asks",
+          "name": "settleVault",
           "isMut": true,
           "isSigner": false
+        },
+        {
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "clientOrderId",
+          "name": "maxBaseTransfer",
+          "type": "i64"
+        },
+        {
+          "name": "maxPnlTransfer",
           "type": "u64"
         }
       ]
     },
     {
-      "name": "perpCancelAllOrders",
+      "name": "perpLiqForceCancelOrders",
       "accounts": [
         {
           "name": "group",
@@ -16923,11 +20329,6 @@ export const IDL: MangoV4 = {
             "group"
           ]
         },
-        {
-          "name": "owner",
-          "isMut": false,
-          "isSigner": true
-        },
         {
           "name": "perpMarket",
           "isMut": true,
@@ -16957,15 +20358,32 @@ export const IDL: MangoV4 = {
       ]
     },
     {
-      "name": "perpCancelAllOrdersBySide",
+      "name": "perpLiqIsolatedPosition",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "insurance_vault"
+          ]
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "oracle"
+          ]
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "account",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -16973,83 +20391,103 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "owner",
+          "name": "liqorOwner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "perpMarket",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group",
-            "bids",
-            "asks"
+            "group"
           ]
         },
         {
-          "name": "bids",
+          "name": "settleBank",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
-          "name": "asks",
+          "name": "settleVault",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "sideOption",
-          "type": {
-            "option": {
-              "defined": "Side"
-            }
-          }
         },
         {
-          "name": "limit",
-          "type": "u8"
-        }
-      ]
-    },
-    {
-      "name": "perpConsumeEvents",
-      "accounts": [
-        {
-          "name": "group",
+          "name": "settleOracle",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "perpMarket",
+          "name": "insuranceVault",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceBank",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group",
-            "event_queue"
+            "group"
           ]
         },
         {
-          "name": "eventQueue",
+          "name": "insuranceBankVault",
           "isMut": true,
           "isSigner": false
+        },
+        {
+          "name": "insuranceOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "limit",
-          "type": "u64"
+          "name": "maxBaseTransfer",
+          "type": "i64"
         }
       ]
     },
     {
-      "name": "perpUpdateFunding",
+      "name": "perpLiqNegativePnlOrBankruptcy",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "insurance_vault"
+          ]
+        },
+        {
+          "name": "liqor",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "liqorOwner",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "liqee",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         },
         {
           "name": "perpMarket",
@@ -17057,39 +20495,63 @@ export const IDL: MangoV4 = {
           "isSigner": false,
           "relations": [
             "group",
-            "bids",
-            "asks",
             "oracle"
           ]
         },
         {
-          "name": "bids",
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "settleBank",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "settleVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "asks",
+          "name": "settleOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "insuranceVault",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "oracle",
+          "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "maxLiabTransfer",
+          "type": "u64"
+        }
+      ]
     },
     {
-      "name": "perpSettlePnl",
+      "name": "perpLiqNegativePnlOrBankruptcyV2",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "insurance_vault"
+          ]
         },
         {
-          "name": "settler",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17097,21 +20559,12 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "settlerOwner",
+          "name": "liqorOwner",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "perpMarket",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group",
-            "oracle"
-          ]
-        },
-        {
-          "name": "accountA",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17119,11 +20572,12 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "accountB",
+          "name": "perpMarket",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group"
+            "group",
+            "oracle"
           ]
         },
         {
@@ -17140,32 +20594,22 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "settleOracle",
-          "isMut": false,
+          "name": "settleVault",
+          "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "perpForceClosePosition",
-      "accounts": [
+        },
         {
-          "name": "group",
+          "name": "settleOracle",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "perpMarket",
+          "name": "insuranceVault",
           "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group",
-            "oracle"
-          ]
+          "isSigner": false
         },
         {
-          "name": "accountA",
+          "name": "insuranceBank",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17173,38 +20617,36 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "accountB",
+          "name": "insuranceBankVault",
           "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "isSigner": false
         },
         {
-          "name": "oracle",
+          "name": "insuranceOracle",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "maxLiabTransfer",
+          "type": "u64"
+        }
+      ]
     },
     {
-      "name": "perpSettleFees",
+      "name": "tokenConditionalSwapCreate",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
           "isSigner": false
         },
-        {
-          "name": "perpMarket",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group",
-            "oracle"
-          ]
-        },
         {
           "name": "account",
           "isMut": true,
@@ -17214,33 +20656,64 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "oracle",
+          "name": "authority",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "settleBank",
-          "isMut": true,
+          "name": "buyBank",
+          "isMut": false,
           "isSigner": false,
           "relations": [
             "group"
           ]
         },
         {
-          "name": "settleOracle",
+          "name": "sellBank",
           "isMut": false,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         }
       ],
       "args": [
         {
-          "name": "maxSettleAmount",
+          "name": "maxBuy",
+          "type": "u64"
+        },
+        {
+          "name": "maxSell",
+          "type": "u64"
+        },
+        {
+          "name": "expiryTimestamp",
           "type": "u64"
+        },
+        {
+          "name": "priceLowerLimit",
+          "type": "f64"
+        },
+        {
+          "name": "priceUpperLimit",
+          "type": "f64"
+        },
+        {
+          "name": "pricePremiumRate",
+          "type": "f64"
+        },
+        {
+          "name": "allowCreatingDeposits",
+          "type": "bool"
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool"
         }
       ]
     },
     {
-      "name": "perpLiqBaseOrPositivePnl",
+      "name": "tokenConditionalSwapCreateV2",
       "accounts": [
         {
           "name": "group",
@@ -17248,72 +20721,92 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "perpMarket",
+          "name": "account",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group",
-            "oracle"
+            "group"
           ]
         },
         {
-          "name": "oracle",
+          "name": "authority",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         },
         {
-          "name": "liqor",
-          "isMut": true,
+          "name": "buyBank",
+          "isMut": false,
           "isSigner": false,
           "relations": [
             "group"
           ]
         },
         {
-          "name": "liqorOwner",
+          "name": "sellBank",
           "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "liqee",
-          "isMut": true,
           "isSigner": false,
           "relations": [
             "group"
           ]
+        }
+      ],
+      "args": [
+        {
+          "name": "maxBuy",
+          "type": "u64"
         },
         {
-          "name": "settleBank",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "maxSell",
+          "type": "u64"
         },
         {
-          "name": "settleVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "settleOracle",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
+          "name": "priceLowerLimit",
+          "type": "f64"
+        },
         {
-          "name": "maxBaseTransfer",
-          "type": "i64"
+          "name": "priceUpperLimit",
+          "type": "f64"
         },
         {
-          "name": "maxPnlTransfer",
-          "type": "u64"
+          "name": "pricePremiumRate",
+          "type": "f64"
+        },
+        {
+          "name": "allowCreatingDeposits",
+          "type": "bool"
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool"
+        },
+        {
+          "name": "displayPriceStyle",
+          "type": {
+            "defined": "TokenConditionalSwapDisplayPriceStyle"
+          }
+        },
+        {
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
         }
       ]
     },
     {
-      "name": "perpLiqForceCancelOrders",
+      "name": "tokenConditionalSwapCreateTwap",
+      "docs": [
+        "Like token_conditional_swap_create_v2, but executes in slices of at most",
+        "interval_max_sell native sell tokens, one slice per interval_seconds.",
+        "",
+        "Passing interval_seconds = 0 creates a regular tcs. If",
+        "premium_auction_duration_seconds is nonzero, price_premium_rate is the maximum",
+        "premium of a linear auction, see token_conditional_swap_create_linear_auction."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -17329,46 +20822,103 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "perpMarket",
-          "isMut": true,
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "buyBank",
+          "isMut": false,
           "isSigner": false,
           "relations": [
-            "group",
-            "bids",
-            "asks"
+            "group"
           ]
         },
         {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
+          "name": "sellBank",
+          "isMut": false,
+          "isSigner": false,
+          "relations": [
+            "group"
+          ]
         }
       ],
       "args": [
         {
-          "name": "limit",
-          "type": "u8"
+          "name": "maxBuy",
+          "type": "u64"
+        },
+        {
+          "name": "maxSell",
+          "type": "u64"
+        },
+        {
+          "name": "expiryTimestamp",
+          "type": "u64"
+        },
+        {
+          "name": "priceLowerLimit",
+          "type": "f64"
+        },
+        {
+          "name": "priceUpperLimit",
+          "type": "f64"
+        },
+        {
+          "name": "pricePremiumRate",
+          "type": "f64"
+        },
+        {
+          "name": "allowCreatingDeposits",
+          "type": "bool"
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool"
+        },
+        {
+          "name": "displayPriceStyle",
+          "type": {
+            "defined": "TokenConditionalSwapDisplayPriceStyle"
+          }
+        },
+        {
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
+        },
+        {
+          "name": "intervalSeconds",
+          "type": "u64"
+        },
+        {
+          "name": "intervalMaxSell",
+          "type": "u64"
+        },
+        {
+          "name": "premiumAuctionDurationSeconds",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "perpLiqNegativePnlOrBankruptcy",
+      "name": "tokenConditionalSwapCreateLinearAuction",
+      "docs": [
+        "Like token_conditional_swap_create_v2, but the premium is a linear auction.",
+        "",
+        "The auction starts when the tcs is first triggered with the price in range: the",
+        "premium then rises from zero to max_price_premium_rate over",
+        "premium_auction_duration_seconds. Triggering with the price out of range resets it."
+      ],
       "accounts": [
         {
           "name": "group",
           "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "insurance_vault"
-          ]
+          "isSigner": false
         },
         {
-          "name": "liqor",
+          "name": "account",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17376,81 +20926,88 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "liqorOwner",
+          "name": "authority",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "liqee",
-          "isMut": true,
+          "name": "buyBank",
+          "isMut": false,
           "isSigner": false,
           "relations": [
             "group"
           ]
         },
         {
-          "name": "perpMarket",
-          "isMut": true,
+          "name": "sellBank",
+          "isMut": false,
           "isSigner": false,
           "relations": [
-            "group",
-            "oracle"
+            "group"
           ]
+        }
+      ],
+      "args": [
+        {
+          "name": "maxBuy",
+          "type": "u64"
         },
         {
-          "name": "oracle",
-          "isMut": false,
-          "isSigner": false
+          "name": "maxSell",
+          "type": "u64"
         },
         {
-          "name": "settleBank",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
-          "name": "settleVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "priceLowerLimit",
+          "type": "f64"
         },
         {
-          "name": "settleOracle",
-          "isMut": false,
-          "isSigner": false
+          "name": "priceUpperLimit",
+          "type": "f64"
         },
         {
-          "name": "insuranceVault",
-          "isMut": true,
-          "isSigner": false
+          "name": "maxPricePremiumRate",
+          "type": "f64"
         },
         {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
+          "name": "allowCreatingDeposits",
+          "type": "bool"
+        },
         {
-          "name": "maxLiabTransfer",
+          "name": "allowCreatingBorrows",
+          "type": "bool"
+        },
+        {
+          "name": "displayPriceStyle",
+          "type": {
+            "defined": "TokenConditionalSwapDisplayPriceStyle"
+          }
+        },
+        {
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
+        },
+        {
+          "name": "premiumAuctionDurationSeconds",
           "type": "u64"
         }
       ]
     },
     {
-      "name": "perpLiqNegativePnlOrBankruptcyV2",
+      "name": "tokenConditionalSwapCancel",
       "accounts": [
         {
           "name": "group",
           "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "insurance_vault"
-          ]
+          "isSigner": false
         },
         {
-          "name": "liqor",
+          "name": "account",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17458,34 +21015,51 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "liqorOwner",
+          "name": "authority",
           "isMut": false,
           "isSigner": true
         },
         {
-          "name": "liqee",
+          "name": "buyBank",
           "isMut": true,
           "isSigner": false,
+          "docs": [
+            "The bank's token_index is checked at #1"
+          ],
           "relations": [
             "group"
           ]
         },
         {
-          "name": "perpMarket",
+          "name": "sellBank",
           "isMut": true,
           "isSigner": false,
           "relations": [
-            "group",
-            "oracle"
+            "group"
           ]
+        }
+      ],
+      "args": [
+        {
+          "name": "tokenConditionalSwapIndex",
+          "type": "u8"
         },
         {
-          "name": "oracle",
+          "name": "tokenConditionalSwapId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "tokenConditionalSwapTrigger",
+      "accounts": [
+        {
+          "name": "group",
           "isMut": false,
           "isSigner": false
         },
         {
-          "name": "settleBank",
+          "name": "liqee",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17493,22 +21067,7 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "settleVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "settleOracle",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "insuranceVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "insuranceBank",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17516,30 +21075,32 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "insuranceBankVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "insuranceOracle",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
+          "name": "liqorAuthority",
           "isMut": false,
-          "isSigner": false
+          "isSigner": true
         }
       ],
       "args": [
         {
-          "name": "maxLiabTransfer",
+          "name": "tokenConditionalSwapIndex",
+          "type": "u8"
+        },
+        {
+          "name": "tokenConditionalSwapId",
+          "type": "u64"
+        },
+        {
+          "name": "maxBuyTokenToLiqee",
+          "type": "u64"
+        },
+        {
+          "name": "maxSellTokenToLiqor",
           "type": "u64"
         }
       ]
     },
     {
-      "name": "tokenConditionalSwapCreate",
+      "name": "perpConditionalOrderCreate",
       "accounts": [
         {
           "name": "group",
@@ -17560,15 +21121,7 @@ export const IDL: MangoV4 = {
           "isSigner": true
         },
         {
-          "name": "buyBank",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "sellBank",
+          "name": "perpMarket",
           "isMut": false,
           "isSigner": false,
           "relations": [
@@ -17578,12 +21131,14 @@ export const IDL: MangoV4 = {
       ],
       "args": [
         {
-          "name": "maxBuy",
-          "type": "u64"
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
         },
         {
-          "name": "maxSell",
-          "type": "u64"
+          "name": "maxBaseLots",
+          "type": "i64"
         },
         {
           "name": "expiryTimestamp",
@@ -17602,17 +21157,23 @@ export const IDL: MangoV4 = {
           "type": "f64"
         },
         {
-          "name": "allowCreatingDeposits",
+          "name": "reduceOnly",
           "type": "bool"
         },
         {
-          "name": "allowCreatingBorrows",
-          "type": "bool"
+          "name": "intention",
+          "type": {
+            "defined": "TokenConditionalSwapIntention"
+          }
         }
       ]
     },
     {
-      "name": "tokenConditionalSwapCreateV2",
+      "name": "perpConditionalOrderCreatePlaceOrder",
+      "docs": [
+        "Creates a perp conditional order that places an order into the book once",
+        "the oracle price is in range, like a stop or take-profit order."
+      ],
       "accounts": [
         {
           "name": "group",
@@ -17633,15 +21194,7 @@ export const IDL: MangoV4 = {
           "isSigner": true
         },
         {
-          "name": "buyBank",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "sellBank",
+          "name": "perpMarket",
           "isMut": false,
           "isSigner": false,
           "relations": [
@@ -17651,42 +21204,70 @@ export const IDL: MangoV4 = {
       ],
       "args": [
         {
-          "name": "maxBuy",
-          "type": "u64"
+          "name": "side",
+          "type": {
+            "defined": "Side"
+          }
         },
         {
-          "name": "maxSell",
-          "type": "u64"
+          "name": "priceLowerLimit",
+          "type": "f64"
         },
         {
-          "name": "expiryTimestamp",
-          "type": "u64"
+          "name": "priceUpperLimit",
+          "type": "f64"
         },
         {
-          "name": "priceLowerLimit",
-          "type": "f64"
+          "name": "priceLots",
+          "type": "i64"
+        },
+        {
+          "name": "pegToOracle",
+          "type": "bool"
+        },
+        {
+          "name": "pegLimit",
+          "type": "i64"
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64"
+        },
+        {
+          "name": "maxQuoteLots",
+          "type": "i64"
+        },
+        {
+          "name": "clientOrderId",
+          "type": "u64"
         },
         {
-          "name": "priceUpperLimit",
-          "type": "f64"
+          "name": "orderType",
+          "type": {
+            "defined": "PlaceOrderType"
+          }
         },
         {
-          "name": "pricePremiumRate",
-          "type": "f64"
+          "name": "selfTradeBehavior",
+          "type": {
+            "defined": "SelfTradeBehavior"
+          }
         },
         {
-          "name": "allowCreatingDeposits",
+          "name": "reduceOnly",
           "type": "bool"
         },
         {
-          "name": "allowCreatingBorrows",
-          "type": "bool"
+          "name": "timeInForce",
+          "type": "u16"
         },
         {
-          "name": "displayPriceStyle",
-          "type": {
-            "defined": "TokenConditionalSwapDisplayPriceStyle"
-          }
+          "name": "triggerPremium",
+          "type": "u64"
+        },
+        {
+          "name": "expiryTimestamp",
+          "type": "u64"
         },
         {
           "name": "intention",
@@ -17697,7 +21278,7 @@ export const IDL: MangoV4 = {
       ]
     },
     {
-      "name": "tokenConditionalSwapCancel",
+      "name": "perpConditionalOrderCancel",
       "accounts": [
         {
           "name": "group",
@@ -17716,40 +21297,80 @@ export const IDL: MangoV4 = {
           "name": "authority",
           "isMut": false,
           "isSigner": true
+        }
+      ],
+      "args": [
+        {
+          "name": "perpConditionalOrderIndex",
+          "type": "u8"
         },
         {
-          "name": "buyBank",
+          "name": "perpConditionalOrderId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "perpConditionalOrderTrigger",
+      "accounts": [
+        {
+          "name": "group",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "liqee",
           "isMut": true,
           "isSigner": false,
-          "docs": [
-            "The bank's token_index is checked at #1"
-          ],
           "relations": [
             "group"
           ]
         },
         {
-          "name": "sellBank",
+          "name": "liqor",
           "isMut": true,
           "isSigner": false,
           "relations": [
             "group"
           ]
+        },
+        {
+          "name": "liqorAuthority",
+          "isMut": false,
+          "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "oracle"
+          ]
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "tokenConditionalSwapIndex",
+          "name": "perpConditionalOrderIndex",
           "type": "u8"
         },
         {
-          "name": "tokenConditionalSwapId",
+          "name": "perpConditionalOrderId",
           "type": "u64"
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64"
         }
       ]
     },
     {
-      "name": "tokenConditionalSwapTrigger",
+      "name": "perpConditionalOrderTriggerPlaceOrder",
       "accounts": [
         {
           "name": "group",
@@ -17757,7 +21378,7 @@ export const IDL: MangoV4 = {
           "isSigner": false
         },
         {
-          "name": "liqee",
+          "name": "account",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17765,7 +21386,7 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "liqor",
+          "name": "triggerer",
           "isMut": true,
           "isSigner": false,
           "relations": [
@@ -17773,27 +21394,55 @@ export const IDL: MangoV4 = {
           ]
         },
         {
-          "name": "liqorAuthority",
+          "name": "triggererAuthority",
           "isMut": false,
           "isSigner": true
+        },
+        {
+          "name": "perpMarket",
+          "isMut": true,
+          "isSigner": false,
+          "relations": [
+            "group",
+            "bids",
+            "asks",
+            "event_queue",
+            "oracle"
+          ]
+        },
+        {
+          "name": "bids",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "asks",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "eventQueue",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "oracle",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "tokenConditionalSwapIndex",
+          "name": "perpConditionalOrderIndex",
           "type": "u8"
         },
         {
-          "name": "tokenConditionalSwapId",
-          "type": "u64"
-        },
-        {
-          "name": "maxBuyTokenToLiqee",
+          "name": "perpConditionalOrderId",
           "type": "u64"
         },
         {
-          "name": "maxSellTokenToLiqor",
-          "type": "u64"
+          "name": "limit",
+          "type": "u8"
         }
       ]
     },
@@ -18232,6 +21881,11 @@ export const IDL: MangoV4 = {
           "name": "solDestination",
           "isMut": true,
           "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": []
@@ -18265,7 +21919,12 @@ export const IDL: MangoV4 = {
         {
           "name": "openbookV2Market",
           "isMut": false,
-          "isSigner": false
+          "isSigner": false,
+          "relations": [
+            "group",
+            "openbook_v2_program",
+            "openbook_v2_market_external"
+          ]
         },
         {
           "name": "openbookV2Program",
@@ -18438,11 +22097,6 @@ export const IDL: MangoV4 = {
           "isMut": true,
           "isSigner": false
         },
-        {
-          "name": "marketRequestQueue",
-          "isMut": true,
-          "isSigner": false
-        },
         {
           "name": "marketBaseVault",
           "isMut": true,
@@ -18482,10 +22136,34 @@ export const IDL: MangoV4 = {
           "isMut": false,
           "isSigner": false
         },
+        {
+          "name": "receiverBank",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The bank that receives the funds from the order"
+          ],
+          "relations": [
+            "group"
+          ]
+        },
+        {
+          "name": "receiverVault",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The bank vault that receives the funds from the order"
+          ]
+        },
         {
           "name": "tokenProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
@@ -19210,12 +22888,48 @@ export const IDL: MangoV4 = {
             "name": "flashLoanSwapFeeRate",
             "type": "f32"
           },
+          {
+            "name": "tokenInsuranceFund",
+            "docs": [
+              "The TokenInsuranceFund for this token, or the default pubkey if there is none.",
+              "",
+              "Bankruptcy instructions must use the fund when it exists."
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "padding2",
+            "type": {
+              "array": [
+                "u8",
+                4
+              ]
+            }
+          },
+          {
+            "name": "otherBanksIndexedDeposits",
+            "docs": [
+              "Sum of indexed_deposits/indexed_borrows of all other banks of the same token",
+              "",
+              "Updated in token_update_index_and_rate. Together with this bank's own values this",
+              "approximates the token-wide deposits and borrows, see native_deposits_all_banks()."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "otherBanksIndexedBorrows",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                2092
+                2024
               ]
             }
           }
@@ -19658,15 +23372,85 @@ export const IDL: MangoV4 = {
             }
           },
           {
-            "name": "registrationTime",
-            "type": "u64"
+            "name": "registrationTime",
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                512
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "openbookV2MarketIndexReservation",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "group",
+            "type": "publicKey"
+          },
+          {
+            "name": "marketIndex",
+            "type": "u16"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                38
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "stubOracle",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "group",
+            "type": "publicKey"
+          },
+          {
+            "name": "mint",
+            "type": "publicKey"
+          },
+          {
+            "name": "price",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "lastUpdateTs",
+            "type": "i64"
+          },
+          {
+            "name": "lastUpdateSlot",
+            "type": "u64"
+          },
+          {
+            "name": "deviation",
+            "type": {
+              "defined": "I80F48"
+            }
           },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                512
+                104
               ]
             }
           }
@@ -19674,7 +23458,20 @@ export const IDL: MangoV4 = {
       }
     },
     {
-      "name": "openbookV2MarketIndexReservation",
+      "name": "twapOracle",
+      "docs": [
+        "Oracle that tracks a time weighted average of the price of a raydium",
+        "concentrated liquidity pool.",
+        "",
+        "The pool must pair the oracle's mint with a token that has the same value and",
+        "decimals as the group's quote token, since the pool price is used as-is.",
+        "",
+        "Anyone may update the oracle. Each sample is weighted by the time elapsed since the",
+        "previous update, and clamped to within max_sample_deviation of the average, so a",
+        "short lived manipulation of the pool price can only move the average a limited",
+        "amount. The deviation reported to the confidence check is the difference between",
+        "the latest (unclamped) sample and the average."
+      ],
       "type": {
         "kind": "struct",
         "fields": [
@@ -19683,36 +23480,39 @@ export const IDL: MangoV4 = {
             "type": "publicKey"
           },
           {
-            "name": "marketIndex",
-            "type": "u16"
+            "name": "mint",
+            "type": "publicKey"
           },
           {
-            "name": "reserved",
+            "name": "pool",
+            "docs": [
+              "The raydium clmm pool the price is sampled from"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "twapPrice",
+            "docs": [
+              "Time weighted average price, in native quote per native base"
+            ],
             "type": {
-              "array": [
-                "u8",
-                38
-              ]
+              "defined": "I80F48"
             }
-          }
-        ]
-      }
-    },
-    {
-      "name": "stubOracle",
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "group",
-            "type": "publicKey"
           },
           {
-            "name": "mint",
-            "type": "publicKey"
+            "name": "lastSamplePrice",
+            "docs": [
+              "Pool price at the last update, in native quote per native base"
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
           },
           {
-            "name": "price",
+            "name": "maxSampleDeviation",
+            "docs": [
+              "Samples are clamped to twap_price * (1 +- max_sample_deviation)"
+            ],
             "type": {
               "defined": "I80F48"
             }
@@ -19726,9 +23526,27 @@ export const IDL: MangoV4 = {
             "type": "u64"
           },
           {
-            "name": "deviation",
+            "name": "windowSecs",
+            "docs": [
+              "Averaging window in seconds: a sample that stayed in effect this long fully",
+              "replaces the average"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "invert",
+            "docs": [
+              "1 if mint is the pool's token_1, meaning the pool price needs to be inverted"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "padding",
             "type": {
-              "defined": "I80F48"
+              "array": [
+                "u8",
+                7
+              ]
             }
           },
           {
@@ -19736,7 +23554,7 @@ export const IDL: MangoV4 = {
             "type": {
               "array": [
                 "u8",
-                104
+                112
               ]
             }
           }
@@ -20106,8 +23924,10 @@ export const IDL: MangoV4 = {
           {
             "name": "feesSettled",
             "docs": [
-              "Fees settled in native quote currency",
-              "these are increased when perp_settle_fees is called, and never decreased"
+              "Fees settled in native settle token, converted at the settle token oracle price",
+              "these are increased when perp_settle_fees is called, and never decreased.",
+              "This is what admin_perp_withdraw_fees withdraws, see fees_settled_quote for the",
+              "same amounts in quote units."
             ],
             "type": {
               "defined": "I80F48"
@@ -20221,12 +24041,22 @@ export const IDL: MangoV4 = {
             "name": "feesWithdrawn",
             "type": "u64"
           },
+          {
+            "name": "feesSettledQuote",
+            "docs": [
+              "Fees settled in native quote currency, the amounts that perp_settle_fees removed",
+              "from fees_accrued. Only counts settlements since this field was introduced."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                1880
+                1864
               ]
             }
           }
@@ -20341,6 +24171,72 @@ export const IDL: MangoV4 = {
           }
         ]
       }
+    },
+    {
+      "name": "tokenInsuranceFund",
+      "docs": [
+        "An insurance fund for a single token.",
+        "",
+        "Bankruptcies in the token, or in perp markets that settle in the token, are",
+        "covered by this fund before the group insurance fund is used and before any",
+        "remaining loss gets socialized.",
+        "",
+        "The vault is owned by the group and holds tokens of the fund's mint."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "group",
+            "type": "publicKey"
+          },
+          {
+            "name": "tokenIndex",
+            "type": "u16"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "vaultBump",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                4
+              ]
+            }
+          },
+          {
+            "name": "mint",
+            "type": "publicKey"
+          },
+          {
+            "name": "vault",
+            "type": "publicKey"
+          },
+          {
+            "name": "totalUsedNative",
+            "docs": [
+              "Total native tokens paid out of the fund to cover bankruptcies"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                128
+              ]
+            }
+          }
+        ]
+      }
     }
   ],
   "types": [
@@ -20578,13 +24474,61 @@ export const IDL: MangoV4 = {
         ]
       }
     },
+    {
+      "name": "OpenbookV2Info",
+      "docs": [
+        "Information about reserved funds on openbook v2 open orders accounts.",
+        "",
+        "Like for Serum3Info, the free funds are added directly to the token info."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "reservedBase",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "reservedQuote",
+            "type": {
+              "defined": "I80F48"
+            }
+          },
+          {
+            "name": "baseInfoIndex",
+            "type": "u64"
+          },
+          {
+            "name": "quoteInfoIndex",
+            "type": "u64"
+          },
+          {
+            "name": "marketIndex",
+            "type": "u16"
+          },
+          {
+            "name": "hasZeroFunds",
+            "docs": [
+              "The open orders account has no free or reserved funds"
+            ],
+            "type": "bool"
+          }
+        ]
+      }
+    },
     {
       "name": "PerpInfo",
       "docs": [
         "Stores information about perp market positions and their open orders.",
         "",
         "Perp markets affect account health indirectly, though the token balance in the",
-        "perp market's settle token. See `effective_token_balances()`."
+        "perp market's settle token. See `effective_token_balances()`.",
+        "",
+        "Perp positions and oracles use the perp quote currency, but the quote amounts and",
+        "prices stored here are converted to settle token native units using the settle",
+        "token's oracle price."
       ],
       "type": {
         "kind": "struct",
@@ -20668,6 +24612,20 @@ export const IDL: MangoV4 = {
           {
             "name": "hasOpenFills",
             "type": "bool"
+          },
+          {
+            "name": "isolated",
+            "docs": [
+              "Isolated positions don't contribute to the account's cross-margined health,",
+              "see HealthCache::isolated_perp_health()"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "isolatedCollateral",
+            "type": {
+              "defined": "I80F48"
+            }
           }
         ]
       }
@@ -20719,6 +24677,14 @@ export const IDL: MangoV4 = {
           {
             "name": "beingLiquidated",
             "type": "bool"
+          },
+          {
+            "name": "openbookV2Infos",
+            "type": {
+              "vec": {
+                "defined": "OpenbookV2Info"
+              }
+            }
           }
         ]
       }
@@ -20916,7 +24882,81 @@ export const IDL: MangoV4 = {
             "type": {
               "array": [
                 "u8",
-                64
+                64
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "OpenbookV2Orders",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "openOrders",
+            "type": "publicKey"
+          },
+          {
+            "name": "baseBorrowsWithoutFee",
+            "docs": [
+              "Tracks the amount of borrows that have flowed into the openbook open orders account.",
+              "These borrows did not have the loan origination fee applied, and that may happen",
+              "later (in openbook_v2_settle_funds) if we can guarantee that the funds were used.",
+              "In particular a place-on-book, cancel, settle should not cost fees."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "quoteBorrowsWithoutFee",
+            "type": "u64"
+          },
+          {
+            "name": "marketIndex",
+            "type": "u16"
+          },
+          {
+            "name": "baseTokenIndex",
+            "docs": [
+              "Store the base/quote token index, so health computations don't need",
+              "to get passed the static OpenbookV2Market to find which tokens a market",
+              "uses and look up the correct oracles."
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "quoteTokenIndex",
+            "type": "u16"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                2
+              ]
+            }
+          },
+          {
+            "name": "baseLotSize",
+            "docs": [
+              "Lot sizes of the external market, copied on open orders creation.",
+              "The openbook v2 open orders account tracks reserved amounts in lots and",
+              "health computations need to convert them to native without loading the market."
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "quoteLotSize",
+            "type": "i64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                48
               ]
             }
           }
@@ -21146,12 +25186,43 @@ export const IDL: MangoV4 = {
               "defined": "I80F48"
             }
           },
+          {
+            "name": "isolated",
+            "docs": [
+              "Whether the position is in isolated margin mode, see isolated_collateral_indexed"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "padding2",
+            "type": {
+              "array": [
+                "u8",
+                7
+              ]
+            }
+          },
+          {
+            "name": "isolatedCollateralIndexed",
+            "docs": [
+              "Settle token set aside to back an isolated position, indexed by the settle",
+              "bank's deposit_index. Use Bank::isolated_collateral_native() to get native units.",
+              "",
+              "Isolated positions are not part of the account's cross-margined health. Instead,",
+              "this collateral plus the position's health unsettled pnl must stay positive",
+              "and the position gets liquidated on its own. The collateral stays part of the",
+              "settle bank's indexed_deposits and earns deposit interest."
+            ],
+            "type": {
+              "defined": "I80F48"
+            }
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                88
+                64
               ]
             }
           }
@@ -21300,12 +25371,19 @@ export const IDL: MangoV4 = {
             "name": "temporaryDelegateExpiry",
             "type": "u64"
           },
+          {
+            "name": "nextPerpConditionalOrderId",
+            "docs": [
+              "Next id to use when adding a perp conditional order"
+            ],
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                160
+                152
               ]
             }
           }
@@ -21851,6 +25929,186 @@ export const IDL: MangoV4 = {
         ]
       }
     },
+    {
+      "name": "PerpConditionalOrder",
+      "docs": [
+        "A perp order that stays dormant in the MangoAccount until the perp oracle price",
+        "is in a range.",
+        "",
+        "Depending on `execution` a triggerer then either trades with the account directly,",
+        "like for a TokenConditionalSwap, or places an order into the orderbook on behalf",
+        "of the account."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "id",
+            "type": "u64"
+          },
+          {
+            "name": "maxBaseLots",
+            "docs": [
+              "maximum amount of base lots to trade"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "executedBaseLots",
+            "docs": [
+              "how many base lots were already traded, only used for TradeWithTriggerer"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "expiryTimestamp",
+            "docs": [
+              "timestamp until which the conditional order is valid"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "priceLowerLimit",
+            "docs": [
+              "The perp oracle price must be >= lower_limit and <= upper_limit for execution.",
+              "",
+              "Prices are in native quote per native base, like perp oracle prices.",
+              "",
+              "Example: Stop loss on a SOL-PERP long opened at 20 USDC/SOL, closing",
+              "the position below 18 USDC/SOL: side=Ask, reduce_only, price_lower_limit=0",
+              "and price_upper_limit=toNative(18)."
+            ],
+            "type": "f64"
+          },
+          {
+            "name": "priceUpperLimit",
+            "docs": [
+              "Parallel to price_lower_limit, but an upper limit."
+            ],
+            "type": "f64"
+          },
+          {
+            "name": "pricePremiumRate",
+            "docs": [
+              "The premium to pay over oracle price to incentivize execution, only used",
+              "for TradeWithTriggerer"
+            ],
+            "type": "f64"
+          },
+          {
+            "name": "priceLots",
+            "docs": [
+              "Limit price of the placed order in lots, only used for PlaceOrder.",
+              "",
+              "If peg_to_oracle is set, this is instead the offset to the oracle price",
+              "and the order is placed as an oracle pegged order. Ignored for market orders."
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "pegLimit",
+            "docs": [
+              "Only used for oracle pegged orders, see perp_place_order_pegged"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "maxQuoteLots",
+            "docs": [
+              "Only used for PlaceOrder"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "clientOrderId",
+            "docs": [
+              "Only used for PlaceOrder"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "triggerPremium",
+            "docs": [
+              "Amount of perp quote native paid to the triggerer when the order is placed,",
+              "only used for PlaceOrder"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "perpMarketIndex",
+            "type": "u16"
+          },
+          {
+            "name": "timeInForce",
+            "docs": [
+              "Time in force of the placed order in seconds, 0 meaning forever"
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "hasData",
+            "type": "u8"
+          },
+          {
+            "name": "side",
+            "docs": [
+              "The side the account trades on, stores a Side enum value"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "reduceOnly",
+            "docs": [
+              "may execution only reduce the perp base position?"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "intention",
+            "docs": [
+              "The intention the user had when placing this order, display-only",
+              "",
+              "Stores a TokenConditionalSwapIntention enum value"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "execution",
+            "docs": [
+              "Stores a PerpConditionalOrderExecution enum value"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "orderType",
+            "docs": [
+              "Stores a PlaceOrderType enum value"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "selfTradeBehavior",
+            "docs": [
+              "Stores a SelfTradeBehavior enum value"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "pegToOracle",
+            "type": "u8"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                20
+              ]
+            }
+          }
+        ]
+      }
+    },
     {
       "name": "StablePriceModel",
       "docs": [
@@ -22104,12 +26362,67 @@ export const IDL: MangoV4 = {
             ],
             "type": "u8"
           },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                7
+              ]
+            }
+          },
+          {
+            "name": "intervalSeconds",
+            "docs": [
+              "If nonzero, the tcs executes in slices: at most interval_max_sell native sell",
+              "tokens can be sold per interval and the next slice unlocks interval_seconds",
+              "after the current slice started."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "intervalMaxSell",
+            "docs": [
+              "Maximum amount of native sell tokens to sell per interval, see interval_seconds"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "intervalStartTimestamp",
+            "docs": [
+              "Timestamp of the first execution in the current interval"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "intervalSold",
+            "docs": [
+              "How many native sell tokens were already sold in the current interval"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "premiumAuctionDurationSeconds",
+            "docs": [
+              "If nonzero, the premium is a linear auction: it starts at zero when the tcs",
+              "is first triggered while the price is in range and rises to price_premium_rate",
+              "over this many seconds. The auction restarts if the price leaves the range."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "premiumAuctionStartTimestamp",
+            "docs": [
+              "Timestamp at which the premium auction started, 0 if it hasn't started"
+            ],
+            "type": "u64"
+          },
           {
             "name": "reserved",
             "type": {
               "array": [
                 "u8",
-                111
+                56
               ]
             }
           }
@@ -22294,6 +26607,18 @@ export const IDL: MangoV4 = {
           },
           {
             "name": "TokenConditionalSwapTrigger"
+          },
+          {
+            "name": "OpenbookV2LiqForceCancelOrders"
+          },
+          {
+            "name": "OpenbookV2PlaceOrder"
+          },
+          {
+            "name": "OpenbookV2SettleFunds"
+          },
+          {
+            "name": "OpenbookV2PlaceTakeOrder"
           }
         ]
       }
@@ -22490,34 +26815,64 @@ export const IDL: MangoV4 = {
             "name": "OpenbookV2CloseOpenOrders"
           },
           {
-            "name": "OpenbookV2CreateOpenOrders"
+            "name": "OpenbookV2CreateOpenOrders"
+          },
+          {
+            "name": "OpenbookV2DeregisterMarket"
+          },
+          {
+            "name": "OpenbookV2EditMarket"
+          },
+          {
+            "name": "OpenbookV2LiqForceCancelOrders"
+          },
+          {
+            "name": "OpenbookV2PlaceOrder"
+          },
+          {
+            "name": "OpenbookV2PlaceTakeOrder"
+          },
+          {
+            "name": "OpenbookV2RegisterMarket"
+          },
+          {
+            "name": "OpenbookV2SettleFunds"
+          },
+          {
+            "name": "AdminTokenWithdrawFees"
+          },
+          {
+            "name": "AdminPerpWithdrawFees"
+          },
+          {
+            "name": "TwapOracleCreate"
           },
           {
-            "name": "OpenbookV2DeregisterMarket"
+            "name": "TwapOracleUpdate"
           },
           {
-            "name": "OpenbookV2EditMarket"
+            "name": "TokenInsuranceFundCreate"
           },
           {
-            "name": "OpenbookV2LiqForceCancelOrders"
+            "name": "TokenInsuranceFundDeposit"
           },
           {
-            "name": "OpenbookV2PlaceOrder"
+            "name": "TokenInsuranceFundWithdraw"
           },
           {
-            "name": "OpenbookV2PlaceTakeOrder"
+            "name": "PerpConditionalOrderCreate"
           },
           {
-            "name": "OpenbookV2RegisterMarket"
+            "name": "PerpConditionalOrderCancel"
           },
           {
-            "name": "OpenbookV2SettleFunds"
+            "name": "PerpConditionalOrderTrigger"
           },
           {
-            "name": "AdminTokenWithdrawFees"
+            "name": "PerpIsolatedMarginTransfer"
           },
           {
-            "name": "AdminPerpWithdrawFees"
+            "name": "PerpLiqIsolatedPosition"
           }
         ]
       }
@@ -22555,6 +26910,12 @@ export const IDL: MangoV4 = {
           },
           {
             "name": "SwitchboardV2"
+          },
+          {
+            "name": "PythV2"
+          },
+          {
+            "name": "Twap"
           }
         ]
       }
@@ -22778,213 +27139,598 @@ export const IDL: MangoV4 = {
           {
             "name": "Bids"
           },
-          {
-            "name": "Asks"
-          }
-        ]
-      }
+          {
+            "name": "Asks"
+          }
+        ]
+      }
+    },
+    {
+      "name": "EventType",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Fill"
+          },
+          {
+            "name": "Out"
+          },
+          {
+            "name": "Liquidate"
+          }
+        ]
+      }
+    },
+    {
+      "name": "PerpConditionalOrderExecution",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "TradeWithTriggerer"
+          },
+          {
+            "name": "PlaceOrder"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TokenConditionalSwapDisplayPriceStyle",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "SellTokenPerBuyToken"
+          },
+          {
+            "name": "BuyTokenPerSellToken"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TokenConditionalSwapIntention",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Unknown"
+          },
+          {
+            "name": "StopLoss"
+          },
+          {
+            "name": "TakeProfit"
+          }
+        ]
+      }
+    }
+  ],
+  "events": [
+    {
+      "name": "MangoAccountData",
+      "fields": [
+        {
+          "name": "healthCache",
+          "type": {
+            "defined": "HealthCache"
+          },
+          "index": false
+        },
+        {
+          "name": "initHealth",
+          "type": {
+            "defined": "I80F48"
+          },
+          "index": false
+        },
+        {
+          "name": "maintHealth",
+          "type": {
+            "defined": "I80F48"
+          },
+          "index": false
+        },
+        {
+          "name": "equity",
+          "type": {
+            "defined": "Equity"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpBalanceLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "basePosition",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quotePosition",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "longSettledFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortSettledFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "longFunding",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "shortFunding",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TokenBalanceLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "indexedPosition",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "depositIndex",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "borrowIndex",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "FlashLoanLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenLoanDetails",
+          "type": {
+            "vec": {
+              "defined": "FlashLoanTokenDetail"
+            }
+          },
+          "index": false
+        },
+        {
+          "name": "flashLoanType",
+          "type": {
+            "defined": "FlashLoanType"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "FlashLoanLogV2",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenLoanDetails",
+          "type": {
+            "vec": {
+              "defined": "FlashLoanTokenDetailV2"
+            }
+          },
+          "index": false
+        },
+        {
+          "name": "flashLoanType",
+          "type": {
+            "defined": "FlashLoanType"
+          },
+          "index": false
+        }
+      ]
     },
     {
-      "name": "EventType",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Fill"
-          },
-          {
-            "name": "Out"
-          },
-          {
-            "name": "Liquidate"
-          }
-        ]
-      }
+      "name": "WithdrawLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "TokenConditionalSwapDisplayPriceStyle",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "SellTokenPerBuyToken"
-          },
-          {
-            "name": "BuyTokenPerSellToken"
-          }
-        ]
-      }
+      "name": "DepositLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "signer",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "TokenConditionalSwapIntention",
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "Unknown"
-          },
-          {
-            "name": "StopLoss"
-          },
-          {
-            "name": "TakeProfit"
-          }
-        ]
-      }
-    }
-  ],
-  "events": [
+      "name": "FillLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "takerSide",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerSlot",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerOut",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "makerOrderId",
+          "type": "u128",
+          "index": false
+        },
+        {
+          "name": "makerFee",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "makerTimestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "taker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "takerOrderId",
+          "type": "u128",
+          "index": false
+        },
+        {
+          "name": "takerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "takerFee",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "i64",
+          "index": false
+        }
+      ]
+    },
     {
-      "name": "MangoAccountData",
+      "name": "FillLogV2",
       "fields": [
         {
-          "name": "healthCache",
-          "type": {
-            "defined": "HealthCache"
-          },
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "marketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "takerSide",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerSlot",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "makerOut",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "timestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maker",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "makerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "makerFee",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "initHealth",
-          "type": {
-            "defined": "I80F48"
-          },
+          "name": "makerTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "maintHealth",
-          "type": {
-            "defined": "I80F48"
-          },
+          "name": "taker",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "equity",
-          "type": {
-            "defined": "Equity"
-          },
+          "name": "takerClientOrderId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "takerFee",
+          "type": "f32",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quantity",
+          "type": "i64",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpBalanceLog",
+      "name": "FillLogV3",
       "fields": [
         {
           "name": "mangoGroup",
           "type": "publicKey",
           "index": false
         },
-        {
-          "name": "mangoAccount",
-          "type": "publicKey",
-          "index": false
-        },
         {
           "name": "marketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "basePosition",
-          "type": "i64",
+          "name": "takerSide",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "quotePosition",
-          "type": "i128",
+          "name": "makerSlot",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "longSettledFunding",
-          "type": "i128",
+          "name": "makerOut",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "shortSettledFunding",
-          "type": "i128",
+          "name": "timestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "longFunding",
-          "type": "i128",
+          "name": "seqNum",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "shortFunding",
-          "type": "i128",
+          "name": "maker",
+          "type": "publicKey",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "TokenBalanceLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "makerClientOrderId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "makerFee",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "makerTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "indexedPosition",
-          "type": "i128",
+          "name": "taker",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "depositIndex",
-          "type": "i128",
+          "name": "takerClientOrderId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "borrowIndex",
-          "type": "i128",
+          "name": "takerFee",
+          "type": "f32",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "FlashLoanLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "price",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "quantity",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "tokenLoanDetails",
-          "type": {
-            "vec": {
-              "defined": "FlashLoanTokenDetail"
-            }
-          },
+          "name": "makerClosedPnl",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "flashLoanType",
-          "type": {
-            "defined": "FlashLoanType"
-          },
+          "name": "takerClosedPnl",
+          "type": "f64",
           "index": false
         }
       ]
     },
     {
-      "name": "FlashLoanLogV2",
+      "name": "PerpUpdateFundingLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -22992,65 +27738,59 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "tokenLoanDetails",
-          "type": {
-            "vec": {
-              "defined": "FlashLoanTokenDetailV2"
-            }
-          },
+          "name": "longFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "flashLoanType",
-          "type": {
-            "defined": "FlashLoanType"
-          },
+          "name": "shortFunding",
+          "type": "i128",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "WithdrawLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "price",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "oracleSlot",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "signer",
-          "type": "publicKey",
+          "name": "stablePrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "feesAccrued",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "u64",
+          "name": "feesSettled",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "price",
+          "name": "openInterest",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "instantaneousFundingRate",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "DepositLog",
+      "name": "PerpUpdateFundingLogV2",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23058,124 +27798,141 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "signer",
-          "type": "publicKey",
+          "name": "longFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "shortFunding",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
+          "name": "price",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "oracleSlot",
           "type": "u64",
           "index": false
         },
         {
-          "name": "price",
+          "name": "oracleConfidence",
           "type": "i128",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "FillLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "oracleType",
+          "type": {
+            "defined": "OracleType"
+          },
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "stablePrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
+          "name": "feesAccrued",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerSlot",
-          "type": "u8",
+          "name": "feesSettled",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerOut",
-          "type": "bool",
+          "name": "openInterest",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "timestamp",
-          "type": "u64",
+          "name": "instantaneousFundingRate",
+          "type": "i128",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "UpdateIndexLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "seqNum",
-          "type": "u64",
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "depositIndex",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "maker",
-          "type": "publicKey",
+          "name": "borrowIndex",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerOrderId",
-          "type": "u128",
+          "name": "avgUtilization",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerFee",
+          "name": "price",
           "type": "i128",
           "index": false
         },
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "stablePrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "taker",
-          "type": "publicKey",
+          "name": "collectedFees",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerOrderId",
-          "type": "u128",
+          "name": "loanFeeRate",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "totalBorrows",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerFee",
+          "name": "totalDeposits",
           "type": "i128",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i64",
+          "name": "borrowRate",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "i64",
+          "name": "depositRate",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "FillLogV2",
+      "name": "UpdateRateLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23183,84 +27940,84 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
+          "name": "tokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
-          "index": false
-        },
-        {
-          "name": "makerSlot",
-          "type": "u8",
+          "name": "rate0",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "makerOut",
-          "type": "bool",
+          "name": "rate1",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "timestamp",
-          "type": "u64",
+          "name": "maxRate",
+          "type": "i128",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "TokenLiqWithTokenLog",
+      "fields": [
         {
-          "name": "seqNum",
-          "type": "u64",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "maker",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerClientOrderId",
-          "type": "u64",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerFee",
-          "type": "f32",
+          "name": "assetTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "liabTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "taker",
-          "type": "publicKey",
+          "name": "assetTransfer",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "liabTransfer",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerFee",
-          "type": "f32",
+          "name": "assetPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i64",
+          "name": "liabPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "i64",
+          "name": "bankruptcy",
+          "type": "bool",
           "index": false
         }
       ]
     },
     {
-      "name": "FillLogV3",
+      "name": "Serum3OpenOrdersBalanceLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23268,94 +28025,104 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerSlot",
-          "type": "u8",
+          "name": "quoteTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerOut",
-          "type": "bool",
+          "name": "baseTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "timestamp",
+          "name": "baseFree",
           "type": "u64",
           "index": false
         },
         {
-          "name": "seqNum",
+          "name": "quoteTotal",
           "type": "u64",
           "index": false
         },
         {
-          "name": "maker",
-          "type": "publicKey",
+          "name": "quoteFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "makerClientOrderId",
+          "name": "referrerRebatesAccrued",
           "type": "u64",
           "index": false
+        }
+      ]
+    },
+    {
+      "name": "Serum3OpenOrdersBalanceLogV2",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
         },
         {
-          "name": "makerFee",
-          "type": "f32",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "makerTimestamp",
-          "type": "u64",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "taker",
-          "type": "publicKey",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "takerClientOrderId",
-          "type": "u64",
+          "name": "quoteTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "takerFee",
-          "type": "f32",
+          "name": "baseTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i64",
+          "name": "baseFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "quantity",
-          "type": "i64",
+          "name": "quoteTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "makerClosedPnl",
-          "type": "f64",
+          "name": "quoteFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "takerClosedPnl",
-          "type": "f64",
+          "name": "referrerRebatesAccrued",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpUpdateFundingLog",
+      "name": "OpenbookV2OpenOrdersBalanceLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23363,59 +28130,54 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "longFunding",
-          "type": "i128",
+          "name": "marketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "shortFunding",
-          "type": "i128",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i128",
+          "name": "quoteTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "oracleSlot",
+          "name": "baseTotal",
           "type": "u64",
           "index": false
         },
         {
-          "name": "stablePrice",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "feesAccrued",
-          "type": "i128",
+          "name": "baseFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "feesSettled",
-          "type": "i128",
+          "name": "quoteTotal",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "openInterest",
-          "type": "i64",
+          "name": "quoteFree",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "instantaneousFundingRate",
-          "type": "i128",
+          "name": "referrerRebatesAvailable",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpUpdateFundingLogV2",
+      "name": "WithdrawLoanOriginationFeeLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23423,71 +28185,75 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "longFunding",
-          "type": "i128",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "shortFunding",
-          "type": "i128",
+          "name": "tokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "price",
+          "name": "loanOriginationFee",
           "type": "i128",
           "index": false
         },
         {
-          "name": "oracleSlot",
-          "type": "u64",
+          "name": "instruction",
+          "type": {
+            "defined": "LoanOriginationFeeInstruction"
+          },
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "WithdrawLoanLog",
+      "fields": [
         {
-          "name": "oracleConfidence",
-          "type": "i128",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "oracleType",
-          "type": {
-            "defined": "OracleType"
-          },
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "stablePrice",
-          "type": "i128",
+          "name": "tokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "feesAccrued",
+          "name": "loanAmount",
           "type": "i128",
           "index": false
         },
         {
-          "name": "feesSettled",
+          "name": "loanOriginationFee",
           "type": "i128",
           "index": false
         },
         {
-          "name": "openInterest",
-          "type": "i64",
+          "name": "instruction",
+          "type": {
+            "defined": "LoanOriginationFeeInstruction"
+          },
           "index": false
         },
         {
-          "name": "instantaneousFundingRate",
-          "type": "i128",
+          "name": "price",
+          "type": {
+            "option": "i128"
+          },
           "index": false
         }
       ]
     },
     {
-      "name": "UpdateIndexLog",
+      "name": "TokenLiqBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23495,69 +28261,59 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "depositIndex",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "borrowIndex",
-          "type": "i128",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "avgUtilization",
-          "type": "i128",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i128",
+          "name": "liabTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "stablePrice",
+          "name": "initialLiabNative",
           "type": "i128",
           "index": false
         },
         {
-          "name": "collectedFees",
+          "name": "liabPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "loanFeeRate",
-          "type": "i128",
+          "name": "insuranceTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "totalBorrows",
+          "name": "insuranceTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "totalDeposits",
+          "name": "socializedLoss",
           "type": "i128",
           "index": false
         },
         {
-          "name": "borrowRate",
+          "name": "startingLiabDepositIndex",
           "type": "i128",
           "index": false
         },
         {
-          "name": "depositRate",
+          "name": "endingLiabDepositIndex",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "UpdateRateLog",
+      "name": "TokenInsuranceFundUseLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23565,29 +28321,34 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "rate0",
-          "type": "i128",
+          "name": "tokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "rate1",
-          "type": "i128",
+          "name": "perpMarketIndex",
+          "docs": [
+            "Set when the payout covered a perp market bankruptcy"
+          ],
+          "type": {
+            "option": "u16"
+          },
           "index": false
         },
         {
-          "name": "maxRate",
-          "type": "i128",
+          "name": "amount",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenLiqWithTokenLog",
+      "name": "DeactivateTokenPositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23595,54 +28356,74 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "tokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "cumulativeDepositInterest",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "cumulativeBorrowInterest",
+          "type": "f64",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "DeactivatePerpPositionLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "assetTokenIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "liabTokenIndex",
+          "name": "marketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "assetTransfer",
-          "type": "i128",
+          "name": "cumulativeLongFunding",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "liabTransfer",
-          "type": "i128",
+          "name": "cumulativeShortFunding",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "assetPrice",
-          "type": "i128",
+          "name": "makerVolume",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "liabPrice",
-          "type": "i128",
+          "name": "takerVolume",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "bankruptcy",
-          "type": "bool",
+          "name": "perpSpotTransfers",
+          "type": "i64",
           "index": false
         }
       ]
     },
     {
-      "name": "Serum3OpenOrdersBalanceLog",
+      "name": "TokenMetaDataLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23650,49 +28431,74 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "mint",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "baseTokenIndex",
+          "name": "tokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "quoteTokenIndex",
-          "type": "u16",
+          "name": "mintDecimals",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "baseTotal",
-          "type": "u64",
+          "name": "oracle",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "baseFree",
-          "type": "u64",
+          "name": "mintInfo",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpMarketMetaDataLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "quoteTotal",
-          "type": "u64",
+          "name": "perpMarket",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "quoteFree",
-          "type": "u64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "referrerRebatesAccrued",
-          "type": "u64",
+          "name": "baseDecimals",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "baseLotSize",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quoteLotSize",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "oracle",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "Serum3OpenOrdersBalanceLogV2",
+      "name": "Serum3RegisterMarketLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23700,7 +28506,7 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "serumMarket",
           "type": "publicKey",
           "index": false
         },
@@ -23720,34 +28526,19 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "baseTotal",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "baseFree",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "quoteTotal",
-          "type": "u64",
-          "index": false
-        },
-        {
-          "name": "quoteFree",
-          "type": "u64",
+          "name": "serumProgram",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "referrerRebatesAccrued",
-          "type": "u64",
+          "name": "serumProgramExternal",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "WithdrawLoanOriginationFeeLog",
+      "name": "OpenbookV2RegisterMarketLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23755,31 +28546,39 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "openbookMarket",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
+          "name": "marketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "loanOriginationFee",
-          "type": "i128",
+          "name": "baseTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "instruction",
-          "type": {
-            "defined": "LoanOriginationFeeInstruction"
-          },
+          "name": "quoteTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "openbookProgram",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "openbookMarketExternal",
+          "type": "publicKey",
           "index": false
         }
       ]
     },
     {
-      "name": "WithdrawLoanLog",
+      "name": "PerpLiqBaseOrPositivePnlLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23787,43 +28586,49 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "liqee",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "baseTransfer",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "loanAmount",
+          "name": "quoteTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "loanOriginationFee",
+          "name": "pnlTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "instruction",
-          "type": {
-            "defined": "LoanOriginationFeeInstruction"
-          },
+          "name": "pnlSettleLimitTransfer",
+          "type": "i128",
           "index": false
         },
         {
           "name": "price",
-          "type": {
-            "option": "i128"
-          },
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenLiqBankruptcyLog",
+      "name": "PerpLiqBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23841,49 +28646,44 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "liabTokenIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "initialLiabNative",
+          "name": "insuranceTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "liabPrice",
+          "name": "socializedLoss",
           "type": "i128",
           "index": false
         },
         {
-          "name": "insuranceTokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "insuranceTransfer",
+          "name": "startingLongFunding",
           "type": "i128",
           "index": false
         },
         {
-          "name": "socializedLoss",
+          "name": "startingShortFunding",
           "type": "i128",
           "index": false
         },
         {
-          "name": "startingLiabDepositIndex",
+          "name": "endingLongFunding",
           "type": "i128",
           "index": false
         },
         {
-          "name": "endingLiabDepositIndex",
+          "name": "endingShortFunding",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "DeactivateTokenPositionLog",
+      "name": "PerpLiqNegativePnlOrBankruptcyLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23891,29 +28691,29 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
-          "type": "u16",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "cumulativeDepositInterest",
-          "type": "f64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "cumulativeBorrowInterest",
-          "type": "f64",
+          "name": "settlement",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "DeactivatePerpPositionLog",
+      "name": "PerpSettlePnlLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23921,44 +28721,39 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "mangoAccountA",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "marketIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "cumulativeLongFunding",
-          "type": "f64",
+          "name": "mangoAccountB",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "cumulativeShortFunding",
-          "type": "f64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "makerVolume",
-          "type": "u64",
+          "name": "settlement",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "takerVolume",
-          "type": "u64",
+          "name": "settler",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "perpSpotTransfers",
-          "type": "i64",
+          "name": "fee",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenMetaDataLog",
+      "name": "PerpSettleFeesLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -23966,34 +28761,24 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mint",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "mintDecimals",
-          "type": "u8",
-          "index": false
-        },
-        {
-          "name": "oracle",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "mintInfo",
-          "type": "publicKey",
+          "name": "settlement",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpMarketMetaDataLog",
+      "name": "AccountBuybackFeesWithMngoLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24001,39 +28786,54 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "perpMarket",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "buybackFees",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "baseDecimals",
-          "type": "u8",
+          "name": "buybackMngo",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "baseLotSize",
-          "type": "i64",
+          "name": "mngoBuybackPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "quoteLotSize",
-          "type": "i64",
+          "name": "oraclePrice",
+          "type": "i128",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "FilledPerpOrderLog",
+      "fields": [
         {
-          "name": "oracle",
+          "name": "mangoGroup",
           "type": "publicKey",
           "index": false
+        },
+        {
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "seqNum",
+          "type": "u64",
+          "index": false
         }
       ]
     },
     {
-      "name": "Serum3RegisterMarketLog",
+      "name": "PerpTakerTradeLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24041,39 +28841,54 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "serumMarket",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "marketIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "baseTokenIndex",
-          "type": "u16",
+          "name": "takerSide",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "quoteTokenIndex",
-          "type": "u16",
+          "name": "totalBaseLotsTaken",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "serumProgram",
-          "type": "publicKey",
+          "name": "totalBaseLotsDecremented",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "serumProgramExternal",
-          "type": "publicKey",
+          "name": "totalQuoteLotsTaken",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "totalQuoteLotsDecremented",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "takerFeesPaid",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "feePenalty",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpLiqBaseOrPositivePnlLog",
+      "name": "PerpForceClosePositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24086,12 +28901,12 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "accountA",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "accountB",
           "type": "publicKey",
           "index": false
         },
@@ -24105,16 +28920,6 @@ export const IDL: MangoV4 = {
           "type": "i128",
           "index": false
         },
-        {
-          "name": "pnlTransfer",
-          "type": "i128",
-          "index": false
-        },
-        {
-          "name": "pnlSettleLimitTransfer",
-          "type": "i128",
-          "index": false
-        },
         {
           "name": "price",
           "type": "i128",
@@ -24123,7 +28928,7 @@ export const IDL: MangoV4 = {
       ]
     },
     {
-      "name": "PerpLiqBankruptcyLog",
+      "name": "TokenForceCloseBorrowsWithTokenLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24131,84 +28936,134 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
+          "name": "assetTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "insuranceTransfer",
-          "type": "i128",
+          "name": "liabTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "socializedLoss",
+          "name": "assetTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "startingLongFunding",
+          "name": "liabTransfer",
           "type": "i128",
           "index": false
         },
         {
-          "name": "startingShortFunding",
+          "name": "assetPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "endingLongFunding",
+          "name": "liabPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "endingShortFunding",
+          "name": "feeFactor",
           "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpLiqNegativePnlOrBankruptcyLog",
+      "name": "TokenConditionalSwapCreateLog",
       "fields": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "mangoAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "id",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maxBuy",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "maxSell",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "expiryTimestamp",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "priceLowerLimit",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "priceUpperLimit",
+          "type": "f64",
+          "index": false
+        },
+        {
+          "name": "pricePremiumRate",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
+          "name": "takerFeeRate",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "liqor",
-          "type": "publicKey",
+          "name": "makerFeeRate",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
+          "name": "buyTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
+          "name": "sellTokenIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "allowCreatingDeposits",
+          "type": "bool",
+          "index": false
+        },
+        {
+          "name": "allowCreatingBorrows",
+          "type": "bool",
           "index": false
         }
       ]
     },
     {
-      "name": "PerpSettlePnlLog",
+      "name": "TokenConditionalSwapCreateLogV2",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24216,99 +29071,89 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccountA",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "mangoAccountB",
-          "type": "publicKey",
+          "name": "id",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "maxBuy",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
+          "name": "maxSell",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "settler",
-          "type": "publicKey",
+          "name": "expiryTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "fee",
-          "type": "i128",
+          "name": "priceLowerLimit",
+          "type": "f64",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpSettleFeesLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "priceUpperLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "pricePremiumRate",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "takerFeeRate",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "settlement",
-          "type": "i128",
+          "name": "makerFeeRate",
+          "type": "f32",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "AccountBuybackFeesWithMngoLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "buyTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "sellTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "buybackFees",
-          "type": "i128",
+          "name": "allowCreatingDeposits",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "buybackMngo",
-          "type": "i128",
+          "name": "allowCreatingBorrows",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "mngoBuybackPrice",
-          "type": "i128",
+          "name": "displayPriceStyle",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "oraclePrice",
-          "type": "i128",
+          "name": "intention",
+          "type": "u8",
           "index": false
         }
       ]
     },
     {
-      "name": "FilledPerpOrderLog",
+      "name": "TokenConditionalSwapCreateLogV3",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24316,114 +29161,104 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "seqNum",
+          "name": "id",
           "type": "u64",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpTakerTradeLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "maxBuy",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "maxSell",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "expiryTimestamp",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "takerSide",
-          "type": "u8",
+          "name": "priceLowerLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "totalBaseLotsTaken",
-          "type": "i64",
+          "name": "priceUpperLimit",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "totalBaseLotsDecremented",
-          "type": "i64",
+          "name": "pricePremiumRate",
+          "type": "f64",
           "index": false
         },
         {
-          "name": "totalQuoteLotsTaken",
-          "type": "i64",
+          "name": "takerFeeRate",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "totalQuoteLotsDecremented",
-          "type": "i64",
+          "name": "makerFeeRate",
+          "type": "f32",
           "index": false
         },
         {
-          "name": "takerFeesPaid",
-          "type": "i128",
+          "name": "buyTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "feePenalty",
-          "type": "i128",
+          "name": "sellTokenIndex",
+          "type": "u16",
           "index": false
-        }
-      ]
-    },
-    {
-      "name": "PerpForceClosePositionLog",
-      "fields": [
+        },
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "allowCreatingDeposits",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "perpMarketIndex",
-          "type": "u16",
+          "name": "allowCreatingBorrows",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "accountA",
-          "type": "publicKey",
+          "name": "displayPriceStyle",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "accountB",
-          "type": "publicKey",
+          "name": "intention",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "baseTransfer",
-          "type": "i64",
+          "name": "intervalSeconds",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "quoteTransfer",
-          "type": "i128",
+          "name": "intervalMaxSell",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "price",
-          "type": "i128",
+          "name": "premiumAuctionDurationSeconds",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenForceCloseBorrowsWithTokenLog",
+      "name": "TokenConditionalSwapTriggerLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24431,134 +29266,174 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "liqee",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "assetTokenIndex",
+          "name": "tokenConditionalSwapId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "buyTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "liabTokenIndex",
+          "name": "sellTokenIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "assetTransfer",
-          "type": "i128",
+          "name": "buyAmount",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "liabTransfer",
-          "type": "i128",
+          "name": "sellAmount",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "assetPrice",
-          "type": "i128",
+          "name": "makerFee",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "liabPrice",
+          "name": "takerFee",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "buyTokenPrice",
           "type": "i128",
           "index": false
         },
         {
-          "name": "feeFactor",
+          "name": "sellTokenPrice",
           "type": "i128",
           "index": false
+        },
+        {
+          "name": "closed",
+          "type": "bool",
+          "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapCreateLog",
+      "name": "TokenConditionalSwapTriggerLogV2",
       "fields": [
         {
-          "name": "mangoGroup",
-          "type": "publicKey",
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "liqee",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "liqor",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "tokenConditionalSwapId",
+          "type": "u64",
+          "index": false
+        },
+        {
+          "name": "buyTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "mangoAccount",
-          "type": "publicKey",
+          "name": "sellTokenIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "id",
+          "name": "buyAmount",
           "type": "u64",
           "index": false
         },
         {
-          "name": "maxBuy",
+          "name": "sellAmount",
           "type": "u64",
           "index": false
         },
         {
-          "name": "maxSell",
+          "name": "makerFee",
           "type": "u64",
           "index": false
         },
         {
-          "name": "expiryTimestamp",
+          "name": "takerFee",
           "type": "u64",
           "index": false
         },
         {
-          "name": "priceLowerLimit",
-          "type": "f64",
+          "name": "buyTokenPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "priceUpperLimit",
-          "type": "f64",
+          "name": "sellTokenPrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "pricePremiumRate",
-          "type": "f64",
+          "name": "closed",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "takerFeeRate",
-          "type": "f32",
+          "name": "displayPriceStyle",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "makerFeeRate",
-          "type": "f32",
+          "name": "intention",
+          "type": "u8",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "TokenConditionalSwapStartLog",
+      "fields": [
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellTokenIndex",
-          "type": "u16",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "allowCreatingDeposits",
-          "type": "bool",
+          "name": "tokenConditionalSwapId",
+          "type": "u64",
           "index": false
         },
         {
-          "name": "allowCreatingBorrows",
-          "type": "bool",
+          "name": "startTimestamp",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapCreateLogV2",
+      "name": "TokenConditionalSwapCancelLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24574,17 +29449,42 @@ export const IDL: MangoV4 = {
           "name": "id",
           "type": "u64",
           "index": false
+        }
+      ]
+    },
+    {
+      "name": "PerpConditionalOrderCreateLog",
+      "fields": [
+        {
+          "name": "mangoGroup",
+          "type": "publicKey",
+          "index": false
         },
         {
-          "name": "maxBuy",
-          "type": "u64",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "maxSell",
+          "name": "id",
           "type": "u64",
           "index": false
         },
+        {
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "side",
+          "type": "u8",
+          "index": false
+        },
+        {
+          "name": "maxBaseLots",
+          "type": "i64",
+          "index": false
+        },
         {
           "name": "expiryTimestamp",
           "type": "u64",
@@ -24606,49 +29506,49 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "takerFeeRate",
-          "type": "f32",
+          "name": "reduceOnly",
+          "type": "bool",
           "index": false
         },
         {
-          "name": "makerFeeRate",
-          "type": "f32",
+          "name": "intention",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
+          "name": "execution",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "sellTokenIndex",
-          "type": "u16",
+          "name": "orderType",
+          "type": "u8",
           "index": false
         },
         {
-          "name": "allowCreatingDeposits",
-          "type": "bool",
+          "name": "priceLots",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "allowCreatingBorrows",
+          "name": "pegToOracle",
           "type": "bool",
           "index": false
         },
         {
-          "name": "displayPriceStyle",
-          "type": "u8",
+          "name": "maxQuoteLots",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "intention",
-          "type": "u8",
+          "name": "triggerPremium",
+          "type": "u64",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapTriggerLog",
+      "name": "PerpConditionalOrderCancelLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24656,57 +29556,57 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
-          "type": "publicKey",
-          "index": false
-        },
-        {
-          "name": "liqor",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenConditionalSwapId",
+          "name": "id",
           "type": "u64",
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "PerpConditionalOrderTriggerLog",
+      "fields": [
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellTokenIndex",
-          "type": "u16",
+          "name": "liqee",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "buyAmount",
-          "type": "u64",
+          "name": "liqor",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellAmount",
+          "name": "perpConditionalOrderId",
           "type": "u64",
           "index": false
         },
         {
-          "name": "makerFee",
-          "type": "u64",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "takerFee",
-          "type": "u64",
+          "name": "baseLots",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "buyTokenPrice",
+          "name": "quoteNative",
           "type": "i128",
           "index": false
         },
         {
-          "name": "sellTokenPrice",
+          "name": "oraclePrice",
           "type": "i128",
           "index": false
         },
@@ -24718,7 +29618,7 @@ export const IDL: MangoV4 = {
       ]
     },
     {
-      "name": "TokenConditionalSwapTriggerLogV2",
+      "name": "PerpConditionalOrderPlaceLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24726,79 +29626,81 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "liqee",
+          "name": "mangoAccount",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "liqor",
+          "name": "triggerer",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "tokenConditionalSwapId",
+          "name": "id",
           "type": "u64",
           "index": false
         },
         {
-          "name": "buyTokenIndex",
-          "type": "u16",
-          "index": false
-        },
-        {
-          "name": "sellTokenIndex",
+          "name": "perpMarketIndex",
           "type": "u16",
           "index": false
         },
         {
-          "name": "buyAmount",
-          "type": "u64",
+          "name": "oraclePrice",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "sellAmount",
+          "name": "triggerPremium",
           "type": "u64",
           "index": false
         },
         {
-          "name": "makerFee",
-          "type": "u64",
+          "name": "orderId",
+          "type": {
+            "option": "u128"
+          },
           "index": false
-        },
+        }
+      ]
+    },
+    {
+      "name": "PerpIsolatedMarginTransferLog",
+      "fields": [
         {
-          "name": "takerFee",
-          "type": "u64",
+          "name": "mangoGroup",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "buyTokenPrice",
-          "type": "i128",
+          "name": "mangoAccount",
+          "type": "publicKey",
           "index": false
         },
         {
-          "name": "sellTokenPrice",
-          "type": "i128",
+          "name": "perpMarketIndex",
+          "type": "u16",
           "index": false
         },
         {
-          "name": "closed",
-          "type": "bool",
+          "name": "amount",
+          "type": "i64",
           "index": false
         },
         {
-          "name": "displayPriceStyle",
-          "type": "u8",
+          "name": "isolatedCollateralIndexed",
+          "type": "i128",
           "index": false
         },
         {
-          "name": "intention",
-          "type": "u8",
+          "name": "depositIndex",
+          "type": "i128",
           "index": false
         }
       ]
     },
     {
-      "name": "TokenConditionalSwapCancelLog",
+      "name": "PerpLiqIsolatedPositionLog",
       "fields": [
         {
           "name": "mangoGroup",
@@ -24806,13 +29708,38 @@ export const IDL: MangoV4 = {
           "index": false
         },
         {
-          "name": "mangoAccount",
+          "name": "perpMarketIndex",
+          "type": "u16",
+          "index": false
+        },
+        {
+          "name": "liqor",
           "type": "publicKey",
           "index": false
         },
         {
-          "name": "id",
-          "type": "u64",
+          "name": "liqee",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "baseTransfer",
+          "type": "i64",
+          "index": false
+        },
+        {
+          "name": "quoteTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "collateralTransfer",
+          "type": "i128",
+          "index": false
+        },
+        {
+          "name": "price",
+          "type": "i128",
           "index": false
         }
       ]
@@ -25068,6 +29995,51 @@ export const IDL: MangoV4 = {
       "code": 6049,
       "name": "TokenConditionalSwapPriceNotInRange",
       "msg": "conditional token swap price is not in execution range"
+    },
+    {
+      "code": 6050,
+      "name": "NoFreeOpenbookV2OpenOrdersIndex",
+      "msg": "no free openbook v2 open orders index"
+    },
+    {
+      "code": 6051,
+      "name": "OpenbookV2OpenOrdersExistAlready",
+      "msg": "openbook v2 open orders exist already"
+    },
+    {
+      "code": 6052,
+      "name": "HasOpenOrUnsettledOpenbookV2Orders",
+      "msg": "there are open or unsettled openbook v2 orders"
+    },
+    {
+      "code": 6053,
+      "name": "PerpConditionalOrderPriceNotInRange",
+      "msg": "perp conditional order price not in range"
+    },
+    {
+      "code": 6054,
+      "name": "TokenConditionalSwapIntervalNotReady",
+      "msg": "conditional token swap interval amount is exhausted"
+    },
+    {
+      "code": 6055,
+      "name": "PerpPositionIsIsolated",
+      "msg": "the perp position is isolated, which this instruction does not support"
+    },
+    {
+      "code": 6056,
+      "name": "PerpPositionIsNotIsolated",
+      "msg": "the perp position is not isolated"
+    },
+    {
+      "code": 6057,
+      "name": "IsolatedPerpHealthMustBePositiveOrIncrease",
+      "msg": "isolated perp position health must be positive or not decrease"
+    },
+    {
+      "code": 6058,
+      "name": "IsolatedPerpPositionNotLiquidatable",
+      "msg": "isolated perp position is not liquidatable"
     }
   ]
 };